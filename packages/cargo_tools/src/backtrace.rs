@@ -0,0 +1,192 @@
+use std::path::Path;
+
+use serde::Serialize;
+
+/// A clickable part of a line of a program's output, its `start` and `length` count UTF-16
+/// code units like the terminal does
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum OutputLink {
+    /// A source location like `src/lib.rs:42:7` of a panic or a backtrace frame
+    Source {
+        start: usize,
+        length: usize,
+        path: String,
+        /// One based
+        line: u32,
+        column: Option<u32>,
+    },
+    /// The note of a panic telling to set `RUST_BACKTRACE`
+    RerunWithBacktrace { start: usize, length: usize },
+}
+
+/// Characters which can't be part of a path in a panic message or backtrace
+const PATH_DELIMITERS: &[char] = &[' ', '\t', '\'', '"', '`', '(', '[', '<', '='];
+
+/// The source locations and backtrace notes of a line of output
+pub fn find_links(line: &str) -> Vec<OutputLink> {
+    let utf16 = |end: usize| line[..end].encode_utf16().count();
+    let mut links = Vec::new();
+
+    if line.trim_start().starts_with("note:")
+        && let Some(start) = line.find("run with `RUST_BACKTRACE=")
+    {
+        links.push(OutputLink::RerunWithBacktrace {
+            start: utf16(start),
+            length: utf16(line.len()) - utf16(start),
+        });
+    }
+
+    let location = location_start(line);
+    let mut searched = 0;
+    while let Some(found) = line[searched..].find(".rs:") {
+        let extension = searched + found;
+        searched = extension + ".rs:".len();
+        let start = match location {
+            // The first location of a panic or frame is the rest of the line up to it
+            Some(start) if links.is_empty() && start <= extension => start,
+            _ => line[..extension]
+                .rfind(PATH_DELIMITERS)
+                .map_or(0, |delimiter| delimiter + 1),
+        };
+        let path = &line[start..extension + ".rs".len()];
+        let Some((line_number, rest)) = leading_number(&line[searched..]) else {
+            continue;
+        };
+        let mut end = searched + line_number.len();
+        let column = rest
+            .strip_prefix(':')
+            .and_then(leading_number)
+            .map(|(column, _)| {
+                end += 1 + column.len();
+                column
+            });
+        // The sources of the standard library aren't on disk under these paths
+        if path.starts_with("/rustc/") || path == ".rs" {
+            continue;
+        }
+        let (Ok(line_number), column) = (line_number.parse(), column.map(str::parse)) else {
+            continue;
+        };
+        links.push(OutputLink::Source {
+            start: utf16(start),
+            length: utf16(end) - utf16(start),
+            path: path.to_string(),
+            line: line_number,
+            column: column.and_then(Result::ok),
+        });
+        searched = end;
+    }
+    links
+}
+
+/// Where the location of a panic or a backtrace frame starts in `line`, its path may contain
+/// spaces
+fn location_start(line: &str) -> Option<usize> {
+    const PANICKED_AT: &str = "panicked at ";
+    if let Some(panic) = line.find(PANICKED_AT) {
+        return Some(panic + PANICKED_AT.len());
+    }
+    let frame = line.trim_start();
+    frame
+        .starts_with("at ")
+        .then(|| line.len() - frame.len() + "at ".len())
+}
+
+/// The digits at the start of `text` and the text after them
+fn leading_number(text: &str) -> Option<(&str, &str)> {
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    (digits > 0).then(|| text.split_at(digits))
+}
+
+/// The files `path` of an output link may refer to. Relative paths are relative to the
+/// workspace root for members and path dependencies, the `roots` are tried in order.
+pub fn candidate_files(path: &str, roots: &[String]) -> Vec<String> {
+    if Path::new(path).is_absolute() || path.get(1..3) == Some(":\\") {
+        return vec![path.to_string()];
+    }
+    roots
+        .iter()
+        .map(|root| Path::new(root).join(path).to_string_lossy().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn source(
+        start: usize,
+        length: usize,
+        path: &str,
+        line: u32,
+        column: Option<u32>,
+    ) -> OutputLink {
+        OutputLink::Source {
+            start,
+            length,
+            path: path.to_string(),
+            line,
+            column,
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn panic_locations_are_links() {
+        check!(
+            find_links("thread 'main' panicked at core/src/lib.rs:42:7:")
+                == vec![source(26, 20, "core/src/lib.rs", 42, Some(7))]
+        );
+        check!(
+            find_links("             at ./src/main.rs:4")
+                == vec![source(16, 15, "./src/main.rs", 4, None)]
+        );
+        let dependency = "   at /home/u/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/serde_json-1.0.128/src/de.rs:2091:9";
+        check!(find_links(dependency).len() == 1);
+        check!(
+            find_links("thread 'main' panicked at /home/my user/app/src/main.rs:4:5:")
+                == vec![source(26, 33, "/home/my user/app/src/main.rs", 4, Some(5))]
+        );
+        check!(
+            find_links("   at C:\\Users\\My User\\app\\src\\main.rs:4")
+                == vec![source(
+                    6,
+                    34,
+                    "C:\\Users\\My User\\app\\src\\main.rs",
+                    4,
+                    None
+                )]
+        );
+        // The standard library is not on disk under this path
+        check!(find_links("at /rustc/90b35a6/library/std/src/panicking.rs:665:5").is_empty());
+        check!(find_links("Compiling core v0.1.0 (/ws/core)").is_empty());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn backtrace_notes_offer_a_rerun() {
+        let note = "note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace";
+        check!(
+            find_links(note)
+                == vec![OutputLink::RerunWithBacktrace {
+                    start: 6,
+                    length: note.len() - 6
+                }]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn relative_paths_are_resolved_against_the_roots() {
+        let roots = ["/ws", "/ws/core"].map(str::to_string);
+        check!(
+            candidate_files("src/lib.rs", &roots)
+                == vec![
+                    "/ws/src/lib.rs".to_string(),
+                    "/ws/core/src/lib.rs".to_string()
+                ]
+        );
+        check!(candidate_files("/ws/core/src/lib.rs", &roots) == vec!["/ws/core/src/lib.rs"]);
+    }
+}
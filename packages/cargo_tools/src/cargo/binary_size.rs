@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Builds kept per artifact
+pub const HISTORY_LEN: usize = 50;
+
+/// The size of an artifact after one build
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SizeSample {
+    /// When the artifact was written, in milliseconds since the epoch
+    pub built_ms: f64,
+    pub bytes: u64,
+    /// The size grew more than the threshold compared to the build before
+    pub jumped: bool,
+}
+
+/// A build of an artifact which grew more than the threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct SizeJump {
+    pub artifact: String,
+    pub before: u64,
+    pub after: u64,
+}
+
+impl SizeJump {
+    pub fn message(&self) -> String {
+        let percent = (self.after as f64 / self.before as f64 - 1.0) * 100.0;
+        format!(
+            "{} grew by {percent:.1}% from {} to {}",
+            self.artifact,
+            format_size(self.before),
+            format_size(self.after)
+        )
+    }
+}
+
+/// The sizes of the bin artifacts after their recent builds, by their path in the target
+/// directory like `release/cli`, persisted across sessions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BinarySizeHistory(BTreeMap<String, Vec<SizeSample>>);
+
+impl BinarySizeHistory {
+    /// Records the size of `artifact` written at `built_ms`, artifacts which weren't written
+    /// again since their last record are skipped. Only the last [HISTORY_LEN] builds are kept.
+    ///
+    /// Returns the jump if the artifact grew more than `threshold_percent`, 0 disables it.
+    pub fn record(
+        &mut self,
+        artifact: String,
+        built_ms: f64,
+        bytes: u64,
+        threshold_percent: f64,
+    ) -> Option<SizeJump> {
+        let samples = self.0.entry(artifact.clone()).or_default();
+        let last = samples.last().copied();
+        if last.is_some_and(|last| last.built_ms >= built_ms) {
+            return None;
+        }
+        let jump = last
+            .filter(|last| {
+                threshold_percent > 0.0
+                    && last.bytes > 0
+                    && bytes as f64 > last.bytes as f64 * (1.0 + threshold_percent / 100.0)
+            })
+            .map(|last| SizeJump {
+                artifact,
+                before: last.bytes,
+                after: bytes,
+            });
+        samples.push(SizeSample {
+            built_ms,
+            bytes,
+            jumped: jump.is_some(),
+        });
+        if samples.len() > HISTORY_LEN {
+            samples.drain(..samples.len() - HISTORY_LEN);
+        }
+        jump
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The recorded artifacts by their paths relative to the target directory
+    pub fn artifacts(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+}
+
+/// `bytes` in binary units like `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn jumps_above_the_threshold_are_flagged() {
+        let mut history = BinarySizeHistory::default();
+        let cli = || "release/cli".to_string();
+
+        check!(history.record(cli(), 1.0, 4 * 1024 * 1024, 10.0).is_none());
+        // Fresh builds don't write the artifact again
+        check!(history.record(cli(), 1.0, 4 * 1024 * 1024, 10.0).is_none());
+        check!(
+            history
+                .record(cli(), 2.0, 4 * 1024 * 1024 + 1024, 10.0)
+                .is_none()
+        );
+        let jump = history.record(cli(), 3.0, 6 * 1024 * 1024, 10.0);
+        check!(
+            jump.as_ref().map(SizeJump::message).as_deref()
+                == Some("release/cli grew by 50.0% from 4.0 MiB to 6.0 MiB")
+        );
+        // Shrinking is no jump
+        check!(history.record(cli(), 4.0, 1024, 10.0).is_none());
+
+        let samples = &history.0["release/cli"];
+        check!(samples.len() == 4);
+        check!(samples.iter().map(|s| s.jumped).collect::<Vec<_>>() == [false, false, true, false]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn only_the_last_builds_are_kept() {
+        let mut history = BinarySizeHistory::default();
+        for i in 0..HISTORY_LEN + 5 {
+            history.record("debug/cli".to_string(), i as f64, 100, 0.0);
+        }
+
+        check!(history.0["debug/cli"].len() == HISTORY_LEN);
+        check!(history.0["debug/cli"][0].built_ms == 5.0);
+        check!(format_size(100) == "100 B");
+        check!(format_size(1536) == "1.5 KiB");
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt::Display;
+
+use crate::cargo::metadata::Package;
+
+/// The state of a single package build within a [`BuildQueue`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+    /// A package this build depends on failed so it was never started
+    Skipped,
+}
+
+impl BuildStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Succeeded | Self::Failed | Self::Skipped)
+    }
+}
+
+impl Display for BuildStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        };
+        f.write_str(status)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct QueuedBuild {
+    package: String,
+    /// Packages of this queue which have to be built before this one
+    dependencies: Vec<String>,
+    status: BuildStatus,
+}
+
+/// Schedules the builds of several packages.
+///
+/// Packages only start once all queued packages they depend on were built successfully
+/// and at most `concurrency` builds run at the same time. A `concurrency` of 1 serializes
+/// the builds.
+#[derive(Debug, Clone)]
+pub struct BuildQueue {
+    builds: Vec<QueuedBuild>,
+    concurrency: usize,
+}
+
+impl BuildQueue {
+    pub fn new(selected: &[String], packages: &[Package], concurrency: usize) -> Self {
+        let builds = packages
+            .iter()
+            .filter(|p| selected.contains(&p.name))
+            .map(|p| QueuedBuild {
+                package: p.name.clone(),
+                dependencies: p
+                    .dependencies
+                    .iter()
+                    .filter(|d| selected.contains(d))
+                    .cloned()
+                    .collect(),
+                status: BuildStatus::Queued,
+            })
+            .collect();
+
+        Self {
+            builds,
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    /// Marks the packages which can be built now as running and returns them
+    pub fn start_ready(&mut self) -> Vec<String> {
+        let mut slots = self.concurrency.saturating_sub(self.running());
+        let mut started = Vec::new();
+
+        for i in 0..self.builds.len() {
+            if slots == 0 {
+                break;
+            }
+            let build = &self.builds[i];
+            let is_ready = build.status == BuildStatus::Queued
+                && build
+                    .dependencies
+                    .iter()
+                    .all(|d| self.status(d) == Some(BuildStatus::Succeeded));
+            if is_ready {
+                self.builds[i].status = BuildStatus::Running;
+                started.push(self.builds[i].package.clone());
+                slots -= 1;
+            }
+        }
+
+        started
+    }
+
+    /// Records the result of the build of `package`.
+    /// A failed build skips all queued packages which depend on it.
+    pub fn finish(&mut self, package: &str, success: bool) {
+        let Some(build) = self.builds.iter_mut().find(|b| b.package == package) else {
+            return;
+        };
+
+        if success {
+            build.status = BuildStatus::Succeeded;
+            return;
+        }
+
+        build.status = BuildStatus::Failed;
+        let mut failed = vec![package.to_string()];
+        while let Some(failed_package) = failed.pop() {
+            for build in self.builds.iter_mut().filter(|b| {
+                b.status == BuildStatus::Queued && b.dependencies.contains(&failed_package)
+            }) {
+                build.status = BuildStatus::Skipped;
+                failed.push(build.package.clone());
+            }
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.builds.iter().all(|b| b.status.is_finished())
+    }
+
+    pub fn status(&self, package: &str) -> Option<BuildStatus> {
+        self.builds
+            .iter()
+            .find(|b| b.package == package)
+            .map(|b| b.status)
+    }
+
+    pub fn statuses(&self) -> impl Iterator<Item = (&str, BuildStatus)> {
+        self.builds.iter().map(|b| (b.package.as_str(), b.status))
+    }
+
+    pub fn count(&self, status: BuildStatus) -> usize {
+        self.builds.iter().filter(|b| b.status == status).count()
+    }
+
+    fn running(&self) -> usize {
+        self.count(BuildStatus::Running)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn package(name: &str, dependencies: &[&str]) -> Package {
+        Package {
+            dependencies: dependencies.iter().map(ToString::to_string).collect(),
+            ..Package::fixture(name, &format!("{name}/Cargo.toml"), Vec::new())
+        }
+    }
+
+    fn packages() -> Vec<Package> {
+        vec![
+            package("cli", &["core"]),
+            package("core", &[]),
+            package("utils", &["core"]),
+            package("web-server", &["core", "utils"]),
+        ]
+    }
+
+    fn selected(names: &[&str]) -> Vec<String> {
+        names.iter().map(ToString::to_string).collect()
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn independent_packages_run_in_parallel() {
+        let mut queue = BuildQueue::new(&selected(&["cli", "utils"]), &packages(), 2);
+
+        check!(queue.start_ready() == vec!["cli", "utils"]);
+        check!(queue.start_ready().is_empty());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn concurrency_limits_running_builds() {
+        let mut queue = BuildQueue::new(&selected(&["cli", "utils"]), &packages(), 1);
+
+        check!(queue.start_ready() == vec!["cli"]);
+        check!(queue.start_ready().is_empty());
+
+        queue.finish("cli", true);
+        check!(queue.start_ready() == vec!["utils"]);
+        queue.finish("utils", true);
+        check!(queue.is_done());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn dependencies_are_built_first() {
+        let mut queue = BuildQueue::new(&selected(&["cli", "core", "utils"]), &packages(), 4);
+
+        check!(queue.start_ready() == vec!["core"]);
+        queue.finish("core", true);
+        check!(queue.start_ready() == vec!["cli", "utils"]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn failed_build_skips_dependents() {
+        let all = selected(&["cli", "core", "utils", "web-server"]);
+        let mut queue = BuildQueue::new(&all, &packages(), 4);
+
+        check!(queue.start_ready() == vec!["core"]);
+        queue.finish("core", false);
+
+        check!(queue.start_ready().is_empty());
+        check!(queue.is_done());
+        check!(queue.status("core") == Some(BuildStatus::Failed));
+        check!(queue.count(BuildStatus::Skipped) == 3);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn zero_concurrency_serializes() {
+        let mut queue = BuildQueue::new(&selected(&["cli", "utils"]), &packages(), 0);
+
+        check!(queue.start_ready().len() == 1);
+    }
+}
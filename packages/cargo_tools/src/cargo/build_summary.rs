@@ -0,0 +1,142 @@
+use std::collections::BTreeSet;
+
+use cargo_metadata::Message;
+use serde::Serialize;
+
+use crate::cargo::diagnostics::{FileDiagnostic, package_name};
+
+/// A compilation unit of a build, e.g. the lib of a package or a build script
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildUnit {
+    pub package: String,
+    /// Whether the unit was up to date and not compiled
+    pub fresh: bool,
+}
+
+/// The units of the `compiler-artifact`s of a cargo invocation with `--message-format=json`
+pub fn build_units(messages: &str) -> Vec<BuildUnit> {
+    Message::parse_stream(messages.as_bytes())
+        .filter_map(Result::ok)
+        .filter_map(|message| match message {
+            Message::CompilerArtifact(artifact) => Some(BuildUnit {
+                package: package_name(&artifact.package_id.repr).to_string(),
+                fresh: artifact.fresh,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The statistics of a finished build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BuildSummary {
+    pub wall_ms: u64,
+    /// The packages of which at least one unit was compiled
+    pub crates_compiled: usize,
+    pub rebuilt_units: usize,
+    pub fresh_units: usize,
+    pub warnings: usize,
+    pub errors: usize,
+}
+
+impl BuildSummary {
+    /// The summary of a build of `units` which took `wall_ms` and reported `diagnostics`
+    pub fn new(units: &[BuildUnit], diagnostics: &[FileDiagnostic], wall_ms: u64) -> Self {
+        let (fresh, rebuilt): (Vec<_>, Vec<_>) = units.iter().partition(|unit| unit.fresh);
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|d| d.severity == severity)
+                .count()
+        };
+        Self {
+            wall_ms,
+            crates_compiled: rebuilt
+                .iter()
+                .map(|unit| &unit.package)
+                .collect::<BTreeSet<_>>()
+                .len(),
+            rebuilt_units: rebuilt.len(),
+            fresh_units: fresh.len(),
+            warnings: count("warning"),
+            errors: count("error"),
+        }
+    }
+
+    /// E.g. `12.3s, 4 crates compiled, 10 units rebuilt, 52 fresh, 3 warnings`
+    pub fn message(&self) -> String {
+        let plural = |count: usize, noun: &str| match count {
+            1 => format!("1 {noun}"),
+            _ => format!("{count} {noun}s"),
+        };
+        let mut message = format!(
+            "{:.1}s, {} compiled, {} rebuilt, {} fresh, {}",
+            self.wall_ms as f64 / 1000.0,
+            plural(self.crates_compiled, "crate"),
+            plural(self.rebuilt_units, "unit"),
+            self.fresh_units,
+            plural(self.warnings, "warning"),
+        );
+        if self.errors > 0 {
+            message.push_str(&format!(", {}", plural(self.errors, "error")));
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::diagnostics::parse_diagnostics;
+
+    fn artifact(package_id: &str, file: &str, fresh: bool) -> String {
+        format!(
+            r#"{{"reason":"compiler-artifact","package_id":"{package_id}","manifest_path":"/ws/Cargo.toml","target":{{"kind":["lib"],"crate_types":["lib"],"name":"unit","src_path":"/ws/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true}},"profile":{{"opt_level":"0","debuginfo":2,"debug_assertions":true,"overflow_checks":true,"test":false}},"features":[],"filenames":["{file}"],"executable":null,"fresh":{fresh}}}"#
+        )
+    }
+
+    const WARNING: &str = r#"{"reason":"compiler-message","package_id":"path+file:///ws/core#0.1.0","manifest_path":"/ws/core/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"core","src_path":"/ws/core/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"message":{"rendered":"warning: unused variable: `x`\n","$message_type":"diagnostic","children":[],"code":{"code":"unused_variables","explanation":null},"level":"warning","message":"unused variable: `x`","spans":[{"byte_end":9,"byte_start":8,"column_end":10,"column_start":9,"expansion":null,"file_name":"core/src/lib.rs","is_primary":true,"label":null,"line_end":1,"line_start":1,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}"#;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn fresh_units_are_not_compiled() {
+        let messages = [
+            artifact(
+                "registry+https://github.com/rust-lang/crates.io-index#serde@1.0.210",
+                "/ws/target/debug/deps/libserde-1.rlib",
+                true,
+            ),
+            WARNING.to_string(),
+            artifact(
+                "path+file:///ws/core#0.1.0",
+                "/ws/target/debug/libcore.rlib",
+                false,
+            ),
+            artifact("path+file:///ws/cli#0.1.0", "/ws/target/debug/cli", false),
+            "   Compiling cli v0.1.0 (/ws/cli)".to_string(),
+        ]
+        .join("\n");
+
+        let units = build_units(&messages);
+        check!(units.len() == 3);
+        check!(units[0].package == "serde");
+        check!(!units[1].fresh);
+        let summary = BuildSummary::new(&units, &parse_diagnostics(&messages, "/ws"), 12_345);
+        check!(
+            summary
+                == BuildSummary {
+                    wall_ms: 12_345,
+                    crates_compiled: 2,
+                    rebuilt_units: 2,
+                    fresh_units: 1,
+                    warnings: 1,
+                    errors: 0,
+                }
+        );
+        check!(
+            summary.message() == "12.3s, 2 crates compiled, 2 units rebuilt, 1 fresh, 1 warning"
+        );
+    }
+}
@@ -1,7 +1,13 @@
+use std::iter;
+
+use cargo_metadata::{Artifact, Message, TargetKind};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    cargo::{Config, metadata::TargetType},
+    cargo::{
+        Config, DocTest, IgnoredTests, Profile, TestCase, TestSelection, TestTarget,
+        metadata::TargetType, nextest,
+    },
     process::{CargoCommandEmpty, CargoTaskContext, Process},
 };
 
@@ -11,10 +17,73 @@ pub enum Command {
     Build(Option<BuildTarget>),
     Run(Option<RunTarget>),
     Debug(Option<RunTarget>),
-    Test { package: Option<String> },
+    Test {
+        package: Option<String>,
+    },
+    /// Tests several packages with one invocation, e.g. those affected by changes
+    TestPackages(Vec<String>),
+    /// Runs a single test function
+    RunTest(TestCase),
+    /// Builds the test executable containing a single test function for debugging
+    DebugTest(TestCase),
+    /// Runs the tests of a test explorer item. The output is captured so libtest reports
+    /// the output of failed tests with their results.
+    RunTests {
+        selection: TestSelection,
+        ignored: IgnoredTests,
+    },
+    /// Lists the documentation tests of a package
+    ListDocTests {
+        package: String,
+    },
+    /// Runs the tests of a test explorer item with `cargo llvm-cov`, which writes an LCOV
+    /// report to `lcov_path`. Doctests need nightly and are not instrumented.
+    Coverage {
+        selection: TestSelection,
+        ignored: IgnoredTests,
+        lcov_path: String,
+    },
+    /// Runs a single documentation test
+    RunDocTest {
+        package: String,
+        test: DocTest,
+    },
+    /// Compiles a documentation test with nightly rustdoc and keeps its executable in
+    /// `persist_dir` for debugging
+    DebugDocTest {
+        package: String,
+        test: DocTest,
+        persist_dir: String,
+    },
+    Check {
+        package: Option<String>,
+    },
+    Clippy {
+        package: Option<String>,
+    },
+    Fmt {
+        package: Option<String>,
+    },
     Bench(Option<BenchTarget>),
+    /// Builds a benchmark executable with debug info for debugging
+    DebugBench(BenchTarget),
     Doc,
-    Clean { package: Option<String> },
+    Clean {
+        package: Option<String>,
+    },
+    /// Runs the tests of `cmd`, a `cargo test` command, with `cargo nextest run` and its
+    /// `profile`. A `tool_config` file adds to the repository's nextest config.
+    Nextest {
+        cmd: Box<Command>,
+        profile: Option<String>,
+        tool_config: Option<String>,
+    },
+    /// Runs `cmd` with the nightly toolchain for `platform_target`. Sanitizers need the
+    /// explicit target so build scripts and proc macros are built without them.
+    Sanitized {
+        cmd: Box<Command>,
+        platform_target: String,
+    },
 }
 
 impl Command {
@@ -73,17 +142,136 @@ impl Command {
                 args.extend(selection_args);
                 args
             }
-            // Debug is special and does not translate into arguments
-            Command::Debug(_) => Vec::new(),
-            Command::Test { package } => {
-                let mut args: Vec<_> = vec!["test".to_string()];
-                let selection_args = config.args(package.as_deref());
-                if let Some(package) = package {
+            // Debugging builds the target, the debug session is started from its artifact
+            Command::Debug(run_target) => {
+                let mut args = Command::Build(run_target.map(BuildTarget::from)).into_args(config);
+                args.push("--message-format=json-render-diagnostics".to_string());
+                args
+            }
+            Command::Test { package } => package_args("test", package, config),
+            Command::TestPackages(packages) => {
+                let mut args = vec!["test".to_string()];
+                for package in packages {
                     args.extend(["--package".to_string(), package]);
                 }
-                args.extend(selection_args);
+                args.extend(config.args(None));
+                args
+            }
+            Command::RunTest(test) => {
+                let mut args =
+                    test_target_args(&test.package, std::slice::from_ref(&test.target), config);
+                args.extend([
+                    "--".to_string(),
+                    test.name,
+                    "--exact".to_string(),
+                    "--nocapture".to_string(),
+                ]);
+                args
+            }
+            Command::DebugTest(test) => {
+                let mut args =
+                    test_target_args(&test.package, std::slice::from_ref(&test.target), config);
+                args.extend([
+                    "--no-run".to_string(),
+                    "--message-format=json-render-diagnostics".to_string(),
+                ]);
+                args
+            }
+            Command::RunTests { selection, ignored } => {
+                let mut args = match selection {
+                    TestSelection::Package(package) => package_args("test", Some(package), config),
+                    TestSelection::Targets { package, targets } => {
+                        test_target_args(&package, &targets, config)
+                    }
+                    TestSelection::Tests {
+                        package,
+                        target,
+                        names,
+                        ..
+                    } => {
+                        let mut args =
+                            test_target_args(&package, std::slice::from_ref(&target), config);
+                        args.push("--".to_string());
+                        args.extend(names);
+                        args.push("--exact".to_string());
+                        args
+                    }
+                    TestSelection::Test(test) => {
+                        let mut args = test_target_args(
+                            &test.package,
+                            std::slice::from_ref(&test.target),
+                            config,
+                        );
+                        args.extend(["--".to_string(), test.name, "--exact".to_string()]);
+                        args
+                    }
+                    TestSelection::DocTests(package) => doc_test_args(package, config),
+                    TestSelection::DocTest { package, test } => {
+                        Command::RunDocTest { package, test }.into_args(config)
+                    }
+                };
+                push_harness_args(&mut args, ignored.harness_arg().map(str::to_string));
+                args
+            }
+            Command::Coverage {
+                selection,
+                ignored,
+                lcov_path,
+            } => {
+                let mut args = vec![
+                    "llvm-cov".to_string(),
+                    "test".to_string(),
+                    "--lcov".to_string(),
+                    "--output-path".to_string(),
+                    lcov_path,
+                ];
+                // The arguments of `cargo test` without the subcommand
+                args.extend(
+                    Command::RunTests { selection, ignored }
+                        .into_args(config)
+                        .into_iter()
+                        .skip(1),
+                );
+                args
+            }
+            Command::RunDocTest { package, test } => {
+                let mut args = doc_test_args(package, config);
+                args.extend(["--".to_string(), test.name(), "--exact".to_string()]);
+                args
+            }
+            Command::ListDocTests { package } => {
+                let mut args = doc_test_args(package, config);
+                args.extend(["--".to_string(), "--list".to_string()]);
                 args
             }
+            Command::DebugDocTest {
+                package,
+                test,
+                persist_dir,
+            } => {
+                // cargo refuses `--no-run` for doctests, rustdoc itself only compiles them
+                // with the unstable `--no-run` and keeps them with `--persist-doctests`
+                let rustdoc_flags = format!(
+                    r#"build.rustdocflags=["-Zunstable-options", "--persist-doctests", "{}", "--no-run"]"#,
+                    persist_dir.replace('\\', "/")
+                );
+                let mut args = vec!["+nightly".to_string()];
+                args.extend(doc_test_args(package, config));
+                args.extend([
+                    "--config".to_string(),
+                    rustdoc_flags,
+                    "--".to_string(),
+                    test.name(),
+                    "--exact".to_string(),
+                ]);
+                args
+            }
+            Command::Check { package } => package_args("check", package, config),
+            Command::Clippy { package } => package_args("clippy", package, config),
+            // Formatting is independent of the build configuration
+            Command::Fmt { package } => iter::once("fmt".to_string())
+                .chain(package.map_or(vec![], |p| vec!["--package".to_string(), p]))
+                .collect(),
             Command::Bench(bench_target) => {
                 let mut args = vec!["bench".to_string()];
                 let selection_args = config.args(bench_target.as_ref().map(|t| t.package.as_str()));
@@ -97,6 +285,29 @@ impl Command {
                 args.extend(selection_args);
                 args
             }
+            Command::DebugBench(BenchTarget { package, target }) => {
+                // The bench profile is used, its missing debug info is enabled explicitly
+                let config = Config {
+                    profile: Profile::None,
+                    ..config.clone()
+                };
+                let mut args = vec![
+                    "bench".to_string(),
+                    "--no-run".to_string(),
+                    "--package".to_string(),
+                    package.clone(),
+                ];
+                if let Some(target) = target {
+                    args.extend(["--bench".to_string(), target]);
+                }
+                args.extend(config.args(Some(&package)));
+                args.extend([
+                    "--config".to_string(),
+                    "profile.bench.debug=true".to_string(),
+                    "--message-format=json-render-diagnostics".to_string(),
+                ]);
+                args
+            }
             Command::Doc => {
                 let package = config.selected_package.as_ref();
                 ["doc".to_string(), "--no-deps".to_string()]
@@ -105,6 +316,40 @@ impl Command {
                     .chain(config.args(package.map(String::as_str)))
                     .collect()
             }
+            Command::Sanitized {
+                cmd,
+                platform_target,
+            } => {
+                let config = Config {
+                    platform_target: Some(platform_target),
+                    ..config.clone()
+                };
+                iter::once("+nightly".to_string())
+                    .chain(cmd.into_args(&config))
+                    .collect()
+            }
+            Command::Nextest {
+                cmd,
+                profile,
+                tool_config,
+            } => {
+                let mut test_args = cmd.into_args(config);
+                if test_args.first().is_some_and(|arg| arg == "test") {
+                    test_args.remove(0);
+                }
+                let mut args = vec!["nextest".to_string(), "run".to_string()];
+                if let Some(profile) = profile {
+                    args.extend(["--profile".to_string(), profile]);
+                }
+                if let Some(tool_config) = tool_config {
+                    args.extend([
+                        "--tool-config-file".to_string(),
+                        format!("{}:{tool_config}", nextest::TOOL_NAME),
+                    ]);
+                }
+                args.extend(test_args);
+                args
+            }
             Command::Clean { package } => {
                 let mut args = vec!["clean".to_string()];
                 if let Some(package) = package {
@@ -120,6 +365,61 @@ impl Command {
     }
 }
 
+/// Arguments of a `cargo` subcommand which only targets an optional package
+fn package_args(subcommand: &str, package: Option<String>, config: &Config) -> Vec<String> {
+    let mut args = vec![subcommand.to_string()];
+    let selection_args = config.args(package.as_deref());
+    if let Some(package) = package {
+        args.extend(["--package".to_string(), package]);
+    }
+    args.extend(selection_args);
+    args
+}
+
+/// Appends arguments for the test harness, after the `--` separating them from cargo's
+fn push_harness_args(args: &mut Vec<String>, harness_args: impl IntoIterator<Item = String>) {
+    let mut harness_args = harness_args.into_iter().peekable();
+    if harness_args.peek().is_some() && !args.iter().any(|arg| arg == "--") {
+        args.push("--".to_string());
+    }
+    args.extend(harness_args);
+}
+
+/// Arguments of `cargo test` which build the test `targets` of `package`
+fn test_target_args(package: &str, targets: &[TestTarget], config: &Config) -> Vec<String> {
+    let mut args = vec![
+        "test".to_string(),
+        "--package".to_string(),
+        package.to_string(),
+    ];
+    args.extend(targets.iter().flat_map(TestTarget::cargo_args));
+    args.extend(config.args(Some(package)));
+    args
+}
+
+/// Arguments of `cargo test` which only run the documentation tests of `package`
+fn doc_test_args(package: String, config: &Config) -> Vec<String> {
+    let selection_args = config.args(Some(&package));
+    let mut args = vec![
+        "test".to_string(),
+        "--doc".to_string(),
+        "--package".to_string(),
+        package,
+    ];
+    args.extend(selection_args);
+    args
+}
+
+/// The artifacts in the JSON messages of a `cargo` invocation with `--message-format=json`
+pub(crate) fn compiler_artifacts(messages: &str) -> impl Iterator<Item = Artifact> {
+    Message::parse_stream(messages.as_bytes())
+        .filter_map(Result::ok)
+        .filter_map(|message| match message {
+            Message::CompilerArtifact(artifact) => Some(artifact),
+            _ => None,
+        })
+}
+
 /// Represents the target options of the `cargo build` command
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildTarget {
@@ -150,6 +450,20 @@ pub struct BenchTarget {
     pub target: Option<String>,
 }
 
+impl BenchTarget {
+    /// Returns the executable of the benchmark from the JSON messages of a
+    /// `cargo bench --no-run --message-format=json` invocation.
+    pub fn executable_from_messages(&self, messages: &str) -> Option<String> {
+        let target = self.target.as_ref()?;
+        compiler_artifacts(messages)
+            .filter(|artifact| {
+                artifact.target.kind.contains(&TargetKind::Bench) && artifact.target.name == *target
+            })
+            .find_map(|artifact| artifact.executable)
+            .map(|path| path.to_string())
+    }
+}
+
 /// The type of sub target which can be built via `cargo build -p <package>`
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum BuildSubTarget {
@@ -180,13 +494,50 @@ impl BuildSubTarget {
 }
 
 /// The type of sub target which can be run via `cargo run -p <package>`
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum RunSubTarget {
     Bin(String),
     Example(String),
 }
 
+impl From<RunTarget> for BuildTarget {
+    fn from(RunTarget { package, target }: RunTarget) -> Self {
+        let target = target.map(|target| match target {
+            RunSubTarget::Bin(bin) => BuildSubTarget::Bin(bin),
+            RunSubTarget::Example(example) => BuildSubTarget::Example(example),
+        });
+        Self { package, target }
+    }
+}
+
 impl RunSubTarget {
+    /// Returns the executable of this target from the JSON messages of a
+    /// `cargo build --message-format=json` invocation.
+    pub fn executable_from_messages(&self, messages: &str) -> Option<String> {
+        compiler_artifacts(messages)
+            .filter(|artifact| {
+                TargetType::from_target(artifact.target.clone())
+                    .is_some_and(|target| self.matches(target, &artifact.target.name))
+            })
+            .find_map(|artifact| artifact.executable)
+            .map(|path| path.to_string())
+    }
+
+    /// Returns the run targets and their executables from the JSON messages of a build, also
+    /// those of fresh targets
+    pub fn executables_from_messages(messages: &str) -> Vec<(RunSubTarget, String)> {
+        compiler_artifacts(messages)
+            .filter_map(|artifact| {
+                let target = match TargetType::from_target(artifact.target.clone())? {
+                    TargetType::Bin => RunSubTarget::Bin(artifact.target.name),
+                    TargetType::Example => RunSubTarget::Example(artifact.target.name),
+                    TargetType::Lib | TargetType::Bench => return None,
+                };
+                Some((target, artifact.executable?.to_string()))
+            })
+            .collect()
+    }
+
     pub fn name(&self) -> &str {
         match self {
             RunSubTarget::Bin(name) => name,
@@ -201,3 +552,311 @@ impl RunSubTarget {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn artifact_message(name: &str, kind: &str, executable: Option<&str>) -> String {
+        let executable = executable.map_or("null".to_string(), |e| format!("\"{e}\""));
+        format!(
+            r#"{{"reason":"compiler-artifact","package_id":"path+file:///ws/cli#0.1.0","target":{{"name":"{name}","kind":["{kind}"],"src_path":"/ws/cli/src/main.rs"}},"profile":{{"opt_level":"0","debug_assertions":true,"overflow_checks":true,"test":false}},"features":[],"filenames":[],"executable":{executable},"fresh":false}}"#
+        )
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn executable_from_build_messages() {
+        let messages = [
+            artifact_message("cli", "lib", None),
+            "Compiling cli v0.1.0".to_string(),
+            artifact_message("demo", "example", Some("/ws/target/debug/examples/demo")),
+            artifact_message("cli", "bin", Some("/ws/target/debug/cli")),
+            r#"{"reason":"build-finished","success":true}"#.to_string(),
+        ]
+        .join("\n");
+
+        let bin = RunSubTarget::Bin("cli".to_string());
+        let example = RunSubTarget::Example("demo".to_string());
+        let missing = RunSubTarget::Example("cli".to_string());
+
+        check!(bin.executable_from_messages(&messages) == Some("/ws/target/debug/cli".to_string()));
+        check!(
+            example.executable_from_messages(&messages)
+                == Some("/ws/target/debug/examples/demo".to_string())
+        );
+        check!(missing.executable_from_messages(&messages).is_none());
+        check!(
+            RunSubTarget::executables_from_messages(&messages)
+                == [
+                    (example, "/ws/target/debug/examples/demo".to_string()),
+                    (bin, "/ws/target/debug/cli".to_string())
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn run_single_test_case() {
+        let test = TestCase {
+            package: "core".to_string(),
+            package_dir: "/ws/core".to_string(),
+            target: TestTarget::Test("api".to_string()),
+            name: "db::connects".to_string(),
+        };
+
+        check!(
+            Command::RunTest(test.clone()).into_args(&Config::default())
+                == [
+                    "test",
+                    "--package",
+                    "core",
+                    "--test",
+                    "api",
+                    "--",
+                    "db::connects",
+                    "--exact",
+                    "--nocapture"
+                ]
+        );
+        check!(
+            Command::DebugTest(test).into_args(&Config::default())
+                == [
+                    "test",
+                    "--package",
+                    "core",
+                    "--test",
+                    "api",
+                    "--no-run",
+                    "--message-format=json-render-diagnostics"
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn coverage_of_single_test() {
+        let test = TestCase {
+            package: "core".to_string(),
+            package_dir: "/ws/core".to_string(),
+            target: TestTarget::Lib,
+            name: "tests::adds".to_string(),
+        };
+        let cmd = Command::Coverage {
+            selection: TestSelection::Test(test),
+            ignored: IgnoredTests::Skip,
+            lcov_path: "/ws/target/lcov.info".to_string(),
+        };
+
+        check!(
+            cmd.into_args(&Config::default())
+                == [
+                    "llvm-cov",
+                    "test",
+                    "--lcov",
+                    "--output-path",
+                    "/ws/target/lcov.info",
+                    "--package",
+                    "core",
+                    "--lib",
+                    "--",
+                    "tests::adds",
+                    "--exact"
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn run_tests_of_file_with_captured_output() {
+        let selection = TestSelection::Tests {
+            package: "core".to_string(),
+            package_dir: "/ws/core".to_string(),
+            target: TestTarget::Lib,
+            module: "parser".to_string(),
+            names: vec![
+                "parser::tests::a".to_string(),
+                "parser::tests::b".to_string(),
+            ],
+        };
+
+        check!(
+            Command::RunTests {
+                selection,
+                ignored: IgnoredTests::Skip
+            }
+            .into_args(&Config::default())
+                == [
+                    "test",
+                    "--package",
+                    "core",
+                    "--lib",
+                    "--",
+                    "parser::tests::a",
+                    "parser::tests::b",
+                    "--exact"
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_several_packages() {
+        let cmd = Command::TestPackages(vec!["cli".to_string(), "core".to_string()]);
+
+        check!(
+            cmd.into_args(&Config::default()) == ["test", "--package", "cli", "--package", "core"]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn nextest_with_profile_and_tool_config() {
+        let cmd = Command::Nextest {
+            cmd: Box::new(Command::Test {
+                package: Some("core".to_string()),
+            }),
+            profile: Some("ci".to_string()),
+            tool_config: Some("/ws/target/cargo-tools/nextest.toml".to_string()),
+        };
+
+        check!(
+            cmd.into_args(&Config::default())
+                == [
+                    "nextest",
+                    "run",
+                    "--profile",
+                    "ci",
+                    "--tool-config-file",
+                    "cargo-tools:/ws/target/cargo-tools/nextest.toml",
+                    "--package",
+                    "core",
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn run_ignored_tests() {
+        let package = Command::RunTests {
+            selection: TestSelection::Package("core".to_string()),
+            ignored: IgnoredTests::Only,
+        };
+        check!(
+            package.into_args(&Config::default())
+                == ["test", "--package", "core", "--", "--ignored"]
+        );
+
+        let test = TestCase {
+            package: "core".to_string(),
+            package_dir: "/ws/core".to_string(),
+            target: TestTarget::Lib,
+            name: "tests::slow".to_string(),
+        };
+        let test = Command::RunTests {
+            selection: TestSelection::Test(test),
+            ignored: IgnoredTests::Include,
+        };
+        check!(
+            test.into_args(&Config::default())
+                == [
+                    "test",
+                    "--package",
+                    "core",
+                    "--lib",
+                    "--",
+                    "tests::slow",
+                    "--exact",
+                    "--include-ignored"
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn debug_bench_uses_bench_profile_with_debug_info() {
+        let config = Config {
+            profile: Profile::Release,
+            ..Default::default()
+        };
+        let bench = BenchTarget {
+            package: "utils".to_string(),
+            target: Some("performance".to_string()),
+        };
+
+        check!(
+            Command::DebugBench(bench.clone()).into_args(&config)
+                == [
+                    "bench",
+                    "--no-run",
+                    "--package",
+                    "utils",
+                    "--bench",
+                    "performance",
+                    "--config",
+                    "profile.bench.debug=true",
+                    "--message-format=json-render-diagnostics"
+                ]
+        );
+
+        let messages = artifact_message(
+            "performance",
+            "bench",
+            Some("/ws/target/release/deps/performance-1234"),
+        );
+        check!(
+            bench.executable_from_messages(&messages)
+                == Some("/ws/target/release/deps/performance-1234".to_string())
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn debug_doc_test_persists_filtered_doctest() {
+        let test = DocTest {
+            file: "src/lib.rs".to_string(),
+            item: "add".to_string(),
+            line: 5,
+        };
+        let cmd = Command::DebugDocTest {
+            package: "core".to_string(),
+            test,
+            persist_dir: "/ws/target/doctests".to_string(),
+        };
+
+        check!(
+            cmd.into_args(&Config::default())
+                == [
+                    "+nightly",
+                    "test",
+                    "--doc",
+                    "--package",
+                    "core",
+                    "--config",
+                    r#"build.rustdocflags=["-Zunstable-options", "--persist-doctests", "/ws/target/doctests", "--no-run"]"#,
+                    "--",
+                    "src/lib.rs - add (line 5)",
+                    "--exact",
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn sanitized_command_uses_nightly_and_platform_target() {
+        let cmd = Command::Sanitized {
+            cmd: Box::new(Command::Run(Some(RunTarget {
+                package: "core".to_string(),
+                target: Some(RunSubTarget::Bin("cli".to_string())),
+            }))),
+            platform_target: "x86_64-unknown-linux-gnu".to_string(),
+        };
+
+        check!(
+            cmd.into_args(&Config::default())
+                == [
+                    "+nightly",
+                    "run",
+                    "--package",
+                    "core",
+                    "--bin",
+                    "cli",
+                    "--target",
+                    "x86_64-unknown-linux-gnu",
+                ]
+        );
+    }
+}
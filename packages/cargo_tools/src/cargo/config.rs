@@ -42,6 +42,8 @@ pub enum Update {
         features: Features,
     },
     SelectedProfile(Profile),
+    /// The nextest profile, [None] for nextest's default
+    SelectedNextestProfile(Option<String>),
     Refresh(HashMap<String, PackageConfig>),
 }
 
@@ -54,6 +56,8 @@ pub struct Config {
     pub platform_target: Option<String>,
     pub profile: Profile,
     pub selected_features: Features,
+    /// The profile tests run with when nextest is the test runner
+    pub nextest_profile: Option<String>,
 }
 
 impl Config {
@@ -89,6 +93,7 @@ impl Config {
             },
             Update::SelectedPlatformTarget(v) => self.platform_target = v,
             Update::SelectedProfile(v) => self.profile = v,
+            Update::SelectedNextestProfile(v) => self.nextest_profile = v,
             Update::Refresh(package_selection) => {
                 self.package_configs = package_selection
                 // TODO: Should include selected workspace features
@@ -106,6 +111,26 @@ impl Config {
         self.package_configs.get(&p)
     }
 
+    /// Returns a copy which additionally enables the `required` features of `package`,
+    /// e.g. the `required-features` of an example cargo refuses to build without them.
+    pub fn with_required_features(&self, package: &str, required: &[String]) -> Self {
+        let mut config = self.clone();
+        let features = self
+            .package_configs
+            .get(package)
+            .map_or(&self.selected_features, |c| &c.selected_features);
+
+        if let Features::Some(items) = features {
+            let items = items.iter().chain(required).unique().cloned().collect();
+            config
+                .package_configs
+                .entry(package.to_string())
+                .or_default()
+                .selected_features = Features::Some(items);
+        }
+        config
+    }
+
     pub fn get<T>(&self, package: &str, get: impl Fn(&PackageConfig) -> Option<T>) -> Option<T> {
         self.package_configs.get(package).and_then(get)
     }
@@ -116,21 +141,24 @@ impl Config {
             args.extend(["--target".to_string(), platform]);
         }
         args.extend(self.profile.cargo_args());
+        args.extend(self.feature_args(package));
+        args
+    }
 
+    /// The `--features` or `--all-features` arguments of the selected features of `package`
+    pub fn feature_args(&self, package: Option<&str>) -> Vec<String> {
         let features = package
             .and_then(|p| self.package_configs.get(p))
             .map(|c| &c.selected_features)
             .unwrap_or(&self.selected_features);
 
         match features {
-            Features::All => args.push("--all-features".to_string()),
+            Features::All => vec!["--all-features".to_string()],
             Features::Some(items) if !items.is_empty() => {
-                args.extend(["--features".to_string(), items.join(",")])
+                vec!["--features".to_string(), items.join(",")]
             }
-            Features::Some(_) => {}
-        };
-
-        args
+            Features::Some(_) => Vec::new(),
+        }
     }
 
     pub fn selected_features(&self) -> Features {
@@ -246,3 +274,33 @@ impl PackageConfig {
         self.benchmark_target.as_deref() == Some(name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn required_features_extend_selection() {
+        let required = ["json".to_string()];
+        let config = Config {
+            selected_features: Features::Some(vec!["tls".to_string()]),
+            ..Default::default()
+        };
+
+        let extended = config.with_required_features("utils", &required);
+        check!(extended.args(Some("utils")) == ["--features", "tls,json"]);
+        check!(extended.args(Some("core")) == ["--features", "tls"]);
+
+        let twice = extended.with_required_features("utils", &required);
+        check!(twice.args(Some("utils")) == ["--features", "tls,json"]);
+
+        let all = Config {
+            selected_features: Features::All,
+            ..Default::default()
+        };
+        check!(all.with_required_features("utils", &required) == all);
+    }
+}
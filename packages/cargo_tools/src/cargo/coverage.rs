@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// How often a line of a source file was executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct LineCoverage {
+    /// Zero based line
+    pub line: usize,
+    pub count: u64,
+}
+
+/// The line coverage of a source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileCoverage {
+    pub file: String,
+    pub lines: Vec<LineCoverage>,
+}
+
+/// Parses the line coverage of an LCOV report, e.g. from `cargo llvm-cov --lcov`
+pub fn parse_lcov(report: &str) -> Vec<FileCoverage> {
+    let mut files = Vec::new();
+    let mut current: Option<FileCoverage> = None;
+
+    for line in report.lines().map(str::trim) {
+        if let Some(file) = line.strip_prefix("SF:") {
+            current = Some(FileCoverage {
+                file: file.to_string(),
+                lines: Vec::new(),
+            });
+        } else if let Some(data) = line.strip_prefix("DA:") {
+            // `DA:<line>,<count>[,<checksum>]` with one based lines
+            let mut fields = data.split(',');
+            let (Some(file), Some(number), Some(count)) = (
+                current.as_mut(),
+                fields.next().and_then(|l| l.parse::<usize>().ok()),
+                fields.next().and_then(|c| c.parse().ok()),
+            ) else {
+                continue;
+            };
+            file.lines.push(LineCoverage {
+                line: number.saturating_sub(1),
+                count,
+            });
+        } else if line == "end_of_record" {
+            files.extend(current.take());
+        }
+    }
+
+    files
+}
+
+/// Merges the coverage of several reports, the counts of lines covered by more than one are
+/// added up
+pub fn merge(reports: impl IntoIterator<Item = Vec<FileCoverage>>) -> Vec<FileCoverage> {
+    let mut files: BTreeMap<String, BTreeMap<usize, u64>> = BTreeMap::new();
+    for file in reports.into_iter().flatten() {
+        let lines = files.entry(file.file).or_default();
+        for LineCoverage { line, count } in file.lines {
+            *lines.entry(line).or_default() += count;
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|(file, lines)| FileCoverage {
+            file,
+            lines: lines
+                .into_iter()
+                .map(|(line, count)| LineCoverage { line, count })
+                .collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_and_merge_lcov_reports() {
+        let report = "SF:/ws/core/src/lib.rs
+FN:1,add
+DA:1,2
+DA:2,0
+LF:2
+LH:1
+end_of_record
+SF:/ws/core/src/parser.rs
+DA:10,1,abc
+end_of_record
+";
+        let reports = parse_lcov(report);
+        check!(
+            reports[0]
+                == FileCoverage {
+                    file: "/ws/core/src/lib.rs".to_string(),
+                    lines: vec![
+                        LineCoverage { line: 0, count: 2 },
+                        LineCoverage { line: 1, count: 0 },
+                    ],
+                }
+        );
+        check!(reports[1].lines == vec![LineCoverage { line: 9, count: 1 }]);
+
+        let merged = merge([
+            reports,
+            parse_lcov("SF:/ws/core/src/lib.rs\nDA:2,3\nend_of_record\n"),
+        ]);
+        check!(merged.len() == 2);
+        check!(
+            merged[0].lines
+                == vec![
+                    LineCoverage { line: 0, count: 2 },
+                    LineCoverage { line: 1, count: 3 },
+                ]
+        );
+    }
+}
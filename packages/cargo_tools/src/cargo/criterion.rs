@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+
+/// The directory below the target directory criterion writes its reports to
+pub const REPORT_DIR: &str = "criterion";
+
+/// Changes within this fraction are noise, criterion's default `noise_threshold`
+const NOISE_THRESHOLD: f64 = 0.01;
+
+#[derive(Debug, Deserialize)]
+struct BenchmarkId {
+    group_id: String,
+    full_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ConfidenceInterval {
+    lower_bound: f64,
+    upper_bound: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Estimate {
+    confidence_interval: ConfidenceInterval,
+    point_estimate: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Estimates {
+    mean: Estimate,
+    median: Estimate,
+}
+
+/// How a statistic changed since the previous run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Improved,
+    Regressed,
+    /// The confidence interval of the change includes changes within the noise threshold
+    Unchanged,
+}
+
+/// The change of a statistic since the previous run
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Change {
+    /// Relative change, e.g. `-0.05` for 5% faster
+    pub fraction: f64,
+    pub verdict: Verdict,
+}
+
+impl Change {
+    fn of(estimate: &Estimate) -> Self {
+        let interval = &estimate.confidence_interval;
+        let verdict = if interval.lower_bound > NOISE_THRESHOLD {
+            Verdict::Regressed
+        } else if interval.upper_bound < -NOISE_THRESHOLD {
+            Verdict::Improved
+        } else {
+            Verdict::Unchanged
+        };
+        Self {
+            fraction: estimate.point_estimate,
+            verdict,
+        }
+    }
+}
+
+/// The last measurement of a benchmark with its change since the previous run
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchmarkReport {
+    /// The id of the benchmark like `fib/20`
+    pub id: String,
+    pub group: String,
+    /// The directory of the benchmark relative to [REPORT_DIR]
+    pub directory: String,
+    pub mean_ns: f64,
+    pub median_ns: f64,
+    /// [None] for the first run
+    pub mean_change: Option<Change>,
+    pub median_change: Option<Change>,
+}
+
+impl BenchmarkReport {
+    /// Parses the `new/benchmark.json`, `new/estimates.json` and, after the first run, the
+    /// `change/estimates.json` comparing with the previous run criterion wrote to the benchmark's
+    /// `directory`
+    pub fn parse(
+        directory: &str,
+        benchmark: &str,
+        estimates: &str,
+        change: Option<&str>,
+    ) -> Result<Self, serde_json::Error> {
+        let id: BenchmarkId = serde_json::from_str(benchmark)?;
+        let estimates: Estimates = serde_json::from_str(estimates)?;
+        let change: Option<Estimates> = change.map(serde_json::from_str).transpose()?;
+        Ok(Self {
+            id: id.full_id,
+            group: id.group_id,
+            directory: directory.to_string(),
+            mean_ns: estimates.mean.point_estimate,
+            median_ns: estimates.median.point_estimate,
+            mean_change: change.as_ref().map(|c| Change::of(&c.mean)),
+            median_change: change.as_ref().map(|c| Change::of(&c.median)),
+        })
+    }
+
+    /// The mean and median with their changes, e.g. `mean 1.20 µs (-4.1%), median 1.18 µs`
+    pub fn summary(&self) -> String {
+        let statistic = |name: &str, ns: f64, change: Option<Change>| {
+            let change = change
+                .map(|c| format!(" ({:+.1}%)", c.fraction * 100.0))
+                .unwrap_or_default();
+            format!("{name} {}{change}", format_duration(ns))
+        };
+        format!(
+            "{}, {}",
+            statistic("mean", self.mean_ns, self.mean_change),
+            statistic("median", self.median_ns, self.median_change)
+        )
+    }
+}
+
+/// `ns` nanoseconds in the largest unit below which it stays at least 1, like criterion
+/// prints them
+pub fn format_duration(ns: f64) -> String {
+    let (value, unit) = if ns < 1e3 {
+        (ns, "ns")
+    } else if ns < 1e6 {
+        (ns / 1e3, "µs")
+    } else if ns < 1e9 {
+        (ns / 1e6, "ms")
+    } else {
+        (ns / 1e9, "s")
+    };
+    format!("{value:.2} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    const BENCHMARK: &str = r#"{"group_id":"fib","function_id":"20","value_str":null,"throughput":null,"full_id":"fib/20","directory_name":"fib/20","title":"fib/20"}"#;
+
+    const ESTIMATES: &str = r#"{"mean":{"confidence_interval":{"confidence_level":0.95,"lower_bound":1190.2,"upper_bound":1210.9},"point_estimate":1200.5,"standard_error":5.3},"median":{"confidence_interval":{"confidence_level":0.95,"lower_bound":1175.0,"upper_bound":1185.0},"point_estimate":1180.0,"standard_error":2.6},"median_abs_dev":{"confidence_interval":{"confidence_level":0.95,"lower_bound":10.0,"upper_bound":14.0},"point_estimate":12.0,"standard_error":1.0},"slope":null,"std_dev":{"confidence_interval":{"confidence_level":0.95,"lower_bound":40.0,"upper_bound":60.0},"point_estimate":50.0,"standard_error":5.0}}"#;
+
+    const CHANGE: &str = r#"{"mean":{"confidence_interval":{"confidence_level":0.95,"lower_bound":-0.052,"upper_bound":-0.031},"point_estimate":-0.041,"standard_error":0.005},"median":{"confidence_interval":{"confidence_level":0.95,"lower_bound":-0.012,"upper_bound":0.004},"point_estimate":-0.004,"standard_error":0.004}}"#;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn changes_are_judged_against_the_noise_threshold() {
+        let report = BenchmarkReport::parse("fib/20", BENCHMARK, ESTIMATES, Some(CHANGE)).unwrap();
+
+        check!(report.id == "fib/20");
+        check!(report.group == "fib");
+        check!(report.mean_change.map(|c| c.verdict) == Some(Verdict::Improved));
+        check!(report.median_change.map(|c| c.verdict) == Some(Verdict::Unchanged));
+        check!(report.summary() == "mean 1.20 µs (-4.1%), median 1.18 µs (-0.4%)");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn first_runs_have_no_change() {
+        let report = BenchmarkReport::parse("fib/20", BENCHMARK, ESTIMATES, None).unwrap();
+
+        check!(report.mean_change.is_none());
+        check!(report.summary() == "mean 1.20 µs, median 1.18 µs");
+        check!(format_duration(2.5e9) == "2.50 s");
+    }
+}
@@ -0,0 +1,331 @@
+use std::path::Path;
+
+use cargo_metadata::{
+    Message,
+    diagnostic::{Applicability, Diagnostic, DiagnosticLevel, DiagnosticSpan, DiagnosticSpanLine},
+};
+use itertools::Itertools;
+use serde::Serialize;
+
+/// The argument making cargo report the compiler diagnostics as JSON messages, including
+/// the suggestions of rustc
+pub const JSON_MESSAGE_FORMAT: &str = "--message-format=json";
+
+/// The message format of tasks, the JSON messages carry the colored rendering of their
+/// diagnostics which is shown instead of the JSON
+pub const RENDERED_MESSAGE_FORMAT: &str = "--message-format=json-diagnostic-rendered-ansi";
+
+/// The cargo subcommands which compile and report their compiler messages
+const COMPILING_SUBCOMMANDS: [&str; 5] = ["build", "check", "clippy", "test", "bench"];
+
+/// The cargo arguments `args` with [RENDERED_MESSAGE_FORMAT] before the arguments of the
+/// program, [None] if the subcommand doesn't compile or the message format is already chosen
+pub fn with_rendered_messages(args: &[String]) -> Option<Vec<String>> {
+    let end = args
+        .iter()
+        .position(|arg| arg == "--")
+        .unwrap_or(args.len());
+    let cargo_args = &args[..end];
+    // Skips the toolchain like `+nightly` and the global options like `--config k=v`
+    let mut subcommand = None;
+    let mut i = 0;
+    while i < cargo_args.len() {
+        let arg = cargo_args[i].as_str();
+        if matches!(arg, "-Z" | "-C" | "--config") {
+            i += 2;
+            continue;
+        }
+        if !arg.starts_with(['+', '-']) {
+            subcommand = Some(arg);
+            break;
+        }
+        i += 1;
+    }
+    if !COMPILING_SUBCOMMANDS.contains(&subcommand?)
+        || cargo_args
+            .iter()
+            .any(|arg| arg.starts_with("--message-format"))
+    {
+        return None;
+    }
+    let mut args = args.to_vec();
+    args.insert(end, RENDERED_MESSAGE_FORMAT.to_string());
+    Some(args)
+}
+
+/// A zero based range of a source file, as VS Code expects it
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SourceRange {
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+impl SourceRange {
+    fn of(span: &DiagnosticSpan) -> Self {
+        // rustc reports one based lines and columns counting chars, VS Code counts UTF-16 code
+        // units. The lines of the span are included unless they are unavailable.
+        let utf16 = |line: Option<&DiagnosticSpanLine>, column: usize| {
+            let column = column.saturating_sub(1);
+            line.map_or(column, |line| {
+                line.text.chars().take(column).map(char::len_utf16).sum()
+            })
+        };
+        Self {
+            start_line: span.line_start.saturating_sub(1),
+            start_column: utf16(span.text.first(), span.column_start),
+            end_line: span.line_end.saturating_sub(1),
+            end_column: utf16(span.text.last(), span.column_end),
+        }
+    }
+}
+
+/// A replacement of a suggestion
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct SuggestionEdit {
+    pub file: String,
+    pub range: SourceRange,
+    pub replacement: String,
+}
+
+/// A suggestion of rustc which can be applied without review, e.g. removing an unused import
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct QuickFix {
+    pub title: String,
+    pub edits: Vec<SuggestionEdit>,
+}
+
+/// A compiler diagnostic at its primary span with the quick fixes of its suggestions
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+pub struct FileDiagnostic {
+    /// The name of the package the diagnostic was reported for
+    pub package: String,
+    pub file: String,
+    pub range: SourceRange,
+    /// `error`, `warning`, `information` or `hint`
+    pub severity: &'static str,
+    pub message: String,
+    /// The error code or lint name, e.g. `E0308` or `unused_imports`
+    pub code: Option<String>,
+    pub fixes: Vec<QuickFix>,
+}
+
+/// The diagnostics of the `compiler-message`s of a cargo invocation with
+/// [JSON_MESSAGE_FORMAT]. Their files are resolved against `workspace_root`, diagnostics
+/// reported for several targets of a package are only listed once.
+pub fn parse_diagnostics(messages: &str, workspace_root: &str) -> Vec<FileDiagnostic> {
+    Message::parse_stream(messages.as_bytes())
+        .filter_map(Result::ok)
+        .filter_map(|message| match message {
+            Message::CompilerMessage(message) => FileDiagnostic::new(
+                &message.message,
+                package_name(&message.package_id.repr),
+                workspace_root,
+            ),
+            _ => None,
+        })
+        .unique()
+        .collect()
+}
+
+impl FileDiagnostic {
+    fn new(diagnostic: &Diagnostic, package: &str, workspace_root: &str) -> Option<Self> {
+        let severity = match diagnostic.level {
+            DiagnosticLevel::Ice | DiagnosticLevel::Error => "error",
+            DiagnosticLevel::Warning => "warning",
+            DiagnosticLevel::Note => "information",
+            DiagnosticLevel::Help => "hint",
+            // Summaries like `aborting due to 2 previous errors` have no location
+            _ => return None,
+        };
+        let span = diagnostic.spans.iter().find(|span| span.is_primary)?;
+        let fixes = iter_suggestions(diagnostic)
+            .filter_map(|(title, spans)| {
+                let edits: Vec<_> = spans
+                    .iter()
+                    .filter(|span| {
+                        span.suggestion_applicability == Some(Applicability::MachineApplicable)
+                    })
+                    .filter_map(|span| {
+                        Some(SuggestionEdit {
+                            file: resolve(workspace_root, &span.file_name),
+                            range: SourceRange::of(span),
+                            replacement: span.suggested_replacement.clone()?,
+                        })
+                    })
+                    .collect();
+                (!edits.is_empty()).then(|| QuickFix {
+                    title: title.to_string(),
+                    edits,
+                })
+            })
+            .collect();
+
+        Some(Self {
+            package: package.to_string(),
+            file: resolve(workspace_root, &span.file_name),
+            range: SourceRange::of(span),
+            severity,
+            message: diagnostic.message.clone(),
+            code: diagnostic.code.as_ref().map(|code| code.code.clone()),
+            fixes,
+        })
+    }
+
+    /// Maps the files cargo reported with `map`, e.g. to the paths of the extension host
+    pub fn map_paths(mut self, map: impl Fn(&str) -> String) -> Self {
+        self.file = map(&self.file);
+        for edit in self.fixes.iter_mut().flat_map(|fix| &mut fix.edits) {
+            edit.file = map(&edit.file);
+        }
+        self
+    }
+}
+
+/// The messages and spans of the suggestions of `diagnostic`, rustc attaches them to its
+/// `help` children
+fn iter_suggestions(diagnostic: &Diagnostic) -> impl Iterator<Item = (&str, &[DiagnosticSpan])> {
+    std::iter::once(diagnostic)
+        .chain(&diagnostic.children)
+        .map(|d| (d.message.as_str(), d.spans.as_slice()))
+}
+
+/// The package name of a package id like `path+file:///ws/core#0.1.0`,
+/// `registry+https://github.com/rust-lang/crates.io-index#serde@1.0.0` or, before cargo 1.77,
+/// `core 0.1.0 (path+file:///ws/core)`
+pub(crate) fn package_name(package_id: &str) -> &str {
+    match package_id.split_once('#') {
+        Some((url, fragment)) => match fragment.split_once('@') {
+            Some((name, _)) => name,
+            // The name is omitted if it is the last segment of the url
+            None => url.rsplit('/').next().unwrap_or(url),
+        },
+        None => package_id.split(' ').next().unwrap_or(package_id),
+    }
+}
+
+fn resolve(workspace_root: &str, file: &str) -> String {
+    Path::new(workspace_root)
+        .join(file)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    const UNUSED_IMPORT: &str = r#"{"reason":"compiler-message","package_id":"path+file:///ws/core#0.1.0","manifest_path":"/ws/core/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"core","src_path":"/ws/core/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"message":{"rendered":"warning: unused import: `std::fmt`\n","$message_type":"diagnostic","children":[{"children":[],"code":null,"level":"note","message":"`#[warn(unused_imports)]` on by default","rendered":null,"spans":[]},{"children":[],"code":null,"level":"help","message":"remove the unused import","rendered":null,"spans":[{"byte_end":14,"byte_start":0,"column_end":1,"column_start":1,"expansion":null,"file_name":"core/src/lib.rs","is_primary":true,"label":null,"line_end":2,"line_start":1,"suggested_replacement":"","suggestion_applicability":"MachineApplicable","text":[]}]}],"code":{"code":"unused_imports","explanation":null},"level":"warning","message":"unused import: `std::fmt`","spans":[{"byte_end":12,"byte_start":4,"column_end":13,"column_start":5,"expansion":null,"file_name":"core/src/lib.rs","is_primary":true,"label":null,"line_end":1,"line_start":1,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}"#;
+
+    const MISMATCHED_TYPES: &str = r#"{"reason":"compiler-message","package_id":"path+file:///ws/core#0.1.0","manifest_path":"/ws/core/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"core","src_path":"/ws/core/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"message":{"rendered":"error[E0308]: mismatched types\n","$message_type":"diagnostic","children":[{"children":[],"code":null,"level":"help","message":"try using a conversion method","rendered":null,"spans":[{"byte_end":40,"byte_start":40,"column_end":20,"column_start":20,"expansion":null,"file_name":"core/src/lib.rs","is_primary":true,"label":null,"line_end":3,"line_start":3,"suggested_replacement":".to_string()","suggestion_applicability":"MaybeIncorrect","text":[]}]}],"code":{"code":"E0308","explanation":null},"level":"error","message":"mismatched types","spans":[{"byte_end":40,"byte_start":35,"column_end":20,"column_start":15,"expansion":null,"file_name":"core/src/lib.rs","is_primary":true,"label":"expected `String`, found `&str`","line_end":3,"line_start":3,"suggested_replacement":null,"suggestion_applicability":null,"text":[]}]}}"#;
+
+    const ABORTING: &str = r#"{"reason":"compiler-message","package_id":"path+file:///ws/core#0.1.0","manifest_path":"/ws/core/Cargo.toml","target":{"kind":["lib"],"crate_types":["lib"],"name":"core","src_path":"/ws/core/src/lib.rs","edition":"2021","doc":true,"doctest":true,"test":true},"message":{"rendered":"error: aborting due to 1 previous error\n","$message_type":"diagnostic","children":[],"code":null,"level":"error","message":"aborting due to 1 previous error","spans":[]}}"#;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn machine_applicable_suggestions_are_quick_fixes() {
+        // The lib and its unit tests report the same warning
+        let messages = [UNUSED_IMPORT, UNUSED_IMPORT, MISMATCHED_TYPES, ABORTING].join("\n");
+
+        let diagnostics = parse_diagnostics(&messages, "/ws");
+
+        check!(diagnostics.len() == 2);
+        check!(
+            diagnostics[0]
+                == FileDiagnostic {
+                    package: "core".to_string(),
+                    file: "/ws/core/src/lib.rs".to_string(),
+                    range: SourceRange {
+                        start_line: 0,
+                        start_column: 4,
+                        end_line: 0,
+                        end_column: 12,
+                    },
+                    severity: "warning",
+                    message: "unused import: `std::fmt`".to_string(),
+                    code: Some("unused_imports".to_string()),
+                    fixes: vec![QuickFix {
+                        title: "remove the unused import".to_string(),
+                        edits: vec![SuggestionEdit {
+                            file: "/ws/core/src/lib.rs".to_string(),
+                            range: SourceRange {
+                                start_line: 0,
+                                start_column: 0,
+                                end_line: 1,
+                                end_column: 0,
+                            },
+                            replacement: String::new(),
+                        }],
+                    }],
+                }
+        );
+        // Suggestions which may be incorrect need a review
+        check!(diagnostics[1].severity == "error");
+        check!(diagnostics[1].code.as_deref() == Some("E0308"));
+        check!(diagnostics[1].fixes.is_empty());
+
+        let mapped = diagnostics[0]
+            .clone()
+            .map_paths(|file| file.replace("/ws", "C:/ws"));
+        check!(mapped.file == "C:/ws/core/src/lib.rs");
+        check!(mapped.fixes[0].edits[0].file == "C:/ws/core/src/lib.rs");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn columns_count_utf16_code_units() {
+        let span: DiagnosticSpan = serde_json::from_str(
+            r#"{"byte_end":36,"byte_start":32,"column_end":33,"column_start":29,"expansion":null,"file_name":"core/src/lib.rs","is_primary":true,"label":null,"line_end":1,"line_start":1,"suggested_replacement":null,"suggestion_applicability":null,"text":[{"text":"let crab = \"🦀\"; let x: u8 = 1u16;","highlight_start":29,"highlight_end":33}]}"#,
+        )
+        .unwrap();
+        let range = SourceRange::of(&span);
+        check!((range.start_column, range.end_column) == (29, 33));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn compiling_subcommands_report_rendered_messages() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        check!(
+            with_rendered_messages(&args(&[
+                "+nightly",
+                "test",
+                "-p",
+                "core",
+                "--",
+                "--nocapture"
+            ])) == Some(args(&[
+                "+nightly",
+                "test",
+                "-p",
+                "core",
+                RENDERED_MESSAGE_FORMAT,
+                "--",
+                "--nocapture"
+            ]))
+        );
+        check!(
+            with_rendered_messages(&args(&["--config", "build.jobs=2", "build"]))
+                == Some(args(&[
+                    "--config",
+                    "build.jobs=2",
+                    "build",
+                    RENDERED_MESSAGE_FORMAT
+                ]))
+        );
+        check!(with_rendered_messages(&args(&["run", "--bin", "build"])).is_none());
+        check!(with_rendered_messages(&args(&["build", "--message-format=short"])).is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn package_names_are_taken_from_package_ids() {
+        check!(package_name("path+file:///ws/core#0.1.0") == "core");
+        check!(package_name("path+file:///ws/crates/api#core-api@0.1.0") == "core-api");
+        check!(
+            package_name("registry+https://github.com/rust-lang/crates.io-index#serde@1.0.210")
+                == "serde"
+        );
+        check!(package_name("core 0.1.0 (path+file:///ws/core)") == "core");
+    }
+}
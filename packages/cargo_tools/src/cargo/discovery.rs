@@ -0,0 +1,100 @@
+/// The root manifests of the Cargo workspaces among `manifests`, pairs of a path relative to
+/// the folder like `rust/Cargo.toml` and its content. A root manifest declares `[workspace]` or
+/// is a package which isn't inside another workspace or package. Manifests more than `max_depth`
+/// directories below the folder are ignored. The shallowest manifests come first.
+pub fn workspace_manifests(manifests: &[(String, String)], max_depth: usize) -> Vec<String> {
+    let mut candidates: Vec<(&str, bool)> = manifests
+        .iter()
+        .filter(|(path, _)| depth(path) <= max_depth)
+        .filter_map(|(path, content)| {
+            let manifest = content.parse::<toml::Table>().ok()?;
+            let is_workspace = manifest.contains_key("workspace");
+            (is_workspace || manifest.contains_key("package"))
+                .then_some((path.as_str(), is_workspace))
+        })
+        .collect();
+    candidates.sort_by(|(a, _), (b, _)| depth(a).cmp(&depth(b)).then(a.cmp(b)));
+
+    let workspaces: Vec<&str> = candidates
+        .iter()
+        .filter(|(_, is_workspace)| *is_workspace)
+        .map(|(path, _)| *path)
+        .collect();
+    let mut roots: Vec<&str> = Vec::new();
+    for (path, is_workspace) in candidates {
+        // Nested workspaces are independent, members and path dependencies of an outer
+        // workspace or package are not
+        let covered = !is_workspace
+            && workspaces
+                .iter()
+                .chain(roots.iter())
+                .any(|root| contains(root, path));
+        if !covered {
+            roots.push(path);
+        }
+    }
+    roots.into_iter().map(str::to_string).collect()
+}
+
+/// The number of directories between the folder and the manifest
+fn depth(manifest: &str) -> usize {
+    manifest.matches('/').count()
+}
+
+fn dir(manifest: &str) -> &str {
+    manifest.rsplit_once('/').map_or("", |(dir, _)| dir)
+}
+
+/// Whether the manifest `inner` is in the directory of the manifest `outer` or below it
+fn contains(outer: &str, inner: &str) -> bool {
+    let outer = dir(outer);
+    outer.is_empty() || dir(inner) == outer || dir(inner).starts_with(&format!("{outer}/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn workspaces_of_monorepo() {
+        let manifest = |path: &str, content: &str| (path.to_string(), content.to_string());
+        let manifests = [
+            manifest("rust/Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n"),
+            manifest(
+                "rust/crates/core/Cargo.toml",
+                "[package]\nname = \"core\"\n",
+            ),
+            manifest("tools/lint/Cargo.toml", "[package]\nname = \"lint\"\n"),
+            manifest(
+                "tools/lint/helper/Cargo.toml",
+                "[package]\nname = \"helper\"\n",
+            ),
+            manifest(
+                "rust/examples/wasm/Cargo.toml",
+                "[package]\nname = \"wasm\"\n\n[workspace]\n",
+            ),
+            manifest(
+                "web/deep/er/than/max/Cargo.toml",
+                "[package]\nname = \"deep\"\n",
+            ),
+            manifest("broken/Cargo.toml", "[package\n"),
+        ];
+        check!(
+            workspace_manifests(&manifests, 3)
+                == [
+                    "rust/Cargo.toml",
+                    "tools/lint/Cargo.toml",
+                    "rust/examples/wasm/Cargo.toml"
+                ]
+        );
+
+        let root = [
+            manifest("Cargo.toml", "[workspace]\n"),
+            manifest("cli/Cargo.toml", "[package]\nname = \"cli\"\n"),
+        ];
+        check!(workspace_manifests(&root, 3) == ["Cargo.toml"]);
+    }
+}
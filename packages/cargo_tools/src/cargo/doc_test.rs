@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cargo::{
+        metadata::{Package, TargetType},
+        test_case::package_of,
+        test_tree::SourceFile,
+    },
+    stable_hash::stable_hash,
+};
+
+/// A documentation test as `cargo test --doc -- --list` reports it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocTest {
+    /// The documented source file relative to the package, e.g. `src/lib.rs`
+    pub file: String,
+    /// Path of the documented item, e.g. `parser::Parser::new`
+    pub item: String,
+    /// Line of the code block in `file`
+    pub line: usize,
+}
+
+impl DocTest {
+    /// Parses the doctests from the output of `cargo test --doc -- --list`, whose lines look
+    /// like `src/lib.rs - parser::Parser::new (line 12): test`
+    pub fn parse_list(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let name = line.trim().strip_suffix(": test")?;
+                let (name, line) = name.rsplit_once(" (line ")?;
+                let (file, item) = name.split_once(" - ")?;
+                Some(Self {
+                    file: file.to_string(),
+                    item: item.to_string(),
+                    line: line.strip_suffix(')')?.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+
+    /// The name libtest reports for the doctest
+    pub fn name(&self) -> String {
+        format!("{} - {} (line {})", self.file, self.item, self.line)
+    }
+
+    /// The directory rustdoc's `--persist-doctests` keeps the doctest executable in, relative
+    /// to the persist directory.
+    pub fn persisted_dir(&self) -> String {
+        let file = self.file.replace(['/', '\\', '.'], "_");
+        // The last number distinguishes doctests starting on the same line, e.g. from macros
+        format!("{file}_{}_0", self.line)
+    }
+}
+
+/// The doctests listed per package with the fingerprint they were listed for, persisted so
+/// that listing them, which builds the package, only runs again when its sources or the
+/// arguments of the listing changed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocTestCache(BTreeMap<String, CachedDocTests>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CachedDocTests {
+    fingerprint: u64,
+    tests: Vec<DocTest>,
+}
+
+impl DocTestCache {
+    /// The doctests of `package` if they were listed for the same [source_hashes] entry and
+    /// `args`
+    pub fn get(&self, package: &str, source_hash: u64, args: &[String]) -> Option<Vec<DocTest>> {
+        self.0
+            .get(package)
+            .filter(|cached| cached.fingerprint == fingerprint(source_hash, args))
+            .map(|cached| cached.tests.clone())
+    }
+
+    /// Caches the doctests listed for `package`, replacing those of an older fingerprint
+    pub fn insert(
+        &mut self,
+        package: String,
+        source_hash: u64,
+        args: &[String],
+        tests: Vec<DocTest>,
+    ) {
+        let fingerprint = fingerprint(source_hash, args);
+        self.0
+            .insert(package, CachedDocTests { fingerprint, tests });
+    }
+}
+
+fn fingerprint(source_hash: u64, args: &[String]) -> u64 {
+    stable_hash(&(source_hash, args))
+}
+
+/// The hash of the paths and contents of the source `files` of each package, which changes
+/// when a file of the package is edited, added or removed
+pub fn source_hashes(packages: &[Package], files: &[SourceFile]) -> HashMap<String, u64> {
+    // Sorted by path, the order the files are found in may differ between discoveries
+    let mut files_by_package: BTreeMap<&str, BTreeMap<String, &str>> = BTreeMap::new();
+    for file in files {
+        let path = file.path.replace('\\', "/");
+        if let Some((package, _)) = package_of(packages, &path) {
+            files_by_package
+                .entry(&package.name)
+                .or_default()
+                .insert(path, &file.source);
+        }
+    }
+    files_by_package
+        .into_iter()
+        .map(|(package, files)| (package.to_string(), stable_hash(&files)))
+        .collect()
+}
+
+/// A code block in the documentation of a library source file, see
+/// [crate::rust_source::find_doc_code_blocks]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocTestLocation {
+    pub package: String,
+    /// The source file relative to the package, as rustdoc names it
+    pub file: String,
+    /// One based line of the code block
+    pub line: usize,
+}
+
+impl DocTestLocation {
+    /// Resolves the code block at the zero based `line` of `file`. Only the sources of
+    /// library targets have doctests.
+    pub fn locate(packages: &[Package], file: &str, line: usize) -> Option<Self> {
+        let file = file.replace('\\', "/");
+        let (package, package_dir) = package_of(packages, &file)?;
+        let lib_dir = package
+            .targets
+            .iter()
+            .find(|t| t.target_type == TargetType::Lib)
+            .and_then(|t| {
+                t.source
+                    .replace('\\', "/")
+                    .rsplit_once('/')
+                    .map(|(dir, _)| dir.to_string())
+            })?;
+        if !file.starts_with(&format!("{lib_dir}/")) {
+            return None;
+        }
+
+        Some(Self {
+            package: package.name.clone(),
+            file: file[package_dir.len()..].to_string(),
+            line: line + 1,
+        })
+    }
+
+    /// The doctest of this code block from the doctests of its package
+    pub fn find(&self, tests: Vec<DocTest>) -> Option<DocTest> {
+        tests
+            .into_iter()
+            .find(|test| test.file.replace('\\', "/") == self.file && test.line == self.line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::metadata::Target;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_doc_test_list() {
+        let output = "src/lib.rs - add (line 5): test\nsrc/parser/mod.rs - parser::Parser::new (line 42): test\n\n2 tests, 0 benchmarks\n";
+
+        let tests = DocTest::parse_list(output);
+
+        check!(
+            tests
+                == vec![
+                    DocTest {
+                        file: "src/lib.rs".to_string(),
+                        item: "add".to_string(),
+                        line: 5,
+                    },
+                    DocTest {
+                        file: "src/parser/mod.rs".to_string(),
+                        item: "parser::Parser::new".to_string(),
+                        line: 42,
+                    },
+                ]
+        );
+        check!(tests[1].name() == "src/parser/mod.rs - parser::Parser::new (line 42)");
+        check!(tests[1].persisted_dir() == "src_parser_mod_rs_42_0");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn doc_test_cache_follows_sources_and_args() {
+        let packages = vec![
+            Package::fixture("app", "/ws/Cargo.toml", Vec::new()),
+            Package::fixture("core", "/ws/core/Cargo.toml", Vec::new()),
+        ];
+        let file = |path: &str, source: &str| SourceFile {
+            path: path.to_string(),
+            source: source.to_string(),
+        };
+        let files = vec![
+            file("/ws/src/main.rs", "fn main() {}"),
+            file(
+                "/ws/core/src/lib.rs",
+                "/// ```\n/// assert!(true);\n/// ```\npub fn f() {}",
+            ),
+        ];
+        let hashes = source_hashes(&packages, &files);
+        let args = vec!["test".to_string(), "--doc".to_string()];
+        let tests = DocTest::parse_list("src/lib.rs - f (line 1): test\n");
+
+        let mut cache = DocTestCache::default();
+        cache.insert("core".to_string(), hashes["core"], &args, tests.clone());
+        check!(cache.get("core", hashes["core"], &args) == Some(tests));
+
+        // Changing another package keeps the cached doctests
+        let mut reordered = files.clone();
+        reordered.reverse();
+        reordered[1].source = "fn main() { println!() }".to_string();
+        let unchanged = source_hashes(&packages, &reordered);
+        check!(unchanged["core"] == hashes["core"]);
+        check!(unchanged["app"] != hashes["app"]);
+
+        let mut edited = files.clone();
+        edited[1].source.push_str("\npub fn g() {}");
+        let edited = source_hashes(&packages, &edited);
+        check!(cache.get("core", edited["core"], &args).is_none());
+        check!(
+            cache
+                .get("core", hashes["core"], &["test".to_string()])
+                .is_none()
+        );
+        check!(cache.get("app", hashes["app"], &args).is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn locate_doc_test_in_library() {
+        let packages = vec![Package::fixture(
+            "core",
+            "/ws/core/Cargo.toml",
+            vec![
+                Target::fixture("core", "/ws/core/src/lib.rs", TargetType::Lib),
+                Target::fixture("core", "/ws/core/examples/demo.rs", TargetType::Example),
+            ],
+        )];
+
+        let location = DocTestLocation::locate(&packages, "/ws/core/src/parser/mod.rs", 41);
+        check!(
+            location
+                == Some(DocTestLocation {
+                    package: "core".to_string(),
+                    file: "src/parser/mod.rs".to_string(),
+                    line: 42,
+                })
+        );
+        check!(DocTestLocation::locate(&packages, "/ws/core/examples/demo.rs", 3).is_none());
+
+        let tests = DocTest::parse_list(
+            "src/lib.rs - add (line 42): test\nsrc/parser/mod.rs - parser::Parser::new (line 42): test\n",
+        );
+        check!(
+            location.and_then(|l| l.find(tests)).map(|t| t.item)
+                == Some("parser::Parser::new".to_string())
+        );
+    }
+}
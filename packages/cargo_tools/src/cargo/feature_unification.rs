@@ -0,0 +1,201 @@
+/// A feature of a dependency after unification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnifiedFeature {
+    pub name: String,
+    /// What enables the feature directly, e.g. `feature "full"` of the dependency itself or
+    /// `reqwest v0.12.0` which declares it in its dependencies
+    pub enabled_by: Vec<String>,
+    /// The workspace members closest to the dependency on the paths enabling the feature
+    pub members: Vec<String>,
+}
+
+/// The unified features of one version of a dependency
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeatureReport {
+    /// The package and its version, e.g. `tokio v1.40.0`
+    pub package: String,
+    pub features: Vec<UnifiedFeature>,
+}
+
+/// A node of the inverted tree of `cargo tree --edges features`
+struct Node<'a> {
+    package: &'a str,
+    /// The feature of `package`, [None] for the package itself
+    feature: Option<&'a str>,
+    text: &'a str,
+}
+
+impl<'a> Node<'a> {
+    fn parse(text: &'a str) -> Self {
+        match text.split_once(" feature \"") {
+            Some((package, feature)) => Self {
+                package,
+                feature: feature.split('"').next(),
+                text,
+            },
+            None => Self {
+                package: text.split(' ').next().unwrap_or(text),
+                feature: None,
+                text,
+            },
+        }
+    }
+
+    /// The node without annotations like `(/path/to/package)`, `(command-line)` or `(*)`
+    fn label(&self, dependency: &str) -> String {
+        match self.feature {
+            Some(feature) if self.package == dependency => format!("feature \"{feature}\""),
+            Some(feature) => format!("{} feature \"{feature}\"", self.package),
+            None => self
+                .text
+                .split(" (")
+                .next()
+                .unwrap_or(self.text)
+                .to_string(),
+        }
+    }
+}
+
+/// The reports of the output of `cargo tree` with the features of the versions of `dependency`,
+/// see [tree_args](super::reverse_dependencies::tree_args), the workspace `members` are the ones
+/// causing its features
+pub fn parse_tree(output: &str, dependency: &str, members: &[String]) -> Vec<FeatureReport> {
+    let mut reports: Vec<FeatureReport> = Vec::new();
+    // The depth of the member found on the current path
+    let mut member_depth: Option<usize> = None;
+    // Whether the current depth 1 node is a feature of the dependency
+    let mut in_feature = false;
+    for line in output.lines() {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        let Ok(depth) = line[..digits].parse::<usize>() else {
+            continue;
+        };
+        let node = Node::parse(line[digits..].trim());
+        if member_depth.is_some_and(|d| depth <= d) {
+            member_depth = None;
+        }
+        match depth {
+            0 => {
+                in_feature = false;
+                reports.push(FeatureReport {
+                    package: node.label(dependency),
+                    features: Vec::new(),
+                });
+            }
+            1 => {
+                let Some(report) = reports.last_mut() else {
+                    continue;
+                };
+                // Dependents which don't enable any feature are no feature nodes
+                in_feature = node.feature.is_some() && node.package == dependency;
+                if let (Some(feature), true) = (node.feature, in_feature) {
+                    report.features.push(UnifiedFeature {
+                        name: feature.to_string(),
+                        enabled_by: Vec::new(),
+                        members: Vec::new(),
+                    });
+                }
+            }
+            _ if in_feature => {
+                let Some(feature) = reports.last_mut().and_then(|r| r.features.last_mut()) else {
+                    continue;
+                };
+                let label = node.label(dependency);
+                if depth == 2 && !feature.enabled_by.contains(&label) {
+                    feature.enabled_by.push(label);
+                }
+                let package = node.package.to_string();
+                if member_depth.is_none() && members.contains(&package) {
+                    member_depth = Some(depth);
+                    if !feature.members.contains(&package) {
+                        feature.members.push(package);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    reports
+}
+
+/// The reports as markdown tables
+pub fn to_markdown(dependency: &str, reports: &[FeatureReport]) -> String {
+    let mut markdown = format!("# Features of {dependency}\n\n");
+    if reports.is_empty() {
+        markdown.push_str("The workspace doesn't depend on it\n");
+    }
+    for report in reports {
+        markdown.push_str(&format!("## {}\n\n", report.package));
+        if report.features.is_empty() {
+            markdown.push_str("No features are enabled\n\n");
+            continue;
+        }
+        markdown.push_str("| Feature | Enabled by | Workspace members |\n| --- | --- | --- |\n");
+        for feature in &report.features {
+            markdown.push_str(&format!(
+                "| {} | {} | {} |\n",
+                feature.name,
+                feature.enabled_by.join(", "),
+                feature.members.join(", ")
+            ));
+        }
+        markdown.push('\n');
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn features_are_explained() {
+        let output = r#"0tokio v1.40.0
+1tokio feature "default"
+2app v0.1.0 (/ws/app)
+3app feature "default" (command-line)
+1tokio feature "macros"
+2tokio feature "full"
+3app v0.1.0 (/ws/app)
+4app feature "default" (command-line)
+2reqwest v0.12.0
+3reqwest feature "default"
+4core v0.1.0 (/ws/core)
+5core feature "default" (command-line)
+6app v0.1.0 (/ws/app)
+7app feature "default" (command-line)
+1core v0.1.0 (/ws/core)
+2core feature "default" (command-line)
+"#;
+        let members = ["app", "core"].map(str::to_string);
+        let reports = parse_tree(output, "tokio", &members);
+        check!(
+            reports
+                == vec![FeatureReport {
+                    package: "tokio v1.40.0".to_string(),
+                    features: vec![
+                        UnifiedFeature {
+                            name: "default".to_string(),
+                            enabled_by: vec!["app v0.1.0".to_string()],
+                            members: vec!["app".to_string()],
+                        },
+                        UnifiedFeature {
+                            name: "macros".to_string(),
+                            enabled_by: vec![
+                                "feature \"full\"".to_string(),
+                                "reqwest v0.12.0".to_string()
+                            ],
+                            members: vec!["app".to_string(), "core".to_string()],
+                        },
+                    ],
+                }]
+        );
+        check!(
+            to_markdown("tokio", &reports)
+                .contains("| macros | feature \"full\", reqwest v0.12.0 | app, core |")
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A cargo-generate template of `cargoTools.cargoGenerate.templates`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Template {
+    /// The git repository of the template, e.g. `https://github.com/rust-github/template`
+    pub git: Option<String>,
+    /// The local directory of the template, used if there is no repository
+    pub path: Option<String>,
+    pub branch: Option<String>,
+    /// The directory of the template within the repository or directory
+    pub subfolder: Option<String>,
+    pub description: Option<String>,
+    /// The placeholders the user is asked for with their defaults, cargo-generate uses the
+    /// defaults of the template for all others
+    pub values: BTreeMap<String, String>,
+}
+
+impl Template {
+    /// Where the template comes from, the repository or the directory and the subfolder
+    pub fn source(&self) -> String {
+        let source = self.git.as_deref().or(self.path.as_deref()).unwrap_or("");
+        match &self.subfolder {
+            Some(subfolder) => format!("{source} ({subfolder})"),
+            None => source.to_string(),
+        }
+    }
+
+    /// The arguments of the `cargo generate` which creates the package `name` in the directory
+    /// `destination`, `values` define the placeholders. [None] if the template has neither a
+    /// repository nor a directory.
+    pub fn args(
+        &self,
+        name: &str,
+        destination: &str,
+        values: &[(String, String)],
+    ) -> Option<Vec<String>> {
+        let source = match (&self.git, &self.path) {
+            (Some(git), _) => ["--git", git.as_str()],
+            (None, Some(path)) => ["--path", path.as_str()],
+            (None, None) => return None,
+        };
+        let mut args: Vec<String> = std::iter::once("generate")
+            .chain(source)
+            .map(str::to_string)
+            .collect();
+        if let Some(branch) = &self.branch {
+            args.extend(["--branch".to_string(), branch.clone()]);
+        }
+        // The package becomes part of the workspace's repository instead of getting its own and
+        // cargo generate does not prompt as it does not run in a terminal
+        args.extend(
+            [
+                "--name",
+                name,
+                "--destination",
+                destination,
+                "--vcs",
+                "none",
+                "--silent",
+            ]
+            .map(str::to_string),
+        );
+        for (key, value) in values {
+            args.extend(["--define".to_string(), format!("{key}={value}")]);
+        }
+        args.extend(self.subfolder.clone());
+        Some(args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn generate_args() {
+        let template = Template {
+            git: Some("https://github.com/acme/templates".to_string()),
+            branch: Some("main".to_string()),
+            subfolder: Some("axum-service".to_string()),
+            ..Template::default()
+        };
+        check!(template.source() == "https://github.com/acme/templates (axum-service)");
+        let values = [("port".to_string(), "8080".to_string())];
+        check!(
+            template.args("orders", "/ws/services", &values).unwrap()
+                == [
+                    "generate",
+                    "--git",
+                    "https://github.com/acme/templates",
+                    "--branch",
+                    "main",
+                    "--name",
+                    "orders",
+                    "--destination",
+                    "/ws/services",
+                    "--vcs",
+                    "none",
+                    "--silent",
+                    "--define",
+                    "port=8080",
+                    "axum-service"
+                ]
+        );
+
+        let local = Template {
+            path: Some("/templates/cli".to_string()),
+            ..Template::default()
+        };
+        check!(
+            local.args("tool", "/ws", &[]).unwrap()
+                == [
+                    "generate",
+                    "--path",
+                    "/templates/cli",
+                    "--name",
+                    "tool",
+                    "--destination",
+                    "/ws",
+                    "--vcs",
+                    "none",
+                    "--silent"
+                ]
+        );
+        check!(Template::default().args("tool", "/ws", &[]).is_none());
+    }
+}
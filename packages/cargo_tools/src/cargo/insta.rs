@@ -0,0 +1,91 @@
+/// The `INSTA_UPDATE` modes which decide how insta's assertions write changed snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstaUpdate {
+    Auto,
+    Always,
+    Unseen,
+    New,
+    No,
+}
+
+impl InstaUpdate {
+    pub const fn all() -> [Self; 5] {
+        [Self::Auto, Self::New, Self::Always, Self::Unseen, Self::No]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Always => "always",
+            Self::Unseen => "unseen",
+            Self::New => "new",
+            Self::No => "no",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|u| u.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Auto => "Write .snap.new files for review, outside of CI",
+            Self::Always => "Overwrite the snapshots with the new values",
+            Self::Unseen => "Write new snapshots directly, changed ones for review",
+            Self::New => "Write .snap.new files for review",
+            Self::No => "Don't write any snapshots",
+        }
+    }
+
+    /// The environment variable which runs insta's assertions in this mode
+    pub fn env(&self) -> (String, String) {
+        ("INSTA_UPDATE".to_string(), self.name().to_string())
+    }
+}
+
+/// The extension of the pending snapshots insta writes next to the `.snap` files for review
+pub const PENDING_SNAPSHOT_EXTENSION: &str = ".snap.new";
+
+/// Whether the output of a failed test reports a mismatching insta snapshot
+pub fn is_snapshot_failure(output: &str) -> bool {
+    output.contains("Snapshot Summary") || output.contains("snapshot assertion for")
+}
+
+/// The `.snap` file a pending `.snap.new` snapshot replaces when it is accepted
+pub fn accepted_snapshot(pending: &str) -> Option<&str> {
+    pending
+        .strip_suffix(".new")
+        .filter(|s| s.ends_with(".snap"))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn insta_update_modes_and_failures() {
+        check!(InstaUpdate::from_name("unseen") == Some(InstaUpdate::Unseen));
+        check!(InstaUpdate::No.env() == ("INSTA_UPDATE".to_string(), "no".to_string()));
+
+        let output = "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+Snapshot Summary ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+Snapshot: renders
+Source: src/lib.rs:12
+thread 'tests::renders' panicked at insta/src/runtime.rs:563:13:
+snapshot assertion for 'renders' failed in line 12
+";
+        check!(is_snapshot_failure(output));
+        check!(!is_snapshot_failure(
+            "thread 'tests::parses' panicked at src/lib.rs:3:5:\nassertion failed"
+        ));
+
+        check!(
+            accepted_snapshot("src/snapshots/core__tests__renders.snap.new")
+                == Some("src/snapshots/core__tests__renders.snap")
+        );
+        check!(accepted_snapshot("src/lib.rs").is_none());
+    }
+}
@@ -0,0 +1,459 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::cargo::{
+    TestTarget,
+    metadata::{Package, TargetType},
+};
+
+/// The outcome of a single test as libtest reports it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TestOutcome {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+/// Which `#[ignore]`d tests libtest runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoredTests {
+    /// Only the tests that are not ignored, libtest's default
+    #[default]
+    Skip,
+    /// Only the ignored tests, `--ignored`
+    Only,
+    /// All tests, `--include-ignored`
+    Include,
+}
+
+impl IgnoredTests {
+    /// The libtest argument selecting the tests
+    pub fn harness_arg(self) -> Option<&'static str> {
+        match self {
+            Self::Skip => None,
+            Self::Only => Some("--ignored"),
+            Self::Include => Some("--include-ignored"),
+        }
+    }
+}
+
+/// The test executable a result was reported by
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestSuite {
+    /// The crate root of the target relative to the package, e.g. `src/lib.rs`
+    Target(String),
+    Doc,
+}
+
+impl TestSuite {
+    /// The target of `package` whose crate root this suite ran
+    pub fn target(&self, package: &Package) -> Option<TestTarget> {
+        let Self::Target(root) = self else {
+            return None;
+        };
+        let root = root.replace('\\', "/");
+        if let Some(test) = root.strip_prefix("tests/") {
+            let test = test.strip_suffix("/main.rs").unwrap_or(test);
+            return Some(TestTarget::Test(test.trim_end_matches(".rs").to_string()));
+        }
+
+        let dir = package.manifest_dir().replace('\\', "/");
+        package
+            .targets
+            .iter()
+            .find(|t| t.source.replace('\\', "/") == format!("{dir}/{root}"))
+            .and_then(|t| match t.target_type {
+                TargetType::Lib => Some(TestTarget::Lib),
+                TargetType::Bin => Some(TestTarget::Bin(t.name.clone())),
+                TargetType::Example | TargetType::Bench => None,
+            })
+    }
+}
+
+/// The result of a single test from the output of `cargo test`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub suite: TestSuite,
+    /// The name libtest reports for the test
+    pub name: String,
+    pub outcome: TestOutcome,
+    /// The captured output of a failed test
+    pub message: Option<String>,
+    /// How long the test took, libtest only reports it with `-Zunstable-options --report-time`
+    pub duration: Option<Duration>,
+}
+
+/// Where a test panicked, as the panic message reports it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PanicLocation {
+    /// Absolute or relative to the workspace root, like `file!()`
+    pub file: String,
+    /// One based line and column
+    pub line: usize,
+    pub column: usize,
+}
+
+impl PanicLocation {
+    /// Parses `src/lib.rs:10:9`, paths on Windows may contain a drive letter
+    fn parse(location: &str) -> Option<Self> {
+        let mut parts = location.trim().rsplitn(3, ':');
+        let column = parts.next()?.parse().ok()?;
+        let line = parts.next()?.parse().ok()?;
+        Some(Self {
+            file: parts.next()?.to_string(),
+            line,
+            column,
+        })
+    }
+}
+
+/// The panic of a failed test from its captured output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    /// The panic message, e.g. ``assertion `left == right` failed``
+    pub message: String,
+    pub location: Option<PanicLocation>,
+    /// The values of a failed `assert_eq!` or `assert_ne!`
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+impl Panic {
+    /// Parses the panic from the output of a failed test, which reports it as
+    ///
+    /// ```text
+    /// thread 'tests::adds' panicked at src/lib.rs:10:9:
+    /// assertion `left == right` failed
+    ///   left: 1
+    ///  right: 2
+    /// ```
+    ///
+    /// Before Rust 1.73 the message came first: ``panicked at 'assertion failed: `(left ==
+    /// right)`\n  left: `1`,\n right: `2`', src/lib.rs:10:9``.
+    pub fn parse(output: &str) -> Option<Self> {
+        let (_, panic) = output.split_once("panicked at ")?;
+        let (location, message) = match panic.strip_prefix('\'') {
+            Some(old) => {
+                let (message, rest) = old.rsplit_once("', ")?;
+                let location = rest.lines().next().unwrap_or_default();
+                (location, message.to_string())
+            }
+            None => {
+                let (location, rest) = panic.split_once('\n').unwrap_or((panic, ""));
+                let message = rest
+                    .lines()
+                    .take_while(|l| !l.starts_with("note: ") && !l.starts_with("stack backtrace:"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                (location.trim_end().trim_end_matches(':'), message)
+            }
+        };
+
+        let (message, left, right) = split_assertion_values(&message);
+        Some(Self {
+            message,
+            location: PanicLocation::parse(location),
+            left,
+            right,
+        })
+    }
+}
+
+/// Splits the `left:` and `right:` values, which may span several lines with `{:#?}`, from
+/// an assertion message
+fn split_assertion_values(message: &str) -> (String, Option<String>, Option<String>) {
+    let lines: Vec<&str> = message.lines().collect();
+    let start = |label: &str| lines.iter().position(|l| l.trim_start().starts_with(label));
+    let (Some(left), Some(right)) = (start("left:"), start("right:")) else {
+        return (message.trim().to_string(), None, None);
+    };
+    if right < left {
+        return (message.trim().to_string(), None, None);
+    }
+
+    let value = |lines: &[&str], label: &str| {
+        let first = lines[0].trim_start().trim_start_matches(label).trim_start();
+        let value = std::iter::once(first)
+            .chain(lines[1..].iter().copied())
+            .collect::<Vec<_>>()
+            .join("\n");
+        // Old messages quote the values in backticks and separate them with a comma
+        let value = value.trim_end().trim_end_matches(',');
+        let value = value
+            .strip_prefix('`')
+            .and_then(|v| v.strip_suffix('`'))
+            .unwrap_or(value);
+        value.to_string()
+    };
+
+    (
+        lines[..left].join("\n").trim().to_string(),
+        Some(value(&lines[left..right], "left:")),
+        Some(value(&lines[right..], "right:")),
+    )
+}
+
+/// Parses the results from the combined stdout and stderr of `cargo test`.
+///
+/// Cargo announces each test executable with `Running unittests src/lib.rs (...)`,
+/// `Running tests/api.rs (...)` or `Doc-tests name` before libtest prints its results.
+pub fn parse_results(output: &str) -> Vec<TestResult> {
+    let mut results: Vec<TestResult> = Vec::new();
+    let mut suite = None;
+    let mut failure: Option<(usize, Vec<&str>)> = None;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(running) = trimmed.strip_prefix("Running ") {
+            finish_failure(&mut results, failure.take());
+            let running = running.strip_prefix("unittests ").unwrap_or(running);
+            let root = running.split(" (").next().unwrap_or(running);
+            suite = Some(TestSuite::Target(root.to_string()));
+            continue;
+        }
+        if trimmed.starts_with("Doc-tests ") {
+            finish_failure(&mut results, failure.take());
+            suite = Some(TestSuite::Doc);
+            continue;
+        }
+        let Some(current) = suite.clone() else {
+            continue;
+        };
+
+        if let Some(name) = trimmed
+            .strip_prefix("---- ")
+            .and_then(|l| l.strip_suffix(" stdout ----"))
+        {
+            finish_failure(&mut results, failure.take());
+            failure = results
+                .iter()
+                .rposition(|r| r.suite == current && r.name == name)
+                .map(|index| (index, Vec::new()));
+            continue;
+        }
+        if let Some((_, message)) = failure.as_mut() {
+            // The captured output ends where libtest lists the failures or prints the summary
+            if trimmed == "failures:" || trimmed.starts_with("test result:") {
+                finish_failure(&mut results, failure.take());
+            } else {
+                message.push(line);
+            }
+            continue;
+        }
+
+        let Some((name, outcome)) = trimmed
+            .strip_prefix("test ")
+            .and_then(|l| l.rsplit_once(" ... "))
+        else {
+            continue;
+        };
+        // `ok <0.012s>` with `--report-time`
+        let (outcome, duration) = match outcome.split_once(" <") {
+            Some((outcome, time)) => (
+                outcome,
+                time.strip_suffix("s>")
+                    .and_then(|t| t.parse().ok())
+                    .map(Duration::from_secs_f64),
+            ),
+            None => (outcome, None),
+        };
+        let outcome = match outcome {
+            "ok" => TestOutcome::Passed,
+            "FAILED" => TestOutcome::Failed,
+            o if o.starts_with("ignored") => TestOutcome::Ignored,
+            _ => continue,
+        };
+        results.push(TestResult {
+            suite: current,
+            name: name.to_string(),
+            outcome,
+            message: None,
+            duration,
+        });
+    }
+    finish_failure(&mut results, failure);
+
+    results
+}
+
+fn finish_failure(results: &mut [TestResult], failure: Option<(usize, Vec<&str>)>) {
+    if let Some((index, message)) = failure {
+        let message = message.join("\n").trim().to_string();
+        results[index].message = (!message.is_empty()).then_some(message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::metadata::Target;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_test_results() {
+        let output = "   Compiling core v0.1.0 (/ws/core)
+    Finished `test` profile [unoptimized + debuginfo] target(s) in 0.50s
+     Running unittests src/lib.rs (target/debug/deps/core-0123)
+
+running 2 tests
+test tests::adds ... ok
+test parser::tests::fails ... FAILED
+
+failures:
+
+---- parser::tests::fails stdout ----
+
+thread 'parser::tests::fails' panicked at src/parser.rs:10:9:
+assertion failed: false
+
+failures:
+    parser::tests::fails
+
+test result: FAILED. 1 passed; 1 failed; 0 ignored; 0 measured; 0 filtered out
+
+     Running tests/api.rs (target/debug/deps/api-4567)
+
+running 1 test
+test slow ... ignored, takes a while
+
+   Doc-tests core
+
+running 1 test
+test src/lib.rs - add (line 5) ... ok
+";
+
+        let results = parse_results(output);
+
+        let lib = TestSuite::Target("src/lib.rs".to_string());
+        check!(
+            results
+                == vec![
+                    TestResult {
+                        suite: lib.clone(),
+                        name: "tests::adds".to_string(),
+                        outcome: TestOutcome::Passed,
+                        message: None,
+                        duration: None,
+                    },
+                    TestResult {
+                        suite: lib,
+                        name: "parser::tests::fails".to_string(),
+                        outcome: TestOutcome::Failed,
+                        message: Some(
+                            "thread 'parser::tests::fails' panicked at src/parser.rs:10:9:\nassertion failed: false"
+                                .to_string()
+                        ),
+                        duration: None,
+                    },
+                    TestResult {
+                        suite: TestSuite::Target("tests/api.rs".to_string()),
+                        name: "slow".to_string(),
+                        outcome: TestOutcome::Ignored,
+                        message: None,
+                        duration: None,
+                    },
+                    TestResult {
+                        suite: TestSuite::Doc,
+                        name: "src/lib.rs - add (line 5)".to_string(),
+                        outcome: TestOutcome::Passed,
+                        message: None,
+                        duration: None,
+                    },
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_reported_test_times() {
+        let output = "     Running unittests src/lib.rs (target/debug/deps/core-0123)
+test tests::adds ... ok <0.012s>
+test tests::slow ... FAILED <1.500s>
+";
+
+        let durations: Vec<_> = parse_results(output)
+            .into_iter()
+            .map(|r| (r.outcome, r.duration))
+            .collect();
+        check!(
+            durations
+                == [
+                    (TestOutcome::Passed, Some(Duration::from_millis(12))),
+                    (TestOutcome::Failed, Some(Duration::from_millis(1500)))
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn suite_target() {
+        let package = Package::fixture(
+            "core",
+            "/ws/core/Cargo.toml",
+            vec![
+                Target::fixture("core", "/ws/core/src/lib.rs", TargetType::Lib),
+                Target::fixture("tool", "/ws/core/src/bin/tool.rs", TargetType::Bin),
+            ],
+        );
+        let suite = |root: &str| TestSuite::Target(root.to_string()).target(&package);
+
+        check!(suite("src/lib.rs") == Some(TestTarget::Lib));
+        check!(suite("src/bin/tool.rs") == Some(TestTarget::Bin("tool".to_string())));
+        check!(suite("tests/api.rs") == Some(TestTarget::Test("api".to_string())));
+        check!(suite("tests/cli/main.rs") == Some(TestTarget::Test("cli".to_string())));
+        check!(TestSuite::Doc.target(&package).is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_assertion_panic() {
+        let output = "thread 'tests::adds' panicked at src/lib.rs:10:9:
+assertion `left == right` failed: sums differ
+  left: Point {
+    x: 1,
+}
+ right: Point {
+    x: 2,
+}
+note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace";
+
+        check!(
+            Panic::parse(output)
+                == Some(Panic {
+                    message: "assertion `left == right` failed: sums differ".to_string(),
+                    location: Some(PanicLocation {
+                        file: "src/lib.rs".to_string(),
+                        line: 10,
+                        column: 9,
+                    }),
+                    left: Some("Point {\n    x: 1,\n}".to_string()),
+                    right: Some("Point {\n    x: 2,\n}".to_string()),
+                })
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_panic_of_old_format_and_windows_path() {
+        let output = "thread 'main' panicked at 'assertion failed: `(left == right)`
+  left: `1`,
+ right: `2`', C:\\ws\\src\\lib.rs:3:5";
+
+        let panic = Panic::parse(output).unwrap();
+        check!(panic.message == "assertion failed: `(left == right)`");
+        check!(panic.left.as_deref() == Some("1"));
+        check!(panic.right.as_deref() == Some("2"));
+        check!(
+            panic.location
+                == Some(PanicLocation {
+                    file: "C:\\ws\\src\\lib.rs".to_string(),
+                    line: 3,
+                    column: 5,
+                })
+        );
+
+        let panic = Panic::parse("thread 'a' panicked at src/a.rs:1:1:\nexplicit panic").unwrap();
+        check!(panic.message == "explicit panic");
+        check!(panic.left.is_none());
+    }
+}
@@ -0,0 +1,186 @@
+use itertools::Itertools;
+
+use crate::cargo::metadata::{ParseError, extract_raw_metadata};
+
+/// The licenses `cargoTools.licenseReport.allowedLicenses` allows by default
+pub const DEFAULT_ALLOWED_LICENSES: [&str; 10] = [
+    "MIT",
+    "Apache-2.0",
+    "Apache-2.0 WITH LLVM-exception",
+    "BSD-2-Clause",
+    "BSD-3-Clause",
+    "ISC",
+    "Zlib",
+    "0BSD",
+    "Unicode-3.0",
+    "Unicode-DFS-2016",
+];
+
+/// A transitive dependency of the workspace and its license
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LicenseEntry {
+    pub name: String,
+    pub version: String,
+    /// The SPDX license expression like `MIT OR Apache-2.0`, [None] for crates which only declare
+    /// a `license-file`
+    pub license: Option<String>,
+    /// Whether the license expression is satisfiable with the allowed licenses
+    pub allowed: bool,
+}
+
+/// The licenses of all packages of `raw_metadata` which are no workspace members, sorted with
+/// the ones the `allowed` licenses don't satisfy first
+pub fn license_entries(
+    raw_metadata: &str,
+    allowed: &[String],
+) -> Result<Vec<LicenseEntry>, ParseError> {
+    let metadata = extract_raw_metadata(raw_metadata)?;
+    Ok(metadata
+        .packages
+        .iter()
+        .filter(|p| !metadata.workspace_members.contains(&p.id))
+        .map(|p| LicenseEntry {
+            name: p.name.to_string(),
+            version: p.version.to_string(),
+            allowed: p.license.as_deref().is_some_and(|l| is_allowed(l, allowed)),
+            license: p.license.clone(),
+        })
+        .sorted_by(|a, b| (a.allowed, &a.name, &a.version).cmp(&(b.allowed, &b.name, &b.version)))
+        .collect())
+}
+
+/// Whether the SPDX license `expression` like `(MIT OR Apache-2.0) AND Unicode-3.0` can be
+/// satisfied with the `allowed` licenses. The old `MIT/Apache-2.0` syntax means `OR`.
+pub fn is_allowed(expression: &str, allowed: &[String]) -> bool {
+    let spaced = expression
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace('/', " OR ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let mut parser = Parser {
+        tokens: &tokens,
+        pos: 0,
+        allowed,
+    };
+    parser.or_expr() && parser.pos == tokens.len()
+}
+
+struct Parser<'a> {
+    tokens: &'a [&'a str],
+    pos: usize,
+    allowed: &'a [String],
+}
+
+impl Parser<'_> {
+    fn next_if(&mut self, token: &str) -> bool {
+        let matches = self.tokens.get(self.pos) == Some(&token);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn or_expr(&mut self) -> bool {
+        let mut allowed = self.and_expr();
+        while self.next_if("OR") {
+            allowed |= self.and_expr();
+        }
+        allowed
+    }
+
+    fn and_expr(&mut self) -> bool {
+        let mut allowed = self.license();
+        while self.next_if("AND") {
+            allowed &= self.license();
+        }
+        allowed
+    }
+
+    /// A license like `MIT`, `Apache-2.0 WITH LLVM-exception` or a parenthesized expression.
+    /// Licenses with an exception are allowed with the exception or without it.
+    fn license(&mut self) -> bool {
+        if self.next_if("(") {
+            let allowed = self.or_expr();
+            return self.next_if(")") && allowed;
+        }
+        let Some(id) = self.tokens.get(self.pos).copied() else {
+            return false;
+        };
+        self.pos += 1;
+        let id = id.trim_end_matches('+');
+        let is_allowed = |license: &str| self.allowed.iter().any(|a| a == license);
+        if self.next_if("WITH") {
+            let Some(exception) = self.tokens.get(self.pos).copied() else {
+                return false;
+            };
+            self.pos += 1;
+            return is_allowed(id) || is_allowed(&format!("{id} WITH {exception}"));
+        }
+        is_allowed(id)
+    }
+}
+
+/// The license report of `entries` as markdown table
+pub fn to_markdown(entries: &[LicenseEntry]) -> String {
+    let flagged = entries.iter().filter(|e| !e.allowed).count();
+    let mut markdown = String::from("# License Report\n\n");
+    markdown.push_str(&format!(
+        "{} dependencies, {flagged} not covered by `cargoTools.licenseReport.allowedLicenses`\n\n",
+        entries.len()
+    ));
+    markdown.push_str("| Crate | Version | License | Allowed |\n| --- | --- | --- | --- |\n");
+    for entry in entries {
+        let license = entry
+            .license
+            .as_deref()
+            .unwrap_or("No SPDX license, see its license file");
+        let allowed = if entry.allowed { "yes" } else { "**no**" };
+        markdown.push_str(&format!(
+            "| {} | {} | {license} | {allowed} |\n",
+            entry.name, entry.version
+        ));
+    }
+    markdown
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn licenses_are_checked() {
+        let allowed = ["MIT", "Apache-2.0", "Unicode-3.0"].map(str::to_string);
+        check!(is_allowed("MIT", &allowed));
+        check!(is_allowed("MIT OR GPL-3.0", &allowed));
+        check!(is_allowed("MIT/Apache-2.0", &allowed));
+        check!(is_allowed("(MIT OR Apache-2.0) AND Unicode-3.0", &allowed));
+        check!(is_allowed("Apache-2.0 WITH LLVM-exception", &allowed));
+        check!(!is_allowed("MIT AND GPL-3.0", &allowed));
+        check!(!is_allowed("(MIT OR Apache-2.0) AND MPL-2.0", &allowed));
+        check!(!is_allowed("(MIT", &allowed));
+
+        let entries = vec![
+            LicenseEntry {
+                name: "ring".to_string(),
+                version: "0.17.8".to_string(),
+                license: None,
+                allowed: false,
+            },
+            LicenseEntry {
+                name: "serde".to_string(),
+                version: "1.0.210".to_string(),
+                license: Some("MIT OR Apache-2.0".to_string()),
+                allowed: true,
+            },
+        ];
+        let markdown = to_markdown(&entries);
+        check!(markdown.contains("2 dependencies, 1 not covered"));
+        check!(
+            markdown.contains("| ring | 0.17.8 | No SPDX license, see its license file | **no** |")
+        );
+        check!(markdown.contains("| serde | 1.0.210 | MIT OR Apache-2.0 | yes |"));
+    }
+}
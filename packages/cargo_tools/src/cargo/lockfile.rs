@@ -0,0 +1,82 @@
+use cargo_metadata::semver::{Version, VersionReq};
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Lockfile {
+    package: Vec<LockedPackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LockedPackage {
+    name: String,
+    version: String,
+    source: Option<String>,
+}
+
+/// The version of the crates.io crate `name` which `Cargo.lock` content `lock` resolved `req`
+/// to, the newest locked one if several versions are locked and `req` doesn't pick one
+pub fn locked_version(lock: &str, name: &str, req: Option<&str>) -> Option<String> {
+    let lockfile: Lockfile = toml::from_str(lock).ok()?;
+    let req = req.and_then(|req| VersionReq::parse(req).ok());
+    lockfile
+        .package
+        .into_iter()
+        .filter(|p| {
+            p.name == name
+                && p.source
+                    .as_deref()
+                    .is_some_and(|s| s.starts_with("registry+") || s.starts_with("sparse+"))
+        })
+        .filter_map(|p| Version::parse(&p.version).ok())
+        .filter(|version| req.as_ref().is_none_or(|req| req.matches(version)))
+        .max()
+        .map(|version| version.to_string())
+}
+
+/// The `cargo update` arguments which update the crate `name` locked at `version` to the newest
+/// release its requirement allows, every locked version of it without one
+pub fn update_args(name: &str, version: Option<&str>) -> Vec<String> {
+    let spec = match version {
+        Some(version) => format!("{name}@{version}"),
+        None => name.to_string(),
+    };
+    vec!["update".to_string(), "--package".to_string(), spec]
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn versions_of_the_lockfile() {
+        let lock = r#"version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+dependencies = ["syn 1.0.109", "syn 2.0.100"]
+
+[[package]]
+name = "syn"
+version = "1.0.109"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "72b64191b275b66ffe2469e8af2c1cfe3bafa67b529ead792a6d0160888b4237"
+
+[[package]]
+name = "syn"
+version = "2.0.100"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+checksum = "b09a44accad81e1ba1cd74a32461ba89dee89095ba17b32f5d03683b1b1fc2a0"
+"#;
+        check!(locked_version(lock, "syn", Some("^1.0")).as_deref() == Some("1.0.109"));
+        check!(locked_version(lock, "syn", None).as_deref() == Some("2.0.100"));
+        check!(locked_version(lock, "app", None).is_none());
+        check!(locked_version(lock, "serde", Some("1")).is_none());
+
+        check!(update_args("syn", Some("1.0.109")) == ["update", "--package", "syn@1.0.109"]);
+    }
+}
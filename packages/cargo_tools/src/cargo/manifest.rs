@@ -0,0 +1,386 @@
+use serde::Serialize;
+
+use crate::cargo::command::RunSubTarget;
+
+/// A dependency declared in a manifest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestDependency {
+    /// The name of the crate, differs from the key for renamed dependencies
+    pub name: String,
+    /// The version requirement, [None] for dependencies inherited with `workspace = true` or
+    /// without a version
+    pub req: Option<String>,
+    /// The alternative registry of `registry = "..."`, [None] for crates.io
+    pub registry: Option<String>,
+    /// Whether it is sourced from a `path` or `git` repository instead of a registry
+    pub path_or_git: bool,
+    /// Whether it inherits its declaration from `[workspace.dependencies]` with
+    /// `workspace = true`, see [inherited]
+    pub workspace: bool,
+    /// The first and last line of the declaration, zero based
+    pub lines: (usize, usize),
+}
+
+/// Whether the table `header` like `target.'cfg(unix)'.dependencies` holds dependencies
+pub(crate) fn is_dependency_table(header: &str) -> bool {
+    let last = header.rsplit('.').next().unwrap_or(header).trim();
+    matches!(
+        last,
+        "dependencies" | "dev-dependencies" | "build-dependencies"
+    ) || header.replace(' ', "") == "workspace.dependencies"
+}
+
+fn unquote(key: &str) -> String {
+    key.trim().trim_matches(['"', '\'']).to_string()
+}
+
+/// The dependency `key = value` declared at `lines`
+fn parse_value(key: &str, value: &toml::Value, lines: (usize, usize)) -> ManifestDependency {
+    let dependency = |name: &str| ManifestDependency {
+        name: name.to_string(),
+        req: None,
+        registry: None,
+        path_or_git: false,
+        workspace: false,
+        lines,
+    };
+    match value {
+        toml::Value::String(req) => ManifestDependency {
+            req: Some(req.clone()),
+            ..dependency(key)
+        },
+        toml::Value::Table(table) => {
+            let get = |key: &str| table.get(key).and_then(toml::Value::as_str);
+            ManifestDependency {
+                req: get("version").map(str::to_string),
+                registry: get("registry").map(str::to_string),
+                path_or_git: get("path").is_some() || get("git").is_some(),
+                workspace: table.get("workspace").and_then(toml::Value::as_bool) == Some(true),
+                ..dependency(get("package").unwrap_or(key))
+            }
+        }
+        _ => dependency(key),
+    }
+}
+
+/// The entry `key = value` starting at the zero based line `start` of `lines` and its last line,
+/// inline tables and arrays may span several lines
+fn parse_entry(lines: &[&str], start: usize) -> Option<(usize, toml::Table)> {
+    let mut entry = String::new();
+    for (i, line) in lines.iter().enumerate().skip(start) {
+        let trimmed = line.trim();
+        if i > start && trimmed.starts_with('[') && trimmed.ends_with(']') {
+            return None;
+        }
+        entry.push_str(line);
+        entry.push('\n');
+        if let Ok(table) = entry.parse::<toml::Table>() {
+            return Some((i, table));
+        }
+    }
+    None
+}
+
+/// The dependencies of all dependency tables of `manifest`, inline like `serde = "1"` and as
+/// table like `[dependencies.serde]`
+pub fn dependencies(manifest: &str) -> Vec<ManifestDependency> {
+    let lines: Vec<&str> = manifest.lines().collect();
+    let mut dependencies = Vec::new();
+    let mut in_dependencies = false;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|l| l.split(']').next())
+            .filter(|_| !line.starts_with("[["))
+        {
+            in_dependencies = is_dependency_table(header);
+            // `[dependencies.serde]` declares serde with the keys up to the next table
+            if let Some((table, key)) = header.rsplit_once('.')
+                && is_dependency_table(table)
+            {
+                let end = (i + 1..lines.len())
+                    .find(|&j| lines[j].trim_start().starts_with('['))
+                    .unwrap_or(lines.len());
+                let body = lines[i + 1..end].join("\n");
+                let value = body
+                    .parse::<toml::Table>()
+                    .map(toml::Value::Table)
+                    .unwrap_or(toml::Value::Boolean(false));
+                let last = (i..end)
+                    .rev()
+                    .find(|&j| !lines[j].trim().is_empty())
+                    .unwrap_or(i);
+                dependencies.push(parse_value(&unquote(key), &value, (i, last)));
+                i = end;
+                continue;
+            }
+        } else if in_dependencies
+            && !line.starts_with('#')
+            && let Some((key, _)) = line.split_once('=')
+            && let Some((last, entry)) = parse_entry(&lines, i)
+            && let Some(value) = entry.values().next()
+        {
+            dependencies.push(parse_value(&unquote(key), value, (i, last)));
+            i = last;
+        }
+        i += 1;
+    }
+    dependencies
+}
+
+/// The dependency declared at the zero based `line` of `manifest`
+pub fn dependency_at(manifest: &str, line: usize) -> Option<ManifestDependency> {
+    dependencies(manifest)
+        .into_iter()
+        .find(|d| (d.lines.0..=d.lines.1).contains(&line))
+}
+
+/// `dependency` with the requirement, registry and source of its entry in the
+/// `[workspace.dependencies]` of the root manifest `root` if it inherits them, unchanged otherwise
+pub fn inherited(root: &str, dependency: ManifestDependency) -> ManifestDependency {
+    if !dependency.workspace {
+        return dependency;
+    }
+    let entry = root.parse::<toml::Table>().ok().and_then(|root| {
+        root.get("workspace")?
+            .get("dependencies")?
+            .get(&dependency.name)
+            .cloned()
+    });
+    match entry {
+        Some(value) => ManifestDependency {
+            workspace: true,
+            ..parse_value(&dependency.name, &value, dependency.lines)
+        },
+        None => dependency,
+    }
+}
+
+/// A section of a manifest which declares the package or one of its run targets
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ManifestSection {
+    /// Zero based line of the section header
+    pub line: usize,
+    /// The `[[bin]]` or `[[example]]` of the section, [None] for `[package]`
+    pub target: Option<RunSubTarget>,
+}
+
+/// The `[package]`, `[[bin]]` and `[[example]]` sections of `manifest`, sections of targets
+/// without a `name` are skipped
+pub fn target_sections(manifest: &str) -> Vec<ManifestSection> {
+    let lines: Vec<&str> = manifest.lines().collect();
+    let name_in = |body: &[&str]| {
+        body.iter()
+            .take_while(|line| !line.trim_start().starts_with('['))
+            .filter_map(|line| line.parse::<toml::Table>().ok())
+            .find_map(|entry| entry.get("name")?.as_str().map(str::to_string))
+    };
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let target = match line.trim() {
+                "[package]" => {
+                    return Some(ManifestSection {
+                        line: i,
+                        target: None,
+                    });
+                }
+                "[[bin]]" => RunSubTarget::Bin(name_in(&lines[i + 1..])?),
+                "[[example]]" => RunSubTarget::Example(name_in(&lines[i + 1..])?),
+                _ => return None,
+            };
+            Some(ManifestSection {
+                line: i,
+                target: Some(target),
+            })
+        })
+        .collect()
+}
+
+/// A feature of the `[features]` table of a manifest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManifestFeature {
+    /// Zero based line of the feature
+    pub line: usize,
+    pub name: String,
+}
+
+/// The features declared in the `[features]` table of `manifest`
+pub fn feature_entries(manifest: &str) -> Vec<ManifestFeature> {
+    let mut in_features = false;
+    let mut features = Vec::new();
+    for (line, text) in manifest.lines().enumerate() {
+        let text = text.trim();
+        if text.starts_with('[') && !in_features_array(text) {
+            in_features = text == "[features]";
+            continue;
+        }
+        if !in_features {
+            continue;
+        }
+        // Lines of multi-line feature arrays have no key
+        let Some((key, _)) = text.split_once('=') else {
+            continue;
+        };
+        let key = key.trim();
+        let name = match key.strip_prefix('"').and_then(|k| k.strip_suffix('"')) {
+            Some(quoted) if !quoted.contains('"') => quoted,
+            _ if !key.is_empty()
+                && key
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+            {
+                key
+            }
+            _ => continue,
+        };
+        features.push(ManifestFeature {
+            line,
+            name: name.to_string(),
+        });
+    }
+    features
+}
+
+/// Whether the line starting with `[` continues an array instead of opening a table, e.g.
+/// `["serde"]` of a multi-line feature
+fn in_features_array(text: &str) -> bool {
+    text.starts_with("[\"") || text.starts_with("[]")
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn dependencies_of_all_tables() {
+        let manifest = r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+serde = "1.0"
+tokio = { version = "1", features = ["full"] }
+json = { package = "serde_json", version = "^1.0.100" }
+core = { workspace = true }
+internal = { version = "0.3", registry = "company" }
+
+[target.'cfg(unix)'.dependencies]
+nix = "0.29"
+
+[dev-dependencies.insta]
+version = "1.40"
+features = ["yaml"]
+
+[build-dependencies]
+build-utils = { path = "../build-utils", features = [
+    "cc",
+] }
+"#;
+        let dependencies: Vec<_> = dependencies(manifest)
+            .into_iter()
+            .map(|d| (d.name, d.req, d.registry, d.lines))
+            .collect();
+        let some = |s: &str| Some(s.to_string());
+        check!(
+            dependencies
+                == [
+                    ("serde".to_string(), some("1.0"), None, (5, 5)),
+                    ("tokio".to_string(), some("1"), None, (6, 6)),
+                    ("serde_json".to_string(), some("^1.0.100"), None, (7, 7)),
+                    ("core".to_string(), None, None, (8, 8)),
+                    ("internal".to_string(), some("0.3"), some("company"), (9, 9)),
+                    ("nix".to_string(), some("0.29"), None, (12, 12)),
+                    ("insta".to_string(), some("1.40"), None, (14, 16)),
+                    ("build-utils".to_string(), None, None, (19, 21)),
+                ]
+        );
+        check!(dependency_at(manifest, 20).is_some_and(|d| d.path_or_git));
+        check!(dependency_at(manifest, 6).is_some_and(|d| !d.path_or_git));
+
+        check!(dependency_at(manifest, 15).map(|d| d.name).as_deref() == Some("insta"));
+        check!(dependency_at(manifest, 2).is_none());
+
+        let root = "[workspace]\nmembers = [\"app\"]\n\n[workspace.dependencies]\ncore = \"0.2\"\n";
+        let core = dependency_at(manifest, 8).unwrap();
+        check!(core.workspace);
+        let core = inherited(root, core);
+        check!((core.req.as_deref(), core.lines) == (Some("0.2"), (8, 8)));
+        let serde = inherited(root, dependency_at(manifest, 5).unwrap());
+        check!(serde.req.as_deref() == Some("1.0"));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn package_and_target_sections() {
+        let manifest = r#"[package]
+name = "app"
+
+[[bin]]
+name = "server"
+path = "src/server.rs"
+
+[[example]]
+path = "examples/unnamed.rs"
+
+[[example]]
+# The demo
+name = "demo"
+"#;
+        check!(
+            target_sections(manifest)
+                == [
+                    ManifestSection {
+                        line: 0,
+                        target: None
+                    },
+                    ManifestSection {
+                        line: 3,
+                        target: Some(RunSubTarget::Bin("server".to_string()))
+                    },
+                    ManifestSection {
+                        line: 10,
+                        target: Some(RunSubTarget::Example("demo".to_string()))
+                    },
+                ]
+        );
+        check!(target_sections("[workspace]\nmembers = [\"app\"]\n").is_empty());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn features_of_table() {
+        let manifest = r#"[package]
+name = "app"
+
+[features]
+default = ["tls"]
+tls = ["dep:rustls"]
+"serde-json" = [
+    "dep:serde_json",
+    "serde",
+]
+serde = []
+
+[dependencies]
+serde = "1"
+"#;
+        let features: Vec<_> = feature_entries(manifest)
+            .into_iter()
+            .map(|f| (f.line, f.name))
+            .collect();
+        check!(
+            features
+                == [
+                    (4, "default".to_string()),
+                    (5, "tls".to_string()),
+                    (6, "serde-json".to_string()),
+                    (10, "serde".to_string()),
+                ]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Line based edits of manifests which keep their formatting and comments
+
+/// `s` as TOML basic string, JSON strings are valid TOML basic strings
+pub fn quote(s: &str) -> String {
+    serde_json::to_string(s).expect("strings are serializable")
+}
+
+/// The string value of `key = "..."` in `line` with its byte range, also inside inline tables
+/// like `{ path = "..", version = "1" }`
+pub fn string_value<'a>(line: &'a str, key: &str) -> Option<(&'a str, usize, usize)> {
+    let is_key_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    line.match_indices(key).find_map(|(start, _)| {
+        if line[..start].chars().next_back().is_some_and(is_key_char) {
+            return None;
+        }
+        let rest = line[start + key.len()..].trim_start();
+        let open = rest.strip_prefix('=')?.trim_start().strip_prefix('"')?;
+        let value_start = line.len() - open.len();
+        let value_end = value_start + open.find('"')?;
+        Some((&line[value_start..value_end], value_start, value_end))
+    })
+}
+
+/// `line` with the string value of `key` replaced by `value`, [None] if `line` doesn't assign
+/// a string to `key`
+pub fn replace_value(line: &str, key: &str, value: &str) -> Option<String> {
+    let (_, start, end) = string_value(line, key)?;
+    Some(format!("{}{value}{}", &line[..start], &line[end..]))
+}
+
+/// The edited `lines` of the manifest `original`, ending with a newline if it did
+pub fn join(original: &str, lines: Vec<String>) -> String {
+    let mut edited = lines.join("\n");
+    if original.ends_with('\n') {
+        edited.push('\n');
+    }
+    edited
+}
+
+/// `manifest` with `entries` appended to the table `header`, like `dependencies`, which is
+/// added at the end if it doesn't exist
+pub fn add_to_table(manifest: &str, header: &str, entries: Vec<String>) -> String {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|l| l.trim().replace(' ', "") == format!("[{header}]"));
+    match start {
+        Some(start) => {
+            let end = lines[start + 1..]
+                .iter()
+                .position(|l| l.trim_start().starts_with('['))
+                .map_or(lines.len(), |i| start + 1 + i);
+            // Keep the blank lines before the next table
+            let end = (start + 1..end)
+                .rev()
+                .find(|&i| !lines[i].trim().is_empty())
+                .map_or(start + 1, |i| i + 1);
+            lines.splice(end..end, entries);
+        }
+        None => {
+            lines.push(String::new());
+            lines.push(format!("[{header}]"));
+            lines.extend(entries);
+        }
+    }
+    let mut edited = lines.join("\n");
+    edited.push('\n');
+    edited
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn values_are_edited_in_place() {
+        let line = r#"core = { path = "../core", version = "1.2" }"#;
+        check!(string_value(line, "path").map(|(value, _, _)| value) == Some("../core"));
+        check!(string_value(line, "ath").is_none());
+        check!(
+            replace_value(line, "version", "1.3").as_deref()
+                == Some(r#"core = { path = "../core", version = "1.3" }"#)
+        );
+        check!(quote("a \"b\"") == r#""a \"b\"""#);
+
+        let manifest = "[package]\nname = \"app\"\n\n[dependencies]\nlog = \"0.4\"\n\n[features]\n";
+        check!(
+            add_to_table(manifest, "dependencies", vec!["itoa = \"1\"".to_string()])
+                == "[package]\nname = \"app\"\n\n[dependencies]\nlog = \"0.4\"\nitoa = \"1\"\n\n[features]\n"
+        );
+        check!(
+            add_to_table("[package]\nname = \"app\"", "dependencies", Vec::new())
+                == "[package]\nname = \"app\"\n\n[dependencies]\n"
+        );
+    }
+}
@@ -1,22 +1,32 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use itertools::Itertools;
+use serde::{Deserialize, Deserializer, Serialize};
 use toml::Table;
 
-use cargo_metadata::MetadataCommand;
 pub use cargo_metadata::TargetKind;
+use cargo_metadata::{DependencyKind, MetadataCommand};
 
 use crate::{
-    cargo::Profile,
+    cargo::{
+        DependencyOverride, Profile, Registries, command::RunSubTarget, overrides::SourceOverride,
+    },
     process::{CargoCommandEmpty, CargoTaskContext, Process},
+    stable_hash::stable_hash,
 };
 
-/// Holds the [`Package`]s, [`Profile`]s and `target_dir` where cargo builds to.
+/// Holds the [`Package`]s, [`Profile`]s, [`Registries`], the declared [`DependencyOverride`]s and
+/// the [`SourceOverride`]s cargo resolved, `target_dir` where cargo builds to and the
+/// `workspace_root` cargo commands have to run in.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
 pub struct Metadata {
     packages: Vec<Package>,
     profiles: Vec<Profile>,
+    registries: Registries,
+    overrides: Vec<DependencyOverride>,
+    source_overrides: Vec<SourceOverride>,
     target_dir: String,
+    workspace_root: String,
 }
 
 impl Metadata {
@@ -32,9 +42,55 @@ impl Metadata {
         &self.packages
     }
 
+    /// Whether the packages, target dir and workspace root are the ones of
+    /// `packages_and_target_dir`
+    pub fn has_packages_and_target_dir(
+        &self,
+        packages_and_target_dir: &PackagesAndTargetDir,
+    ) -> bool {
+        self.packages == packages_and_target_dir.packages
+            && self.target_dir == packages_and_target_dir.target_dir
+            && self.workspace_root == packages_and_target_dir.workspace_root
+    }
+
+    /// Replaces the packages which differ in `packages_and_target_dir` and returns their names.
+    /// Returns [None] and keeps the packages if packages were added, removed or renamed or the
+    /// target dir or workspace root changed.
+    pub fn patch_packages(
+        &mut self,
+        packages_and_target_dir: &PackagesAndTargetDir,
+    ) -> Option<Vec<String>> {
+        let PackagesAndTargetDir {
+            packages,
+            target_dir,
+            workspace_root,
+        } = packages_and_target_dir;
+        let same_packages = self.packages.len() == packages.len()
+            && self
+                .packages
+                .iter()
+                .zip(packages)
+                .all(|(old, new)| old.name == new.name);
+        if !same_packages
+            || self.target_dir != *target_dir
+            || self.workspace_root != *workspace_root
+        {
+            return None;
+        }
+        let mut changed = Vec::new();
+        for (old, new) in self.packages.iter_mut().zip(packages) {
+            if old != new {
+                *old = new.clone();
+                changed.push(new.name.clone());
+            }
+        }
+        Some(changed)
+    }
+
     pub fn set_packages_and_target_dir(&mut self, packages_and_target_dir: PackagesAndTargetDir) {
         self.packages = packages_and_target_dir.packages;
         self.target_dir = packages_and_target_dir.target_dir;
+        self.workspace_root = packages_and_target_dir.workspace_root;
     }
 
     pub fn profiles(&self) -> &[Profile] {
@@ -45,13 +101,65 @@ impl Metadata {
         self.profiles = profiles.into()
     }
 
+    pub fn registries(&self) -> &Registries {
+        &self.registries
+    }
+
+    pub fn set_registries(&mut self, registries: Registries) {
+        self.registries = registries
+    }
+
+    pub fn overrides(&self) -> &[DependencyOverride] {
+        &self.overrides
+    }
+
+    pub fn set_overrides(&mut self, overrides: Vec<DependencyOverride>) {
+        self.overrides = overrides
+    }
+
+    pub fn set_source_overrides(&mut self, source_overrides: Vec<SourceOverride>) {
+        self.source_overrides = source_overrides
+    }
+
+    /// Where cargo sources the registry dependency `dependency` from if it isn't its registry,
+    /// with the declaration of the override if it was found
+    pub fn override_of(
+        &self,
+        dependency: &RegistryDependency,
+    ) -> Option<(&SourceOverride, Option<&DependencyOverride>)> {
+        let source = self
+            .source_overrides
+            .iter()
+            .find(|o| o.applies_to(dependency))?;
+        let declaration = self
+            .overrides
+            .iter()
+            .find(|o| o.applies_to(dependency, &self.registries));
+        Some((source, declaration))
+    }
+
     pub fn target_dir(&self) -> &str {
         &self.target_dir
     }
+
+    /// The `required-features` of the run `target` of `package`
+    pub fn required_features(&self, package: &str, target: &RunSubTarget) -> &[String] {
+        self.packages
+            .iter()
+            .filter(|p| p.name == package)
+            .flat_map(|p| p.targets.iter())
+            .find(|t| target.matches(t.target_type, &t.name))
+            .map_or(&[], |t| t.required_features.as_slice())
+    }
+
+    /// The root of the cargo workspace which is not necessarily the VS Code workspace folder
+    pub fn workspace_root(&self) -> &str {
+        &self.workspace_root
+    }
 }
 
 /// Represents the kinds of targets which a `cargo` command can target
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TargetType {
     Lib,
     Bin,
@@ -100,11 +208,66 @@ pub enum ParseError {
     CargoCommandEmpty(CargoCommandEmpty),
 }
 
-/// Holds the [`Package`]s and `target_dir` going into [`Metadata`].
-#[derive(Debug, Clone)]
+/// Holds the [`Package`]s, `target_dir` and `workspace_root` going into [`Metadata`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PackagesAndTargetDir {
     packages: Vec<Package>,
     target_dir: String,
+    workspace_root: String,
+}
+
+impl PackagesAndTargetDir {
+    fn from_metadata(metadata: cargo_metadata::Metadata) -> Self {
+        let target_dir = metadata.target_directory.to_string();
+        let workspace_root = metadata.workspace_root.to_string();
+        let packages = Package::from_metadata(metadata);
+
+        Self {
+            packages,
+            target_dir,
+            workspace_root,
+        }
+    }
+
+    /// The manifests of the packages
+    pub fn manifests(&self) -> Vec<String> {
+        self.packages.iter().map(|p| p.manifest.clone()).collect()
+    }
+
+    /// Maps the paths cargo reported with `map`, e.g. to the paths of the extension host
+    pub fn map_paths(mut self, map: impl Fn(&str) -> String) -> Self {
+        for package in &mut self.packages {
+            package.manifest = map(&package.manifest);
+            for target in &mut package.targets {
+                target.source = map(&target.source);
+            }
+        }
+        self.target_dir = map(&self.target_dir);
+        self.workspace_root = map(&self.workspace_root);
+        self
+    }
+}
+
+/// [`PackagesAndTargetDir`] cached with the hash of the files it was parsed from, i.e. the
+/// manifests and the cargo config, to show the workspace before `cargo metadata` finished
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataCache {
+    pub files: Vec<String>,
+    /// The [files_hash] of the contents of `files`
+    pub hash: u64,
+    pub packages_and_target_dir: PackagesAndTargetDir,
+}
+
+impl MetadataCache {
+    /// The cached packages if the `contents` of its files still have its hash
+    pub fn get(self, contents: &[String]) -> Option<PackagesAndTargetDir> {
+        (files_hash(contents) == self.hash).then_some(self.packages_and_target_dir)
+    }
+}
+
+/// The hash of the `contents` of files, missing files are empty
+pub fn files_hash(contents: &[String]) -> u64 {
+    stable_hash(&contents)
 }
 
 /// Tries to parse the packages and target dir from `Cargo.toml` at `root_dir`.
@@ -133,13 +296,20 @@ pub async fn parse_packages_and_target_dir(
 
     let metadata = extract_raw_metadata(&metadata)?;
 
-    let target_dir = metadata.target_directory.to_string();
-    let packages = Package::from_metadata(metadata);
+    Ok(PackagesAndTargetDir::from_metadata(metadata))
+}
 
-    Ok(PackagesAndTargetDir {
-        packages,
-        target_dir,
-    })
+/// The arguments of `cargo metadata` resolving all dependencies of the workspace of `manifest`
+pub fn resolve_args(manifest: &str) -> Vec<String> {
+    [
+        "metadata",
+        "--format-version",
+        "1",
+        "--manifest-path",
+        manifest,
+    ]
+    .map(str::to_string)
+    .to_vec()
 }
 
 /// Tries to parse the profiles from the provided `file_paths`.
@@ -165,7 +335,14 @@ pub async fn parse_profiles(
         .collect()
 }
 
-fn extract_raw_metadata(raw_metadata: &str) -> Result<cargo_metadata::Metadata, ParseError> {
+/// Whether `source` is a registry, git and path dependencies have other sources
+pub(crate) fn is_registry_source(source: &cargo_metadata::Source) -> bool {
+    source.repr.starts_with("registry+") || source.repr.starts_with("sparse+")
+}
+
+pub(crate) fn extract_raw_metadata(
+    raw_metadata: &str,
+) -> Result<cargo_metadata::Metadata, ParseError> {
     raw_metadata
         .lines()
         .find(|line| line.starts_with('{'))
@@ -191,26 +368,130 @@ fn extract_profiles(toml: String) -> Vec<Profile> {
 }
 
 /// The package information holding only information needed to build `cargo` commands
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Package {
     pub name: String,
     pub manifest: String,
     pub targets: Vec<Target>,
     pub features: Vec<String>,
+    /// Names of the workspace members this package depends on
+    pub dependencies: Vec<String>,
+    /// The dependencies from crates.io and alternative registries, sorted by name
+    pub registry_dependencies: Vec<RegistryDependency>,
+}
+
+/// A dependency from a registry with its version requirement like `^1.0`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RegistryDependency {
+    pub name: String,
+    pub req: String,
+    /// `dev` or `build` for the dependencies of the other sections
+    #[serde(deserialize_with = "deserialize_kind")]
+    pub kind: DependencyKindLabel,
+    /// The index URL of an alternative registry, [None] for crates.io
+    pub registry: Option<String>,
 }
 
+// Hides the `'static` lifetime from the derived `Deserialize` which would borrow it otherwise
+type DependencyKindLabel = Option<&'static str>;
+
+fn deserialize_kind<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<DependencyKindLabel, D::Error> {
+    let kind = Option::<String>::deserialize(deserializer)?;
+    Ok(match kind.as_deref() {
+        Some("dev") => Some("dev"),
+        Some("build") => Some("build"),
+        _ => None,
+    })
+}
+
+impl RegistryDependency {
+    pub fn is_crates_io(&self) -> bool {
+        self.registry.is_none()
+    }
+}
+
+/// Globs of the sources and the manifest of a package which trigger watch mode, relative to its
+/// directory. The target directory is left out as builds write to it.
+pub const WATCHED_PATTERNS: [&str; 6] = [
+    "src/**/*.rs",
+    "tests/**/*.rs",
+    "examples/**/*.rs",
+    "benches/**/*.rs",
+    "build.rs",
+    "Cargo.toml",
+];
+
 impl Package {
+    /// Directory of the package's manifest, cargo runs tests and benchmarks in it
+    pub fn manifest_dir(&self) -> &str {
+        self.manifest
+            .rsplit_once(['/', '\\'])
+            .map_or("", |(dir, _)| dir)
+    }
+
+    /// Globs of the sources and the manifest which trigger watch mode, relative to
+    /// `workspace_root`. [None] for a package outside of it.
+    pub fn watched_patterns(&self, workspace_root: &str) -> Option<Vec<String>> {
+        let normalize = |path: &str| path.replace('\\', "/");
+        let root = normalize(workspace_root);
+        let dir = normalize(self.manifest_dir());
+        let prefix = match dir.strip_prefix(root.trim_end_matches('/'))? {
+            "" => String::new(),
+            relative => format!("{}/", relative.strip_prefix('/')?),
+        };
+        Some(
+            WATCHED_PATTERNS
+                .map(|pattern| format!("{prefix}{pattern}"))
+                .to_vec(),
+        )
+    }
+
     fn from_metadata(metadata: cargo_metadata::Metadata) -> Vec<Package> {
-        metadata
+        let members: Vec<_> = metadata
             .packages
             .into_iter()
             .filter(|p| metadata.workspace_members.contains(&p.id))
-            .map(Package::from_cargo)
+            .collect();
+        let member_names: Vec<String> = members.iter().map(|p| p.name.to_string()).collect();
+
+        members
+            .into_iter()
+            .map(|p| Package::from_cargo(p, &member_names))
             .sorted_by(|a, b| a.name.cmp(&b.name))
             .collect()
     }
 
-    fn from_cargo(package: cargo_metadata::Package) -> Self {
+    fn from_cargo(package: cargo_metadata::Package, member_names: &[String]) -> Self {
+        let dependencies = package
+            .dependencies
+            .iter()
+            // dev-dependencies may form cycles and are not needed to build the package
+            .filter(|d| d.kind != DependencyKind::Development)
+            .filter(|d| d.path.is_some() && member_names.contains(&d.name))
+            .map(|d| d.name.clone())
+            .unique()
+            .collect();
+        let registry_dependencies = package
+            .dependencies
+            .iter()
+            // Git and path dependencies have no registry source
+            .filter(|d| d.source.as_ref().is_some_and(is_registry_source))
+            .map(|d| RegistryDependency {
+                name: d.name.clone(),
+                req: d.req.to_string(),
+                kind: match d.kind {
+                    DependencyKind::Development => Some("dev"),
+                    DependencyKind::Build => Some("build"),
+                    _ => None,
+                },
+                // Alternative registries set `registry`
+                registry: d.registry.clone(),
+            })
+            .sorted()
+            .dedup_by(|a, b| a.name == b.name)
+            .collect();
         Self {
             name: package.name.to_string(),
             manifest: package.manifest_path.to_string(),
@@ -221,31 +502,105 @@ impl Package {
                 .sorted_by_key(|t| t.target_type)
                 .collect(),
             features: package.features.keys().cloned().collect(),
+            dependencies,
+            registry_dependencies,
         }
     }
 }
 
 /// The condensed target information holding only information needed to build `cargo` commands
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Target {
     pub name: String,
     pub source: String,
     pub target_type: TargetType,
     pub target_kind: Vec<TargetKind>,
+    /// Features cargo requires to be enabled to build the target
+    pub required_features: Vec<String>,
+}
+
+#[cfg(test)]
+impl Package {
+    /// A package without features and dependencies, shared by the tests of the modules working
+    /// on packages
+    pub(crate) fn fixture(name: &str, manifest: &str, targets: Vec<Target>) -> Self {
+        Self {
+            name: name.to_string(),
+            manifest: manifest.to_string(),
+            targets,
+            features: Vec::new(),
+            dependencies: Vec::new(),
+            registry_dependencies: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Target {
+    /// A target of the single kind of `target_type` without required features, see
+    /// [Package::fixture]
+    pub(crate) fn fixture(name: &str, source: &str, target_type: TargetType) -> Self {
+        Self {
+            name: name.to_string(),
+            source: source.to_string(),
+            target_type,
+            target_kind: Vec::new(),
+            required_features: Vec::new(),
+        }
+    }
 }
 
 impl Target {
     pub fn try_from_cargo(target: cargo_metadata::Target) -> Option<Self> {
         let target_kind = target.kind.clone();
+        let required_features = target.required_features.clone();
         Some(Self {
             name: target.name.to_string(),
             source: target.src_path.to_string(),
+            required_features,
             target_type: TargetType::from_target(target)?,
             target_kind,
         })
     }
 }
 
+/// The packages `changed_files` belong to and the workspace members depending on them, also
+/// transitively, sorted by name. Files outside of all packages, e.g. the workspace's
+/// `Cargo.lock`, don't affect any.
+pub fn affected_packages(packages: &[Package], changed_files: &[String]) -> Vec<String> {
+    let mut affected: BTreeSet<&str> = changed_files
+        .iter()
+        .filter_map(|file| {
+            let file = file.replace('\\', "/");
+            // The innermost package, packages may be nested in others
+            packages
+                .iter()
+                .filter(|p| {
+                    let dir = p.manifest_dir().replace('\\', "/");
+                    file.strip_prefix(&dir)
+                        .is_some_and(|rest| rest.starts_with('/'))
+                })
+                .max_by_key(|p| p.manifest_dir().len())
+        })
+        .map(|p| p.name.as_str())
+        .collect();
+
+    loop {
+        let dependents: Vec<&str> = packages
+            .iter()
+            .filter(|p| !affected.contains(p.name.as_str()))
+            .filter(|p| p.dependencies.iter().any(|d| affected.contains(d.as_str())))
+            .map(|p| p.name.as_str())
+            .collect();
+        if dependents.is_empty() {
+            break;
+        }
+        affected.extend(dependents);
+    }
+
+    affected.into_iter().map(str::to_string).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use assert2::check;
@@ -253,6 +608,19 @@ mod tests {
 
     use super::*;
 
+    #[wasm_bindgen_test(unsupported = test)]
+    fn watch_mode_watches_globs_of_the_packages() {
+        let root = Package::fixture("root", "/ws/Cargo.toml", Vec::new());
+        check!(root.watched_patterns("/ws").unwrap() == WATCHED_PATTERNS);
+        // Globs, not literal file names, relative to the workspace root
+        let core = Package::fixture("core", "C:\\ws\\crates\\core\\Cargo.toml", Vec::new());
+        let patterns = core.watched_patterns("C:\\ws\\").unwrap();
+        check!(patterns[0] == "crates/core/src/**/*.rs");
+        check!(patterns[5] == "crates/core/Cargo.toml");
+        let outside = Package::fixture("outside", "/wsx/Cargo.toml", Vec::new());
+        check!(outside.watched_patterns("/ws").is_none());
+    }
+
     #[wasm_bindgen_test(unsupported = test)]
     fn extract_profiles_valid() {
         let toml = r#"
@@ -338,4 +706,120 @@ version = "0.1.0"
 
         Ok(())
     }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_workspace_root() -> anyhow::Result<()> {
+        let metadata = include_str!("../../res/test-rust-project-metadata.json").to_string();
+        let metadata = extract_raw_metadata(&metadata)?;
+
+        let mut parsed = Metadata::default();
+        parsed.set_packages_and_target_dir(PackagesAndTargetDir::from_metadata(metadata));
+
+        check!(parsed.workspace_root() == "/test/repos/test-rust-project");
+        check!(parsed.target_dir() == "/test/repos/test-rust-project/target");
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cached_metadata_is_reused_for_unchanged_files() -> anyhow::Result<()> {
+        let metadata = include_str!("../../res/test-rust-project-metadata.json").to_string();
+        let parsed = PackagesAndTargetDir::from_metadata(extract_raw_metadata(&metadata)?);
+
+        let contents = vec!["[workspace]\n".to_string(), String::new()];
+        let cache = MetadataCache {
+            files: vec!["Cargo.toml".to_string(), ".cargo/config.toml".to_string()],
+            hash: files_hash(&contents),
+            packages_and_target_dir: parsed.clone(),
+        };
+        let cache: MetadataCache = serde_json::from_str(&serde_json::to_string(&cache)?)?;
+        check!(cache.clone().get(&contents) == Some(parsed));
+        check!(cache.get(&["[workspace]\nmembers = []\n".to_string(), String::new()]) == None);
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn only_changed_packages_are_patched() -> anyhow::Result<()> {
+        let metadata = include_str!("../../res/test-rust-project-metadata.json").to_string();
+        let parsed = PackagesAndTargetDir::from_metadata(extract_raw_metadata(&metadata)?);
+        let mut current = Metadata::default();
+        current.set_packages_and_target_dir(parsed.clone());
+
+        let mut edited = parsed.clone();
+        edited.packages[0].features.push("simd".to_string());
+        let name = edited.packages[0].name.clone();
+        check!(current.patch_packages(&edited) == Some(vec![name]));
+        check!(current.has_packages_and_target_dir(&edited));
+        check!(current.patch_packages(&edited) == Some(vec![]));
+
+        let mut removed = parsed;
+        removed.packages.pop();
+        check!(current.patch_packages(&removed) == None);
+        check!(current.has_packages_and_target_dir(&edited));
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_workspace_dependencies() -> anyhow::Result<()> {
+        let metadata = include_str!("../../res/test-rust-project-metadata.json").to_string();
+        let metadata = extract_raw_metadata(&metadata)?;
+
+        let packages = Package::from_metadata(metadata);
+        let dependencies = |name: &str| {
+            packages
+                .iter()
+                .find(|p| p.name == name)
+                .map(|p| p.dependencies.clone())
+        };
+
+        check!(dependencies("cli") == Some(vec!["core".to_string()]));
+        check!(dependencies("web-server") == Some(vec!["core".to_string()]));
+        check!(dependencies("core") == Some(vec![]));
+
+        let web_server = packages.iter().find(|p| p.name == "web-server").unwrap();
+        let registry: Vec<_> = web_server
+            .registry_dependencies
+            .iter()
+            .map(|d| (d.name.as_str(), d.req.as_str(), d.kind))
+            .collect();
+        check!(
+            registry
+                == [
+                    ("anyhow", "^1.0", None),
+                    ("serde", "^1.0", None),
+                    ("tokio", "^1.0", None),
+                    ("tokio-test", "^0.4", Some("dev")),
+                ]
+        );
+
+        Ok(())
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn changed_files_affect_their_packages_and_dependents() -> anyhow::Result<()> {
+        let metadata = include_str!("../../res/test-rust-project-metadata.json").to_string();
+        let packages = Package::from_metadata(extract_raw_metadata(&metadata)?);
+        let files = |files: &[&str]| files.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        check!(
+            affected_packages(
+                &packages,
+                &files(&["/test/repos/test-rust-project/core/src/lib.rs"])
+            ) == ["cli", "core", "utils", "web-server"]
+        );
+        check!(
+            affected_packages(
+                &packages,
+                &files(&[
+                    "/test/repos/test-rust-project/utils/src/lib.rs",
+                    "/test/repos/test-rust-project/Cargo.lock",
+                    "/test/repos/test-rust-project/core-docs/README.md",
+                ])
+            ) == ["utils"]
+        );
+
+        Ok(())
+    }
 }
@@ -1,10 +1,93 @@
+pub mod binary_size;
+pub use binary_size::BinarySizeHistory;
+
+pub mod build_queue;
+pub use build_queue::{BuildQueue, BuildStatus};
+
+pub mod build_summary;
+pub use build_summary::BuildSummary;
+
 pub mod command;
 pub use command::Command;
 
 pub mod config;
 pub use config::{Config, Features, Update as ConfigUpdate};
 
+pub mod coverage;
+
+pub mod criterion;
+pub use criterion::BenchmarkReport;
+
+pub mod diagnostics;
+pub use diagnostics::FileDiagnostic;
+
+pub mod discovery;
+
+pub mod doc_test;
+pub use doc_test::{DocTest, DocTestCache, DocTestLocation, source_hashes};
+
+pub mod feature_unification;
+
+pub mod generate;
+pub use generate::Template;
+
+pub mod insta;
+pub use insta::InstaUpdate;
+
+pub mod licenses;
+
+pub mod libtest;
+pub use libtest::{IgnoredTests, TestOutcome, TestResult};
+
+pub mod lockfile;
+
+pub mod manifest;
+pub use manifest::ManifestDependency;
+
+pub mod manifest_edit;
+
 pub mod metadata;
 
+pub mod msrv;
+
+pub mod nextest;
+
+pub mod new_package;
+
+pub mod new_target;
+
+pub mod normalize_manifest;
+
+pub mod overrides;
+pub use overrides::DependencyOverride;
+
+pub mod pre_commit;
+
 pub mod profile;
 pub use profile::Profile;
+
+pub mod registries;
+pub use registries::Registries;
+
+pub mod rename_package;
+
+pub mod reverse_dependencies;
+pub use reverse_dependencies::Dependent;
+
+pub mod runner;
+
+pub mod sanitizer;
+pub use sanitizer::Sanitizer;
+
+pub mod test_case;
+pub use test_case::{TestCase, TestTarget};
+
+pub mod test_history;
+pub use test_history::TestHistory;
+
+pub mod test_tree;
+pub use test_tree::{TestSelection, TestTree};
+
+pub mod version_bump;
+
+pub mod workspace_dependencies;
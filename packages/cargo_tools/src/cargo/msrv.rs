@@ -0,0 +1,191 @@
+use toml::Table;
+
+use crate::cargo::manifest_edit::{join, quote};
+
+/// The cargo subcommand of cargo-msrv
+pub const CARGO_MSRV: &str = "cargo-msrv";
+
+/// Whether `version` is a valid `rust-version` like `1.70` or `1.70.1`
+pub fn is_valid(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    (2..=3).contains(&parts.len())
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// The `rust-version` of the package of `manifest`, the one of `[workspace.package]` of the
+/// root manifest `root` if it inherits it
+pub fn rust_version(manifest: &str, root: &str) -> Option<String> {
+    let parse = |manifest: &str| manifest.parse::<Table>().ok();
+    let package = parse(manifest)?;
+    let version = package.get("package")?.get("rust-version")?;
+    if let Some(version) = version.as_str() {
+        return Some(version.to_string());
+    }
+    version.get("workspace")?.as_bool()?.then_some(())?;
+    let root = parse(root)?;
+    let version = root.get("workspace")?.get("package")?.get("rust-version")?;
+    version.as_str().map(str::to_string)
+}
+
+/// `manifest` with `key = value` in the table `header`, replacing the existing assignment of
+/// `key` like `key.workspace = true` or appended to the table, [None] without the table
+fn set_key(manifest: &str, header: &str, key: &str, value: &str) -> Option<String> {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|l| l.trim().replace(' ', "") == format!("[{header}]"))?;
+    let end = lines[start + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| start + 1 + i);
+    let assignment = format!("{key} = {value}");
+    let base = key.split('.').next().unwrap_or(key);
+    let existing = (start + 1..end).find(|&i| {
+        let line = lines[i].trim_start();
+        line.strip_prefix(base)
+            .is_some_and(|rest| rest.trim_start().starts_with(['=', '.']))
+    });
+    match existing {
+        Some(i) => lines[i] = assignment,
+        None => {
+            // After the last key of the table, before the blank lines of the next one
+            let last = (start + 1..end)
+                .rev()
+                .find(|&i| !lines[i].trim().is_empty())
+                .map_or(start + 1, |i| i + 1);
+            lines.insert(last, assignment);
+        }
+    }
+    Some(join(manifest, lines))
+}
+
+/// The root manifest `root` with `version` as `rust-version` of `[workspace.package]`, [None]
+/// if it has no such table
+pub fn set_workspace_rust_version(root: &str, version: &str) -> Option<String> {
+    let value = quote(version);
+    set_key(root, "workspace.package", "rust-version", &value)
+}
+
+/// The member manifest `manifest` with `version` as `rust-version`, inherited from the
+/// workspace instead if `version` is [None]
+pub fn set_package_rust_version(manifest: &str, version: Option<&str>) -> Option<String> {
+    match version {
+        Some(version) => {
+            let value = quote(version);
+            set_key(manifest, "package", "rust-version", &value)
+        }
+        None => set_key(manifest, "package", "rust-version.workspace", "true"),
+    }
+}
+
+/// The `cargo` arguments that check `package` with the rustup toolchain `toolchain`
+pub fn check_args(toolchain: &str, package: &str) -> Vec<String> {
+    [&format!("+{toolchain}"), "check", "--package", package]
+        .map(str::to_string)
+        .to_vec()
+}
+
+/// The `cargo msrv` arguments that verify the MSRV of the package in `dir`
+pub fn verify_args(dir: &str) -> Vec<String> {
+    ["msrv", "--path", dir, "verify"]
+        .map(str::to_string)
+        .to_vec()
+}
+
+/// A dependency which needs a newer rustc than the one building it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsupportedDependency {
+    /// The package with its version like `clap@4.5.1`
+    pub package: String,
+    /// The rustc version it requires
+    pub required: String,
+}
+
+/// The dependencies cargo refuses to build because of their `rust-version`. Cargo lists several
+/// as `  clap@4.5.1 requires rustc 1.74` and a single one as
+/// ``package `clap v4.5.1` cannot be built because it requires rustc 1.74 or newer``.
+pub fn unsupported_dependencies(output: &str) -> Vec<UnsupportedDependency> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if let Some((package, required)) = line.split_once(" requires rustc ")
+                && !package.contains(' ')
+            {
+                return Some(UnsupportedDependency {
+                    package: package.to_string(),
+                    required: required.trim().to_string(),
+                });
+            }
+            let (_, rest) = line.split_once("package `")?;
+            let (package, rest) = rest.split_once('`')?;
+            let (_, required) = rest.split_once("requires rustc ")?;
+            Some(UnsupportedDependency {
+                package: package.replacen(" v", "@", 1),
+                required: required.split_whitespace().next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether the cargo `output` reports errors
+pub fn failed(output: &str) -> bool {
+    output.lines().any(|line| line.starts_with("error"))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rust_versions_are_set_and_verified() {
+        check!(is_valid("1.70"));
+        check!(is_valid("1.70.1"));
+        check!(!is_valid("1"));
+        check!(!is_valid("1.70-nightly"));
+
+        let root = "[workspace]\nmembers = [\"core\"]\n\n[workspace.package]\nedition = \"2021\"\n\n[workspace.dependencies]\n";
+        let core = "[package]\nname = \"core\"\nrust-version = \"1.65\"\n\n[dependencies]\n";
+        check!(rust_version(core, root).as_deref() == Some("1.65"));
+
+        let root = set_workspace_rust_version(root, "1.74").unwrap();
+        check!(
+            root.contains("[workspace.package]\nedition = \"2021\"\nrust-version = \"1.74\"\n\n")
+        );
+        let core = set_package_rust_version(core, None).unwrap();
+        check!(
+            core == "[package]\nname = \"core\"\nrust-version.workspace = true\n\n[dependencies]\n"
+        );
+        check!(rust_version(&core, &root).as_deref() == Some("1.74"));
+        check!(set_workspace_rust_version("[workspace]\n", "1.74").is_none());
+
+        let output = "    Updating crates.io index
+error: rustc 1.70.0 is not supported by the following packages:
+  clap@4.5.1 requires rustc 1.74
+  clap_lex@0.7.0 requires rustc 1.74
+";
+        let unsupported = unsupported_dependencies(output);
+        check!(unsupported.len() == 2);
+        check!(unsupported[0].package == "clap@4.5.1");
+        check!(unsupported[0].required == "1.74");
+        check!(failed(output));
+        let single = "error: package `clap v4.5.1` cannot be built because it requires rustc 1.74 or newer, while the currently active rustc version is 1.70.0";
+        check!(
+            unsupported_dependencies(single)
+                == [UnsupportedDependency {
+                    package: "clap@4.5.1".to_string(),
+                    required: "1.74".to_string(),
+                }]
+        );
+        check!(!failed(
+            "    Finished `dev` profile [unoptimized + debuginfo] target(s)"
+        ));
+
+        check!(check_args("1.74", "core") == ["+1.74", "check", "--package", "core"]);
+    }
+}
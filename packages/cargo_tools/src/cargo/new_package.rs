@@ -0,0 +1,293 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::cargo::manifest_edit::{add_to_table, quote};
+
+/// The kind of package `cargo new` creates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageKind {
+    Lib,
+    Bin,
+}
+
+impl PackageKind {
+    pub const fn all() -> [Self; 2] {
+        [Self::Lib, Self::Bin]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Lib => "lib",
+            Self::Bin => "bin",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Lib => "A library with src/lib.rs",
+            Self::Bin => "A binary with src/main.rs",
+        }
+    }
+
+    /// The source file `cargo new` creates for this kind
+    pub fn source_file(&self) -> &'static str {
+        match self {
+            Self::Lib => "src/lib.rs",
+            Self::Bin => "src/main.rs",
+        }
+    }
+}
+
+/// Whether cargo accepts `name` as package name
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The arguments of the `cargo new` which creates the package `name` of `kind` at `path`
+pub fn new_args(kind: PackageKind, name: &str, path: &str) -> Vec<String> {
+    ["new", &format!("--{}", kind.name()), "--name", name, path]
+        .map(str::to_string)
+        .to_vec()
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Manifest {
+    workspace: Option<Workspace>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Workspace {
+    members: Vec<String>,
+    dependencies: BTreeMap<String, toml::Value>,
+}
+
+fn workspace(manifest: &str) -> Workspace {
+    toml::from_str::<Manifest>(manifest)
+        .ok()
+        .and_then(|m| m.workspace)
+        .unwrap_or_default()
+}
+
+/// Where a new package `name` goes, next to the members of a glob like `crates/*` of the root
+/// manifest `manifest` if there is one
+pub fn default_location(manifest: &str, name: &str) -> String {
+    workspace(manifest)
+        .members
+        .iter()
+        .find_map(|member| member.strip_suffix("/*"))
+        .map_or(name.to_string(), |dir| format!("{dir}/{name}"))
+}
+
+/// The root manifest `manifest` with `path` appended to `workspace.members`, [None] if a
+/// member or glob already covers it
+pub fn add_workspace_member(manifest: &str, path: &str) -> Option<String> {
+    let members = workspace(manifest).members;
+    let parent = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let covered = members.iter().any(|member| {
+        member.trim_end_matches('/') == path
+            || member.strip_suffix("/*").is_some_and(|dir| dir == parent)
+            || (member == "*" && parent.is_empty())
+    });
+    if covered {
+        return None;
+    }
+
+    let member = quote(path);
+    let lines: Vec<&str> = manifest.lines().collect();
+    let Some(header) = lines.iter().position(|l| l.trim() == "[workspace]") else {
+        let separator = if manifest.ends_with('\n') { "" } else { "\n" };
+        return Some(format!(
+            "{manifest}{separator}\n[workspace]\nmembers = [{member}]\n"
+        ));
+    };
+    let section_end = lines[header + 1..]
+        .iter()
+        .position(|l| l.trim_start().starts_with('['))
+        .map_or(lines.len(), |i| header + 1 + i);
+    let members_line = (header + 1..section_end).find(|&i| {
+        lines[i]
+            .split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "members")
+    });
+
+    let mut edited: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    match members_line {
+        None => edited.insert(header + 1, format!("members = [{member}]")),
+        Some(start) => {
+            let end = (start..lines.len()).find(|&i| lines[i].contains(']'))?;
+            let line = &lines[end];
+            let close = line.rfind(']')?;
+            if start == end {
+                let open = line.find('[')?;
+                let items = line[open + 1..close].trim().trim_end_matches(',');
+                let items = if items.is_empty() {
+                    member
+                } else {
+                    format!("{items}, {member}")
+                };
+                edited[end] = format!("{}[{items}]{}", &line[..open], &line[close + 1..]);
+            } else {
+                let indent = lines[start + 1..end]
+                    .iter()
+                    .find(|l| !l.trim().is_empty())
+                    .map_or("    ", |l| &l[..l.len() - l.trim_start().len()]);
+                // The previous item may lack a trailing comma
+                if let Some(last) = (start + 1..end)
+                    .rev()
+                    .find(|&i| !lines[i].trim().is_empty())
+                    && !lines[last].trim_end().ends_with(',')
+                {
+                    edited[last] = format!("{},", lines[last].trim_end());
+                }
+                let before = line[..close].trim();
+                if before.is_empty() {
+                    edited.insert(end, format!("{indent}{member},"));
+                } else {
+                    edited[end] = format!("{indent}{before}, {member}\n{}", &line[close..]);
+                }
+            }
+        }
+    }
+    let mut edited = edited.join("\n");
+    if manifest.ends_with('\n') {
+        edited.push('\n');
+    }
+    Some(edited)
+}
+
+/// A dependency of a new package on a workspace member
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MemberDependency {
+    /// The member is a dependency of `[workspace.dependencies]`
+    Workspace(String),
+    /// The member at the relative path
+    Path(String, String),
+}
+
+impl MemberDependency {
+    /// The dependency on the member `name` at `dir` of a package at `package_dir`, inherited
+    /// from the root manifest `root_manifest` if it declares it
+    pub fn new(name: &str, dir: &Path, package_dir: &Path, root_manifest: &str) -> Self {
+        if workspace(root_manifest).dependencies.contains_key(name) {
+            Self::Workspace(name.to_string())
+        } else {
+            let path = relative_path(package_dir, dir)
+                .to_string_lossy()
+                .replace('\\', "/");
+            Self::Path(name.to_string(), path)
+        }
+    }
+
+    fn to_toml(&self) -> String {
+        match self {
+            Self::Workspace(name) => format!("{name} = {{ workspace = true }}"),
+            Self::Path(name, path) => format!("{name} = {{ path = {} }}", quote(path)),
+        }
+    }
+}
+
+/// The package manifest `manifest` with `dependencies` added to its `[dependencies]`
+pub fn add_dependencies(manifest: &str, dependencies: &[MemberDependency]) -> String {
+    if dependencies.is_empty() {
+        return manifest.to_string();
+    }
+    let entries: Vec<String> = dependencies.iter().map(MemberDependency::to_toml).collect();
+    add_to_table(manifest, "dependencies", entries)
+}
+
+/// The path of `to` relative to `from`, both absolute or both relative to the same directory
+pub(crate) fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<Component> = from.components().collect();
+    let to: Vec<Component> = to.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    std::iter::repeat_n(Component::ParentDir, from.len() - common)
+        .chain(to[common..].iter().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn names_locations_and_args() {
+        check!(is_valid_name("my-crate_2"));
+        check!(!is_valid_name("2fast"));
+        check!(!is_valid_name("my crate"));
+        check!(!is_valid_name(""));
+
+        let manifest = "[workspace]\nmembers = [\"crates/*\", \"xtask\"]\n";
+        check!(default_location(manifest, "parser") == "crates/parser");
+        check!(default_location("[workspace]\nmembers = [\"cli\"]\n", "parser") == "parser");
+
+        check!(
+            new_args(PackageKind::Lib, "parser", "crates/parser")
+                == ["new", "--lib", "--name", "parser", "crates/parser"]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn members_are_appended() {
+        let inline =
+            "[workspace]\nresolver = \"2\"\nmembers = [\"cli\"]\n\n[profile.release]\nlto = true\n";
+        check!(
+            add_workspace_member(inline, "core").as_deref()
+                == Some(
+                    "[workspace]\nresolver = \"2\"\nmembers = [\"cli\", \"core\"]\n\n[profile.release]\nlto = true\n"
+                )
+        );
+
+        let multiline = "[workspace]\nmembers = [\n    \"cli\",\n    \"core\"\n]\n";
+        check!(
+            add_workspace_member(multiline, "parser").as_deref()
+                == Some(
+                    "[workspace]\nmembers = [\n    \"cli\",\n    \"core\",\n    \"parser\",\n]\n"
+                )
+        );
+
+        check!(
+            add_workspace_member("[workspace]\nresolver = \"2\"\n", "cli").as_deref()
+                == Some("[workspace]\nmembers = [\"cli\"]\nresolver = \"2\"\n")
+        );
+        check!(
+            add_workspace_member("[package]\nname = \"app\"\n", "cli").as_deref()
+                == Some("[package]\nname = \"app\"\n\n[workspace]\nmembers = [\"cli\"]\n")
+        );
+
+        let globbed = "[workspace]\nmembers = [\"crates/*\"]\n";
+        check!(add_workspace_member(globbed, "crates/parser").is_none());
+        check!(add_workspace_member(inline, "cli").is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn dependencies_on_members() {
+        let root = "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\ncore = { path = \"crates/core\" }\n";
+        let package_dir = Path::new("/ws/crates/parser");
+        let core = MemberDependency::new("core", Path::new("/ws/crates/core"), package_dir, root);
+        let cli = MemberDependency::new("cli", Path::new("/ws/cli"), package_dir, root);
+        check!(core == MemberDependency::Workspace("core".to_string()));
+        check!(cli == MemberDependency::Path("cli".to_string(), "../../cli".to_string()));
+
+        let manifest = "[package]\nname = \"parser\"\nedition = \"2024\"\n\n[dependencies]\n\n[dev-dependencies]\n";
+        check!(
+            add_dependencies(manifest, &[core.clone(), cli])
+                == "[package]\nname = \"parser\"\nedition = \"2024\"\n\n[dependencies]\ncore = { workspace = true }\ncli = { path = \"../../cli\" }\n\n[dev-dependencies]\n"
+        );
+        check!(
+            add_dependencies("[package]\nname = \"parser\"\n", &[core])
+                == "[package]\nname = \"parser\"\n\n[dependencies]\ncore = { workspace = true }\n"
+        );
+    }
+}
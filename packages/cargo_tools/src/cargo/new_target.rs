@@ -0,0 +1,199 @@
+use toml::Table;
+
+use crate::cargo::manifest_edit::{add_to_table, quote};
+
+/// The kind of target a **New Example**, **New Integration Test** or **New Benchmark** action
+/// creates in the directory cargo discovers it in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+    Example,
+    Test,
+    Bench,
+}
+
+impl TargetKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Example => "Example",
+            Self::Test => "Integration Test",
+            Self::Bench => "Benchmark",
+        }
+    }
+
+    /// The directory of the package cargo discovers targets of this kind in
+    pub fn dir(&self) -> &'static str {
+        match self {
+            Self::Example => "examples",
+            Self::Test => "tests",
+            Self::Bench => "benches",
+        }
+    }
+
+    /// The file of the target `name` relative to the package directory
+    pub fn file(&self, name: &str) -> String {
+        format!("{}/{name}.rs", self.dir())
+    }
+}
+
+/// The harness a new benchmark is written for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchHarness {
+    Criterion,
+    Libtest,
+}
+
+impl BenchHarness {
+    pub const fn all() -> [Self; 2] {
+        [Self::Criterion, Self::Libtest]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Criterion => "criterion",
+            Self::Libtest => "libtest",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Criterion => {
+                "Criterion benchmark with `harness = false`, adds the dev-dependency"
+            }
+            Self::Libtest => "#[bench] functions, require a nightly toolchain",
+        }
+    }
+}
+
+/// The version requirement of criterion added to the dev-dependencies of a package
+pub const CRITERION_REQ: &str = "0.7";
+
+/// The source of a new target of `kind`, benchmarks are written for `harness`
+pub fn source(kind: TargetKind, name: &str, harness: BenchHarness) -> String {
+    match (kind, harness) {
+        (TargetKind::Example, _) => {
+            format!("fn main() {{\n    println!(\"Hello from the {name} example!\");\n}}\n")
+        }
+        (TargetKind::Test, _) => {
+            "#[test]\nfn it_works() {\n    assert_eq!(2 + 2, 4);\n}\n".to_string()
+        }
+        (TargetKind::Bench, BenchHarness::Criterion) => format!(
+            r#"use std::hint::black_box;
+
+use criterion::{{Criterion, criterion_group, criterion_main}};
+
+fn bench(c: &mut Criterion) {{
+    c.bench_function("{name}", |b| b.iter(|| black_box(2 + 2)));
+}}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
+"#
+        ),
+        (TargetKind::Bench, BenchHarness::Libtest) => r#"#![feature(test)]
+
+extern crate test;
+
+use test::Bencher;
+
+#[bench]
+fn bench(b: &mut Bencher) {
+    b.iter(|| test::black_box(2 + 2));
+}
+"#
+        .to_string(),
+    }
+}
+
+fn append(manifest: &str, section: &str) -> String {
+    let mut edited = manifest.trim_end().to_string();
+    edited.push_str("\n\n");
+    edited.push_str(section);
+    edited
+}
+
+/// `manifest` with a `[[bench]]` entry for the benchmark `name` which disables the libtest
+/// harness, unchanged if it already declares the benchmark
+pub fn with_bench_target(manifest: &str, name: &str) -> String {
+    let declared = manifest
+        .parse::<Table>()
+        .ok()
+        .and_then(|m| m.get("bench")?.as_array().cloned())
+        .into_iter()
+        .flatten()
+        .any(|bench| bench.get("name").and_then(|n| n.as_str()) == Some(name));
+    if declared {
+        return manifest.to_string();
+    }
+    let name = quote(name);
+    append(
+        manifest,
+        &format!("[[bench]]\nname = {name}\nharness = false\n"),
+    )
+}
+
+/// `manifest` with `name = "req"` in its `[dev-dependencies]`, which are added if missing,
+/// unchanged if it already declares the dev-dependency. It inherits the dependency with
+/// `{ workspace = true }` instead if `[workspace.dependencies]` of the root manifest `root`
+/// declares it.
+pub fn with_dev_dependency(manifest: &str, root: &str, name: &str, req: &str) -> String {
+    let parsed = manifest.parse::<Table>().ok();
+    let dev_dependencies = parsed.as_ref().and_then(|m| m.get("dev-dependencies"));
+    if dev_dependencies.and_then(|d| d.get(name)).is_some() {
+        return manifest.to_string();
+    }
+    let inherited = root
+        .parse::<Table>()
+        .ok()
+        .and_then(|r| r.get("workspace")?.get("dependencies")?.get(name).cloned())
+        .is_some();
+    let entry = if inherited {
+        format!("{name} = {{ workspace = true }}")
+    } else {
+        format!("{name} = {}", quote(req))
+    };
+    add_to_table(manifest, "dev-dependencies", vec![entry])
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn targets_are_scaffolded() {
+        check!(TargetKind::Test.file("api") == "tests/api.rs");
+        check!(
+            source(TargetKind::Example, "demo", BenchHarness::Criterion).contains("demo example")
+        );
+        check!(
+            source(TargetKind::Bench, "parse", BenchHarness::Criterion)
+                .contains("c.bench_function(\"parse\"")
+        );
+        check!(source(TargetKind::Bench, "parse", BenchHarness::Libtest).contains("#[bench]"));
+
+        let manifest =
+            "[package]\nname = \"core\"\n\n[dev-dependencies]\nassert2 = \"0.3\"\n\n[features]\n";
+        let root = "[workspace]\nmembers = [\"core\"]\n";
+        let edited = with_dev_dependency(manifest, root, "criterion", "0.5");
+        check!(
+            edited
+                == "[package]\nname = \"core\"\n\n[dev-dependencies]\nassert2 = \"0.3\"\ncriterion = \"0.5\"\n\n[features]\n"
+        );
+        check!(with_dev_dependency(&edited, root, "criterion", "0.4") == edited);
+        check!(
+            with_dev_dependency("[package]\nname = \"core\"\n", root, "criterion", "0.5")
+                == "[package]\nname = \"core\"\n\n[dev-dependencies]\ncriterion = \"0.5\"\n"
+        );
+        let root = "[workspace]\n\n[workspace.dependencies]\ncriterion = \"0.5\"\n";
+        check!(
+            with_dev_dependency(manifest, root, "criterion", CRITERION_REQ)
+                .contains("\ncriterion = { workspace = true }\n")
+        );
+
+        let edited = with_bench_target(manifest, "parse");
+        check!(edited.ends_with("[features]\n\n[[bench]]\nname = \"parse\"\nharness = false\n"));
+        check!(with_bench_target(&edited, "parse") == edited);
+    }
+}
@@ -0,0 +1,111 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::Deserialize;
+
+use crate::cargo::manifest_edit::quote;
+
+/// The profile nextest uses when none is selected
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The name the tool config of the extension is passed to nextest with
+pub const TOOL_NAME: &str = "cargo-tools";
+
+/// The profiles of a nextest config like `.config/nextest.toml`, [DEFAULT_PROFILE] first
+pub fn profiles(config: &str) -> Vec<String> {
+    #[derive(Deserialize)]
+    struct NextestConfig {
+        #[serde(default)]
+        profile: BTreeMap<String, toml::Table>,
+    }
+
+    let defined: Vec<String> = toml::from_str::<NextestConfig>(config)
+        .map(|config| config.profile.into_keys().collect())
+        .unwrap_or_default();
+    std::iter::once(DEFAULT_PROFILE.to_string())
+        .chain(defined.into_iter().filter(|p| p != DEFAULT_PROFILE))
+        .collect()
+}
+
+/// The settings the extension adds to the nextest config of a profile, settings of the
+/// repository's config take precedence
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolConfig {
+    /// Where nextest writes a JUnit report of the runs
+    pub junit_path: Option<String>,
+    /// After how long nextest terminates a single test
+    pub test_timeout: Option<Duration>,
+}
+
+impl ToolConfig {
+    pub fn is_empty(&self) -> bool {
+        self.junit_path.is_none() && self.test_timeout.is_none()
+    }
+
+    /// The tool config file with the settings for `profile`
+    pub fn to_toml(&self, profile: &str) -> String {
+        let profile = quote(profile);
+
+        let mut toml = String::new();
+        if let Some(timeout) = self.test_timeout {
+            // A test is terminated at its first slow timeout
+            toml.push_str(&format!(
+                "[profile.{profile}]\nslow-timeout = {{ period = \"{}s\", terminate-after = 1 }}\n",
+                timeout.as_secs()
+            ));
+        }
+        if let Some(junit_path) = &self.junit_path {
+            toml.push_str(&format!(
+                "[profile.{profile}.junit]\npath = {}\n",
+                quote(junit_path)
+            ));
+        }
+        toml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn profiles_and_tool_config() {
+        let config = r#"
+[profile.default]
+retries = 1
+
+[profile.ci]
+fail-fast = false
+
+[profile.ci.junit]
+path = "junit.xml"
+"#;
+        check!(profiles(config) == ["default", "ci"]);
+        check!(profiles("not toml [") == ["default"]);
+
+        let tool_config = ToolConfig {
+            junit_path: Some("/ws/reports/junit.xml".to_string()),
+            test_timeout: None,
+        };
+        check!(
+            tool_config.to_toml("ci")
+                == "[profile.\"ci\".junit]\npath = \"/ws/reports/junit.xml\"\n"
+        );
+
+        let tool_config = ToolConfig {
+            test_timeout: Some(Duration::from_secs(30)),
+            ..tool_config
+        };
+        check!(
+            tool_config.to_toml("default")
+                == "[profile.\"default\"]
+slow-timeout = { period = \"30s\", terminate-after = 1 }
+[profile.\"default\".junit]
+path = \"/ws/reports/junit.xml\"
+"
+        );
+        check!(ToolConfig::default().is_empty());
+    }
+}
@@ -0,0 +1,526 @@
+use crate::cargo::manifest::is_dependency_table;
+
+/// How **Normalize Manifest** declares dependencies with more than a version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyStyle {
+    /// Keep inline tables and `[dependencies.serde]` tables as they are
+    Keep,
+    /// `serde = { version = "1", features = ["derive"] }`
+    Inline,
+    /// `[dependencies.serde]` tables
+    Expanded,
+}
+
+impl DependencyStyle {
+    /// The style of the `cargoTools.normalizeManifest.dependencyStyle` value `value`
+    pub fn from_setting(value: &str) -> Self {
+        match value {
+            "inline" => Self::Inline,
+            "expanded" => Self::Expanded,
+            _ => Self::Keep,
+        }
+    }
+}
+
+/// `line` split at the first `#` outside of strings into code and comment
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some('"') if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return (&line[..i], Some(&line[i..])),
+            None => {}
+        }
+    }
+    (line, None)
+}
+
+/// The change of the bracket depth by the code of `line`
+fn depth_change(line: &str) -> i32 {
+    let (code, _) = split_comment(line);
+    let mut quote = None;
+    let mut depth = 0;
+    for c in code.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            _ => {}
+        }
+    }
+    depth
+}
+
+/// `s` split at the `sep` characters outside of strings and brackets
+fn split_top_level(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, c) if c == sep && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+fn unquote(key: &str) -> String {
+    key.trim().trim_matches(['"', '\'']).to_string()
+}
+
+/// A key of a table with the comment lines directly above it
+#[derive(Debug, Clone)]
+struct Entry {
+    comments: Vec<String>,
+    /// More than one for values spanning several lines
+    lines: Vec<String>,
+}
+
+impl Entry {
+    fn key(&self) -> String {
+        let (key, _) = self.lines[0]
+            .split_once('=')
+            .unwrap_or((&self.lines[0], ""));
+        unquote(key)
+    }
+
+    /// The fields of a single line inline table value like `{ version = "1" }` and the comment
+    /// after it
+    fn inline_table(&self) -> Option<(Vec<String>, Option<String>)> {
+        let [line] = self.lines.as_slice() else {
+            return None;
+        };
+        let (code, comment) = split_comment(line);
+        let (_, value) = code.split_once('=')?;
+        let fields = value.trim().strip_prefix('{')?.strip_suffix('}')?;
+        let fields = split_top_level(fields, ',')
+            .into_iter()
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+        Some((fields, comment.map(str::to_string)))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Item {
+    Blank,
+    Comment(String),
+    Entry(Entry),
+}
+
+fn items(body: &[String]) -> Vec<Item> {
+    let mut items = Vec::new();
+    let mut i = 0;
+    while i < body.len() {
+        let line = body[i].trim();
+        if line.is_empty() {
+            items.push(Item::Blank);
+        } else if line.starts_with('#') {
+            items.push(Item::Comment(body[i].clone()));
+        } else {
+            // The comments directly above belong to the entry
+            let mut comments = Vec::new();
+            while let Some(Item::Comment(comment)) = items.last() {
+                comments.insert(0, comment.clone());
+                items.pop();
+            }
+            let mut lines = vec![body[i].clone()];
+            let mut depth = depth_change(&body[i]);
+            while depth > 0 && i + 1 < body.len() {
+                i += 1;
+                depth += depth_change(&body[i]);
+                lines.push(body[i].clone());
+            }
+            items.push(Item::Entry(Entry { comments, lines }));
+        }
+        i += 1;
+    }
+    items
+}
+
+fn render(items: &[Item]) -> Vec<String> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            Item::Blank => vec![String::new()],
+            Item::Comment(comment) => vec![comment.clone()],
+            Item::Entry(entry) => entry.comments.iter().chain(&entry.lines).cloned().collect(),
+        })
+        .collect()
+}
+
+/// `items` with the entries of each group separated by blank lines sorted by key
+fn sort_items(items: Vec<Item>) -> Vec<Item> {
+    let mut sorted = Vec::new();
+    let mut group: Vec<Entry> = Vec::new();
+    let flush = |group: &mut Vec<Entry>, sorted: &mut Vec<Item>| {
+        group.sort_by_key(|entry| entry.key().to_lowercase());
+        sorted.extend(group.drain(..).map(Item::Entry));
+    };
+    for item in items {
+        match item {
+            Item::Entry(entry) => group.push(entry),
+            item => {
+                flush(&mut group, &mut sorted);
+                sorted.push(item);
+            }
+        }
+    }
+    flush(&mut group, &mut sorted);
+    sorted
+}
+
+/// A table of a manifest with the comments directly above its header
+#[derive(Debug, Clone)]
+struct Table {
+    comments: Vec<String>,
+    /// [None] for the keys before the first table
+    header: Option<String>,
+    body: Vec<String>,
+}
+
+impl Table {
+    /// The name of the table like `dependencies` or `dependencies.serde`
+    fn name(&self) -> Option<String> {
+        let header = self.header.as_ref()?.trim();
+        let name = header.strip_prefix('[')?.split(']').next()?;
+        (!header.starts_with("[[")).then(|| name.replace(' ', ""))
+    }
+
+    /// The dependency table and the dependency of a table like `[dependencies.serde]`
+    fn dependency(&self) -> Option<(String, String)> {
+        let name = self.name()?;
+        let (table, key) = name.rsplit_once('.')?;
+        is_dependency_table(table).then(|| (table.to_string(), unquote(key)))
+    }
+
+    fn is_dependency_table(&self) -> bool {
+        self.name().is_some_and(|name| is_dependency_table(&name))
+    }
+}
+
+fn tables(manifest: &str) -> Vec<Table> {
+    let mut tables = vec![Table {
+        comments: Vec::new(),
+        header: None,
+        body: Vec::new(),
+    }];
+    for line in manifest.lines() {
+        if line.trim_start().starts_with('[') {
+            // The comments directly above belong to the header
+            let body = &mut tables.last_mut().expect("starts with a table").body;
+            let start = body
+                .iter()
+                .rposition(|l| !l.trim_start().starts_with('#'))
+                .map_or(0, |i| i + 1);
+            let comments = body.split_off(start);
+            tables.push(Table {
+                comments,
+                header: Some(line.to_string()),
+                body: Vec::new(),
+            });
+        } else {
+            tables
+                .last_mut()
+                .expect("starts with a table")
+                .body
+                .push(line.to_string());
+        }
+    }
+    tables
+}
+
+/// `manifest` with the entries of its dependency tables sorted alphabetically within their
+/// groups separated by blank lines, and `[dependencies.serde]` tables sorted after their
+/// dependency table. Dependencies with more than a version are converted to `style`, those
+/// with comments inside their declaration are kept. Comments stay above the entries and tables
+/// they are written above.
+pub fn normalize(manifest: &str, style: DependencyStyle) -> String {
+    let mut tables = tables(manifest);
+    match style {
+        DependencyStyle::Keep => {}
+        DependencyStyle::Inline => inline_tables(&mut tables),
+        DependencyStyle::Expanded => expand_tables(&mut tables),
+    }
+    for table in tables.iter_mut().filter(|t| t.is_dependency_table()) {
+        table.body = render(&sort_items(items(&table.body)));
+    }
+    sort_dependency_tables(&mut tables);
+
+    let mut lines: Vec<String> = Vec::new();
+    for table in tables {
+        lines.extend(table.comments);
+        lines.extend(table.header);
+        lines.extend(table.body);
+    }
+    let mut normalized = lines.join("\n");
+    if manifest.ends_with('\n') {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Adds `lines` after the last entry of `body`, before the blank lines of the next table
+fn append_entry(body: &mut Vec<String>, lines: Vec<String>) {
+    let end = body
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map_or(0, |i| i + 1);
+    body.splice(end..end, lines);
+}
+
+/// Turns the `[dependencies.serde]` tables without comments inside into inline tables of
+/// their dependency table, if the manifest has one
+fn inline_tables(tables: &mut Vec<Table>) {
+    let mut i = 0;
+    while i < tables.len() {
+        let Some((parent, key)) = tables[i].dependency() else {
+            i += 1;
+            continue;
+        };
+        let fields: Option<Vec<String>> = items(&tables[i].body)
+            .into_iter()
+            .filter(|item| !matches!(item, Item::Blank))
+            .map(|item| match item {
+                Item::Entry(entry) if entry.comments.is_empty() && entry.lines.len() == 1 => {
+                    let (code, comment) = split_comment(&entry.lines[0]);
+                    comment.is_none().then(|| code.trim().to_string())
+                }
+                _ => None,
+            })
+            .collect();
+        let target = tables
+            .iter()
+            .position(|t| t.name().as_deref() == Some(parent.as_str()));
+        let (Some(fields), Some(target)) = (fields, target) else {
+            i += 1;
+            continue;
+        };
+        let table = tables.remove(i);
+        let mut lines = table.comments;
+        lines.push(format!("{key} = {{ {} }}", fields.join(", ")));
+        let target = if target > i { target - 1 } else { target };
+        append_entry(&mut tables[target].body, lines);
+    }
+}
+
+/// Moves the single line inline tables of the dependency tables into `[dependencies.serde]`
+/// tables after their dependency table
+fn expand_tables(tables: &mut Vec<Table>) {
+    let mut i = 0;
+    while i < tables.len() {
+        if !tables[i].is_dependency_table() {
+            i += 1;
+            continue;
+        }
+        let name = tables[i].name().expect("dependency tables have a name");
+        let mut kept = Vec::new();
+        let mut expanded = Vec::new();
+        for item in items(&tables[i].body) {
+            match item {
+                Item::Entry(entry) if let Some((fields, comment)) = entry.inline_table() => {
+                    let mut comments = entry.comments.clone();
+                    comments.extend(comment);
+                    let mut body: Vec<String> = fields;
+                    body.push(String::new());
+                    expanded.push(Table {
+                        comments,
+                        header: Some(format!("[{name}.{}]", entry.key())),
+                        body,
+                    });
+                }
+                item => kept.push(item),
+            }
+        }
+        if expanded.is_empty() {
+            i += 1;
+            continue;
+        }
+        tables[i].body = render(&kept);
+        if tables[i].body.last().is_none_or(|l| !l.trim().is_empty()) {
+            tables[i].body.push(String::new());
+        }
+        // After the existing `[dependencies.serde]` tables of the dependency table
+        let mut end = i + 1;
+        while tables
+            .get(end)
+            .and_then(Table::dependency)
+            .is_some_and(|(parent, _)| parent == name)
+        {
+            end += 1;
+        }
+        if let Some(last) = expanded.last_mut()
+            && end == tables.len()
+        {
+            last.body.pop();
+        }
+        if end == tables.len()
+            && let Some(previous) = tables.get_mut(end - 1)
+            && previous.body.last().is_none_or(|l| !l.trim().is_empty())
+        {
+            previous.body.push(String::new());
+        }
+        let count = expanded.len();
+        tables.splice(end..end, expanded);
+        i = end + count;
+    }
+}
+
+/// Sorts the runs of consecutive `[dependencies.serde]` tables of the same dependency table by
+/// the dependency, the blank lines at the end of the run stay at its end
+fn sort_dependency_tables(tables: &mut [Table]) {
+    let mut i = 0;
+    while i < tables.len() {
+        let Some((parent, _)) = tables[i].dependency() else {
+            i += 1;
+            continue;
+        };
+        let end = (i..tables.len())
+            .find(|&j| tables[j].dependency().is_none_or(|(p, _)| p != parent))
+            .unwrap_or(tables.len());
+        let run = &mut tables[i..end];
+        // Each table ends with one blank line, except for the last one of the manifest
+        let last_blank = run
+            .last()
+            .is_some_and(|t| t.body.last().is_some_and(|l| l.trim().is_empty()));
+        for table in run.iter_mut() {
+            while table.body.last().is_some_and(|l| l.trim().is_empty()) {
+                table.body.pop();
+            }
+        }
+        run.sort_by_key(|t| t.dependency().map(|(_, key)| key.to_lowercase()));
+        let count = run.len();
+        for (j, table) in run.iter_mut().enumerate() {
+            if j + 1 < count || last_blank {
+                table.body.push(String::new());
+            }
+        }
+        i = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    const MANIFEST: &str = r#"[package]
+name = "app"
+
+[dependencies]
+# Logging
+tracing = "0.1"
+anyhow = "1" # errors
+serde = { version = "1", features = ["derive"] }
+
+# Internal
+core = { path = "../core" }
+api = { path = "../api" }
+
+[dependencies.tokio]
+version = "1"
+features = [
+    "rt",
+]
+
+[dependencies.clap]
+version = "4"
+features = ["derive"]
+
+[features]
+default = []
+"#;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn manifests_are_normalized() {
+        check!(split_comment("a = \"#1\" # b") == ("a = \"#1\" ", Some("# b")));
+        check!(split_top_level("a = 1, b = [1, 2]", ',') == ["a = 1", " b = [1, 2]"]);
+
+        check!(
+            normalize(MANIFEST, DependencyStyle::Keep)
+                == r#"[package]
+name = "app"
+
+[dependencies]
+anyhow = "1" # errors
+serde = { version = "1", features = ["derive"] }
+# Logging
+tracing = "0.1"
+
+api = { path = "../api" }
+# Internal
+core = { path = "../core" }
+
+[dependencies.clap]
+version = "4"
+features = ["derive"]
+
+[dependencies.tokio]
+version = "1"
+features = [
+    "rt",
+]
+
+[features]
+default = []
+"#
+        );
+
+        // The multi line features of tokio stay expanded
+        check!(
+            normalize(MANIFEST, DependencyStyle::Inline)
+                == r#"[package]
+name = "app"
+
+[dependencies]
+anyhow = "1" # errors
+serde = { version = "1", features = ["derive"] }
+# Logging
+tracing = "0.1"
+
+api = { path = "../api" }
+clap = { version = "4", features = ["derive"] }
+# Internal
+core = { path = "../core" }
+
+[dependencies.tokio]
+version = "1"
+features = [
+    "rt",
+]
+
+[features]
+default = []
+"#
+        );
+
+        check!(
+            normalize(
+                "[dependencies]\nlog = \"0.4\"\nserde = { version = \"1\", features = [\"derive\"] } # serde\n",
+                DependencyStyle::Expanded
+            ) == "[dependencies]\nlog = \"0.4\"\n\n# serde\n[dependencies.serde]\nversion = \"1\"\nfeatures = [\"derive\"]\n"
+        );
+    }
+}
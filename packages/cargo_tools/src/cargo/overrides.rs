@@ -0,0 +1,454 @@
+use itertools::Itertools;
+use toml::Table;
+
+use crate::cargo::{
+    Registries,
+    manifest_edit::quote,
+    metadata::{ParseError, RegistryDependency, extract_raw_metadata, is_registry_source},
+};
+
+/// A `[patch]`, `[replace]` or `paths` override of the root manifest or the cargo config which
+/// redirects where cargo sources a dependency from
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DependencyOverride {
+    /// The name of the overridden crate
+    pub name: String,
+    /// The source the override applies to like `crates-io`, the name of a registry or the URL of
+    /// a registry or git repository, [None] for `[replace]` and `paths` which apply to all
+    pub patched: Option<String>,
+    /// Where cargo sources the crate from instead, e.g. `path ../serde`
+    pub source: String,
+    /// The section declaring the override like `patch.crates-io`
+    pub section: String,
+    /// The file declaring the override
+    pub file: String,
+    /// Zero based line of the declaration in `file`
+    pub line: usize,
+}
+
+impl DependencyOverride {
+    /// Whether the override redirects the registry `dependency`
+    pub fn applies_to(&self, dependency: &RegistryDependency, registries: &Registries) -> bool {
+        if self.name != dependency.name {
+            return false;
+        }
+        match (self.patched.as_deref(), dependency.registry.as_deref()) {
+            (None, _) => true,
+            (Some("crates-io"), None) => true,
+            (Some(patched), Some(registry)) => {
+                let trim = |url: &str| {
+                    url.trim_start_matches("sparse+")
+                        .trim_end_matches('/')
+                        .to_string()
+                };
+                trim(patched) == trim(registry) || registries.name_of(registry) == Some(patched)
+            }
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// A registry dependency of a member which cargo sources from elsewhere, e.g. because of a
+/// `[patch]` of a cargo config outside of the workspace
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceOverride {
+    /// The name of the overridden crate
+    pub name: String,
+    /// The index URL of the registry the dependency declares, [None] for crates.io
+    pub registry: Option<String>,
+    /// Where cargo sources the crate from instead, e.g. `path /ws/vendor/serde`
+    pub source: String,
+}
+
+impl SourceOverride {
+    /// Whether cargo sources the registry `dependency` from elsewhere
+    pub fn applies_to(&self, dependency: &RegistryDependency) -> bool {
+        self.name == dependency.name && self.registry == dependency.registry
+    }
+
+    /// Explains where the crate is sourced from and the `declaration` of the override if it is
+    /// known, e.g. for a tooltip
+    pub fn description(&self, declaration: Option<&DependencyOverride>) -> String {
+        match declaration {
+            Some(declaration) => format!(
+                "Overridden by [{}] in {}:{}, sourced from {}",
+                declaration.section,
+                declaration.file,
+                declaration.line + 1,
+                self.source
+            ),
+            None => format!("Overridden, sourced from {}", self.source),
+        }
+    }
+
+    /// The override with the directory of a path source mapped by `map`
+    pub fn map_paths(mut self, map: impl Fn(&str) -> String) -> Self {
+        if let Some(dir) = self.source.strip_prefix("path ") {
+            self.source = format!("path {}", map(dir));
+        }
+        self
+    }
+}
+
+/// Where the resolved `package` is sourced from
+fn resolved_source(package: &cargo_metadata::Package) -> String {
+    let Some(source) = &package.source else {
+        let dir = package
+            .manifest_path
+            .parent()
+            .unwrap_or(&package.manifest_path);
+        return format!("path {dir}");
+    };
+    match source.repr.strip_prefix("git+") {
+        // The fragment is the locked commit
+        Some(git) => format!("git {}", git.split('#').next().unwrap_or(git)),
+        None => format!("registry {}", source.repr),
+    }
+}
+
+/// The registry dependencies of the members of the resolved `cargo metadata` output
+/// `raw_metadata` whose packages cargo sources from elsewhere than their registry
+pub fn source_overrides(raw_metadata: &str) -> Result<Vec<SourceOverride>, ParseError> {
+    let metadata = extract_raw_metadata(raw_metadata)?;
+    let Some(resolve) = &metadata.resolve else {
+        return Ok(Vec::new());
+    };
+    let overrides = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter_map(|member| {
+            let node = resolve.nodes.iter().find(|node| node.id == member.id)?;
+            let resolved: Vec<_> = node.deps.iter().map(|dep| &metadata[&dep.pkg]).collect();
+            Some((member, resolved))
+        })
+        .flat_map(|(member, resolved)| {
+            member
+                .dependencies
+                .iter()
+                .filter(|d| d.source.as_ref().is_some_and(is_registry_source))
+                .filter_map(move |d| {
+                    let packages: Vec<_> = resolved
+                        .iter()
+                        .filter(|p| p.name.as_str() == d.name)
+                        .collect();
+                    // Dependencies which aren't built, like disabled optional ones, aren't
+                    // resolved
+                    if packages.iter().any(|p| p.source == d.source) {
+                        return None;
+                    }
+                    Some(SourceOverride {
+                        name: d.name.clone(),
+                        registry: d.registry.clone(),
+                        source: resolved_source(packages.first()?),
+                    })
+                })
+        })
+        .sorted()
+        .dedup()
+        .collect();
+    Ok(overrides)
+}
+
+/// The dotted keys of the table header `header`, quoted keys may contain dots
+fn header_keys(header: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut key = String::new();
+    let mut quote = None;
+    for c in header.chars() {
+        match (c, quote) {
+            ('"' | '\'', None) => quote = Some(c),
+            (c, Some(q)) if c == q => quote = None,
+            ('.', None) => keys.push(std::mem::take(&mut key).trim().to_string()),
+            (c, _) => key.push(c),
+        }
+    }
+    keys.push(key.trim().to_string());
+    keys
+}
+
+/// Where the override `value` sources a crate from
+fn source_of(value: &toml::Value) -> String {
+    let Some(table) = value.as_table() else {
+        return value
+            .as_str()
+            .map_or_else(|| "another version".to_string(), |v| format!("version {v}"));
+    };
+    let get = |key: &str| table.get(key).and_then(toml::Value::as_str);
+    if let Some(path) = get("path") {
+        return format!("path {path}");
+    }
+    if let Some(git) = get("git") {
+        let reference = ["branch", "tag", "rev"]
+            .into_iter()
+            .find_map(|key| get(key).map(|v| format!(" ({key} {v})")))
+            .unwrap_or_default();
+        return format!("git {git}{reference}");
+    }
+    let mut source =
+        get("version").map_or_else(|| "another version".to_string(), |v| format!("version {v}"));
+    if let Some(registry) = get("registry") {
+        source.push_str(&format!(" of the registry {registry}"));
+    }
+    source
+}
+
+/// The crate name of the override `key = value`, `[replace]` keys are package ids like
+/// `serde:1.0.0`
+fn name_of(key: &str, value: &toml::Value, replace: bool) -> String {
+    if replace {
+        return key.split([':', '@']).next().unwrap_or(key).to_string();
+    }
+    value
+        .get("package")
+        .and_then(toml::Value::as_str)
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// The `[patch.<source>]` and `[replace]` overrides of the root manifest or cargo config
+/// `content` of `file`, inline like `serde = { path = "../serde" }` and as table like
+/// `[patch.crates-io.serde]`
+pub fn parse_overrides(file: &str, content: &str) -> Vec<DependencyOverride> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut overrides = Vec::new();
+    // The keys of the current `[patch.<source>]` or `[replace]` table
+    let mut table: Option<Vec<String>> = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i].trim();
+        if let Some(header) = line
+            .strip_prefix('[')
+            .and_then(|l| l.split(']').next())
+            .filter(|_| !line.starts_with("[["))
+        {
+            let keys = header_keys(header);
+            table = None;
+            match keys.as_slice() {
+                [patch, _] if patch == "patch" => table = Some(keys),
+                [replace] if replace == "replace" => table = Some(keys),
+                // `[patch.crates-io.serde]` or `[replace."serde:1.0.0"]` with the keys up to the
+                // next table
+                [section, _, key] | [section, key]
+                    if section == "patch" || section == "replace" =>
+                {
+                    let end = (i + 1..lines.len())
+                        .find(|&j| lines[j].trim_start().starts_with('['))
+                        .unwrap_or(lines.len());
+                    let value = lines[i + 1..end]
+                        .join("\n")
+                        .parse::<Table>()
+                        .map(toml::Value::Table)
+                        .unwrap_or(toml::Value::Boolean(false));
+                    let replace = section == "replace";
+                    overrides.push(DependencyOverride {
+                        name: name_of(key, &value, replace),
+                        patched: (!replace).then(|| keys[1].clone()),
+                        source: source_of(&value),
+                        section: keys[..keys.len() - 1].join("."),
+                        file: file.to_string(),
+                        line: i,
+                    });
+                    i = end;
+                    continue;
+                }
+                _ => {}
+            }
+        } else if let Some(keys) = &table
+            && !line.starts_with('#')
+            && let Ok(entry) = line.parse::<Table>()
+            && let Some((key, value)) = entry.iter().next()
+        {
+            let replace = keys[0] == "replace";
+            overrides.push(DependencyOverride {
+                name: name_of(key, value, replace),
+                patched: keys.get(1).cloned(),
+                source: source_of(value),
+                section: keys.join("."),
+                file: file.to_string(),
+                line: i,
+            });
+        }
+        i += 1;
+    }
+    overrides
+}
+
+/// The `paths` of the cargo config `content` with their zero based line, cargo uses the packages
+/// in these directories instead of the dependencies with the same name
+pub fn override_paths(content: &str) -> Vec<(String, usize)> {
+    let Some(paths) = content
+        .parse::<Table>()
+        .ok()
+        .and_then(|c| c.get("paths")?.as_array().cloned())
+    else {
+        return Vec::new();
+    };
+    let line_of = |path: &str| {
+        let quoted = quote(path);
+        content
+            .lines()
+            .position(|l| l.contains(&quoted) || l.contains(&format!("'{path}'")))
+            .unwrap_or_default()
+    };
+    paths
+        .iter()
+        .filter_map(toml::Value::as_str)
+        .map(|path| (path.to_string(), line_of(path)))
+        .collect()
+}
+
+/// The override of the `paths` entry `path` at `line` of the cargo config `file` by the package
+/// of `manifest`, [None] without a package
+pub fn path_override(
+    file: &str,
+    line: usize,
+    path: &str,
+    manifest: &str,
+) -> Option<DependencyOverride> {
+    let manifest = manifest.parse::<Table>().ok()?;
+    let name = manifest.get("package")?.get("name")?.as_str()?;
+    Some(DependencyOverride {
+        name: name.to_string(),
+        patched: None,
+        source: format!("path {path}"),
+        section: "paths".to_string(),
+        file: file.to_string(),
+        line,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn overrides_are_parsed() {
+        let manifest = r#"[workspace]
+members = ["cli"]
+
+[patch.crates-io]
+serde = { path = "../serde" }
+# A fork
+tokio = { git = "https://github.com/me/tokio", branch = "fix" }
+
+[patch."https://github.com/rust-lang/regex"]
+regex = { path = "../regex" }
+
+[patch.crates-io.log]
+path = "vendor/log"
+
+[replace]
+"rand:0.8.5" = { path = "vendor/rand" }
+"#;
+        let overrides = parse_overrides("/ws/Cargo.toml", manifest);
+        let summary: Vec<_> = overrides
+            .iter()
+            .map(|o| {
+                (
+                    o.name.as_str(),
+                    o.patched.as_deref(),
+                    o.source.as_str(),
+                    o.line,
+                )
+            })
+            .collect();
+        check!(
+            summary
+                == vec![
+                    ("serde", Some("crates-io"), "path ../serde", 4),
+                    (
+                        "tokio",
+                        Some("crates-io"),
+                        "git https://github.com/me/tokio (branch fix)",
+                        6
+                    ),
+                    (
+                        "regex",
+                        Some("https://github.com/rust-lang/regex"),
+                        "path ../regex",
+                        9
+                    ),
+                    ("log", Some("crates-io"), "path vendor/log", 11),
+                    ("rand", None, "path vendor/rand", 15),
+                ]
+        );
+        check!(overrides[3].section == "patch.crates-io");
+
+        let serde = RegistryDependency {
+            name: "serde".to_string(),
+            req: "^1".to_string(),
+            kind: None,
+            registry: None,
+        };
+        let registries = Registries::default();
+        check!(overrides[0].applies_to(&serde, &registries));
+        check!(!overrides[1].applies_to(&serde, &registries));
+        let internal = RegistryDependency {
+            registry: Some("sparse+https://my.registry/index/".to_string()),
+            ..serde
+        };
+        check!(!overrides[0].applies_to(&internal, &registries));
+
+        let config = "paths = [\"../serde\"]\n";
+        check!(override_paths(config) == vec![("../serde".to_string(), 0)]);
+        let path = path_override(
+            "/ws/.cargo/config.toml",
+            0,
+            "../serde",
+            "[package]\nname = \"serde\"\n",
+        );
+        check!(path.is_some_and(|o| o.applies_to(&internal, &registries)));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn source_overrides_come_from_the_resolved_packages() {
+        let crates_io = "registry+https://github.com/rust-lang/crates.io-index";
+        let package = |name: &str, id: &str, source: Option<&str>, dependencies: &str| {
+            let source = source.map_or("null".to_string(), |s| format!("\"{s}\""));
+            format!(
+                r#"{{"name":"{name}","version":"1.0.0","id":"{id}","source":{source},"dependencies":[{dependencies}],"targets":[],"features":{{}},"manifest_path":"/ws/vendor/{name}/Cargo.toml"}}"#
+            )
+        };
+        let dependency = |name: &str| {
+            format!(
+                r#"{{"name":"{name}","source":"{crates_io}","req":"^1","kind":null,"optional":false,"uses_default_features":true,"features":[],"target":null,"rename":null,"registry":null}}"#
+            )
+        };
+        let app = "path+file:///ws/app#0.1.0";
+        let serde = "path+file:///ws/vendor/serde#1.0.0";
+        let log = format!("{crates_io}#log@1.0.0");
+        let metadata = format!(
+            r#"{{"packages":[{},{},{}],"workspace_members":["{app}"],"resolve":{{"nodes":[{{"id":"{app}","dependencies":["{serde}","{log}"],"deps":[{{"name":"serde","pkg":"{serde}","dep_kinds":[]}},{{"name":"log","pkg":"{log}","dep_kinds":[]}}],"features":[]}}],"root":null}},"target_directory":"/ws/target","version":1,"workspace_root":"/ws"}}"#,
+            package(
+                "app",
+                app,
+                None,
+                &[dependency("serde"), dependency("log")].join(",")
+            ),
+            package("serde", serde, None, ""),
+            package("log", &log, Some(crates_io), ""),
+        );
+
+        let overrides = source_overrides(&metadata).unwrap();
+        check!(
+            overrides
+                == [SourceOverride {
+                    name: "serde".to_string(),
+                    registry: None,
+                    source: "path /ws/vendor/serde".to_string(),
+                }]
+        );
+        let declaration = parse_overrides(
+            "/ws/Cargo.toml",
+            "[patch.crates-io]\nserde = { path = \"vendor/serde\" }\n",
+        );
+        check!(
+            overrides[0].description(declaration.first())
+                == "Overridden by [patch.crates-io] in /ws/Cargo.toml:2, sourced from path /ws/vendor/serde"
+        );
+        check!(overrides[0].description(None) == "Overridden, sourced from path /ws/vendor/serde");
+    }
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cargo::Config;
+
+/// Marks hooks written by [hook_script] so they can be replaced without asking
+pub const HOOK_MARKER: &str = "# Installed by Cargo Tools";
+
+/// A check of **Run Pre-commit Checks** and the pre-commit hook
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Check {
+    Fmt,
+    Clippy,
+    Test,
+}
+
+impl Check {
+    /// The command line shown for the check
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Fmt => "cargo fmt --check",
+            Self::Clippy => "cargo clippy -- -D warnings",
+            Self::Test => "cargo test",
+        }
+    }
+
+    /// The cargo arguments which check `packages`, the whole workspace if there are none.
+    /// Clippy and tests build with the selection of `config`.
+    pub fn args(&self, packages: &[String], config: &Config) -> Vec<String> {
+        let package_args = || -> Vec<String> {
+            if packages.is_empty() {
+                vec!["--workspace".to_string()]
+            } else {
+                packages
+                    .iter()
+                    .flat_map(|p| ["--package".to_string(), p.clone()])
+                    .collect()
+            }
+        };
+        match self {
+            Self::Fmt => {
+                let mut args = vec!["fmt".to_string()];
+                if packages.is_empty() {
+                    args.push("--all".to_string());
+                } else {
+                    args.extend(package_args());
+                }
+                args.push("--check".to_string());
+                args
+            }
+            Self::Clippy => {
+                let mut args = vec!["clippy".to_string()];
+                args.extend(package_args());
+                args.push("--all-targets".to_string());
+                args.extend(config.args(None));
+                args
+            }
+            Self::Test => {
+                let mut args = vec!["test".to_string()];
+                args.extend(package_args());
+                args.extend(config.args(None));
+                args
+            }
+        }
+    }
+
+    /// The arguments cargo passes on after `--`
+    pub fn program_args(&self) -> Vec<String> {
+        match self {
+            Self::Clippy => vec!["-D".to_string(), "warnings".to_string()],
+            Self::Fmt | Self::Test => Vec::new(),
+        }
+    }
+}
+
+/// The state of a check in the Pre-commit Checks view
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Queued,
+    Running,
+    Passed,
+    Failed,
+}
+
+/// The summary of the finished `results` like `2 of 3 pre-commit checks passed`
+pub fn summary(results: &[(Check, CheckStatus)]) -> String {
+    let passed = results
+        .iter()
+        .filter(|(_, status)| *status == CheckStatus::Passed)
+        .count();
+    if passed == results.len() {
+        format!("All {passed} pre-commit checks passed")
+    } else {
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|(_, status)| *status == CheckStatus::Failed)
+            .map(|(check, _)| check.title())
+            .collect();
+        format!(
+            "{passed} of {} pre-commit checks passed, failed: {}",
+            results.len(),
+            failed.join(", ")
+        )
+    }
+}
+
+/// A git pre-commit hook which runs `checks` for the whole workspace at `workspace_root` with
+/// `cargo` and rejects the commit if one fails
+pub fn hook_script(checks: &[Check], workspace_root: &str, cargo: &str) -> String {
+    let quote = |s: &str| format!("'{}'", s.replace('\'', r"'\''"));
+    let mut script = format!(
+        "#!/bin/sh\n{HOOK_MARKER}, a failing check rejects the commit\nset -e\ncd {}\n",
+        quote(workspace_root)
+    );
+    for check in checks {
+        let mut args = check.args(&[], &Config::default());
+        let program_args = check.program_args();
+        if !program_args.is_empty() {
+            args.push("--".to_string());
+            args.extend(program_args);
+        }
+        script.push_str(&format!("{cargo} {}\n", args.join(" ")));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn checks_summary_and_hook() {
+        let packages = ["cli".to_string(), "core".to_string()];
+        let config = Config::default();
+        check!(
+            Check::Fmt.args(&packages, &config)
+                == ["fmt", "--package", "cli", "--package", "core", "--check"]
+        );
+        check!(
+            Check::Clippy.args(&packages, &config)
+                == [
+                    "clippy",
+                    "--package",
+                    "cli",
+                    "--package",
+                    "core",
+                    "--all-targets"
+                ]
+        );
+        check!(Check::Test.args(&[], &config) == ["test", "--workspace"]);
+
+        let results = [
+            (Check::Fmt, CheckStatus::Passed),
+            (Check::Clippy, CheckStatus::Failed),
+            (Check::Test, CheckStatus::Passed),
+        ];
+        check!(
+            summary(&results)
+                == "2 of 3 pre-commit checks passed, failed: cargo clippy -- -D warnings"
+        );
+        check!(summary(&results[..1]) == "All 1 pre-commit checks passed");
+
+        check!(
+            hook_script(&[Check::Fmt, Check::Clippy], "/home/dev/it's", "cargo")
+                == "#!/bin/sh
+# Installed by Cargo Tools, a failing check rejects the commit
+set -e
+cd '/home/dev/it'\\''s'
+cargo fmt --all --check
+cargo clippy --workspace --all-targets -- -D warnings
+"
+        );
+    }
+}
@@ -73,6 +73,15 @@ impl Profile {
         }
     }
 
+    /// Returns the directory below the target dir which cargo writes the profile's artifacts to.
+    pub fn output_dir(&self) -> &str {
+        match self {
+            Profile::None | Profile::Dev | Profile::Test | Profile::Doc => "debug",
+            Profile::Release | Profile::Bench => "release",
+            Profile::Custom(name) => name.as_str(),
+        }
+    }
+
     pub fn cargo_args(&self) -> Vec<String> {
         match self.get_name() {
             Some(p) => vec!["--profile".to_string(), p.to_string()],
@@ -80,6 +89,20 @@ impl Profile {
         }
     }
 
+    /// The environment variable which enables debug info for profiles which build without it
+    /// by default, e.g. `CARGO_PROFILE_RELEASE_DEBUG=true`
+    pub fn debug_info_env(&self) -> Option<(String, String)> {
+        let name = match self {
+            Profile::None | Profile::Dev | Profile::Test | Profile::Doc => return None,
+            Profile::Release | Profile::Bench | Profile::Custom(_) => self.get_name()?,
+        };
+        let key = format!(
+            "CARGO_PROFILE_{}_DEBUG",
+            name.to_uppercase().replace('-', "_")
+        );
+        Some((key, "true".to_string()))
+    }
+
     pub fn is_standard(&self) -> bool {
         match self {
             Profile::Dev | Profile::Release | Profile::Test | Profile::Bench | Profile::Doc => true,
@@ -117,3 +140,24 @@ impl From<String> for Profile {
         Profile::from(s.as_str())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn debug_info_env_of_optimized_profiles() {
+        let env = |profile: Profile| profile.debug_info_env().map(|(key, _)| key);
+
+        check!(env(Profile::Release).as_deref() == Some("CARGO_PROFILE_RELEASE_DEBUG"));
+        check!(
+            env(Profile::Custom("release-lto".to_string())).as_deref()
+                == Some("CARGO_PROFILE_RELEASE_LTO_DEBUG")
+        );
+        check!(env(Profile::Dev).is_none());
+        check!(env(Profile::None).is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use toml::Table;
+
+use crate::process::split_args;
+
+/// The name cargo uses for crates.io in `--registry` and `[registry] default`
+pub const CRATES_IO: &str = "crates-io";
+
+/// An alternative registry of `[registries]`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Registry {
+    pub name: String,
+    pub index: Option<String>,
+}
+
+/// The alternative registries of the cargo configs and the default registry of `cargo publish`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Registries {
+    pub registries: Vec<Registry>,
+    /// `[registry] default`, crates.io if it isn't set
+    pub default: Option<String>,
+}
+
+/// The index URL without its protocol prefix like `sparse+` and trailing slash
+fn normalize_index(index: &str) -> &str {
+    index
+        .trim_start_matches("sparse+")
+        .trim_start_matches("registry+")
+        .trim_end_matches('/')
+}
+
+impl Registries {
+    /// Parses the `configs` like `$CARGO_HOME/config.toml` and `.cargo/config.toml`, later
+    /// configs override registries of the same name as in cargo
+    pub fn parse<'a>(configs: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut this = Self::default();
+        for config in configs {
+            let Ok(table) = config.parse::<Table>() else {
+                continue;
+            };
+            if let Some(registries) = table.get("registries").and_then(toml::Value::as_table) {
+                for (name, registry) in registries {
+                    let index = registry
+                        .get("index")
+                        .and_then(toml::Value::as_str)
+                        .map(str::to_string);
+                    this.registries.retain(|r| r.name != *name);
+                    this.registries.push(Registry {
+                        name: name.clone(),
+                        index,
+                    });
+                }
+            }
+            if let Some(default) = table
+                .get("registry")
+                .and_then(|registry| registry.get("default"))
+                .and_then(toml::Value::as_str)
+            {
+                this.default = Some(default.to_string());
+            }
+        }
+        this.registries.sort_by(|a, b| a.name.cmp(&b.name));
+        this
+    }
+
+    /// The name of the registry with the index URL `index` as cargo metadata reports it
+    pub fn name_of(&self, index: &str) -> Option<&str> {
+        self.registries
+            .iter()
+            .find(|r| {
+                r.index
+                    .as_deref()
+                    .is_some_and(|i| normalize_index(i) == normalize_index(index))
+            })
+            .map(|r| r.name.as_str())
+    }
+
+    /// The registries to pick from, the default one first
+    pub fn options(&self) -> Vec<String> {
+        let default = self.default.as_deref().unwrap_or(CRATES_IO);
+        let mut options = vec![default.to_string()];
+        options.extend(
+            std::iter::once(CRATES_IO)
+                .chain(self.registries.iter().map(|r| r.name.as_str()))
+                .filter(|name| *name != default)
+                .map(str::to_string),
+        );
+        options
+    }
+}
+
+/// The variable cargo reads the token of `registry` from
+pub fn token_env_var(registry: &str) -> String {
+    if registry == CRATES_IO {
+        return "CARGO_REGISTRY_TOKEN".to_string();
+    }
+    format!(
+        "CARGO_REGISTRIES_{}_TOKEN",
+        registry.to_uppercase().replace('-', "_")
+    )
+}
+
+/// The `--registry` arguments of `registry`, none for the default registry `default`
+fn registry_args(registry: &str, default: Option<&str>) -> Vec<String> {
+    if registry == default.unwrap_or(CRATES_IO) {
+        Vec::new()
+    } else {
+        vec!["--registry".to_string(), registry.to_string()]
+    }
+}
+
+/// The `cargo add` arguments which add `spec` like `serde@1` from `registry` to `package`,
+/// as dev or build dependency for the `kind` `dev` or `build`
+pub fn add_args(
+    package: &str,
+    spec: &str,
+    registry: &str,
+    registries: &Registries,
+    kind: Option<&str>,
+) -> Vec<String> {
+    let mut args = vec!["add", "--package", package]
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    args.extend(split_args(spec));
+    args.extend(registry_args(registry, registries.default.as_deref()));
+    args.extend(kind.map(|kind| format!("--{kind}")));
+    args
+}
+
+/// The `cargo publish` arguments which publish `package` to `registry`
+pub fn publish_args(
+    package: &str,
+    registry: &str,
+    registries: &Registries,
+    dry_run: bool,
+) -> Vec<String> {
+    let mut args = vec![
+        "publish".to_string(),
+        "--package".to_string(),
+        package.to_string(),
+    ];
+    args.extend(registry_args(registry, registries.default.as_deref()));
+    if dry_run {
+        args.push("--dry-run".to_string());
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn registries_of_configs() {
+        let home = r#"
+[registries.company]
+index = "sparse+https://cargo.company.dev/index/"
+
+[registries.my-mirror]
+index = "https://mirror.example.com/git/index"
+"#;
+        let workspace = r#"
+[registry]
+default = "company"
+
+[registries.company]
+index = "sparse+https://cargo.internal.company.dev/index/"
+"#;
+        let registries = Registries::parse([home, workspace]);
+        check!(registries.default.as_deref() == Some("company"));
+        check!(
+            registries.name_of("sparse+https://cargo.internal.company.dev/index")
+                == Some("company")
+        );
+        check!(registries.name_of("https://mirror.example.com/git/index") == Some("my-mirror"));
+        check!(
+            registries
+                .name_of("https://github.com/rust-lang/crates.io-index")
+                .is_none()
+        );
+        check!(registries.options() == ["company", "crates-io", "my-mirror"]);
+
+        check!(token_env_var("my-mirror") == "CARGO_REGISTRIES_MY_MIRROR_TOKEN");
+        check!(token_env_var(CRATES_IO) == "CARGO_REGISTRY_TOKEN");
+
+        check!(
+            add_args(
+                "cli",
+                "serde@1 --features derive",
+                "crates-io",
+                &registries,
+                Some("dev")
+            ) == [
+                "add",
+                "--package",
+                "cli",
+                "serde@1",
+                "--features",
+                "derive",
+                "--registry",
+                "crates-io",
+                "--dev"
+            ]
+        );
+        check!(
+            publish_args("cli", "company", &registries, true)
+                == ["publish", "--package", "cli", "--dry-run"]
+        );
+    }
+}
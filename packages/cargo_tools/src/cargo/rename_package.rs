@@ -0,0 +1,273 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::cargo::{
+    manifest,
+    manifest_edit::{join, quote, string_value},
+    new_package::relative_path,
+};
+
+/// The name the code of other crates uses for the package `name`
+pub fn crate_ident(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// `path` without `.` and with `dir/..` removed
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn replace_range(line: &str, start: usize, end: usize, value: &str) -> String {
+    format!("{}{value}{}", &line[..start], &line[end..])
+}
+
+/// The member manifest `manifest` with `package.name` set to `name`, [None] without one
+pub fn set_package_name(manifest: &str, name: &str) -> Option<String> {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|l| l.trim().replace(' ', "") == "[package]")?;
+    let line = lines[start + 1..]
+        .iter()
+        .take_while(|l| !l.trim_start().starts_with('['))
+        .position(|l| {
+            l.split_once('=')
+                .is_some_and(|(key, _)| key.trim() == "name")
+        })
+        .map(|i| start + 1 + i)?;
+    let (_, value_start, value_end) = string_value(&lines[line], "name")?;
+    lines[line] = replace_range(&lines[line], value_start, value_end, name);
+    Some(join(manifest, lines))
+}
+
+/// The directories of a package renamed by **Rename Package**
+#[derive(Debug, Clone)]
+pub struct Rename<'a> {
+    pub old: &'a str,
+    pub new: &'a str,
+    pub old_dir: &'a Path,
+    /// Equal to `old_dir` if the directory is kept
+    pub new_dir: &'a Path,
+}
+
+/// The manifest `manifest` in `manifest_dir` with its dependencies on the renamed package
+/// renamed and their `path` pointing to the new directory. Dependencies which already rename
+/// the package with `package = "..."` keep their key.
+///
+/// Returns whether the key of a dependency of the package changed, the code of the package uses
+/// the new crate name then. Renaming a key of `[workspace.dependencies]` doesn't count, only the
+/// members which inherit it use the new name.
+pub fn rename_dependencies(manifest: &str, manifest_dir: &Path, rename: &Rename) -> (String, bool) {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    let mut renamed_key = false;
+    for dependency in manifest::dependencies(manifest) {
+        if dependency.name != rename.old {
+            continue;
+        }
+        let (first, last) = dependency.lines;
+        let table = lines[..=first]
+            .iter()
+            .rev()
+            .map(|l| l.trim())
+            .find(|l| l.starts_with('['))
+            .unwrap_or_default();
+        let workspace = table.replace(' ', "").starts_with("[workspace.");
+        for (i, line) in lines.iter_mut().enumerate().take(last + 1).skip(first) {
+            let mut edited = line.clone();
+            // The key of `old = ...` or the last segment of `[dependencies.old]`
+            if i == first {
+                let trimmed = line.trim_start();
+                let indent = &line[..line.len() - trimmed.len()];
+                if let Some(header) = trimmed.strip_prefix('[')
+                    && let Some(close) = header.find(']')
+                    && let Some((table, key)) = header[..close].rsplit_once('.')
+                    && key.trim().trim_matches(['"', '\'']) == rename.old
+                {
+                    edited = format!("[{table}.{}]{}", rename.new, &header[close + 1..]);
+                    renamed_key |= !workspace;
+                } else if let Some((key, value)) = trimmed.split_once('=')
+                    && key.trim().trim_matches(['"', '\'']) == rename.old
+                {
+                    edited = format!("{indent}{} ={value}", rename.new);
+                    renamed_key |= !workspace;
+                }
+            }
+            if let Some((package, start, end)) = string_value(&edited, "package")
+                && package == rename.old
+            {
+                edited = replace_range(&edited, start, end, rename.new);
+            }
+            if let Some((path, start, end)) = string_value(&edited, "path")
+                && normalize(&manifest_dir.join(path)) == normalize(rename.old_dir)
+            {
+                let path = relative_path(&normalize(manifest_dir), &normalize(rename.new_dir))
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                edited = replace_range(&edited, start, end, &path);
+            }
+            *line = edited;
+        }
+    }
+    if renamed_key {
+        rename_feature_references(&mut lines, rename.old, rename.new);
+    }
+    (join(manifest, lines), renamed_key)
+}
+
+/// Renames `old/feature`, `old?/feature` and `dep:old` in the `[features]` table of `lines`
+fn rename_feature_references(lines: &mut [String], old: &str, new: &str) {
+    let mut in_features = false;
+    for line in lines.iter_mut() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && !trimmed.starts_with("[\"") {
+            in_features = trimmed.replace(' ', "") == "[features]";
+            continue;
+        }
+        if !in_features {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value
+            .replace(&format!("\"{old}/"), &format!("\"{new}/"))
+            .replace(&format!("\"{old}?/"), &format!("\"{new}?/"))
+            .replace(&format!("\"dep:{old}\""), &format!("\"dep:{new}\""));
+        *line = format!("{key}={value}");
+    }
+}
+
+/// The `name` of the `[lib]` target of `manifest`, the code of other crates uses it instead of
+/// the package name then
+pub fn lib_name(manifest: &str) -> Option<String> {
+    let manifest = manifest.parse::<toml::Table>().ok()?;
+    Some(manifest.get("lib")?.get("name")?.as_str()?.to_string())
+}
+
+/// The root manifest `root` with the member `old` of `workspace.members` replaced by `new`,
+/// both relative to the workspace root. Globs like `crates/*` are kept.
+pub fn rename_member(root: &str, old: &str, new: &str) -> String {
+    let old = quote(old.trim_end_matches('/'));
+    let lines: Vec<String> = root
+        .lines()
+        .map(|line| line.replace(&old, &quote(new)))
+        .collect();
+    join(root, lines)
+}
+
+/// `source` with the paths of the crate `old` like `use old::Parser` or `old::parse()` using the
+/// crate `new`, [None] if it doesn't mention `old`. Paths like `crate::old` or `self::old` of
+/// modules with the same name are kept.
+pub fn rename_crate_in_source(source: &str, old: &str, new: &str) -> Option<String> {
+    let (old, new) = (crate_ident(old), crate_ident(new));
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut edited = String::with_capacity(source.len());
+    let mut rest = source;
+    let mut changed = false;
+    while let Some(i) = rest.find(&old) {
+        let before = &source[..source.len() - rest.len() + i];
+        let after = &rest[i + old.len()..];
+        let word_start = !before.chars().next_back().is_some_and(is_ident_char);
+        let word_end = !after.chars().next().is_some_and(is_ident_char);
+        let trimmed = before.trim_end();
+        let path = after.starts_with("::") && !trimmed.ends_with("::");
+        let item = trimmed.ends_with("use") || trimmed.ends_with("extern crate");
+        edited.push_str(&rest[..i]);
+        if word_start && word_end && (path || item) {
+            edited.push_str(&new);
+            changed = true;
+        } else {
+            edited.push_str(&old);
+        }
+        rest = after;
+    }
+    edited.push_str(rest);
+    changed.then_some(edited)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn packages_are_renamed() {
+        check!(
+            set_package_name(
+                "[package]\nname = \"my-parser\"\nversion = \"0.1.0\"\n",
+                "syntax"
+            ) == Some("[package]\nname = \"syntax\"\nversion = \"0.1.0\"\n".to_string())
+        );
+        check!(set_package_name("[workspace]\n", "syntax").is_none());
+        check!(lib_name("[package]\nname = \"my-parser\"\n").is_none());
+        check!(
+            lib_name("[package]\nname = \"my-parser\"\n\n[lib]\nname = \"parser\"\n").as_deref()
+                == Some("parser")
+        );
+
+        let rename = Rename {
+            old: "my-parser",
+            new: "syntax",
+            old_dir: Path::new("/ws/crates/my-parser"),
+            new_dir: Path::new("/ws/crates/syntax"),
+        };
+        let cli = r#"[package]
+name = "cli"
+
+[dependencies]
+my-parser = { path = "../my-parser", version = "0.1" }
+parse = { package = "my-parser", path = "../my-parser" }
+
+[dev-dependencies.my-parser]
+path = "../my-parser"
+
+[features]
+fast = ["my-parser/fast", "dep:my-parser"]
+"#;
+        let (edited, renamed_key) = rename_dependencies(cli, Path::new("/ws/crates/cli"), &rename);
+        check!(renamed_key);
+        check!(
+            edited
+                == r#"[package]
+name = "cli"
+
+[dependencies]
+syntax = { path = "../syntax", version = "0.1" }
+parse = { package = "syntax", path = "../syntax" }
+
+[dev-dependencies.syntax]
+path = "../syntax"
+
+[features]
+fast = ["syntax/fast", "dep:syntax"]
+"#
+        );
+        let root = "[workspace]\nmembers = [\"crates/my-parser\", \"crates/*\"]\n\n[workspace.dependencies]\nmy-parser = { path = \"crates/my-parser\" }\n";
+        let (root, renamed_key) = rename_dependencies(root, Path::new("/ws"), &rename);
+        check!(!renamed_key);
+        check!(
+            rename_member(&root, "crates/my-parser", "crates/syntax")
+                == "[workspace]\nmembers = [\"crates/syntax\", \"crates/*\"]\n\n[workspace.dependencies]\nsyntax = { path = \"crates/syntax\" }\n"
+        );
+
+        let source = "use my_parser::{Parser, parse};\nmod my_parser_ext;\nfn main() {\n    my_parser::run(crate::my_parser::X);\n}\n";
+        check!(
+            rename_crate_in_source(source, "my-parser", "syntax").as_deref()
+                == Some(
+                    "use syntax::{Parser, parse};\nmod my_parser_ext;\nfn main() {\n    syntax::run(crate::my_parser::X);\n}\n"
+                )
+        );
+        check!(rename_crate_in_source("fn main() {}\n", "my-parser", "syntax").is_none());
+    }
+}
@@ -0,0 +1,230 @@
+use cargo_metadata::PackageId;
+use itertools::Itertools;
+use serde::Serialize;
+
+use crate::cargo::metadata::{ParseError, extract_raw_metadata};
+
+/// The arguments of `cargo tree` printing the paths from the packages `specs` like
+/// `syn@2.0.87` to the packages depending on them as children, one tree per spec. Only the paths
+/// into `package` of the workspace of `manifest` are printed if given, with `features` the
+/// features are nodes too.
+pub fn tree_args(
+    manifest: &str,
+    package: Option<&str>,
+    specs: &[String],
+    features: bool,
+) -> Vec<String> {
+    let mut args = vec!["tree", "--manifest-path", manifest];
+    if let Some(package) = package {
+        args.extend(["--package", package]);
+    }
+    if features {
+        args.extend(["--edges", "features"]);
+    }
+    for spec in specs {
+        args.extend(["--invert", spec]);
+    }
+    args.extend(["--prefix", "depth", "--no-dedupe", "--color", "never"]);
+    args.into_iter().map(str::to_string).collect()
+}
+
+/// The spec of a package `cargo tree --invert` accepts even if several versions of it are
+/// resolved, e.g. `syn@2.0.87`
+fn spec(package: &cargo_metadata::Package) -> String {
+    format!("{}@{}", package.name, package.version)
+}
+
+/// The specs of all packages the workspace of `raw_metadata` resolved which are no workspace
+/// members, sorted with each version of a crate on its own
+pub fn dependency_specs(raw_metadata: &str) -> Result<Vec<String>, ParseError> {
+    let metadata = extract_raw_metadata(raw_metadata)?;
+    Ok(metadata
+        .packages
+        .iter()
+        .filter(|p| !metadata.workspace_members.contains(&p.id))
+        .sorted_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)))
+        .map(spec)
+        .dedup()
+        .collect())
+}
+
+/// The specs of the crates named `name` the workspace member `package` of `raw_metadata`
+/// depends on directly, several if it depends on more than one version
+pub fn direct_dependency_specs(
+    raw_metadata: &str,
+    package: &str,
+    name: &str,
+) -> Result<Vec<String>, ParseError> {
+    let metadata = extract_raw_metadata(raw_metadata)?;
+    let member = metadata
+        .workspace_packages()
+        .into_iter()
+        .find(|p| p.name.as_str() == package)
+        .map(|p| p.id.clone());
+    let dependencies: Vec<&PackageId> = metadata
+        .resolve
+        .iter()
+        .flat_map(|resolve| &resolve.nodes)
+        .filter(|node| Some(&node.id) == member.as_ref())
+        .flat_map(|node| node.deps.iter().map(|dep| &dep.pkg))
+        .collect();
+    Ok(metadata
+        .packages
+        .iter()
+        .filter(|p| p.name.as_str() == name && dependencies.contains(&&p.id))
+        .map(spec)
+        .sorted()
+        .collect())
+}
+
+/// A package of the inverted tree with the packages depending on it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Dependent {
+    /// The package and its version, e.g. `hyper v1.4.1`
+    pub label: String,
+    /// The directory of packages which are no registry dependencies, e.g. workspace members
+    pub path: Option<String>,
+    pub dependents: Vec<Dependent>,
+}
+
+impl Dependent {
+    fn parse(text: &str) -> Self {
+        // Path packages are annotated like `core v0.1.0 (/ws/core)`, features and proc-macros
+        // like `(proc-macro)` are no paths
+        let (label, annotation) = match text.split_once(" (") {
+            Some((label, annotation)) => (label, annotation.trim_end_matches(')')),
+            None => (text, ""),
+        };
+        let is_path = annotation.starts_with(['/', '\\']) || annotation.get(1..3) == Some(":\\");
+        Self {
+            label: label.to_string(),
+            path: is_path.then(|| annotation.to_string()),
+            dependents: Vec::new(),
+        }
+    }
+}
+
+/// The trees of the output of `cargo tree` with [tree_args], one for each spec
+pub fn parse_tree(output: &str) -> Vec<Dependent> {
+    let mut roots: Vec<Dependent> = Vec::new();
+    // The last node of each depth on the current path, attached to its parent once a node of
+    // the same or a lower depth follows
+    let mut path: Vec<Dependent> = Vec::new();
+    for line in output.lines() {
+        let digits = line.chars().take_while(char::is_ascii_digit).count();
+        let Ok(depth) = line[..digits].parse::<usize>() else {
+            continue;
+        };
+        if depth > path.len() {
+            continue;
+        }
+        fold_path(&mut path, depth, &mut roots);
+        path.push(Dependent::parse(line[digits..].trim()));
+    }
+    fold_path(&mut path, 0, &mut roots);
+    roots
+}
+
+/// Attaches the nodes of `path` deeper than `depth` to their parents
+fn fold_path(path: &mut Vec<Dependent>, depth: usize, roots: &mut Vec<Dependent>) {
+    while path.len() > depth {
+        let Some(node) = path.pop() else {
+            break;
+        };
+        match path.last_mut() {
+            Some(parent) => parent.dependents.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn node(label: &str, path: Option<&str>, dependents: Vec<Dependent>) -> Dependent {
+        Dependent {
+            label: label.to_string(),
+            path: path.map(str::to_string),
+            dependents,
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn reverse_paths_are_parsed() {
+        let specs = ["syn@1.0.109", "syn@2.0.87"].map(str::to_string);
+        check!(
+            tree_args("/ws/Cargo.toml", Some("app"), &specs, false)
+                == [
+                    "tree",
+                    "--manifest-path",
+                    "/ws/Cargo.toml",
+                    "--package",
+                    "app",
+                    "--invert",
+                    "syn@1.0.109",
+                    "--invert",
+                    "syn@2.0.87",
+                    "--prefix",
+                    "depth",
+                    "--no-dedupe",
+                    "--color",
+                    "never",
+                ]
+        );
+
+        // One tree per spec of the arguments above
+        let output = r#"0syn v1.0.109
+1serde_derive v1.0.228 (proc-macro)
+2serde v1.0.228
+3app v0.1.0 (/ws/app)
+3core v0.1.0 (/ws/core)
+4app v0.1.0 (/ws/app)
+0syn v2.0.87
+1app v0.1.0 (/ws/app)
+"#;
+        let app = || node("app v0.1.0", Some("/ws/app"), Vec::new());
+        check!(
+            parse_tree(output)
+                == vec![
+                    node(
+                        "syn v1.0.109",
+                        None,
+                        vec![node(
+                            "serde_derive v1.0.228",
+                            None,
+                            vec![node(
+                                "serde v1.0.228",
+                                None,
+                                vec![app(), node("core v0.1.0", Some("/ws/core"), vec![app()])]
+                            )]
+                        )]
+                    ),
+                    node("syn v2.0.87", None, vec![app()]),
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn versions_of_dependencies_are_specs() {
+        let package = |name: &str, version: &str, id: &str| {
+            format!(
+                r#"{{"name":"{name}","version":"{version}","id":"{id}","source":null,"dependencies":[],"targets":[],"features":{{}},"manifest_path":"/ws/{name}/Cargo.toml"}}"#
+            )
+        };
+        let app = "path+file:///ws/app#0.1.0";
+        let syn1 = "registry+https://github.com/rust-lang/crates.io-index#syn@1.0.109";
+        let syn2 = "registry+https://github.com/rust-lang/crates.io-index#syn@2.0.87";
+        let metadata = format!(
+            r#"{{"packages":[{},{},{}],"workspace_members":["{app}"],"resolve":{{"nodes":[{{"id":"{app}","dependencies":["{syn2}"],"deps":[{{"name":"syn","pkg":"{syn2}","dep_kinds":[]}}],"features":[]}}],"root":null}},"target_directory":"/ws/target","version":1,"workspace_root":"/ws"}}"#,
+            package("app", "0.1.0", app),
+            package("syn", "2.0.87", syn2),
+            package("syn", "1.0.109", syn1),
+        );
+        check!(dependency_specs(&metadata).unwrap() == ["syn@1.0.109", "syn@2.0.87"]);
+        check!(direct_dependency_specs(&metadata, "app", "syn").unwrap() == ["syn@2.0.87"]);
+    }
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+/// The variable which sets cargo's `target.<triple>.runner` for `triple`, e.g.
+/// `CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER`
+pub fn runner_env_key(triple: &str) -> String {
+    let triple = triple.to_uppercase().replace(['-', '.'], "_");
+    format!("CARGO_TARGET_{triple}_RUNNER")
+}
+
+/// The variables which make `cargo run` and `cargo test` execute the binaries of each target
+/// triple of `runners` with its runner command, e.g. `qemu-aarch64 -L /usr/aarch64-linux-gnu`
+pub fn runner_env(runners: HashMap<String, String>) -> impl Iterator<Item = (String, String)> {
+    runners
+        .into_iter()
+        .filter(|(_, runner)| !runner.trim().is_empty())
+        .map(|(triple, runner)| (runner_env_key(&triple), runner))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn runner_variables_of_triples() {
+        check!(
+            runner_env_key("aarch64-unknown-linux-gnu")
+                == "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_RUNNER"
+        );
+        check!(
+            runner_env_key("thumbv8m.main-none-eabihf")
+                == "CARGO_TARGET_THUMBV8M_MAIN_NONE_EABIHF_RUNNER"
+        );
+
+        let runners = HashMap::from([
+            (
+                "riscv64gc-unknown-linux-gnu".to_string(),
+                "qemu-riscv64 -L /usr/riscv64-linux-gnu".to_string(),
+            ),
+            ("wasm32-wasip1".to_string(), " ".to_string()),
+        ]);
+        let env: Vec<_> = runner_env(runners).collect();
+        check!(
+            env == [(
+                "CARGO_TARGET_RISCV64GC_UNKNOWN_LINUX_GNU_RUNNER".to_string(),
+                "qemu-riscv64 -L /usr/riscv64-linux-gnu".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,77 @@
+/// The sanitizers of the nightly `-Zsanitizer` flag that programs can be run and debugged with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sanitizer {
+    Address,
+    Thread,
+    Leak,
+}
+
+impl Sanitizer {
+    pub const fn all() -> [Self; 3] {
+        [Self::Address, Self::Thread, Self::Leak]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Address => "address",
+            Self::Thread => "thread",
+            Self::Leak => "leak",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|s| s.name() == name)
+    }
+
+    /// The rustc flag which instruments the build with this sanitizer
+    pub fn rustflag(&self) -> String {
+        format!("-Zsanitizer={}", self.name())
+    }
+
+    /// The runtime options of the sanitizer. When `debugging`, reports abort the program so
+    /// the debugger stops at the faulty access instead of seeing the program exit.
+    pub fn options_env(&self, debugging: bool) -> Option<(String, String)> {
+        let (key, options) = match (self, debugging) {
+            (Self::Address, false) => ("ASAN_OPTIONS", "detect_leaks=1"),
+            (Self::Address, true) => ("ASAN_OPTIONS", "detect_leaks=1:abort_on_error=1"),
+            (Self::Thread, false) => return None,
+            (Self::Thread, true) => ("TSAN_OPTIONS", "halt_on_error=1:abort_on_error=1"),
+            // Leaks are only reported when the program exits
+            (Self::Leak, _) => return None,
+        };
+        Some((key.to_string(), options.to_string()))
+    }
+}
+
+/// The host target triple from the output of `rustc -vV`
+pub fn host_triple(rustc_version: &str) -> Option<String> {
+    rustc_version
+        .lines()
+        .find_map(|line| line.strip_prefix("host:"))
+        .map(|host| host.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn sanitizer_flags_and_options() {
+        check!(Sanitizer::from_name("thread") == Some(Sanitizer::Thread));
+        check!(Sanitizer::Address.rustflag() == "-Zsanitizer=address");
+        check!(
+            Sanitizer::Address.options_env(true)
+                == Some((
+                    "ASAN_OPTIONS".to_string(),
+                    "detect_leaks=1:abort_on_error=1".to_string()
+                ))
+        );
+        check!(Sanitizer::Leak.options_env(true).is_none());
+
+        let version = "rustc 1.92.0-nightly (abc 2026-09-01)\nbinary: rustc\nhost: x86_64-unknown-linux-gnu\nrelease: 1.92.0-nightly\n";
+        check!(host_triple(version).as_deref() == Some("x86_64-unknown-linux-gnu"));
+    }
+}
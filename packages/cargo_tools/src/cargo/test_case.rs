@@ -0,0 +1,285 @@
+use cargo_metadata::TargetKind;
+
+use crate::cargo::{
+    command::compiler_artifacts,
+    metadata::{Package, TargetType},
+};
+
+/// The target a test function is compiled into
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TestTarget {
+    Lib,
+    Bin(String),
+    /// An integration test in the `tests` directory
+    Test(String),
+}
+
+impl TestTarget {
+    /// The `cargo test` arguments which select this target
+    pub fn cargo_args(&self) -> Vec<String> {
+        match self {
+            Self::Lib => vec!["--lib".to_string()],
+            Self::Bin(bin) => vec!["--bin".to_string(), bin.clone()],
+            Self::Test(test) => vec!["--test".to_string(), test.clone()],
+        }
+    }
+
+    fn matches(&self, target: &cargo_metadata::Target) -> bool {
+        match self {
+            Self::Lib => TargetType::from_target(target.clone()) == Some(TargetType::Lib),
+            Self::Bin(bin) => target.kind.contains(&TargetKind::Bin) && target.name == *bin,
+            Self::Test(test) => target.kind.contains(&TargetKind::Test) && target.name == *test,
+        }
+    }
+
+    /// Returns the test executable of this target from the JSON messages of a
+    /// `cargo test --no-run --message-format=json` invocation.
+    pub fn executable_from_messages(&self, messages: &str) -> Option<String> {
+        compiler_artifacts(messages)
+            .filter(|artifact| artifact.profile.test && self.matches(&artifact.target))
+            .find_map(|artifact| artifact.executable)
+            .map(|path| path.to_string())
+    }
+}
+
+/// A single test function
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub package: String,
+    /// Directory of the package's manifest which cargo runs the tests in
+    pub package_dir: String,
+    pub target: TestTarget,
+    /// The full path of the test as libtest reports it, e.g. `cargo::command::tests::parse`
+    pub name: String,
+}
+
+impl TestCase {
+    /// Resolves the test `name` of `file`, which is relative to the module of the file.
+    pub fn locate(packages: &[Package], file: &str, name: &str) -> Option<Self> {
+        let file = file.replace('\\', "/");
+        let (package, package_dir) = package_of(packages, &file)?;
+
+        let relative = &file[package_dir.len()..];
+        let (target, module) = match relative.strip_prefix("tests/") {
+            Some(test) => {
+                let (test, module) = match test.split_once('/') {
+                    Some((dir, "main.rs")) => (dir, Vec::new()),
+                    Some((dir, module)) => (dir, module_path(module)),
+                    None => (test.trim_end_matches(".rs"), Vec::new()),
+                };
+                (TestTarget::Test(test.to_string()), module)
+            }
+            None => target_of(package, &file)?,
+        };
+
+        let name = module
+            .iter()
+            .map(String::as_str)
+            .chain(std::iter::once(name))
+            .collect::<Vec<_>>()
+            .join("::");
+
+        Some(Self {
+            package: package.name.clone(),
+            package_dir: package_dir.trim_end_matches('/').to_string(),
+            target,
+            name,
+        })
+    }
+}
+
+/// The innermost package containing `file` and its directory with a trailing `/`
+pub(crate) fn package_of<'a>(packages: &'a [Package], file: &str) -> Option<(&'a Package, String)> {
+    packages
+        .iter()
+        .filter_map(|p| {
+            let dir = p.manifest.replace('\\', "/");
+            let dir = dir.strip_suffix("Cargo.toml")?;
+            file.starts_with(dir).then(|| (p, dir.to_string()))
+        })
+        .max_by_key(|(_, dir)| dir.len())
+}
+
+/// Finds the lib or bin target `file` belongs to and the module path of `file` in it
+fn target_of(package: &Package, file: &str) -> Option<(TestTarget, Vec<String>)> {
+    let targets = package.targets.iter().filter_map(|t| {
+        let target = match t.target_type {
+            TargetType::Lib => TestTarget::Lib,
+            TargetType::Bin => TestTarget::Bin(t.name.clone()),
+            TargetType::Example | TargetType::Bench => return None,
+        };
+        Some((target, t.source.replace('\\', "/")))
+    });
+
+    let mut best: Option<(TestTarget, Vec<String>, usize)> = None;
+    for (target, source) in targets {
+        if source == file {
+            return Some((target, Vec::new()));
+        }
+        let Some((root_dir, _)) = source.rsplit_once('/') else {
+            continue;
+        };
+        let Some(module) = file
+            .strip_prefix(root_dir)
+            .and_then(|m| m.strip_prefix('/'))
+        else {
+            continue;
+        };
+        // Prefer the innermost root and the lib when a bin shares its directory
+        let is_better = best.as_ref().is_none_or(|(best_target, _, len)| {
+            root_dir.len() > *len || (root_dir.len() == *len && *best_target != TestTarget::Lib)
+        });
+        if is_better {
+            best = Some((target, module_path(module), root_dir.len()));
+        }
+    }
+
+    best.map(|(target, module, _)| (target, module))
+}
+
+/// Module path of a file relative to the directory of its crate root
+fn module_path(relative: &str) -> Vec<String> {
+    let mut module: Vec<String> = relative
+        .trim_end_matches(".rs")
+        .split('/')
+        .map(ToString::to_string)
+        .collect();
+    if module.last().is_some_and(|m| m == "mod") {
+        module.pop();
+    }
+    module
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::metadata::Target;
+
+    fn packages() -> Vec<Package> {
+        vec![
+            Package::fixture(
+                "core",
+                "/ws/core/Cargo.toml",
+                vec![
+                    Target::fixture("core", "/ws/core/src/lib.rs", TargetType::Lib),
+                    Target::fixture("core", "/ws/core/src/main.rs", TargetType::Bin),
+                    Target::fixture("tool", "/ws/core/src/bin/tool.rs", TargetType::Bin),
+                ],
+            ),
+            Package::fixture(
+                "cli",
+                "/ws/core/cli/Cargo.toml",
+                vec![Target::fixture(
+                    "cli",
+                    "/ws/core/cli/src/main.rs",
+                    TargetType::Bin,
+                )],
+            ),
+        ]
+    }
+
+    fn locate(file: &str, name: &str) -> Option<(String, TestTarget, String)> {
+        TestCase::locate(&packages(), file, name).map(|t| (t.package, t.target, t.name))
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn locate_unit_tests() {
+        check!(
+            locate("/ws/core/src/lib.rs", "tests::a")
+                == Some(("core".to_string(), TestTarget::Lib, "tests::a".to_string()))
+        );
+        check!(
+            locate("/ws/core/src/cargo/mod.rs", "tests::a")
+                == Some((
+                    "core".to_string(),
+                    TestTarget::Lib,
+                    "cargo::tests::a".to_string()
+                ))
+        );
+        check!(
+            locate("/ws/core/src/main.rs", "a")
+                == Some((
+                    "core".to_string(),
+                    TestTarget::Bin("core".to_string()),
+                    "a".to_string()
+                ))
+        );
+        check!(
+            locate("/ws/core/src/bin/tool.rs", "a")
+                == Some((
+                    "core".to_string(),
+                    TestTarget::Bin("tool".to_string()),
+                    "a".to_string()
+                ))
+        );
+        check!(
+            locate("/ws/core/cli/src/args.rs", "a")
+                == Some((
+                    "cli".to_string(),
+                    TestTarget::Bin("cli".to_string()),
+                    "args::a".to_string()
+                ))
+        );
+        check!(locate("/other/src/lib.rs", "a").is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn locate_integration_tests() {
+        check!(
+            locate("/ws/core/tests/api.rs", "a")
+                == Some((
+                    "core".to_string(),
+                    TestTarget::Test("api".to_string()),
+                    "a".to_string()
+                ))
+        );
+        check!(
+            locate("/ws/core/tests/suite/main.rs", "a")
+                == Some((
+                    "core".to_string(),
+                    TestTarget::Test("suite".to_string()),
+                    "a".to_string()
+                ))
+        );
+        check!(
+            locate("/ws/core/tests/suite/db.rs", "a")
+                == Some((
+                    "core".to_string(),
+                    TestTarget::Test("suite".to_string()),
+                    "db::a".to_string()
+                ))
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_executable_from_build_messages() {
+        let artifact = |kind: &str, test: bool, executable: &str| {
+            format!(
+                r#"{{"reason":"compiler-artifact","package_id":"path+file:///ws/core#0.1.0","target":{{"name":"api","kind":["{kind}"],"src_path":"/ws/core/src/lib.rs"}},"profile":{{"opt_level":"0","debug_assertions":true,"overflow_checks":true,"test":{test}}},"features":[],"filenames":[],"executable":"{executable}","fresh":false}}"#
+            )
+        };
+        let messages = [
+            artifact("lib", false, "/ws/target/debug/libapi.rlib"),
+            artifact("lib", true, "/ws/target/debug/deps/api-1234"),
+            artifact("test", true, "/ws/target/debug/deps/api-5678"),
+        ]
+        .join("\n");
+
+        check!(
+            TestTarget::Lib.executable_from_messages(&messages)
+                == Some("/ws/target/debug/deps/api-1234".to_string())
+        );
+        check!(
+            TestTarget::Test("api".to_string()).executable_from_messages(&messages)
+                == Some("/ws/target/debug/deps/api-5678".to_string())
+        );
+        check!(
+            TestTarget::Bin("api".to_string())
+                .executable_from_messages(&messages)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+/// Runs kept per test
+pub const HISTORY_LEN: usize = 20;
+
+/// Tests kept, the ones which ran longest ago are dropped, e.g. renamed or removed tests
+pub const MAX_TESTS: usize = 1000;
+
+/// A duration counts as regressed when it is this many times the median of the earlier runs
+const REGRESSION_FACTOR: f64 = 2.0;
+
+/// Earlier runs with a duration needed before a regression is flagged
+const MIN_REGRESSION_SAMPLES: usize = 3;
+
+/// Shorter runs aren't flagged, their durations vary too much
+const MIN_REGRESSION_DURATION: Duration = Duration::from_millis(50);
+
+/// The outcome of one run of a test
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestRecord {
+    pub passed: bool,
+    /// Only known when libtest reports test times
+    pub duration_ms: Option<u64>,
+}
+
+/// How a test performed in its recorded runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestStats {
+    pub id: String,
+    pub runs: usize,
+    pub failures: usize,
+    pub mean: Duration,
+    pub last: Duration,
+    /// The last run was much slower than the earlier ones
+    pub regressed: bool,
+}
+
+impl TestStats {
+    /// The path of the test in its file, test ids are the file path followed by it
+    pub fn name(&self) -> &str {
+        self.id
+            .split_once(".rs::")
+            .map_or(self.id.as_str(), |(_, name)| name)
+    }
+
+    /// The durations and failures, e.g. `0.12s mean, 0.30s last, 1 of 5 recent runs failed`
+    pub fn summary(&self) -> String {
+        format!(
+            "{:.2}s mean, {:.2}s last, {} of {} recent runs failed",
+            self.mean.as_secs_f64(),
+            self.last.as_secs_f64(),
+            self.failures,
+            self.runs
+        )
+    }
+}
+
+/// The recent runs of the tests by test item id, persisted across sessions
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TestHistory {
+    tests: BTreeMap<String, TestRuns>,
+    /// Counts the recorded runs to tell which tests ran longest ago
+    recorded: u64,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct TestRuns {
+    /// [TestHistory::recorded] at the last run of the test
+    last_recorded: u64,
+    records: Vec<TestRecord>,
+}
+
+impl TestHistory {
+    /// Records a run of test `id`, only the last [HISTORY_LEN] runs of the last [MAX_TESTS]
+    /// tests are kept
+    pub fn record(&mut self, id: String, record: TestRecord) {
+        self.recorded += 1;
+        let runs = self.tests.entry(id).or_default();
+        runs.last_recorded = self.recorded;
+        runs.records.push(record);
+        if runs.records.len() > HISTORY_LEN {
+            runs.records.drain(..runs.records.len() - HISTORY_LEN);
+        }
+        if self.tests.len() > MAX_TESTS
+            && let Some(oldest) = self
+                .tests
+                .iter()
+                .min_by_key(|(_, runs)| runs.last_recorded)
+                .map(|(id, _)| id.clone())
+        {
+            self.tests.remove(&oldest);
+        }
+    }
+
+    /// The stats of test `id`, if any of its runs has a duration
+    pub fn stats(&self, id: &str) -> Option<TestStats> {
+        let records = &self.tests.get(id)?.records;
+        let durations: Vec<_> = records
+            .iter()
+            .filter_map(|r| r.duration_ms)
+            .map(Duration::from_millis)
+            .collect();
+        let (&last, earlier) = durations.split_last()?;
+        let mean = durations.iter().sum::<Duration>() / durations.len() as u32;
+
+        Some(TestStats {
+            id: id.to_string(),
+            runs: records.len(),
+            failures: records.iter().filter(|r| !r.passed).count(),
+            mean,
+            last,
+            regressed: is_regression(last, earlier),
+        })
+    }
+
+    /// The stats of the tests with durations, slowest first
+    pub fn slowest(&self) -> Vec<TestStats> {
+        let mut stats: Vec<_> = self.tests.keys().filter_map(|id| self.stats(id)).collect();
+        stats.sort_by(|a, b| b.mean.cmp(&a.mean).then_with(|| a.id.cmp(&b.id)));
+        stats
+    }
+}
+
+fn is_regression(last: Duration, earlier: &[Duration]) -> bool {
+    if earlier.len() < MIN_REGRESSION_SAMPLES || last < MIN_REGRESSION_DURATION {
+        return false;
+    }
+    let mut sorted = earlier.to_vec();
+    sorted.sort();
+    let median = sorted[sorted.len() / 2];
+    last.as_secs_f64() > median.as_secs_f64() * REGRESSION_FACTOR
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn run(passed: bool, duration_ms: u64) -> TestRecord {
+        TestRecord {
+            passed,
+            duration_ms: Some(duration_ms),
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn slowest_tests_with_regressions() {
+        let mut history = TestHistory::default();
+        for duration in [100, 110, 90, 300] {
+            history.record("slow".to_string(), run(true, duration));
+        }
+        history.record("fast".to_string(), run(false, 5));
+        history.record(
+            "untimed".to_string(),
+            TestRecord {
+                passed: true,
+                duration_ms: None,
+            },
+        );
+
+        let slowest = history.slowest();
+        check!(slowest.len() == 2);
+        check!(slowest[0].id == "slow");
+        check!(slowest[0].mean == Duration::from_millis(150));
+        check!(slowest[0].last == Duration::from_millis(300));
+        check!(slowest[0].regressed);
+        check!(slowest[1].failures == 1);
+        check!(!slowest[1].regressed);
+        check!(slowest[0].summary() == "0.15s mean, 0.30s last, 0 of 4 recent runs failed");
+        let named = TestStats {
+            id: "/ws/core/src/lib.rs::tests::adds".to_string(),
+            ..slowest[0].clone()
+        };
+        check!(named.name() == "tests::adds");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn keep_only_recent_runs() {
+        let mut history = TestHistory::default();
+        for duration in 0..(HISTORY_LEN as u64 + 5) {
+            history.record("test".to_string(), run(true, duration));
+        }
+
+        let stats = history.stats("test").unwrap();
+        check!(stats.runs == HISTORY_LEN);
+        check!(stats.last == Duration::from_millis(HISTORY_LEN as u64 + 4));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn drop_the_tests_which_ran_longest_ago() {
+        let mut history = TestHistory::default();
+        for test in 0..MAX_TESTS {
+            history.record(test.to_string(), run(true, 1));
+        }
+        // Running the first test again keeps it
+        history.record("0".to_string(), run(true, 1));
+        history.record("new".to_string(), run(true, 1));
+
+        check!(history.slowest().len() == MAX_TESTS);
+        check!(history.stats("0").is_some());
+        check!(history.stats("1").is_none());
+        check!(history.stats("new").is_some());
+    }
+}
@@ -0,0 +1,704 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cargo::{
+        DocTest, TestCase, TestTarget,
+        libtest::{TestResult, TestSuite},
+        metadata::{Package, TargetType},
+    },
+    rust_source,
+};
+
+/// A Rust source file of the workspace
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceFile {
+    pub path: String,
+    pub source: String,
+}
+
+/// An item of the test hierarchy package → unit tests, integration tests or doctests → file →
+/// module → test, the typescript test controller
+/// creates its test items from it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TestItem {
+    pub id: String,
+    pub label: String,
+    pub file: Option<String>,
+    /// Zero based line of the test
+    pub line: Option<usize>,
+    pub children: Vec<TestItem>,
+    /// The children are only discovered once the item is expanded, see
+    /// [TestTree::add_doc_tests] and [TestTree::lazy_items]
+    pub lazy: bool,
+    /// The test is annotated with `#[ignore]`, so only runs with `--ignored`
+    pub ignored: bool,
+    /// The test asserts an insta snapshot
+    pub snapshot: bool,
+}
+
+/// The tests a test item runs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestSelection {
+    /// All tests of a package
+    Package(String),
+    /// All tests of some targets of a package, e.g. its unit or its integration tests
+    Targets {
+        package: String,
+        targets: Vec<TestTarget>,
+    },
+    /// Tests of the same target, e.g. those of a file or an inline module
+    Tests {
+        package: String,
+        package_dir: String,
+        target: TestTarget,
+        /// Module path of the tests in their target, empty for the crate root or tests of
+        /// several modules
+        module: String,
+        /// Full names of the tests
+        names: Vec<String>,
+    },
+    Test(TestCase),
+    /// All documentation tests of a package
+    DocTests(String),
+    DocTest {
+        package: String,
+        test: DocTest,
+    },
+}
+
+impl TestSelection {
+    pub fn package(&self) -> &str {
+        match self {
+            Self::Package(package) | Self::DocTests(package) => package,
+            Self::Targets { package, .. }
+            | Self::Tests { package, .. }
+            | Self::DocTest { package, .. } => package,
+            Self::Test(test) => &test.package,
+        }
+    }
+
+    /// A selection which runs the tests of both with one cargo invocation, if there is one
+    fn merged(&self, other: &Self) -> Option<Self> {
+        match (self, other) {
+            (Self::Package(package), selection) | (selection, Self::Package(package))
+                if selection.package() == package =>
+            {
+                return Some(Self::Package(package.clone()));
+            }
+            (
+                Self::Targets { package, targets },
+                Self::Targets {
+                    package: other_package,
+                    targets: other_targets,
+                },
+            ) if package == other_package => {
+                let mut targets = targets.clone();
+                for target in other_targets {
+                    if !targets.contains(target) {
+                        targets.push(target.clone());
+                    }
+                }
+                return Some(Self::Targets {
+                    package: package.clone(),
+                    targets,
+                });
+            }
+            // Tests of one of the targets already run with them
+            (Self::Targets { package, targets }, selection)
+            | (selection, Self::Targets { package, targets }) => {
+                let (other_package, _, target, _) = selection.target_tests()?;
+                return (other_package == *package && targets.contains(&target)).then(|| {
+                    Self::Targets {
+                        package: package.clone(),
+                        targets: targets.clone(),
+                    }
+                });
+            }
+            _ => {}
+        }
+
+        let (package, package_dir, target, mut names) = self.target_tests()?;
+        let (other_package, _, other_target, other_names) = other.target_tests()?;
+        if package != other_package || target != other_target {
+            return None;
+        }
+        for name in other_names {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+        Some(Self::Tests {
+            package,
+            package_dir,
+            target,
+            module: String::new(),
+            names,
+        })
+    }
+
+    fn target_tests(&self) -> Option<(String, String, TestTarget, Vec<String>)> {
+        match self {
+            Self::Tests {
+                package,
+                package_dir,
+                target,
+                names,
+                ..
+            } => Some((
+                package.clone(),
+                package_dir.clone(),
+                target.clone(),
+                names.clone(),
+            )),
+            Self::Test(test) => Some((
+                test.package.clone(),
+                test.package_dir.clone(),
+                test.target.clone(),
+                vec![test.name.clone()],
+            )),
+            Self::Package(_) | Self::Targets { .. } | Self::DocTests(_) | Self::DocTest { .. } => {
+                None
+            }
+        }
+    }
+}
+
+/// The tests of the workspace with their selections by test item id
+#[derive(Debug, Default)]
+pub struct TestTree {
+    items: Vec<TestItem>,
+    selections: HashMap<String, TestSelection>,
+    /// Test item ids by package, target and full test name
+    test_ids: HashMap<(String, TestTarget, String), String>,
+}
+
+impl TestTree {
+    /// Builds the tree of the unit and integration tests found in `files`. Packages with a
+    /// library get a lazy item for their doctests, listing them requires a build.
+    pub fn build(packages: &[Package], files: &[SourceFile]) -> Self {
+        let mut tree = Self::default();
+        let mut files_by_package: BTreeMap<&str, Vec<(TestTarget, TestItem)>> = BTreeMap::new();
+
+        for file in files {
+            let located: Vec<_> = rust_source::find_test_functions(&file.source)
+                .into_iter()
+                .filter_map(|f| Some((TestCase::locate(packages, &file.path, &f.name)?, f)))
+                .collect();
+            let Some((first, function)) = located.first() else {
+                continue;
+            };
+            let file_module = first
+                .name
+                .strip_suffix(function.name.as_str())
+                .unwrap_or_default()
+                .trim_end_matches("::")
+                .to_string();
+            let tests_of = |module: String, names: Vec<String>| TestSelection::Tests {
+                package: first.package.clone(),
+                package_dir: first.package_dir.clone(),
+                target: first.target.clone(),
+                module,
+                names,
+            };
+            let selection = tests_of(
+                file_module.clone(),
+                located.iter().map(|(test, _)| test.name.clone()).collect(),
+            );
+
+            let mut tests = Vec::new();
+            // Full test names by the inline modules of the file enclosing them
+            let mut modules: BTreeMap<String, Vec<String>> = BTreeMap::new();
+            for (test, function) in &located {
+                let id = format!("{}::{}", file.path, function.name);
+                tree.test_ids.insert(
+                    (test.package.clone(), test.target.clone(), test.name.clone()),
+                    id.clone(),
+                );
+
+                let (module, label) = function
+                    .name
+                    .rsplit_once("::")
+                    .unwrap_or(("", &function.name));
+                for module in module_paths(module) {
+                    modules.entry(module).or_default().push(test.name.clone());
+                }
+                insert_test(
+                    &mut tests,
+                    &file.path,
+                    module,
+                    TestItem {
+                        id: id.clone(),
+                        label: label.to_string(),
+                        file: Some(file.path.clone()),
+                        line: Some(function.line),
+                        children: Vec::new(),
+                        lazy: false,
+                        ignored: function.ignored,
+                        snapshot: function.snapshot,
+                    },
+                );
+                tree.selections
+                    .insert(id, TestSelection::Test(test.clone()));
+            }
+            for (module, names) in modules {
+                let full_module = [file_module.as_str(), &module]
+                    .into_iter()
+                    .filter(|m| !m.is_empty())
+                    .collect::<Vec<_>>()
+                    .join("::");
+                tree.selections
+                    .insert(module_id(&file.path, &module), tests_of(full_module, names));
+            }
+
+            let Some(package) = packages.iter().find(|p| p.name == selection.package()) else {
+                continue;
+            };
+            let dir = package.manifest_dir().replace('\\', "/");
+            let path = file.path.replace('\\', "/");
+            let label = path
+                .strip_prefix(&dir)
+                .map_or(path.as_str(), |p| p.trim_start_matches('/'));
+            files_by_package.entry(&package.name).or_default().push((
+                first.target.clone(),
+                TestItem {
+                    id: file.path.clone(),
+                    label: label.to_string(),
+                    file: Some(file.path.clone()),
+                    line: None,
+                    children: tests,
+                    lazy: false,
+                    ignored: false,
+                    snapshot: false,
+                },
+            ));
+            tree.selections.insert(file.path.clone(), selection);
+        }
+
+        for package in packages {
+            // Unit and integration tests are built and filtered differently, so they are
+            // grouped apart
+            let (integration, unit): (Vec<_>, Vec<_>) = files_by_package
+                .remove(package.name.as_str())
+                .unwrap_or_default()
+                .into_iter()
+                .partition(|(target, _)| matches!(target, TestTarget::Test(_)));
+            let mut children = Vec::new();
+            for (group, label, files) in [
+                ("unit-tests", "Unit Tests", unit),
+                ("integration-tests", "Integration Tests", integration),
+            ] {
+                if files.is_empty() {
+                    continue;
+                }
+                let mut targets = Vec::new();
+                let mut items = Vec::new();
+                for (target, item) in files {
+                    if !targets.contains(&target) {
+                        targets.push(target);
+                    }
+                    items.push(item);
+                }
+                items.sort_by(|a, b| a.label.cmp(&b.label));
+
+                let id = format!("{}/{group}", package.name);
+                children.push(TestItem {
+                    id: id.clone(),
+                    label: label.to_string(),
+                    file: None,
+                    line: None,
+                    children: items,
+                    lazy: false,
+                    ignored: false,
+                    snapshot: false,
+                });
+                tree.selections.insert(
+                    id,
+                    TestSelection::Targets {
+                        package: package.name.clone(),
+                        targets,
+                    },
+                );
+            }
+            if package
+                .targets
+                .iter()
+                .any(|t| t.target_type == TargetType::Lib)
+            {
+                let id = doc_tests_id(&package.name);
+                children.push(TestItem {
+                    id: id.clone(),
+                    label: "Doctests".to_string(),
+                    file: None,
+                    line: None,
+                    children: Vec::new(),
+                    lazy: true,
+                    ignored: false,
+                    snapshot: false,
+                });
+                tree.selections
+                    .insert(id, TestSelection::DocTests(package.name.clone()));
+            }
+            if children.is_empty() {
+                continue;
+            }
+
+            tree.items.push(TestItem {
+                id: package.name.clone(),
+                label: package.name.clone(),
+                file: Some(package.manifest.clone()),
+                line: None,
+                children,
+                lazy: false,
+                ignored: false,
+                snapshot: false,
+            });
+            tree.selections.insert(
+                package.name.clone(),
+                TestSelection::Package(package.name.clone()),
+            );
+        }
+
+        tree
+    }
+
+    pub fn items(&self) -> &[TestItem] {
+        &self.items
+    }
+
+    /// The package items without their children, which are the [TestTree::children] of the
+    /// item once it is expanded. Large workspaces have too many tests to create all at once.
+    pub fn lazy_items(&self) -> Vec<TestItem> {
+        self.items
+            .iter()
+            .map(|item| TestItem {
+                id: item.id.clone(),
+                label: item.label.clone(),
+                file: item.file.clone(),
+                line: item.line,
+                children: Vec::new(),
+                lazy: true,
+                ignored: item.ignored,
+                snapshot: item.snapshot,
+            })
+            .collect()
+    }
+
+    /// The children of the package item `id`
+    pub fn children(&self, id: &str) -> Option<&[TestItem]> {
+        self.items
+            .iter()
+            .find(|item| item.id == id)
+            .map(|item| item.children.as_slice())
+    }
+
+    pub fn selection(&self, id: &str) -> Option<&TestSelection> {
+        self.selections.get(id)
+    }
+
+    /// The selections which run the items `ids`. Items of the same package and target are
+    /// run by a single cargo invocation instead of one per item.
+    pub fn run_selections(&self, ids: &[String]) -> Vec<TestSelection> {
+        let mut selections: Vec<TestSelection> = Vec::new();
+        for selection in ids.iter().filter_map(|id| self.selection(id)) {
+            let merged = selections
+                .iter_mut()
+                .find_map(|s| s.merged(selection).map(|merged| (s, merged)));
+            match merged {
+                Some((existing, merged)) => *existing = merged,
+                None => selections.push(selection.clone()),
+            }
+        }
+        selections
+    }
+
+    /// Adds the doctests listed for `package` and returns the children of its doctest item
+    pub fn add_doc_tests(&mut self, package: &Package, tests: Vec<DocTest>) -> Vec<TestItem> {
+        let dir = package.manifest_dir();
+        tests
+            .into_iter()
+            .map(|test| {
+                let id = doc_test_id(&package.name, &test.name());
+                let item = TestItem {
+                    id: id.clone(),
+                    label: test.item.clone(),
+                    file: Some(format!("{dir}/{}", test.file)),
+                    // rustdoc reports the line of the code block one based
+                    line: Some(test.line.saturating_sub(1)),
+                    children: Vec::new(),
+                    lazy: false,
+                    ignored: false,
+                    snapshot: false,
+                };
+                self.selections.insert(
+                    id,
+                    TestSelection::DocTest {
+                        package: package.name.clone(),
+                        test,
+                    },
+                );
+                item
+            })
+            .collect()
+    }
+
+    /// The id of the test item `result` was reported for by a run of `package`
+    pub fn result_id(&self, package: &Package, result: &TestResult) -> Option<String> {
+        if result.suite == TestSuite::Doc {
+            let id = doc_test_id(&package.name, &result.name);
+            return self.selections.contains_key(&id).then_some(id);
+        }
+        let target = result.suite.target(package)?;
+        self.test_ids
+            .get(&(package.name.clone(), target, result.name.clone()))
+            .cloned()
+    }
+}
+
+/// Inserts the test `item` below the items of the inline modules `module` of `file`
+fn insert_test(items: &mut Vec<TestItem>, file: &str, module: &str, item: TestItem) {
+    let mut items = items;
+    for (path, label) in module_paths(module).zip(module.split("::")) {
+        let id = module_id(file, &path);
+        let index = match items.iter().position(|i| i.id == id) {
+            Some(index) => index,
+            None => {
+                items.push(TestItem {
+                    id,
+                    label: label.to_string(),
+                    file: Some(file.to_string()),
+                    line: None,
+                    children: Vec::new(),
+                    lazy: false,
+                    ignored: false,
+                    snapshot: false,
+                });
+                items.len() - 1
+            }
+        };
+        items = &mut items[index].children;
+    }
+    items.push(item);
+}
+
+/// The paths of `module` and its parents, e.g. `a` and `a::b` for `a::b`
+fn module_paths(module: &str) -> impl Iterator<Item = String> + '_ {
+    module
+        .split("::")
+        .filter(|segment| !segment.is_empty())
+        .scan(String::new(), |path, segment| {
+            if !path.is_empty() {
+                path.push_str("::");
+            }
+            path.push_str(segment);
+            Some(path.clone())
+        })
+}
+
+/// Module ids end with `::` so they differ from a test of the same name
+fn module_id(file: &str, module: &str) -> String {
+    format!("{file}::{module}::")
+}
+
+fn doc_tests_id(package: &str) -> String {
+    format!("{package}/doctests")
+}
+
+fn doc_test_id(package: &str, name: &str) -> String {
+    format!("{}/{name}", doc_tests_id(package))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::{
+        libtest::TestOutcome,
+        metadata::{Target, TargetType},
+    };
+
+    fn package() -> Package {
+        Package::fixture(
+            "core",
+            "/ws/core/Cargo.toml",
+            vec![Target::fixture(
+                "core",
+                "/ws/core/src/lib.rs",
+                TargetType::Lib,
+            )],
+        )
+    }
+
+    fn file(path: &str, source: &str) -> SourceFile {
+        SourceFile {
+            path: path.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn build_test_tree() {
+        let packages = vec![package()];
+        let files = vec![
+            file(
+                "/ws/core/src/parser.rs",
+                "#[cfg(test)]\nmod tests {\n    #[test]\n    fn parses() {}\n}\n",
+            ),
+            file("/ws/core/src/lib.rs", "pub mod parser;\n"),
+            file("/ws/core/tests/api.rs", "#[test]\nfn works() {}\n"),
+        ];
+
+        let tree = TestTree::build(&packages, &files);
+
+        let [package_item] = tree.items() else {
+            panic!("expected one package, got {:?}", tree.items());
+        };
+        let labels: Vec<_> = package_item
+            .children
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        check!(labels == ["Unit Tests", "Integration Tests", "Doctests"]);
+        check!(package_item.children[1].children[0].label == "tests/api.rs");
+        let module = &package_item.children[0].children[0].children[0];
+        check!(module.label == "tests");
+        check!(module.children[0].id == "/ws/core/src/parser.rs::tests::parses");
+        check!(module.children[0].label == "parses");
+        check!(module.children[0].line == Some(2));
+        check!(package_item.children[2].lazy);
+
+        let [lazy_item] = tree.lazy_items().try_into().expect("one package");
+        check!(lazy_item.lazy);
+        check!(lazy_item.children.is_empty());
+        check!(tree.children(&lazy_item.id) == Some(package_item.children.as_slice()));
+        check!(tree.children("core/unit-tests") == None);
+
+        check!(
+            tree.selection("/ws/core/src/parser.rs")
+                == Some(&TestSelection::Tests {
+                    package: "core".to_string(),
+                    package_dir: "/ws/core".to_string(),
+                    target: TestTarget::Lib,
+                    module: "parser".to_string(),
+                    names: vec!["parser::tests::parses".to_string()],
+                })
+        );
+        check!(
+            tree.selection("core/integration-tests")
+                == Some(&TestSelection::Targets {
+                    package: "core".to_string(),
+                    targets: vec![TestTarget::Test("api".to_string())],
+                })
+        );
+        check!(
+            tree.selection(&module.id)
+                == Some(&TestSelection::Tests {
+                    package: "core".to_string(),
+                    package_dir: "/ws/core".to_string(),
+                    target: TestTarget::Lib,
+                    module: "parser::tests".to_string(),
+                    names: vec!["parser::tests::parses".to_string()],
+                })
+        );
+
+        let result = TestResult {
+            suite: TestSuite::Target("tests/api.rs".to_string()),
+            name: "works".to_string(),
+            outcome: TestOutcome::Passed,
+            message: None,
+            duration: None,
+        };
+        check!(
+            tree.result_id(&packages[0], &result).as_deref()
+                == Some("/ws/core/tests/api.rs::works")
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn merge_selections_of_same_target() {
+        let packages = vec![package()];
+        let files = vec![
+            file(
+                "/ws/core/src/lib.rs",
+                "#[test]\nfn a() {}\n#[test]\nfn b() {}\n",
+            ),
+            file("/ws/core/tests/api.rs", "#[test]\nfn works() {}\n"),
+        ];
+        let tree = TestTree::build(&packages, &files);
+        let ids = |ids: &[&str]| ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        check!(
+            tree.run_selections(&ids(&[
+                "/ws/core/src/lib.rs::a",
+                "/ws/core/tests/api.rs::works",
+                "/ws/core/src/lib.rs::b",
+            ])) == vec![
+                TestSelection::Tests {
+                    package: "core".to_string(),
+                    package_dir: "/ws/core".to_string(),
+                    target: TestTarget::Lib,
+                    module: String::new(),
+                    names: vec!["a".to_string(), "b".to_string()],
+                },
+                tree.selection("/ws/core/tests/api.rs::works")
+                    .cloned()
+                    .unwrap(),
+            ]
+        );
+        check!(
+            tree.run_selections(&ids(&["/ws/core/src/lib.rs::a", "core"]))
+                == vec![TestSelection::Package("core".to_string())]
+        );
+        check!(
+            tree.run_selections(&ids(&[
+                "/ws/core/src/lib.rs::a",
+                "core/unit-tests",
+                "/ws/core/tests/api.rs::works",
+            ])) == vec![
+                TestSelection::Targets {
+                    package: "core".to_string(),
+                    targets: vec![TestTarget::Lib],
+                },
+                tree.selection("/ws/core/tests/api.rs::works")
+                    .cloned()
+                    .unwrap(),
+            ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn add_doc_tests_to_tree() {
+        let package = package();
+        let mut tree = TestTree::build(std::slice::from_ref(&package), &[]);
+        let test = DocTest {
+            file: "src/lib.rs".to_string(),
+            item: "add".to_string(),
+            line: 5,
+        };
+
+        let items = tree.add_doc_tests(&package, vec![test.clone()]);
+
+        check!(items.len() == 1);
+        check!(items[0].label == "add");
+        check!(items[0].line == Some(4));
+        check!(
+            tree.selection(&items[0].id)
+                == Some(&TestSelection::DocTest {
+                    package: "core".to_string(),
+                    test,
+                })
+        );
+
+        let result = TestResult {
+            suite: TestSuite::Doc,
+            name: "src/lib.rs - add (line 5)".to_string(),
+            outcome: TestOutcome::Passed,
+            message: None,
+            duration: None,
+        };
+        check!(tree.result_id(&package, &result) == Some(items[0].id.clone()));
+    }
+}
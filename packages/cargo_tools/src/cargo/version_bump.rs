@@ -0,0 +1,235 @@
+pub use cargo_metadata::semver::Version;
+use cargo_metadata::semver::{BuildMetadata, Prerelease};
+use toml::Table;
+
+use crate::cargo::{
+    manifest,
+    manifest_edit::{join, replace_value},
+};
+
+/// The part of a version **Bump Version...** increments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+    PreRelease,
+}
+
+impl Bump {
+    pub const fn all() -> [Self; 4] {
+        [Self::Patch, Self::Minor, Self::Major, Self::PreRelease]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Patch => "patch",
+            Self::Minor => "minor",
+            Self::Major => "major",
+            Self::PreRelease => "pre-release",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Patch => "1.2.3 → 1.2.4, releases a pre-release like 1.2.4-rc.1 as 1.2.4",
+            Self::Minor => "1.2.3 → 1.3.0",
+            Self::Major => "1.2.3 → 2.0.0",
+            Self::PreRelease => "1.2.3 → 1.2.4-rc.1, 1.2.4-rc.1 → 1.2.4-rc.2",
+        }
+    }
+
+    /// The version following `version`, without build metadata
+    pub fn apply(&self, version: &Version) -> Version {
+        let mut next = version.clone();
+        next.build = BuildMetadata::EMPTY;
+        match self {
+            Self::Patch if !version.pre.is_empty() => {}
+            Self::Patch => next.patch += 1,
+            Self::Minor => {
+                next.minor += 1;
+                next.patch = 0;
+            }
+            Self::Major => {
+                next.major += 1;
+                next.minor = 0;
+                next.patch = 0;
+            }
+            Self::PreRelease => {
+                let pre = if version.pre.is_empty() {
+                    next.patch += 1;
+                    "rc.1".to_string()
+                } else {
+                    // The last numeric identifier counts, `alpha` becomes `alpha.1`
+                    match version.pre.rsplit_once('.') {
+                        Some((name, n)) if let Ok(n) = n.parse::<u64>() => {
+                            format!("{name}.{}", n + 1)
+                        }
+                        _ => format!("{}.1", version.pre),
+                    }
+                };
+                next.pre = Prerelease::new(&pre).unwrap_or_default();
+                return next;
+            }
+        }
+        next.pre = Prerelease::EMPTY;
+        next
+    }
+}
+
+/// The table `header` like `workspace.package` of `manifest`
+fn table<'a>(manifest: &'a Table, header: &str) -> Option<&'a Table> {
+    header
+        .split('.')
+        .try_fold(manifest, |table, key| table.get(key)?.as_table())
+}
+
+/// Whether the package of `manifest` inherits its version from `[workspace.package]`
+pub fn inherits_version(manifest: &str) -> bool {
+    manifest
+        .parse::<Table>()
+        .ok()
+        .and_then(|m| {
+            Some(
+                table(&m, "package")?
+                    .get("version")?
+                    .get("workspace")?
+                    .as_bool(),
+            )
+        })
+        .flatten()
+        .unwrap_or(false)
+}
+
+/// The version of the package of `manifest`, the one of `[workspace.package]` of the root
+/// manifest `root` if it inherits it
+pub fn package_version(manifest: &str, root: &str) -> Option<Version> {
+    let (manifest, header) = if inherits_version(manifest) {
+        (root, "workspace.package")
+    } else {
+        (manifest, "package")
+    };
+    let manifest = manifest.parse::<Table>().ok()?;
+    Version::parse(table(&manifest, header)?.get("version")?.as_str()?).ok()
+}
+
+/// Whether the package of `manifest` can be published, it isn't with `publish = false` or
+/// `publish = []`
+pub fn is_publishable(manifest: &str) -> bool {
+    let Ok(manifest) = manifest.parse::<Table>() else {
+        return false;
+    };
+    match table(&manifest, "package").and_then(|p| p.get("publish")) {
+        Some(toml::Value::Boolean(publish)) => *publish,
+        Some(toml::Value::Array(registries)) => !registries.is_empty(),
+        _ => true,
+    }
+}
+
+/// `manifest` with the `version` of the table `header`, `package` or `workspace.package`, set
+/// to `version`, [None] if the table doesn't declare one
+pub fn set_version(manifest: &str, header: &str, version: &Version) -> Option<String> {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    let start = lines
+        .iter()
+        .position(|l| l.trim().replace(' ', "") == format!("[{header}]"))?;
+    let line = lines[start + 1..]
+        .iter()
+        .take_while(|l| !l.trim_start().starts_with('['))
+        .position(|l| l.trim_start().starts_with("version"))
+        .map(|i| start + 1 + i)?;
+    lines[line] = replace_value(&lines[line], "version", &version.to_string())?;
+    Some(join(manifest, lines))
+}
+
+/// `manifest` with the version requirements of its dependencies on the crate `name` set to
+/// `version`, keeping operators like `=` or `~`
+pub fn set_dependency_version(manifest: &str, name: &str, version: &Version) -> String {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    for dependency in manifest::dependencies(manifest) {
+        let Some(req) = dependency.req.filter(|_| dependency.name == name) else {
+            continue;
+        };
+        let operator: String = req.chars().take_while(|c| "=^~ ".contains(*c)).collect();
+        let req = format!("{}{version}", operator.trim());
+        let (first, last) = dependency.lines;
+        // `name = "1"` or a `version` key of an inline table or a dependency table
+        let edited = (first..=last).find_map(|i| {
+            let line = &lines[i];
+            let value = line.split_once('=')?.1.trim_start();
+            if i == first && value.starts_with('"') {
+                let start = line.len() - value.len() + 1;
+                let end = start + value[1..].find('"')?;
+                return Some((i, format!("{}{req}{}", &line[..start], &line[end..])));
+            }
+            replace_value(line, "version", &req).map(|edited| (i, edited))
+        });
+        if let Some((i, edited)) = edited {
+            lines[i] = edited;
+        }
+    }
+    join(manifest, lines)
+}
+
+/// The git tag of the release of `package` at `version`, `v1.2.3` for a workspace with a
+/// single package
+pub fn tag_name(package: &str, version: &Version, single_package: bool) -> String {
+    if single_package {
+        format!("v{version}")
+    } else {
+        format!("{package}-v{version}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn versions_and_requirements_are_bumped() {
+        let bump = |bump: Bump, version: &str| bump.apply(&Version::parse(version).unwrap());
+        check!(bump(Bump::Patch, "1.2.3").to_string() == "1.2.4");
+        check!(bump(Bump::Patch, "1.2.4-rc.1").to_string() == "1.2.4");
+        check!(bump(Bump::Minor, "1.2.3+build.5").to_string() == "1.3.0");
+        check!(bump(Bump::Major, "0.9.1").to_string() == "1.0.0");
+        check!(bump(Bump::PreRelease, "1.2.3").to_string() == "1.2.4-rc.1");
+        check!(bump(Bump::PreRelease, "1.2.4-rc.9").to_string() == "1.2.4-rc.10");
+        check!(bump(Bump::PreRelease, "2.0.0-alpha").to_string() == "2.0.0-alpha.1");
+
+        let root = "[workspace]\nmembers = [\"core\", \"cli\"]\n\n[workspace.package]\nversion = \"0.4.0\"\n\n[workspace.dependencies]\ncore = { path = \"core\", version = \"=0.4.0\" }\n";
+        let core = "[package]\nname = \"core\"\nversion.workspace = true\n";
+        let cli = "[package]\nname = \"cli\"\nversion = \"1.1.0\" # released\npublish = false\n\n[dependencies]\ncore = { workspace = true }\n\n[dev-dependencies.core]\npath = \"../core\"\nversion = \"0.4\"\n";
+        check!(inherits_version(core));
+        check!(package_version(core, root) == Version::parse("0.4.0").ok());
+        check!(package_version(cli, root) == Version::parse("1.1.0").ok());
+        check!(is_publishable(core));
+        check!(!is_publishable(cli));
+
+        let next = Version::parse("0.5.0").unwrap();
+        check!(
+            set_version(cli, "package", &next).unwrap().lines().nth(2)
+                == Some("version = \"0.5.0\" # released")
+        );
+        check!(set_version(core, "package", &next).is_none());
+        let root = set_version(root, "workspace.package", &next).unwrap();
+        check!(root.contains("[workspace.package]\nversion = \"0.5.0\"\n"));
+        check!(
+            set_dependency_version(&root, "core", &next)
+                .ends_with("core = { path = \"core\", version = \"=0.5.0\" }\n")
+        );
+        check!(
+            set_dependency_version(cli, "core", &next)
+                .ends_with("path = \"../core\"\nversion = \"0.5.0\"\n")
+        );
+        check!(
+            set_dependency_version("[dependencies]\ncore = \"0.4\" # core\n", "core", &next)
+                == "[dependencies]\ncore = \"0.5.0\" # core\n"
+        );
+
+        check!(tag_name("core", &next, false) == "core-v0.5.0");
+        check!(tag_name("core", &next, true) == "v0.5.0");
+    }
+}
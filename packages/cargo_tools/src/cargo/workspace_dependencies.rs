@@ -0,0 +1,446 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::semver::VersionReq;
+use toml::{Table, Value};
+
+use crate::cargo::{
+    manifest::{self, ManifestDependency},
+    manifest_edit::{add_to_table, join, quote},
+    rename_package::normalize,
+};
+
+/// Why **Promote to Workspace Dependency** can't move a dependency
+#[derive(Debug, thiserror::Error)]
+pub enum PromoteError {
+    #[error("{0} is a workspace dependency already")]
+    Declared(String),
+    #[error("No member declares {0} with a version, path or git repository")]
+    NotDeclared(String),
+    #[error("The members declare {0} from different sources")]
+    ConflictingSources(String),
+    #[error("The edited manifest {0} is no valid TOML, no manifest was changed")]
+    InvalidEdit(String),
+}
+
+/// Where a dependency comes from, the same for all members which inherit it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Source {
+    /// Relative to the workspace root
+    path: Option<String>,
+    git: Option<String>,
+    branch: Option<String>,
+    tag: Option<String>,
+    rev: Option<String>,
+    registry: Option<String>,
+}
+
+impl Source {
+    /// The keys of the source in a `[workspace.dependencies]` entry
+    fn fields(&self) -> Vec<String> {
+        [
+            ("path", &self.path),
+            ("git", &self.git),
+            ("branch", &self.branch),
+            ("tag", &self.tag),
+            ("rev", &self.rev),
+            ("registry", &self.registry),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some(format!("{key} = {}", quote(value.as_ref()?))))
+        .collect()
+    }
+}
+
+/// A declaration of a dependency in a member manifest which can be inherited from
+/// `[workspace.dependencies]`
+#[derive(Debug, Clone)]
+struct Declaration {
+    /// The first and last line, zero based
+    lines: (usize, usize),
+    /// Declared inline like `serde = "1"` instead of as `[dependencies.serde]` table
+    inline: bool,
+    req: Option<String>,
+    source: Source,
+    features: Vec<String>,
+    optional: bool,
+    default_features: bool,
+}
+
+/// The header of the table the zero based `line` of `lines` belongs to
+fn table_of<'a>(lines: &[&'a str], line: usize) -> &'a str {
+    lines[..=line]
+        .iter()
+        .rev()
+        .map(|l| l.trim())
+        .find(|l| l.starts_with('['))
+        .map_or("", |l| l.trim_matches(['[', ']']))
+}
+
+/// The declarations of `name` in the dependency tables of the member manifest `manifest` in
+/// `dir`, relative to the workspace root, without the renamed ones and those which already
+/// inherit it or have neither a version nor a path or git repository
+fn declarations(manifest: &str, name: &str, dir: &Path) -> Vec<Declaration> {
+    let lines: Vec<&str> = manifest.lines().collect();
+    manifest::dependencies(manifest)
+        .into_iter()
+        .filter(|d| d.name == name)
+        .filter_map(|ManifestDependency { lines: range, .. }| {
+            let table = table_of(&lines, range.0);
+            if table.replace(' ', "").starts_with("workspace.") {
+                return None;
+            }
+            let inline = !lines[range.0].trim_start().starts_with('[');
+            let (key, value) = if inline {
+                let entry = lines[range.0..=range.1].join("\n").parse::<Table>().ok()?;
+                entry.into_iter().next()?
+            } else {
+                let key = table.rsplit('.').next()?.trim().trim_matches(['"', '\'']);
+                let body = lines[range.0 + 1..=range.1].join("\n");
+                (key.to_string(), Value::Table(body.parse().ok()?))
+            };
+            if key != name {
+                return None;
+            }
+            let get = |key: &str| value.get(key);
+            let string = |key: &str| get(key).and_then(Value::as_str).map(str::to_string);
+            let source = Source {
+                path: string("path").map(|path| {
+                    normalize(&dir.join(path))
+                        .to_string_lossy()
+                        .replace('\\', "/")
+                }),
+                git: string("git"),
+                branch: string("branch"),
+                tag: string("tag"),
+                rev: string("rev"),
+                registry: string("registry"),
+            };
+            let req = match &value {
+                Value::String(req) => Some(req.clone()),
+                _ => string("version"),
+            };
+            if req.is_none() && source.path.is_none() && source.git.is_none() {
+                return None;
+            }
+            Some(Declaration {
+                lines: range,
+                inline,
+                req,
+                source,
+                features: get("features")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect(),
+                optional: get("optional").and_then(Value::as_bool).unwrap_or(false),
+                default_features: get("default-features")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true),
+            })
+        })
+        .collect()
+}
+
+/// The crates `[workspace.dependencies]` of the root manifest `root` doesn't declare yet which
+/// several of the `members` manifests declare
+pub fn promotable(root: &str, members: &[String]) -> Vec<String> {
+    let inherited = workspace_dependencies(root);
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for member in members {
+        let mut names: Vec<String> = manifest::dependencies(member)
+            .into_iter()
+            .map(|d| d.name)
+            .filter(|name| !declarations(member, name, Path::new("")).is_empty())
+            .collect();
+        names.sort();
+        names.dedup();
+        for name in names {
+            *counts.entry(name).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(name, count)| *count > 1 && !inherited.contains(name))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// The dependency declared at the zero based `line` of the member manifest `manifest` if it
+/// could inherit it from `[workspace.dependencies]`
+pub fn promotable_at(manifest: &str, line: usize) -> Option<String> {
+    let dependency = manifest::dependency_at(manifest, line)?;
+    declarations(manifest, &dependency.name, Path::new(""))
+        .iter()
+        .any(|d| (d.lines.0..=d.lines.1).contains(&line))
+        .then_some(dependency.name)
+}
+
+fn workspace_dependencies(root: &str) -> Vec<String> {
+    root.parse::<Table>()
+        .ok()
+        .and_then(|t| t.get("workspace")?.get("dependencies")?.as_table().cloned())
+        .map(|deps| deps.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The lower bound of `req` to pick the highest requirement of several members
+fn lower_bound(req: &str) -> (u64, u64, u64) {
+    VersionReq::parse(req)
+        .ok()
+        .and_then(|req| req.comparators.first().cloned())
+        .map_or((0, 0, 0), |c| {
+            (c.major, c.minor.unwrap_or(0), c.patch.unwrap_or(0))
+        })
+}
+
+/// The manifests with the dependency `name` moved from the `members` into
+/// `[workspace.dependencies]` of the root manifest `root`. The members inherit it with
+/// `workspace = true` and keep their features and `optional`. The workspace declares the
+/// highest requirement of the members and their source, a `path` relative to the root, without
+/// default features unless all members use them, the members which use them enable the
+/// `default` feature then.
+///
+/// `dirs` are the directories of the `members` relative to the workspace root. `root_member`
+/// is the index of the root manifest among the `members` if it is a package too, its edits are
+/// part of the edited root then.
+///
+/// Returns the edited root and the edited members, [None] for members which don't declare
+/// `name` and the root member. All edited manifests are valid TOML.
+pub fn promote(
+    name: &str,
+    root: &str,
+    members: &[String],
+    dirs: &[PathBuf],
+    root_member: Option<usize>,
+) -> Result<(String, Vec<Option<String>>), PromoteError> {
+    if workspace_dependencies(root).iter().any(|d| d == name) {
+        return Err(PromoteError::Declared(name.to_string()));
+    }
+    let declared: Vec<Vec<Declaration>> = members
+        .iter()
+        .zip(dirs)
+        .map(|(member, dir)| declarations(member, name, dir))
+        .collect();
+    let all: Vec<&Declaration> = declared.iter().flatten().collect();
+    let Some(first) = all.first() else {
+        return Err(PromoteError::NotDeclared(name.to_string()));
+    };
+    if all.iter().any(|d| d.source != first.source) {
+        return Err(PromoteError::ConflictingSources(name.to_string()));
+    }
+    let req = all
+        .iter()
+        .filter_map(|d| d.req.as_deref())
+        .max_by_key(|req| lower_bound(req));
+    let default_features = all.iter().all(|d| d.default_features);
+
+    let mut fields: Vec<String> = req
+        .iter()
+        .map(|req| format!("version = {}", quote(req)))
+        .collect();
+    fields.extend(first.source.fields());
+    if !default_features {
+        fields.push("default-features = false".to_string());
+    }
+    let entry = match (req, fields.len()) {
+        (Some(req), 1) => format!("{name} = {}", quote(req)),
+        _ => format!("{name} = {{ {} }}", fields.join(", ")),
+    };
+
+    let mut edited_members: Vec<Option<String>> = members
+        .iter()
+        .zip(&declared)
+        .map(|(member, declarations)| {
+            (!declarations.is_empty())
+                .then(|| inherit(member, name, declarations, default_features))
+        })
+        .collect();
+    let root = match root_member.and_then(|i| edited_members.get_mut(i)?.take()) {
+        Some(edited) => edited,
+        None => root.to_string(),
+    };
+    let root = add_to_table(&root, "workspace.dependencies", vec![entry]);
+
+    // Checked before any manifest is written so a failed edit changes none of them
+    let invalid = std::iter::once(&root)
+        .chain(edited_members.iter().flatten())
+        .any(|manifest| manifest.parse::<Table>().is_err());
+    if invalid {
+        return Err(PromoteError::InvalidEdit(name.to_string()));
+    }
+    Ok((root, edited_members))
+}
+
+/// The member manifest `manifest` with its `declarations` of `name` inheriting it
+fn inherit(
+    manifest: &str,
+    name: &str,
+    declarations: &[Declaration],
+    workspace_default_features: bool,
+) -> String {
+    let mut lines: Vec<String> = manifest.lines().map(str::to_string).collect();
+    // From the last one so the lines of the others stay valid
+    for declaration in declarations.iter().rev() {
+        let mut features = declaration.features.clone();
+        if !workspace_default_features
+            && declaration.default_features
+            && !features.iter().any(|f| f == "default")
+        {
+            features.insert(0, "default".to_string());
+        }
+        let mut fields = vec!["workspace = true".to_string()];
+        if !features.is_empty() {
+            let features: Vec<String> = features.iter().map(|f| quote(f)).collect();
+            fields.push(format!("features = [{}]", features.join(", ")));
+        }
+        if declaration.optional {
+            fields.push("optional = true".to_string());
+        }
+
+        let (first, last) = declaration.lines;
+        if declaration.inline {
+            let indent: String = lines[first]
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .collect();
+            let entry = format!("{indent}{name} = {{ {} }}", fields.join(", "));
+            lines.splice(first..=last, [entry]);
+        } else {
+            lines.splice(first + 1..=last, fields);
+        }
+    }
+    join(manifest, lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn dependencies_move_to_the_workspace() {
+        let root = "[workspace]\nmembers = [\"app\", \"core\"]\n\n[workspace.dependencies]\nlog = \"0.4\"\n";
+        let app = r#"[package]
+name = "app"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"], optional = true }
+log = "0.4"
+"#;
+        let core = r#"[package]
+name = "core"
+
+[dependencies.serde]
+version = "1.0.200"
+default-features = false
+
+[dev-dependencies]
+log = "0.4"
+"#;
+        let members = [app.to_string(), core.to_string()];
+        check!(promotable(root, &members) == ["serde"]);
+        check!(promotable_at(core, 4).as_deref() == Some("serde"));
+        check!(promotable_at(root, 4).is_none());
+
+        let dirs = [PathBuf::from("app"), PathBuf::from("core")];
+        let (root, members) = promote("serde", root, &members, &dirs, None).unwrap();
+        check!(
+            root == "[workspace]\nmembers = [\"app\", \"core\"]\n\n[workspace.dependencies]\nlog = \"0.4\"\nserde = { version = \"1.0.200\", default-features = false }\n"
+        );
+        check!(
+            members[0].as_deref()
+                == Some(
+                    r#"[package]
+name = "app"
+
+[dependencies]
+serde = { workspace = true, features = ["default", "derive"], optional = true }
+log = "0.4"
+"#
+                )
+        );
+        check!(
+            members[1].as_deref()
+                == Some(
+                    r#"[package]
+name = "core"
+
+[dependencies.serde]
+workspace = true
+
+[dev-dependencies]
+log = "0.4"
+"#
+                )
+        );
+        let members: Vec<String> = members.into_iter().flatten().collect();
+        check!(let Err(PromoteError::Declared(_)) = promote("log", &root, &members, &dirs, None));
+
+        let package_root =
+            "[package]\nname = \"root\"\n\n[workspace]\n\n[dependencies]\nitoa = \"1\"\n";
+        let (root, members) = promote(
+            "itoa",
+            package_root,
+            &[package_root.to_string()],
+            &[PathBuf::new()],
+            Some(0),
+        )
+        .unwrap();
+        check!(
+            root == "[package]\nname = \"root\"\n\n[workspace]\n\n[dependencies]\nitoa = { workspace = true }\n\n[workspace.dependencies]\nitoa = \"1\"\n"
+        );
+        check!(members == [None]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn sources_move_to_the_workspace() {
+        let root = "[workspace]\nmembers = [\"crates/*\"]\n";
+        let app = r#"[dependencies]
+core = { version = "0.1", path = "../core" }
+tool = {
+    git = "https://example.com/tool",
+    branch = "main",
+    features = ["cli"],
+}
+"#;
+        let cli = r#"[dependencies]
+core = { path = "../core" }
+tool = { git = "https://example.com/tool", branch = "main" }
+"#;
+        let members = [app.to_string(), cli.to_string()];
+        let dirs = [PathBuf::from("crates/app"), PathBuf::from("crates/cli")];
+        check!(promotable(root, &members) == ["core", "tool"]);
+
+        let (edited, members_edited) = promote("core", root, &members, &dirs, None).unwrap();
+        check!(
+            edited
+                == "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\ncore = { version = \"0.1\", path = \"crates/core\" }\n"
+        );
+        check!(
+            members_edited[1]
+                .as_deref()
+                .is_some_and(|m| m.starts_with("[dependencies]\ncore = { workspace = true }\n"))
+        );
+
+        let (edited, members_edited) = promote("tool", root, &members, &dirs, None).unwrap();
+        check!(
+            edited.ends_with("tool = { git = \"https://example.com/tool\", branch = \"main\" }\n")
+        );
+        check!(
+            members_edited[0].as_deref()
+                == Some(
+                    "[dependencies]\ncore = { version = \"0.1\", path = \"../core\" }\ntool = { workspace = true, features = [\"cli\"] }\n"
+                )
+        );
+
+        let other = "[dependencies]\ncore = { path = \"../../core\" }\n".to_string();
+        check!(let Err(PromoteError::ConflictingSources(_)) = promote("core", root, &[app.to_string(), other], &dirs, None));
+    }
+}
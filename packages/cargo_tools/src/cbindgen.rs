@@ -0,0 +1,101 @@
+use std::{iter, path::PathBuf};
+
+use crate::cargo::{
+    Config,
+    metadata::{Package, TargetKind},
+};
+
+/// The command which generates C headers for the `extern "C"` items of a crate
+pub const CBINDGEN: &str = "cbindgen";
+/// The config cbindgen reads from the package directory
+pub const CONFIG_FILE: &str = "cbindgen.toml";
+
+/// The name of the `cdylib` target of `package`, the file name of the library cargo builds
+pub fn cdylib_name(package: &Package) -> Option<&str> {
+    package
+        .targets
+        .iter()
+        .find(|t| t.target_kind.contains(&TargetKind::CDyLib))
+        .map(|t| t.name.as_str())
+}
+
+/// The header of the `cdylib` named `lib_name` next to the library cargo builds with `config`
+/// in `target_dir`, e.g. `target/debug/my_lib.h`
+pub fn header_path(target_dir: &str, config: &Config, lib_name: &str) -> String {
+    let file_name = format!("{}.h", lib_name.replace('-', "_"));
+    let path_components = iter::once(target_dir)
+        .chain(config.platform_target.as_deref())
+        .chain(iter::once(config.profile.output_dir()))
+        .chain(iter::once(file_name.as_str()));
+
+    PathBuf::from_iter(path_components)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// The cbindgen arguments which write the header of `package` to `header`. Without a
+/// [CONFIG_FILE] it is generated for C instead of cbindgen's default C++.
+pub fn args(package: &str, header: &str, has_config: bool) -> Vec<String> {
+    let mut args = vec!["--crate".to_string(), package.to_string()];
+    if has_config {
+        args.extend(["--config".to_string(), CONFIG_FILE.to_string()]);
+    } else {
+        args.extend(["--lang".to_string(), "c".to_string()]);
+    }
+    args.extend(["--output".to_string(), header.to_string()]);
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::{
+        ConfigUpdate, Profile,
+        metadata::{Target, TargetType},
+    };
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cdylib_headers() {
+        let mut package = Package::fixture(
+            "test-cdylib",
+            "/ws/test-cdylib/Cargo.toml",
+            vec![Target {
+                target_kind: vec![TargetKind::CDyLib, TargetKind::Lib],
+                ..Target::fixture("test_cdylib", "/ws/test-cdylib/src/lib.rs", TargetType::Lib)
+            }],
+        );
+        check!(cdylib_name(&package) == Some("test_cdylib"));
+
+        let mut config = Config::default();
+        check!(
+            header_path("/ws/target", &config, "test_cdylib") == "/ws/target/debug/test_cdylib.h"
+        );
+        config.update(ConfigUpdate::SelectedProfile(Profile::Release));
+        config.update(ConfigUpdate::SelectedPlatformTarget(Some(
+            "aarch64-linux-android".to_string(),
+        )));
+        check!(
+            header_path("/ws/target", &config, "test_cdylib")
+                == "/ws/target/aarch64-linux-android/release/test_cdylib.h"
+        );
+
+        check!(
+            args("test-cdylib", "/ws/target/debug/test_cdylib.h", true)
+                == [
+                    "--crate",
+                    "test-cdylib",
+                    "--config",
+                    "cbindgen.toml",
+                    "--output",
+                    "/ws/target/debug/test_cdylib.h"
+                ]
+        );
+        check!(args("test-cdylib", "out.h", false)[2..4] == ["--lang", "c"]);
+
+        package.targets[0].target_kind = vec![TargetKind::Lib];
+        check!(cdylib_name(&package).is_none());
+    }
+}
@@ -0,0 +1,153 @@
+use serde::Serialize;
+
+/// How a finished build or test task ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Succeeded,
+    Failed,
+    /// The task was terminated before its process reported an exit code
+    Terminated,
+}
+
+/// The summary of a finished build or test which is posted to `cargoTools.completionHook.url`
+/// and passed to `cargoTools.completionHook.command`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    /// `build` or `test`
+    pub command: &'static str,
+    /// The `--package` of the command, [None] for the whole workspace
+    pub package: Option<String>,
+    pub duration_ms: u64,
+    pub status: Status,
+    pub exit_code: Option<i32>,
+    pub command_line: String,
+}
+
+impl Summary {
+    /// The summary of the task running `cmd` with `args`, [None] if it's neither a build nor
+    /// a test, e.g. `cargo clippy`
+    pub fn of_task(
+        cmd: &str,
+        args: &[String],
+        exit_code: Option<i32>,
+        duration_ms: u64,
+    ) -> Option<Self> {
+        // A toolchain like `+nightly` precedes the subcommand
+        let mut subcommands = args.iter().filter(|arg| !arg.starts_with('+'));
+        let command = match subcommands.next()?.as_str() {
+            "build" | "b" => "build",
+            "test" | "t" => "test",
+            "nextest" if subcommands.next().is_some_and(|arg| arg == "run") => "test",
+            _ => return None,
+        };
+        let package = args
+            .iter()
+            .skip_while(|arg| *arg != "--package" && *arg != "-p")
+            .nth(1)
+            .cloned();
+        let status = match exit_code {
+            Some(0) => Status::Succeeded,
+            Some(_) => Status::Failed,
+            None => Status::Terminated,
+        };
+        let command_line = std::iter::once(cmd)
+            .chain(args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Some(Self {
+            command,
+            package,
+            duration_ms,
+            status,
+            exit_code,
+            command_line,
+        })
+    }
+
+    /// The variables of the summary for `cargoTools.completionHook.command`, which gets the
+    /// JSON on stdin as well
+    pub fn env(&self) -> Vec<(String, String)> {
+        let status = match self.status {
+            Status::Succeeded => "succeeded",
+            Status::Failed => "failed",
+            Status::Terminated => "terminated",
+        };
+        [
+            ("CARGO_TOOLS_COMMAND", self.command.to_string()),
+            (
+                "CARGO_TOOLS_PACKAGE",
+                self.package.clone().unwrap_or_default(),
+            ),
+            ("CARGO_TOOLS_DURATION_MS", self.duration_ms.to_string()),
+            ("CARGO_TOOLS_STATUS", status.to_string()),
+            (
+                "CARGO_TOOLS_EXIT_CODE",
+                self.exit_code.map(|c| c.to_string()).unwrap_or_default(),
+            ),
+            ("CARGO_TOOLS_COMMAND_LINE", self.command_line.clone()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn summaries_of_builds_and_tests() {
+        let summary = Summary::of_task(
+            "cargo",
+            &args("+nightly build --package cli --profile release"),
+            Some(0),
+            1250,
+        );
+        check!(
+            summary
+                == Some(Summary {
+                    command: "build",
+                    package: Some("cli".to_string()),
+                    duration_ms: 1250,
+                    status: Status::Succeeded,
+                    exit_code: Some(0),
+                    command_line: "cargo +nightly build --package cli --profile release"
+                        .to_string(),
+                })
+        );
+        let json = serde_json::to_string(&summary).unwrap();
+        check!(json.contains(r#""durationMs":1250"#));
+        check!(json.contains(r#""status":"succeeded""#));
+
+        let nextest = Summary::of_task("cargo", &args("nextest run"), Some(100), 10).unwrap();
+        check!(nextest.command == "test");
+        check!(nextest.status == Status::Failed);
+        check!(nextest.package.is_none());
+        check!(
+            nextest.env()[..2]
+                == [
+                    ("CARGO_TOOLS_COMMAND".to_string(), "test".to_string()),
+                    ("CARGO_TOOLS_PACKAGE".to_string(), String::new()),
+                ]
+        );
+
+        check!(
+            Summary::of_task("cargo", &args("test"), None, 5)
+                .unwrap()
+                .status
+                == Status::Terminated
+        );
+        check!(Summary::of_task("cargo", &args("clippy --package cli"), Some(0), 5).is_none());
+        check!(Summary::of_task("cargo", &args("nextest list"), Some(0), 5).is_none());
+    }
+}
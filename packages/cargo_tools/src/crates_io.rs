@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+use cargo_metadata::semver::{Op, Version, VersionReq};
+use serde::{Deserialize, Serialize};
+
+/// How long fetched crate information is used before it is fetched again, one day
+pub const CACHE_TTL_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// The crates.io API endpoint of the crate `name`
+pub fn api_url(name: &str) -> String {
+    format!("https://crates.io/api/v1/crates/{name}")
+}
+
+/// The crates.io page of the crate `name`
+pub fn crate_url(name: &str) -> String {
+    format!("https://crates.io/crates/{name}")
+}
+
+/// The docs.rs documentation of the crate `name` for a version like the locked `1.0.219` or a
+/// requirement like `^1.0` or `~1.2`, docs.rs resolves requirements to the newest matching
+/// release. Other requirements like `>=1.2, <2` link the latest release.
+pub fn docs_url(name: &str, req: &str) -> String {
+    let version = VersionReq::parse(req.trim())
+        .ok()
+        .and_then(|req| match req.comparators.as_slice() {
+            [comparator] => {
+                let mut version = comparator.major.to_string();
+                version.extend(comparator.minor.map(|minor| format!(".{minor}")));
+                version.extend(comparator.patch.map(|patch| format!(".{patch}")));
+                if !comparator.pre.is_empty() {
+                    version.push_str(&format!("-{}", comparator.pre));
+                }
+                match comparator.op {
+                    Op::Caret => Some(version),
+                    Op::Exact if comparator.patch.is_some() => Some(version),
+                    Op::Exact | Op::Tilde => Some(format!("~{version}")),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .unwrap_or_else(|| "latest".to_string());
+    format!("https://docs.rs/{name}/{version}")
+}
+
+/// Whether the newest release `latest` doesn't match the requirement `req`, so updating to it
+/// needs a new requirement
+pub fn is_outdated(req: &str, latest: &str) -> bool {
+    match (VersionReq::parse(req), Version::parse(latest)) {
+        (Ok(req), Ok(latest)) => !req.matches(&latest),
+        _ => false,
+    }
+}
+
+/// The information about a crate shown next to dependencies
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrateInfo {
+    /// The newest stable release, the newest release if there is no stable one
+    pub latest_version: String,
+    pub downloads: u64,
+    pub description: Option<String>,
+    /// The releases which aren't yanked, empty for information cached before they were stored
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    #[serde(rename = "crate")]
+    krate: ResponseCrate,
+    #[serde(default)]
+    versions: Vec<ResponseVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseCrate {
+    max_version: String,
+    max_stable_version: Option<String>,
+    downloads: u64,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseVersion {
+    num: String,
+    #[serde(default)]
+    yanked: bool,
+}
+
+impl CrateInfo {
+    /// Parses the response of [api_url]
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        let Response { krate, versions } = serde_json::from_str(json)?;
+        Ok(Self {
+            latest_version: krate.max_stable_version.unwrap_or(krate.max_version),
+            downloads: krate.downloads,
+            description: krate
+                .description
+                .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+            versions: versions
+                .into_iter()
+                .filter(|v| !v.yanked)
+                .map(|v| v.num)
+                .collect(),
+        })
+    }
+
+    /// The downloads shortened like `1.2M`
+    pub fn downloads_display(&self) -> String {
+        match self.downloads {
+            n if n >= 1_000_000_000 => format!("{:.1}B", n as f64 / 1e9),
+            n if n >= 1_000_000 => format!("{:.1}M", n as f64 / 1e6),
+            n if n >= 1_000 => format!("{:.1}k", n as f64 / 1e3),
+            n => n.to_string(),
+        }
+    }
+}
+
+/// The releases of a crate a dependency with a version requirement can use
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Releases {
+    /// The newest stable release, the newest release if there is no stable one
+    pub latest: String,
+    /// The newest release which isn't yanked and matches the requirement
+    pub latest_compatible: Option<String>,
+    /// Whether [Self::latest] needs a new requirement, see [is_outdated]
+    pub outdated: bool,
+}
+
+impl Releases {
+    /// The releases of `info` for a dependency with the requirement `req`, any release is
+    /// compatible without one
+    pub fn new(info: &CrateInfo, req: Option<&str>) -> Self {
+        let parsed_req = VersionReq::parse(req.unwrap_or("*")).unwrap_or(VersionReq::STAR);
+        let latest_compatible = info
+            .versions
+            .iter()
+            .filter_map(|v| Version::parse(v).ok())
+            .filter(|version| parsed_req.matches(version))
+            .max()
+            .map(|version| version.to_string());
+        Self {
+            latest: info.latest_version.clone(),
+            latest_compatible,
+            outdated: req.is_some_and(|req| is_outdated(req, &info.latest_version)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct CacheEntry {
+    info: CrateInfo,
+    /// Milliseconds since the Unix epoch
+    fetched_at: f64,
+}
+
+/// The fetched [CrateInfo]s by crate name, entries stay usable after [CACHE_TTL_MS] so
+/// dependencies keep their information while offline
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrateInfoCache(BTreeMap<String, CacheEntry>);
+
+impl CrateInfoCache {
+    pub fn get(&self, name: &str) -> Option<&CrateInfo> {
+        self.0.get(name).map(|entry| &entry.info)
+    }
+
+    /// The information of `name` if it was fetched in the last [CACHE_TTL_MS]
+    pub fn fresh(&self, name: &str, now: f64) -> Option<&CrateInfo> {
+        self.0
+            .get(name)
+            .filter(|entry| now - entry.fetched_at <= CACHE_TTL_MS)
+            .map(|entry| &entry.info)
+    }
+
+    pub fn insert(&mut self, name: String, info: CrateInfo, now: f64) {
+        self.0.insert(
+            name,
+            CacheEntry {
+                info,
+                fetched_at: now,
+            },
+        );
+    }
+
+    /// The crates among `names` which were never fetched or longer than [CACHE_TTL_MS] ago
+    pub fn stale<'a>(&self, names: impl IntoIterator<Item = &'a str>, now: f64) -> Vec<String> {
+        let mut stale: Vec<String> = names
+            .into_iter()
+            .filter(|name| self.fresh(name, now).is_none())
+            .map(str::to_string)
+            .collect();
+        stale.sort();
+        stale.dedup();
+        stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn crate_info_and_cache() {
+        let json = r#"{
+  "crate": {
+    "id": "serde",
+    "name": "serde",
+    "description": "A generic serialization/deserialization framework\n",
+    "downloads": 512345678,
+    "max_version": "2.0.0-alpha.1",
+    "max_stable_version": "1.0.219"
+  },
+  "versions": []
+}"#;
+        let info = CrateInfo::parse(json).unwrap();
+        check!(info.latest_version == "1.0.219");
+        check!(info.downloads_display() == "512.3M");
+        check!(
+            info.description.as_deref()
+                == Some("A generic serialization/deserialization framework")
+        );
+
+        check!(docs_url("serde", "^1.0") == "https://docs.rs/serde/1.0");
+        check!(docs_url("serde", "*") == "https://docs.rs/serde/latest");
+        check!(docs_url("serde", "1.0.219") == "https://docs.rs/serde/1.0.219");
+        check!(docs_url("serde", "=1.0.219") == "https://docs.rs/serde/1.0.219");
+        check!(docs_url("serde", "~1.2") == "https://docs.rs/serde/~1.2");
+        check!(docs_url("serde", ">=1.2, <2") == "https://docs.rs/serde/latest");
+        check!(!is_outdated("^1.0", "1.0.219"));
+        check!(is_outdated("^0.4", "0.5.1"));
+        check!(crate_url("serde") == "https://crates.io/crates/serde");
+
+        let mut cache = CrateInfoCache::default();
+        cache.insert("serde".to_string(), info, 1000.0);
+        check!(cache.stale(["serde", "tokio"], 2000.0) == ["tokio"]);
+        check!(cache.stale(["serde"], 2000.0 + CACHE_TTL_MS) == ["serde"]);
+        check!(cache.get("serde").is_some());
+        check!(cache.fresh("serde", 2000.0 + CACHE_TTL_MS).is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn releases_of_requirement() {
+        let json = r#"{
+  "crate": { "max_version": "0.5.0-rc.1", "max_stable_version": "0.4.3", "downloads": 10 },
+  "versions": [
+    { "num": "0.5.0-rc.1", "yanked": false },
+    { "num": "0.4.3", "yanked": false },
+    { "num": "0.3.9", "yanked": true },
+    { "num": "0.3.8", "yanked": false }
+  ]
+}"#;
+        let info = CrateInfo::parse(json).unwrap();
+        check!(info.versions == ["0.5.0-rc.1", "0.4.3", "0.3.8"]);
+        check!(
+            Releases::new(&info, Some("0.3"))
+                == Releases {
+                    latest: "0.4.3".to_string(),
+                    latest_compatible: Some("0.3.8".to_string()),
+                    outdated: true,
+                }
+        );
+        let any = Releases::new(&info, None);
+        check!(any.latest_compatible.as_deref() == Some("0.4.3"));
+        check!(!any.outdated);
+    }
+}
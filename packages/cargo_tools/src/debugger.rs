@@ -0,0 +1,1081 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// The debug adapters debug configurations can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Debugger {
+    /// The `vadimcn.vscode-lldb` extension
+    CodeLldb,
+    /// The `llvm-vs-code-extensions.lldb-dap` extension
+    LldbDap,
+    /// The Visual Studio debugger of the C/C++ extension, windows only
+    CppVsDbg,
+    /// gdb or lldb driven through the C/C++ extension
+    CppDbg,
+}
+
+impl Debugger {
+    pub const fn all() -> [Self; 4] {
+        [Self::CodeLldb, Self::LldbDap, Self::CppVsDbg, Self::CppDbg]
+    }
+
+    /// The value of the `cargoTools.debugger` setting which selects the debugger
+    pub fn setting_name(&self) -> &'static str {
+        match self {
+            Self::CodeLldb => "codelldb",
+            Self::LldbDap => "lldb-dap",
+            Self::CppVsDbg => "cppvsdbg",
+            Self::CppDbg => "cppdbg",
+        }
+    }
+
+    pub fn from_setting_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|d| d.setting_name() == name)
+    }
+
+    /// The VS Code extension which provides the debug adapter
+    pub fn extension_id(&self) -> &'static str {
+        match self {
+            Self::CodeLldb => "vadimcn.vscode-lldb",
+            Self::LldbDap => "llvm-vs-code-extensions.lldb-dap",
+            Self::CppVsDbg | Self::CppDbg => "ms-vscode.cpptools",
+        }
+    }
+
+    /// The debuggers which are usable on `platform` ordered by preference
+    pub fn defaults(platform: &str) -> Vec<Self> {
+        match platform {
+            // The MSVC toolchain emits PDB debug info which only cppvsdbg fully understands
+            "win32" => vec![Self::CppVsDbg, Self::CodeLldb, Self::LldbDap],
+            _ => vec![Self::CodeLldb, Self::LldbDap, Self::CppDbg],
+        }
+    }
+
+    /// Selects the debugger from the `cargoTools.debugger` setting.
+    ///
+    /// An explicitly configured debugger is always used, `auto` picks the most suitable one
+    /// for `platform` whose extension is installed.
+    pub fn select(
+        setting: &str,
+        platform: &str,
+        is_installed: impl Fn(&str) -> bool,
+    ) -> Option<Self> {
+        Self::from_setting_name(setting).or_else(|| {
+            Self::defaults(platform)
+                .into_iter()
+                .find(|d| is_installed(d.extension_id()))
+        })
+    }
+
+    /// Builds the debug configuration which launches `launch` with this debugger
+    pub fn launch_configuration(&self, launch: Launch, host: &Host) -> LaunchConfiguration {
+        let Launch {
+            name,
+            program,
+            args,
+            cwd,
+            env,
+            follow_child_processes,
+        } = launch;
+
+        // The adapters take the variables in different formats, the C/C++ extension
+        // even under another key
+        let (env, environment) = match self {
+            _ if env.is_empty() => (None, None),
+            Self::CodeLldb => (Some(Environment::Object(env)), None),
+            Self::LldbDap => {
+                let env = env.into_iter().map(|(k, v)| format!("{k}={v}")).collect();
+                (Some(Environment::Strings(env)), None)
+            }
+            Self::CppVsDbg | Self::CppDbg => {
+                let env = env
+                    .into_iter()
+                    .map(|(name, value)| EnvironmentVariable { name, value })
+                    .collect();
+                (None, Some(env))
+            }
+        };
+
+        let mut commands = self.pretty_printers(host);
+        if follow_child_processes {
+            commands = commands.chain(self.follow_child_commands(&host.platform));
+        }
+
+        LaunchConfiguration {
+            debug_type: self.debug_type().to_string(),
+            request: "launch".to_string(),
+            name,
+            program,
+            args,
+            cwd: cwd.unwrap_or_else(|| "${workspaceFolder}".to_string()),
+            env,
+            environment,
+            source_languages: self.source_languages(),
+            mi_mode: self.mi_mode(&host.platform),
+            commands,
+        }
+    }
+
+    /// Builds the debug configuration which attaches this debugger to a running process
+    pub fn attach_configuration(&self, attach: Attach, host: &Host) -> AttachConfiguration {
+        let Attach { name, pid, program } = attach;
+
+        // The lldb based adapters take a numeric `pid`, the C/C++ extension a `processId`
+        let (pid, process_id) = match self {
+            Self::CodeLldb | Self::LldbDap => (Some(pid), None),
+            Self::CppVsDbg | Self::CppDbg => (None, Some(pid.to_string())),
+        };
+
+        AttachConfiguration {
+            debug_type: self.debug_type().to_string(),
+            request: "attach".to_string(),
+            name,
+            program,
+            pid,
+            process_id,
+            source_languages: self.source_languages(),
+            mi_mode: self.mi_mode(&host.platform),
+            commands: self.pretty_printers(host),
+        }
+    }
+
+    /// Builds the debug configuration which loads the core dump of `core_dump` into this
+    /// debugger. The MSVC debugger reads minidumps and no core files.
+    pub fn core_dump_configuration(
+        &self,
+        core_dump: CoreDump,
+        host: &Host,
+    ) -> CoreDumpConfiguration {
+        let CoreDump {
+            name,
+            program,
+            core,
+        } = core_dump;
+
+        let mut config = CoreDumpConfiguration {
+            debug_type: self.debug_type().to_string(),
+            request: "launch".to_string(),
+            name,
+            program: program.clone(),
+            source_languages: self.source_languages(),
+            mi_mode: self.mi_mode(&host.platform),
+            commands: self.pretty_printers(host),
+            ..Default::default()
+        };
+        match self {
+            // CodeLLDB only loads core files with custom commands
+            Self::CodeLldb => {
+                config.request = "custom".to_string();
+                config.target_create_commands =
+                    Some(vec![format!("target create -c \"{core}\" \"{program}\"")]);
+                config.process_create_commands = Some(Vec::new());
+            }
+            Self::LldbDap => {
+                config.request = "attach".to_string();
+                config.core_file = Some(core);
+            }
+            Self::CppDbg => config.core_dump_path = Some(core),
+            Self::CppVsDbg => config.dump_path = Some(core),
+        }
+        config
+    }
+
+    /// Builds the debug configuration which connects this debugger to the debug server of
+    /// `remote` which runs the program of `launch`. Returns [None] for the MSVC debugger,
+    /// which can't connect to gdb servers.
+    pub fn remote_configuration(
+        &self,
+        launch: Launch,
+        remote: &RemoteTarget,
+        host: &Host,
+    ) -> Option<RemoteConfiguration> {
+        let Launch {
+            name,
+            program,
+            args: _,
+            cwd,
+            env: _,
+            follow_child_processes: _,
+        } = launch;
+        let address = remote.address();
+        let source_map = &remote.source_map;
+
+        let mut config = RemoteConfiguration {
+            debug_type: self.debug_type().to_string(),
+            request: "launch".to_string(),
+            name,
+            program: program.clone(),
+            cwd: cwd.unwrap_or_else(|| "${workspaceFolder}".to_string()),
+            source_languages: self.source_languages(),
+            commands: self.pretty_printers(host),
+            ..Default::default()
+        };
+        match self {
+            // CodeLLDB only connects to a server with custom commands
+            Self::CodeLldb => {
+                config.request = "custom".to_string();
+                config.target_create_commands = Some(vec![format!("target create \"{program}\"")]);
+                config.process_create_commands = Some(vec![format!("gdb-remote {address}")]);
+                config.source_map = Some(SourceMap::Object(source_map.clone()));
+            }
+            Self::LldbDap => {
+                config.request = "attach".to_string();
+                config.gdb_remote_hostname = Some(remote.host.clone());
+                config.gdb_remote_port = Some(remote.port);
+                config.source_map = Some(SourceMap::Pairs(
+                    source_map
+                        .iter()
+                        .map(|(from, to)| [from.clone(), to.clone()])
+                        .collect(),
+                ));
+            }
+            Self::CppDbg => {
+                // Only gdb speaks the remote protocol of gdbserver and qemu
+                config.mi_mode = Some("gdb".to_string());
+                config.mi_debugger_server_address = Some(address);
+                config.mi_debugger_path = remote.debugger_path.clone();
+                config.source_file_map = Some(source_map.clone());
+            }
+            Self::CppVsDbg => return None,
+        }
+        Some(config)
+    }
+
+    fn debug_type(&self) -> &'static str {
+        match self {
+            Self::CodeLldb => "lldb",
+            Self::LldbDap => "lldb-dap",
+            Self::CppVsDbg => "cppvsdbg",
+            Self::CppDbg => "cppdbg",
+        }
+    }
+
+    fn source_languages(&self) -> Option<Vec<String>> {
+        (*self == Self::CodeLldb).then(|| vec!["rust".to_string()])
+    }
+
+    fn mi_mode(&self, platform: &str) -> Option<String> {
+        (*self == Self::CppDbg).then(|| {
+            let mode = if platform == "darwin" { "lldb" } else { "gdb" };
+            mode.to_string()
+        })
+    }
+
+    /// Whether this debugger can follow the child processes of the program on `platform`
+    pub fn follows_child_processes(&self, platform: &str) -> bool {
+        match self {
+            // lldb and gdb only follow forks on these platforms
+            Self::CodeLldb | Self::LldbDap | Self::CppDbg => {
+                matches!(platform, "linux" | "freebsd")
+            }
+            Self::CppVsDbg => false,
+        }
+    }
+
+    /// Makes the debugger switch to a child process when the program forks, so breakpoints
+    /// in workers spawned by the program are hit. gdb also keeps the parent attached.
+    fn follow_child_commands(&self, platform: &str) -> DebuggerCommands {
+        if !self.follows_child_processes(platform) {
+            return DebuggerCommands::default();
+        }
+
+        match self {
+            Self::CppDbg => {
+                let setup_command = |text: &str| SetupCommand {
+                    text: text.to_string(),
+                    ignore_failures: false,
+                };
+                DebuggerCommands {
+                    init_commands: None,
+                    setup_commands: Some(vec![
+                        setup_command("-gdb-set follow-fork-mode child"),
+                        setup_command("-gdb-set detach-on-fork off"),
+                    ]),
+                }
+            }
+            Self::CodeLldb | Self::LldbDap | Self::CppVsDbg => DebuggerCommands {
+                init_commands: Some(vec![
+                    "settings set target.process.follow-fork-mode child".to_string(),
+                ]),
+                setup_commands: None,
+            },
+        }
+    }
+
+    /// Loads the pretty printers rustc ships in `<sysroot>/lib/rustlib/etc`, so `Vec`,
+    /// `String` or `Option` are shown with their contents.
+    ///
+    /// CodeLLDB loads them itself for `sourceLanguages: ["rust"]` and the MSVC debugger reads
+    /// the natvis files embedded in the PDB.
+    fn pretty_printers(&self, host: &Host) -> DebuggerCommands {
+        let Some(etc) = host
+            .sysroot
+            .as_ref()
+            .map(|s| format!("{s}/lib/rustlib/etc"))
+        else {
+            return DebuggerCommands::default();
+        };
+
+        match self {
+            Self::LldbDap => DebuggerCommands {
+                init_commands: Some(vec![
+                    format!("command script import \"{etc}/lldb_lookup.py\""),
+                    format!("command source -s 0 \"{etc}/lldb_commands\""),
+                ]),
+                setup_commands: None,
+            },
+            Self::CppDbg if host.platform != "darwin" => {
+                let setup_command = |text: String| SetupCommand {
+                    text,
+                    ignore_failures: true,
+                };
+                // Rust binaries request gdb_load_rust_pretty_printers.py in their
+                // .debug_gdb_scripts section, gdb finds and trusts it like rust-gdb does
+                DebuggerCommands {
+                    init_commands: None,
+                    setup_commands: Some(vec![
+                        setup_command("-enable-pretty-printing".to_string()),
+                        setup_command(format!("directory {etc}")),
+                        setup_command(format!("add-auto-load-safe-path {etc}")),
+                    ]),
+                }
+            }
+            Self::CodeLldb | Self::CppVsDbg | Self::CppDbg => DebuggerCommands::default(),
+        }
+    }
+}
+
+/// The debuggers with Rust pretty printers the toolchain ships, a stopgap to debug in the
+/// terminal without a debug adapter extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TerminalDebugger {
+    RustGdb,
+    RustLldb,
+}
+
+impl TerminalDebugger {
+    /// The debuggers which rustup installs on `platform`, there are none for the MSVC toolchain
+    pub fn available(platform: &str) -> Vec<Self> {
+        match platform {
+            "win32" => Vec::new(),
+            "darwin" => vec![Self::RustLldb],
+            _ => vec![Self::RustGdb, Self::RustLldb],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::RustGdb => "rust-gdb",
+            Self::RustLldb => "rust-lldb",
+        }
+    }
+
+    /// The arguments which start the debugger for `program` with the program arguments `args`
+    pub fn args(&self, program: &str, args: &[String]) -> Vec<String> {
+        let separator = match self {
+            Self::RustGdb => "--args",
+            Self::RustLldb => "--",
+        };
+        [separator.to_string(), program.to_string()]
+            .into_iter()
+            .chain(args.iter().cloned())
+            .collect()
+    }
+}
+
+/// A debug server the debugger connects to instead of starting the program itself, e.g.
+/// `qemu-aarch64 -g 1234` for an emulated target or `gdbserver` on a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteTarget {
+    /// Command which starts the program under the debug server, `${program}` and `${port}`
+    /// are replaced. Without a runner the server is expected to be running already.
+    #[serde(default)]
+    pub runner: Option<String>,
+    #[serde(default = "RemoteTarget::default_host")]
+    pub host: String,
+    pub port: u16,
+    /// The gdb which is used instead of the default one, e.g. `gdb-multiarch`
+    #[serde(default)]
+    pub debugger_path: Option<String>,
+    /// Maps source paths compiled into the executable to local paths, e.g. from a build
+    /// container
+    #[serde(default)]
+    pub source_map: BTreeMap<String, String>,
+}
+
+impl RemoteTarget {
+    fn default_host() -> String {
+        "localhost".to_string()
+    }
+
+    pub fn address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// The command line which starts `program` with `args` under the debug server
+    pub fn runner_command(&self, program: &str, args: &[String]) -> Option<Vec<String>> {
+        let runner = self.runner.as_ref()?;
+        let port = self.port.to_string();
+        let command = runner
+            .split_whitespace()
+            .map(|part| {
+                part.replace("${program}", program)
+                    .replace("${port}", &port)
+            })
+            .chain(args.iter().cloned())
+            .collect();
+        Some(command)
+    }
+}
+
+/// Settings of a run target which are merged into each debug configuration generated for it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DebugOverrides {
+    /// Variables of the program, they take precedence over generated ones
+    pub env: BTreeMap<String, String>,
+    /// Program arguments appended to the ones of the session
+    pub args: Vec<String>,
+    /// Adapter specific properties like `sourceMap` or `initCommands`. Objects are merged,
+    /// lists are appended and other values replace the generated ones.
+    pub configuration: Map<String, Value>,
+}
+
+impl DebugOverrides {
+    /// Adds the variables and arguments to `launch`
+    pub fn apply(&self, mut launch: Launch) -> Launch {
+        launch.env.extend(self.env.clone());
+        launch.args.extend(self.args.iter().cloned());
+        launch
+    }
+
+    /// Merges the adapter specific properties into the serialized debug configuration
+    pub fn merge_into(&self, config: &mut Value) {
+        merge(config, Value::Object(self.configuration.clone()));
+    }
+}
+
+fn merge(base: &mut Value, overrides: Value) {
+    match (base, overrides) {
+        (Value::Object(base), Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(base) => merge(base, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(base), Value::Array(overrides)) => base.extend(overrides),
+        (base, overrides) => *base = overrides,
+    }
+}
+
+/// A VS Code debug configuration which debugs a program through a debug server
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteConfiguration {
+    #[serde(rename = "type")]
+    pub debug_type: String,
+    pub request: String,
+    pub name: String,
+    pub program: String,
+    pub cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_languages: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_create_commands: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_create_commands: Option<Vec<String>>,
+    #[serde(
+        rename = "gdb-remote-hostname",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub gdb_remote_hostname: Option<String>,
+    #[serde(rename = "gdb-remote-port", skip_serializing_if = "Option::is_none")]
+    pub gdb_remote_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_map: Option<SourceMap>,
+    #[serde(rename = "MIMode", skip_serializing_if = "Option::is_none")]
+    pub mi_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mi_debugger_server_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mi_debugger_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_file_map: Option<BTreeMap<String, String>>,
+    #[serde(flatten)]
+    pub commands: DebuggerCommands,
+}
+
+/// Source path mappings, CodeLLDB takes an object and lldb-dap a list of pairs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum SourceMap {
+    Object(BTreeMap<String, String>),
+    Pairs(Vec<[String; 2]>),
+}
+
+/// The host a debug session runs on
+#[derive(Debug, Clone, Default)]
+pub struct Host {
+    /// The node platform, e.g. `linux` or `win32`
+    pub platform: String,
+    /// The sysroot of the workspace's toolchain as `rustc --print sysroot` reports it
+    pub sysroot: Option<String>,
+}
+
+/// Debugger commands run before the program starts, e.g. to load the Rust pretty printers
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebuggerCommands {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub init_commands: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_commands: Option<Vec<SetupCommand>>,
+}
+
+impl DebuggerCommands {
+    /// Appends the commands of `other`, which run after the ones of `self`
+    fn chain(mut self, other: Self) -> Self {
+        fn append<T>(commands: &mut Option<Vec<T>>, other: Option<Vec<T>>) {
+            if let Some(other) = other {
+                commands.get_or_insert_with(Vec::new).extend(other);
+            }
+        }
+        append(&mut self.init_commands, other.init_commands);
+        append(&mut self.setup_commands, other.setup_commands);
+        self
+    }
+}
+
+/// A gdb command the C/C++ extension runs before the program starts
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupCommand {
+    pub text: String,
+    pub ignore_failures: bool,
+}
+
+/// The program a debug session is started for
+#[derive(Debug, Clone, Default)]
+pub struct Launch {
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: Option<String>,
+    /// Variables set for the program in addition to the ones of VS Code
+    pub env: BTreeMap<String, String>,
+    /// Whether the debugger follows the child processes the program spawns
+    pub follow_child_processes: bool,
+}
+
+/// A VS Code debug configuration as it would be written to `launch.json`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchConfiguration {
+    #[serde(rename = "type")]
+    pub debug_type: String,
+    pub request: String,
+    pub name: String,
+    pub program: String,
+    pub args: Vec<String>,
+    pub cwd: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Environment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Vec<EnvironmentVariable>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_languages: Option<Vec<String>>,
+    #[serde(rename = "MIMode", skip_serializing_if = "Option::is_none")]
+    pub mi_mode: Option<String>,
+    #[serde(flatten)]
+    pub commands: DebuggerCommands,
+}
+
+/// The `env` of a launch configuration in the format of the debug adapter
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Environment {
+    /// `{"KEY": "value"}` as CodeLLDB takes it
+    Object(BTreeMap<String, String>),
+    /// `["KEY=value"]` as lldb-dap takes it
+    Strings(Vec<String>),
+}
+
+/// An entry of the `environment` of the C/C++ extension's launch configurations
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnvironmentVariable {
+    pub name: String,
+    pub value: String,
+}
+
+/// A running process a debug session is attached to
+#[derive(Debug, Clone)]
+pub struct Attach {
+    pub name: String,
+    pub pid: u32,
+    /// The executable of the process, which provides the debug info
+    pub program: String,
+}
+
+/// A VS Code debug configuration which attaches to a running process
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachConfiguration {
+    #[serde(rename = "type")]
+    pub debug_type: String,
+    pub request: String,
+    pub name: String,
+    pub program: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_languages: Option<Vec<String>>,
+    #[serde(rename = "MIMode", skip_serializing_if = "Option::is_none")]
+    pub mi_mode: Option<String>,
+    #[serde(flatten)]
+    pub commands: DebuggerCommands,
+}
+
+/// A core dump of a crashed program which is loaded into the debugger
+#[derive(Debug, Clone)]
+pub struct CoreDump {
+    pub name: String,
+    /// The executable which crashed, which provides the debug info
+    pub program: String,
+    /// The core file or minidump
+    pub core: String,
+}
+
+/// A VS Code debug configuration which inspects a core dump
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreDumpConfiguration {
+    #[serde(rename = "type")]
+    pub debug_type: String,
+    pub request: String,
+    pub name: String,
+    pub program: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_languages: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_create_commands: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_create_commands: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_dump_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dump_path: Option<String>,
+    #[serde(rename = "MIMode", skip_serializing_if = "Option::is_none")]
+    pub mi_mode: Option<String>,
+    #[serde(flatten)]
+    pub commands: DebuggerCommands,
+}
+
+/// A process running on the host
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningProcess {
+    pub pid: u32,
+    /// The command line of the process, only the image name on windows
+    pub command: String,
+}
+
+impl RunningProcess {
+    /// Parses the output of `ps -A -o pid= -o args=`
+    pub fn parse_ps(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let (pid, command) = line.trim().split_once(char::is_whitespace)?;
+                Some(Self {
+                    pid: pid.parse().ok()?,
+                    command: command.trim().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Parses the output of `tasklist /FO CSV /NH`, e.g. `"app.exe","1234","Console","1","8,120 K"`
+    pub fn parse_tasklist(output: &str) -> Vec<Self> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let mut columns = line.trim().split("\",\"");
+                let command = columns.next()?.trim_start_matches('"');
+                Some(Self {
+                    pid: columns.next()?.parse().ok()?,
+                    command: command.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the process runs `executable`, either started with its absolute path or with
+    /// a relative one like `./target/debug/app`.
+    pub fn runs(&self, executable: &str) -> bool {
+        let command = self.command.replace('\\', "/");
+        let executable = executable.replace('\\', "/");
+        if command == executable || command.starts_with(&format!("{executable} ")) {
+            return true;
+        }
+
+        let program = command
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .trim_start_matches("./");
+        !program.is_empty()
+            && !program.starts_with('/')
+            && (executable == program || executable.ends_with(&format!("/{program}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn select_debugger() {
+        let installed = |ids: &'static [&'static str]| move |id: &str| ids.contains(&id);
+
+        check!(Debugger::select("lldb-dap", "linux", installed(&[])) == Some(Debugger::LldbDap));
+        check!(
+            Debugger::select("auto", "linux", installed(&["ms-vscode.cpptools"]))
+                == Some(Debugger::CppDbg)
+        );
+        check!(
+            Debugger::select("auto", "win32", installed(&["ms-vscode.cpptools"]))
+                == Some(Debugger::CppVsDbg)
+        );
+        check!(
+            Debugger::select(
+                "auto",
+                "darwin",
+                installed(&["ms-vscode.cpptools", "vadimcn.vscode-lldb"])
+            ) == Some(Debugger::CodeLldb)
+        );
+        check!(Debugger::select("auto", "linux", installed(&[])).is_none());
+    }
+
+    fn host(platform: &str) -> Host {
+        Host {
+            platform: platform.to_string(),
+            sysroot: None,
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn launch_configuration_of_debugger() {
+        let launch = Launch {
+            name: "Debug cli".to_string(),
+            program: "/ws/target/debug/cli".to_string(),
+            args: vec!["--verbose".to_string()],
+            cwd: Some("/ws".to_string()),
+            env: BTreeMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+            follow_child_processes: false,
+        };
+
+        let lldb = Debugger::CodeLldb.launch_configuration(launch.clone(), &host("linux"));
+        check!(lldb.debug_type == "lldb");
+        check!(lldb.source_languages == Some(vec!["rust".to_string()]));
+        check!(lldb.mi_mode.is_none());
+        check!(lldb.env == Some(Environment::Object(launch.env.clone())));
+
+        let lldb_dap = Debugger::LldbDap.launch_configuration(launch.clone(), &host("linux"));
+        check!(lldb_dap.env == Some(Environment::Strings(vec!["RUST_LOG=debug".to_string()])));
+
+        let gdb = Debugger::CppDbg.launch_configuration(launch.clone(), &host("linux"));
+        check!(gdb.debug_type == "cppdbg");
+        check!(gdb.mi_mode.as_deref() == Some("gdb"));
+        check!(gdb.program == "/ws/target/debug/cli");
+        check!(gdb.cwd == "/ws");
+        check!(gdb.env.is_none());
+        check!(
+            gdb.environment
+                == Some(vec![EnvironmentVariable {
+                    name: "RUST_LOG".to_string(),
+                    value: "debug".to_string()
+                }])
+        );
+
+        let mac = Debugger::CppDbg.launch_configuration(launch, &host("darwin"));
+        check!(mac.mi_mode.as_deref() == Some("lldb"));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn attach_configuration_of_debugger() {
+        let attach = Attach {
+            name: "Attach cli".to_string(),
+            pid: 42,
+            program: "/ws/target/debug/cli".to_string(),
+        };
+
+        let lldb = Debugger::CodeLldb.attach_configuration(attach.clone(), &host("linux"));
+        check!(lldb.request == "attach");
+        check!(lldb.pid == Some(42));
+        check!(lldb.process_id.is_none());
+
+        let gdb = Debugger::CppDbg.attach_configuration(attach, &host("linux"));
+        check!(gdb.pid.is_none());
+        check!(gdb.process_id.as_deref() == Some("42"));
+        check!(gdb.program == "/ws/target/debug/cli");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn core_dump_configuration_of_debugger() {
+        let core_dump = CoreDump {
+            name: "Core dump of cli".to_string(),
+            program: "/ws/target/debug/cli".to_string(),
+            core: "/tmp/core.42".to_string(),
+        };
+
+        let lldb = Debugger::CodeLldb.core_dump_configuration(core_dump.clone(), &host("linux"));
+        check!(lldb.request == "custom");
+        check!(
+            lldb.target_create_commands
+                == Some(vec![
+                    r#"target create -c "/tmp/core.42" "/ws/target/debug/cli""#.to_string()
+                ])
+        );
+        check!(lldb.process_create_commands == Some(Vec::new()));
+
+        let lldb_dap = Debugger::LldbDap.core_dump_configuration(core_dump.clone(), &host("linux"));
+        check!(lldb_dap.request == "attach");
+        check!(lldb_dap.core_file.as_deref() == Some("/tmp/core.42"));
+
+        let gdb = Debugger::CppDbg.core_dump_configuration(core_dump.clone(), &host("linux"));
+        check!(gdb.request == "launch");
+        check!(gdb.core_dump_path.as_deref() == Some("/tmp/core.42"));
+        check!(gdb.mi_mode.as_deref() == Some("gdb"));
+
+        let msvc = Debugger::CppVsDbg.core_dump_configuration(core_dump, &host("win32"));
+        check!(msvc.dump_path.as_deref() == Some("/tmp/core.42"));
+        check!(msvc.core_dump_path.is_none());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn pretty_printers_of_sysroot() {
+        let toolchain = Host {
+            platform: "linux".to_string(),
+            sysroot: Some("/rust/toolchains/stable".to_string()),
+        };
+        let launch = Launch::default();
+
+        let lldb_dap = Debugger::LldbDap.launch_configuration(launch.clone(), &toolchain);
+        check!(
+            lldb_dap.commands.init_commands
+                == Some(vec![
+                    r#"command script import "/rust/toolchains/stable/lib/rustlib/etc/lldb_lookup.py""#
+                        .to_string(),
+                    r#"command source -s 0 "/rust/toolchains/stable/lib/rustlib/etc/lldb_commands""#
+                        .to_string(),
+                ])
+        );
+
+        let gdb = Debugger::CppDbg.launch_configuration(launch.clone(), &toolchain);
+        let setup_commands = gdb.commands.setup_commands.unwrap_or_default();
+        check!(setup_commands.len() == 3);
+        check!(setup_commands[1].text == "directory /rust/toolchains/stable/lib/rustlib/etc");
+
+        let lldb = Debugger::CodeLldb.launch_configuration(launch.clone(), &toolchain);
+        check!(lldb.commands == DebuggerCommands::default());
+
+        let without_sysroot = Debugger::LldbDap.launch_configuration(launch, &host("linux"));
+        check!(without_sysroot.commands == DebuggerCommands::default());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn follow_child_processes_of_debugger() {
+        let toolchain = Host {
+            platform: "linux".to_string(),
+            sysroot: Some("/rust".to_string()),
+        };
+        let launch = Launch {
+            follow_child_processes: true,
+            ..Default::default()
+        };
+
+        let lldb_dap = Debugger::LldbDap.launch_configuration(launch.clone(), &toolchain);
+        let init_commands = lldb_dap.commands.init_commands.unwrap_or_default();
+        // The pretty printers are loaded first
+        check!(init_commands.len() == 3);
+        check!(init_commands[2] == "settings set target.process.follow-fork-mode child");
+
+        let gdb = Debugger::CppDbg.launch_configuration(launch.clone(), &host("linux"));
+        let setup_commands: Vec<String> = gdb
+            .commands
+            .setup_commands
+            .unwrap_or_default()
+            .into_iter()
+            .map(|c| c.text)
+            .collect();
+        check!(
+            setup_commands
+                == vec![
+                    "-gdb-set follow-fork-mode child".to_string(),
+                    "-gdb-set detach-on-fork off".to_string(),
+                ]
+        );
+
+        check!(!Debugger::CppVsDbg.follows_child_processes("win32"));
+        check!(!Debugger::CodeLldb.follows_child_processes("darwin"));
+        let mac = Debugger::CppDbg.launch_configuration(launch, &host("darwin"));
+        check!(mac.commands == DebuggerCommands::default());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn overrides_merge_into_configuration() {
+        let overrides: DebugOverrides = serde_json::from_str(
+            r#"{
+                "env": {"RUST_LOG": "trace"},
+                "args": ["--port", "80"],
+                "configuration": {
+                    "initCommands": ["settings set target.x86-disassembly-flavor intel"],
+                    "sourceMap": {"/rustc/abc": "/src/rust"},
+                    "stopOnEntry": true
+                }
+            }"#,
+        )
+        .unwrap();
+        let launch = Launch {
+            args: vec!["--verbose".to_string()],
+            env: BTreeMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+            ..Default::default()
+        };
+
+        let launch = overrides.apply(launch);
+        check!(launch.args == ["--verbose", "--port", "80"]);
+        check!(launch.env["RUST_LOG"] == "trace");
+
+        let mut config = serde_json::json!({
+            "type": "lldb",
+            "initCommands": ["command source a"],
+            "sourceMap": {"/project": "/ws"},
+            "stopOnEntry": false
+        });
+        overrides.merge_into(&mut config);
+        check!(
+            config
+                == serde_json::json!({
+                    "type": "lldb",
+                    "initCommands": [
+                        "command source a",
+                        "settings set target.x86-disassembly-flavor intel"
+                    ],
+                    "sourceMap": {"/project": "/ws", "/rustc/abc": "/src/rust"},
+                    "stopOnEntry": true
+                })
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn terminal_debugger_of_platform() {
+        check!(TerminalDebugger::available("win32").is_empty());
+        check!(TerminalDebugger::available("darwin") == vec![TerminalDebugger::RustLldb]);
+
+        let args = vec!["--verbose".to_string()];
+        check!(
+            TerminalDebugger::RustGdb.args("/ws/target/debug/cli", &args)
+                == ["--args", "/ws/target/debug/cli", "--verbose"]
+        );
+        check!(
+            TerminalDebugger::RustLldb.args("/ws/target/debug/cli", &args)
+                == ["--", "/ws/target/debug/cli", "--verbose"]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn running_processes_of_executable() {
+        let ps = "    1 /sbin/init\n  420 /ws/target/debug/cli --port 80\n  421 ./target/debug/cli\n  422 /usr/bin/cli\n";
+        let tasklist = "\"System\",\"4\",\"Services\",\"0\",\"144 K\"\n\"cli.exe\",\"1234\",\"Console\",\"1\",\"8,120 K\"\n";
+
+        let pids = |processes: Vec<RunningProcess>, executable: &str| {
+            processes
+                .into_iter()
+                .filter(|p| p.runs(executable))
+                .map(|p| p.pid)
+                .collect::<Vec<_>>()
+        };
+
+        check!(pids(RunningProcess::parse_ps(ps), "/ws/target/debug/cli") == vec![420, 421]);
+        check!(
+            pids(
+                RunningProcess::parse_tasklist(tasklist),
+                "C:\\ws\\target\\debug\\cli.exe"
+            ) == vec![1234]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn remote_configuration_of_debugger() {
+        let remote = RemoteTarget {
+            runner: Some("qemu-aarch64 -g ${port} ${program}".to_string()),
+            host: "localhost".to_string(),
+            port: 1234,
+            debugger_path: Some("gdb-multiarch".to_string()),
+            source_map: BTreeMap::from([("/project".to_string(), "/ws".to_string())]),
+        };
+        let launch = Launch {
+            name: "Debug cli".to_string(),
+            program: "/ws/target/aarch64-unknown-linux-gnu/debug/cli".to_string(),
+            args: vec!["--verbose".to_string()],
+            cwd: None,
+            env: BTreeMap::new(),
+            follow_child_processes: false,
+        };
+
+        check!(
+            remote.runner_command(&launch.program, &launch.args)
+                == Some(vec![
+                    "qemu-aarch64".to_string(),
+                    "-g".to_string(),
+                    "1234".to_string(),
+                    "/ws/target/aarch64-unknown-linux-gnu/debug/cli".to_string(),
+                    "--verbose".to_string(),
+                ])
+        );
+
+        let gdb = Debugger::CppDbg
+            .remote_configuration(launch.clone(), &remote, &host("linux"))
+            .unwrap();
+        check!(gdb.mi_debugger_server_address.as_deref() == Some("localhost:1234"));
+        check!(gdb.mi_debugger_path.as_deref() == Some("gdb-multiarch"));
+        check!(gdb.source_file_map == Some(remote.source_map.clone()));
+
+        let lldb = Debugger::CodeLldb
+            .remote_configuration(launch.clone(), &remote, &host("linux"))
+            .unwrap();
+        check!(lldb.request == "custom");
+        check!(lldb.process_create_commands == Some(vec!["gdb-remote localhost:1234".to_string()]));
+
+        let lldb_dap = Debugger::LldbDap
+            .remote_configuration(launch.clone(), &remote, &host("linux"))
+            .unwrap();
+        check!(lldb_dap.gdb_remote_port == Some(1234));
+        check!(
+            lldb_dap.source_map
+                == Some(SourceMap::Pairs(vec![[
+                    "/project".to_string(),
+                    "/ws".to_string()
+                ]]))
+        );
+
+        check!(
+            Debugger::CppVsDbg
+                .remote_configuration(launch, &remote, &host("win32"))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use crate::cargo::Profile;
+
+/// Returns the env files which apply to `profile`, in the order they have to be merged.
+///
+/// Next to `env_file` itself a profile specific variant `<env_file>.<profile>` (e.g. `.env.release`)
+/// is used which overrides values of the general one.
+pub fn env_file_paths(root_dir: &str, env_file: &str, profile: &Profile) -> Vec<String> {
+    if env_file.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let path = if env_file.starts_with('/') || env_file.contains(':') {
+        env_file.to_string()
+    } else {
+        format!("{root_dir}/{env_file}")
+    };
+
+    let profile_path = profile.get_name().map(|name| format!("{path}.{name}"));
+    std::iter::once(path).chain(profile_path).collect()
+}
+
+/// Parses the content of a `.env` file.
+///
+/// Supports `KEY=value` lines with optional `export` prefix, single and double quoted values
+/// and `#` comments. Lines which are no valid assignment are ignored.
+pub fn parse(content: &str) -> HashMap<String, String> {
+    content.lines().filter_map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let line = line.strip_prefix("export ").unwrap_or(line);
+    let (key, value) = line.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() || key.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some((key.to_string(), parse_value(value.trim())))
+}
+
+fn parse_value(value: &str) -> String {
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote)
+            && let Some(end) = rest.find(quote)
+        {
+            let quoted = &rest[..end];
+            return if quote == '"' {
+                quoted.replace("\\n", "\n").replace("\\\"", "\"")
+            } else {
+                quoted.to_string()
+            };
+        }
+    }
+
+    // Unquoted values end at an inline comment
+    match value.find(" #") {
+        Some(end) => value[..end].trim_end().to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn parse_env_file() {
+        let content = r#"
+# database settings
+DATABASE_URL=postgres://localhost/db
+export RUST_LOG = debug # verbose
+GREETING="hello world"
+SINGLE='single # quoted'
+EMPTY=
+not a valid line
+"#;
+        let env = parse(content);
+
+        check!(env.len() == 5);
+        check!(env["DATABASE_URL"] == "postgres://localhost/db");
+        check!(env["RUST_LOG"] == "debug");
+        check!(env["GREETING"] == "hello world");
+        check!(env["SINGLE"] == "single # quoted");
+        check!(env["EMPTY"] == "");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn profile_env_file_paths() {
+        check!(env_file_paths("/ws", ".env", &Profile::None) == vec!["/ws/.env"]);
+        check!(
+            env_file_paths("/ws", ".env", &Profile::Release)
+                == vec!["/ws/.env", "/ws/.env.release"]
+        );
+        check!(
+            env_file_paths("/ws", "/abs/vars.env", &Profile::Dev)
+                == vec!["/abs/vars.env", "/abs/vars.env.dev"]
+        );
+        check!(env_file_paths("/ws", "", &Profile::Dev).is_empty());
+    }
+}
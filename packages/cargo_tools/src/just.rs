@@ -0,0 +1,203 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::process::{CargoCommandEmpty, CargoTaskContext, Process};
+
+/// The command runner of `justfile`s
+pub const JUST: &str = "just";
+
+/// The names a justfile can have, in the order just looks for them
+pub const JUSTFILE_NAMES: [&str; 3] = ["justfile", "Justfile", ".justfile"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JustRecipe {
+    pub name: String,
+    /// The comment above the recipe
+    pub doc: Option<String>,
+    pub parameters: Vec<JustParameter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JustParameter {
+    pub name: String,
+    /// Whether an argument has to be passed, i.e. the parameter has no default and isn't
+    /// variadic like `*flags`
+    pub required: bool,
+}
+
+impl JustRecipe {
+    /// The process running the recipe `name` of `justfile` with `args`, `ctx` is a context of
+    /// [JUST]
+    pub fn try_into_process(
+        name: String,
+        justfile: String,
+        args: Vec<String>,
+        ctx: CargoTaskContext,
+    ) -> Result<Process, CargoCommandEmpty> {
+        let mut just_args = vec!["--justfile".to_string(), justfile, name];
+        just_args.extend(args);
+        ctx.try_into_process(just_args)
+    }
+
+    /// Whether the recipe can't run without arguments
+    pub fn requires_args(&self) -> bool {
+        self.parameters.iter().any(|p| p.required)
+    }
+
+    /// The parameters like just lists them, e.g. `host *flags`
+    pub fn parameters_display(&self) -> String {
+        self.parameters
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn keep(&self, filter: &str) -> bool {
+        filter.is_empty() || self.name.to_lowercase().contains(filter)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JustRecipes(Vec<JustRecipe>);
+
+impl JustRecipes {
+    pub fn filtered(&self, filter: &str) -> Self {
+        let filter = filter.to_lowercase();
+        let recipes = self.iter().filter(|r| r.keep(&filter)).cloned().collect();
+        Self(recipes)
+    }
+}
+
+impl Deref for JustRecipes {
+    type Target = Vec<JustRecipe>;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for JustRecipes {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error(transparent)]
+    CargoCommandEmpty(CargoCommandEmpty),
+    #[error("just is not installed: {0}")]
+    JustNotInstalled(String),
+    #[error("Failed to retrieve the recipes of the justfile: {0}")]
+    FailedToRetrieve(String),
+}
+
+#[derive(Deserialize)]
+struct Dump {
+    recipes: BTreeMap<String, DumpRecipe>,
+}
+
+#[derive(Deserialize)]
+struct DumpRecipe {
+    name: String,
+    doc: Option<String>,
+    #[serde(default)]
+    private: bool,
+    #[serde(default)]
+    parameters: Vec<DumpParameter>,
+}
+
+#[derive(Deserialize)]
+struct DumpParameter {
+    name: String,
+    default: Option<serde_json::Value>,
+    kind: String,
+}
+
+/// The public recipes of `justfile`, sorted by name. `ctx` is a context of [JUST].
+pub async fn parse_recipes(
+    justfile: String,
+    ctx: CargoTaskContext,
+    exec: impl AsyncFn(Process) -> Result<String, String>,
+) -> Result<JustRecipes, ParseError> {
+    let version = ctx
+        .clone()
+        .try_into_process(vec!["--version".to_string()])
+        .map_err(ParseError::CargoCommandEmpty)?;
+    exec(version).await.map_err(ParseError::JustNotInstalled)?;
+
+    let args = ["--justfile", &justfile, "--dump", "--dump-format", "json"]
+        .map(str::to_string)
+        .to_vec();
+    let dump = ctx
+        .try_into_process(args)
+        .map_err(ParseError::CargoCommandEmpty)?;
+    let output = exec(dump).await.map_err(ParseError::FailedToRetrieve)?;
+    parse_dump(&output)
+}
+
+/// Parses the output of `just --dump --dump-format json`
+fn parse_dump(output: &str) -> Result<JustRecipes, ParseError> {
+    let dump: Dump =
+        serde_json::from_str(output).map_err(|e| ParseError::FailedToRetrieve(e.to_string()))?;
+    let recipes = dump
+        .recipes
+        .into_values()
+        .filter(|recipe| !recipe.private)
+        .map(|recipe| JustRecipe {
+            name: recipe.name,
+            doc: recipe.doc,
+            parameters: recipe
+                .parameters
+                .into_iter()
+                .map(|p| JustParameter {
+                    required: p.default.is_none() && p.kind != "star",
+                    name: p.name,
+                })
+                .collect(),
+        })
+        .collect();
+    Ok(JustRecipes(recipes))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn public_recipes_of_dump() {
+        let recipes = parse_dump(include_str!("../res/test-justfile-dump.json")).unwrap();
+        let names: Vec<&str> = recipes.iter().map(|r| r.name.as_str()).collect();
+        check!(names == ["build", "deploy", "lint"]);
+
+        let build = &recipes[0];
+        check!(build.doc.as_deref() == Some("Build the workspace"));
+        check!(!build.requires_args());
+        let deploy = &recipes[1];
+        check!(deploy.requires_args());
+        check!(deploy.parameters_display() == "host flags");
+        check!(!deploy.parameters[1].required);
+
+        check!(recipes.filtered("DEP").len() == 1);
+
+        let ctx = CargoTaskContext::new(HashMap::new(), Vec::new(), JUST.to_string());
+        let process = JustRecipe::try_into_process(
+            "deploy".to_string(),
+            "/ws/justfile".to_string(),
+            vec!["pi.local".to_string()],
+            ctx,
+        )
+        .unwrap();
+        check!(process.cmd() == "just");
+        check!(process.args() == ["--justfile", "/ws/justfile", "deploy", "pi.local"]);
+    }
+}
@@ -1,5 +1,23 @@
+pub mod backtrace;
 pub mod cargo;
 pub use cargo::Command as CargoCommand;
 pub mod cargo_make;
+pub mod cbindgen;
+pub mod completion_hook;
+pub mod crates_io;
+pub mod debugger;
+pub mod env_file;
+pub mod just;
+pub mod output;
+pub mod path_mapping;
+pub mod probe_rs;
 pub mod process;
+pub mod rust_analyzer;
+pub mod rust_source;
+pub mod rustup;
+pub mod sccache;
+pub mod selection;
+pub mod shell_env;
+pub mod stable_hash;
+pub mod wasm;
 pub mod xtask;
@@ -0,0 +1,168 @@
+use std::collections::VecDeque;
+
+/// A ring buffer of the last lines of a command output, so long outputs like builds with
+/// hundreds of warnings don't slow down the panel showing them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BoundedOutput {
+    lines: VecDeque<String>,
+    /// The last line until its line break arrives
+    partial: String,
+    /// Number of lines kept, unbounded if 0
+    max_lines: usize,
+    /// Number of lines which were dropped
+    dropped: usize,
+}
+
+impl BoundedOutput {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            max_lines,
+            ..Self::default()
+        }
+    }
+
+    /// Appends `chunk`, which doesn't need to end at a line break, dropping the oldest lines
+    /// beyond the maximum
+    pub fn push(&mut self, chunk: &str) {
+        let mut rest = chunk;
+        while let Some(end) = rest.find('\n') {
+            self.partial.push_str(rest[..end].trim_end_matches('\r'));
+            self.lines.push_back(std::mem::take(&mut self.partial));
+            rest = &rest[end + 1..];
+            if self.max_lines > 0 && self.lines.len() > self.max_lines {
+                self.lines.pop_front();
+                self.dropped += 1;
+            }
+        }
+        self.partial.push_str(rest);
+    }
+
+    /// Number of lines which were dropped
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty() && self.partial.is_empty() && self.dropped == 0
+    }
+
+    /// The kept lines, preceded by a note about the dropped ones which refers to `log_file`
+    /// with the full output
+    pub fn text(&self, log_file: &str) -> String {
+        let mut text = String::new();
+        if self.dropped > 0 {
+            text.push_str(&format!(
+                "... {} earlier lines omitted, the full output is in {log_file}\n",
+                self.dropped
+            ));
+        }
+        for line in &self.lines {
+            text.push_str(line);
+            text.push('\n');
+        }
+        text.push_str(&self.partial);
+        text
+    }
+}
+
+/// The output of a task for its terminal: the first lines are shown as they arrive, the
+/// following ones are kept in a [BoundedOutput] until the task finished
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreamedOutput {
+    /// Number of lines shown as they arrived, unbounded if 0
+    max_lines: usize,
+    shown: usize,
+    rest: BoundedOutput,
+}
+
+impl StreamedOutput {
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            max_lines,
+            shown: 0,
+            rest: BoundedOutput::new(max_lines),
+        }
+    }
+
+    /// Appends `chunk` and returns the part of it to show right away
+    pub fn push(&mut self, chunk: &str) -> String {
+        if self.max_lines == 0 {
+            return chunk.to_string();
+        }
+        let mut shown = String::new();
+        let mut rest = chunk;
+        while self.shown < self.max_lines {
+            let Some(end) = rest.find('\n') else {
+                shown.push_str(rest);
+                return shown;
+            };
+            shown.push_str(&rest[..=end]);
+            rest = &rest[end + 1..];
+            self.shown += 1;
+        }
+        self.rest.push(rest);
+        shown
+    }
+
+    /// Whether output is held back until the task finished
+    pub fn shortened(&self) -> bool {
+        !self.rest.is_empty()
+    }
+
+    /// The kept output after the shown lines, preceded by a note about the dropped ones which
+    /// refers to `log_file` with the full output
+    pub fn finish(&self, log_file: &str) -> String {
+        self.rest.text(log_file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn keeps_the_last_lines() {
+        let mut output = BoundedOutput::new(2);
+        output.push("warning: unused\r\nwarning: dead");
+        output.push(" code\nerror: mismatched types\n");
+        output.push("Finished");
+
+        check!(output.dropped() == 1);
+        check!(
+            output.text("/ws/target/cargo-tools/test-output.log")
+                == "... 1 earlier lines omitted, the full output is in /ws/target/cargo-tools/test-output.log\nwarning: dead code\nerror: mismatched types\nFinished"
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn unbounded_without_maximum() {
+        let mut output = BoundedOutput::new(0);
+        output.push(&"line\n".repeat(100));
+
+        check!(output.dropped() == 0);
+        check!(output.text("log") == "line\n".repeat(100));
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn shows_the_first_lines_and_keeps_the_last() {
+        let mut output = StreamedOutput::new(2);
+        check!(output.push("Compiling a\nCompil") == "Compiling a\nCompil");
+        check!(!output.shortened());
+        check!(output.push("ing b\nwarning: unused\nwarning: dead\n") == "ing b\n");
+        check!(output.shortened());
+        output.push("error: mismatched types\nFinished");
+
+        check!(
+            output.finish("/ws/build.log")
+                == "... 1 earlier lines omitted, the full output is in /ws/build.log\nwarning: dead\nerror: mismatched types\nFinished"
+        );
+
+        let mut unbounded = StreamedOutput::new(0);
+        check!(unbounded.push(&"line\n".repeat(10)) == "line\n".repeat(10));
+        check!(!unbounded.shortened());
+        check!(unbounded.finish("log").is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+/// Maps the paths cargo reports to the paths of the extension host and back, for cargo
+/// commands running on another system than VS Code, e.g. `wsl cargo` on Windows. Remote-SSH,
+/// WSL and dev container sessions run the extension next to cargo and need no mapping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathMappings {
+    /// Path prefixes of cargo and the extension host, the longest cargo prefix first
+    prefixes: Vec<(String, String)>,
+    /// Whether cargo runs in WSL, its drives are mounted at `/mnt/<drive>`
+    wsl: bool,
+    /// The WSL distribution cargo runs in, [None] while the default one isn't known
+    wsl_distro: Option<String>,
+}
+
+/// Whether `path` is a Windows path like `C:\ws` or `\\wsl.localhost\Ubuntu\home`
+fn is_windows_path(path: &str) -> bool {
+    path.starts_with("\\\\") || path.as_bytes().get(1) == Some(&b':')
+}
+
+/// `rest` of a path with the separators of the path `prefix` belongs to
+fn join(prefix: &str, rest: &str) -> String {
+    if is_windows_path(prefix) {
+        format!("{prefix}{}", rest.replace('/', "\\"))
+    } else {
+        format!("{prefix}{}", rest.replace('\\', "/"))
+    }
+}
+
+/// Strips `prefix` from `path` if it is followed by a separator or ends the path
+fn strip_path_prefix<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(prefix.trim_end_matches(['/', '\\']))?;
+    (rest.is_empty() || rest.starts_with(['/', '\\'])).then_some(rest)
+}
+
+impl PathMappings {
+    /// The mappings of `cargoTools.pathMappings` from cargo path prefixes to host path
+    /// prefixes, and the WSL distribution of a cargo command like `wsl -d Ubuntu cargo`,
+    /// `default_wsl_distro` if it doesn't name one
+    pub fn new(
+        prefixes: HashMap<String, String>,
+        cargo_command: &str,
+        default_wsl_distro: Option<&str>,
+    ) -> Self {
+        let mut prefixes: Vec<_> = prefixes.into_iter().collect();
+        prefixes.sort_by_key(|(cargo, _)| std::cmp::Reverse(cargo.len()));
+        let wsl = is_wsl_command(cargo_command);
+        Self {
+            prefixes,
+            wsl,
+            wsl_distro: wsl
+                .then(|| wsl_distro(cargo_command).or(default_wsl_distro))
+                .flatten()
+                .map(str::to_string),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && !self.wsl
+    }
+
+    /// The host path of the `path` cargo reports
+    pub fn to_host(&self, path: &str) -> String {
+        for (cargo, host) in &self.prefixes {
+            if let Some(rest) = strip_path_prefix(path, cargo) {
+                return join(host.trim_end_matches(['/', '\\']), rest);
+            }
+        }
+        if !self.wsl || is_windows_path(path) || !path.starts_with('/') {
+            return path.to_string();
+        }
+        let drive = path
+            .strip_prefix("/mnt/")
+            .filter(|rest| rest.len() == 1 || rest.as_bytes().get(1) == Some(&b'/'))
+            .and_then(|rest| rest.chars().next());
+        match (drive, &self.wsl_distro) {
+            (Some(drive), _) => join(&format!("{}:", drive.to_ascii_uppercase()), &path[6..]),
+            (None, Some(distro)) => join(&format!("\\\\wsl.localhost\\{distro}"), path),
+            (None, None) => path.to_string(),
+        }
+    }
+
+    /// The path cargo understands for the host `path`, e.g. of `--manifest-path`
+    pub fn to_cargo(&self, path: &str) -> String {
+        for (cargo, host) in &self.prefixes {
+            if let Some(rest) = strip_path_prefix(path, host) {
+                return join(cargo.trim_end_matches(['/', '\\']), rest);
+            }
+        }
+        if !self.wsl {
+            return path.to_string();
+        }
+        if let Some(distro) = &self.wsl_distro {
+            for share in ["\\\\wsl.localhost\\", "\\\\wsl$\\"] {
+                if let Some(rest) = strip_path_prefix(path, &format!("{share}{distro}")) {
+                    return join("", rest);
+                }
+            }
+        }
+        match path.split_once(":\\") {
+            Some((drive, rest)) if drive.len() == 1 => {
+                join(&format!("/mnt/{}/", drive.to_ascii_lowercase()), rest)
+            }
+            _ => path.to_string(),
+        }
+    }
+}
+
+/// Whether `cargo_command` runs cargo with `wsl`
+pub fn is_wsl_command(cargo_command: &str) -> bool {
+    matches!(
+        cargo_command.split_whitespace().next(),
+        Some("wsl" | "wsl.exe")
+    )
+}
+
+/// The distribution `cargo_command` names with `-d` or `--distribution`
+pub fn wsl_distro(cargo_command: &str) -> Option<&str> {
+    cargo_command
+        .split_whitespace()
+        .skip_while(|part| *part != "-d" && *part != "--distribution")
+        .nth(1)
+}
+
+/// The default distribution in the output of `wsl -l`, which marks it with `(Default)`.
+/// `wsl.exe` writes UTF-16, read as UTF-8 every other byte is a NUL.
+pub fn parse_default_wsl_distro(output: &str) -> Option<String> {
+    output
+        .replace('\0', "")
+        .lines()
+        .find_map(|line| Some(line.trim().strip_suffix("(Default)")?.trim().to_string()))
+        .filter(|distro| !distro.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn wsl_and_configured_mappings() {
+        let wsl = PathMappings::new(HashMap::new(), "wsl -d Debian cargo", Some("Ubuntu"));
+        check!(wsl.to_host("/mnt/c/ws/Cargo.toml") == "C:\\ws\\Cargo.toml");
+        check!(
+            wsl.to_host("/home/me/ws/target") == "\\\\wsl.localhost\\Debian\\home\\me\\ws\\target"
+        );
+        check!(wsl.to_cargo("C:\\ws\\Cargo.toml") == "/mnt/c/ws/Cargo.toml");
+        check!(wsl.to_cargo("\\\\wsl$\\Debian\\home\\me\\ws") == "/home/me/ws");
+        check!(wsl.to_host("C:\\already\\host") == "C:\\already\\host");
+
+        let default = PathMappings::new(HashMap::new(), "wsl cargo", Some("Ubuntu"));
+        check!(default.to_host("/home/me") == "\\\\wsl.localhost\\Ubuntu\\home\\me");
+        let unknown = PathMappings::new(HashMap::new(), "wsl cargo", None);
+        check!(unknown.to_host("/home/me") == "/home/me");
+        check!(unknown.to_cargo("D:\\ws") == "/mnt/d/ws");
+
+        let native = PathMappings::new(HashMap::new(), "cargo +nightly", Some("Ubuntu"));
+        check!(native.is_empty());
+        check!(native.to_host("/ws/Cargo.toml") == "/ws/Cargo.toml");
+
+        let container = PathMappings::new(
+            HashMap::from([
+                ("/workspaces/app".to_string(), "/home/me/app".to_string()),
+                ("/workspaces".to_string(), "/home/me/src".to_string()),
+            ]),
+            "docker exec dev cargo",
+            None,
+        );
+        check!(container.to_host("/workspaces/app/src/main.rs") == "/home/me/app/src/main.rs");
+        check!(container.to_host("/workspaces/lib/Cargo.toml") == "/home/me/src/lib/Cargo.toml");
+        check!(container.to_host("/workspacesx/a") == "/workspacesx/a");
+        check!(container.to_cargo("/home/me/app/Cargo.toml") == "/workspaces/app/Cargo.toml");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn default_wsl_distro_is_parsed() {
+        let output =
+            "Windows Subsystem for Linux Distributions:\r\nDebian\r\nUbuntu-22.04 (Default)\r\n";
+        check!(parse_default_wsl_distro(output).as_deref() == Some("Ubuntu-22.04"));
+        let utf16: String = "Arch (Default)\r\n"
+            .chars()
+            .flat_map(|c| [c, '\0'])
+            .collect();
+        check!(parse_default_wsl_distro(&utf16).as_deref() == Some("Arch"));
+        check!(parse_default_wsl_distro("Ubuntu\r\n").is_none());
+    }
+}
@@ -0,0 +1,58 @@
+/// The command which flashes executables to embedded targets and streams their RTT output
+pub const PROBE_RS: &str = "probe-rs";
+
+/// Whether executables of `platform_target` run on a microcontroller, e.g. `thumbv7em-none-eabihf`
+/// or `riscv32imac-unknown-none-elf`
+pub fn is_embedded_target(platform_target: &str) -> bool {
+    platform_target.starts_with("thumbv") || platform_target.contains("-none")
+}
+
+/// What probe-rs does with the ELF of a run target
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeRsCommand {
+    /// Flashes the ELF, resets the target and streams its RTT output
+    Run,
+    /// Attaches to the running firmware of the ELF and streams its RTT output
+    Attach,
+}
+
+impl ProbeRsCommand {
+    /// The probe-rs arguments for the ELF `elf` on the chip `chip`, probe-rs asks for the chip
+    /// if none is given
+    pub fn args(self, elf: String, chip: Option<String>) -> Vec<String> {
+        let subcommand = match self {
+            Self::Run => "run",
+            Self::Attach => "attach",
+        };
+        std::iter::once(subcommand.to_string())
+            .chain(
+                chip.into_iter()
+                    .flat_map(|chip| ["--chip".to_string(), chip]),
+            )
+            .chain(std::iter::once(elf))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn embedded_targets_and_args() {
+        check!(is_embedded_target("thumbv7em-none-eabihf"));
+        check!(is_embedded_target("riscv32imac-unknown-none-elf"));
+        check!(!is_embedded_target("x86_64-unknown-linux-gnu"));
+        check!(!is_embedded_target("aarch64-apple-darwin"));
+
+        let elf = "/ws/target/thumbv7em-none-eabihf/debug/blinky".to_string();
+        check!(
+            ProbeRsCommand::Run.args(elf.clone(), Some("nRF52840_xxAA".to_string()))
+                == ["run", "--chip", "nRF52840_xxAA", elf.as_str()]
+        );
+        check!(ProbeRsCommand::Attach.args(elf.clone(), None) == ["attach", elf.as_str()]);
+    }
+}
@@ -1,15 +1,23 @@
 use std::collections::HashMap;
 
 /// Represents a task that can be executed e.g. on the command line
+#[derive(Debug, Clone)]
 pub struct Process {
     cmd: String,
     args: Vec<String>,
     env: HashMap<String, String>,
+    /// Directory to run in, the host's default if [None]
+    cwd: Option<String>,
 }
 
 impl Process {
     pub fn new(cmd: String, args: Vec<String>, env: HashMap<String, String>) -> Self {
-        Self { cmd, args, env }
+        Self {
+            cmd,
+            args,
+            env,
+            cwd: None,
+        }
     }
     pub fn cmd(&self) -> &str {
         &self.cmd
@@ -20,43 +28,351 @@ impl Process {
     pub fn env(&self) -> &HashMap<String, String> {
         &self.env
     }
+    pub fn cwd(&self) -> Option<&str> {
+        self.cwd.as_deref()
+    }
+
+    /// Sets the directory the process runs in
+    pub fn with_cwd(mut self, cwd: Option<String>) -> Self {
+        self.cwd = cwd;
+        self
+    }
+
+    /// Inserts `cargo_args` before the arguments cargo passes through to the executed program
+    pub fn with_cargo_args(mut self, cargo_args: Vec<String>) -> Self {
+        let end = self
+            .args
+            .iter()
+            .position(|arg| arg == "--")
+            .unwrap_or(self.args.len());
+        self.args.splice(end..end, cargo_args);
+        self
+    }
+
+    /// Appends `program_args` to the arguments cargo passes through to the executed program,
+    /// reusing an existing `--` separator.
+    pub fn with_program_args(mut self, program_args: Vec<String>) -> Self {
+        if program_args.is_empty() {
+            return self;
+        }
+        if !self.args.iter().any(|arg| arg == "--") {
+            self.args.push("--".to_string());
+        }
+        self.args.extend(program_args);
+        self
+    }
+}
+
+/// Host variables which are kept in a clean environment, without them cargo can't be found or run
+pub const CLEAN_ENV_HOST_VARS: [&str; 8] = [
+    "PATH",
+    "HOME",
+    "USER",
+    "LANG",
+    "TERM",
+    "TMPDIR",
+    "CARGO_HOME",
+    "RUSTUP_HOME",
+];
+
+impl Process {
+    /// Wraps the process in `env -i` so it only sees `host_env` and its own variables
+    /// instead of everything the user's shell exports.
+    /// The process' own variables take precedence over `host_env`.
+    pub fn into_clean_env(self, host_env: HashMap<String, String>) -> Self {
+        let Self {
+            cmd,
+            args,
+            env,
+            cwd,
+        } = self;
+
+        let mut vars = host_env;
+        vars.extend(env);
+        let mut clean_env: Vec<_> = vars
+            .into_iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        // Deterministic command lines are easier to compare with CI logs
+        clean_env.sort();
+
+        let args = std::iter::once("-i".to_string())
+            .chain(clean_env)
+            .chain(std::iter::once(cmd))
+            .chain(args)
+            .collect();
+
+        Self {
+            cmd: "env".to_string(),
+            args,
+            env: HashMap::new(),
+            cwd,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct CargoTaskContext {
     env: HashMap<String, String>,
     extra_args: Vec<String>,
+    harness_args: Vec<String>,
     cargo_cmd: String,
+    cwd: Option<String>,
+    cross_host: Option<String>,
 }
 
+/// The command which replaces cargo for builds of other platform targets, see
+/// [CargoTaskContext::with_cross]
+pub const CROSS_COMMAND: &str = "cross";
+
 impl CargoTaskContext {
     pub fn new(env: HashMap<String, String>, extra_args: Vec<String>, cargo_cmd: String) -> Self {
         Self {
             env,
             extra_args,
+            harness_args: Vec::new(),
             cargo_cmd,
+            cwd: None,
+            cross_host: None,
         }
     }
 
+    /// Sets the arguments passed to the test harness after `--`, e.g. `--nocapture`
+    pub fn with_harness_args(mut self, harness_args: Vec<String>) -> Self {
+        self.harness_args = harness_args;
+        self
+    }
+
+    /// Sets the directory processes of this context run in
+    pub fn with_cwd(mut self, cwd: Option<String>) -> Self {
+        self.cwd = cwd;
+        self
+    }
+
+    /// Runs processes with `cross` instead of cargo when their `--target` isn't `host`. All
+    /// arguments and variables are passed on unchanged.
+    pub fn with_cross(mut self, host: Option<String>) -> Self {
+        self.cross_host = host;
+        self
+    }
+
+    /// Sets the variable `key` for processes of this context
+    pub fn with_env(mut self, key: String, value: String) -> Self {
+        self.env.insert(key, value);
+        self
+    }
+
+    /// Appends `flag` to the `RUSTFLAGS` of this context, configured flags are kept
+    pub fn with_rustflag(mut self, flag: String) -> Self {
+        let flags = match self.env.remove("RUSTFLAGS") {
+            Some(flags) if !flags.trim().is_empty() => format!("{} {flag}", flags.trim()),
+            _ => flag,
+        };
+        self.env.insert("RUSTFLAGS".to_string(), flags);
+        self
+    }
+
     pub fn try_into_process(self, args: Vec<String>) -> Result<Process, CargoCommandEmpty> {
         let Self {
             env,
             extra_args,
+            harness_args,
             cargo_cmd,
+            cwd,
+            cross_host,
         } = self;
 
         let mut cmd_parts = cargo_cmd.split_whitespace();
-        let cmd = cmd_parts.next().ok_or(CargoCommandEmpty)?.to_string();
+        let mut cmd = cmd_parts.next().ok_or(CargoCommandEmpty)?.to_string();
+        // Arguments after `--` are the program's
+        let platform_target = args
+            .iter()
+            .take_while(|arg| *arg != "--")
+            .skip_while(|arg| *arg != "--target")
+            .nth(1);
+        if let Some(host) = cross_host
+            && platform_target.is_some_and(|target| *target != host)
+        {
+            // A toolchain like `cargo +nightly` is kept as `cross +nightly`
+            cmd = CROSS_COMMAND.to_string();
+        }
         let args = cmd_parts
             .map(ToString::to_string)
             .chain(args)
             .chain(extra_args)
             .collect();
 
-        Ok(Process { cmd, args, env })
+        Ok(Process {
+            cmd,
+            args,
+            env,
+            cwd,
+        }
+        .with_program_args(harness_args))
+    }
+}
+
+/// The arguments of the command line `line` split like a POSIX shell does: arguments are
+/// separated by whitespace, which single and double quotes keep together, and a backslash
+/// escapes the next character outside of single quotes
+pub fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => args.extend(arg.take()),
+            '\'' => {
+                let quoted = arg.get_or_insert_default();
+                quoted.extend(chars.by_ref().take_while(|c| *c != '\''));
+            }
+            '"' => {
+                let quoted = arg.get_or_insert_default();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(escaped @ ('"' | '\\' | '$' | '`')) => quoted.push(escaped),
+                            Some(other) => quoted.extend(['\\', other]),
+                            None => quoted.push('\\'),
+                        },
+                        c => quoted.push(c),
+                    }
+                }
+            }
+            '\\' => arg.get_or_insert_default().extend(chars.next()),
+            c => arg.get_or_insert_default().push(c),
+        }
     }
+    args.extend(arg);
+    args
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error("The configured 'cargo' is empty")]
 pub struct CargoCommandEmpty;
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn clean_env_keeps_only_host_and_configured_vars() {
+        let env = HashMap::from([("RUST_LOG".to_string(), "debug".to_string())]);
+        let process = Process::new("cargo".to_string(), vec!["build".to_string()], env);
+        let host_env = HashMap::from([
+            ("PATH".to_string(), "/usr/bin".to_string()),
+            ("RUST_LOG".to_string(), "trace".to_string()),
+        ]);
+
+        let process = process.into_clean_env(host_env);
+
+        check!(process.cmd() == "env");
+        check!(process.args() == ["-i", "PATH=/usr/bin", "RUST_LOG=debug", "cargo", "build"]);
+        check!(process.env().is_empty());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn args_are_split_like_a_shell() {
+        check!(split_args("  --exact  tests::a ") == ["--exact", "tests::a"]);
+        check!(
+            split_args(r#"--skip "slow test" 'a b'c \"x\ y "\"q\"""#)
+                == ["--skip", "slow test", "a bc", "\"x y", "\"q\""]
+        );
+        check!(
+            split_args(r#"serde --features "derive rc""#) == ["serde", "--features", "derive rc"]
+        );
+        check!(split_args("''").len() == 1);
+        check!(split_args("").is_empty());
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn program_args_are_appended_after_separator() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let cargo = |cargo_args: &[&str]| {
+            Process::new("cargo".to_string(), args(cargo_args), HashMap::new())
+        };
+
+        check!(cargo(&["run"]).with_program_args(Vec::new()).args() == ["run"]);
+        check!(cargo(&["run"]).with_program_args(args(&["-v"])).args() == ["run", "--", "-v"]);
+        check!(
+            cargo(&["test", "--", "a"])
+                .with_program_args(args(&["-v"]))
+                .args()
+                == ["test", "--", "a", "-v"]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cargo_args_are_inserted_before_separator() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let cargo = |cargo_args: &[&str]| {
+            Process::new("cargo".to_string(), args(cargo_args), HashMap::new())
+        };
+        let json = || args(&["--message-format=json"]);
+
+        check!(
+            cargo(&["check"]).with_cargo_args(json()).args() == ["check", "--message-format=json"]
+        );
+        check!(
+            cargo(&["clippy", "--", "-Dwarnings"])
+                .with_cargo_args(json())
+                .args()
+                == ["clippy", "--message-format=json", "--", "-Dwarnings"]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn harness_args_follow_extra_args() {
+        let ctx = CargoTaskContext::new(
+            HashMap::new(),
+            vec!["--locked".to_string()],
+            "cargo".to_string(),
+        )
+        .with_harness_args(vec!["--nocapture".to_string()]);
+        let process = ctx.try_into_process(vec!["test".to_string()]).unwrap();
+
+        check!(process.args() == ["test", "--locked", "--", "--nocapture"]);
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn rustflags_are_appended_to_configured_ones() {
+        let env = HashMap::from([("RUSTFLAGS".to_string(), "-Ctarget-cpu=native".to_string())]);
+        let ctx = CargoTaskContext::new(env, Vec::new(), "cargo".to_string())
+            .with_rustflag("-Zsanitizer=address".to_string());
+        let process = ctx.try_into_process(vec!["run".to_string()]).unwrap();
+
+        check!(process.env()["RUSTFLAGS"] == "-Ctarget-cpu=native -Zsanitizer=address");
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn cross_builds_other_platform_targets() {
+        let args = |args: &[&str]| args.iter().map(ToString::to_string).collect::<Vec<_>>();
+        let ctx = CargoTaskContext::new(HashMap::new(), Vec::new(), "cargo +nightly".to_string())
+            .with_cross(Some("x86_64-unknown-linux-gnu".to_string()));
+        let process = |cargo_args: &[&str]| ctx.clone().try_into_process(args(cargo_args)).unwrap();
+
+        let cross = process(&[
+            "build",
+            "--target",
+            "aarch64-unknown-linux-gnu",
+            "--release",
+        ]);
+        check!(cross.cmd() == "cross");
+        check!(
+            cross.args()
+                == [
+                    "+nightly",
+                    "build",
+                    "--target",
+                    "aarch64-unknown-linux-gnu",
+                    "--release"
+                ]
+        );
+        check!(process(&["build", "--target", "x86_64-unknown-linux-gnu"]).cmd() == "cargo");
+        check!(process(&["build"]).cmd() == "cargo");
+    }
+}
@@ -0,0 +1,107 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    cargo::{Config, Features},
+    process::Process,
+};
+
+/// The rust-analyzer settings which make its analysis and diagnostics match a selection,
+/// serialized with their keys in the `rust-analyzer` section
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RustAnalyzerSettings {
+    /// `rust-analyzer.cargo.target`
+    #[serde(rename = "cargo.target")]
+    pub target: Option<String>,
+    /// `rust-analyzer.cargo.features`, `"all"` or the features
+    #[serde(rename = "cargo.features")]
+    pub features: Value,
+    /// `rust-analyzer.check.overrideCommand`
+    #[serde(rename = "check.overrideCommand")]
+    pub check_command: Vec<String>,
+}
+
+impl RustAnalyzerSettings {
+    /// The settings of the selection of `config`, diagnostics come from `check`, e.g. the
+    /// `cargo check` of the selected package
+    pub fn new(config: &Config, check: &Process) -> Self {
+        let features = match config.selected_features() {
+            Features::All => Value::from("all"),
+            // rust-analyzer analyzes the whole workspace, where package features need the
+            // package as prefix
+            Features::Some(features) => match &config.selected_package {
+                Some(package) => features
+                    .iter()
+                    .map(|feature| format!("{package}/{feature}"))
+                    .collect(),
+                None => features.into_iter().collect(),
+            },
+        };
+
+        let check_command = std::iter::once(check.cmd().to_string())
+            .chain(check.args().iter().cloned())
+            .chain(["--message-format=json".to_string()])
+            .collect();
+
+        Self {
+            target: config.platform_target.clone(),
+            features,
+            check_command,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::{ConfigUpdate, config::FeatureTarget};
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn settings_of_selection() {
+        let mut config = Config {
+            selected_package: Some("core".to_string()),
+            platform_target: Some("wasm32-unknown-unknown".to_string()),
+            ..Config::default()
+        };
+        config.update(ConfigUpdate::SelectedFeatures {
+            feature_target: FeatureTarget::Package("core".to_string()),
+            features: Features::Some(vec!["serde".to_string()]),
+        });
+        let check = Process::new(
+            "cargo".to_string(),
+            ["check", "--package", "core"].map(str::to_string).to_vec(),
+            HashMap::new(),
+        );
+
+        let settings = RustAnalyzerSettings::new(&config, &check);
+        check!(settings.target.as_deref() == Some("wasm32-unknown-unknown"));
+        check!(settings.features == serde_json::json!(["core/serde"]));
+        check!(
+            settings.check_command
+                == [
+                    "cargo",
+                    "check",
+                    "--package",
+                    "core",
+                    "--message-format=json"
+                ]
+        );
+
+        config.selected_package = None;
+        config.update(ConfigUpdate::SelectedFeatures {
+            feature_target: FeatureTarget::Workspace,
+            features: Features::All,
+        });
+        let settings = RustAnalyzerSettings::new(&config, &check);
+        check!(settings.features == "all");
+        // The keys of the `rust-analyzer` section
+        let json = serde_json::to_value(&settings).unwrap();
+        check!(json["cargo.target"] == "wasm32-unknown-unknown");
+        check!(json["cargo.features"] == "all");
+    }
+}
@@ -0,0 +1,545 @@
+use serde::Serialize;
+
+/// A test function found in a Rust source file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TestFunction {
+    /// Zero based line of the test attribute
+    pub line: usize,
+    /// Path of the function relative to the file's module, e.g. `tests::parses_input`
+    pub name: String,
+    /// The test is annotated with `#[ignore]`
+    pub ignored: bool,
+    /// The test asserts an insta snapshot, e.g. with `assert_snapshot!` or
+    /// `insta::assert_debug_snapshot!`
+    pub snapshot: bool,
+}
+
+/// Finds the functions annotated with `#[test]` or a `<crate>::test` attribute like
+/// `#[tokio::test]`.
+///
+/// This is a lightweight scan of the tokens, not a full parse. Inline modules are tracked
+/// so the returned names match the names libtest reports.
+pub fn find_test_functions(source: &str) -> Vec<TestFunction> {
+    let tokens = tokenize(source);
+    let mut tests = Vec::new();
+    let mut modules: Vec<(String, usize)> = Vec::new();
+    let mut pending_module: Option<String> = None;
+    let mut pending_test: Option<usize> = None;
+    let mut pending_ignore = false;
+    // The test whose body is next or currently scanned, with the depth of its body
+    let mut pending_body: Option<usize> = None;
+    let mut body: Option<(usize, usize)> = None;
+    let mut depth = 0;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let Token { kind, line } = &tokens[i];
+        match kind {
+            TokenKind::Punct('#') if tokens.get(i + 1).is_some_and(|t| t.is_punct('[')) => {
+                let end = attribute_end(&tokens, i + 1);
+                let attribute = &tokens[i + 2..end];
+                if is_test_attribute(attribute) {
+                    pending_test = Some(*line);
+                } else if attribute.first().and_then(Token::ident) == Some("ignore") {
+                    pending_ignore = true;
+                }
+                i = end;
+            }
+            TokenKind::Ident(ident) if ident == "mod" => {
+                pending_module = tokens.get(i + 1).and_then(Token::ident).map(str::to_string);
+            }
+            TokenKind::Ident(ident) if ident == "fn" => {
+                let ignored = std::mem::take(&mut pending_ignore);
+                if let (Some(line), Some(name)) = (
+                    pending_test.take(),
+                    tokens.get(i + 1).and_then(Token::ident),
+                ) {
+                    let name = modules
+                        .iter()
+                        .map(|(module, _)| module.as_str())
+                        .chain(std::iter::once(name))
+                        .collect::<Vec<_>>()
+                        .join("::");
+                    pending_body = Some(tests.len());
+                    tests.push(TestFunction {
+                        line,
+                        name,
+                        ignored,
+                        snapshot: false,
+                    });
+                }
+            }
+            TokenKind::Ident(ident)
+                if is_snapshot_assertion(ident)
+                    && tokens.get(i + 1).is_some_and(|t| t.is_punct('!')) =>
+            {
+                if let Some((test, _)) = body {
+                    tests[test].snapshot = true;
+                }
+            }
+            TokenKind::Punct('{') => {
+                depth += 1;
+                if let Some(module) = pending_module.take() {
+                    modules.push((module, depth));
+                }
+                if let Some(test) = pending_body.take() {
+                    body = Some((test, depth));
+                }
+            }
+            TokenKind::Punct('}') => {
+                if modules.last().is_some_and(|(_, d)| *d == depth) {
+                    modules.pop();
+                }
+                if body.is_some_and(|(_, d)| d == depth) {
+                    body = None;
+                }
+                depth = depth.saturating_sub(1);
+            }
+            TokenKind::Punct(';') => pending_module = None,
+            _ => {}
+        }
+        i += 1;
+    }
+
+    tests
+}
+
+/// insta's assertion macros are `assert_snapshot!` and `assert_<format>_snapshot!`
+fn is_snapshot_assertion(ident: &str) -> bool {
+    ident.starts_with("assert_") && ident.ends_with("snapshot")
+}
+
+/// A fenced Rust code block in a `///` or `//!` doc comment, which rustdoc runs as doctest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DocCodeBlock {
+    /// Zero based line of the opening fence, rustdoc names the doctest after it
+    pub line: usize,
+}
+
+/// Finds the Rust code blocks of the line doc comments in `source`. Code blocks in
+/// `#[doc = "..."]` attributes and `/** */` comments are not found.
+pub fn find_doc_code_blocks(source: &str) -> Vec<DocCodeBlock> {
+    let mut blocks = Vec::new();
+    // The fence of the code block the doc comment is currently in
+    let mut open_fence: Option<&str> = None;
+
+    for (line, text) in source.lines().enumerate() {
+        let trimmed = text.trim_start();
+        let Some(doc) = trimmed
+            .strip_prefix("///")
+            .filter(|doc| !doc.starts_with('/'))
+            .or_else(|| trimmed.strip_prefix("//!"))
+        else {
+            // Code blocks end with their doc comment
+            open_fence = None;
+            continue;
+        };
+        let doc = doc.trim_start();
+
+        match open_fence {
+            Some(fence) => {
+                if doc.trim_end() == fence {
+                    open_fence = None;
+                }
+            }
+            None => {
+                let Some(fence) = ["```", "~~~"].into_iter().find(|f| doc.starts_with(f)) else {
+                    continue;
+                };
+                open_fence = Some(fence);
+                if is_rust_code_block(doc.trim_start_matches(fence)) {
+                    blocks.push(DocCodeBlock { line });
+                }
+            }
+        }
+    }
+
+    blocks
+}
+
+/// Whether the info string of a code block, e.g. `rust,no_run` or `text`, marks Rust code
+fn is_rust_code_block(info: &str) -> bool {
+    info.split([',', ' ', '\t'])
+        .map(str::trim)
+        .filter(|attr| !attr.is_empty())
+        .all(|attr| {
+            matches!(
+                attr,
+                "rust"
+                    | "ignore"
+                    | "should_panic"
+                    | "no_run"
+                    | "compile_fail"
+                    | "test_harness"
+                    | "standalone_crate"
+            ) || attr.starts_with("edition")
+                || attr.starts_with("ignore-")
+                // Error codes of `compile_fail` tests like `E0499`
+                || (attr.starts_with('E') && attr[1..].chars().all(|c| c.is_ascii_digit()))
+        })
+}
+
+/// Returns the index of the `]` closing the attribute opened at `open`
+fn attribute_end(tokens: &[Token], open: usize) -> usize {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate().skip(open) {
+        if token.is_punct('[') {
+            depth += 1;
+        } else if token.is_punct(']') {
+            depth -= 1;
+            if depth == 0 {
+                return i;
+            }
+        }
+    }
+    tokens.len()
+}
+
+/// Whether the attribute path, e.g. `tokio::test(flavor = "multi_thread")`, names a test
+fn is_test_attribute(tokens: &[Token]) -> bool {
+    let path: Vec<&str> = tokens
+        .iter()
+        .take_while(|t| t.ident().is_some() || t.is_punct(':'))
+        .filter_map(Token::ident)
+        .collect();
+    path.last() == Some(&"test")
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+    Ident(String),
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    line: usize,
+}
+
+impl Token {
+    fn ident(&self) -> Option<&str> {
+        match &self.kind {
+            TokenKind::Ident(ident) => Some(ident),
+            TokenKind::Punct(_) => None,
+        }
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.kind == TokenKind::Punct(c)
+    }
+}
+
+/// Splits `source` into identifiers and punctuation, skipping comments and literals
+fn tokenize(source: &str) -> Vec<Token> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut line = 0;
+    let mut i = 0;
+
+    // Advances `i` to `end` and counts the skipped newlines
+    let skip = |i: &mut usize, end: usize, line: &mut usize| {
+        *line += chars[*i..end.min(chars.len())]
+            .iter()
+            .filter(|c| **c == '\n')
+            .count();
+        *i = end;
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        match c {
+            '\n' => {
+                line += 1;
+                i += 1;
+            }
+            c if c.is_whitespace() => i += 1,
+            '/' if next == Some('/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if next == Some('*') => {
+                let end = block_comment_end(&chars, i);
+                skip(&mut i, end, &mut line);
+            }
+            '"' => {
+                let end = string_end(&chars, i + 1);
+                skip(&mut i, end, &mut line);
+            }
+            '\'' => i = char_literal_end(&chars, i),
+            c if c.is_alphanumeric() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                    i += 1;
+                }
+                let ident: String = chars[start..i].iter().collect();
+                // `r#ident` is a raw identifier, raw strings need a quote after the `#`s
+                let hashes = chars[i..].iter().take_while(|c| **c == '#').count();
+                let is_raw_string_prefix = matches!(ident.as_str(), "r" | "br" | "cr")
+                    && chars.get(i + hashes) == Some(&'"');
+                if is_raw_string_prefix {
+                    let end = raw_string_end(&chars, i);
+                    skip(&mut i, end, &mut line);
+                } else {
+                    tokens.push(Token {
+                        kind: TokenKind::Ident(ident),
+                        line,
+                    });
+                }
+            }
+            c => {
+                tokens.push(Token {
+                    kind: TokenKind::Punct(c),
+                    line,
+                });
+                i += 1;
+            }
+        }
+    }
+
+    tokens
+}
+
+fn block_comment_end(chars: &[char], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i + 1 < chars.len() {
+        match (chars[i], chars[i + 1]) {
+            ('/', '*') => {
+                depth += 1;
+                i += 2;
+            }
+            ('*', '/') => {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return i;
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Returns the index after the closing quote of a string whose content starts at `start`
+fn string_end(chars: &[char], start: usize) -> usize {
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            '"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Returns the index after a raw string whose `#`s or opening quote start at `start`
+fn raw_string_end(chars: &[char], start: usize) -> usize {
+    let hashes = chars[start..].iter().take_while(|c| **c == '#').count();
+    let mut i = start + hashes + 1;
+    while i < chars.len() {
+        if chars[i] == '"'
+            && chars[i + 1..]
+                .iter()
+                .take(hashes)
+                .filter(|c| **c == '#')
+                .count()
+                == hashes
+        {
+            return i + 1 + hashes;
+        }
+        i += 1;
+    }
+    chars.len()
+}
+
+/// Skips a char literal like `'{'` or `'\n'`, lifetimes only skip the quote
+fn char_literal_end(chars: &[char], start: usize) -> usize {
+    match (chars.get(start + 1), chars.get(start + 2)) {
+        (Some('\\'), _) => chars[start + 2..]
+            .iter()
+            .position(|c| *c == '\'')
+            .map_or(chars.len(), |p| start + 3 + p),
+        (Some(_), Some('\'')) => start + 3,
+        _ => start + 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn names(source: &str) -> Vec<String> {
+        find_test_functions(source)
+            .into_iter()
+            .map(|t| t.name)
+            .collect()
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn find_tests_in_modules() {
+        let source = r##"
+fn helper() -> char { '{' }
+
+#[test]
+fn top_level() {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /* a { brace in a comment */
+    #[test]
+    #[should_panic]
+    fn panics() {
+        let s = "}";
+    }
+
+    mod nested {
+        #[tokio::test(flavor = "multi_thread")]
+        async fn runs_async<'a>(r#type: &'a str) {
+            let raw = r#"{"json": {}}"#;
+        }
+    }
+
+    fn not_a_test() {}
+}
+
+mod other;
+
+#[test]
+fn after_modules() {}
+"##;
+
+        check!(
+            names(source)
+                == vec![
+                    "top_level",
+                    "tests::panics",
+                    "tests::nested::runs_async",
+                    "after_modules"
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn test_lines_point_at_attribute() {
+        let source = "mod tests {\n    #[test]\n    fn first() {}\n}\n";
+
+        check!(
+            find_test_functions(source)
+                == vec![TestFunction {
+                    line: 1,
+                    name: "tests::first".to_string(),
+                    ignored: false,
+                    snapshot: false,
+                }]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn find_ignored_tests() {
+        let source = r#"
+#[ignore = "slow"]
+#[test]
+fn slow() {}
+
+#[ignore]
+fn helper() {}
+
+#[test]
+fn fast() {}
+
+#[tokio::test]
+#[ignore]
+async fn network() {}
+"#;
+
+        let ignored: Vec<_> = find_test_functions(source)
+            .into_iter()
+            .map(|t| (t.name, t.ignored))
+            .collect();
+        check!(
+            ignored
+                == [
+                    ("slow".to_string(), true),
+                    ("fast".to_string(), false),
+                    ("network".to_string(), true)
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn find_snapshot_tests() {
+        let source = r#"
+#[test]
+fn renders() {
+    insta::assert_snapshot!(render());
+}
+
+#[test]
+fn parses() {
+    assert_eq!(parse("1"), Some(1));
+}
+
+#[test]
+fn serializes() -> Result<(), Error> {
+    let value = load()?;
+    assert_json_snapshot!(value, { ".id" => "[id]" });
+    Ok(())
+}
+"#;
+
+        let snapshots: Vec<_> = find_test_functions(source)
+            .into_iter()
+            .map(|t| (t.name, t.snapshot))
+            .collect();
+        check!(
+            snapshots
+                == [
+                    ("renders".to_string(), true),
+                    ("parses".to_string(), false),
+                    ("serializes".to_string(), true),
+                ]
+        );
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn find_rust_code_blocks_in_doc_comments() {
+        let source = r#"//! Crate docs
+//! ```
+//! assert!(true);
+//! ```
+//!
+//! ```text
+//! not rust
+//! ```
+
+/// Adds numbers
+///
+/// ```rust,no_run
+/// let x = add(1, 2);
+/// ```
+//// not a doc comment
+/// ```compile_fail,E0308
+/// let x: u8 = "";
+/// ```
+pub fn add(a: u8, b: u8) -> u8 {
+    a + b
+}
+"#;
+
+        let lines: Vec<_> = find_doc_code_blocks(source)
+            .into_iter()
+            .map(|b| b.line)
+            .collect();
+        check!(lines == [1, 11, 15]);
+    }
+}
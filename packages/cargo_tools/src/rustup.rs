@@ -0,0 +1,169 @@
+use crate::CargoCommand;
+
+pub const RUSTUP: &str = "rustup";
+
+/// The component cargo llvm-cov needs for coverage
+pub const LLVM_TOOLS: &str = "llvm-tools";
+
+/// The components **Manage Toolchain Components** offers, with what they are needed for
+pub const COMPONENTS: [(&str, &str); 5] = [
+    (
+        "clippy",
+        "Clippy lints of the Clippy command and pre-commit checks",
+    ),
+    (
+        "rustfmt",
+        "Formatting of the Fmt command and pre-commit checks",
+    ),
+    (
+        "rust-src",
+        "Standard library sources for debugging and rust-analyzer",
+    ),
+    ("miri", "Undefined behavior detection with cargo miri"),
+    (LLVM_TOOLS, "Test coverage with cargo llvm-cov"),
+];
+
+/// An installed toolchain of `rustup toolchain list`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toolchain {
+    pub name: String,
+    pub default: bool,
+}
+
+/// A component of [COMPONENTS] and whether a toolchain has it installed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub installed: bool,
+}
+
+/// Parses the output of `rustup toolchain list` with lines like
+/// `stable-x86_64-unknown-linux-gnu (active, default)`
+pub fn parse_toolchains(output: &str) -> Vec<Toolchain> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, status) = line.trim().split_once(' ').unwrap_or((line.trim(), ""));
+            // Without toolchains rustup prints `no installed toolchains`
+            (!name.is_empty() && name != "no").then(|| Toolchain {
+                name: name.to_string(),
+                default: status.contains("default"),
+            })
+        })
+        .collect()
+}
+
+/// The [COMPONENTS] of the output of `rustup component list`, which lists components like
+/// `clippy-x86_64-unknown-linux-gnu (installed)` with the host triple
+pub fn parse_components(output: &str) -> Vec<Component> {
+    COMPONENTS
+        .iter()
+        .map(|(name, description)| {
+            let installed = output.lines().any(|line| {
+                let Some(component) = line.trim().strip_suffix("(installed)") else {
+                    return false;
+                };
+                // Followed by the host triple or `-preview` for older toolchains
+                let component = component.trim();
+                component == *name
+                    || component
+                        .strip_prefix(name)
+                        .is_some_and(|rest| rest.starts_with('-'))
+            });
+            Component {
+                name,
+                description,
+                installed,
+            }
+        })
+        .collect()
+}
+
+/// The component `cmd` can't run without
+pub fn required_component(cmd: &CargoCommand) -> Option<&'static str> {
+    match cmd {
+        CargoCommand::Clippy { .. } => Some("clippy"),
+        CargoCommand::Fmt { .. } => Some("rustfmt"),
+        CargoCommand::Coverage { .. } => Some(LLVM_TOOLS),
+        CargoCommand::Sanitized { cmd, .. } | CargoCommand::Nextest { cmd, .. } => {
+            required_component(cmd)
+        }
+        _ => None,
+    }
+}
+
+/// The `rustup component` arguments which install (`add`) or remove the `components` of
+/// `toolchain`, the toolchain of the working directory if it is [None]
+pub fn component_args(action: &str, components: &[&str], toolchain: Option<&str>) -> Vec<String> {
+    let mut args = vec!["component".to_string(), action.to_string()];
+    if let Some(toolchain) = toolchain {
+        args.extend(["--toolchain".to_string(), toolchain.to_string()]);
+    }
+    args.extend(components.iter().map(ToString::to_string));
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn toolchains_and_components() {
+        let toolchains = parse_toolchains(
+            "stable-x86_64-unknown-linux-gnu (active, default)\nnightly-x86_64-unknown-linux-gnu\n",
+        );
+        check!(
+            toolchains
+                == [
+                    Toolchain {
+                        name: "stable-x86_64-unknown-linux-gnu".to_string(),
+                        default: true,
+                    },
+                    Toolchain {
+                        name: "nightly-x86_64-unknown-linux-gnu".to_string(),
+                        default: false,
+                    },
+                ]
+        );
+        check!(parse_toolchains("no installed toolchains\n").is_empty());
+
+        let components = parse_components(
+            "cargo-x86_64-unknown-linux-gnu (installed)
+clippy-x86_64-unknown-linux-gnu (installed)
+llvm-tools-x86_64-unknown-linux-gnu
+miri-x86_64-unknown-linux-gnu
+rust-src (installed)
+rustfmt-x86_64-unknown-linux-gnu (installed)
+",
+        );
+        let installed: Vec<_> = components.iter().map(|c| (c.name, c.installed)).collect();
+        check!(
+            installed
+                == [
+                    ("clippy", true),
+                    ("rustfmt", true),
+                    ("rust-src", true),
+                    ("miri", false),
+                    ("llvm-tools", false),
+                ]
+        );
+
+        check!(required_component(&CargoCommand::Fmt { package: None }) == Some("rustfmt"));
+        check!(required_component(&CargoCommand::Doc).is_none());
+        check!(
+            component_args("add", &["miri", "rust-src"], Some("nightly"))
+                == [
+                    "component",
+                    "add",
+                    "--toolchain",
+                    "nightly",
+                    "miri",
+                    "rust-src"
+                ]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::cargo::binary_size::format_size;
+
+/// The compiler wrapper `RUSTC_WRAPPER` is set to when sccache is enabled
+pub const SCCACHE: &str = "sccache";
+
+/// Whether the `RUSTC_WRAPPER` `wrapper` is sccache, also as path like `~/.cargo/bin/sccache`
+pub fn is_sccache(wrapper: &str) -> bool {
+    let file_name = wrapper.rsplit(['/', '\\']).next().unwrap_or(wrapper);
+    file_name.strip_suffix(".exe").unwrap_or(file_name) == SCCACHE
+}
+
+/// The arguments which make sccache print its statistics as JSON
+pub fn stats_args() -> Vec<String> {
+    ["--show-stats", "--stats-format=json"]
+        .map(str::to_string)
+        .to_vec()
+}
+
+/// The statistics of `sccache --show-stats --stats-format=json`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub stats: CacheStats,
+    pub cache_location: String,
+    pub cache_size: Option<u64>,
+    pub max_cache_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct CacheStats {
+    pub compile_requests: u64,
+    pub requests_executed: u64,
+    pub requests_not_cacheable: u64,
+    pub compile_fails: u64,
+    pub cache_hits: Counts,
+    pub cache_misses: Counts,
+}
+
+/// Counts per language, e.g. `Rust` or `C/C++`
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Counts {
+    pub counts: BTreeMap<String, u64>,
+}
+
+impl Counts {
+    fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+}
+
+impl Stats {
+    pub fn parse(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// The statistics as Markdown with the hit rate of each language
+    pub fn to_markdown(&self) -> String {
+        let hits = &self.stats.cache_hits;
+        let misses = &self.stats.cache_misses;
+        let mut languages: Vec<&String> = hits.counts.keys().chain(misses.counts.keys()).collect();
+        languages.sort();
+        languages.dedup();
+
+        let mut markdown = String::from("# Compiler Cache Stats\n\n");
+        markdown.push_str(&format!("Cache location: {}\n\n", self.cache_location));
+        if let Some(size) = self.cache_size {
+            let max = self
+                .max_cache_size
+                .map_or(String::new(), |max| format!(" of {}", format_size(max)));
+            markdown.push_str(&format!("Cache size: {}{max}\n\n", format_size(size)));
+        }
+
+        markdown
+            .push_str("| Language | Hits | Misses | Hit rate |\n| --- | ---: | ---: | ---: |\n");
+        let row = |name: &str, hits: u64, misses: u64| {
+            format!(
+                "| {name} | {hits} | {misses} | {} |\n",
+                hit_rate(hits, misses)
+            )
+        };
+        for language in languages {
+            let count = |counts: &Counts| counts.counts.get(language).copied().unwrap_or(0);
+            markdown.push_str(&row(language, count(hits), count(misses)));
+        }
+        markdown.push_str(&row("**Total**", hits.total(), misses.total()));
+
+        let CacheStats {
+            compile_requests,
+            requests_executed,
+            requests_not_cacheable,
+            compile_fails,
+            ..
+        } = self.stats;
+        markdown.push_str(&format!(
+            "\n{compile_requests} compile requests, {requests_executed} executed, \
+             {requests_not_cacheable} not cacheable, {compile_fails} failed\n"
+        ));
+        markdown
+    }
+}
+
+fn hit_rate(hits: u64, misses: u64) -> String {
+    match hits + misses {
+        0 => "-".to_string(),
+        total => format!("{:.1} %", hits as f64 * 100.0 / total as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn stats_with_hit_rates() {
+        check!(is_sccache("sccache"));
+        check!(is_sccache("/home/dev/.cargo/bin/sccache"));
+        check!(is_sccache("C:\\tools\\sccache.exe"));
+        check!(!is_sccache("cachepot"));
+
+        let json = r#"{
+  "stats": {
+    "compile_requests": 12,
+    "requests_executed": 10,
+    "requests_not_cacheable": 2,
+    "compile_fails": 0,
+    "cache_hits": { "counts": { "Rust": 6, "C/C++": 1 }, "adv_counts": {} },
+    "cache_misses": { "counts": { "Rust": 2 }, "adv_counts": {} },
+    "cache_timeouts": 0
+  },
+  "cache_location": "Local disk: \"/home/dev/.cache/sccache\"",
+  "cache_size": 1572864,
+  "max_cache_size": 10737418240
+}"#;
+        let stats = Stats::parse(json).unwrap();
+        check!(
+            stats.to_markdown()
+                == "# Compiler Cache Stats
+
+Cache location: Local disk: \"/home/dev/.cache/sccache\"
+
+Cache size: 1.5 MiB of 10.0 GiB
+
+| Language | Hits | Misses | Hit rate |
+| --- | ---: | ---: | ---: |
+| C/C++ | 1 | 0 | 100.0 % |
+| Rust | 6 | 2 | 75.0 % |
+| **Total** | 7 | 2 | 77.8 % |
+
+12 compile requests, 10 executed, 2 not cacheable, 0 failed
+"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    cargo::{
+        Config, Features,
+        command::{BuildSubTarget, RunSubTarget},
+    },
+    process::Process,
+};
+
+/// A snapshot of the selection which is shared with other extensions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Selection {
+    pub workspace_root: String,
+    pub package: Option<String>,
+    pub build_target: Option<SelectedTarget>,
+    pub run_target: Option<SelectedTarget>,
+    pub platform_target: Option<String>,
+    pub profile: Option<String>,
+    /// `"all"` or the features
+    pub features: Value,
+    /// The arguments of the `cargo build` of the selection
+    pub build_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelectedTarget {
+    /// `bin`, `example`, `lib` or `bench`
+    pub kind: &'static str,
+    pub name: String,
+    /// The path of the executable from the last build of a run target, unknown before
+    pub executable_path: Option<String>,
+}
+
+impl Selection {
+    /// The snapshot of the selection of `config`, `build` is the build command of the selection
+    /// and `executable` the last built executable of the run target
+    pub fn new(
+        config: &Config,
+        workspace_root: &str,
+        executable: Option<String>,
+        build: &Process,
+    ) -> Self {
+        let selection = config.package_selection();
+        let build_target = selection
+            .and_then(|s| s.build_target.as_ref())
+            .map(|target| SelectedTarget {
+                kind: match target {
+                    BuildSubTarget::Bin(_) => "bin",
+                    BuildSubTarget::Example(_) => "example",
+                    BuildSubTarget::Lib(_) => "lib",
+                    BuildSubTarget::Bench(_) => "bench",
+                },
+                name: target.name().to_string(),
+                executable_path: None,
+            });
+        let run_target =
+            selection
+                .and_then(|s| s.run_target.as_ref())
+                .map(|target| SelectedTarget {
+                    kind: match target {
+                        RunSubTarget::Bin(_) => "bin",
+                        RunSubTarget::Example(_) => "example",
+                    },
+                    name: target.name().to_string(),
+                    executable_path: executable,
+                });
+        let features = match config.selected_features() {
+            Features::All => Value::from("all"),
+            Features::Some(features) => features.into_iter().collect(),
+        };
+
+        Self {
+            workspace_root: workspace_root.to_string(),
+            package: config.selected_package.clone(),
+            build_target,
+            run_target,
+            platform_target: config.platform_target.clone(),
+            profile: config.profile.get_name().map(str::to_string),
+            features,
+            build_args: build.args().to_vec(),
+        }
+    }
+
+    /// The variables which pass the selection to project tasks like just recipes, e.g.
+    /// `CARGO_TOOLS_PACKAGE`. Variables of unselected parts are left out.
+    pub fn env(&self) -> HashMap<String, String> {
+        let features = match &self.features {
+            Value::Array(features) => features
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(","),
+            features => features.as_str().unwrap_or_default().to_string(),
+        };
+        [
+            (
+                "CARGO_TOOLS_WORKSPACE_ROOT",
+                Some(self.workspace_root.clone()),
+            ),
+            ("CARGO_TOOLS_PACKAGE", self.package.clone()),
+            (
+                "CARGO_TOOLS_BUILD_TARGET",
+                self.build_target.as_ref().map(|t| t.name.clone()),
+            ),
+            (
+                "CARGO_TOOLS_RUN_TARGET",
+                self.run_target.as_ref().map(|t| t.name.clone()),
+            ),
+            ("CARGO_TOOLS_PLATFORM_TARGET", self.platform_target.clone()),
+            ("CARGO_TOOLS_PROFILE", self.profile.clone()),
+            (
+                "CARGO_TOOLS_FEATURES",
+                Some(features).filter(|f| !f.is_empty()),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key.to_string(), value?)))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::{Profile, config::PackageConfig};
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn snapshot_of_selection() {
+        let config = Config {
+            selected_package: Some("cli".to_string()),
+            profile: Profile::Release,
+            package_configs: HashMap::from([(
+                "cli".to_string(),
+                PackageConfig {
+                    build_target: Some(BuildSubTarget::Lib("cli".to_string())),
+                    run_target: Some(RunSubTarget::Bin("cli".to_string())),
+                    ..PackageConfig::default()
+                },
+            )]),
+            ..Config::default()
+        };
+        let build = Process::new(
+            "cargo".to_string(),
+            ["build", "--package", "cli", "--lib", "--release"]
+                .map(str::to_string)
+                .to_vec(),
+            HashMap::new(),
+        );
+
+        let selection = Selection::new(
+            &config,
+            "/ws",
+            Some("/ws/target/release/cli".to_string()),
+            &build,
+        );
+        check!(selection.package.as_deref() == Some("cli"));
+        check!(selection.profile.as_deref() == Some("release"));
+        check!(selection.features == serde_json::json!([]));
+        check!(selection.build_args == ["build", "--package", "cli", "--lib", "--release"]);
+        check!(
+            serde_json::to_value(&selection.build_target).unwrap()
+                == serde_json::json!({ "kind": "lib", "name": "cli", "executablePath": null })
+        );
+        let env = selection.env();
+        check!(env.get("CARGO_TOOLS_PACKAGE").map(String::as_str) == Some("cli"));
+        check!(env.get("CARGO_TOOLS_RUN_TARGET").map(String::as_str) == Some("cli"));
+        check!(!env.contains_key("CARGO_TOOLS_FEATURES"));
+        check!(!env.contains_key("CARGO_TOOLS_PLATFORM_TARGET"));
+        check!(
+            selection
+                .run_target
+                .and_then(|t| t.executable_path)
+                .as_deref()
+                == Some("/ws/target/release/cli")
+        );
+    }
+}
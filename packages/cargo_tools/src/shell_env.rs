@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+/// The program which captures the environment of `.envrc` files
+pub const DIRENV: &str = "direnv";
+
+/// The shell a configured init command is sourced in
+pub const SHELL: &str = "bash";
+
+/// Variables every shell sets itself, they never come from the captured environment
+const SHELL_VARS: [&str; 5] = ["_", "PWD", "OLDPWD", "SHLVL", "BASH_EXECUTION_STRING"];
+
+/// The arguments of `direnv` which print the variables `.envrc` sets as JSON
+pub fn direnv_args() -> Vec<String> {
+    ["export", "json"].map(str::to_string).to_vec()
+}
+
+/// Parses the output of `direnv export json`, which maps variables to their value or to `null`
+/// if `.envrc` unsets them. Unset variables are left out, direnv prints nothing if `.envrc`
+/// changes nothing.
+pub fn parse_direnv_export(json: &str) -> Result<HashMap<String, String>, serde_json::Error> {
+    if json.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    let vars: HashMap<String, Option<String>> = serde_json::from_str(json)?;
+    Ok(vars
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect())
+}
+
+/// The [SHELL] arguments which print the environment with `NUL` separated variables, after
+/// running `init` like `source env.sh` if given. Variables `init` assigns are exported and its
+/// output is discarded.
+pub fn env_args(init: Option<&str>) -> Vec<String> {
+    let script = match init {
+        Some(init) => format!("set -a\n{{ {init}\n}} >/dev/null </dev/null\nset +a\nenv -0"),
+        None => "env -0".to_string(),
+    };
+    vec!["-c".to_string(), script]
+}
+
+/// Parses the output of `env -0`
+pub fn parse_env(output: &str) -> HashMap<String, String> {
+    output
+        .split('\0')
+        .filter_map(|var| var.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// The variables of `after` which are new or differ from `before`, the changes an init command
+/// made to the environment
+pub fn changed_vars(
+    before: &HashMap<String, String>,
+    after: HashMap<String, String>,
+) -> HashMap<String, String> {
+    after
+        .into_iter()
+        .filter(|(key, value)| {
+            !SHELL_VARS.contains(&key.as_str()) && before.get(key) != Some(value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn captured_variables() {
+        let direnv = parse_direnv_export(
+            r#"{"PKG_CONFIG_PATH":"/opt/sysroot/lib/pkgconfig","OLD_VAR":null}"#,
+        )
+        .unwrap();
+        check!(
+            direnv
+                == HashMap::from([(
+                    "PKG_CONFIG_PATH".to_string(),
+                    "/opt/sysroot/lib/pkgconfig".to_string()
+                )])
+        );
+        check!(parse_direnv_export("").unwrap().is_empty());
+        check!(parse_direnv_export("direnv: error").is_err());
+
+        check!(
+            env_args(Some("source env.sh"))
+                == [
+                    "-c",
+                    "set -a\n{ source env.sh\n} >/dev/null </dev/null\nset +a\nenv -0"
+                ]
+        );
+
+        let before = parse_env("HOME=/home/dev\0PATH=/usr/bin\0SHLVL=1\0");
+        let after =
+            parse_env("HOME=/home/dev\0PATH=/opt/cross/bin:/usr/bin\0SHLVL=2\0CC=clang\0EMPTY=\0");
+        check!(
+            changed_vars(&before, after)
+                == HashMap::from([
+                    ("PATH".to_string(), "/opt/cross/bin:/usr/bin".to_string()),
+                    ("CC".to_string(), "clang".to_string()),
+                    ("EMPTY".to_string(), String::new()),
+                ])
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Hashes which stay the same across builds and versions of Rust, unlike the ones of
+//! [std::hash::DefaultHasher], for hashes persisted between sessions
+
+use std::hash::{Hash, Hasher};
+
+const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const PRIME: u64 = 0x0100_0000_01b3;
+
+/// A 64 bit FNV-1a [Hasher]. Lengths are hashed as `u64` so that 32 and 64 bit targets agree.
+#[derive(Debug, Clone, Copy)]
+pub struct StableHasher(u64);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self(OFFSET_BASIS)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(PRIME);
+        }
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write(&(i as u64).to_le_bytes());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+}
+
+/// The [StableHasher] hash of `value`
+pub fn stable_hash(value: &impl Hash) -> u64 {
+    let mut hasher = StableHasher::default();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn hashes_are_stable() {
+        let mut hasher = StableHasher::default();
+        hasher.write(b"a");
+        check!(hasher.finish() == 0xaf63_dc4c_8601_ec8c);
+        check!(stable_hash(&vec!["a".to_string()]) == stable_hash(&["a"]));
+        check!(stable_hash(&(1u64, "a")) != stable_hash(&(2u64, "a")));
+    }
+}
@@ -0,0 +1,229 @@
+use crate::cargo::{
+    Config, Profile,
+    metadata::{Package, TargetKind},
+};
+
+pub const TRUNK: &str = "trunk";
+pub const WASM_PACK: &str = "wasm-pack";
+/// The platform target of wasm-bindgen builds
+pub const WASM_TARGET: &str = "wasm32-unknown-unknown";
+/// Trunk builds packages with one of these files in their directory
+pub const TRUNK_FILES: [&str; 2] = ["Trunk.toml", "index.html"];
+
+/// The tool which builds a package depending on wasm-bindgen
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmTool {
+    /// Builds and serves the web application of a package with an `index.html`
+    Trunk,
+    /// Builds the `cdylib` of a package into an npm package
+    WasmPack,
+    /// Builds the package with cargo for [WASM_TARGET], wasm-bindgen runs separately
+    WasmBindgen,
+}
+
+/// Whether `package` depends on wasm-bindgen, its build dependencies don't count
+pub fn depends_on_wasm_bindgen(package: &Package) -> bool {
+    package
+        .registry_dependencies
+        .iter()
+        .any(|d| d.name == "wasm-bindgen" && d.kind != Some("build"))
+}
+
+impl WasmTool {
+    /// The tool which builds `package`, [None] if it doesn't depend on wasm-bindgen.
+    /// `has_trunk_file` is whether one of the [TRUNK_FILES] is in its directory.
+    pub fn of(package: &Package, has_trunk_file: bool) -> Option<Self> {
+        if !depends_on_wasm_bindgen(package) {
+            return None;
+        }
+        let cdylib = package
+            .targets
+            .iter()
+            .any(|t| t.target_kind.contains(&TargetKind::CDyLib));
+        Some(if has_trunk_file {
+            Self::Trunk
+        } else if cdylib {
+            Self::WasmPack
+        } else {
+            Self::WasmBindgen
+        })
+    }
+
+    /// The program running the tool, [None] for cargo
+    pub fn program(self) -> Option<&'static str> {
+        match self {
+            Self::Trunk => Some(TRUNK),
+            Self::WasmPack => Some(WASM_PACK),
+            Self::WasmBindgen => None,
+        }
+    }
+
+    /// Where to find the installation instructions of the tool
+    pub fn install_url(self) -> &'static str {
+        match self {
+            Self::Trunk => "https://trunkrs.dev",
+            Self::WasmPack => "https://rustwasm.github.io/wasm-pack/installer",
+            Self::WasmBindgen => "https://rustwasm.github.io/docs/wasm-bindgen",
+        }
+    }
+
+    /// The arguments which build `package` with the selected profile and features of `config`.
+    /// Trunk and wasm-pack run in the package directory.
+    pub fn build_args(self, package: &str, config: &Config) -> Vec<String> {
+        let features = config.feature_args(Some(package));
+        match self {
+            Self::Trunk => std::iter::once("build".to_string())
+                .chain(trunk_profile_args(&config.profile))
+                .chain(features)
+                .collect(),
+            Self::WasmPack => {
+                let mut args = ["build", "--target", "web"].map(str::to_string).to_vec();
+                args.extend(wasm_pack_profile_args(&config.profile));
+                // Arguments after `--` are passed to cargo
+                if !features.is_empty() {
+                    args.push("--".to_string());
+                    args.extend(features);
+                }
+                args
+            }
+            Self::WasmBindgen => ["build", "--package", package, "--target", WASM_TARGET]
+                .into_iter()
+                .map(str::to_string)
+                .chain(config.profile.cargo_args())
+                .chain(features)
+                .collect(),
+        }
+    }
+
+    /// The arguments which serve `package` on a dev server which rebuilds it on changes,
+    /// [None] if only Trunk has one
+    pub fn serve_args(self, package: &str, config: &Config) -> Option<Vec<String>> {
+        match self {
+            Self::Trunk => {
+                let mut args = self.build_args(package, config);
+                args[0] = "serve".to_string();
+                Some(args)
+            }
+            Self::WasmPack | Self::WasmBindgen => None,
+        }
+    }
+}
+
+/// Trunk builds in release mode with `--release`, other profiles than dev are cargo's
+fn trunk_profile_args(profile: &Profile) -> Vec<String> {
+    match profile {
+        Profile::None | Profile::Dev => Vec::new(),
+        Profile::Release => vec!["--release".to_string()],
+        profile => profile
+            .get_name()
+            .map(|name| vec!["--cargo-profile".to_string(), name.to_string()])
+            .unwrap_or_default(),
+    }
+}
+
+/// wasm-pack builds in release mode unless `--dev` is given
+fn wasm_pack_profile_args(profile: &Profile) -> Vec<String> {
+    match profile {
+        Profile::None | Profile::Dev => vec!["--dev".to_string()],
+        Profile::Release => vec!["--release".to_string()],
+        profile => profile
+            .get_name()
+            .map(|name| vec!["--profile".to_string(), name.to_string()])
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert2::check;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+    use crate::cargo::{
+        ConfigUpdate, Features,
+        config::FeatureTarget,
+        metadata::{RegistryDependency, Target, TargetType},
+    };
+
+    fn package(kind: TargetKind) -> Package {
+        Package {
+            name: "app".to_string(),
+            manifest: "/ws/app/Cargo.toml".to_string(),
+            targets: vec![Target {
+                name: "app".to_string(),
+                source: "/ws/app/src/lib.rs".to_string(),
+                target_type: TargetType::Lib,
+                target_kind: vec![kind],
+                required_features: Vec::new(),
+            }],
+            features: vec!["hydrate".to_string()],
+            dependencies: Vec::new(),
+            registry_dependencies: vec![RegistryDependency {
+                name: "wasm-bindgen".to_string(),
+                req: "^0.2".to_string(),
+                kind: None,
+                registry: None,
+            }],
+        }
+    }
+
+    #[wasm_bindgen_test(unsupported = test)]
+    fn wasm_tools_and_args() {
+        check!(WasmTool::of(&package(TargetKind::Lib), true) == Some(WasmTool::Trunk));
+        check!(WasmTool::of(&package(TargetKind::CDyLib), false) == Some(WasmTool::WasmPack));
+        check!(WasmTool::of(&package(TargetKind::Lib), false) == Some(WasmTool::WasmBindgen));
+        let mut native = package(TargetKind::Lib);
+        native.registry_dependencies.clear();
+        check!(WasmTool::of(&native, true).is_none());
+
+        let mut config = Config::default();
+        config.update(ConfigUpdate::SelectedProfile(Profile::Release));
+        config.update(ConfigUpdate::SelectedFeatures {
+            feature_target: FeatureTarget::Package("app".to_string()),
+            features: Features::Some(vec!["hydrate".to_string()]),
+        });
+        check!(
+            WasmTool::Trunk.serve_args("app", &config)
+                == Some(
+                    ["serve", "--release", "--features", "hydrate"]
+                        .map(str::to_string)
+                        .to_vec()
+                )
+        );
+        check!(
+            WasmTool::WasmPack.build_args("app", &config)
+                == [
+                    "build",
+                    "--target",
+                    "web",
+                    "--release",
+                    "--",
+                    "--features",
+                    "hydrate"
+                ]
+        );
+        check!(WasmTool::WasmPack.serve_args("app", &config).is_none());
+
+        config.update(ConfigUpdate::SelectedProfile(Profile::Custom(
+            "web".to_string(),
+        )));
+        check!(
+            WasmTool::WasmBindgen.build_args("app", &config)
+                == [
+                    "build",
+                    "--package",
+                    "app",
+                    "--target",
+                    "wasm32-unknown-unknown",
+                    "--profile",
+                    "web",
+                    "--features",
+                    "hydrate"
+                ]
+        );
+        check!(
+            WasmTool::Trunk.build_args("app", &config)
+                == ["build", "--cargo-profile", "web", "--features", "hydrate"]
+        );
+    }
+}
@@ -1,5 +1,5 @@
 // Update this number when adding commands
-pub const NUMBER_CMDS: usize = 18;
+pub const NUMBER_CMDS: usize = 79;
 
 pub const CARGO_TOOLS_SELECT_PROFILE: &str = "cargo-tools.selectProfile";
 pub const CARGO_TOOLS_SELECT_PACKAGE: &str = "cargo-tools.selectPackage";
@@ -14,10 +14,75 @@ pub const CARGO_TOOLS_BUILD_DOCS: &str = "cargo-tools.buildDocs";
 pub const CARGO_TOOLS_SELECT_FEATURES: &str = "cargo-tools.selectFeatures";
 pub const CARGO_TOOLS_REFRESH: &str = "cargo-tools.refresh";
 pub const CARGO_TOOLS_CLEAN: &str = "cargo-tools.clean";
+pub const CARGO_TOOLS_BUILD_PACKAGES: &str = "cargo-tools.buildPackages";
+pub const CARGO_TOOLS_CANCEL_BUILD: &str = "cargo-tools.cancelBuild";
+pub const CARGO_TOOLS_TOGGLE_WATCH: &str = "cargo-tools.toggleWatch";
+pub const CARGO_TOOLS_MAKE_DEFAULT_BUILD_TASK: &str = "cargo-tools.makeDefaultBuildTask";
+pub const CARGO_TOOLS_BUILD_AND_TEST: &str = "cargo-tools.buildAndTest";
+pub const CARGO_TOOLS_RUN_IN_CLEAN_ENVIRONMENT: &str = "cargo-tools.runInCleanEnvironment";
+pub const CARGO_TOOLS_RUN_TEST: &str = "cargo-tools.runTest";
+pub const CARGO_TOOLS_DEBUG_TEST: &str = "cargo-tools.debugTest";
+pub const CARGO_TOOLS_ATTACH_TO_PROCESS: &str = "cargo-tools.attachToProcess";
+pub const CARGO_TOOLS_DEBUG_DOC_TEST: &str = "cargo-tools.debugDocTest";
+pub const CARGO_TOOLS_RUN_DOC_TEST: &str = "cargo-tools.runDocTest";
+pub const CARGO_TOOLS_RERUN_FAILED_TESTS: &str = "cargo-tools.rerunFailedTests";
+pub const CARGO_TOOLS_SHOW_SLOWEST_TESTS: &str = "cargo-tools.showSlowestTests";
+pub const CARGO_TOOLS_TEST_CHANGED_PACKAGES: &str = "cargo-tools.testChangedPackages";
+pub const CARGO_TOOLS_SELECT_NEXTEST_PROFILE: &str = "cargo-tools.selectNextestProfile";
+pub const CARGO_TOOLS_RUN_SNAPSHOT_TESTS: &str = "cargo-tools.runSnapshotTests";
+pub const CARGO_TOOLS_REVIEW_SNAPSHOTS: &str = "cargo-tools.reviewSnapshots";
+pub const CARGO_TOOLS_DEBUG_LAST: &str = "cargo-tools.debugLast";
+pub const CARGO_TOOLS_OPEN_CORE_DUMP: &str = "cargo-tools.openCoreDump";
+pub const CARGO_TOOLS_SHOW_COMPILER_CACHE_STATS: &str = "cargo-tools.showCompilerCacheStats";
+pub const CARGO_TOOLS_SHOW_BINARY_SIZE_TREND: &str = "cargo-tools.showBinarySizeTrend";
+pub const CARGO_TOOLS_OPEN_BENCHMARK_REPORT: &str = "cargo-tools.openBenchmarkReport";
+pub const CARGO_TOOLS_REFRESH_BUILD_RESULTS: &str = "cargo-tools.refreshBuildResults";
+pub const CARGO_TOOLS_FILTER_BUILD_RESULTS: &str = "cargo-tools.filterBuildResults";
+pub const CARGO_TOOLS_SHOW_PACKAGE_PROBLEMS: &str = "cargo-tools.showPackageProblems";
+pub const CARGO_TOOLS_FLASH_AND_RUN: &str = "cargo-tools.flashAndRun";
+pub const CARGO_TOOLS_ATTACH_RTT: &str = "cargo-tools.attachRtt";
+pub const CARGO_TOOLS_NEW_PACKAGE: &str = "cargo-tools.newPackage";
+pub const CARGO_TOOLS_NEW_PACKAGE_FROM_TEMPLATE: &str = "cargo-tools.newPackageFromTemplate";
+pub const CARGO_TOOLS_SELECT_WORKSPACE: &str = "cargo-tools.selectWorkspace";
+pub const CARGO_TOOLS_OPEN_DEPENDENCY_DOCS: &str = "cargo-tools.openDependencyDocs";
+pub const CARGO_TOOLS_UPDATE_DEPENDENCY: &str = "cargo-tools.updateDependency";
+pub const CARGO_TOOLS_BUMP_VERSION: &str = "cargo-tools.bumpVersion";
+pub const CARGO_TOOLS_RENAME_PACKAGE: &str = "cargo-tools.renamePackage";
+pub const CARGO_TOOLS_NORMALIZE_MANIFEST: &str = "cargo-tools.normalizeManifest";
+pub const CARGO_TOOLS_LICENSE_REPORT: &str = "cargo-tools.licenseReport";
+pub const CARGO_TOOLS_EXPLAIN_FEATURES: &str = "cargo-tools.explainFeatures";
+pub const CARGO_TOOLS_SET_RUST_VERSION: &str = "cargo-tools.setRustVersion";
+pub const CARGO_TOOLS_VERIFY_RUST_VERSION: &str = "cargo-tools.verifyRustVersion";
+pub const CARGO_TOOLS_PROMOTE_TO_WORKSPACE_DEPENDENCY: &str =
+    "cargo-tools.promoteToWorkspaceDependency";
+pub const CARGO_TOOLS_RUN_PRE_COMMIT_CHECKS: &str = "cargo-tools.runPreCommitChecks";
+pub const CARGO_TOOLS_INSTALL_PRE_COMMIT_HOOK: &str = "cargo-tools.installPreCommitHook";
+pub const CARGO_TOOLS_RELOAD_SHELL_ENVIRONMENT: &str = "cargo-tools.reloadShellEnvironment";
+pub const CARGO_TOOLS_MANAGE_TOOLCHAIN_COMPONENTS: &str = "cargo-tools.manageToolchainComponents";
+pub const CARGO_TOOLS_ADD_DEPENDENCY: &str = "cargo-tools.addDependency";
+pub const CARGO_TOOLS_PUBLISH_PACKAGE: &str = "cargo-tools.publishPackage";
+pub const CARGO_TOOLS_SET_REGISTRY_TOKEN: &str = "cargo-tools.setRegistryToken";
+pub const CARGO_TOOLS_BUILD_WASM: &str = "cargo-tools.buildWasm";
+pub const CARGO_TOOLS_SERVE_WASM: &str = "cargo-tools.serveWasm";
+pub const CARGO_TOOLS_GENERATE_C_HEADER: &str = "cargo-tools.generateCHeader";
+pub const CARGO_TOOLS_MANIFEST_LENS_BUILD: &str = "cargo-tools.manifestLens.build";
+pub const CARGO_TOOLS_MANIFEST_LENS_TEST: &str = "cargo-tools.manifestLens.test";
+pub const CARGO_TOOLS_MANIFEST_LENS_RUN: &str = "cargo-tools.manifestLens.run";
+pub const CARGO_TOOLS_MANIFEST_LENS_DEBUG: &str = "cargo-tools.manifestLens.debug";
+pub const CARGO_TOOLS_MANIFEST_LENS_SELECT: &str = "cargo-tools.manifestLens.select";
+pub const CARGO_TOOLS_MANIFEST_LENS_TOGGLE_FEATURE: &str = "cargo-tools.manifestLens.toggleFeature";
 pub const CARGO_TOOLS_PROJECT_STATUS_BUILD: &str = "cargo-tools.projectStatus.build";
 pub const CARGO_TOOLS_PROJECT_STATUS_RUN: &str = "cargo-tools.projectStatus.run";
 pub const CARGO_TOOLS_PROJECT_STATUS_DEBUG: &str = "cargo-tools.projectStatus.debug";
+pub const CARGO_TOOLS_PROJECT_STATUS_RUN_WITH_ARGS: &str = "cargo-tools.projectStatus.runWithArgs";
+pub const CARGO_TOOLS_PROJECT_STATUS_DEBUG_WITH_ARGS: &str =
+    "cargo-tools.projectStatus.debugWithArgs";
+pub const CARGO_TOOLS_PROJECT_STATUS_RUN_WITH_SANITIZER: &str =
+    "cargo-tools.projectStatus.runWithSanitizer";
+pub const CARGO_TOOLS_PROJECT_STATUS_DEBUG_WITH_SANITIZER: &str =
+    "cargo-tools.projectStatus.debugWithSanitizer";
 pub const CARGO_TOOLS_PROJECT_STATUS_TEST: &str = "cargo-tools.projectStatus.test";
 pub const CARGO_TOOLS_PROJECT_STATUS_BENCH: &str = "cargo-tools.projectStatus.bench";
+pub const CARGO_TOOLS_PROJECT_STATUS_DEBUG_BENCH: &str = "cargo-tools.projectStatus.debugBench";
 pub const CARGO_TOOLS_PROJECT_STATUS_TOGGLE_FEATURE: &str =
     "cargo-tools.projectStatus.toggleFeature";
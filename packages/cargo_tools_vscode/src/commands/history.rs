@@ -0,0 +1,7 @@
+// Update this number when adding commands
+pub const NUMBER_CMDS: usize = 4;
+
+pub const CARGO_TOOLS_TASK_HISTORY_SHOW: &str = "cargo-tools.taskHistory.show";
+pub const CARGO_TOOLS_TASK_HISTORY_RERUN: &str = "cargo-tools.taskHistory.rerun";
+pub const CARGO_TOOLS_TASK_HISTORY_COPY_COMMAND: &str = "cargo-tools.taskHistory.copyCommand";
+pub const CARGO_TOOLS_TASK_HISTORY_CLEAR: &str = "cargo-tools.taskHistory.clear";
@@ -0,0 +1,6 @@
+// Update this number when adding commands
+pub const NUMBER_CMDS: usize = 3;
+
+pub const CARGO_TOOLS_JUST_RUN_RECIPE: &str = "cargo-tools.just.runRecipe";
+pub const CARGO_TOOLS_JUST_RUN_RECIPE_WITH_ARGS: &str = "cargo-tools.just.runRecipeWithArgs";
+pub const CARGO_TOOLS_JUST_SELECT_AND_RUN: &str = "cargo-tools.just.selectAndRunRecipe";
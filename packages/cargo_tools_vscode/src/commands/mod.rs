@@ -3,7 +3,10 @@
 /// run tests that make sure that all commands in package.json are also implemented
 pub mod cargo_make;
 pub mod configuration;
+pub mod history;
+pub mod just;
 pub mod outline;
 pub mod pinned;
 pub mod tasks;
+pub mod variables;
 pub mod xtask;
@@ -1,5 +1,5 @@
 // Update this number when adding commands
-pub const NUMBER_CMDS: usize = 25;
+pub const NUMBER_CMDS: usize = 33;
 
 pub const CARGO_TOOLS_PROJECT_OUTLINE_SELECT_PACKAGE: &str =
     "cargo-tools.projectOutline.selectPackage";
@@ -32,6 +32,8 @@ pub const CARGO_TOOLS_PROJECT_OUTLINE_BUILD_TARGET: &str = "cargo-tools.projectO
 pub const CARGO_TOOLS_PROJECT_OUTLINE_RUN_TARGET: &str = "cargo-tools.projectOutline.runTarget";
 pub const CARGO_TOOLS_PROJECT_OUTLINE_DEBUG_TARGET: &str = "cargo-tools.projectOutline.debugTarget";
 pub const CARGO_TOOLS_PROJECT_OUTLINE_BENCH_TARGET: &str = "cargo-tools.projectOutline.benchTarget";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_DEBUG_BENCH_TARGET: &str =
+    "cargo-tools.projectOutline.debugBenchTarget";
 pub const CARGO_TOOLS_PROJECT_OUTLINE_SET_WORKSPACE_MEMBER_FILTER: &str =
     "cargo-tools.projectOutline.setWorkspaceMemberFilter";
 pub const CARGO_TOOLS_PROJECT_OUTLINE_EDIT_WORKSPACE_MEMBER_FILTER: &str =
@@ -46,3 +48,15 @@ pub const CARGO_TOOLS_PROJECT_OUTLINE_TOGGLE_WORKSPACE_MEMBER_GROUPING: &str =
     "cargo-tools.projectOutline.toggleWorkspaceMemberGrouping";
 pub const CARGO_TOOLS_PROJECT_OUTLINE_TOGGLE_FEATURE: &str =
     "cargo-tools.projectOutline.toggleFeature";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_OPEN_ON_CRATES_IO: &str =
+    "cargo-tools.projectOutline.openOnCratesIo";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_OPEN_DOCS_RS: &str = "cargo-tools.projectOutline.openDocsRs";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_GO_TO_OVERRIDE: &str =
+    "cargo-tools.projectOutline.goToOverride";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_EXPLAIN_DEPENDENCY: &str =
+    "cargo-tools.projectOutline.explainDependency";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_NEW_EXAMPLE: &str = "cargo-tools.projectOutline.newExample";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_NEW_INTEGRATION_TEST: &str =
+    "cargo-tools.projectOutline.newIntegrationTest";
+pub const CARGO_TOOLS_PROJECT_OUTLINE_NEW_BENCHMARK: &str =
+    "cargo-tools.projectOutline.newBenchmark";
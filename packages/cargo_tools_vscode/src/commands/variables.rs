@@ -0,0 +1,7 @@
+// Update this number when adding commands
+pub const NUMBER_CMDS: usize = 4;
+
+pub const CARGO_TOOLS_SELECTED_PACKAGE: &str = "cargo-tools.selectedPackage";
+pub const CARGO_TOOLS_SELECTED_TARGET_NAME: &str = "cargo-tools.selectedTargetName";
+pub const CARGO_TOOLS_LAUNCH_TARGET_PATH: &str = "cargo-tools.launchTargetPath";
+pub const CARGO_TOOLS_BUILD_PROFILE_FLAG: &str = "cargo-tools.buildProfileFlag";
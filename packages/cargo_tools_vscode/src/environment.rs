@@ -1,9 +1,24 @@
-use cargo_tools::{CargoCommand, process::CargoTaskContext};
+use cargo_tools::{
+    CargoCommand,
+    cargo::{
+        Profile, Template, licenses::DEFAULT_ALLOWED_LICENSES, normalize_manifest::DependencyStyle,
+        pre_commit::Check, runner,
+    },
+    cbindgen,
+    debugger::{DebugOverrides, RemoteTarget},
+    just,
+    path_mapping::PathMappings,
+    process::CargoTaskContext,
+    rustup, sccache,
+    wasm::WasmTool,
+};
 use serde::{Serialize, de::DeserializeOwned};
 use serde_wasm_bindgen::{from_value, to_value};
-use std::collections::HashMap;
+use std::{cell::RefCell, collections::HashMap, time::Duration};
 use wasm_bindgen::{JsValue, prelude::wasm_bindgen};
 
+use crate::runtime::ExecutionMode;
+
 #[wasm_bindgen(raw_module = "../../../packages/cargo_tools_vscode/src/environment.ts")]
 extern "C" {
     fn get_config(
@@ -16,45 +31,409 @@ extern "C" {
 
 const CARGO_TOOLS_SECTION: &str = "cargoTools";
 const RUST_ANALYZER_SECTION: &str = "rust-analyzer";
+const RUSTC_WRAPPER: &str = "RUSTC_WRAPPER";
+const CARGO_TARGET_DIR: &str = "CARGO_TARGET_DIR";
 
-fn general_task_context() -> CargoTaskContext {
-    VsCodeTaskContext::General.to_cargo_task_context()
+/// The settings changing the packages or the target directory `cargo metadata` reports
+pub const METADATA_SETTINGS: [&str; 4] = [
+    "cargoTools.targetDir",
+    "cargoTools.extraEnv",
+    "cargoTools.envFile",
+    "cargoTools.cargoCommand",
+];
+
+/// The setting enabling `cross`, see [cross_enabled]
+pub const CROSS_ENABLED_SETTING: &str = "cargoTools.cross.enabled";
+
+thread_local! {
+    /// Variables loaded from the configured env files, see [set_env_file_vars]
+    static ENV_FILE_VARS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+
+    /// Variables captured from direnv or the shell init command, see [set_shell_env_vars]
+    static SHELL_ENV_VARS: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+
+    /// Root of the cargo workspace, see [set_workspace_root]
+    static WORKSPACE_ROOT: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// The VS Code workspace folder, see [set_workspace_folder]
+    static WORKSPACE_FOLDER: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// Host triple of the toolchain if `cross` is installed, see [set_cross_host]
+    static CROSS_HOST: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// The default WSL distribution, see [set_default_wsl_distro]
+    static DEFAULT_WSL_DISTRO: RefCell<Option<String>> = const { RefCell::new(None) };
+
+    /// Variables of the selection for project tasks, see [set_selection_env]
+    static SELECTION_ENV: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+
+    /// Root manifest of the discovered workspace in use instead of `cargoTools.manifestPath`,
+    /// see [set_active_manifest]
+    static ACTIVE_MANIFEST: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Sets the variables of the env files which are added to every task
+pub fn set_env_file_vars(vars: HashMap<String, String>) {
+    ENV_FILE_VARS.with(|env| *env.borrow_mut() = vars);
+}
+
+/// Sets the variables captured from direnv or the shell init command which are added to every
+/// task
+pub fn set_shell_env_vars(vars: HashMap<String, String>) {
+    SHELL_ENV_VARS.with(|env| *env.borrow_mut() = vars);
+}
+
+/// Sets the root of the cargo workspace every task runs in
+pub fn set_workspace_root(root: Option<String>) {
+    WORKSPACE_ROOT.with(|workspace_root| *workspace_root.borrow_mut() = root);
+}
+
+/// Sets the VS Code workspace folder the root manifest of `cargoTools.manifestPath` is in
+pub fn set_workspace_folder(folder: String) {
+    WORKSPACE_FOLDER.with(|workspace_folder| *workspace_folder.borrow_mut() = Some(folder));
+}
+
+/// The shared target directory of `cargoTools.targetDir`, relative paths are resolved against
+/// the directory of the root manifest. Cargo itself resolves them against its working directory,
+/// which differs between `cargo metadata` and the tasks.
+pub fn shared_target_dir() -> Option<String> {
+    let dir = get(CARGO_TOOLS_SECTION, "targetDir", String::new());
+    if dir.is_empty() {
+        return None;
+    }
+    let is_absolute = dir.starts_with(['/', '\\']) || dir.chars().nth(1) == Some(':');
+    if is_absolute {
+        return Some(dir);
+    }
+    let folder = WORKSPACE_FOLDER.with(|folder| folder.borrow().clone())?;
+    let manifest = format!("{folder}/{}", manifest_path());
+    let manifest_dir = manifest
+        .rsplit_once('/')
+        .map_or(folder.as_str(), |(dir, _)| dir);
+    Some(format!("{manifest_dir}/{}", dir.trim_start_matches("./")))
+}
+
+/// Sets the host triple builds for other platform targets run with `cross` for, `None` if
+/// `cross` isn't available
+pub fn set_cross_host(host: Option<String>) {
+    CROSS_HOST.with(|cross_host| *cross_host.borrow_mut() = host);
+}
+
+/// Sets the distribution a `wsl cargo` command without `-d` runs in, `None` if it is unknown
+pub fn set_default_wsl_distro(distro: Option<String>) {
+    DEFAULT_WSL_DISTRO.with(|default_distro| *default_distro.borrow_mut() = distro);
+}
+
+/// Sets the variables which pass the selection to cargo-make tasks, aliases and just recipes
+pub fn set_selection_env(env: HashMap<String, String>) {
+    SELECTION_ENV.with(|selection_env| *selection_env.borrow_mut() = env);
+}
+
+/// Whether dependencies in the Project Outline show the latest version, downloads and
+/// description from crates.io, off by default as it sends the dependencies to crates.io
+pub fn crates_io_metadata_enabled() -> bool {
+    get(CARGO_TOOLS_SECTION, "cratesIo.fetchMetadata", false)
+}
+
+/// Whether `cross` replaces cargo for builds of a selected non-host platform target
+pub fn cross_enabled() -> bool {
+    get(CARGO_TOOLS_SECTION, "cross.enabled", false)
+}
+
+/// Whether cargo commands use sccache as `RUSTC_WRAPPER` unless one is configured
+pub fn sccache_enabled() -> bool {
+    get(CARGO_TOOLS_SECTION, "sccache.enabled", false)
+}
+
+/// The `RUSTC_WRAPPER` of cargo commands, configured or `host_wrapper` of the environment
+/// VS Code runs in
+pub fn rustc_wrapper(host_wrapper: Option<String>) -> Option<String> {
+    VsCodeTaskContext::General
+        .env()
+        .remove(RUSTC_WRAPPER)
+        .or(host_wrapper)
+        .filter(|wrapper| !wrapper.is_empty())
+}
+
+/// The chip probe-rs flashes, probe-rs asks for one if none is configured
+pub fn probe_rs_chip() -> Option<String> {
+    Some(get(CARGO_TOOLS_SECTION, "probeRs.chip", String::new())).filter(|chip| !chip.is_empty())
+}
+
+/// The favorite cargo-generate templates of `cargoTools.cargoGenerate.templates` by name
+pub fn cargo_generate_templates() -> HashMap<String, Template> {
+    get(
+        CARGO_TOOLS_SECTION,
+        "cargoGenerate.templates",
+        HashMap::new(),
+    )
+}
+
+/// Path of the workspace's root manifest, relative to the VS Code workspace folder
+pub fn manifest_path() -> String {
+    ACTIVE_MANIFEST
+        .with(|manifest| manifest.borrow().clone())
+        .unwrap_or_else(configured_manifest_path)
 }
 
-fn run_task_context() -> CargoTaskContext {
-    VsCodeTaskContext::Run.to_cargo_task_context()
+/// The root manifest of `cargoTools.manifestPath`
+pub fn configured_manifest_path() -> String {
+    get(
+        CARGO_TOOLS_SECTION,
+        "manifestPath",
+        "Cargo.toml".to_string(),
+    )
 }
 
-fn test_task_context() -> CargoTaskContext {
-    VsCodeTaskContext::Test.to_cargo_task_context()
+/// Uses the root manifest `manifest` of a discovered workspace instead of
+/// `cargoTools.manifestPath`, [None] returns to the configured one
+pub fn set_active_manifest(manifest: Option<String>) {
+    let manifest = manifest.filter(|manifest| *manifest != configured_manifest_path());
+    ACTIVE_MANIFEST.with(|active| *active.borrow_mut() = manifest);
+}
+
+/// The directory under which the selection of the workspace in use is persisted, the VS Code
+/// folder `root_dir` for the configured root manifest so its selection is kept
+pub fn project_state_root(root_dir: &str) -> String {
+    ACTIVE_MANIFEST.with(|manifest| match manifest.borrow().as_deref() {
+        Some(manifest) => {
+            let dir = manifest.rsplit_once('/').map_or("", |(dir, _)| dir);
+            format!("{root_dir}/{dir}")
+        }
+        None => root_dir.to_string(),
+    })
+}
+
+/// How many directories below the VS Code folder Cargo workspaces are discovered
+pub fn discovery_max_depth() -> u32 {
+    get(CARGO_TOOLS_SECTION, "discovery.maxDepth", 3)
+}
+
+/// Workspaces with more members show their packages in the Project Outline collapsed and list
+/// the tests of a package in the Testing view once it is expanded
+pub fn large_workspace_threshold() -> usize {
+    get(
+        CARGO_TOOLS_SECTION,
+        "largeWorkspace.packageThreshold",
+        30u32,
+    ) as usize
+}
+
+/// Milliseconds the manifest and cargo config watcher waits for more changes before refreshing
+pub fn manifest_watcher_debounce() -> u32 {
+    get(CARGO_TOOLS_SECTION, "manifestWatcher.debounce", 300)
+}
+
+/// Globs of the directories which are skipped when discovering Cargo workspaces
+pub fn discovery_exclude() -> Vec<String> {
+    get(
+        CARGO_TOOLS_SECTION,
+        "discovery.exclude",
+        ["**/target/**", "**/node_modules/**", "**/.git/**"]
+            .map(str::to_string)
+            .to_vec(),
+    )
+}
+
+/// Path of the env file loaded into every task, relative to the workspace root
+pub fn env_file() -> String {
+    get(CARGO_TOOLS_SECTION, "envFile", ".env".to_string())
+}
+
+/// Whether the variables `direnv` loads from `.envrc` are set for every cargo command
+pub fn shell_env_direnv() -> bool {
+    get(CARGO_TOOLS_SECTION, "shellEnv.direnv", false)
+}
+
+/// The shell command like `source env.sh` whose exported variables are set for every cargo
+/// command, `None` if it isn't configured
+pub fn shell_env_init_command() -> Option<String> {
+    let init: String = get(CARGO_TOOLS_SECTION, "shellEnv.initCommand", String::new());
+    let init = init.trim();
+    (!init.is_empty()).then(|| init.to_string())
+}
+
+/// The debug adapter used for generated debug configurations, `auto` detects one
+pub fn debugger() -> String {
+    get(CARGO_TOOLS_SECTION, "debugger", "auto".to_string())
+}
+
+/// The debug server configured for the platform target, debug sessions of executables built
+/// for it connect to the server instead of running them on the host
+pub fn remote_target(platform_target: Option<&str>) -> Option<RemoteTarget> {
+    let mut targets: HashMap<String, RemoteTarget> =
+        get(CARGO_TOOLS_SECTION, "debug.remoteTargets", HashMap::new());
+    targets.remove(platform_target?)
+}
+
+/// The overrides of `cargoTools.debug.targetOverrides` for the run target `target`
+pub fn debug_overrides(target: &str) -> DebugOverrides {
+    let mut overrides: HashMap<String, DebugOverrides> =
+        get(CARGO_TOOLS_SECTION, "debug.targetOverrides", HashMap::new());
+    overrides.remove(target).unwrap_or_default()
+}
+
+/// Whether debug sessions of the run target `target` follow the child processes it spawns
+pub fn follow_child_processes(target: &str) -> bool {
+    let targets: Vec<String> = get(
+        CARGO_TOOLS_SECTION,
+        "debug.followChildProcesses",
+        Vec::new(),
+    );
+    targets.iter().any(|t| t == target)
+}
+
+fn general_task_context() -> CargoTaskContext {
+    VsCodeTaskContext::General.to_cargo_task_context()
 }
 
 pub trait CommandExt {
     fn ctx(&self) -> CargoTaskContext;
+
+    /// Whether the command runs as VS Code task or in the Cargo Tools terminal
+    fn execution_mode(&self) -> ExecutionMode;
 }
 
 impl CommandExt for CargoCommand {
     fn ctx(&self) -> CargoTaskContext {
-        match self {
-            Self::Run(_) | Self::Debug(_) => run_task_context(),
-            Self::Test { package: _ } => test_task_context(),
-            Self::Build(_) | Self::Bench(_) | Self::Doc | Self::Clean { package: _ } => {
-                general_task_context()
+        let context = VsCodeTaskContext::of(self);
+        let ctx = context.to_cargo_task_context();
+        match context {
+            // Debugging only builds the test executable
+            VsCodeTaskContext::Test if !matches!(self, CargoCommand::DebugTest(_)) => {
+                ctx.with_harness_args(test_harness_args())
             }
+            _ => ctx,
         }
     }
+
+    fn execution_mode(&self) -> ExecutionMode {
+        VsCodeTaskContext::of(self).execution_mode()
+    }
+}
+
+/// `ctx` with the variables of the selection
+fn with_selection_env(ctx: CargoTaskContext) -> CargoTaskContext {
+    SELECTION_ENV.with(|env| {
+        env.borrow().iter().fold(ctx, |ctx, (key, value)| {
+            ctx.with_env(key.clone(), value.clone())
+        })
+    })
 }
 
 pub fn makefile_task_context() -> CargoTaskContext {
-    general_task_context()
+    with_selection_env(general_task_context())
 }
 
 pub fn xtask_task_context() -> CargoTaskContext {
-    general_task_context()
+    with_selection_env(general_task_context())
+}
+
+/// Context of `just`, recipes get the variables of cargo commands but not their extra args
+pub fn just_task_context() -> CargoTaskContext {
+    let ctx = CargoTaskContext::new(
+        VsCodeTaskContext::General.env(),
+        Vec::new(),
+        just::JUST.to_string(),
+    )
+    .with_cwd(WORKSPACE_ROOT.with(|root| root.borrow().clone()));
+    with_selection_env(ctx)
+}
+
+/// Context of `rustup` invocations, they run in the workspace root so its toolchain file applies
+pub fn rustup_task_context() -> CargoTaskContext {
+    CargoTaskContext::new(HashMap::new(), Vec::new(), rustup::RUSTUP.to_string())
+        .with_cwd(WORKSPACE_ROOT.with(|root| root.borrow().clone()))
+}
+
+/// Context of the WASM builds and dev servers of `tool`, which run in the package directory
+/// `dir` with the variables of cargo commands
+pub fn wasm_task_context(tool: WasmTool, dir: String) -> CargoTaskContext {
+    let ctx = match tool.program() {
+        Some(program) => CargoTaskContext::new(
+            VsCodeTaskContext::General.env(),
+            Vec::new(),
+            program.to_string(),
+        ),
+        None => general_task_context(),
+    };
+    with_selection_env(ctx.with_cwd(Some(dir)))
+}
+
+/// Context of cbindgen, which runs in the package directory `dir` to find its config
+pub fn cbindgen_task_context(dir: String) -> CargoTaskContext {
+    CargoTaskContext::new(
+        VsCodeTaskContext::General.env(),
+        Vec::new(),
+        cbindgen::CBINDGEN.to_string(),
+    )
+    .with_cwd(Some(dir))
+}
+
+/// Whether builds of packages with a `cdylib` target regenerate its C header
+pub fn cbindgen_generate_on_build() -> bool {
+    get(CARGO_TOOLS_SECTION, "cbindgen.generateOnBuild", false)
+}
+
+/// Context of `rustc` invocations, they run in the workspace root so its toolchain file applies
+pub fn rustc_task_context() -> CargoTaskContext {
+    CargoTaskContext::new(HashMap::new(), Vec::new(), "rustc".to_string())
+        .with_cwd(WORKSPACE_ROOT.with(|root| root.borrow().clone()))
+}
+
+/// Context of `cmd`, which builds an executable for debugging with `profile`. Profiles without
+/// debug info get it unless `cargoTools.debug.injectDebugInfo` is disabled.
+pub fn debug_build_context(cmd: &CargoCommand, profile: &Profile) -> CargoTaskContext {
+    let ctx = cmd.ctx();
+    if !get(CARGO_TOOLS_SECTION, "debug.injectDebugInfo", true) {
+        return ctx;
+    }
+    match profile.debug_info_env() {
+        Some((key, value)) => ctx.with_env(key, value),
+        None => ctx,
+    }
+}
+
+/// Context of the pre-commit `check`, tests get the configured harness args and clippy denies
+/// warnings
+pub fn pre_commit_task_context(check: Check) -> CargoTaskContext {
+    match check {
+        Check::Test => CargoCommand::TestPackages(Vec::new()).ctx(),
+        Check::Fmt | Check::Clippy => {
+            general_task_context().with_harness_args(check.program_args())
+        }
+    }
+}
+
+/// The checks of **Run Pre-commit Checks** and the pre-commit hook
+pub fn pre_commit_checks() -> Vec<Check> {
+    get(
+        CARGO_TOOLS_SECTION,
+        "preCommit.checks",
+        vec![Check::Fmt, Check::Clippy, Check::Test],
+    )
+}
+
+/// The configured cargo command, e.g. `cargo +nightly`
+pub fn cargo_command() -> String {
+    get(CARGO_TOOLS_SECTION, "cargoCommand", "cargo".to_string())
+}
+
+/// The mappings between the paths of cargo and the extension host of `cargoTools.pathMappings`
+/// and a `wsl` cargo command
+pub fn path_mappings() -> PathMappings {
+    DEFAULT_WSL_DISTRO.with(|default_distro| {
+        PathMappings::new(
+            get(CARGO_TOOLS_SECTION, "pathMappings", HashMap::new()),
+            &cargo_command(),
+            default_distro.borrow().as_deref(),
+        )
+    })
 }
 
 pub fn metadata_task_context() -> CargoTaskContext {
-    general_task_context()
+    // The workspace root is only known once the metadata was parsed
+    general_task_context().with_cwd(None)
 }
 
 /// The context in which a vs code task is run
@@ -67,21 +446,84 @@ enum VsCodeTaskContext {
 }
 
 impl VsCodeTaskContext {
+    fn of(cmd: &CargoCommand) -> Self {
+        match cmd {
+            CargoCommand::Sanitized { cmd, .. } | CargoCommand::Nextest { cmd, .. } => {
+                Self::of(cmd)
+            }
+            CargoCommand::Run(_) => Self::Run,
+            CargoCommand::Test { package: _ }
+            | CargoCommand::TestPackages(_)
+            | CargoCommand::RunTest(_)
+            | CargoCommand::DebugTest(_)
+            | CargoCommand::RunTests { .. }
+            | CargoCommand::RunDocTest { .. }
+            | CargoCommand::Coverage { .. } => Self::Test,
+            // Debugging only builds the target through cargo
+            CargoCommand::Debug(_)
+            | CargoCommand::Build(_)
+            | CargoCommand::Check { package: _ }
+            | CargoCommand::Clippy { package: _ }
+            | CargoCommand::Fmt { package: _ }
+            | CargoCommand::Bench(_)
+            | CargoCommand::DebugBench(_)
+            | CargoCommand::ListDocTests { package: _ }
+            | CargoCommand::DebugDocTest { .. }
+            | CargoCommand::Doc
+            | CargoCommand::Clean { package: _ } => Self::General,
+        }
+    }
+
+    fn execution_mode(self) -> ExecutionMode {
+        let key = match self {
+            Self::General => "executionMode",
+            Self::Run => "run.executionMode",
+            Self::Test => "test.executionMode",
+        };
+        match get(CARGO_TOOLS_SECTION, key, "task".to_string()).as_str() {
+            "terminal" => ExecutionMode::Terminal,
+            _ => ExecutionMode::Task,
+        }
+    }
+
     fn to_cargo_task_context(self) -> CargoTaskContext {
-        CargoTaskContext::new(
-            self.env(),
-            self.extra_args(),
-            get(CARGO_TOOLS_SECTION, "cargoCommand", "cargo".to_string()),
-        )
+        CargoTaskContext::new(self.env(), self.extra_args(), cargo_command())
+            .with_cwd(WORKSPACE_ROOT.with(|root| root.borrow().clone()))
+            .with_cross(
+                cross_enabled()
+                    .then(|| CROSS_HOST.with(|host| host.borrow().clone()))
+                    .flatten(),
+            )
     }
 
     fn env(&self) -> HashMap<String, String> {
-        let mut env = get(CARGO_TOOLS_SECTION, "extraEnv", HashMap::new());
+        // Explicitly configured variables take precedence over the env files, which take
+        // precedence over the captured shell environment
+        let mut env = SHELL_ENV_VARS.with(|env| env.borrow().clone());
+        env.extend(ENV_FILE_VARS.with(|env| env.borrow().clone()));
+        env.extend(get(CARGO_TOOLS_SECTION, "extraEnv", HashMap::new()));
 
         if use_rust_analyzer_env_and_args() {
             env.extend(get(RUST_ANALYZER_SECTION, "cargo.extraEnv", HashMap::new()));
         }
 
+        // Configured runner variables take precedence
+        let runners = get(CARGO_TOOLS_SECTION, "run.targetRunners", HashMap::new());
+        for (key, runner) in runner::runner_env(runners) {
+            env.entry(key).or_insert(runner);
+        }
+
+        if sccache_enabled() && !env.contains_key(RUSTC_WRAPPER) {
+            env.insert(RUSTC_WRAPPER.to_string(), sccache::SCCACHE.to_string());
+        }
+
+        // `cargo metadata` runs with it too, so it reports the shared directory
+        if let Some(target_dir) = shared_target_dir()
+            && !env.contains_key(CARGO_TARGET_DIR)
+        {
+            env.insert(CARGO_TARGET_DIR.to_string(), target_dir);
+        }
+
         match self {
             Self::General => {}
             Self::Run => {
@@ -151,6 +593,8 @@ pub enum ConfigValueType {
     String,
     VecString,
     HashMapString,
+    Number,
+    Object,
 }
 
 trait ToConfigValueType {
@@ -175,12 +619,156 @@ impl ToConfigValueType for Vec<String> {
     }
 }
 
+impl ToConfigValueType for Vec<Check> {
+    fn to_config_value_type() -> ConfigValueType {
+        ConfigValueType::VecString
+    }
+}
+
 impl ToConfigValueType for HashMap<String, String> {
     fn to_config_value_type() -> ConfigValueType {
         ConfigValueType::HashMapString
     }
 }
 
+impl ToConfigValueType for HashMap<String, RemoteTarget> {
+    fn to_config_value_type() -> ConfigValueType {
+        ConfigValueType::Object
+    }
+}
+
+impl ToConfigValueType for HashMap<String, DebugOverrides> {
+    fn to_config_value_type() -> ConfigValueType {
+        ConfigValueType::Object
+    }
+}
+
+impl ToConfigValueType for HashMap<String, Template> {
+    fn to_config_value_type() -> ConfigValueType {
+        ConfigValueType::Object
+    }
+}
+
+impl ToConfigValueType for u32 {
+    fn to_config_value_type() -> ConfigValueType {
+        ConfigValueType::Number
+    }
+}
+
+/// The command watch mode re-runs when files of `package` change
+pub fn watch_command(package: Option<String>) -> CargoCommand {
+    match get(CARGO_TOOLS_SECTION, "watch.command", "check".to_string()).as_str() {
+        "clippy" => CargoCommand::Clippy { package },
+        "test" => CargoCommand::Test { package },
+        _ => CargoCommand::Check { package },
+    }
+}
+
+/// The command run on save of a source file of `package` if check on save is enabled
+pub fn check_on_save_command(package: Option<String>) -> Option<CargoCommand> {
+    if !get(CARGO_TOOLS_SECTION, "checkOnSave.enabled", false) {
+        return None;
+    }
+    Some(check_command(package))
+}
+
+/// The check or clippy of `cargoTools.checkOnSave.command` for `package`, which also refreshes
+/// the **Build Results**
+pub fn check_command(package: Option<String>) -> CargoCommand {
+    match get(
+        CARGO_TOOLS_SECTION,
+        "checkOnSave.command",
+        "check".to_string(),
+    )
+    .as_str()
+    {
+        "clippy" => CargoCommand::Clippy { package },
+        _ => CargoCommand::Check { package },
+    }
+}
+
+/// The git ref **Test Changed Packages** diffs against
+pub fn test_changed_base_ref() -> String {
+    get(
+        CARGO_TOOLS_SECTION,
+        "test.changedBaseRef",
+        "origin/main".to_string(),
+    )
+}
+
+/// The arguments of `cargoTools.test.harnessArgs` passed to the test harness
+pub fn test_harness_args() -> Vec<String> {
+    get(CARGO_TOOLS_SECTION, "test.harnessArgs", Vec::new())
+}
+
+/// The SPDX licenses the dependencies may use according to the **License Report**
+pub fn license_report_allowed_licenses() -> Vec<String> {
+    get(
+        CARGO_TOOLS_SECTION,
+        "licenseReport.allowedLicenses",
+        DEFAULT_ALLOWED_LICENSES.map(str::to_string).to_vec(),
+    )
+}
+
+/// The style **Normalize Manifest** declares dependencies with more than a version in
+pub fn normalize_manifest_dependency_style() -> DependencyStyle {
+    DependencyStyle::from_setting(&get(
+        CARGO_TOOLS_SECTION,
+        "normalizeManifest.dependencyStyle",
+        "inline".to_string(),
+    ))
+}
+
+/// Whether test commands run with `cargo nextest run` instead of `cargo test`
+pub fn nextest_runner() -> bool {
+    get(CARGO_TOOLS_SECTION, "test.runner", "cargo".to_string()) == "nextest"
+}
+
+/// Where nextest writes the JUnit report of test commands, none if empty
+pub fn nextest_junit_path() -> Option<String> {
+    Some(get(CARGO_TOOLS_SECTION, "test.junitPath", String::new())).filter(|p| !p.is_empty())
+}
+
+/// After how long a Testing view run is stopped, none if `cargoTools.test.timeout` is 0
+pub fn test_timeout() -> Option<Duration> {
+    let secs = get(CARGO_TOOLS_SECTION, "test.timeout", 0u32);
+    (secs > 0).then(|| Duration::from_secs(secs.into()))
+}
+
+/// After how long nextest terminates a single test, none if `cargoTools.test.perTestTimeout`
+/// is 0
+pub fn nextest_test_timeout() -> Option<Duration> {
+    let secs = get(CARGO_TOOLS_SECTION, "test.perTestTimeout", 0u32);
+    (secs > 0).then(|| Duration::from_secs(secs.into()))
+}
+
+/// How often failed tests of a Testing view run are retried
+pub fn test_retries() -> usize {
+    get(CARGO_TOOLS_SECTION, "test.retries", 0u32) as usize
+}
+
+/// How many of the last lines of a Testing view run's output are shown, all if
+/// `cargoTools.output.maxLines` is 0
+pub fn output_max_lines() -> usize {
+    get(CARGO_TOOLS_SECTION, "output.maxLines", 5000u32) as usize
+}
+
+/// By how many percent a bin artifact may grow from one build to the next before the build is
+/// flagged, never if `cargoTools.binarySize.jumpThreshold` is 0
+pub fn binary_size_jump_threshold() -> u32 {
+    get(CARGO_TOOLS_SECTION, "binarySize.jumpThreshold", 10u32)
+}
+
+/// Maximum number of package builds which may run at the same time
+pub fn build_parallel_jobs() -> usize {
+    get(CARGO_TOOLS_SECTION, "build.parallelJobs", 1u32) as usize
+}
+
+/// Whether the selection is written into the rust-analyzer settings
+pub fn sync_rust_analyzer_selection() -> bool {
+    get(CARGO_TOOLS_SECTION, "syncRustAnalyzerSelection", false)
+}
+
 fn use_rust_analyzer_env_and_args() -> bool {
     get(CARGO_TOOLS_SECTION, "useRustAnalyzerEnvAndArgs", false)
 }
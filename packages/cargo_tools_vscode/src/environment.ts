@@ -13,5 +13,9 @@ export function get_config(section: string, key: string, type: ConfigValueType,
             return config.get<string[]>(key, default_value as string[]);
         case ConfigValueType.HashMapString:
             return config.get<{ [key: string]: string }>(key, default_value as { [key: string]: string });
+        case ConfigValueType.Number:
+            return config.get<number>(key, default_value as number);
+        case ConfigValueType.Object:
+            return config.get<object>(key, default_value as object);
     }
 }
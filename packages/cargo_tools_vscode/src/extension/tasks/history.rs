@@ -0,0 +1,163 @@
+use cargo_tools::{
+    cargo::{
+        BuildStatus, BuildSummary, build_summary::build_units, diagnostics::parse_diagnostics,
+    },
+    completion_hook::Summary,
+};
+use futures::channel::mpsc::channel;
+use iced_viewless::Task;
+use serde::Serialize;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::js_sys::Array;
+
+use crate::{
+    commands::history::*,
+    extension::vscode_task_utils::{CommandBinding, register_commands, take_first},
+    runtime::CHANNEL_CAPACITY,
+};
+
+#[wasm_bindgen(raw_module = "../../../packages/cargo_tools_vscode/src/extension/tasks/history.ts")]
+extern "C" {
+    /// Records the cargo tasks run in this session and shows them in a view and quick pick
+    type TaskHistory;
+
+    #[wasm_bindgen(constructor)]
+    fn new() -> TaskHistory;
+
+    #[wasm_bindgen(method)]
+    fn show(this: &TaskHistory);
+
+    #[wasm_bindgen(method)]
+    fn rerun(this: &TaskHistory, id: String);
+
+    #[wasm_bindgen(method)]
+    fn copy_command(this: &TaskHistory, id: String);
+
+    #[wasm_bindgen(method)]
+    fn clear(this: &TaskHistory);
+
+    #[wasm_bindgen(js_name = show_package_builds)]
+    fn show_package_builds_js(statuses: JsValue);
+}
+
+/// Lists the packages of **Build Packages...** with their [BuildStatus] in the Task History
+/// view
+pub fn show_package_builds<'a>(statuses: impl Iterator<Item = (&'a str, BuildStatus)>) {
+    let statuses: Vec<_> = statuses
+        .map(|(package, status)| (package, status.to_string()))
+        .collect();
+    match serde_wasm_bindgen::to_value(&statuses) {
+        Ok(statuses) => show_package_builds_js(statuses),
+        Err(e) => error!("Failed to serialize the package builds: {e}"),
+    }
+}
+
+/// What the completion hook gets of a finished build or test
+#[derive(Serialize)]
+struct CompletionPayload {
+    json: String,
+    env: Vec<(String, String)>,
+}
+
+/// The summary of the finished task `cmd` for the completion hook as JSON and as variables for
+/// the hook command, undefined if the task is neither a build nor a test
+#[wasm_bindgen]
+pub fn completion_summary(
+    cmd: &str,
+    args: Vec<String>,
+    exit_code: Option<i32>,
+    duration_ms: f64,
+) -> JsValue {
+    let Some(summary) = Summary::of_task(cmd, &args, exit_code, duration_ms as u64) else {
+        return JsValue::UNDEFINED;
+    };
+    let payload = CompletionPayload {
+        json: serde_json::to_string(&summary).unwrap_or_default(),
+        env: summary.env(),
+    };
+    serde_wasm_bindgen::to_value(&payload).unwrap_or(JsValue::UNDEFINED)
+}
+
+/// The statistics of the finished build task `cmd` from the JSON `messages` it reported,
+/// undefined if the task is no build
+#[wasm_bindgen]
+pub fn build_summary(
+    cmd: &str,
+    args: Vec<String>,
+    messages: &str,
+    exit_code: Option<i32>,
+    duration_ms: f64,
+) -> JsValue {
+    let is_build = Summary::of_task(cmd, &args, exit_code, 0).is_some_and(|s| s.command == "build");
+    if !is_build {
+        return JsValue::UNDEFINED;
+    }
+    let summary = BuildSummary::new(
+        &build_units(messages),
+        &parse_diagnostics(messages, ""),
+        duration_ms as u64,
+    );
+    serde_wasm_bindgen::to_value(&summary.message()).unwrap_or(JsValue::UNDEFINED)
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Show,
+    Rerun(String),
+    CopyCommand(String),
+    Clear,
+}
+
+type CmdFn = fn(Array) -> Option<Command>;
+
+impl Command {
+    const fn all() -> [(&'static str, CmdFn); NUMBER_CMDS] {
+        [
+            (CARGO_TOOLS_TASK_HISTORY_SHOW, |_| Some(Self::Show)),
+            (CARGO_TOOLS_TASK_HISTORY_RERUN, |arg| {
+                take_first(arg).map(Self::Rerun)
+            }),
+            (CARGO_TOOLS_TASK_HISTORY_COPY_COMMAND, |arg| {
+                take_first(arg).map(Self::CopyCommand)
+            }),
+            (CARGO_TOOLS_TASK_HISTORY_CLEAR, |_| Some(Self::Clear)),
+        ]
+    }
+}
+
+#[derive(Debug)]
+pub enum Message {
+    Cmd(Command),
+}
+
+pub struct History {
+    history: TaskHistory,
+    _cmds: Vec<CommandBinding>,
+}
+
+impl History {
+    pub fn init() -> (Self, Task<Message>) {
+        let (cmd_tx, cmd_rx) = channel(CHANNEL_CAPACITY);
+        let _cmds = register_commands(cmd_tx, Command::all());
+
+        let this = Self {
+            history: TaskHistory::new(),
+            _cmds,
+        };
+
+        (this, Task::stream(cmd_rx).map(Message::Cmd))
+    }
+
+    pub fn update(&mut self, msg: Message) -> Task<Message> {
+        match msg {
+            Message::Cmd(cmd) => match cmd {
+                Command::Show => self.history.show(),
+                Command::Rerun(id) => self.history.rerun(id),
+                Command::CopyCommand(id) => self.history.copy_command(id),
+                Command::Clear => self.history.clear(),
+            },
+        }
+        Task::none()
+    }
+}
@@ -0,0 +1,323 @@
+import * as vscode from 'vscode';
+import { spawn } from 'child_process';
+import { build_summary, completion_summary } from '../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../vscode_extension/src/extension';
+import { onDidEndJsonTask, onDidStartJsonTask, task_command } from '../../runtime';
+
+const MAX_ENTRIES = 20;
+
+interface HistoryEntry {
+    id: string;
+    task: vscode.Task;
+    commandLine: string;
+    start: number;
+    duration?: number;
+    exitCode?: number;
+    /** The statistics of a finished build */
+    buildSummary?: string;
+}
+
+function commandLine(task: vscode.Task): string {
+    const command = task_command(task);
+    return command ? [command.cmd, ...command.args].join(' ') : task.name;
+}
+
+interface CompletionPayload {
+    json: string;
+    env: [string, string][];
+}
+
+async function postSummary(url: string, json: string): Promise<void> {
+    try {
+        const response = await fetch(url, {
+            method: 'POST',
+            headers: { 'Content-Type': 'application/json' },
+            body: json,
+        });
+        if (!response.ok) {
+            vscode.window.showWarningMessage(`Completion hook ${url} responded with ${response.status}`);
+        }
+    } catch (error) {
+        vscode.window.showWarningMessage(`Failed to post to completion hook ${url}: ${error}`);
+    }
+}
+
+function runHookCommand(command: string, payload: CompletionPayload): void {
+    const child = spawn(command, {
+        shell: true,
+        cwd: vscode.workspace.workspaceFolders?.[0]?.uri.fsPath,
+        env: { ...process.env, ...Object.fromEntries(payload.env) },
+    });
+    child.on('error', (error) => {
+        vscode.window.showWarningMessage(`Failed to run completion hook '${command}': ${error.message}`);
+    });
+    child.on('close', (code) => {
+        if (code !== 0) {
+            vscode.window.showWarningMessage(`Completion hook '${command}' exited with ${code}`);
+        }
+    });
+    child.stdin?.end(payload.json);
+}
+
+/**
+ * Posts the summary of a finished build or test to `cargoTools.completionHook.url` and passes
+ * it to `cargoTools.completionHook.command`
+ */
+function runCompletionHook(task: vscode.Task, exitCode: number | undefined, duration: number): void {
+    const config = vscode.workspace.getConfiguration('cargoTools');
+    const url = config.get<string>('completionHook.url', '');
+    const command = config.get<string>('completionHook.command', '');
+    const taskCommand = task_command(task);
+    if ((!url && !command) || !taskCommand) {
+        return;
+    }
+    const payload: CompletionPayload | undefined =
+        completion_summary(taskCommand.cmd, taskCommand.args, exitCode, duration);
+    if (!payload) {
+        return;
+    }
+    if (url) {
+        postSummary(url, payload.json);
+    }
+    if (command) {
+        runHookCommand(command, payload);
+    }
+}
+
+/**
+ * Adds the statistics of a finished build to its `entry` if `cargoTools.buildSummary.enabled`,
+ * taken from the JSON `messages` the build reported
+ */
+function summarizeBuild(entry: HistoryEntry, messages: string): void {
+    const config = vscode.workspace.getConfiguration('cargoTools');
+    const command = task_command(entry.task);
+    if (!config.get<boolean>('buildSummary.enabled', true) || !command || entry.duration === undefined) {
+        return;
+    }
+    const summary: string | undefined =
+        build_summary(command.cmd, command.args, messages, entry.exitCode, entry.duration);
+    if (!summary) {
+        return;
+    }
+    entry.buildSummary = summary;
+    if (config.get<boolean>('buildSummary.notification', false)) {
+        vscode.window.showInformationMessage(`Build finished: ${summary}`);
+    }
+}
+
+/** The element of the group listing the packages of the last **Build Packages...** */
+const PACKAGE_BUILDS = 'packageBuilds';
+const PACKAGE_BUILD_PREFIX = 'packageBuild:';
+
+/** `queued`, `running`, `succeeded`, `failed` or `skipped` by package name */
+type PackageBuilds = [string, string][];
+
+function packageBuildIcon(status: string): vscode.ThemeIcon {
+    switch (status) {
+        case 'running':
+            return new vscode.ThemeIcon('loading~spin');
+        case 'succeeded':
+            return new vscode.ThemeIcon('pass', new vscode.ThemeColor('testing.iconPassed'));
+        case 'failed':
+            return new vscode.ThemeIcon('error', new vscode.ThemeColor('testing.iconFailed'));
+        case 'skipped':
+            return new vscode.ThemeIcon('debug-step-over');
+        default:
+            return new vscode.ThemeIcon('clock');
+    }
+}
+
+let taskHistory: TaskHistory | undefined;
+
+/** Lists the packages of **Build Packages...** with their `statuses` in the Task History view */
+export function show_package_builds(statuses: PackageBuilds): void {
+    taskHistory?.showPackageBuilds(statuses);
+}
+
+function isCargoToolsTask(task: vscode.Task): boolean {
+    return task.definition.type.startsWith('cargo-tools');
+}
+
+function describe(entry: HistoryEntry): string {
+    if (entry.duration === undefined) {
+        return 'running';
+    }
+    const seconds = (entry.duration / 1000).toFixed(1);
+    const outcome = entry.exitCode === undefined ? 'terminated' : `exit code ${entry.exitCode}`;
+    return `${outcome}, ${seconds}s`;
+}
+
+function icon(entry: HistoryEntry): vscode.ThemeIcon {
+    if (entry.duration === undefined) {
+        return new vscode.ThemeIcon('loading~spin');
+    }
+    return entry.exitCode === 0
+        ? new vscode.ThemeIcon('pass', new vscode.ThemeColor('testing.iconPassed'))
+        : new vscode.ThemeIcon('error', new vscode.ThemeColor('testing.iconFailed'));
+}
+
+/**
+ * Records the cargo tasks run in this session, most recent first.
+ * Tree elements are the entry ids so view item commands receive them as argument.
+ */
+export class TaskHistory implements vscode.TreeDataProvider<string> {
+    private _onDidChangeTreeData = new vscode.EventEmitter<string | undefined | null | void>();
+    readonly onDidChangeTreeData = this._onDidChangeTreeData.event;
+
+    private entries: HistoryEntry[] = [];
+    private running = new Map<vscode.Task, HistoryEntry>();
+    private nextId = 0;
+    private packageBuilds: PackageBuilds = [];
+
+    constructor() {
+        const startListener = vscode.tasks.onDidStartTaskProcess((event) => this.onStart(event.execution.task));
+        const endListener = vscode.tasks.onDidEndTaskProcess((event) => this.onEnd(event.execution.task, event.exitCode));
+        // Tasks which report their compiler messages run without a task process
+        const jsonStartListener = onDidStartJsonTask((task) => this.onStart(task));
+        const jsonEndListener = onDidEndJsonTask((end) => this.onEnd(end.task, end.exitCode, end.messages));
+        const view = vscode.window.createTreeView('cargoToolsTaskHistory', { treeDataProvider: this });
+
+        extension_context?.subscriptions.push(startListener, endListener, jsonStartListener, jsonEndListener, view);
+        taskHistory = this;
+    }
+
+    getTreeItem(id: string): vscode.TreeItem {
+        if (id === PACKAGE_BUILDS) {
+            const finished = this.packageBuilds.filter(([, status]) => status !== 'queued' && status !== 'running');
+            const item = new vscode.TreeItem('Build Packages', vscode.TreeItemCollapsibleState.Expanded);
+            item.description = `${finished.length}/${this.packageBuilds.length} finished`;
+            item.iconPath = new vscode.ThemeIcon('package');
+            return item;
+        }
+        if (id.startsWith(PACKAGE_BUILD_PREFIX)) {
+            const name = id.slice(PACKAGE_BUILD_PREFIX.length);
+            const status = this.packageBuilds.find(([pkg]) => pkg === name)?.[1] ?? 'queued';
+            const item = new vscode.TreeItem(name);
+            item.description = status;
+            item.tooltip = status === 'skipped'
+                ? `${name} was skipped because a package it depends on failed to build`
+                : `Build of ${name}: ${status}`;
+            item.iconPath = packageBuildIcon(status);
+            item.contextValue = 'packageBuild';
+            return item;
+        }
+        const entry = this.find(id);
+        const item = new vscode.TreeItem(entry?.commandLine ?? id);
+        if (entry) {
+            item.description = entry.buildSummary ?? describe(entry);
+            item.tooltip = `${entry.commandLine}\n${new Date(entry.start).toLocaleTimeString()}: ${describe(entry)}`
+                + (entry.buildSummary ? `\n${entry.buildSummary}` : '');
+            item.iconPath = icon(entry);
+        }
+        item.contextValue = 'historyEntry';
+        return item;
+    }
+
+    getChildren(id?: string): string[] {
+        if (id === PACKAGE_BUILDS) {
+            return this.packageBuilds.map(([name]) => `${PACKAGE_BUILD_PREFIX}${name}`);
+        }
+        if (id) {
+            return [];
+        }
+        const entries = this.entries.map((entry) => entry.id);
+        return this.packageBuilds.length > 0 ? [PACKAGE_BUILDS, ...entries] : entries;
+    }
+
+    /** Replaces the listed package builds with `statuses` */
+    showPackageBuilds(statuses: PackageBuilds): void {
+        this.packageBuilds = statuses;
+        this._onDidChangeTreeData.fire();
+    }
+
+    show(): void {
+        if (this.entries.length === 0) {
+            vscode.window.showInformationMessage('No cargo tasks were run yet');
+            return;
+        }
+
+        const copyButton: vscode.QuickInputButton = {
+            iconPath: new vscode.ThemeIcon('copy'),
+            tooltip: 'Copy command',
+        };
+        const quickPick = vscode.window.createQuickPick<vscode.QuickPickItem & { id: string }>();
+        quickPick.placeholder = 'Select a task to re-run it';
+        quickPick.items = this.entries.map((entry) => ({
+            id: entry.id,
+            label: entry.commandLine,
+            description: describe(entry),
+            buttons: [copyButton],
+        }));
+        quickPick.onDidTriggerItemButton((event) => {
+            this.copy_command(event.item.id);
+            quickPick.hide();
+        });
+        quickPick.onDidAccept(() => {
+            const selected = quickPick.selectedItems[0];
+            if (selected) {
+                this.rerun(selected.id);
+            }
+            quickPick.hide();
+        });
+        quickPick.onDidHide(() => quickPick.dispose());
+        quickPick.show();
+    }
+
+    rerun(id: string): void {
+        const entry = this.find(id);
+        if (entry) {
+            vscode.tasks.executeTask(entry.task).then(undefined, (error) => {
+                vscode.window.showErrorMessage(`Failed to re-run '${entry.commandLine}': ${error}`);
+            });
+        }
+    }
+
+    copy_command(id: string): void {
+        const entry = this.find(id);
+        if (entry) {
+            vscode.env.clipboard.writeText(entry.commandLine);
+        }
+    }
+
+    clear(): void {
+        this.entries = this.entries.filter((entry) => entry.duration === undefined);
+        if (this.packageBuilds.every(([, status]) => status !== 'queued' && status !== 'running')) {
+            this.packageBuilds = [];
+        }
+        this._onDidChangeTreeData.fire();
+    }
+
+    private find(id: string): HistoryEntry | undefined {
+        return this.entries.find((entry) => entry.id === id);
+    }
+
+    private onStart(task: vscode.Task): void {
+        if (!isCargoToolsTask(task)) {
+            return;
+        }
+        const entry: HistoryEntry = {
+            id: `${this.nextId++}`,
+            task,
+            commandLine: commandLine(task),
+            start: Date.now(),
+        };
+        this.running.set(task, entry);
+        this.entries = [entry, ...this.entries].slice(0, MAX_ENTRIES);
+        this._onDidChangeTreeData.fire();
+    }
+
+    private onEnd(task: vscode.Task, exitCode: number | undefined, messages?: string): void {
+        const entry = this.running.get(task);
+        if (!entry) {
+            return;
+        }
+        this.running.delete(task);
+        entry.duration = Date.now() - entry.start;
+        entry.exitCode = exitCode;
+        if (messages !== undefined) {
+            summarizeBuild(entry, messages);
+        }
+        this._onDidChangeTreeData.fire();
+        runCompletionHook(entry.task, exitCode, entry.duration);
+    }
+}
@@ -0,0 +1,45 @@
+use futures::channel::mpsc::Sender;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::js_sys::Array;
+
+use crate::{
+    commands::just::*,
+    extension::vscode_task_utils::{CommandBinding, register_commands},
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/tasks/just/command.ts"
+)]
+extern "C" {
+    #[wasm_bindgen]
+    fn try_get_just_recipe(value: Array) -> Option<String>;
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    RunRecipe(String),
+    RunRecipeWithArgs(String),
+    SelectAndRun,
+}
+
+type CmdFn = fn(Array) -> Option<Command>;
+
+impl Command {
+    const fn all() -> [(&'static str, CmdFn); NUMBER_CMDS] {
+        [
+            (CARGO_TOOLS_JUST_RUN_RECIPE, |arg| {
+                try_get_just_recipe(arg).map(Self::RunRecipe)
+            }),
+            (CARGO_TOOLS_JUST_RUN_RECIPE_WITH_ARGS, |arg| {
+                try_get_just_recipe(arg).map(Self::RunRecipeWithArgs)
+            }),
+            (CARGO_TOOLS_JUST_SELECT_AND_RUN, |_| {
+                Some(Self::SelectAndRun)
+            }),
+        ]
+    }
+}
+
+pub fn register_just_commands(tx: Sender<Command>) -> Vec<CommandBinding> {
+    register_commands(tx, Command::all())
+}
@@ -0,0 +1,8 @@
+import { JustNode } from './tree_provider';
+
+export function try_get_just_recipe(value: any[]): string | undefined {
+    if (value[0] instanceof JustNode) {
+        return value[0].label;
+    }
+    return undefined;
+}
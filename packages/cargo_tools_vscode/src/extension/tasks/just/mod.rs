@@ -0,0 +1,4 @@
+pub mod command;
+pub mod tree_provider;
+mod ui;
+pub use ui::{Event, Just, Message, SettingsUpdate};
@@ -0,0 +1,70 @@
+use cargo_tools::just::JustRecipes;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use crate::icon::JUST_RECIPE;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/tasks/just/tree_provider.ts"
+)]
+extern "C" {
+    pub type JustNode;
+
+    #[wasm_bindgen(constructor)]
+    fn new(
+        label: String,
+        icon: crate::icon::Icon,
+        collapsible_state: u32,
+        context_value: String,
+        description: String,
+        tooltip: String,
+    ) -> JustNode;
+}
+
+const RECIPE_CONTEXT: &str = "justRecipe";
+
+// Keep in sync with TreeItemCollapsibleState
+enum CollapsibleState {
+    None = 0,
+}
+
+/// Handler for the just tree section — provides the flat recipe list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[wasm_bindgen]
+pub struct JustTreeProviderHandler {
+    recipes: JustRecipes,
+}
+
+#[wasm_bindgen]
+impl JustTreeProviderHandler {
+    #[wasm_bindgen]
+    pub fn recipes(&self) -> Vec<JustNode> {
+        self.recipes
+            .iter()
+            .map(|recipe| {
+                let doc = recipe
+                    .doc
+                    .as_ref()
+                    .map(|doc| format!("\n{doc}"))
+                    .unwrap_or_default();
+                let invocation = format!("just {} {}", recipe.name, recipe.parameters_display());
+                let tooltip = format!("Recipe: {}{doc}", invocation.trim_end());
+
+                JustNode::new(
+                    recipe.name.clone(),
+                    JUST_RECIPE,
+                    CollapsibleState::None as u32,
+                    RECIPE_CONTEXT.to_string(),
+                    recipe.parameters_display(),
+                    tooltip,
+                )
+            })
+            .collect()
+    }
+}
+
+impl JustTreeProviderHandler {
+    pub fn new(recipes: JustRecipes) -> Self {
+        Self { recipes }
+    }
+}
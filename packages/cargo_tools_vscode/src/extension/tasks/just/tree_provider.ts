@@ -0,0 +1,19 @@
+import * as vscode from 'vscode';
+import { Icon } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+
+export class JustNode extends vscode.TreeItem {
+    constructor(
+        public readonly label: string,
+        public readonly icon: Icon,
+        public readonly collapsibleState: vscode.TreeItemCollapsibleState,
+        public readonly contextValue: string,
+        public readonly description: string,
+        public readonly tooltip: string,
+    ) {
+        super(label, collapsibleState);
+        this.iconPath = new vscode.ThemeIcon(icon.icon, new vscode.ThemeColor(icon.color));
+        this.contextValue = contextValue;
+        this.description = description;
+        this.tooltip = tooltip;
+    }
+}
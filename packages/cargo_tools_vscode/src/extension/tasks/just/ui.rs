@@ -0,0 +1,306 @@
+use cargo_tools::{
+    just::{JUSTFILE_NAMES, JustRecipe, JustRecipes, ParseError, parse_recipes},
+    process::split_args,
+};
+use futures::channel::mpsc::channel;
+use iced_viewless::Task;
+use serde::{Deserialize, Serialize};
+
+use crate::recent_items::RecentItems;
+use crate::{
+    environment::just_task_context,
+    extension::{
+        CommandBinding, send_file_changed,
+        tasks::just::{
+            command::{Command, register_just_commands},
+            tree_provider::JustTreeProviderHandler,
+        },
+    },
+    quick_pick::{QuickPickItem, SelectInput, ToQuickPickItem, show_input_box},
+    runtime::{
+        CHANNEL_CAPACITY, TsFileWatcher, VsCodeTask, exec_vs_code, execute_task,
+        file_exists_vs_code, get_state_vs_code, persist_state_vs_code, set_justfile_context,
+    },
+};
+use tracing::error;
+
+impl ToQuickPickItem for JustRecipe {
+    fn to_item(&self, _picked: bool) -> QuickPickItem {
+        let item =
+            QuickPickItem::new(self.name.clone()).with_description(self.parameters_display());
+        match &self.doc {
+            Some(doc) => item.with_detail(doc.clone()),
+            None => item,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RecipesUpdate {
+    New(String, JustRecipes),
+    NoJustfile,
+    JustNotInstalled(String),
+    FailedToRetrieve(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    JustfileChanged,
+    RecipesChanged(RecipesUpdate),
+    PreviewSettings(SettingsUpdate),
+    SettingsChanged(SettingsUpdate),
+    Cmd(Command),
+}
+
+#[derive(Debug, Clone)]
+pub enum SettingsUpdate {
+    Filter(String),
+    RecordRun(String),
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    TreeChanged(JustTreeProviderHandler),
+}
+
+#[derive(Debug)]
+pub struct Just {
+    recipes: JustRecipes,
+    /// The justfile of the workspace, the first of [JUSTFILE_NAMES] that exists
+    justfile: Option<String>,
+    settings: Settings,
+    _cmds: Vec<CommandBinding>,
+    _file_watcher: TsFileWatcher,
+    root_dir: String,
+}
+
+impl Just {
+    pub fn init(root_dir: String) -> (Self, Task<Message>) {
+        let (justfile_changed_tx, justfile_changed_rx) = channel(CHANNEL_CAPACITY);
+        let _file_watcher = TsFileWatcher::new(send_file_changed(justfile_changed_tx));
+        _file_watcher.watch_files(
+            JUSTFILE_NAMES
+                .iter()
+                .map(|name| format!("{root_dir}/{name}"))
+                .collect(),
+        );
+
+        let (cmd_tx, cmd_rx) = channel(CHANNEL_CAPACITY);
+        let _cmds = register_just_commands(cmd_tx);
+
+        let settings: Settings = get_state_vs_code(settings_key(&root_dir)).unwrap_or_default();
+
+        let this = Self {
+            recipes: JustRecipes::default(),
+            justfile: None,
+            settings,
+            _cmds,
+            _file_watcher,
+            root_dir,
+        };
+
+        let justfile_update = Task::stream(justfile_changed_rx).map(|()| Message::JustfileChanged);
+        let cmd = Task::stream(cmd_rx).map(Message::Cmd);
+        let initial_parse =
+            Task::future(find_and_parse(this.root_dir.clone())).map(Message::RecipesChanged);
+
+        (this, Task::batch([justfile_update, cmd, initial_parse]))
+    }
+
+    pub fn recipes(&self) -> &JustRecipes {
+        &self.recipes
+    }
+
+    pub fn update(&mut self, msg: Message) -> (Task<Message>, Option<Event>) {
+        match msg {
+            Message::RecipesChanged(update) => match update {
+                RecipesUpdate::New(justfile, recipes) => {
+                    self.settings
+                        .recent_recipes
+                        .remove_obsolete(&recipes, |recipe| &recipe.name);
+                    self.recipes = recipes;
+                    self.justfile = Some(justfile);
+                    let event = self.tree_changed_event();
+                    (
+                        Task::batch([
+                            Task::future(set_justfile_context(true)).discard(),
+                            Task::future(persist_state_vs_code(
+                                settings_key(&self.root_dir),
+                                self.settings.clone(),
+                            ))
+                            .discard(),
+                        ]),
+                        Some(event),
+                    )
+                }
+                RecipesUpdate::JustNotInstalled(e) => {
+                    error!("{e}");
+                    self.set_context_false()
+                }
+                RecipesUpdate::NoJustfile => self.set_context_false(),
+                // For an invalid justfile leave everything as is
+                RecipesUpdate::FailedToRetrieve(e) => {
+                    error!("{e}");
+                    (Task::none(), None)
+                }
+            },
+            Message::JustfileChanged => (
+                Task::future(find_and_parse(self.root_dir.clone())).map(Message::RecipesChanged),
+                None,
+            ),
+            Message::PreviewSettings(update) => self.update_state(update, false),
+            Message::SettingsChanged(update) => self.update_state(update, true),
+            Message::Cmd(cmd) => (self.handle_cmd(cmd), None),
+        }
+    }
+
+    fn set_context_false(&mut self) -> (Task<Message>, Option<Event>) {
+        self.recipes = JustRecipes::default();
+        self.justfile = None;
+        let event = self.tree_changed_event();
+        (
+            Task::future(set_justfile_context(false)).discard(),
+            Some(event),
+        )
+    }
+
+    fn update_state(
+        &mut self,
+        update: SettingsUpdate,
+        persist: bool,
+    ) -> (Task<Message>, Option<Event>) {
+        let event = match update {
+            SettingsUpdate::Filter(filter) => {
+                self.settings.filter = filter;
+                Some(self.tree_changed_event())
+            }
+            SettingsUpdate::RecordRun(name) => {
+                self.settings.recent_recipes.record(name);
+                None
+            }
+        };
+        let persist = if persist {
+            Task::future(persist_state_vs_code(
+                settings_key(&self.root_dir),
+                self.settings.clone(),
+            ))
+            .discard()
+        } else {
+            Task::none()
+        };
+        (persist, event)
+    }
+
+    fn tree_changed_event(&self) -> Event {
+        let recipes = self.recipes.filtered(&self.settings.filter);
+        Event::TreeChanged(JustTreeProviderHandler::new(recipes))
+    }
+
+    fn handle_cmd(&self, cmd: Command) -> Task<Message> {
+        match cmd {
+            Command::RunRecipe(name) => {
+                // Recipes with required parameters fail without arguments
+                let requires_args = self
+                    .recipes
+                    .iter()
+                    .any(|recipe| recipe.name == name && recipe.requires_args());
+                if requires_args {
+                    self.handle_cmd(Command::RunRecipeWithArgs(name))
+                } else {
+                    self.run_recipe(name, Vec::new())
+                }
+            }
+            Command::RunRecipeWithArgs(name) => {
+                let Some(recipe) = self.recipes.iter().find(|r| r.name == name).cloned() else {
+                    return Task::none();
+                };
+                let Some(justfile) = self.justfile.clone() else {
+                    return Task::none();
+                };
+                Task::future(async move {
+                    let placeholder = format!(
+                        "Arguments for 'just {} {}'",
+                        recipe.name,
+                        recipe.parameters_display()
+                    );
+                    let args = show_input_box(placeholder, String::new())
+                        .await
+                        .ok()?
+                        .as_string()?;
+                    let args = split_args(&args);
+                    run(recipe.name, justfile, args).await
+                })
+                .and_then(Task::done)
+            }
+            Command::SelectAndRun => {
+                let options = self
+                    .settings
+                    .recent_recipes
+                    .apply(&self.recipes, |recipe| &recipe.name);
+                Task::future(async move {
+                    SelectInput {
+                        options,
+                        current: Vec::new(),
+                    }
+                    .select()
+                    .await
+                    .map(|recipe| Command::RunRecipe(recipe.name))
+                })
+                .and_then(Task::done)
+                .map(Message::Cmd)
+            }
+        }
+    }
+
+    fn run_recipe(&self, name: String, args: Vec<String>) -> Task<Message> {
+        let Some(justfile) = self.justfile.clone() else {
+            return Task::none();
+        };
+        Task::future(run(name, justfile, args)).and_then(Task::done)
+    }
+}
+
+/// Runs the recipe `name` with `args` and records the run
+async fn run(name: String, justfile: String, args: Vec<String>) -> Option<Message> {
+    match JustRecipe::try_into_process(name.clone(), justfile, args, just_task_context()) {
+        Ok(process) => {
+            execute_task(VsCodeTask::just(process)).await;
+            Some(Message::SettingsChanged(SettingsUpdate::RecordRun(name)))
+        }
+        Err(e) => {
+            error!("{e}");
+            None
+        }
+    }
+}
+
+/// The recipes of the first justfile of `root_dir`
+async fn find_and_parse(root_dir: String) -> RecipesUpdate {
+    let mut justfile = None;
+    for name in JUSTFILE_NAMES {
+        let path = format!("{root_dir}/{name}");
+        if file_exists_vs_code(path.clone()).await {
+            justfile = Some(path);
+            break;
+        }
+    }
+    let Some(justfile) = justfile else {
+        return RecipesUpdate::NoJustfile;
+    };
+    match parse_recipes(justfile.clone(), just_task_context(), exec_vs_code).await {
+        Ok(recipes) => RecipesUpdate::New(justfile, recipes),
+        Err(ParseError::JustNotInstalled(e)) => RecipesUpdate::JustNotInstalled(e),
+        Err(e) => RecipesUpdate::FailedToRetrieve(e.to_string()),
+    }
+}
+
+fn settings_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.tasks.just.ui_settings")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Settings {
+    filter: String,
+    #[serde(default)]
+    recent_recipes: RecentItems,
+}
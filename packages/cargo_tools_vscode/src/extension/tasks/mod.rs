@@ -1,4 +1,6 @@
 pub mod cargo_make;
+pub mod history;
+pub mod just;
 pub mod pinned;
 mod ui;
 pub mod xtask;
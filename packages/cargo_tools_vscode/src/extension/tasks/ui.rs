@@ -1,6 +1,6 @@
 use std::iter;
 
-use cargo_tools::xtask::XtaskAliases;
+use cargo_tools::{just::JustRecipes, xtask::XtaskAliases};
 use futures::channel::mpsc::{Sender, channel};
 use iced_viewless::Task;
 use serde_wasm_bindgen::to_value;
@@ -13,6 +13,8 @@ use crate::{
         select_name_filter,
         tasks::{
             cargo_make::{self, tree_provider::CargoMakeTreeProviderHandler},
+            history,
+            just::{self, tree_provider::JustTreeProviderHandler},
             pinned::{self, SettingsUpdate},
             xtask::{self, tree_provider::XtaskTreeProviderHandler},
         },
@@ -27,13 +29,20 @@ extern "C" {
     type TasksTreeProvider;
 
     #[wasm_bindgen(constructor)]
-    fn new(cm: CargoMakeTreeProviderHandler, xt: XtaskTreeProviderHandler) -> TasksTreeProvider;
+    fn new(
+        cm: CargoMakeTreeProviderHandler,
+        xt: XtaskTreeProviderHandler,
+        just: JustTreeProviderHandler,
+    ) -> TasksTreeProvider;
 
     #[wasm_bindgen(method)]
     fn update_cargo_make(this: &TasksTreeProvider, handler: CargoMakeTreeProviderHandler);
 
     #[wasm_bindgen(method)]
     fn update_xtask(this: &TasksTreeProvider, handler: XtaskTreeProviderHandler);
+
+    #[wasm_bindgen(method)]
+    fn update_just(this: &TasksTreeProvider, handler: JustTreeProviderHandler);
 }
 
 use cargo_tools::cargo_make::MakefileTasks;
@@ -49,15 +58,20 @@ pub enum SharedCommand {
 #[derive(Debug)]
 pub enum Message {
     CargoMake(cargo_make::Message),
+    History(history::Message),
+    Just(just::Message),
     Pinned(pinned::Message),
     Xtask(xtask::Message),
     UpdateCargoMakeTree(CargoMakeTreeProviderHandler),
     UpdateXtaskTree(XtaskTreeProviderHandler),
+    UpdateJustTree(JustTreeProviderHandler),
     SharedCmd(SharedCommand),
 }
 
 pub struct Tasks {
     cargo_make: cargo_make::CargoMake,
+    history: history::History,
+    just: just::Just,
     pinned: pinned::Pinned,
     xtask: xtask::Xtask,
     tasks_tree: TasksTreeProvider,
@@ -68,12 +82,16 @@ pub struct Tasks {
 impl Tasks {
     pub fn init(root_dir: String) -> (Self, Task<Message>) {
         let (cargo_make, cargo_make_task) = cargo_make::CargoMake::init(root_dir.clone());
+        let (history, history_task) = history::History::init();
+        let (just, just_task) = just::Just::init(root_dir.clone());
         let (pinned, pinned_task) = pinned::Pinned::init(root_dir.clone());
         let (xtask, xtask_task) = xtask::Xtask::init(root_dir);
 
         let initial_cm_handler = CargoMakeTreeProviderHandler::new(MakefileTasks::default());
         let initial_xt_handler = XtaskTreeProviderHandler::new(XtaskAliases::default());
-        let tasks_tree = TasksTreeProvider::new(initial_cm_handler, initial_xt_handler);
+        let initial_just_handler = JustTreeProviderHandler::new(JustRecipes::default());
+        let tasks_tree =
+            TasksTreeProvider::new(initial_cm_handler, initial_xt_handler, initial_just_handler);
 
         let (shared_cmd_tx, shared_cmd_rx) = channel(CHANNEL_CAPACITY);
         let _shared_cmds = register_commands(
@@ -90,6 +108,8 @@ impl Tasks {
 
         let this = Self {
             cargo_make,
+            history,
+            just,
             pinned,
             xtask,
             tasks_tree,
@@ -98,6 +118,8 @@ impl Tasks {
         };
         let task = Task::batch([
             cargo_make_task.map(Message::CargoMake),
+            history_task.map(Message::History),
+            just_task.map(Message::Just),
             pinned_task.map(Message::Pinned),
             xtask_task.map(Message::Xtask),
             Task::stream(shared_cmd_rx).map(Message::SharedCmd),
@@ -115,6 +137,14 @@ impl Tasks {
                         .chain(event.map(|evt| Task::done(evt.into_message()))),
                 )
             }
+            Message::History(msg) => self.history.update(msg).map(Message::History),
+            Message::Just(msg) => {
+                let (task, event) = self.just.update(msg);
+                Task::batch(
+                    iter::once(task.map(Message::Just))
+                        .chain(event.map(|evt| Task::done(evt.into_message()))),
+                )
+            }
             Message::Pinned(msg) => {
                 let (task, event) =
                     self.pinned
@@ -139,6 +169,10 @@ impl Tasks {
                 self.tasks_tree.update_xtask(handler);
                 Task::none()
             }
+            Message::UpdateJustTree(handler) => {
+                self.tasks_tree.update_just(handler);
+                Task::none()
+            }
             Message::SharedCmd(cmd) => self.handle_shared_cmd(cmd),
         }
     }
@@ -166,6 +200,11 @@ impl Tasks {
                         options.push(&v);
                     }
                 }
+                for item in self.just.recipes().iter() {
+                    if let Ok(v) = to_value(&item.to_item(false)) {
+                        options.push(&v);
+                    }
+                }
                 select_name_filter(
                     current,
                     options,
@@ -180,7 +219,10 @@ impl Tasks {
                     cargo_make::SettingsUpdate::TaskFilter(filter.clone()),
                 ))),
                 Task::done(Message::Xtask(xtask::Message::PreviewSettings(
-                    xtask::SettingsUpdate::Filter(filter),
+                    xtask::SettingsUpdate::Filter(filter.clone()),
+                ))),
+                Task::done(Message::Just(just::Message::PreviewSettings(
+                    just::SettingsUpdate::Filter(filter),
                 ))),
             ]),
             SharedCommand::CommitNameFilter(filter) => Task::batch([
@@ -188,7 +230,10 @@ impl Tasks {
                     cargo_make::SettingsUpdate::TaskFilter(filter.clone()),
                 ))),
                 Task::done(Message::Xtask(xtask::Message::SettingsChanged(
-                    xtask::SettingsUpdate::Filter(filter),
+                    xtask::SettingsUpdate::Filter(filter.clone()),
+                ))),
+                Task::done(Message::Just(just::Message::SettingsChanged(
+                    just::SettingsUpdate::Filter(filter),
                 ))),
             ]),
             SharedCommand::ClearAllFilters => Task::batch([
@@ -201,6 +246,9 @@ impl Tasks {
                 Task::done(Message::Xtask(xtask::Message::SettingsChanged(
                     xtask::SettingsUpdate::Filter(String::new()),
                 ))),
+                Task::done(Message::Just(just::Message::SettingsChanged(
+                    just::SettingsUpdate::Filter(String::new()),
+                ))),
             ]),
         }
     }
@@ -232,6 +280,14 @@ impl IntoMessage for xtask::Event {
     }
 }
 
+impl IntoMessage for just::Event {
+    fn into_message(self) -> Message {
+        match self {
+            just::Event::TreeChanged(handler) => Message::UpdateJustTree(handler),
+        }
+    }
+}
+
 impl IntoMessage for pinned::Event {
     fn into_message(self) -> Message {
         match self {
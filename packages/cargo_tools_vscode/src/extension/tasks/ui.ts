@@ -1,11 +1,12 @@
 import * as vscode from 'vscode';
-import { CargoMakeTreeProviderHandler, XtaskTreeProviderHandler } from '../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { CargoMakeTreeProviderHandler, JustTreeProviderHandler, XtaskTreeProviderHandler } from '../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
 import { CargoMakeNode } from './cargo_make/tree_provider';
+import { JustNode } from './just/tree_provider';
 import { XtaskNode } from './xtask/tree_provider';
 
 class SectionNode extends vscode.TreeItem {
     constructor(
-        public readonly key: 'makefile' | 'alias',
+        public readonly key: 'makefile' | 'alias' | 'recipe',
         label: string,
         icon: string,
     ) {
@@ -15,7 +16,7 @@ class SectionNode extends vscode.TreeItem {
     }
 }
 
-type TaskNode = SectionNode | CargoMakeNode | XtaskNode;
+type TaskNode = SectionNode | CargoMakeNode | XtaskNode | JustNode;
 
 export class TasksTreeProvider implements vscode.TreeDataProvider<TaskNode> {
     private _onDidChangeTreeData: vscode.EventEmitter<TaskNode | undefined | null | void> =
@@ -25,13 +26,20 @@ export class TasksTreeProvider implements vscode.TreeDataProvider<TaskNode> {
 
     private cmHandler: CargoMakeTreeProviderHandler;
     private xtHandler: XtaskTreeProviderHandler;
+    private justHandler: JustTreeProviderHandler;
 
     private readonly makefileSection = new SectionNode('makefile', 'Makefile Tasks', 'list-tree');
     private readonly aliasSection = new SectionNode('alias', 'Alias Tasks', 'terminal');
+    private readonly recipeSection = new SectionNode('recipe', 'Just Recipes', 'run');
 
-    constructor(cmHandler: CargoMakeTreeProviderHandler, xtHandler: XtaskTreeProviderHandler) {
+    constructor(
+        cmHandler: CargoMakeTreeProviderHandler,
+        xtHandler: XtaskTreeProviderHandler,
+        justHandler: JustTreeProviderHandler,
+    ) {
         this.cmHandler = cmHandler;
         this.xtHandler = xtHandler;
+        this.justHandler = justHandler;
 
         vscode.window.createTreeView('cargoToolsMakefile', {
             treeDataProvider: this,
@@ -50,18 +58,26 @@ export class TasksTreeProvider implements vscode.TreeDataProvider<TaskNode> {
         this._onDidChangeTreeData.fire();
     }
 
+    update_just(handler: JustTreeProviderHandler): void {
+        this.justHandler = handler;
+        this._onDidChangeTreeData.fire();
+    }
+
     getTreeItem(element: TaskNode): vscode.TreeItem {
         return element;
     }
 
     getChildren(element?: TaskNode): TaskNode[] {
         if (!element) {
-            return [this.makefileSection, this.aliasSection];
+            return [this.makefileSection, this.aliasSection, this.recipeSection];
         }
         if (element instanceof SectionNode) {
             if (element.key === 'makefile') {
                 return this.cmHandler.categories() as unknown as CargoMakeNode[];
             }
+            if (element.key === 'recipe') {
+                return this.justHandler.recipes() as unknown as JustNode[];
+            }
             return this.xtHandler.aliases() as unknown as XtaskNode[];
         }
         if (element instanceof CargoMakeNode) {
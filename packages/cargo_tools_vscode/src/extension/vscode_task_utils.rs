@@ -1,10 +1,17 @@
 use std::{collections::HashMap, fmt::Debug};
 
-use futures::{SinkExt, channel::mpsc::Sender};
+use futures::{
+    SinkExt, StreamExt,
+    channel::mpsc::{Sender, channel},
+};
 use iced_viewless::Task;
 use serde::de::DeserializeOwned;
 use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::{js_sys::Array, spawn_local};
+use wasm_bindgen_futures::{
+    future_to_promise,
+    js_sys::{Array, Promise},
+    spawn_local,
+};
 
 use crate::quick_pick::show_quick_pick_type;
 use tracing::{debug, error};
@@ -16,9 +23,23 @@ extern "C" {
     #[wasm_bindgen(catch)]
     fn register_command(command: &str, callback: &Closure<dyn FnMut(Array)>)
     -> Result<(), JsValue>;
+
+    #[wasm_bindgen(catch, js_name = register_command)]
+    fn register_query_command(
+        command: &str,
+        callback: &Closure<dyn FnMut(Array) -> Promise>,
+    ) -> Result<(), JsValue>;
 }
 
 pub type CommandBinding = Closure<dyn FnMut(Array)>;
+pub type QueryBinding = Closure<dyn FnMut(Array) -> Promise>;
+
+/// Request for the value of a query command, answered via `tx`
+#[derive(Debug, Clone)]
+pub struct QueryRequest<Q> {
+    pub query: Q,
+    pub tx: Sender<Option<String>>,
+}
 type CommandMap = HashMap<&'static str, CommandBinding>;
 
 pub type OnFileChanged = Closure<dyn FnMut()>;
@@ -34,6 +55,19 @@ pub fn send_file_changed(tx: Sender<()>) -> OnFileChanged {
     })
 }
 
+pub type OnTaskMessages = Closure<dyn FnMut(String)>;
+
+pub fn send_task_messages(tx: Sender<String>) -> OnTaskMessages {
+    Closure::new(move |messages: String| {
+        let tx = tx.clone();
+        spawn_local(async move {
+            if let Err(e) = tx.clone().send(messages).await {
+                error!("Failed to pass the messages of a task: {e}",)
+            }
+        })
+    })
+}
+
 fn register_tasks(cmds: CommandMap) -> Vec<CommandBinding> {
     cmds.into_iter()
         .map(|(command_id, cmd)| {
@@ -105,6 +139,43 @@ pub fn register_commands<Cmd: Debug + 'static, const N: usize>(
     ))
 }
 
+/// Registers commands which return a value to their caller.
+/// VS Code e.g. resolves `${command:...}` variables in `tasks.json` and `launch.json` with them.
+pub fn register_query_commands<Q: Debug + Copy + 'static, const N: usize>(
+    tx: Sender<QueryRequest<Q>>,
+    all: [(&'static str, Q); N],
+) -> Vec<QueryBinding> {
+    all.into_iter()
+        .map(|(command_id, query)| {
+            let tx = tx.clone();
+            let cmd: QueryBinding = Closure::new(move |_args: Array| {
+                let mut tx = tx.clone();
+                future_to_promise(async move {
+                    let (reply_tx, mut reply_rx) = channel(1);
+                    let request = QueryRequest {
+                        query,
+                        tx: reply_tx,
+                    };
+                    if let Err(e) = tx.send(request).await {
+                        error!("Failed to queue query '{query:?}': {e}");
+                    }
+                    Ok(reply_rx
+                        .next()
+                        .await
+                        .flatten()
+                        .map(JsValue::from)
+                        .unwrap_or(JsValue::UNDEFINED))
+                })
+            });
+            debug!("Register query '{command_id}'");
+            if let Err(e) = register_query_command(command_id, &cmd) {
+                error!("Failed to register query '{command_id}': {e:?}");
+            }
+            cmd
+        })
+        .collect()
+}
+
 pub fn select_name_filter<C: Send + 'static>(
     current: String,
     options: Array,
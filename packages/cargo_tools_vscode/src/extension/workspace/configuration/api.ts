@@ -0,0 +1,55 @@
+import * as vscode from 'vscode';
+
+export interface SelectedTarget {
+    /** `bin`, `example`, `lib` or `bench` */
+    kind: string;
+    name: string;
+    /** The path of the executable cargo builds, only known for run targets */
+    executablePath?: string;
+}
+
+export interface Selection {
+    workspaceRoot: string;
+    package?: string;
+    buildTarget?: SelectedTarget;
+    runTarget?: SelectedTarget;
+    platformTarget?: string;
+    profile?: string;
+    /** `"all"` or the features */
+    features: 'all' | string[];
+    /** The arguments of the `cargo build` of the selection */
+    buildArgs: string[];
+}
+
+/** The API other extensions get from `vscode.extensions.getExtension(...).exports` */
+export interface CargoToolsApi {
+    /** The workspace root and selected package, undefined until the metadata is loaded */
+    getActiveProject(): { workspaceRoot: string; package?: string } | undefined;
+    /** The selected build target, the run target if there is none */
+    getSelectedTarget(): SelectedTarget | undefined;
+    getBuildArgs(): string[] | undefined;
+    readonly onSelectionChanged: vscode.Event<Selection>;
+}
+
+let selection: Selection | undefined;
+const selectionChanged = new vscode.EventEmitter<Selection>();
+
+export function publish_selection(next: Selection): void {
+    const changed = JSON.stringify(next) !== JSON.stringify(selection);
+    selection = next;
+    if (changed) {
+        selectionChanged.fire(next);
+    }
+}
+
+export function create_api(): CargoToolsApi {
+    return {
+        getActiveProject: () => selection && {
+            workspaceRoot: selection.workspaceRoot,
+            package: selection.package,
+        },
+        getSelectedTarget: () => selection?.buildTarget ?? selection?.runTarget,
+        getBuildArgs: () => selection?.buildArgs,
+        onSelectionChanged: selectionChanged.event,
+    };
+}
@@ -0,0 +1,72 @@
+use cargo_tools::cargo::BenchmarkReport;
+use serde::Serialize;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::runtime::read_file_vs_code;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/benchmarks.ts"
+)]
+extern "C" {
+    /// Lists the benchmarks of the criterion reports
+    #[derive(Clone)]
+    pub type BenchmarkReportsView;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> BenchmarkReportsView;
+
+    #[wasm_bindgen(method)]
+    fn show_reports(this: &BenchmarkReportsView, reports: JsValue);
+
+    /// The directories below `dir` which contain a `new/benchmark.json`, relative to `dir`
+    async fn find_criterion_benchmarks(dir: String) -> JsValue;
+
+    /// Shows the HTML report `file` below the criterion `report_dir` in a webview
+    pub async fn open_criterion_report(report_dir: String, file: String, title: String);
+}
+
+#[derive(Serialize)]
+struct BenchmarkItem<'a> {
+    #[serde(flatten)]
+    report: &'a BenchmarkReport,
+    summary: String,
+}
+
+/// Reads the benchmarks criterion reported to `report_dir` and lists them in `view`
+pub async fn load_benchmark_reports(view: BenchmarkReportsView, report_dir: String) {
+    let directories: Vec<String> =
+        serde_wasm_bindgen::from_value(find_criterion_benchmarks(report_dir.clone()).await)
+            .unwrap_or_default();
+
+    let mut reports = Vec::new();
+    for directory in directories {
+        let file = |name: &str| format!("{report_dir}/{directory}/{name}");
+        let (Ok(benchmark), Ok(estimates)) = (
+            read_file_vs_code(file("new/benchmark.json")).await,
+            read_file_vs_code(file("new/estimates.json")).await,
+        ) else {
+            continue;
+        };
+        // The comparison with the previous run, missing until the benchmark ran twice
+        let change = read_file_vs_code(file("change/estimates.json")).await.ok();
+        match BenchmarkReport::parse(&directory, &benchmark, &estimates, change.as_deref()) {
+            Ok(report) => reports.push(report),
+            Err(e) => error!("Failed to parse the criterion report of {directory}: {e}"),
+        }
+    }
+    reports.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let items: Vec<_> = reports
+        .iter()
+        .map(|report| BenchmarkItem {
+            report,
+            summary: report.summary(),
+        })
+        .collect();
+    match to_value(&items) {
+        Ok(items) => view.show_reports(items),
+        Err(e) => error!("Failed to show the benchmark reports: {e}"),
+    }
+}
@@ -0,0 +1,145 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+type Verdict = 'improved' | 'regressed' | 'unchanged';
+
+interface Change {
+    fraction: number;
+    verdict: Verdict;
+}
+
+interface BenchmarkItem {
+    id: string;
+    group: string;
+    directory: string;
+    mean_ns: number;
+    median_ns: number;
+    mean_change?: Change;
+    median_change?: Change;
+    summary: string;
+}
+
+type Node = { group: string, benchmarks: BenchmarkItem[] } | BenchmarkItem;
+
+function icon(change?: Change): vscode.ThemeIcon {
+    switch (change?.verdict) {
+        case 'improved':
+            return new vscode.ThemeIcon('arrow-down', new vscode.ThemeColor('testing.iconPassed'));
+        case 'regressed':
+            return new vscode.ThemeIcon('arrow-up', new vscode.ThemeColor('testing.iconFailed'));
+        case 'unchanged':
+            return new vscode.ThemeIcon('arrow-both');
+        default:
+            return new vscode.ThemeIcon('dashboard');
+    }
+}
+
+/**
+ * Lists the benchmarks criterion reported to `target/criterion` by group, with their mean and
+ * median and how they changed since the previous run. The view appears once there are reports.
+ */
+export class BenchmarkReportsView implements vscode.TreeDataProvider<Node> {
+    private _onDidChangeTreeData = new vscode.EventEmitter<Node | undefined | null | void>();
+    readonly onDidChangeTreeData = this._onDidChangeTreeData.event;
+
+    private view: vscode.TreeView<Node>;
+    private groups: { group: string, benchmarks: BenchmarkItem[] }[] = [];
+
+    constructor() {
+        this.view = vscode.window.createTreeView('cargoToolsBenchmarks', { treeDataProvider: this });
+        extension_context?.subscriptions.push(this.view);
+    }
+
+    getTreeItem(node: Node): vscode.TreeItem {
+        if ('benchmarks' in node) {
+            const item = new vscode.TreeItem(node.group, vscode.TreeItemCollapsibleState.Expanded);
+            item.iconPath = new vscode.ThemeIcon('folder');
+            item.contextValue = 'benchmarkGroup';
+            return item;
+        }
+        const prefix = `${node.group}/`;
+        const label = node.id.startsWith(prefix) && node.id !== node.group ? node.id.slice(prefix.length) : node.id;
+        const item = new vscode.TreeItem(label);
+        item.description = node.summary;
+        item.tooltip = `${node.id}\n${node.summary}`;
+        item.iconPath = icon(node.mean_change);
+        item.contextValue = 'benchmark';
+        item.command = {
+            command: 'cargo-tools.openBenchmarkReport',
+            title: 'Open Benchmark Report',
+            arguments: [node.directory],
+        };
+        return item;
+    }
+
+    getChildren(node?: Node): Node[] {
+        if (!node) {
+            return this.groups;
+        }
+        return 'benchmarks' in node ? node.benchmarks : [];
+    }
+
+    show_reports(benchmarks: BenchmarkItem[]): void {
+        const groups = new Map<string, BenchmarkItem[]>();
+        for (const benchmark of benchmarks) {
+            groups.set(benchmark.group, [...(groups.get(benchmark.group) ?? []), benchmark]);
+        }
+        this.groups = [...groups].map(([group, benchmarks]) => ({ group, benchmarks }));
+        const regressed = benchmarks.filter((b) => b.mean_change?.verdict === 'regressed').length;
+        this.view.description = regressed > 0 ? `${regressed} regressed` : undefined;
+        vscode.commands.executeCommand('setContext', 'cargoTools:hasCriterionReports', benchmarks.length > 0);
+        this._onDidChangeTreeData.fire();
+    }
+}
+
+export async function find_criterion_benchmarks(dir: string): Promise<string[]> {
+    const benchmarks: string[] = [];
+    const visit = async (relative: string): Promise<void> => {
+        const uri = vscode.Uri.file(path.join(dir, relative));
+        const benchmark = vscode.Uri.joinPath(uri, 'new', 'benchmark.json');
+        if (await vscode.workspace.fs.stat(benchmark).then(() => true, () => false)) {
+            benchmarks.push(relative.split(path.sep).join('/'));
+            return;
+        }
+        const entries = await Promise.resolve(vscode.workspace.fs.readDirectory(uri)).catch(() => []);
+        for (const [name, type] of entries) {
+            // `report` holds the summary of all benchmarks
+            if ((type & vscode.FileType.Directory) !== 0 && name !== 'report') {
+                await visit(relative ? path.join(relative, name) : name);
+            }
+        }
+    };
+    await visit('');
+    return benchmarks;
+}
+
+let panel: vscode.WebviewPanel | undefined;
+
+/**
+ * Shows the HTML report `file` below the criterion `report_dir` with the SVG plots next to it
+ */
+export async function open_criterion_report(report_dir: string, file: string, title: string): Promise<void> {
+    const uri = vscode.Uri.file(file);
+    let html: string;
+    try {
+        html = new TextDecoder().decode(await vscode.workspace.fs.readFile(uri));
+    } catch {
+        vscode.window.showWarningMessage(`No criterion report at ${file}, run the benchmarks first`);
+        return;
+    }
+    if (!panel) {
+        panel = vscode.window.createWebviewPanel('cargoToolsBenchmarkReport', title,
+            vscode.ViewColumn.Active, { localResourceRoots: [vscode.Uri.file(report_dir)] });
+        panel.onDidDispose(() => {
+            panel = undefined;
+        });
+        extension_context?.subscriptions.push(panel);
+    } else {
+        panel.reveal();
+    }
+    // Relative links like the plots resolve next to the report
+    const base = panel.webview.asWebviewUri(vscode.Uri.joinPath(uri, '..'));
+    panel.title = title;
+    panel.webview.html = html.replace(/<head>/i, `<head><base href="${base.toString()}/">`);
+}
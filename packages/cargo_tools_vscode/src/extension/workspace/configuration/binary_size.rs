@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use cargo_tools::cargo::{BinarySizeHistory, binary_size};
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::runtime::{FileStat, file_stat_vs_code};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/binary_size.ts"
+)]
+extern "C" {
+    fn show_binary_size_trend_js(history: JsValue, reveal: bool);
+
+    async fn show_size_jumps(messages: Vec<String>) -> JsValue;
+}
+
+/// Shows the size trend of each artifact of `history` in a webview. An open webview is updated
+/// in place, it is only revealed or opened if `reveal` is set.
+pub fn show_binary_size_trend(history: &BinarySizeHistory, reveal: bool) {
+    match to_value(history) {
+        Ok(history) => show_binary_size_trend_js(history, reveal),
+        Err(e) => error!("Failed to show the binary sizes: {e}"),
+    }
+}
+
+/// `bytes` in binary units like `1.5 MiB`, for the size trend webview
+#[wasm_bindgen]
+pub fn format_size(bytes: f64) -> String {
+    binary_size::format_size(bytes as u64)
+}
+
+/// Warns about the builds whose artifacts grew more than the threshold. Returns whether the
+/// size trend is to be shown.
+pub async fn warn_size_jumps(messages: Vec<String>) -> bool {
+    show_size_jumps(messages).await.as_bool().unwrap_or(false)
+}
+
+/// The size of each of the `executables` which exists, by its path relative to `target_dir`
+pub async fn read_executable_sizes(
+    target_dir: String,
+    executables: Vec<String>,
+) -> Vec<(String, FileStat)> {
+    let mut sizes = Vec::new();
+    for executable in executables {
+        let Some(stat) = file_stat_vs_code(executable.clone()).await else {
+            continue;
+        };
+        let artifact = Path::new(&executable)
+            .strip_prefix(&target_dir)
+            .map_or(executable.clone(), |path| {
+                path.to_string_lossy().replace('\\', "/")
+            });
+        sizes.push((artifact, stat));
+    }
+    sizes
+}
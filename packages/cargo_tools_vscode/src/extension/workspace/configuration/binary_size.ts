@@ -0,0 +1,99 @@
+import * as vscode from 'vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+import { format_size } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+
+interface SizeSample {
+    built_ms: number;
+    bytes: number;
+    jumped: boolean;
+}
+
+type BinarySizeHistory = { [artifact: string]: SizeSample[] };
+
+const WIDTH = 640;
+const HEIGHT = 160;
+const PADDING = 8;
+
+let panel: vscode.WebviewPanel | undefined;
+
+function escapeHtml(text: string): string {
+    return text.replace(/[&<>"]/g, (c) => ({ '&': '&amp;', '<': '&lt;', '>': '&gt;', '"': '&quot;' }[c] ?? c));
+}
+
+/** A line of the sizes over the builds, jumps are marked red */
+function trendSvg(samples: SizeSample[]): string {
+    const max = Math.max(...samples.map((s) => s.bytes));
+    const min = Math.min(...samples.map((s) => s.bytes));
+    // Flat trends are drawn in the middle
+    const range = max - min || 1;
+    const x = (i: number) => samples.length === 1
+        ? WIDTH / 2
+        : PADDING + (i * (WIDTH - 2 * PADDING)) / (samples.length - 1);
+    const y = (bytes: number) => max === min
+        ? HEIGHT / 2
+        : HEIGHT - PADDING - ((bytes - min) * (HEIGHT - 2 * PADDING)) / range;
+
+    const points = samples.map((s, i) => `${x(i)},${y(s.bytes)}`).join(' ');
+    const markers = samples.map((s, i) => {
+        const title = `${format_size(s.bytes)} at ${new Date(s.built_ms).toLocaleString()}`;
+        const cls = s.jumped ? 'jump' : 'build';
+        return `<circle class="${cls}" cx="${x(i)}" cy="${y(s.bytes)}" r="${s.jumped ? 5 : 3}"><title>${escapeHtml(title)}</title></circle>`;
+    }).join('');
+    return `<svg viewBox="0 0 ${WIDTH} ${HEIGHT}" width="${WIDTH}" height="${HEIGHT}">`
+        + `<polyline points="${points}"/>${markers}</svg>`;
+}
+
+function html(history: BinarySizeHistory): string {
+    const artifacts = Object.entries(history).filter(([, samples]) => samples.length > 0);
+    const sections = artifacts.map(([artifact, samples]) => {
+        const last = samples[samples.length - 1];
+        const jumps = samples.filter((s) => s.jumped).length;
+        const summary = `${format_size(last.bytes)} after ${samples.length} builds`
+            + (jumps > 0 ? `, <span class="jump-text">${jumps} jumps</span>` : '');
+        return `<h2>${escapeHtml(artifact)}</h2><p>${summary}, between ${format_size(Math.min(...samples.map((s) => s.bytes)))}`
+            + ` and ${format_size(Math.max(...samples.map((s) => s.bytes)))}</p>${trendSvg(samples)}`;
+    }).join('');
+    const body = sections || '<p>No bin target was built yet.</p>';
+    return `<!DOCTYPE html>
+<html>
+<head>
+<meta charset="UTF-8">
+<meta http-equiv="Content-Security-Policy" content="default-src 'none'; style-src 'unsafe-inline';">
+<style>
+    body { font-family: var(--vscode-font-family); color: var(--vscode-foreground); }
+    polyline { fill: none; stroke: var(--vscode-charts-blue); stroke-width: 2; }
+    circle.build { fill: var(--vscode-charts-blue); }
+    circle.jump { fill: var(--vscode-charts-red); }
+    .jump-text { color: var(--vscode-charts-red); }
+    svg { border-bottom: 1px solid var(--vscode-panel-border); }
+</style>
+</head>
+<body><h1>Binary Sizes</h1>${body}</body>
+</html>`;
+}
+
+/**
+ * Shows the size trend of the bin artifacts in a webview, which is updated after each build
+ * while it is open.
+ */
+export function show_binary_size_trend_js(history: BinarySizeHistory, reveal: boolean): void {
+    if (!panel) {
+        if (!reveal) {
+            return;
+        }
+        panel = vscode.window.createWebviewPanel('cargoToolsBinarySizes', 'Binary Sizes', vscode.ViewColumn.Active, {});
+        panel.onDidDispose(() => {
+            panel = undefined;
+        });
+        extension_context?.subscriptions.push(panel);
+    } else if (reveal) {
+        panel.reveal();
+    }
+    panel.webview.html = html(history);
+}
+
+export async function show_size_jumps(messages: string[]): Promise<boolean> {
+    const show = 'Show Size Trend';
+    const selected = await vscode.window.showWarningMessage(messages.join('\n'), show);
+    return selected === show;
+}
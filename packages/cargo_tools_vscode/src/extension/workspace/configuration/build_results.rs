@@ -0,0 +1,38 @@
+use cargo_tools::cargo::FileDiagnostic;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/build_results.ts"
+)]
+extern "C" {
+    /// Lists the compiler messages of the last check, build or test, filterable by level, package
+    /// and lint
+    #[derive(Clone)]
+    pub type BuildResultsView;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> BuildResultsView;
+
+    #[wasm_bindgen(method, js_name = show_results)]
+    fn show_results_js(this: &BuildResultsView, diagnostics: JsValue);
+
+    /// Prompts for the levels, packages and lints to show, none selected shows all
+    #[wasm_bindgen(method)]
+    pub fn pick_filter(this: &BuildResultsView);
+
+    /// Reveals the view showing the messages of `package`, of all packages without one
+    #[wasm_bindgen(method)]
+    pub fn show_package(this: &BuildResultsView, package: Option<String>);
+}
+
+impl BuildResultsView {
+    /// Replaces the listed messages, the filter is kept
+    pub fn show_results(&self, diagnostics: &[FileDiagnostic]) {
+        match to_value(diagnostics) {
+            Ok(diagnostics) => self.show_results_js(diagnostics),
+            Err(e) => error!("Failed to serialize build results: {e}"),
+        }
+    }
+}
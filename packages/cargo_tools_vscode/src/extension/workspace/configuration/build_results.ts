@@ -0,0 +1,158 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+interface SourceRange {
+    start_line: number;
+    start_column: number;
+    end_line: number;
+    end_column: number;
+}
+
+interface FileDiagnostic {
+    package: string;
+    file: string;
+    range: SourceRange;
+    severity: 'error' | 'warning' | 'information' | 'hint';
+    message: string;
+    code?: string;
+}
+
+type FilterKind = 'level' | 'package' | 'lint';
+
+interface FilterItem extends vscode.QuickPickItem {
+    filterKind: FilterKind;
+    value: string;
+}
+
+const ICONS = {
+    error: new vscode.ThemeIcon('error', new vscode.ThemeColor('list.errorForeground')),
+    warning: new vscode.ThemeIcon('warning', new vscode.ThemeColor('list.warningForeground')),
+    information: new vscode.ThemeIcon('info'),
+    hint: new vscode.ThemeIcon('lightbulb'),
+};
+
+const ORDER = ['error', 'warning', 'information', 'hint'];
+
+/**
+ * Lists the compiler messages of the last check, build or test task as rows, errors first. The
+ * rows can be filtered by level, package and lint, a filter of several kinds shows the rows
+ * matching all.
+ */
+export class BuildResultsView implements vscode.TreeDataProvider<FileDiagnostic> {
+    private _onDidChangeTreeData = new vscode.EventEmitter<FileDiagnostic | undefined | null | void>();
+    readonly onDidChangeTreeData = this._onDidChangeTreeData.event;
+
+    private view: vscode.TreeView<FileDiagnostic>;
+    private diagnostics: FileDiagnostic[] = [];
+    private filter = new Map<FilterKind, Set<string>>();
+
+    constructor() {
+        this.view = vscode.window.createTreeView('cargoToolsBuildResults', { treeDataProvider: this });
+        extension_context?.subscriptions.push(this.view);
+    }
+
+    getTreeItem(diagnostic: FileDiagnostic): vscode.TreeItem {
+        const item = new vscode.TreeItem(diagnostic.message);
+        const line = diagnostic.range.start_line + 1;
+        item.description = `${diagnostic.package} · ${path.basename(diagnostic.file)}:${line}`;
+        item.tooltip = `${diagnostic.severity}${diagnostic.code ? `[${diagnostic.code}]` : ''}: ${diagnostic.message}\n`
+            + `${diagnostic.file}:${line}:${diagnostic.range.start_column + 1}`;
+        item.iconPath = ICONS[diagnostic.severity];
+        item.contextValue = 'buildResult';
+        const range = diagnostic.range;
+        item.command = {
+            command: 'vscode.open',
+            title: 'Open',
+            arguments: [vscode.Uri.file(diagnostic.file), {
+                selection: new vscode.Range(range.start_line, range.start_column, range.end_line, range.end_column),
+            }],
+        };
+        return item;
+    }
+
+    getChildren(diagnostic?: FileDiagnostic): FileDiagnostic[] {
+        return diagnostic ? [] : this.diagnostics.filter((d) => this.matches(d));
+    }
+
+    show_results(diagnostics: FileDiagnostic[]): void {
+        this.diagnostics = [...diagnostics].sort((a, b) => ORDER.indexOf(a.severity) - ORDER.indexOf(b.severity)
+            || a.file.localeCompare(b.file)
+            || a.range.start_line - b.range.start_line);
+        this.refresh();
+    }
+
+    async pick_filter(): Promise<void> {
+        const items: (FilterItem | vscode.QuickPickItem)[] = [];
+        const section = (label: string, filterKind: FilterKind, values: Iterable<string>) => {
+            const sorted = [...new Set(values)].sort();
+            if (sorted.length === 0) {
+                return;
+            }
+            items.push({ label, kind: vscode.QuickPickItemKind.Separator });
+            for (const value of sorted) {
+                const count = this.diagnostics.filter((d) => this.value(d, filterKind) === value).length;
+                items.push({
+                    label: value,
+                    description: `${count}`,
+                    picked: this.filter.get(filterKind)?.has(value) ?? false,
+                    filterKind,
+                    value,
+                });
+            }
+        };
+        section('Level', 'level', this.diagnostics.map((d) => d.severity));
+        section('Package', 'package', this.diagnostics.map((d) => d.package));
+        section('Lint', 'lint', this.diagnostics.flatMap((d) => d.code ? [d.code] : []));
+
+        const picked = await vscode.window.showQuickPick(items, {
+            canPickMany: true,
+            title: 'Filter Build Results',
+            placeHolder: 'Show the messages of the selected levels, packages and lints, none selected shows all',
+        });
+        if (!picked) {
+            return;
+        }
+        this.filter.clear();
+        for (const item of picked) {
+            if ('filterKind' in item) {
+                this.filter.set(item.filterKind, (this.filter.get(item.filterKind) ?? new Set()).add(item.value));
+            }
+        }
+        this.refresh();
+    }
+
+    async show_package(name?: string): Promise<void> {
+        if (name) {
+            this.filter.set('package', new Set([name]));
+        } else {
+            this.filter.delete('package');
+        }
+        this.refresh();
+        await vscode.commands.executeCommand('cargoToolsBuildResults.focus');
+    }
+
+    private value(diagnostic: FileDiagnostic, kind: FilterKind): string | undefined {
+        switch (kind) {
+            case 'level':
+                return diagnostic.severity;
+            case 'package':
+                return diagnostic.package;
+            case 'lint':
+                return diagnostic.code;
+        }
+    }
+
+    private matches(diagnostic: FileDiagnostic): boolean {
+        return [...this.filter].every(([kind, values]) => values.has(this.value(diagnostic, kind) ?? ''));
+    }
+
+    private refresh(): void {
+        const shown = this.diagnostics.filter((d) => this.matches(d)).length;
+        const errors = this.diagnostics.filter((d) => d.severity === 'error').length;
+        const warnings = this.diagnostics.filter((d) => d.severity === 'warning').length;
+        const summary = `${errors} errors, ${warnings} warnings`;
+        this.view.description = this.filter.size > 0 ? `${shown} of ${this.diagnostics.length} shown · ${summary}` : summary;
+        this._onDidChangeTreeData.fire();
+    }
+}
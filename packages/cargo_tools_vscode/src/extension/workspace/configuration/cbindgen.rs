@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use cargo_tools::{
+    cargo::{Config, metadata::Package},
+    cbindgen::{self, CBINDGEN, CONFIG_FILE},
+    process::Process,
+};
+use tracing::{error, info};
+
+use crate::{
+    environment::{cbindgen_task_context, metadata_task_context},
+    runtime::{
+        VsCodeTask, exec_vs_code, execute_task_and_wait, file_exists_vs_code, show_warning_message,
+    },
+};
+
+const INSTALL: &str = "Install";
+
+/// Whether cbindgen is installed, offers to install it with `cargo install` if it isn't
+async fn cbindgen_installed() -> bool {
+    let version = Process::new(
+        CBINDGEN.to_string(),
+        vec!["--version".to_string()],
+        HashMap::new(),
+    );
+    if exec_vs_code(version).await.is_ok() {
+        return true;
+    }
+    let message = "cbindgen is not installed";
+    let action = show_warning_message(message, vec![INSTALL.to_string()]).await;
+    if action.as_string().as_deref() == Some(INSTALL) {
+        let args = ["install", "--locked", CBINDGEN]
+            .map(str::to_string)
+            .to_vec();
+        match metadata_task_context().try_into_process(args) {
+            Ok(process) => {
+                execute_task_and_wait(VsCodeTask::cargo(process)).await;
+            }
+            Err(e) => error!("{e}"),
+        }
+    }
+    false
+}
+
+/// Generates the C header of the `cdylib` of `package` with cbindgen and the `cbindgen.toml`
+/// of the package, next to the library cargo builds with `config` in `target_dir`
+pub async fn generate_c_header(package: Package, target_dir: String, config: Config) {
+    let Some(lib_name) = cbindgen::cdylib_name(&package) else {
+        error!("{} has no cdylib target", package.name);
+        return;
+    };
+    if !cbindgen_installed().await {
+        return;
+    }
+    let dir = package.manifest_dir().to_string();
+    let has_config = file_exists_vs_code(format!("{dir}/{CONFIG_FILE}")).await;
+    let header = cbindgen::header_path(&target_dir, &config, lib_name);
+    let args = cbindgen::args(&package.name, &header, has_config);
+
+    match cbindgen_task_context(dir).try_into_process(args) {
+        Ok(process) => {
+            if execute_task_and_wait(VsCodeTask::cargo(process)).await {
+                info!("Generated {header}");
+            }
+        }
+        Err(e) => error!("{e}"),
+    }
+}
@@ -1,9 +1,13 @@
+use cargo_tools::cargo::{command::RunSubTarget, new_target::TargetKind};
 use futures::channel::mpsc::Sender;
 use wasm_bindgen_futures::js_sys::Array;
 
 use crate::{
-    commands::configuration::*,
-    extension::vscode_task_utils::{CommandBinding, register_commands, take_first},
+    commands::{configuration::*, variables},
+    extension::vscode_task_utils::{
+        CommandBinding, QueryBinding, QueryRequest, register_commands, register_query_commands,
+        take_first, take_first_two,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -20,11 +24,144 @@ pub enum Command {
     BuildDocs,
     Refresh,
     Clean,
+    BuildPackages,
+    CancelBuild,
+    ToggleWatch,
+    MakeDefaultBuildTask,
+    BuildAndTest,
+    RunInCleanEnvironment,
+    /// A test function in a source file, the name is relative to the file's module
+    RunTest {
+        file: String,
+        name: String,
+    },
+    DebugTest {
+        file: String,
+        name: String,
+    },
+    AttachToProcess,
+    /// Debugs the doctest of the code block at a zero based line of a file, or one picked
+    /// from the doctests of the selected package
+    DebugDocTest {
+        location: Option<(String, usize)>,
+    },
+    RunDocTest {
+        file: String,
+        line: usize,
+    },
+    /// Runs the tests which failed in their last run from the Testing view
+    RerunFailedTests,
+    /// Lists the tests by their recorded durations in the **Slowest Tests** view, or reveals
+    /// the test clicked in it
+    ShowSlowestTests(Option<String>),
+    /// Tests the packages with changes since `cargoTools.test.changedBaseRef` and their
+    /// dependents
+    TestChangedPackages,
+    /// Picks the profile of nextest test runs from the profiles of `.config/nextest.toml`
+    SelectNextestProfile,
+    /// Prompts for an `INSTA_UPDATE` mode the tests of the selection run with
+    RunSnapshotTests,
+    /// Accepts or rejects the pending `.snap.new` snapshots one by one
+    ReviewSnapshots,
+    /// Repeats the previous debug session without pickers
+    DebugLast,
+    /// Inspects a core dump of the run target
+    OpenCoreDump,
+    /// Shows the hit rates of sccache if it is the compiler wrapper
+    ShowCompilerCacheStats,
+    /// Shows the sizes of the bin executables after their recent builds
+    ShowBinarySizeTrend,
+    /// Opens the criterion report of the benchmark in the directory, of all benchmarks if
+    /// there is none
+    OpenBenchmarkReport(Option<String>),
+    /// Checks the selected package to list its compiler messages in the **Build Results**
+    RefreshBuildResults,
+    /// Prompts for the levels, packages and lints the **Build Results** are filtered by
+    FilterBuildResults,
+    /// Focuses the Problems panel on the problems of the selected package
+    ShowPackageProblems,
+    /// Flashes the run target built for the embedded platform target with probe-rs
+    FlashAndRun,
+    /// Attaches probe-rs to the running firmware of the run target for its RTT output
+    AttachRtt,
+    /// Creates a workspace member with `cargo new` from prompted name, kind and location
+    NewPackage,
+    /// Generates a workspace member with cargo-generate from a configured template
+    NewPackageFromTemplate,
+    /// Creates an example, integration test or benchmark in `package`, from the outline
+    NewTarget {
+        package: String,
+        kind: TargetKind,
+    },
+    /// Picks the Cargo workspace in use among the ones discovered in the folder
+    SelectWorkspace,
+    /// Opens docs.rs for the locked version of the dependency under the cursor or a picked one
+    OpenDependencyDocs,
+    /// Updates a dependency locked at a version with `cargo update`, from its hover
+    UpdateDependency {
+        name: String,
+        version: Option<String>,
+    },
+    /// Moves a dependency of the members to `[workspace.dependencies]`, the one of the code
+    /// action or a picked one
+    PromoteToWorkspaceDependency(Option<String>),
+    /// Bumps the version of a package or all publishable members
+    BumpVersion,
+    /// Renames a package, its dependents and optionally its directory
+    RenamePackage,
+    /// Sorts the dependency tables of the manifests of a package or the workspace
+    NormalizeManifest,
+    /// Shows the licenses of all transitive dependencies
+    LicenseReport,
+    /// Explains the unified features of a dependency and the members enabling them
+    ExplainFeatures,
+    /// Sets the `rust-version` of all members
+    SetRustVersion,
+    /// Checks the members with their `rust-version`
+    VerifyRustVersion,
+    /// Runs fmt, clippy and the tests of the packages changed since the last commit
+    RunPreCommitChecks,
+    /// Installs a git pre-commit hook which rejects commits failing the checks
+    InstallPreCommitHook,
+    /// Captures the variables of direnv and `cargoTools.shellEnv.initCommand` again
+    ReloadShellEnvironment,
+    /// Installs and removes rustup components like clippy or miri of a picked toolchain
+    ManageToolchainComponents,
+    /// Adds a crate to a package with `cargo add` from a picked registry
+    AddDependency,
+    /// Publishes a package to a picked registry, as dry run if picked
+    PublishPackage,
+    /// Stores the token of a picked registry in the secret storage
+    SetRegistryToken,
+    /// Builds the selected package with Trunk, wasm-pack or cargo for wasm32
+    BuildWasm,
+    /// Serves the selected package with `trunk serve`
+    ServeWasm,
+    /// Generates the C header of the `cdylib` of the selected package with cbindgen
+    GenerateCHeader,
+    /// A CodeLens of a manifest acting on its package, or on one of its run targets
+    ManifestLens {
+        action: ManifestLensAction,
+        manifest: String,
+        target: Option<RunSubTarget>,
+    },
+    /// Toggles a feature of the `[features]` table of the manifest of the selected package
+    ToggleManifestFeature {
+        manifest: String,
+        feature: String,
+    },
     Build,
     Run,
     Debug,
+    /// Prompts for one-off program arguments
+    RunWithArgs,
+    DebugWithArgs,
+    /// Prompts for a sanitizer the run target is built with
+    RunWithSanitizer,
+    DebugWithSanitizer,
     Test,
     Bench,
+    DebugBench,
     ToggleFeature(String),
 }
 
@@ -57,11 +194,166 @@ impl Command {
             (CARGO_TOOLS_SELECT_FEATURES, |_| Some(Self::SelectFeatures)),
             (CARGO_TOOLS_REFRESH, |_| Some(Self::Refresh)),
             (CARGO_TOOLS_CLEAN, |_| Some(Self::Clean)),
+            (CARGO_TOOLS_BUILD_PACKAGES, |_| Some(Self::BuildPackages)),
+            (CARGO_TOOLS_CANCEL_BUILD, |_| Some(Self::CancelBuild)),
+            (CARGO_TOOLS_TOGGLE_WATCH, |_| Some(Self::ToggleWatch)),
+            (CARGO_TOOLS_MAKE_DEFAULT_BUILD_TASK, |_| {
+                Some(Self::MakeDefaultBuildTask)
+            }),
+            (CARGO_TOOLS_BUILD_AND_TEST, |_| Some(Self::BuildAndTest)),
+            (CARGO_TOOLS_RUN_IN_CLEAN_ENVIRONMENT, |_| {
+                Some(Self::RunInCleanEnvironment)
+            }),
+            (CARGO_TOOLS_RUN_TEST, |arg| {
+                take_first_two(arg).map(|(file, name)| Self::RunTest { file, name })
+            }),
+            (CARGO_TOOLS_DEBUG_TEST, |arg| {
+                take_first_two(arg).map(|(file, name)| Self::DebugTest { file, name })
+            }),
+            (CARGO_TOOLS_ATTACH_TO_PROCESS, |_| {
+                Some(Self::AttachToProcess)
+            }),
+            (CARGO_TOOLS_DEBUG_DOC_TEST, |arg| {
+                // The CodeLens passes the code block, the command palette nothing
+                let location = (arg.length() >= 2).then(|| take_first_two(arg)).flatten();
+                Some(Self::DebugDocTest { location })
+            }),
+            (CARGO_TOOLS_RUN_DOC_TEST, |arg| {
+                take_first_two(arg).map(|(file, line)| Self::RunDocTest { file, line })
+            }),
+            (CARGO_TOOLS_RERUN_FAILED_TESTS, |_| {
+                Some(Self::RerunFailedTests)
+            }),
+            (CARGO_TOOLS_SHOW_SLOWEST_TESTS, |arg| {
+                let id = (arg.length() >= 1).then(|| take_first(arg)).flatten();
+                Some(Self::ShowSlowestTests(id))
+            }),
+            (CARGO_TOOLS_TEST_CHANGED_PACKAGES, |_| {
+                Some(Self::TestChangedPackages)
+            }),
+            (CARGO_TOOLS_SELECT_NEXTEST_PROFILE, |_| {
+                Some(Self::SelectNextestProfile)
+            }),
+            (CARGO_TOOLS_RUN_SNAPSHOT_TESTS, |_| {
+                Some(Self::RunSnapshotTests)
+            }),
+            // The Testing view passes the test message of a snapshot failure, which isn't needed
+            (CARGO_TOOLS_REVIEW_SNAPSHOTS, |_| {
+                Some(Self::ReviewSnapshots)
+            }),
+            (CARGO_TOOLS_DEBUG_LAST, |_| Some(Self::DebugLast)),
+            (CARGO_TOOLS_OPEN_CORE_DUMP, |_| Some(Self::OpenCoreDump)),
+            (CARGO_TOOLS_SHOW_COMPILER_CACHE_STATS, |_| {
+                Some(Self::ShowCompilerCacheStats)
+            }),
+            (CARGO_TOOLS_SHOW_BINARY_SIZE_TREND, |_| {
+                Some(Self::ShowBinarySizeTrend)
+            }),
+            (CARGO_TOOLS_OPEN_BENCHMARK_REPORT, |arg| {
+                let directory = (arg.length() >= 1).then(|| take_first(arg)).flatten();
+                Some(Self::OpenBenchmarkReport(directory))
+            }),
+            (CARGO_TOOLS_REFRESH_BUILD_RESULTS, |_| {
+                Some(Self::RefreshBuildResults)
+            }),
+            (CARGO_TOOLS_FILTER_BUILD_RESULTS, |_| {
+                Some(Self::FilterBuildResults)
+            }),
+            (CARGO_TOOLS_SHOW_PACKAGE_PROBLEMS, |_| {
+                Some(Self::ShowPackageProblems)
+            }),
+            (CARGO_TOOLS_FLASH_AND_RUN, |_| Some(Self::FlashAndRun)),
+            (CARGO_TOOLS_ATTACH_RTT, |_| Some(Self::AttachRtt)),
+            (CARGO_TOOLS_NEW_PACKAGE, |_| Some(Self::NewPackage)),
+            (CARGO_TOOLS_NEW_PACKAGE_FROM_TEMPLATE, |_| {
+                Some(Self::NewPackageFromTemplate)
+            }),
+            (CARGO_TOOLS_SELECT_WORKSPACE, |_| {
+                Some(Self::SelectWorkspace)
+            }),
+            (CARGO_TOOLS_OPEN_DEPENDENCY_DOCS, |_| {
+                Some(Self::OpenDependencyDocs)
+            }),
+            (CARGO_TOOLS_UPDATE_DEPENDENCY, |arg| {
+                take_first_two(arg).map(|(name, version)| Self::UpdateDependency { name, version })
+            }),
+            (CARGO_TOOLS_BUMP_VERSION, |_| Some(Self::BumpVersion)),
+            (CARGO_TOOLS_RENAME_PACKAGE, |_| Some(Self::RenamePackage)),
+            (CARGO_TOOLS_NORMALIZE_MANIFEST, |_| {
+                Some(Self::NormalizeManifest)
+            }),
+            (CARGO_TOOLS_LICENSE_REPORT, |_| Some(Self::LicenseReport)),
+            (CARGO_TOOLS_EXPLAIN_FEATURES, |_| {
+                Some(Self::ExplainFeatures)
+            }),
+            (CARGO_TOOLS_SET_RUST_VERSION, |_| Some(Self::SetRustVersion)),
+            (CARGO_TOOLS_VERIFY_RUST_VERSION, |_| {
+                Some(Self::VerifyRustVersion)
+            }),
+            (CARGO_TOOLS_PROMOTE_TO_WORKSPACE_DEPENDENCY, |arg| {
+                take_first(arg).map(Self::PromoteToWorkspaceDependency)
+            }),
+            (CARGO_TOOLS_RUN_PRE_COMMIT_CHECKS, |_| {
+                Some(Self::RunPreCommitChecks)
+            }),
+            (CARGO_TOOLS_INSTALL_PRE_COMMIT_HOOK, |_| {
+                Some(Self::InstallPreCommitHook)
+            }),
+            (CARGO_TOOLS_RELOAD_SHELL_ENVIRONMENT, |_| {
+                Some(Self::ReloadShellEnvironment)
+            }),
+            (CARGO_TOOLS_MANAGE_TOOLCHAIN_COMPONENTS, |_| {
+                Some(Self::ManageToolchainComponents)
+            }),
+            (CARGO_TOOLS_ADD_DEPENDENCY, |_| Some(Self::AddDependency)),
+            (CARGO_TOOLS_PUBLISH_PACKAGE, |_| Some(Self::PublishPackage)),
+            (CARGO_TOOLS_SET_REGISTRY_TOKEN, |_| {
+                Some(Self::SetRegistryToken)
+            }),
+            (CARGO_TOOLS_BUILD_WASM, |_| Some(Self::BuildWasm)),
+            (CARGO_TOOLS_SERVE_WASM, |_| Some(Self::ServeWasm)),
+            (CARGO_TOOLS_GENERATE_C_HEADER, |_| {
+                Some(Self::GenerateCHeader)
+            }),
+            (CARGO_TOOLS_MANIFEST_LENS_BUILD, |arg| {
+                Self::manifest_lens(ManifestLensAction::Build, arg)
+            }),
+            (CARGO_TOOLS_MANIFEST_LENS_TEST, |arg| {
+                Self::manifest_lens(ManifestLensAction::Test, arg)
+            }),
+            (CARGO_TOOLS_MANIFEST_LENS_RUN, |arg| {
+                Self::manifest_lens(ManifestLensAction::Run, arg)
+            }),
+            (CARGO_TOOLS_MANIFEST_LENS_DEBUG, |arg| {
+                Self::manifest_lens(ManifestLensAction::Debug, arg)
+            }),
+            (CARGO_TOOLS_MANIFEST_LENS_SELECT, |arg| {
+                Self::manifest_lens(ManifestLensAction::Select, arg)
+            }),
+            (CARGO_TOOLS_MANIFEST_LENS_TOGGLE_FEATURE, |arg| {
+                take_first_two(arg)
+                    .map(|(manifest, feature)| Self::ToggleManifestFeature { manifest, feature })
+            }),
             (CARGO_TOOLS_PROJECT_STATUS_BUILD, |_| Some(Self::Build)),
             (CARGO_TOOLS_PROJECT_STATUS_RUN, |_| Some(Self::Run)),
             (CARGO_TOOLS_PROJECT_STATUS_DEBUG, |_| Some(Self::Debug)),
+            (CARGO_TOOLS_PROJECT_STATUS_RUN_WITH_ARGS, |_| {
+                Some(Self::RunWithArgs)
+            }),
+            (CARGO_TOOLS_PROJECT_STATUS_DEBUG_WITH_ARGS, |_| {
+                Some(Self::DebugWithArgs)
+            }),
+            (CARGO_TOOLS_PROJECT_STATUS_RUN_WITH_SANITIZER, |_| {
+                Some(Self::RunWithSanitizer)
+            }),
+            (CARGO_TOOLS_PROJECT_STATUS_DEBUG_WITH_SANITIZER, |_| {
+                Some(Self::DebugWithSanitizer)
+            }),
             (CARGO_TOOLS_PROJECT_STATUS_TEST, |_| Some(Self::Test)),
             (CARGO_TOOLS_PROJECT_STATUS_BENCH, |_| Some(Self::Bench)),
+            (CARGO_TOOLS_PROJECT_STATUS_DEBUG_BENCH, |_| {
+                Some(Self::DebugBench)
+            }),
             (CARGO_TOOLS_PROJECT_STATUS_TOGGLE_FEATURE, |arg| {
                 take_first(arg).map(Self::ToggleFeature)
             }),
@@ -69,6 +361,53 @@ impl Command {
     }
 }
 
+impl Command {
+    /// The CodeLens passes the manifest and the run target of its section, none for `[package]`
+    fn manifest_lens(action: ManifestLensAction, arg: Array) -> Option<Self> {
+        take_first_two(arg).map(|(manifest, target)| Self::ManifestLens {
+            action,
+            manifest,
+            target,
+        })
+    }
+}
+
+/// What a CodeLens of a manifest does with its package or run target
+#[derive(Debug, Clone, Copy)]
+pub enum ManifestLensAction {
+    Build,
+    Test,
+    Run,
+    Debug,
+    /// Selects the package and the target as build and run target
+    Select,
+}
+
 pub fn register_configuration_commands(tx: Sender<Command>) -> Vec<CommandBinding> {
     register_commands(tx, Command::all())
 }
+
+/// Values of the current configuration which can be used as `${command:...}` variables
+#[derive(Debug, Clone, Copy)]
+pub enum Variable {
+    SelectedPackage,
+    SelectedTargetName,
+    LaunchTargetPath,
+    BuildProfileFlag,
+}
+
+impl Variable {
+    pub const fn all() -> [(&'static str, Self); variables::NUMBER_CMDS] {
+        use variables::*;
+        [
+            (CARGO_TOOLS_SELECTED_PACKAGE, Self::SelectedPackage),
+            (CARGO_TOOLS_SELECTED_TARGET_NAME, Self::SelectedTargetName),
+            (CARGO_TOOLS_LAUNCH_TARGET_PATH, Self::LaunchTargetPath),
+            (CARGO_TOOLS_BUILD_PROFILE_FLAG, Self::BuildProfileFlag),
+        ]
+    }
+}
+
+pub fn register_variable_commands(tx: Sender<QueryRequest<Variable>>) -> Vec<QueryBinding> {
+    register_query_commands(tx, Variable::all())
+}
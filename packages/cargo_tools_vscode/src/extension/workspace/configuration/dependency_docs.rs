@@ -0,0 +1,118 @@
+use cargo_tools::{
+    cargo::{
+        lockfile::{locked_version, update_args},
+        manifest,
+    },
+    crates_io::{Releases, docs_url},
+};
+use serde::{Deserialize, Serialize};
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    environment::{crates_io_metadata_enabled, metadata_task_context},
+    extension::workspace::outline::crate_info,
+    runtime::{VsCodeTask, execute_task_and_wait},
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/dependency_docs.ts"
+)]
+extern "C" {
+    pub type DependencyDocsHoverProvider;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DependencyDocsHoverProvider;
+
+    /// The docs of the dependency under the cursor if the active editor shows a manifest
+    pub async fn active_dependency_docs() -> JsValue;
+}
+
+/// The docs.rs documentation of a dependency
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyDocs {
+    pub name: String,
+    /// The version of `Cargo.lock`, the requirement of the manifest without a lockfile
+    pub version: Option<String>,
+    pub url: String,
+    /// The requirement of the manifest, the one of `[workspace.dependencies]` for inherited
+    /// dependencies
+    #[serde(default)]
+    pub req: Option<String>,
+    /// The version of `Cargo.lock`
+    #[serde(default)]
+    pub locked: Option<String>,
+}
+
+impl DependencyDocs {
+    /// The docs of the crate `name` pinned to the version `lock` resolved `req` to
+    pub fn new(name: String, req: Option<String>, lock: Option<&str>) -> Self {
+        let locked = lock.and_then(|lock| locked_version(lock, &name, req.as_deref()));
+        let version = locked.clone().or_else(|| req.clone());
+        let url = docs_url(&name, version.as_deref().unwrap_or("*"));
+        Self {
+            version,
+            name,
+            url,
+            req,
+            locked,
+        }
+    }
+}
+
+/// The docs of the dependency declared at `line` of `manifest` for the typescript hover
+/// provider, `lock` and `root` are the contents of the `Cargo.lock` and the root manifest of the
+/// workspace
+#[wasm_bindgen]
+pub fn dependency_docs(
+    manifest: &str,
+    line: u32,
+    lock: Option<String>,
+    root: Option<String>,
+) -> JsValue {
+    let dependency =
+        manifest::dependency_at(manifest, line as usize).map(|dependency| match &root {
+            Some(root) => manifest::inherited(root, dependency),
+            None => dependency,
+        });
+    // docs.rs only documents crates.io crates
+    let Some(dependency) =
+        dependency.filter(|dependency| dependency.registry.is_none() && !dependency.path_or_git)
+    else {
+        return JsValue::NULL;
+    };
+    let docs = DependencyDocs::new(dependency.name, dependency.req, lock.as_deref());
+    to_value(&docs).unwrap_or_else(|e| {
+        error!("Failed to serialize dependency docs: {e}");
+        JsValue::NULL
+    })
+}
+
+/// The releases of the crates.io crate `name` for the hover of a dependency with the
+/// requirement `req`, null if fetching crates.io metadata is disabled or failed. Shares the
+/// cached information of the outline.
+#[wasm_bindgen]
+pub async fn dependency_releases(name: String, req: Option<String>) -> JsValue {
+    if !crates_io_metadata_enabled() {
+        return JsValue::NULL;
+    }
+    let Some(info) = crate_info(&name).await else {
+        return JsValue::NULL;
+    };
+    to_value(&Releases::new(&info, req.as_deref())).unwrap_or_else(|e| {
+        error!("Failed to serialize the releases of {name}: {e}");
+        JsValue::NULL
+    })
+}
+
+/// Updates the crate `name` locked at `version` in the `Cargo.lock` of the workspace `root`
+pub async fn update_dependency(root: String, name: String, version: Option<String>) {
+    let ctx = metadata_task_context().with_cwd(Some(root));
+    match ctx.try_into_process(update_args(&name, version.as_deref())) {
+        Ok(process) => {
+            execute_task_and_wait(VsCodeTask::cargo(process)).await;
+        }
+        Err(e) => error!("{e}"),
+    }
+}
@@ -0,0 +1,153 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+import { dependency_docs, dependency_releases } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+interface DependencyDocs {
+    name: string;
+    version?: string;
+    url: string;
+    req?: string;
+    locked?: string;
+}
+
+interface Releases {
+    latest: string;
+    latest_compatible?: string;
+    outdated: boolean;
+}
+
+/** The lockfile and root manifest of a workspace */
+interface WorkspaceFiles {
+    lock?: string;
+    root?: string;
+}
+
+/** The workspace files of a manifest by its directory, until a lockfile or manifest changes */
+const workspaces = new Map<string, Promise<WorkspaceFiles>>();
+/** The docs of the dependencies of a manifest by line, until the manifest or a lockfile changes */
+const documentDocs = new Map<string, { version: number; docs: Map<number, DependencyDocs | undefined> }>();
+
+async function read_text(file: string): Promise<string | undefined> {
+    try {
+        return new TextDecoder().decode(await vscode.workspace.fs.readFile(vscode.Uri.file(file)));
+    } catch {
+        return undefined;
+    }
+}
+
+/**
+ * The `Cargo.lock` next to `manifest` or in one of its parent directories and the root manifest
+ * next to it, which declares the `[workspace.dependencies]`
+ */
+async function find_workspace(manifest: string): Promise<WorkspaceFiles> {
+    let dir = path.dirname(manifest);
+    for (;;) {
+        const lock = await read_text(path.join(dir, 'Cargo.lock'));
+        if (lock !== undefined) {
+            return { lock, root: await read_text(path.join(dir, 'Cargo.toml')) };
+        }
+        const parent = path.dirname(dir);
+        if (parent === dir) {
+            return {};
+        }
+        dir = parent;
+    }
+}
+
+function cached_workspace(manifest: string): Promise<WorkspaceFiles> {
+    const dir = path.dirname(manifest);
+    let workspace = workspaces.get(dir);
+    if (!workspace) {
+        workspace = find_workspace(manifest);
+        workspaces.set(dir, workspace);
+    }
+    return workspace;
+}
+
+async function docs_at(document: vscode.TextDocument, line: number): Promise<DependencyDocs | undefined> {
+    const key = document.uri.toString();
+    let cached = documentDocs.get(key);
+    if (cached?.version !== document.version) {
+        cached = { version: document.version, docs: new Map() };
+        documentDocs.set(key, cached);
+    }
+    if (!cached.docs.has(line)) {
+        const { lock, root } = await cached_workspace(document.uri.fsPath);
+        cached.docs.set(line, dependency_docs(document.getText(), line, lock, root) ?? undefined);
+    }
+    return cached.docs.get(line);
+}
+
+function command_link(title: string, command: string, args: unknown[]): string {
+    return `[${title}](command:${command}?${encodeURIComponent(JSON.stringify(args))})`;
+}
+
+/**
+ * Shows the locked version of the dependency under the mouse with its latest compatible and latest
+ * release on crates.io, and links its docs.rs documentation and an update of the lockfile
+ */
+export class DependencyDocsHoverProvider implements vscode.HoverProvider {
+    constructor() {
+        const lockfileWatcher = vscode.workspace.createFileSystemWatcher('**/Cargo.lock');
+        const manifestWatcher = vscode.workspace.createFileSystemWatcher('**/Cargo.toml');
+        const forget = () => {
+            workspaces.clear();
+            documentDocs.clear();
+        };
+        extension_context?.subscriptions.push(
+            vscode.languages.registerHoverProvider({ scheme: 'file', pattern: '**/Cargo.toml' }, this),
+            lockfileWatcher,
+            lockfileWatcher.onDidChange(forget),
+            lockfileWatcher.onDidCreate(forget),
+            lockfileWatcher.onDidDelete(forget),
+            manifestWatcher,
+            manifestWatcher.onDidChange(forget),
+            vscode.workspace.onDidCloseTextDocument((document) => documentDocs.delete(document.uri.toString())),
+        );
+    }
+
+    async provideHover(document: vscode.TextDocument, position: vscode.Position): Promise<vscode.Hover | undefined> {
+        const docs = await docs_at(document, position.line);
+        if (!docs) {
+            return undefined;
+        }
+        const releases: Releases | undefined = (await dependency_releases(docs.name, docs.req)) ?? undefined;
+
+        const lines = [`**${docs.name}** \`${docs.req ?? '*'}\``];
+        if (docs.locked) {
+            lines.push(`Cargo.lock: \`${docs.locked}\``);
+        }
+        if (releases?.latest_compatible) {
+            lines.push(`Latest compatible: \`${releases.latest_compatible}\``);
+        }
+        if (releases) {
+            const outdated = releases.outdated ? ' $(warning) needs a new requirement' : '';
+            lines.push(`Latest: \`${releases.latest}\`${outdated}`);
+        }
+
+        const version = docs.version ? ` ${docs.version}` : '';
+        const actions = [`[$(book) ${docs.name}${version} on docs.rs](${docs.url})`];
+        if (docs.locked && releases?.latest_compatible && releases.latest_compatible !== docs.locked) {
+            actions.push(
+                command_link(`$(arrow-up) Update to ${releases.latest_compatible}`, 'cargo-tools.updateDependency', [
+                    docs.name,
+                    docs.locked,
+                ])
+            );
+        }
+        lines.push(actions.join(' | '));
+
+        const markdown = new vscode.MarkdownString(lines.join('\n\n'), true);
+        markdown.isTrusted = { enabledCommands: ['cargo-tools.updateDependency'] };
+        return new vscode.Hover(markdown);
+    }
+}
+
+export async function active_dependency_docs(): Promise<DependencyDocs | undefined> {
+    const editor = vscode.window.activeTextEditor;
+    if (!editor || path.basename(editor.document.uri.fsPath) !== 'Cargo.toml') {
+        return undefined;
+    }
+    return docs_at(editor.document, editor.selection.active.line);
+}
@@ -0,0 +1,28 @@
+use cargo_tools::cargo::FileDiagnostic;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/diagnostics.ts"
+)]
+extern "C" {
+    pub type CargoDiagnostics;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CargoDiagnostics;
+
+    #[wasm_bindgen(method, js_name = publish)]
+    fn publish_js(this: &CargoDiagnostics, diagnostics: JsValue);
+}
+
+impl CargoDiagnostics {
+    /// Replaces the diagnostics in the Problems panel, their machine applicable suggestions
+    /// are offered as quick fixes
+    pub fn publish(&self, diagnostics: &[FileDiagnostic]) {
+        match to_value(diagnostics) {
+            Ok(diagnostics) => self.publish_js(diagnostics),
+            Err(e) => error!("Failed to serialize diagnostics: {e}"),
+        }
+    }
+}
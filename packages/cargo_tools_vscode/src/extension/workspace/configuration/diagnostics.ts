@@ -0,0 +1,98 @@
+import * as vscode from 'vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+interface SourceRange {
+    start_line: number;
+    start_column: number;
+    end_line: number;
+    end_column: number;
+}
+
+interface SuggestionEdit {
+    file: string;
+    range: SourceRange;
+    replacement: string;
+}
+
+interface QuickFix {
+    title: string;
+    edits: SuggestionEdit[];
+}
+
+interface FileDiagnostic {
+    package: string;
+    file: string;
+    range: SourceRange;
+    severity: 'error' | 'warning' | 'information' | 'hint';
+    message: string;
+    code?: string;
+    fixes: QuickFix[];
+}
+
+const SEVERITIES = {
+    error: vscode.DiagnosticSeverity.Error,
+    warning: vscode.DiagnosticSeverity.Warning,
+    information: vscode.DiagnosticSeverity.Information,
+    hint: vscode.DiagnosticSeverity.Hint,
+};
+
+function toRange(range: SourceRange): vscode.Range {
+    return new vscode.Range(range.start_line, range.start_column, range.end_line, range.end_column);
+}
+
+/**
+ * The diagnostics of the cargo checks the extension runs, rustc's machine applicable
+ * suggestions are offered as quick fixes on them
+ */
+export class CargoDiagnostics implements vscode.CodeActionProvider {
+    static readonly providedCodeActionKinds = [vscode.CodeActionKind.QuickFix];
+
+    private collection = vscode.languages.createDiagnosticCollection('cargo');
+    // The quick fixes of the shown diagnostics by file, code actions only get copies of them
+    private fixes = new Map<string, { diagnostic: vscode.Diagnostic; fixes: QuickFix[] }[]>();
+
+    constructor() {
+        extension_context?.subscriptions.push(
+            this.collection,
+            vscode.languages.registerCodeActionsProvider({ scheme: 'file', language: 'rust' }, this, {
+                providedCodeActionKinds: CargoDiagnostics.providedCodeActionKinds,
+            })
+        );
+    }
+
+    publish(diagnostics: FileDiagnostic[]): void {
+        const byFile = new Map<string, vscode.Diagnostic[]>();
+        this.fixes.clear();
+        for (const data of diagnostics) {
+            const diagnostic = new vscode.Diagnostic(toRange(data.range), data.message, SEVERITIES[data.severity]);
+            diagnostic.source = 'cargo';
+            diagnostic.code = data.code;
+            byFile.set(data.file, [...byFile.get(data.file) ?? [], diagnostic]);
+            if (data.fixes.length > 0) {
+                const key = vscode.Uri.file(data.file).toString();
+                this.fixes.set(key, [...this.fixes.get(key) ?? [], { diagnostic, fixes: data.fixes }]);
+            }
+        }
+        this.collection.clear();
+        this.collection.set([...byFile].map(([file, fileDiagnostics]) => [vscode.Uri.file(file), fileDiagnostics]));
+    }
+
+    provideCodeActions(document: vscode.TextDocument, _range: vscode.Range,
+        context: vscode.CodeActionContext): vscode.CodeAction[] {
+        const shown = this.fixes.get(document.uri.toString()) ?? [];
+        return context.diagnostics.flatMap((diagnostic) => {
+            const match = shown.find((s) => s.diagnostic.range.isEqual(diagnostic.range)
+                && s.diagnostic.message === diagnostic.message);
+            return (match?.fixes ?? []).map((fix) => {
+                const action = new vscode.CodeAction(fix.title, vscode.CodeActionKind.QuickFix);
+                action.diagnostics = [diagnostic];
+                action.isPreferred = match?.fixes.length === 1;
+                action.edit = new vscode.WorkspaceEdit();
+                for (const edit of fix.edits) {
+                    action.edit.replace(vscode.Uri.file(edit.file), toRange(edit.range), edit.replacement);
+                }
+                return action;
+            });
+        });
+    }
+}
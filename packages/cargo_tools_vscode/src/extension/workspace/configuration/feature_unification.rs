@@ -0,0 +1,50 @@
+use cargo_tools::cargo::{
+    feature_unification::{parse_tree, to_markdown},
+    metadata::resolve_args,
+    reverse_dependencies::{dependency_specs, tree_args},
+};
+use tracing::error;
+
+use crate::{
+    environment::{metadata_task_context, path_mappings},
+    quick_pick::SelectInput,
+    runtime::{exec_vs_code, show_markdown},
+};
+
+async fn cargo(args: Vec<String>) -> Option<String> {
+    let process = metadata_task_context()
+        .try_into_process(args)
+        .inspect_err(|e| error!("{e}"))
+        .ok()?;
+    exec_vs_code(process)
+        .await
+        .inspect_err(|e| error!("{e}"))
+        .ok()
+}
+
+/// Shows the features a picked dependency of the workspace of `root_manifest` is built with,
+/// what enables each of them and which of the workspace `members` cause it
+pub async fn explain_features(root_manifest: String, members: Vec<String>) -> Option<()> {
+    let manifest = path_mappings().to_cargo(&root_manifest);
+    let metadata = cargo(resolve_args(&manifest)).await?;
+    let options = dependency_specs(&metadata)
+        .inspect_err(|e| error!("Failed to parse the dependencies: {e}"))
+        .ok()?;
+    let spec = SelectInput {
+        options,
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+    let tree = cargo(tree_args(
+        &manifest,
+        None,
+        std::slice::from_ref(&spec),
+        true,
+    ))
+    .await?;
+    let dependency = spec.split('@').next().unwrap_or(&spec);
+    let reports = parse_tree(&tree, dependency, &members);
+    show_markdown(to_markdown(&spec, &reports)).await;
+    Some(())
+}
@@ -0,0 +1,43 @@
+use cargo_tools::cargo::{
+    licenses::{license_entries, to_markdown},
+    metadata::resolve_args,
+};
+use tracing::error;
+
+use crate::{
+    environment::{license_report_allowed_licenses, metadata_task_context, path_mappings},
+    runtime::{exec_vs_code, show_markdown, show_warning_message},
+};
+
+/// Shows the licenses of all transitive dependencies of the workspace of `root_manifest` and
+/// warns about the ones `cargoTools.licenseReport.allowedLicenses` doesn't cover
+pub async fn license_report(root_manifest: String) {
+    let manifest = path_mappings().to_cargo(&root_manifest);
+    let process = match metadata_task_context().try_into_process(resolve_args(&manifest)) {
+        Ok(process) => process,
+        Err(e) => {
+            error!("{e}");
+            return;
+        }
+    };
+    let entries = match exec_vs_code(process).await {
+        Ok(metadata) => license_entries(&metadata, &license_report_allowed_licenses()),
+        Err(e) => {
+            error!("Failed to resolve the dependencies: {e}");
+            return;
+        }
+    };
+    match entries {
+        Ok(entries) => {
+            show_markdown(to_markdown(&entries)).await;
+            let flagged = entries.iter().filter(|e| !e.allowed).count();
+            if flagged > 0 {
+                let message = format!(
+                    "{flagged} dependencies have licenses which are not in cargoTools.licenseReport.allowedLicenses"
+                );
+                show_warning_message(&message, Vec::new()).await;
+            }
+        }
+        Err(e) => error!("Failed to parse the dependencies: {e}"),
+    }
+}
@@ -0,0 +1,42 @@
+use cargo_tools::cargo::manifest;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/manifest_lens.ts"
+)]
+extern "C" {
+    pub type ManifestLensProvider;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ManifestLensProvider;
+
+    /// Shows the `selected` features as enabled in the `[features]` table of `manifest`, the
+    /// manifest of the selected package
+    #[wasm_bindgen(method)]
+    pub fn set_feature_selection(
+        this: &ManifestLensProvider,
+        manifest: Option<String>,
+        selected: Vec<String>,
+    );
+}
+
+/// The `[package]`, `[[bin]]` and `[[example]]` sections of a manifest for the typescript
+/// CodeLens provider
+#[wasm_bindgen]
+pub fn manifest_target_sections(manifest: &str) -> JsValue {
+    to_value(&manifest::target_sections(manifest)).unwrap_or_else(|e| {
+        error!("Failed to serialize manifest sections: {e}");
+        JsValue::NULL
+    })
+}
+
+/// The features of the `[features]` table of a manifest for the typescript CodeLens provider
+#[wasm_bindgen]
+pub fn manifest_feature_entries(manifest: &str) -> JsValue {
+    to_value(&manifest::feature_entries(manifest)).unwrap_or_else(|e| {
+        error!("Failed to serialize manifest features: {e}");
+        JsValue::NULL
+    })
+}
@@ -0,0 +1,93 @@
+import * as vscode from 'vscode';
+import { manifest_feature_entries, manifest_target_sections } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+// A run target as serde serializes it, e.g. `{ Bin: "cli" }`
+type RunSubTarget = { Bin: string } | { Example: string };
+
+interface ManifestSection {
+    line: number;
+    target: RunSubTarget | null;
+}
+
+interface ManifestFeature {
+    line: number;
+    name: string;
+}
+
+/**
+ * Shows Build, Test, Run and Select above the `[package]` section of a Cargo.toml and Build,
+ * Run, Debug and Select above each of its `[[bin]]` and `[[example]]` sections. The features of
+ * the manifest of the selected package get a checkbox toggling them in the feature selection.
+ */
+export class ManifestLensProvider implements vscode.CodeLensProvider {
+    private changed = new vscode.EventEmitter<void>();
+    readonly onDidChangeCodeLenses = this.changed.event;
+    private featureManifest: string | undefined;
+    private selectedFeatures: string[] = [];
+
+    constructor() {
+        extension_context?.subscriptions.push(
+            this.changed,
+            vscode.languages.registerCodeLensProvider({ scheme: 'file', pattern: '**/Cargo.toml' }, this),
+            vscode.workspace.onDidChangeConfiguration((e) => {
+                if (e.affectsConfiguration('cargoTools.manifestCodeLens.enabled')) {
+                    this.changed.fire();
+                }
+            })
+        );
+    }
+
+    provideCodeLenses(document: vscode.TextDocument): vscode.CodeLens[] {
+        const enabled = vscode.workspace.getConfiguration('cargoTools').get<boolean>('manifestCodeLens.enabled', true);
+        if (!enabled) {
+            return [];
+        }
+
+        const sections: ManifestSection[] = manifest_target_sections(document.getText()) ?? [];
+        const sectionLenses = sections.flatMap((section) => {
+            const range = new vscode.Range(section.line, 0, section.line, 0);
+            if (!section.target) {
+                const args = [document.uri.fsPath];
+                return [
+                    new vscode.CodeLens(range, { title: '$(tools) Build', command: 'cargo-tools.manifestLens.build', arguments: args }),
+                    new vscode.CodeLens(range, { title: '$(beaker) Test', command: 'cargo-tools.manifestLens.test', arguments: args }),
+                    new vscode.CodeLens(range, { title: '$(play) Run', command: 'cargo-tools.manifestLens.run', arguments: args }),
+                    new vscode.CodeLens(range, { title: '$(check) Select', command: 'cargo-tools.manifestLens.select', arguments: args }),
+                ];
+            }
+            const args = [document.uri.fsPath, section.target];
+            return [
+                new vscode.CodeLens(range, { title: '$(tools) Build', command: 'cargo-tools.manifestLens.build', arguments: args }),
+                new vscode.CodeLens(range, { title: '$(play) Run', command: 'cargo-tools.manifestLens.run', arguments: args }),
+                new vscode.CodeLens(range, { title: '$(debug-alt) Debug', command: 'cargo-tools.manifestLens.debug', arguments: args }),
+                new vscode.CodeLens(range, { title: '$(check) Select', command: 'cargo-tools.manifestLens.select', arguments: args }),
+            ];
+        });
+
+        return [...sectionLenses, ...this.featureLenses(document)];
+    }
+
+    set_feature_selection(manifest: string | undefined, selected: string[]): void {
+        this.featureManifest = manifest;
+        this.selectedFeatures = selected;
+        this.changed.fire();
+    }
+
+    private featureLenses(document: vscode.TextDocument): vscode.CodeLens[] {
+        if (document.uri.fsPath !== this.featureManifest) {
+            return [];
+        }
+        const features: ManifestFeature[] = manifest_feature_entries(document.getText()) ?? [];
+        return features.map((feature) => {
+            const range = new vscode.Range(feature.line, 0, feature.line, 0);
+            const enabled = this.selectedFeatures.includes(feature.name);
+            return new vscode.CodeLens(range, {
+                title: `${enabled ? '$(pass-filled)' : '$(circle-large-outline)'} ${feature.name}`,
+                tooltip: `${enabled ? 'Disable' : 'Enable'} the feature ${feature.name}`,
+                command: 'cargo-tools.manifestLens.toggleFeature',
+                arguments: [document.uri.fsPath, feature.name],
+            });
+        });
+    }
+}
@@ -0,0 +1,43 @@
+use tracing::error;
+
+use crate::runtime::read_file_vs_code;
+
+/// `path` with `/` separators, the paths of cargo metadata and of the root manifest may differ
+/// in their separators on Windows
+pub fn normalize(path: &str) -> String {
+    path.replace('\\', "/")
+}
+
+/// The content of the manifest `path`, logs an error if it can't be read
+pub async fn read_manifest(path: &str) -> Option<String> {
+    read_file_vs_code(path.to_string())
+        .await
+        .inspect_err(|e| error!("Failed to read {path}: {e}"))
+        .ok()
+}
+
+/// The paths and contents of the manifests of the workspace, the root manifest first and
+/// shared with a root package, and the index of each of the `manifests` among them
+pub async fn read_manifests(
+    root_manifest: &str,
+    manifests: &[String],
+) -> Option<(Vec<(String, String)>, Vec<usize>)> {
+    let mut files = vec![(
+        root_manifest.to_string(),
+        read_manifest(root_manifest).await?,
+    )];
+    let mut indices = Vec::new();
+    for manifest in manifests {
+        match files
+            .iter()
+            .position(|(p, _)| normalize(p) == normalize(manifest))
+        {
+            Some(i) => indices.push(i),
+            None => {
+                files.push((manifest.clone(), read_manifest(manifest).await?));
+                indices.push(files.len() - 1);
+            }
+        }
+    }
+    Some((files, indices))
+}
@@ -1,4 +1,30 @@
+pub mod benchmarks;
+pub mod binary_size;
+pub mod build_results;
+pub mod cbindgen;
 pub mod command;
+pub mod dependency_docs;
+pub mod diagnostics;
+pub mod feature_unification;
+pub mod licenses;
+pub mod manifest_lens;
+mod manifests;
+pub mod msrv;
+pub mod new_target;
+pub mod normalize_manifest;
+pub mod output_links;
+pub mod pre_commit;
+pub mod problem_count;
+pub mod registries;
+pub mod rename_package;
+pub mod slowest_tests;
 mod ui;
 pub use ui::{Configuration, Event, Message};
+pub mod task_provider;
+pub mod test_explorer;
+pub mod test_lens;
+pub mod toolchain;
 pub mod treeprovider;
+pub mod version_bump;
+pub mod wasm;
+pub mod workspace_dependencies;
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+
+use cargo_tools::cargo::msrv::{
+    CARGO_MSRV, check_args, failed, is_valid, rust_version, set_package_rust_version,
+    set_workspace_rust_version, unsupported_dependencies, verify_args,
+};
+use tracing::{error, info};
+
+use crate::{
+    environment::{metadata_task_context, rustup_task_context},
+    extension::workspace::configuration::manifests::read_manifests,
+    quick_pick::{SelectInput, show_input_box_with_value},
+    runtime::{
+        VsCodeTask, exec_output_vs_code, execute_task_and_wait, show_warning_message,
+        write_file_vs_code,
+    },
+};
+
+const PINNED_TOOLCHAIN: &str = "Build with the pinned toolchain";
+const WITH_CARGO_MSRV: &str = "Verify with cargo-msrv";
+
+/// Sets the entered `rust-version` for all `packages` given by name and manifest. With a
+/// `[workspace.package]` table in the root manifest it is set there and the members inherit
+/// it, otherwise it is set in the manifest of each member.
+pub async fn set_rust_version(root: String, packages: Vec<(String, String)>) -> Option<()> {
+    let root_manifest = format!("{root}/Cargo.toml");
+    let manifests: Vec<String> = packages.iter().map(|(_, m)| m.clone()).collect();
+    let (mut files, indices) = read_manifests(&root_manifest, &manifests).await?;
+    let current = indices
+        .first()
+        .and_then(|i| rust_version(&files[*i].1, &files[0].1))
+        .unwrap_or_default();
+    let version = show_input_box_with_value(
+        "The minimum supported Rust version, e.g. 1.74".to_string(),
+        "Set MSRV of the workspace".to_string(),
+        current,
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let version = version.trim();
+    if !is_valid(version) {
+        error!("{version} is no valid rust-version, expected e.g. 1.74 or 1.74.1");
+        return None;
+    }
+
+    let original: Vec<String> = files.iter().map(|(_, content)| content.clone()).collect();
+    let inherited = set_workspace_rust_version(&files[0].1, version);
+    let inherits = inherited.is_some();
+    if let Some(edited) = inherited {
+        files[0].1 = edited;
+    }
+    for i in indices {
+        let version = (!inherits).then_some(version);
+        match set_package_rust_version(&files[i].1, version) {
+            Some(edited) => files[i].1 = edited,
+            None => error!("{} has no [package] table", files[i].0),
+        }
+    }
+    for ((path, content), original) in files.into_iter().zip(original) {
+        if content == original {
+            continue;
+        }
+        if let Err(e) = write_file_vs_code(path.clone(), content).await {
+            error!("Failed to update {path}: {e}");
+        }
+    }
+    info!("Set the rust-version of the workspace to {version}");
+    Some(())
+}
+
+/// Verifies the `rust-version` of the `packages` given by name and manifest which declare one,
+/// by checking them with the toolchain of their MSRV or with cargo-msrv. Packages which fail
+/// and dependencies which need a newer rustc are reported.
+pub async fn verify_rust_version(root: String, packages: Vec<(String, String)>) -> Option<()> {
+    let root_manifest = format!("{root}/Cargo.toml");
+    let manifests: Vec<String> = packages.iter().map(|(_, m)| m.clone()).collect();
+    let (files, indices) = read_manifests(&root_manifest, &manifests).await?;
+    let mut declared: BTreeMap<String, Vec<(String, String)>> = BTreeMap::new();
+    for ((name, manifest), i) in packages.into_iter().zip(indices) {
+        if let Some(version) = rust_version(&files[i].1, &files[0].1) {
+            declared.entry(version).or_default().push((name, manifest));
+        }
+    }
+    if declared.is_empty() {
+        info!("No member declares a rust-version to verify");
+        return None;
+    }
+    let method = SelectInput {
+        options: [PINNED_TOOLCHAIN, WITH_CARGO_MSRV]
+            .map(str::to_string)
+            .to_vec(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+
+    let mut failures = Vec::new();
+    for (version, packages) in declared {
+        if method == WITH_CARGO_MSRV {
+            for (name, manifest) in packages {
+                let dir = manifest
+                    .replace('\\', "/")
+                    .trim_end_matches("/Cargo.toml")
+                    .to_string();
+                let process = metadata_task_context()
+                    .with_cwd(Some(root.clone()))
+                    .try_into_process(verify_args(&dir));
+                match process {
+                    Ok(process) => {
+                        if !execute_task_and_wait(VsCodeTask::cargo(process)).await {
+                            failures.push(format!(
+                                "{name} failed the {CARGO_MSRV} verification of Rust {version}"
+                            ));
+                        }
+                    }
+                    Err(e) => error!("{e}"),
+                }
+            }
+            continue;
+        }
+
+        let install = ["toolchain", "install", &version, "--profile", "minimal"]
+            .map(str::to_string)
+            .to_vec();
+        match rustup_task_context().try_into_process(install) {
+            Ok(process) => {
+                if !execute_task_and_wait(VsCodeTask::rustup(process)).await {
+                    error!("Failed to install Rust {version}");
+                    continue;
+                }
+            }
+            Err(e) => {
+                error!("{e}");
+                continue;
+            }
+        }
+        for (name, _) in packages {
+            let process = metadata_task_context()
+                .with_cwd(Some(root.clone()))
+                .try_into_process(check_args(&version, &name));
+            let output = match process {
+                Ok(process) => exec_output_vs_code(process).await,
+                Err(e) => Err(e.to_string()),
+            };
+            let output = output.unwrap_or_else(|e| e);
+            let unsupported = unsupported_dependencies(&output);
+            if !unsupported.is_empty() {
+                let dependencies: Vec<String> = unsupported
+                    .iter()
+                    .map(|d| format!("{} (rustc {})", d.package, d.required))
+                    .collect();
+                failures.push(format!(
+                    "{name} depends on {} which exceed its MSRV {version}",
+                    dependencies.join(", ")
+                ));
+            } else if failed(&output) {
+                failures.push(format!("{name} doesn't build with Rust {version}"));
+                error!("cargo +{version} check --package {name} failed:\n{output}");
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        info!("All members build with their declared rust-version");
+    } else {
+        for failure in &failures {
+            error!("{failure}");
+        }
+        show_warning_message(&failures.join("; "), Vec::new()).await;
+    }
+    Some(())
+}
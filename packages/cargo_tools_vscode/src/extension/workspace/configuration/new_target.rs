@@ -0,0 +1,84 @@
+use cargo_tools::cargo::{
+    new_package::is_valid_name,
+    new_target::{
+        BenchHarness, CRITERION_REQ, TargetKind, source, with_bench_target, with_dev_dependency,
+    },
+};
+use tracing::{error, info};
+
+use crate::{
+    quick_pick::{SelectInput, show_input_box},
+    runtime::{file_exists_vs_code, read_file_vs_code, write_file_vs_code},
+};
+
+/// Creates a target of `kind` with an entered name in the package of `manifest` from a
+/// template. Criterion benchmarks are declared with `harness = false` and criterion is added to
+/// the dev-dependencies, inherited if the workspace at `root` declares it. Returns the created
+/// file.
+pub async fn new_target(
+    root: String,
+    package: String,
+    manifest: String,
+    kind: TargetKind,
+) -> Option<String> {
+    let name = show_input_box(
+        format!("Name of the {}, e.g. parse", kind.label().to_lowercase()),
+        format!("New {} in {package}", kind.label()),
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let name = name.trim().to_string();
+    if !is_valid_name(&name) {
+        error!("'{name}' is no valid target name");
+        return None;
+    }
+    let harness = match kind {
+        TargetKind::Bench => {
+            SelectInput {
+                options: BenchHarness::all().to_vec(),
+                current: Vec::new(),
+            }
+            .select()
+            .await?
+        }
+        _ => BenchHarness::Libtest,
+    };
+
+    let dir = manifest
+        .replace('\\', "/")
+        .trim_end_matches("/Cargo.toml")
+        .to_string();
+    let path = format!("{dir}/{}", kind.file(&name));
+    if file_exists_vs_code(path.clone()).await {
+        error!("{path} already exists");
+        return None;
+    }
+    // The manifest declares the benchmark only once its source exists
+    if let Err(e) = write_file_vs_code(path.clone(), source(kind, &name, harness)).await {
+        error!("Failed to create {path}: {e}");
+        return None;
+    }
+    if kind == TargetKind::Bench && harness == BenchHarness::Criterion {
+        let content = read_file_vs_code(manifest.clone())
+            .await
+            .inspect_err(|e| error!("Failed to read {manifest}: {e}"))
+            .ok()?;
+        let root_manifest = format!("{root}/Cargo.toml");
+        let root_content = read_file_vs_code(root_manifest).await.unwrap_or_default();
+        let edited = with_dev_dependency(
+            &with_bench_target(&content, &name),
+            &root_content,
+            "criterion",
+            CRITERION_REQ,
+        );
+        if edited != content
+            && let Err(e) = write_file_vs_code(manifest.clone(), edited).await
+        {
+            error!("Failed to update {manifest}: {e}");
+            return None;
+        }
+    }
+    info!("Created {path}");
+    Some(path)
+}
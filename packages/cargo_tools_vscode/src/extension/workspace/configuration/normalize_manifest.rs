@@ -0,0 +1,65 @@
+use cargo_tools::cargo::normalize_manifest::normalize;
+use tracing::{error, info};
+
+use crate::{
+    environment::normalize_manifest_dependency_style,
+    quick_pick::SelectInput,
+    runtime::{read_file_vs_code, write_file_vs_code},
+};
+
+const ALL_MANIFESTS: &str = "All manifests of the workspace";
+
+/// Sorts the dependency tables of the manifest of a picked package, or of the root manifest
+/// and all `packages` given by name and manifest, in the style of
+/// `cargoTools.normalizeManifest.dependencyStyle`
+pub async fn normalize_manifests(
+    root: String,
+    packages: Vec<(String, String)>,
+    selected: Option<String>,
+) -> Option<()> {
+    let mut options = vec![ALL_MANIFESTS.to_string()];
+    options.extend(packages.iter().map(|(name, _)| name.clone()));
+    let current = selected.into_iter().collect();
+    let scope = SelectInput { options, current }.select().await?;
+
+    let candidates: Vec<String> = match scope.as_str() {
+        ALL_MANIFESTS => std::iter::once(format!("{root}/Cargo.toml"))
+            .chain(packages.into_iter().map(|(_, manifest)| manifest))
+            .collect(),
+        scope => packages
+            .into_iter()
+            .filter(|(name, _)| name == scope)
+            .map(|(_, manifest)| manifest)
+            .collect(),
+    };
+    // The root manifest is the manifest of the root package too
+    let mut manifests: Vec<String> = Vec::new();
+    for manifest in candidates {
+        let normalized = manifest.replace('\\', "/");
+        if !manifests.iter().any(|m| m.replace('\\', "/") == normalized) {
+            manifests.push(manifest);
+        }
+    }
+
+    let style = normalize_manifest_dependency_style();
+    let mut normalized = 0;
+    for manifest in manifests {
+        let content = match read_file_vs_code(manifest.clone()).await {
+            Ok(content) => content,
+            Err(e) => {
+                error!("Failed to read {manifest}: {e}");
+                continue;
+            }
+        };
+        let edited = normalize(&content, style);
+        if edited == content {
+            continue;
+        }
+        match write_file_vs_code(manifest.clone(), edited).await {
+            Ok(()) => normalized += 1,
+            Err(e) => error!("Failed to update {manifest}: {e}"),
+        }
+    }
+    info!("Normalized {normalized} manifests");
+    Some(())
+}
@@ -0,0 +1,53 @@
+use cargo_tools::backtrace::{self, OutputLink};
+use serde::Serialize;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::environment::path_mappings;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/output_links.ts"
+)]
+extern "C" {
+    /// Turns the source locations of panics and backtraces in the terminals into links
+    pub type OutputLinkProvider;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> OutputLinkProvider;
+
+    /// The directories relative source paths of the output are resolved against
+    #[wasm_bindgen(method)]
+    pub fn set_roots(this: &OutputLinkProvider, roots: Vec<String>);
+}
+
+#[derive(Serialize)]
+struct LinkItem {
+    #[serde(flatten)]
+    link: OutputLink,
+    /// The host files the link may refer to, the first existing one is opened
+    candidates: Vec<String>,
+}
+
+/// The links of a line of terminal output for the typescript terminal link provider
+#[wasm_bindgen]
+pub fn find_output_links(line: &str, roots: Vec<String>) -> JsValue {
+    let mappings = path_mappings();
+    let items: Vec<_> = backtrace::find_links(line)
+        .into_iter()
+        .map(|link| {
+            let candidates = match &link {
+                OutputLink::Source { path, .. } => backtrace::candidate_files(path, &roots)
+                    .iter()
+                    .map(|file| mappings.to_host(file))
+                    .collect(),
+                OutputLink::RerunWithBacktrace { .. } => Vec::new(),
+            };
+            LinkItem { link, candidates }
+        })
+        .collect();
+    to_value(&items).unwrap_or_else(|e| {
+        error!("Failed to serialize output links: {e}");
+        JsValue::NULL
+    })
+}
@@ -0,0 +1,67 @@
+import * as vscode from 'vscode';
+import { find_output_links } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+import { rerun_in_terminal } from '../../../runtime';
+
+type OutputLinkItem = {
+    start: number;
+    length: number;
+    candidates: string[];
+} & ({ kind: 'source', path: string, line: number, column?: number } | { kind: 'rerun_with_backtrace' });
+
+interface OutputLink extends vscode.TerminalLink {
+    item: OutputLinkItem;
+    terminal: vscode.Terminal;
+}
+
+async function exists(file: string): Promise<boolean> {
+    return vscode.workspace.fs.stat(vscode.Uri.file(file)).then(() => true, () => false);
+}
+
+/**
+ * Turns the `src/lib.rs:42:7` locations of panics and backtraces in the terminals into links,
+ * including the frames of dependencies, and the backtrace note of a panic into a link which
+ * runs the command again with `RUST_BACKTRACE=full`.
+ */
+export class OutputLinkProvider implements vscode.TerminalLinkProvider<OutputLink> {
+    private roots: string[] = [];
+
+    constructor() {
+        extension_context?.subscriptions.push(vscode.window.registerTerminalLinkProvider(this));
+    }
+
+    set_roots(roots: string[]): void {
+        this.roots = roots;
+    }
+
+    provideTerminalLinks(context: vscode.TerminalLinkContext): OutputLink[] {
+        const items: OutputLinkItem[] = find_output_links(context.line, this.roots) ?? [];
+        return items.map((item) => ({
+            startIndex: item.start,
+            length: item.length,
+            tooltip: item.kind === 'source' ? 'Open file' : 'Re-run with RUST_BACKTRACE=full',
+            item,
+            terminal: context.terminal,
+        }));
+    }
+
+    async handleTerminalLink(link: OutputLink): Promise<void> {
+        const item = link.item;
+        if (item.kind === 'rerun_with_backtrace') {
+            if (!await rerun_in_terminal(link.terminal, { RUST_BACKTRACE: 'full' })) {
+                vscode.window.showWarningMessage('The command of this terminal is unknown, run it again from Cargo Tools');
+            }
+            return;
+        }
+        for (const file of item.candidates) {
+            if (await exists(file)) {
+                const position = new vscode.Position(item.line - 1, (item.column ?? 1) - 1);
+                await vscode.window.showTextDocument(vscode.Uri.file(file), {
+                    selection: new vscode.Range(position, position),
+                });
+                return;
+            }
+        }
+        vscode.window.showWarningMessage(`${item.path} was not found`);
+    }
+}
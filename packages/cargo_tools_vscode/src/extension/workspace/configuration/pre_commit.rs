@@ -0,0 +1,157 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use cargo_tools::{
+    cargo::{
+        Config,
+        pre_commit::{Check, CheckStatus, HOOK_MARKER, hook_script, summary},
+    },
+    process::Process,
+};
+use serde::Serialize;
+use serde_wasm_bindgen::to_value;
+use tracing::{error, info};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    environment::pre_commit_task_context,
+    runtime::{
+        VsCodeTask, exec_vs_code, execute_task_and_wait, host_platform, read_file_vs_code,
+        write_file_vs_code,
+    },
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/pre_commit.ts"
+)]
+extern "C" {
+    /// Shows the checks of the last pre-commit run
+    #[derive(Clone)]
+    pub type PreCommitChecksView;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> PreCommitChecksView;
+
+    #[wasm_bindgen(method)]
+    fn show_results(this: &PreCommitChecksView, results: JsValue);
+
+    async fn confirm_replace_hook(path: String) -> JsValue;
+    fn show_pre_commit_summary(summary: String, success: bool);
+}
+
+#[derive(Serialize)]
+struct CheckResult {
+    title: &'static str,
+    status: CheckStatus,
+}
+
+#[derive(Serialize)]
+struct PreCommitResults<'a> {
+    packages: &'a [String],
+    checks: Vec<CheckResult>,
+}
+
+impl PreCommitChecksView {
+    fn show(&self, packages: &[String], results: &[(Check, CheckStatus)]) {
+        let results = PreCommitResults {
+            packages,
+            checks: results
+                .iter()
+                .map(|(check, status)| CheckResult {
+                    title: check.title(),
+                    status: *status,
+                })
+                .collect(),
+        };
+        match to_value(&results) {
+            Ok(results) => self.show_results(results),
+            Err(e) => error!("Failed to show the pre-commit checks: {e}"),
+        }
+    }
+}
+
+/// Runs `checks` one after the other for the changed `packages` with the selection of
+/// `config` and shows their progress in `view`. All checks run even if one fails so the view
+/// lists every problem.
+pub async fn run_pre_commit_checks(
+    view: PreCommitChecksView,
+    checks: Vec<Check>,
+    packages: Vec<String>,
+    config: Config,
+) {
+    let mut results: Vec<_> = checks
+        .iter()
+        .map(|check| (*check, CheckStatus::Queued))
+        .collect();
+    for i in 0..results.len() {
+        let check = results[i].0;
+        results[i].1 = CheckStatus::Running;
+        view.show(&packages, &results);
+        let passed =
+            match pre_commit_task_context(check).try_into_process(check.args(&packages, &config)) {
+                Ok(process) => execute_task_and_wait(VsCodeTask::cargo(process)).await,
+                Err(e) => {
+                    error!("{e}");
+                    false
+                }
+            };
+        results[i].1 = if passed {
+            CheckStatus::Passed
+        } else {
+            CheckStatus::Failed
+        };
+    }
+    view.show(&packages, &results);
+
+    let success = results
+        .iter()
+        .all(|(_, status)| *status == CheckStatus::Passed);
+    show_pre_commit_summary(summary(&results), success);
+}
+
+/// Installs a git pre-commit hook in the repository of the workspace at `root` which runs
+/// `checks` with `cargo`. Hooks not written by Cargo Tools are only replaced once confirmed.
+pub async fn install_pre_commit_hook(root: String, checks: Vec<Check>, cargo: String) {
+    let git_path = Process::new(
+        "git".to_string(),
+        ["rev-parse", "--git-path", "hooks/pre-commit"]
+            .map(str::to_string)
+            .to_vec(),
+        HashMap::new(),
+    )
+    .with_cwd(Some(root.clone()));
+    let hook = match exec_vs_code(git_path).await {
+        // Relative to the workspace root unless hooks are configured elsewhere
+        Ok(output) => PathBuf::from(&root)
+            .join(output.trim())
+            .to_string_lossy()
+            .to_string(),
+        Err(e) => {
+            error!("Failed to find the git hooks of {root}, is it a git repository? {e}");
+            return;
+        }
+    };
+
+    if let Ok(existing) = read_file_vs_code(hook.clone()).await
+        && !existing.contains(HOOK_MARKER)
+        && confirm_replace_hook(hook.clone()).await.as_bool() != Some(true)
+    {
+        return;
+    }
+
+    if let Err(e) = write_file_vs_code(hook.clone(), hook_script(&checks, &root, &cargo)).await {
+        error!("Failed to write the pre-commit hook {hook}: {e}");
+        return;
+    }
+    if host_platform() != "win32" {
+        let chmod = Process::new(
+            "chmod".to_string(),
+            vec!["+x".to_string(), hook.clone()],
+            HashMap::new(),
+        );
+        if let Err(e) = exec_vs_code(chmod).await {
+            error!("Failed to make the pre-commit hook {hook} executable: {e}");
+            return;
+        }
+    }
+    info!("Installed the pre-commit hook {hook}, failing checks reject commits");
+}
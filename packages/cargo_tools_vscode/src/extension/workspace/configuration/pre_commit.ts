@@ -0,0 +1,76 @@
+import * as vscode from 'vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+type CheckStatus = 'queued' | 'running' | 'passed' | 'failed';
+
+interface CheckResult {
+    title: string;
+    status: CheckStatus;
+}
+
+interface PreCommitResults {
+    packages: string[];
+    checks: CheckResult[];
+}
+
+function icon(status: CheckStatus): vscode.ThemeIcon {
+    switch (status) {
+        case 'queued':
+            return new vscode.ThemeIcon('circle-outline');
+        case 'running':
+            return new vscode.ThemeIcon('loading~spin');
+        case 'passed':
+            return new vscode.ThemeIcon('pass', new vscode.ThemeColor('testing.iconPassed'));
+        case 'failed':
+            return new vscode.ThemeIcon('error', new vscode.ThemeColor('testing.iconFailed'));
+    }
+}
+
+/**
+ * Shows the checks of the last "Run Pre-commit Checks", the view appears after the first run.
+ */
+export class PreCommitChecksView implements vscode.TreeDataProvider<CheckResult> {
+    private _onDidChangeTreeData = new vscode.EventEmitter<CheckResult | undefined | null | void>();
+    readonly onDidChangeTreeData = this._onDidChangeTreeData.event;
+
+    private view: vscode.TreeView<CheckResult>;
+    private results: PreCommitResults = { packages: [], checks: [] };
+
+    constructor() {
+        this.view = vscode.window.createTreeView('cargoToolsPreCommit', { treeDataProvider: this });
+        extension_context?.subscriptions.push(this.view);
+    }
+
+    getTreeItem(check: CheckResult): vscode.TreeItem {
+        const item = new vscode.TreeItem(check.title);
+        item.description = check.status;
+        item.iconPath = icon(check.status);
+        item.contextValue = 'preCommitCheck';
+        return item;
+    }
+
+    getChildren(check?: CheckResult): CheckResult[] {
+        return check ? [] : this.results.checks;
+    }
+
+    show_results(results: PreCommitResults): void {
+        this.results = results;
+        this.view.description = results.packages.length > 0 ? results.packages.join(', ') : 'workspace';
+        vscode.commands.executeCommand('setContext', 'cargoTools:preCommitChecksRan', true);
+        this._onDidChangeTreeData.fire();
+    }
+}
+
+export async function confirm_replace_hook(path: string): Promise<boolean> {
+    const choice = await vscode.window.showWarningMessage(
+        `${path} was not installed by Cargo Tools, replace it?`, { modal: true }, 'Replace');
+    return choice === 'Replace';
+}
+
+export function show_pre_commit_summary(summary: string, success: boolean): void {
+    if (success) {
+        vscode.window.showInformationMessage(summary);
+    } else {
+        vscode.window.showWarningMessage(summary);
+    }
+}
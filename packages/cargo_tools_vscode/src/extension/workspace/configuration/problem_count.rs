@@ -0,0 +1,17 @@
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/problem_count.ts"
+)]
+extern "C" {
+    /// Counts the errors and warnings of checks and builds in the status bar
+    pub type ProblemCountStatus;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ProblemCountStatus;
+
+    /// Counts the problems of the files below `dir`, which belong to the package or workspace
+    /// `label`, except for the files of the members nested in it below `excluded`
+    #[wasm_bindgen(method)]
+    pub fn set_scope(this: &ProblemCountStatus, label: String, dir: String, excluded: Vec<String>);
+}
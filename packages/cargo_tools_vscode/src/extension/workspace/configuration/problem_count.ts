@@ -0,0 +1,72 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+/**
+ * The sources of the diagnostics of check on save and of the `$rustc` problem matcher of tasks,
+ * rust-analyzer reports the diagnostics of its own check as `rustc` too
+ */
+const SOURCES = new Set(['cargo', 'rustc']);
+
+function isBelow(dir: string, file: string): boolean {
+    const relative = path.relative(dir, file);
+    return !relative.startsWith('..') && !path.isAbsolute(relative);
+}
+
+/**
+ * Shows the errors and warnings of the selected package in the status bar. Clicking it shows the
+ * messages of the package in the Build Results view.
+ */
+export class ProblemCountStatus {
+    private item = vscode.window.createStatusBarItem(vscode.StatusBarAlignment.Left);
+    private label = '';
+    private dir?: string;
+    private excluded: string[] = [];
+
+    constructor() {
+        this.item.command = 'cargo-tools.showPackageProblems';
+        extension_context?.subscriptions.push(
+            this.item,
+            vscode.languages.onDidChangeDiagnostics(() => this.update()),
+        );
+    }
+
+    set_scope(label: string, dir: string, excluded: string[]): void {
+        this.label = label;
+        this.dir = dir;
+        this.excluded = excluded;
+        this.update();
+    }
+
+    private update(): void {
+        const dir = this.dir;
+        if (dir === undefined) {
+            return;
+        }
+        const seen = new Set<string>();
+        let errors = 0;
+        let warnings = 0;
+        for (const [uri, diagnostics] of vscode.languages.getDiagnostics()) {
+            if (uri.scheme !== 'file' || !isBelow(dir, uri.fsPath)
+                || this.excluded.some((nested) => isBelow(nested, uri.fsPath))) {
+                continue;
+            }
+            for (const diagnostic of diagnostics) {
+                // A check and a build report the same problem once each
+                const key = `${uri.toString()}:${diagnostic.range.start.line}:${diagnostic.range.start.character}:${diagnostic.message}`;
+                if (!SOURCES.has(diagnostic.source ?? '') || seen.has(key)) {
+                    continue;
+                }
+                seen.add(key);
+                if (diagnostic.severity === vscode.DiagnosticSeverity.Error) {
+                    errors += 1;
+                } else if (diagnostic.severity === vscode.DiagnosticSeverity.Warning) {
+                    warnings += 1;
+                }
+            }
+        }
+        this.item.text = `$(error) ${errors} $(warning) ${warnings}`;
+        this.item.tooltip = `Errors and warnings rustc reported for ${this.label} in checks, builds and the checks of rust-analyzer. Click to show the messages of the last check or build in the Build Results view.`;
+        this.item.show();
+    }
+}
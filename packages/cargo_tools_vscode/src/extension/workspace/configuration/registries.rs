@@ -0,0 +1,138 @@
+use cargo_tools::{
+    cargo::{
+        Registries,
+        registries::{add_args, publish_args, token_env_var},
+    },
+    process::CargoTaskContext,
+};
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    environment::metadata_task_context,
+    quick_pick::{SelectInput, show_input_box},
+    runtime::{VsCodeTask, execute_task_and_wait},
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/registries.ts"
+)]
+extern "C" {
+    async fn get_registry_token(registry: String) -> JsValue;
+    async fn input_registry_token(registry: String) -> JsValue;
+    async fn store_registry_token(registry: String, token: String);
+}
+
+const DRY_RUN: &str = "Dry run";
+const PUBLISH: &str = "Publish";
+
+/// The package of an operation, the selected one or the only member if there is one
+async fn pick_package(packages: Vec<String>, selected: Option<String>) -> Option<String> {
+    if let Some(selected) = selected {
+        return Some(selected);
+    }
+    if let [package] = packages.as_slice() {
+        return Some(package.clone());
+    }
+    SelectInput {
+        options: packages,
+        current: Vec::new(),
+    }
+    .select()
+    .await
+}
+
+/// The registry of an operation, picked if `[registries]` configures alternative ones
+async fn pick_registry(registries: &Registries) -> Option<String> {
+    let options = registries.options();
+    if let [registry] = options.as_slice() {
+        return Some(registry.clone());
+    }
+    let current = options.first().cloned().into_iter().collect();
+    SelectInput { options, current }.select().await
+}
+
+/// The context of cargo commands accessing `registry` in the workspace `root`, with the token
+/// stored for it. Without one cargo uses its own credential providers.
+async fn registry_context(root: String, registry: &str) -> CargoTaskContext {
+    let ctx = metadata_task_context().with_cwd(Some(root));
+    match get_registry_token(registry.to_string()).await.as_string() {
+        Some(token) => ctx.with_env(token_env_var(registry), token),
+        None => ctx,
+    }
+}
+
+async fn execute_cargo(ctx: CargoTaskContext, args: Vec<String>) {
+    match ctx.try_into_process(args) {
+        Ok(process) => {
+            execute_task_and_wait(VsCodeTask::cargo(process)).await;
+        }
+        Err(e) => error!("{e}"),
+    }
+}
+
+/// Adds a crate with `cargo add` from a picked registry to one of `packages`
+pub async fn add_dependency(
+    root: String,
+    packages: Vec<String>,
+    selected: Option<String>,
+    registries: Registries,
+) -> Option<()> {
+    let package = pick_package(packages, selected).await?;
+    let spec = show_input_box(
+        "Crate to add, e.g. serde@1 or serde --features derive".to_string(),
+        format!("Add Dependency to {package}"),
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    if spec.trim().is_empty() {
+        return None;
+    }
+    let registry = pick_registry(&registries).await?;
+    let kind = SelectInput {
+        options: ["normal", "dev", "build"].map(str::to_string).to_vec(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+    let kind = (kind != "normal").then_some(kind);
+
+    let args = add_args(&package, &spec, &registry, &registries, kind.as_deref());
+    execute_cargo(registry_context(root, &registry).await, args).await;
+    Some(())
+}
+
+/// Runs `cargo publish` for one of `packages` to a picked registry, as dry run if picked
+pub async fn publish_package(
+    root: String,
+    packages: Vec<String>,
+    selected: Option<String>,
+    registries: Registries,
+) -> Option<()> {
+    let package = pick_package(packages, selected).await?;
+    let registry = pick_registry(&registries).await?;
+    let mode = SelectInput {
+        options: [DRY_RUN, PUBLISH].map(str::to_string).to_vec(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+
+    let args = publish_args(&package, &registry, &registries, mode == DRY_RUN);
+    execute_cargo(registry_context(root, &registry).await, args).await;
+    Some(())
+}
+
+/// Stores the token the user enters for a picked registry, an empty one removes it
+pub async fn set_registry_token(registries: Registries) -> Option<()> {
+    let registry = SelectInput {
+        options: registries.options(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+    let token = input_registry_token(registry.clone()).await.as_string()?;
+    store_registry_token(registry, token.trim().to_string()).await;
+    Some(())
+}
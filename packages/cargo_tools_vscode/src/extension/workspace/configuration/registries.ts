@@ -0,0 +1,34 @@
+import * as vscode from 'vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+// Tokens are kept in the secret storage of VS Code, never in settings
+function secret_key(registry: string): string {
+    return `cargoTools.registryToken.${registry}`;
+}
+
+export async function get_registry_token(registry: string): Promise<string | undefined> {
+    return extension_context?.secrets.get(secret_key(registry));
+}
+
+export async function input_registry_token(registry: string): Promise<string | undefined> {
+    return vscode.window.showInputBox({
+        title: `Token of ${registry}`,
+        prompt: 'Leave empty to remove the stored token',
+        password: true,
+        ignoreFocusOut: true,
+    });
+}
+
+export async function store_registry_token(registry: string, token: string): Promise<void> {
+    const secrets = extension_context?.secrets;
+    if (!secrets) {
+        return;
+    }
+    if (token) {
+        await secrets.store(secret_key(registry), token);
+        vscode.window.showInformationMessage(`Stored the token of ${registry}`);
+    } else {
+        await secrets.delete(secret_key(registry));
+        vscode.window.showInformationMessage(`Removed the token of ${registry}`);
+    }
+}
@@ -0,0 +1,192 @@
+use std::path::{Path, PathBuf};
+
+use cargo_tools::cargo::{
+    new_package::is_valid_name,
+    rename_package::{
+        Rename, lib_name, rename_crate_in_source, rename_dependencies, rename_member,
+        set_package_name,
+    },
+};
+use tracing::{error, info};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    extension::workspace::configuration::manifests::normalize,
+    quick_pick::{SelectInput, show_input_box_with_value},
+    runtime::{read_file_vs_code, write_file_vs_code},
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/rename_package.ts"
+)]
+extern "C" {
+    async fn find_rust_files(dir: String) -> JsValue;
+    async fn rename_directory(from: String, to: String) -> JsValue;
+}
+
+const RENAME_DIRECTORY: &str = "Rename the directory too";
+const KEEP_DIRECTORY: &str = "Keep the directory";
+
+fn dir_of(manifest: &str) -> String {
+    normalize(manifest)
+        .trim_end_matches("/Cargo.toml")
+        .to_string()
+}
+
+async fn read(path: &str) -> Option<String> {
+    read_file_vs_code(path.to_string())
+        .await
+        .inspect_err(|e| error!("Failed to read {path}: {e}"))
+        .ok()
+}
+
+/// Renames a picked package of `packages` given by name and manifest, `selected` by default.
+/// Its `package.name`, the dependencies of the workspace on it and the paths of its crate in
+/// the code of the members which depend on it are renamed, the paths are kept if it sets a
+/// `[lib] name`. Its directory can be renamed too, updating the `path` of the
+/// dependencies and `workspace.members`. Returns the old and the new name.
+pub async fn rename_package(
+    root: String,
+    packages: Vec<(String, String)>,
+    selected: Option<String>,
+) -> Option<(String, String)> {
+    let old = SelectInput {
+        options: packages.iter().map(|(name, _)| name.clone()).collect(),
+        current: selected.into_iter().collect(),
+    }
+    .select()
+    .await?;
+    let new = show_input_box_with_value(
+        "New package name".to_string(),
+        format!("Rename Package {old}"),
+        old.clone(),
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let new = new.trim().to_string();
+    if new == old {
+        return None;
+    }
+    if !is_valid_name(&new) || packages.iter().any(|(name, _)| *name == new) {
+        error!("'{new}' is no valid name or already used by a member");
+        return None;
+    }
+
+    let root = normalize(&root);
+    let root_manifest = format!("{root}/Cargo.toml");
+    let manifest = packages
+        .iter()
+        .find(|(name, _)| *name == old)
+        .map(|(_, manifest)| normalize(manifest))?;
+    let old_dir = dir_of(&manifest);
+    // The directory is renamed if it is named after the package, never the workspace root
+    let parent = old_dir
+        .rsplit_once('/')
+        .map(|(parent, _)| parent.to_string());
+    let new_dir = match parent {
+        Some(parent) if old_dir != root && old_dir.ends_with(&format!("/{old}")) => {
+            let action = SelectInput {
+                options: [RENAME_DIRECTORY, KEEP_DIRECTORY]
+                    .map(str::to_string)
+                    .to_vec(),
+                current: Vec::new(),
+            }
+            .select()
+            .await?;
+            if action == RENAME_DIRECTORY {
+                format!("{parent}/{new}")
+            } else {
+                old_dir.clone()
+            }
+        }
+        _ => old_dir.clone(),
+    };
+    let rename = Rename {
+        old: &old,
+        new: &new,
+        old_dir: Path::new(&old_dir),
+        new_dir: Path::new(&new_dir),
+    };
+
+    // The root manifest first, it is the manifest of the root package too
+    let mut manifests = vec![root_manifest.clone()];
+    for (_, path) in &packages {
+        let path = normalize(path);
+        if !manifests.contains(&path) {
+            manifests.push(path);
+        }
+    }
+    let mut sources = vec![old_dir.clone()];
+    let mut edited = Vec::new();
+    // The code uses the `[lib] name` of the package, it doesn't change with the package name
+    let mut keeps_crate_name = false;
+    for path in manifests {
+        let content = read(&path).await?;
+        let mut updated = if path == manifest {
+            keeps_crate_name = lib_name(&content).is_some();
+            set_package_name(&content, &new)?
+        } else {
+            content.clone()
+        };
+        let (renamed, renamed_key) =
+            rename_dependencies(&updated, &PathBuf::from(dir_of(&path)), &rename);
+        updated = renamed;
+        if path == root_manifest && new_dir != old_dir {
+            let relative = |dir: &str| dir.strip_prefix(&format!("{root}/")).map(str::to_string);
+            if let (Some(old), Some(new)) = (relative(&old_dir), relative(&new_dir)) {
+                updated = rename_member(&updated, &old, &new);
+            }
+        }
+        if renamed_key && !sources.contains(&dir_of(&path)) {
+            sources.push(dir_of(&path));
+        }
+        if updated != content {
+            edited.push((path, updated));
+        }
+    }
+    if keeps_crate_name {
+        sources.clear();
+    }
+    // The directories may be nested, e.g. in a root package, a file belongs to the package with
+    // the innermost directory
+    let package_dirs: Vec<String> = packages.iter().map(|(_, m)| dir_of(m)).collect();
+    let owner = |file: &str| {
+        package_dirs
+            .iter()
+            .filter(|dir| normalize(file).starts_with(&format!("{dir}/")))
+            .max_by_key(|dir| dir.len())
+            .cloned()
+    };
+    let mut files: Vec<String> = Vec::new();
+    for dir in &sources {
+        let found: Vec<String> =
+            serde_wasm_bindgen::from_value(find_rust_files(dir.clone()).await).unwrap_or_default();
+        for file in found {
+            let owned = owner(&file).is_some_and(|owner| sources.contains(&owner));
+            if owned && !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    for file in files {
+        let Some(source) = read(&file).await else {
+            continue;
+        };
+        if let Some(updated) = rename_crate_in_source(&source, &old, &new) {
+            edited.push((file, updated));
+        }
+    }
+
+    for (path, content) in edited {
+        if let Err(e) = write_file_vs_code(path.clone(), content).await {
+            error!("Failed to update {path}: {e}");
+        }
+    }
+    // Shows an error if the directory can't be renamed, the manifests are updated anyway
+    if new_dir != old_dir {
+        rename_directory(old_dir, new_dir).await;
+    }
+    info!("Renamed {old} to {new}");
+    Some((old, new))
+}
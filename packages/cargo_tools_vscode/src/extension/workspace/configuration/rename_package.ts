@@ -0,0 +1,17 @@
+import * as vscode from 'vscode';
+
+export async function find_rust_files(dir: string): Promise<string[]> {
+    const pattern = new vscode.RelativePattern(vscode.Uri.file(dir), '**/*.rs');
+    const uris = await vscode.workspace.findFiles(pattern, '**/target/**');
+    return uris.map((uri) => uri.fsPath);
+}
+
+export async function rename_directory(from: string, to: string): Promise<boolean> {
+    try {
+        await vscode.workspace.fs.rename(vscode.Uri.file(from), vscode.Uri.file(to));
+        return true;
+    } catch (e) {
+        vscode.window.showErrorMessage(`Failed to rename ${from} to ${to}: ${e}`);
+        return false;
+    }
+}
@@ -0,0 +1,81 @@
+use cargo_tools::cargo::TestHistory;
+use serde::Serialize;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    environment::{metadata_task_context, test_harness_args},
+    runtime::exec_vs_code,
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/slowest_tests.ts"
+)]
+extern "C" {
+    /// Lists the tests by their recorded durations
+    #[derive(Clone)]
+    pub type SlowestTestsView;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SlowestTestsView;
+
+    #[wasm_bindgen(method)]
+    fn show_tests(this: &SlowestTestsView, tests: JsValue, message: Option<String>);
+
+    /// Shows the view, which appears once it was revealed
+    #[wasm_bindgen(method)]
+    pub fn reveal(this: &SlowestTestsView);
+}
+
+#[derive(Serialize)]
+struct SlowTest<'a> {
+    id: &'a str,
+    name: &'a str,
+    summary: String,
+    regressed: bool,
+}
+
+impl SlowestTestsView {
+    /// Lists the tests of `history` with durations, slowest first, with `message` explaining
+    /// why there are none
+    pub fn show_history(&self, history: &TestHistory, message: Option<String>) {
+        let stats = history.slowest();
+        let tests: Vec<_> = stats
+            .iter()
+            .map(|stats| SlowTest {
+                id: &stats.id,
+                name: stats.name(),
+                summary: stats.summary(),
+                regressed: stats.regressed,
+            })
+            .collect();
+        match to_value(&tests) {
+            Ok(tests) => self.show_tests(tests, message),
+            Err(e) => error!("Failed to show the slowest tests: {e}"),
+        }
+    }
+}
+
+/// Why no test durations were recorded: libtest only reports them on nightly and with
+/// `-Zunstable-options --report-time`
+pub async fn no_durations_message() -> String {
+    let version = match metadata_task_context().try_into_process(vec!["--version".to_string()]) {
+        Ok(process) => exec_vs_code(process).await.unwrap_or_default(),
+        Err(e) => {
+            error!("{e}");
+            String::new()
+        }
+    };
+    let version = version.trim();
+    if !version.contains("nightly") {
+        let toolchain = if version.is_empty() { "cargo" } else { version };
+        return format!(
+            "Test durations are only reported on nightly, but the tests run with {toolchain}. Set `cargoTools.cargoCommand` to `cargo +nightly` or use a nightly toolchain."
+        );
+    }
+    if !test_harness_args().iter().any(|arg| arg == "--report-time") {
+        return "No test durations recorded. libtest reports them with `cargoTools.test.harnessArgs` set to `[\"-Zunstable-options\", \"--report-time\"]`.".to_string();
+    }
+    "No test durations recorded yet, run the tests of the Testing view.".to_string()
+}
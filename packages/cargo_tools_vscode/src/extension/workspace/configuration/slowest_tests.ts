@@ -0,0 +1,60 @@
+import * as vscode from 'vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+interface SlowTest {
+    id: string;
+    name: string;
+    summary: string;
+    regressed: boolean;
+}
+
+/**
+ * Lists the tests of the Testing view by their mean duration in recent runs, with their
+ * failures and whether their last run was much slower than usual. Clicking a test reveals it
+ * in the Testing view. The view appears once **Show Slowest Tests** revealed it.
+ */
+export class SlowestTestsView implements vscode.TreeDataProvider<SlowTest> {
+    private _onDidChangeTreeData = new vscode.EventEmitter<SlowTest | undefined | null | void>();
+    readonly onDidChangeTreeData = this._onDidChangeTreeData.event;
+
+    private view: vscode.TreeView<SlowTest>;
+    private tests: SlowTest[] = [];
+
+    constructor() {
+        this.view = vscode.window.createTreeView('cargoToolsSlowestTests', { treeDataProvider: this });
+        extension_context?.subscriptions.push(this.view);
+    }
+
+    getTreeItem(test: SlowTest): vscode.TreeItem {
+        const item = new vscode.TreeItem(test.name);
+        item.description = test.summary;
+        item.tooltip = test.regressed
+            ? `${test.id}\nThe last run was much slower than usual\n${test.summary}`
+            : `${test.id}\n${test.summary}`;
+        item.iconPath = test.regressed
+            ? new vscode.ThemeIcon('warning', new vscode.ThemeColor('list.warningForeground'))
+            : new vscode.ThemeIcon('watch');
+        item.contextValue = 'slowTest';
+        item.command = {
+            command: 'cargo-tools.showSlowestTests',
+            title: 'Reveal Test',
+            arguments: [test.id],
+        };
+        return item;
+    }
+
+    getChildren(test?: SlowTest): SlowTest[] {
+        return test ? [] : this.tests;
+    }
+
+    show_tests(tests: SlowTest[], message?: string): void {
+        this.tests = tests;
+        this.view.message = message;
+        this._onDidChangeTreeData.fire();
+    }
+
+    reveal(): void {
+        vscode.commands.executeCommand('setContext', 'cargoTools:slowestTestsShown', true);
+        vscode.commands.executeCommand('cargoToolsSlowestTests.focus');
+    }
+}
@@ -0,0 +1,160 @@
+use cargo_tools::process::Process;
+use futures::{
+    SinkExt, StreamExt,
+    channel::mpsc::{Sender, channel},
+};
+use wasm_bindgen::prelude::*;
+
+use crate::runtime::VsCodeTask;
+use tracing::error;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/task_provider.ts"
+)]
+extern "C" {
+    pub type CargoTaskProvider;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(handler: CargoTaskProviderHandler) -> CargoTaskProvider;
+
+    #[wasm_bindgen(method)]
+    pub fn invalidate(this: &CargoTaskProvider);
+
+    #[wasm_bindgen(catch)]
+    pub async fn make_default_build_task() -> Result<(), JsValue>;
+}
+
+/// The kinds of `cargo-tools` tasks which can be referenced in `tasks.json`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    Build,
+    Run,
+    Test,
+    Clippy,
+    Fmt,
+    Doc,
+    Bench,
+}
+
+impl TaskKind {
+    pub const fn all() -> [Self; 7] {
+        [
+            Self::Build,
+            Self::Run,
+            Self::Test,
+            Self::Clippy,
+            Self::Fmt,
+            Self::Doc,
+            Self::Bench,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Build => "build",
+            Self::Run => "run",
+            Self::Test => "test",
+            Self::Clippy => "clippy",
+            Self::Fmt => "fmt",
+            Self::Doc => "doc",
+            Self::Bench => "bench",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().into_iter().find(|kind| kind.name() == name)
+    }
+
+    /// The VS Code task group the kind belongs to
+    fn group(&self) -> Option<String> {
+        match self {
+            Self::Build | Self::Clippy => Some("build".to_string()),
+            Self::Test | Self::Bench => Some("test".to_string()),
+            Self::Run | Self::Fmt | Self::Doc => None,
+        }
+    }
+}
+
+/// A task built from the current configuration
+#[derive(Debug)]
+pub struct ProvidedTask {
+    pub kind: TaskKind,
+    pub process: Process,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskProviderRequest {
+    pub tx: Sender<Vec<ProvidedTask>>,
+    pub kinds: Vec<TaskKind>,
+}
+
+/// Task type which is exported in typescript code
+#[wasm_bindgen]
+pub struct VsCodeProvidedTask {
+    kind: TaskKind,
+    task: VsCodeTask,
+}
+
+#[wasm_bindgen]
+impl VsCodeProvidedTask {
+    #[wasm_bindgen]
+    pub fn kind(&self) -> String {
+        self.kind.name().to_string()
+    }
+
+    #[wasm_bindgen]
+    pub fn group(&self) -> Option<String> {
+        self.kind.group()
+    }
+
+    #[wasm_bindgen]
+    pub fn into_task(self) -> VsCodeTask {
+        self.task
+    }
+}
+
+#[wasm_bindgen]
+pub struct CargoTaskProviderHandler {
+    tx: Sender<TaskProviderRequest>,
+}
+
+#[wasm_bindgen]
+impl CargoTaskProviderHandler {
+    pub async fn provide_tasks(&self) -> Vec<VsCodeProvidedTask> {
+        self.request(TaskKind::all().to_vec()).await
+    }
+
+    pub async fn resolve_task(&self, kind: String) -> Option<VsCodeProvidedTask> {
+        let kind = TaskKind::from_name(&kind)?;
+        self.request(vec![kind]).await.into_iter().next()
+    }
+}
+
+impl CargoTaskProviderHandler {
+    pub fn new(tx: Sender<TaskProviderRequest>) -> Self {
+        Self { tx }
+    }
+
+    async fn request(&self, kinds: Vec<TaskKind>) -> Vec<VsCodeProvidedTask> {
+        let (tx, mut rx) = channel(1);
+
+        if let Err(e) = self
+            .tx
+            .clone()
+            .send(TaskProviderRequest { tx, kinds })
+            .await
+        {
+            error!("Failed to send TaskProviderRequest: {e}");
+        }
+
+        rx.next()
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .map(|ProvidedTask { kind, process }| VsCodeProvidedTask {
+                kind,
+                task: VsCodeTask::cargo(process),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,93 @@
+import * as vscode from 'vscode';
+import { CargoTaskProviderHandler, VsCodeProvidedTask } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+import { createTask } from '../../../runtime';
+
+const TASK_TYPE = 'cargo-tools';
+
+export class CargoTaskProvider implements vscode.TaskProvider {
+    private handler: CargoTaskProviderHandler;
+    private registration?: vscode.Disposable;
+
+    constructor(handler: CargoTaskProviderHandler) {
+        this.handler = handler;
+
+        // The registration is swapped on every invalidation, only the current one is disposed
+        extension_context?.subscriptions.push({ dispose: () => this.registration?.dispose() });
+        // register on creation
+        this.register();
+    }
+
+    /**
+     * VS Code caches provided tasks, re-registering the provider makes it fetch them again
+     */
+    invalidate(): void {
+        this.register();
+    }
+
+    private register(): void {
+        this.registration?.dispose();
+        this.registration = vscode.tasks.registerTaskProvider(TASK_TYPE, this);
+    }
+
+    async provideTasks(): Promise<vscode.Task[]> {
+        const tasks = await this.handler.provide_tasks();
+        return tasks.map((task) => toTask(task, { type: TASK_TYPE, command: task.kind() }));
+    }
+
+    async resolveTask(task: vscode.Task): Promise<vscode.Task | undefined> {
+        const command = task.definition.command;
+        if (typeof command !== 'string') {
+            return undefined;
+        }
+        const resolved = await this.handler.resolve_task(command);
+        // VS Code requires the resolved task to keep the definition from tasks.json
+        return resolved ? toTask(resolved, task.definition) : undefined;
+    }
+}
+
+function toTask(provided: VsCodeProvidedTask, definition: vscode.TaskDefinition): vscode.Task {
+    const name = provided.kind();
+    const group = provided.group();
+    const task = createTask(provided.into_task(), definition, name);
+    if (group === 'build') {
+        task.group = vscode.TaskGroup.Build;
+    } else if (group === 'test') {
+        task.group = vscode.TaskGroup.Test;
+    }
+    return task;
+}
+
+const DEFAULT_BUILD_TASK_LABEL = `${TASK_TYPE}: build`;
+
+function isBuildGroup(group: any): boolean {
+    return group === 'build' || group?.kind === 'build';
+}
+
+export async function make_default_build_task(): Promise<void> {
+    const folder = vscode.workspace.workspaceFolders?.[0];
+    if (!folder) {
+        throw new Error('No workspace folder found');
+    }
+    const config = vscode.workspace.getConfiguration('tasks', folder.uri);
+
+    // Only one default build task may exist, otherwise VS Code asks which one to run
+    const tasks = (config.get<any[]>('tasks') ?? [])
+        .filter((task) => task.label !== DEFAULT_BUILD_TASK_LABEL)
+        .map((task) => isBuildGroup(task.group) ? { ...task, group: 'build' } : task);
+
+    // The task resolves to the active build target whenever it runs
+    tasks.push({
+        type: TASK_TYPE,
+        command: 'build',
+        label: DEFAULT_BUILD_TASK_LABEL,
+        group: { kind: 'build', isDefault: true },
+        problemMatcher: ['$rustc'],
+    });
+
+    if (!config.get<string>('version')) {
+        await config.update('version', '2.0.0', vscode.ConfigurationTarget.WorkspaceFolder);
+    }
+    await config.update('tasks', tasks, vscode.ConfigurationTarget.WorkspaceFolder);
+    vscode.window.showInformationMessage(`'${DEFAULT_BUILD_TASK_LABEL}' is now the default build task`);
+}
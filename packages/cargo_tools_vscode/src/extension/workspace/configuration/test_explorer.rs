@@ -0,0 +1,260 @@
+use std::path::Path;
+
+use cargo_tools::{
+    cargo::{
+        IgnoredTests, TestOutcome,
+        coverage::FileCoverage,
+        insta,
+        libtest::Panic,
+        test_tree::{SourceFile, TestItem},
+    },
+    process::split_args,
+};
+use futures::{
+    SinkExt, StreamExt,
+    channel::mpsc::{Sender, channel},
+};
+use serde::Serialize;
+use serde_wasm_bindgen::{from_value, to_value};
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/test_explorer.ts"
+)]
+extern "C" {
+    pub type TestExplorer;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new(handler: TestExplorerHandler) -> TestExplorer;
+
+    /// Discovers the tests again, e.g. after the workspace members changed
+    #[wasm_bindgen(method)]
+    pub fn refresh(this: &TestExplorer);
+
+    /// Starts a test run of the items `ids` in the Testing view
+    #[wasm_bindgen(method)]
+    pub fn run_tests(this: &TestExplorer, ids: Vec<String>);
+
+    /// Shows the item `id` in the Testing view
+    #[wasm_bindgen(method)]
+    pub fn reveal_test(this: &TestExplorer, id: String);
+}
+
+#[derive(Debug)]
+pub enum TestExplorerRequest {
+    /// Builds the test tree from the Rust source files of the workspace
+    Discover {
+        files: Vec<SourceFile>,
+        tx: Sender<Vec<TestItem>>,
+    },
+    /// Lists the children of a lazy item, the doctests of a doctest item or the tests of a
+    /// package of a large workspace
+    DiscoverChildren {
+        id: String,
+        tx: Sender<Vec<TestItem>>,
+    },
+    /// Runs the items of a test run, see [cargo_tools::cargo::TestTree::run_selections].
+    /// With `coverage` the tests run with `cargo llvm-cov`.
+    Run {
+        ids: Vec<String>,
+        coverage: bool,
+        ignored: IgnoredTests,
+        /// Test harness arguments of this run, after those of `cargoTools.test.harnessArgs`
+        harness_args: Vec<String>,
+        /// Identifies the processes of the run, which are killed when it is cancelled
+        run_id: String,
+        tx: Sender<TestRunResult>,
+    },
+    Debug {
+        id: String,
+    },
+}
+
+/// The output of running a test item and the results of the tests it reported
+#[derive(Debug, Default, Serialize)]
+pub struct TestRunResult {
+    pub output: String,
+    pub results: Vec<TestItemResult>,
+    /// The line coverage of a coverage run
+    pub coverage: Vec<FileCoverage>,
+    /// The run was stopped after this timeout, its tests without a result never finished
+    pub timeout_secs: Option<u64>,
+    /// The file with the full output if `output` only holds its last lines
+    pub log_file: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestItemResult {
+    pub id: String,
+    pub outcome: &'static str,
+    pub message: Option<String>,
+    /// The `right` and `left` values of a failed assertion, shown as diff
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+    /// Where the test panicked, with a zero based line and column
+    pub location: Option<TestLocation>,
+    /// The test failed first and passed on a retry
+    pub flaky: bool,
+    /// How long the test took, if libtest reported it
+    pub duration_ms: Option<u64>,
+    /// The test took much longer than in its recent runs, see [cargo_tools::cargo::TestHistory]
+    pub regressed: bool,
+    /// The test failed on a mismatching insta snapshot, which can be reviewed
+    pub snapshot: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TestLocation {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl TestItemResult {
+    /// The result of a test with the captured `output` of a failure. Relative panic
+    /// locations are resolved against `workspace_root`.
+    pub fn new(
+        id: String,
+        outcome: TestOutcome,
+        output: Option<String>,
+        workspace_root: &str,
+    ) -> Self {
+        let snapshot = outcome == TestOutcome::Failed
+            && output.as_deref().is_some_and(insta::is_snapshot_failure);
+        let outcome = match outcome {
+            TestOutcome::Passed => "passed",
+            TestOutcome::Failed => "failed",
+            TestOutcome::Ignored => "ignored",
+        };
+        let panic = output.as_deref().and_then(Panic::parse);
+        let location = panic
+            .as_ref()
+            .and_then(|p| p.location.as_ref())
+            .map(|location| TestLocation {
+                file: Path::new(workspace_root)
+                    .join(&location.file)
+                    .to_string_lossy()
+                    .to_string(),
+                line: location.line.saturating_sub(1),
+                column: location.column.saturating_sub(1),
+            });
+        let (message, expected, actual) = match panic {
+            // The diff shows the values, the message only needs the assertion
+            Some(Panic {
+                message,
+                left: Some(left),
+                right: Some(right),
+                ..
+            }) => (Some(message), Some(right), Some(left)),
+            _ => (output, None, None),
+        };
+
+        Self {
+            id,
+            outcome,
+            message,
+            expected,
+            actual,
+            location,
+            flaky: false,
+            duration_ms: None,
+            regressed: false,
+            snapshot,
+        }
+    }
+}
+
+#[wasm_bindgen]
+pub struct TestExplorerHandler {
+    tx: Sender<TestExplorerRequest>,
+}
+
+#[wasm_bindgen]
+impl TestExplorerHandler {
+    pub async fn discover(&self, files: JsValue) -> JsValue {
+        let files = match from_value(files) {
+            Ok(files) => files,
+            Err(e) => {
+                error!("Failed to deserialize source files: {e}");
+                return JsValue::NULL;
+            }
+        };
+        let items = self
+            .request(|tx| TestExplorerRequest::Discover { files, tx })
+            .await;
+        serialize(&items)
+    }
+
+    pub async fn discover_children(&self, id: String) -> JsValue {
+        let items = self
+            .request(|tx| TestExplorerRequest::DiscoverChildren { id, tx })
+            .await;
+        serialize(&items)
+    }
+
+    /// Runs the items `ids`, `ignored` is `"skip"`, `"only"` or `"include"`. The processes of
+    /// the run are killed by `kill_output_processes(run_id)`.
+    pub async fn run(
+        &self,
+        ids: Vec<String>,
+        coverage: bool,
+        ignored: JsValue,
+        harness_args: String,
+        run_id: String,
+    ) -> JsValue {
+        let harness_args = split_args(&harness_args);
+        let ignored = from_value(ignored).unwrap_or_else(|e| {
+            error!("Failed to deserialize ignored tests mode: {e}");
+            IgnoredTests::Skip
+        });
+        let result = self
+            .request(|tx| TestExplorerRequest::Run {
+                ids,
+                coverage,
+                ignored,
+                harness_args,
+                run_id,
+                tx,
+            })
+            .await;
+        serialize(&result)
+    }
+
+    pub async fn debug(&self, id: String) {
+        if let Err(e) = self
+            .tx
+            .clone()
+            .send(TestExplorerRequest::Debug { id })
+            .await
+        {
+            error!("Failed to send TestExplorerRequest: {e}");
+        }
+    }
+}
+
+impl TestExplorerHandler {
+    pub fn new(tx: Sender<TestExplorerRequest>) -> Self {
+        Self { tx }
+    }
+
+    async fn request<T: Default>(
+        &self,
+        request: impl FnOnce(Sender<T>) -> TestExplorerRequest,
+    ) -> T {
+        let (tx, mut rx) = channel(1);
+
+        if let Err(e) = self.tx.clone().send(request(tx)).await {
+            error!("Failed to send TestExplorerRequest: {e}");
+        }
+
+        rx.next().await.unwrap_or_default()
+    }
+}
+
+fn serialize(value: &impl Serialize) -> JsValue {
+    to_value(value).unwrap_or_else(|e| {
+        error!("Failed to serialize test explorer response: {e}");
+        JsValue::NULL
+    })
+}
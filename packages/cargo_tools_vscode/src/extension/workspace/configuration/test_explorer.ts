@@ -0,0 +1,430 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+import { TestExplorerHandler } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+import { kill_output_processes } from '../../../runtime';
+
+// Saves in quick succession, e.g. Save All, only start one continuous run
+const CONTINUOUS_RUN_DEBOUNCE_MS = 300;
+
+// Tags `#[ignore]`d tests, e.g. to filter them with `@cargo-tools:ignored`
+const IGNORED_TAG = new vscode.TestTag('ignored');
+
+// Tags tests asserting insta snapshots, e.g. to filter them with `@cargo-tools:snapshot`
+const SNAPSHOT_TAG = new vscode.TestTag('snapshot');
+
+// The context value of snapshot failure messages, which offer to review the pending snapshots
+const SNAPSHOT_MESSAGE_CONTEXT = 'cargo-tools.snapshot';
+
+// The workspace state key of the harness arguments last entered for a run
+const HARNESS_ARGS_KEY = 'cargo-tools.testHarnessArgs';
+
+// Which `#[ignore]`d tests a run includes, libtest's `--ignored` and `--include-ignored`
+type IgnoredTests = 'skip' | 'only' | 'include';
+
+interface TestItemData {
+    id: string;
+    label: string;
+    file?: string;
+    line?: number;
+    children: TestItemData[];
+    lazy: boolean;
+    ignored: boolean;
+    snapshot: boolean;
+}
+
+interface TestItemResult {
+    id: string;
+    outcome: 'passed' | 'failed' | 'ignored';
+    message?: string;
+    expected?: string;
+    actual?: string;
+    location?: { file: string; line: number; column: number };
+    flaky: boolean;
+    duration_ms?: number;
+    regressed: boolean;
+    snapshot: boolean;
+}
+
+interface FileCoverageData {
+    file: string;
+    lines: { line: number; count: number }[];
+}
+
+interface TestRunResult {
+    output: string;
+    results: TestItemResult[];
+    coverage: FileCoverageData[];
+    timeout_secs?: number;
+    log_file?: string;
+}
+
+/**
+ * The line coverage of a file, its statements are loaded when the file is opened
+ */
+class LineCoverage extends vscode.FileCoverage {
+    readonly details: vscode.StatementCoverage[];
+
+    constructor(data: FileCoverageData) {
+        const details = data.lines.map((line) =>
+            new vscode.StatementCoverage(line.count, new vscode.Position(line.line, 0)));
+        const covered = details.filter((detail) => detail.executed).length;
+        super(vscode.Uri.file(data.file), new vscode.TestCoverageCount(covered, details.length));
+        this.details = details;
+    }
+}
+
+/**
+ * Shows the unit, integration and doc tests of the workspace in the Testing view with the
+ * hierarchy package → unit tests, integration tests or doctests → file → module → test
+ */
+export class TestExplorer {
+    private handler: TestExplorerHandler;
+    private nextRunId = 0;
+    private controller: vscode.TestController;
+    private runProfile: vscode.TestRunProfile;
+    private items = new Map<string, vscode.TestItem>();
+    private refreshTimer?: ReturnType<typeof setTimeout>;
+
+    constructor(handler: TestExplorerHandler) {
+        this.handler = handler;
+        this.controller = vscode.tests.createTestController('cargo-tools', 'Cargo Tools');
+        this.controller.resolveHandler = async (item) => {
+            if (!item) {
+                await this.discover();
+            } else if (item.canResolveChildren) {
+                await this.discoverChildren(item);
+            }
+        };
+        this.controller.refreshHandler = () => this.discover();
+        this.runProfile = this.controller.createRunProfile('Run', vscode.TestRunProfileKind.Run,
+            (request, token) => request.continuous
+                ? this.runContinuously(request, token)
+                : this.run(request, token),
+            true, undefined, true);
+        this.controller.createRunProfile('Debug', vscode.TestRunProfileKind.Debug,
+            (request) => this.debug(request), true);
+        const coverageProfile = this.controller.createRunProfile('Coverage',
+            vscode.TestRunProfileKind.Coverage,
+            (request, token) => this.run(request, token, true), true);
+        this.controller.createRunProfile('Run Ignored', vscode.TestRunProfileKind.Run,
+            (request, token) => this.run(request, token, false, 'only'), false);
+        this.controller.createRunProfile('Run Including Ignored', vscode.TestRunProfileKind.Run,
+            (request, token) => this.run(request, token, false, 'include'), false);
+        this.controller.createRunProfile('Run with Arguments...', vscode.TestRunProfileKind.Run,
+            (request, token) => this.runWithArguments(request, token), false);
+        coverageProfile.loadDetailedCoverage = async (_run, coverage) =>
+            coverage instanceof LineCoverage ? coverage.details : [];
+
+        // Tests are found in the sources, so added and removed tests show up without a build
+        const watcher = vscode.workspace.createFileSystemWatcher('**/*.rs');
+        watcher.onDidChange(() => this.refresh());
+        watcher.onDidCreate(() => this.refresh());
+        watcher.onDidDelete(() => this.refresh());
+        extension_context?.subscriptions.push(this.controller, watcher);
+    }
+
+    refresh(): void {
+        if (this.refreshTimer) {
+            clearTimeout(this.refreshTimer);
+        }
+        this.refreshTimer = setTimeout(() => {
+            this.refreshTimer = undefined;
+            this.discover();
+        }, 500);
+    }
+
+    async run_tests(ids: string[]): Promise<void> {
+        await this.resolvePackagesOf(ids);
+        const include = ids
+            .map((id) => this.items.get(id))
+            .filter((item): item is vscode.TestItem => item !== undefined);
+        if (include.length === 0) {
+            return;
+        }
+        const request = new vscode.TestRunRequest(include, undefined, this.runProfile);
+        const cancellation = new vscode.CancellationTokenSource();
+        this.run(request, cancellation.token).finally(() => cancellation.dispose());
+    }
+
+    async reveal_test(id: string): Promise<void> {
+        await this.resolvePackagesOf([id]);
+        const item = this.items.get(id);
+        if (item) {
+            vscode.commands.executeCommand('vscode.revealTestInExplorer', item);
+        }
+    }
+
+    private async discover(): Promise<void> {
+        const uris = await vscode.workspace.findFiles('**/*.rs', '**/target/**');
+        const decoder = new TextDecoder();
+        const files = await Promise.all(uris.map(async (uri) => ({
+            path: uri.fsPath,
+            source: decoder.decode(await vscode.workspace.fs.readFile(uri)),
+        })));
+
+        const roots: TestItemData[] = await this.handler.discover(files) ?? [];
+        this.items.clear();
+        this.controller.items.replace(roots.map((root) => this.create(root)));
+    }
+
+    private async discoverChildren(item: vscode.TestItem): Promise<void> {
+        const children: TestItemData[] = await this.handler.discover_children(item.id) ?? [];
+        item.children.replace(children.map((child) => this.create(child)));
+    }
+
+    /** Creates the items of the packages of `ids` which are lazy and weren't expanded yet */
+    private async resolvePackagesOf(ids: string[]): Promise<void> {
+        const missing = ids.filter((id) => !this.items.has(id));
+        const roots: vscode.TestItem[] = [];
+        this.controller.items.forEach((root) => {
+            const dir = packageDir(root);
+            if (root.canResolveChildren && root.children.size === 0 && dir
+                && missing.some((id) => id.startsWith(dir))) {
+                roots.push(root);
+            }
+        });
+        await Promise.all(roots.map((root) => this.discoverChildren(root)));
+    }
+
+    private create(data: TestItemData): vscode.TestItem {
+        const uri = data.file ? vscode.Uri.file(data.file) : undefined;
+        const item = this.controller.createTestItem(data.id, data.label, uri);
+        if (data.line !== undefined) {
+            item.range = new vscode.Range(data.line, 0, data.line, 0);
+        }
+        item.canResolveChildren = data.lazy;
+        if (data.ignored) {
+            item.description = 'ignored';
+            item.tags = [IGNORED_TAG];
+        }
+        if (data.snapshot) {
+            item.tags = [...item.tags, SNAPSHOT_TAG];
+        }
+        item.children.replace(data.children.map((child) => this.create(child)));
+        this.items.set(data.id, item);
+        return item;
+    }
+
+    private included(request: vscode.TestRunRequest): vscode.TestItem[] {
+        const items: vscode.TestItem[] = [];
+        if (request.include) {
+            items.push(...request.include);
+        } else {
+            this.controller.items.forEach((item) => items.push(item));
+        }
+        return items.filter((item) => !request.exclude?.includes(item));
+    }
+
+    private async run(request: vscode.TestRunRequest, token: vscode.CancellationToken,
+        coverage = false, ignored: IgnoredTests = 'skip', harnessArgs = ''): Promise<void> {
+        // Only the processes of this run are killed, other runs go on
+        const runId = String(this.nextRunId++);
+        const cancellation = token.onCancellationRequested(() => kill_output_processes(runId));
+        try {
+            await this.runTests(request, token, runId, coverage, ignored, harnessArgs);
+        } finally {
+            cancellation.dispose();
+        }
+    }
+
+    /**
+     * Prompts for test harness arguments, e.g. `--nocapture --test-threads=1`, and runs the
+     * included items with them
+     */
+    private async runWithArguments(request: vscode.TestRunRequest,
+        token: vscode.CancellationToken): Promise<void> {
+        const harnessArgs = await vscode.window.showInputBox({
+            prompt: 'Arguments for the test harness, passed after --',
+            placeHolder: '--nocapture --test-threads=1',
+            value: extension_context?.workspaceState.get<string>(HARNESS_ARGS_KEY) ?? '',
+        });
+        if (harnessArgs === undefined) {
+            return;
+        }
+        await extension_context?.workspaceState.update(HARNESS_ARGS_KEY, harnessArgs);
+        await this.run(request, token, false, 'skip', harnessArgs);
+    }
+
+    /**
+     * Re-runs the included items of a package whenever one of its Rust files is saved,
+     * until the continuous run is stopped. A save cancels the run still in flight.
+     */
+    private runContinuously(request: vscode.TestRunRequest, token: vscode.CancellationToken): Promise<void> {
+        const included = this.included(request);
+        let inFlight: vscode.CancellationTokenSource | undefined;
+        let timer: ReturnType<typeof setTimeout> | undefined;
+        let affected = new Set<vscode.TestItem>();
+
+        const start = () => {
+            timer = undefined;
+            inFlight?.cancel();
+            inFlight?.dispose();
+            inFlight = new vscode.CancellationTokenSource();
+            const include = [...affected];
+            affected = new Set();
+            this.run(new vscode.TestRunRequest(include, request.exclude, request.profile), inFlight.token);
+        };
+
+        const saved = vscode.workspace.onDidSaveTextDocument((document) => {
+            if (document.languageId !== 'rust') {
+                return;
+            }
+            const items = included.filter((item) => {
+                const dir = packageDir(item);
+                return dir !== undefined && document.uri.fsPath.startsWith(dir + path.sep);
+            });
+            if (items.length === 0) {
+                return;
+            }
+            items.forEach((item) => affected.add(item));
+            if (timer) {
+                clearTimeout(timer);
+            }
+            timer = setTimeout(start, CONTINUOUS_RUN_DEBOUNCE_MS);
+        });
+
+        return new Promise((resolve) => {
+            token.onCancellationRequested(() => {
+                saved.dispose();
+                if (timer) {
+                    clearTimeout(timer);
+                }
+                inFlight?.cancel();
+                inFlight?.dispose();
+                resolve();
+            });
+        });
+    }
+
+    private async runTests(request: vscode.TestRunRequest, token: vscode.CancellationToken, runId: string,
+        coverage: boolean, ignored: IgnoredTests, harnessArgs: string): Promise<void> {
+        const run = this.controller.createTestRun(request);
+        const included = this.included(request);
+        // With `--ignored` libtest doesn't report the other tests at all
+        const tests = included.flatMap((item) => leaves(item))
+            .filter((test) => ignored !== 'only' || test.tags.includes(IGNORED_TAG));
+        tests.forEach((test) => run.started(test));
+
+        // Items of the same package and target are run by a single cargo invocation
+        const result: TestRunResult = await this.handler.run(included.map((item) => item.id), coverage,
+            ignored, harnessArgs, runId)
+            ?? { output: '', results: [], coverage: [] };
+        if (token.isCancellationRequested) {
+            run.end();
+            return;
+        }
+        run.appendOutput(result.output.replace(/\r?\n/g, '\r\n'));
+        if (result.log_file !== undefined) {
+            showFullLog(result.log_file);
+        }
+        result.coverage.forEach((file) => run.addCoverage(new LineCoverage(file)));
+
+        const reported = new Set<string>();
+        for (const testResult of result.results) {
+            const test = this.items.get(testResult.id);
+            if (!test) {
+                continue;
+            }
+            reported.add(testResult.id);
+            markFlaky(test, testResult.flaky);
+            if (testResult.regressed) {
+                run.appendOutput(`Slower than usual: ${test.id} took ${testResult.duration_ms} ms\r\n`,
+                    undefined, test);
+            }
+            const duration = testResult.duration_ms;
+            if (testResult.outcome === 'passed') {
+                if (testResult.flaky) {
+                    run.appendOutput(`Flaky: ${test.id} failed and passed on a retry\r\n`, undefined, test);
+                }
+                run.passed(test, duration);
+            } else if (testResult.outcome === 'ignored') {
+                run.skipped(test);
+            } else {
+                run.failed(test, failureMessage(testResult), duration);
+            }
+        }
+
+        // Tests without a result hung when the run timed out, without any result the tests
+        // didn't build and their output shows why
+        for (const test of tests.filter((test) => !reported.has(test.id))) {
+            if (result.timeout_secs !== undefined) {
+                run.errored(test, new vscode.TestMessage(
+                    `The test run was stopped after the timeout of ${result.timeout_secs} s`));
+            } else if (result.results.length === 0) {
+                run.errored(test, new vscode.TestMessage('cargo test failed, see the test output'));
+            } else {
+                run.skipped(test);
+            }
+        }
+        run.end();
+    }
+
+    private async debug(request: vscode.TestRunRequest): Promise<void> {
+        for (const item of this.included(request)) {
+            await this.handler.debug(item.id);
+        }
+    }
+}
+
+/**
+ * Shows the values of a failed assertion as diff and links the panic location
+ */
+function failureMessage(result: TestItemResult): vscode.TestMessage {
+    const text = result.message ?? 'Test failed';
+    const message = result.expected !== undefined && result.actual !== undefined
+        ? vscode.TestMessage.diff(text, result.expected, result.actual)
+        : new vscode.TestMessage(text);
+    if (result.location) {
+        const position = new vscode.Position(result.location.line, result.location.column);
+        message.location = new vscode.Location(vscode.Uri.file(result.location.file), position);
+    }
+    if (result.snapshot) {
+        message.contextValue = SNAPSHOT_MESSAGE_CONTEXT;
+    }
+    return message;
+}
+
+/**
+ * Marks tests which only passed on a retry until they pass or fail consistently again
+ */
+function markFlaky(test: vscode.TestItem, flaky: boolean): void {
+    if (flaky) {
+        test.description = 'flaky';
+    } else if (test.description === 'flaky') {
+        test.description = undefined;
+    }
+}
+
+/**
+ * The directory of the package an item belongs to, package items link its manifest
+ */
+function packageDir(item: vscode.TestItem): string | undefined {
+    let root = item;
+    while (root.parent) {
+        root = root.parent;
+    }
+    return root.uri ? vscode.Uri.joinPath(root.uri, '..').fsPath : undefined;
+}
+
+/**
+ * The tests of an item, lazy doctest items only know theirs once they are expanded
+ */
+function leaves(item: vscode.TestItem): vscode.TestItem[] {
+    if (item.children.size === 0) {
+        return item.canResolveChildren ? [] : [item];
+    }
+    const tests: vscode.TestItem[] = [];
+    item.children.forEach((child) => tests.push(...leaves(child)));
+    return tests;
+}
+
+/** Offers to open the full output of a test run whose output was shortened to its last lines */
+async function showFullLog(logFile: string): Promise<void> {
+    const open = 'Open Full Log';
+    const choice = await vscode.window.showInformationMessage(
+        'The test output was shortened to its last lines (cargoTools.output.maxLines).', open);
+    if (choice === open) {
+        await vscode.window.showTextDocument(vscode.Uri.file(logFile));
+    }
+}
@@ -0,0 +1,32 @@
+use cargo_tools::rust_source;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/test_lens.ts"
+)]
+extern "C" {
+    pub type TestLensProvider;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> TestLensProvider;
+}
+
+/// The test functions of a Rust source file for the typescript CodeLens provider
+#[wasm_bindgen]
+pub fn find_test_functions(source: &str) -> JsValue {
+    to_value(&rust_source::find_test_functions(source)).unwrap_or_else(|e| {
+        error!("Failed to serialize test functions: {e}");
+        JsValue::NULL
+    })
+}
+
+/// The doctest code blocks of a Rust source file for the typescript CodeLens provider
+#[wasm_bindgen]
+pub fn find_doc_code_blocks(source: &str) -> JsValue {
+    to_value(&rust_source::find_doc_code_blocks(source)).unwrap_or_else(|e| {
+        error!("Failed to serialize doc code blocks: {e}");
+        JsValue::NULL
+    })
+}
@@ -0,0 +1,62 @@
+import * as vscode from 'vscode';
+import { find_doc_code_blocks, find_test_functions } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+interface TestFunction {
+    line: number;
+    name: string;
+}
+
+interface DocCodeBlock {
+    line: number;
+}
+
+/**
+ * Shows Run Test and Debug Test above every test function of a Rust file and Run Doctest
+ * and Debug Doctest above the Rust code blocks of its doc comments
+ */
+export class TestLensProvider implements vscode.CodeLensProvider {
+    private changed = new vscode.EventEmitter<void>();
+    readonly onDidChangeCodeLenses = this.changed.event;
+
+    constructor() {
+        extension_context?.subscriptions.push(
+            this.changed,
+            vscode.languages.registerCodeLensProvider({ language: 'rust', scheme: 'file' }, this),
+            vscode.workspace.onDidChangeConfiguration((e) => {
+                if (e.affectsConfiguration('cargoTools.testCodeLens.enabled')) {
+                    this.changed.fire();
+                }
+            })
+        );
+    }
+
+    provideCodeLenses(document: vscode.TextDocument): vscode.CodeLens[] {
+        const enabled = vscode.workspace.getConfiguration('cargoTools').get<boolean>('testCodeLens.enabled', true);
+        if (!enabled) {
+            return [];
+        }
+
+        const tests: TestFunction[] = find_test_functions(document.getText()) ?? [];
+        const testLenses = tests.flatMap((test) => {
+            const range = new vscode.Range(test.line, 0, test.line, 0);
+            const args = [document.uri.fsPath, test.name];
+            return [
+                new vscode.CodeLens(range, { title: '$(play) Run Test', command: 'cargo-tools.runTest', arguments: args }),
+                new vscode.CodeLens(range, { title: '$(debug-alt) Debug Test', command: 'cargo-tools.debugTest', arguments: args }),
+            ];
+        });
+
+        const blocks: DocCodeBlock[] = find_doc_code_blocks(document.getText()) ?? [];
+        const docTestLenses = blocks.flatMap((block) => {
+            const range = new vscode.Range(block.line, 0, block.line, 0);
+            const args = [document.uri.fsPath, block.line];
+            return [
+                new vscode.CodeLens(range, { title: '$(play) Run Doctest', command: 'cargo-tools.runDocTest', arguments: args }),
+                new vscode.CodeLens(range, { title: '$(debug-alt) Debug Doctest', command: 'cargo-tools.debugDocTest', arguments: args }),
+            ];
+        });
+
+        return [...testLenses, ...docTestLenses];
+    }
+}
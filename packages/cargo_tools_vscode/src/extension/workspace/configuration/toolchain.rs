@@ -0,0 +1,117 @@
+use cargo_tools::rustup::{self, Component, Toolchain};
+use tracing::error;
+
+use crate::{
+    environment::rustup_task_context,
+    quick_pick::SelectInput,
+    runtime::{VsCodeTask, exec_vs_code, execute_task_and_wait, show_warning_message},
+};
+
+const INSTALL: &str = "Install";
+
+/// Runs `rustup` with `args` in the workspace root and returns its output
+async fn rustup_output(args: &[&str]) -> Result<String, String> {
+    let args = args.iter().map(ToString::to_string).collect();
+    let process = rustup_task_context()
+        .try_into_process(args)
+        .map_err(|e| e.to_string())?;
+    exec_vs_code(process).await
+}
+
+/// Installs and removes the components of a picked toolchain. The components picked in the
+/// quick pick are installed, the unpicked ones removed.
+pub async fn manage_toolchain_components() {
+    let toolchains = match rustup_output(&["toolchain", "list"]).await {
+        Ok(output) => rustup::parse_toolchains(&output),
+        Err(e) => {
+            error!("Failed to list the rustup toolchains, is rustup installed? {e}");
+            return;
+        }
+    };
+    let toolchain = match toolchains.as_slice() {
+        [] => {
+            error!("rustup has no toolchains installed");
+            return;
+        }
+        [toolchain] => toolchain.clone(),
+        _ => {
+            let current: Vec<Toolchain> =
+                toolchains.iter().filter(|t| t.default).cloned().collect();
+            let input = SelectInput {
+                options: toolchains,
+                current,
+            };
+            let Some(toolchain) = input.select().await else {
+                return;
+            };
+            toolchain
+        }
+    };
+
+    let components =
+        match rustup_output(&["component", "list", "--toolchain", &toolchain.name]).await {
+            Ok(output) => rustup::parse_components(&output),
+            Err(e) => {
+                error!("Failed to list the components of {}: {e}", toolchain.name);
+                return;
+            }
+        };
+    let installed: Vec<Component> = components.iter().filter(|c| c.installed).cloned().collect();
+    let input = SelectInput {
+        options: components.clone(),
+        current: installed,
+    };
+    let Some(picked) = input.select_multiple(|_| {}).await else {
+        return;
+    };
+
+    let add: Vec<&str> = picked
+        .iter()
+        .filter(|c| !c.installed)
+        .map(|c| c.name)
+        .collect();
+    let remove: Vec<&str> = components
+        .iter()
+        .filter(|c| c.installed && !picked.iter().any(|p| p.name == c.name))
+        .map(|c| c.name)
+        .collect();
+    for (action, names) in [("add", add), ("remove", remove)] {
+        if names.is_empty() {
+            continue;
+        }
+        let args = rustup::component_args(action, &names, Some(&toolchain.name));
+        match rustup_task_context().try_into_process(args) {
+            Ok(process) => {
+                execute_task_and_wait(VsCodeTask::rustup(process)).await;
+            }
+            Err(e) => error!("{e}"),
+        }
+    }
+}
+
+/// Warns if the toolchain of the workspace lacks `component` and offers to install it.
+/// Workspaces without rustup are not checked.
+pub async fn warn_missing_component(component: &'static str) {
+    let Ok(output) = rustup_output(&["component", "list"]).await else {
+        return;
+    };
+    let missing = rustup::parse_components(&output)
+        .iter()
+        .any(|c| c.name == component && !c.installed);
+    if !missing {
+        return;
+    }
+
+    let message = format!("The toolchain of the workspace is missing the {component} component");
+    let action = show_warning_message(&message, vec![INSTALL.to_string()]).await;
+    if action.as_string().as_deref() != Some(INSTALL) {
+        return;
+    }
+    match rustup_task_context().try_into_process(rustup::component_args("add", &[component], None))
+    {
+        Ok(process) => {
+            execute_task_and_wait(VsCodeTask::rustup(process)).await;
+        }
+        Err(e) => error!("{e}"),
+    }
+}
@@ -1,43 +1,144 @@
-use std::{collections::HashMap, iter, path::PathBuf};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    iter,
+    path::{Path, PathBuf},
+};
 
 use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
 
 use cargo_tools::{
     CargoCommand,
     cargo::{
-        Config, ConfigUpdate, Features, Profile,
-        command::{BenchTarget, BuildSubTarget, BuildTarget, RunSubTarget, RunTarget},
+        BinarySizeHistory, BuildQueue, BuildStatus, Config, ConfigUpdate, DocTest, DocTestCache,
+        DocTestLocation, Features, IgnoredTests, InstaUpdate, Sanitizer, Template, TestCase,
+        TestHistory, TestOutcome, TestSelection, TestTree,
+        command::{BenchTarget, BuildTarget, RunSubTarget, RunTarget},
         config::FeatureTarget,
-        metadata::Metadata,
+        coverage::{self, FileCoverage},
+        criterion,
+        diagnostics::{FileDiagnostic, JSON_MESSAGE_FORMAT, parse_diagnostics},
+        doc_test::source_hashes,
+        insta::{PENDING_SNAPSHOT_EXTENSION, accepted_snapshot},
+        libtest::parse_results,
+        metadata::{
+            Metadata, Package, RegistryDependency, TargetType, WATCHED_PATTERNS, affected_packages,
+        },
+        new_package::{self, MemberDependency, PackageKind},
+        nextest::{self, DEFAULT_PROFILE, ToolConfig},
+        test_history::TestRecord,
+        test_tree::TestItem,
     },
+    cbindgen,
+    output::BoundedOutput,
+    probe_rs::{self, ProbeRsCommand},
     process::Process,
+    rust_analyzer::RustAnalyzerSettings,
+    rustup::{self, LLVM_TOOLS},
+    sccache::{self, Stats},
+    selection::Selection,
+    wasm,
+};
+use futures::{
+    FutureExt, SinkExt, StreamExt,
+    channel::mpsc::{Sender, channel},
+    future::LocalBoxFuture,
+    stream::FuturesUnordered,
 };
-use futures::{SinkExt, channel::mpsc::channel};
 use iced_viewless::Task;
+use serde::Serialize;
 
 use crate::{
-    environment::CommandExt,
+    environment::{
+        CommandExt, binary_size_jump_threshold, build_parallel_jobs, cargo_command,
+        cargo_generate_templates, cbindgen_generate_on_build, check_command, check_on_save_command,
+        debug_build_context, large_workspace_threshold, metadata_task_context, nextest_junit_path,
+        nextest_runner, nextest_test_timeout, output_max_lines, path_mappings, pre_commit_checks,
+        probe_rs_chip, project_state_root, remote_target, rustc_wrapper, set_selection_env,
+        sync_rust_analyzer_selection, test_changed_base_ref, test_retries, test_timeout,
+        watch_command,
+    },
     extension::{
-        CommandBinding,
+        CommandBinding, send_file_changed,
+        tasks::history::show_package_builds,
+        vscode_task_utils::{QueryBinding, QueryRequest, send_task_messages},
         workspace::configuration::{
-            command::{Command, register_configuration_commands},
+            benchmarks::{BenchmarkReportsView, load_benchmark_reports, open_criterion_report},
+            binary_size::{read_executable_sizes, show_binary_size_trend, warn_size_jumps},
+            build_results::BuildResultsView,
+            cbindgen::generate_c_header,
+            command::{
+                Command, ManifestLensAction, Variable, register_configuration_commands,
+                register_variable_commands,
+            },
+            dependency_docs::{
+                DependencyDocs, DependencyDocsHoverProvider, active_dependency_docs,
+                update_dependency,
+            },
+            diagnostics::CargoDiagnostics,
+            feature_unification::explain_features,
+            licenses::license_report,
+            manifest_lens::ManifestLensProvider,
+            msrv::{set_rust_version, verify_rust_version},
+            new_target::new_target,
+            normalize_manifest::normalize_manifests,
+            output_links::OutputLinkProvider,
+            pre_commit::{PreCommitChecksView, install_pre_commit_hook, run_pre_commit_checks},
+            problem_count::ProblemCountStatus,
+            registries::{add_dependency, publish_package, set_registry_token},
+            rename_package::rename_package,
+            slowest_tests::{SlowestTestsView, no_durations_message},
+            task_provider::{
+                CargoTaskProvider, CargoTaskProviderHandler, ProvidedTask, TaskKind,
+                TaskProviderRequest, make_default_build_task,
+            },
+            test_explorer::{
+                TestExplorer, TestExplorerHandler, TestExplorerRequest, TestItemResult,
+                TestRunResult,
+            },
+            test_lens::TestLensProvider,
+            toolchain::{manage_toolchain_components, warn_missing_component},
             treeprovider::{CargoConfigurationTreeProviderHandler, ConfigUiRequest, NodeData},
+            version_bump::bump_version,
+            wasm::{build_wasm, serve_wasm_package},
+            workspace_dependencies::{
+                WorkspaceDependencyActionProvider, promote_to_workspace_dependency,
+            },
         },
     },
-    quick_pick::SelectInput,
+    quick_pick::{SelectInput, show_input_box, show_input_box_with_value, show_input_with_history},
+    recent_items::RecentItems,
+    runtime::{
+        CHANNEL_CAPACITY, FileStat, TsFileWatcher, TsSaveWatcher, TsTaskMessagesWatcher,
+        VsCodeTask, delete_file_vs_code, exec_output_with_timeout_vs_code, exec_stdout_vs_code,
+        exec_vs_code, execute_task_and_wait, get_state_vs_code, persist_state_vs_code,
+        read_file_vs_code, write_file_vs_code,
+    },
     runtime::{
-        CHANNEL_CAPACITY, VsCodeTask, exec_vs_code, get_state_vs_code, persist_state_vs_code,
+        JsValueExt, attach_to_process, build_and_debug, build_and_debug_bench,
+        build_and_debug_doc_test, build_and_debug_tests, build_and_flash, build_executable,
+        cancel_tasks, debug_core_dump, debug_last, execute, execute_task, host_triple, host_var,
+        into_clean_env, now_ms, open_url, set_embedded_target_context, set_wasm_package_context,
     },
-    runtime::{JsValueExt, debug, execute_task, host_platform},
 };
-use tracing::error;
+use tracing::{error, info};
 
 #[wasm_bindgen(
     raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/ui.ts"
 )]
 extern "C" {
     fn get_rust_analyzer_check_targets() -> Vec<String>;
-    async fn update_rust_analyzer_check_targets(targets: Vec<String>);
+    fn show_build_queue_status(status: Option<String>);
+    fn show_build_queue_summary(summary: String, success: bool);
+    fn show_watch_status(active: bool);
+    fn show_tests_skipped();
+    async fn show_compiler_cache_stats(markdown: String);
+    async fn open_file(path: String);
+    async fn confirm_workspace_member(member: String) -> JsValue;
+    fn show_compiler_cache_stats_failed(reason: String);
+    async fn find_pending_snapshots(root: String, extension: &str) -> JsValue;
+    async fn review_snapshot(pending: String, accepted: String) -> JsValue;
+    async fn update_rust_analyzer_settings(settings: JsValue);
 
     type CargoConfigurationTreeProvider;
 
@@ -48,23 +149,201 @@ extern "C" {
     fn update(this: &CargoConfigurationTreeProvider);
 }
 
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/api.ts"
+)]
+extern "C" {
+    fn publish_selection(selection: JsValue);
+}
+
 #[derive(Debug)]
 pub enum Message {
     ManifestFilesChanged,
     ConfigChanged(ConfigUpdate),
+    /// Updates applied together, so the views and the selection consumers refresh only once
+    ConfigsChanged(Vec<ConfigUpdate>),
     Cmd(Command),
     ConfigUiRequest(ConfigUiRequest),
+    TaskProviderRequest(TaskProviderRequest),
+    VariableRequest(QueryRequest<Variable>),
+    WatchedFilesChanged,
+    WatchRunFinished,
+    CleanEnvironmentRun(TaskKind),
+    /// One-off program arguments entered for a run or debug session
+    RunWithArgs(String),
+    DebugWithArgs(String),
+    /// A sanitizer and the platform target the run target is built for with it
+    RunWithSanitizer(Sanitizer, String),
+    DebugWithSanitizer(Sanitizer, String),
+    /// A doctest of a package to debug or run
+    DebugDocTest(String, DocTest),
+    RunDocTest(String, DocTest),
+    TestExplorerRequest(TestExplorerRequest),
+    /// Shows a test picked from the slowest tests in the Testing view
+    RevealTest(String),
+    /// The files changed since the base ref of **Test Changed Packages**
+    ChangedFilesListed(Vec<String>),
+    /// The files changed since the last commit for **Run Pre-commit Checks**
+    PreCommitChangesListed(Vec<String>),
+    /// The `INSTA_UPDATE` mode picked for **Run Snapshot Tests**
+    RunSnapshotTests(InstaUpdate),
+    /// A pending snapshot picked for review, relative to the workspace root
+    ReviewSnapshot(String),
+    /// The doctests listed for the lazy doctest item `id` of the test explorer
+    DocTestsListed {
+        id: String,
+        tests: Vec<DocTest>,
+        /// The source hash and arguments of a new listing, [None] for cached doctests
+        listing: Option<(u64, Vec<String>)>,
+        tx: Sender<Vec<TestItem>>,
+    },
+    /// The outputs of the cargo invocations of a test explorer run
+    TestsRan {
+        outputs: TestOutputs,
+        run: TestRun,
+    },
+    FileSaved,
+    CheckOnSaveFinished(Vec<FileDiagnostic>),
+    /// **Refresh Build Results** finished
+    BuildResultsChecked(Vec<FileDiagnostic>),
+    /// The JSON messages of a finished build or test task
+    TaskMessages(String),
+    /// A build wrote the executables of bin targets
+    ExecutablesChanged,
+    /// The sizes of the existing bin executables by their path in the target directory
+    ExecutableSizesRead(Vec<(String, FileStat)>),
+    ShowBinarySizeTrend,
+    /// Criterion wrote reports of benchmarks
+    CriterionReportsChanged,
+    /// **New Package** or a new target of the outline edited the manifests
+    PackageCreated,
+    /// **Select Cargo Workspace** was run
+    SelectWorkspace,
+    /// **Reload Shell Environment** was run
+    ReloadShellEnv,
+    /// Another Cargo workspace of the folder is in use, its selection is loaded
+    ProjectChanged,
 }
 
 pub enum Event {
     ConfigUpdate,
+    /// Manifests were edited and the metadata needs to be parsed again
+    ManifestsEdited,
+    /// The user wants to pick one of the discovered Cargo workspaces
+    SelectWorkspace,
+    /// The user wants to capture the shell environment of direnv and the init command again
+    ReloadShellEnv,
 }
 
 pub struct Configuration {
     config: Config,
     ui: CargoConfigurationTreeProvider,
+    task_provider: CargoTaskProvider,
+    _test_lens: TestLensProvider,
+    output_links: OutputLinkProvider,
+    manifest_lens: ManifestLensProvider,
+    _dependency_docs: DependencyDocsHoverProvider,
+    _workspace_dependency_actions: WorkspaceDependencyActionProvider,
+    pre_commit_view: PreCommitChecksView,
+    test_explorer: TestExplorer,
+    /// The tests last discovered for the test explorer
+    test_tree: TestTree,
+    /// Ids of the tests which failed in their last run
+    failed_tests: BTreeSet<String>,
+    /// Recent outcomes and durations of the tests, persisted per workspace
+    test_history: TestHistory,
+    /// The doctests listed per package, persisted per workspace
+    doc_test_cache: DocTestCache,
+    /// The hashes of the package sources of the last test discovery, see [source_hashes]
+    source_hashes: HashMap<String, u64>,
     _cmds: Vec<CommandBinding>,
+    _variable_cmds: Vec<QueryBinding>,
     root_dir: String,
+    /// Where the selection of the Cargo workspace in use is persisted, see [project_state_root]
+    project: String,
+    /// Previously entered one-off program arguments
+    argument_history: RecentItems,
+    watch: Option<Watch>,
+    check_on_save: CheckOnSave,
+    /// The executables of the run targets from the messages of their last build
+    executables: HashMap<RunSubTarget, String>,
+    /// Watches the executables of the bin targets to record their sizes after builds
+    executable_watcher: TsFileWatcher,
+    /// The sizes of the bin executables after recent builds, persisted per workspace
+    binary_sizes: BinarySizeHistory,
+    benchmark_reports: BenchmarkReportsView,
+    slowest_tests: SlowestTestsView,
+    build_results: BuildResultsView,
+    /// Shows the compiler messages of finished build and test tasks in [Self::build_results]
+    _task_messages_watcher: TsTaskMessagesWatcher,
+    problem_count: ProblemCountStatus,
+    /// Watches the estimates criterion writes after each benchmark
+    criterion_watcher: TsFileWatcher,
+    /// The target directory [Self::criterion_watcher] watches
+    criterion_target_dir: Option<String>,
+}
+
+/// Tracks a command which is re-run on changes so that only one run is active at a time
+#[derive(Debug, Default)]
+struct Rerun {
+    running: bool,
+    /// Changes happened while the command was running
+    pending: bool,
+}
+
+impl Rerun {
+    /// Returns whether a run may start now, otherwise it is queued until the current one finishes
+    fn start(&mut self) -> bool {
+        if self.running {
+            self.pending = true;
+            return false;
+        }
+        self.running = true;
+        true
+    }
+
+    /// Returns whether changes are pending which require another run
+    fn finish(&mut self) -> bool {
+        self.running = false;
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Re-runs the watch command whenever a source file of the watched packages changes
+struct Watch {
+    file_watcher: TsFileWatcher,
+    rerun: Rerun,
+}
+
+/// Runs a lightweight check whenever a source file is saved, see [check_on_save_command]
+struct CheckOnSave {
+    _save_watcher: TsSaveWatcher,
+    rerun: Rerun,
+    /// The diagnostics of the last check with quick fixes for rustc's suggestions
+    diagnostics: CargoDiagnostics,
+}
+
+/// The outputs of the cargo invocations of a test explorer run
+#[derive(Debug)]
+pub struct TestOutputs {
+    /// The output by package
+    runs: Vec<(String, String)>,
+    coverage: Vec<FileCoverage>,
+    /// The run was stopped after `cargoTools.test.timeout`
+    timed_out: bool,
+}
+
+/// A test explorer run, its failed tests are retried up to `cargoTools.test.retries` times
+#[derive(Debug)]
+pub struct TestRun {
+    ignored: IgnoredTests,
+    harness_args: Vec<String>,
+    /// Identifies the processes of the run to cancel it
+    run_id: String,
+    /// Number of retries so far
+    attempt: usize,
+    result: TestRunResult,
+    tx: Sender<TestRunResult>,
 }
 
 impl Configuration {
@@ -72,21 +351,106 @@ impl Configuration {
         let (cmd_tx, cmd_rx) = channel(CHANNEL_CAPACITY);
         let _cmds = register_configuration_commands(cmd_tx);
 
-        let config: Config = get_state_vs_code(state_key(&root_dir)).unwrap_or_default();
+        let (variable_tx, variable_rx) = channel(CHANNEL_CAPACITY);
+        let _variable_cmds = register_variable_commands(variable_tx);
+
+        let project = project_state_root(&root_dir);
+        let config: Config = get_state_vs_code(state_key(&project)).unwrap_or_default();
+        let argument_history =
+            get_state_vs_code(argument_history_key(&project)).unwrap_or_default();
+        let test_history = get_state_vs_code(test_history_key(&project)).unwrap_or_default();
+        let doc_test_cache = get_state_vs_code(doc_tests_key(&project)).unwrap_or_default();
+        let binary_sizes = get_state_vs_code(binary_sizes_key(&project)).unwrap_or_default();
+
+        // Linkers write executables in several steps
+        let (executables_tx, executables_rx) = channel(CHANNEL_CAPACITY);
+        let executable_watcher = TsFileWatcher::new(send_file_changed(executables_tx));
+        executable_watcher.set_debounce(1000);
+
+        // Criterion writes the estimates of all benchmarks of a run one after the other
+        let (criterion_tx, criterion_rx) = channel(CHANNEL_CAPACITY);
+        let criterion_watcher = TsFileWatcher::new(send_file_changed(criterion_tx));
+        criterion_watcher.set_debounce(1000);
+
+        let (saved_tx, saved_rx) = channel(CHANNEL_CAPACITY);
+        let check_on_save = CheckOnSave {
+            _save_watcher: TsSaveWatcher::new(send_file_changed(saved_tx)),
+            rerun: Rerun::default(),
+            diagnostics: CargoDiagnostics::new(),
+        };
+
+        let (task_messages_tx, task_messages_rx) = channel(CHANNEL_CAPACITY);
+        let _task_messages_watcher =
+            TsTaskMessagesWatcher::new(send_task_messages(task_messages_tx));
 
         let (ui_tx, ui_rx) = channel(CHANNEL_CAPACITY);
         let handler = CargoConfigurationTreeProviderHandler::new(ui_tx);
 
+        let (task_provider_tx, task_provider_rx) = channel(CHANNEL_CAPACITY);
+        let task_provider_handler = CargoTaskProviderHandler::new(task_provider_tx);
+
+        let (test_explorer_tx, test_explorer_rx) = channel(CHANNEL_CAPACITY);
+        let test_explorer_handler = TestExplorerHandler::new(test_explorer_tx);
+
         let this = Self {
             config,
             ui: CargoConfigurationTreeProvider::new(handler),
+            task_provider: CargoTaskProvider::new(task_provider_handler),
+            _test_lens: TestLensProvider::new(),
+            output_links: OutputLinkProvider::new(),
+            manifest_lens: ManifestLensProvider::new(),
+            _dependency_docs: DependencyDocsHoverProvider::new(),
+            _workspace_dependency_actions: WorkspaceDependencyActionProvider::new(),
+            pre_commit_view: PreCommitChecksView::new(),
+            test_explorer: TestExplorer::new(test_explorer_handler),
+            test_tree: TestTree::default(),
+            failed_tests: BTreeSet::new(),
             _cmds,
+            _variable_cmds,
             root_dir,
+            project,
+            argument_history,
+            test_history,
+            doc_test_cache,
+            source_hashes: HashMap::new(),
+            watch: None,
+            check_on_save,
+            executables: HashMap::new(),
+            executable_watcher,
+            binary_sizes,
+            benchmark_reports: BenchmarkReportsView::new(),
+            slowest_tests: SlowestTestsView::new(),
+            build_results: BuildResultsView::new(),
+            _task_messages_watcher,
+            problem_count: ProblemCountStatus::new(),
+            criterion_watcher,
+            criterion_target_dir: None,
         };
 
         let cmd = Task::stream(cmd_rx).map(Message::Cmd);
         let ui_config_request = Task::stream(ui_rx).map(Message::ConfigUiRequest);
-        let tasks = Task::batch([cmd, ui_config_request]);
+        let task_provider_request =
+            Task::stream(task_provider_rx).map(Message::TaskProviderRequest);
+        let variable_request = Task::stream(variable_rx).map(Message::VariableRequest);
+        let test_explorer_request =
+            Task::stream(test_explorer_rx).map(Message::TestExplorerRequest);
+        let file_saved = Task::stream(saved_rx).map(|()| Message::FileSaved);
+        let executables_changed =
+            Task::stream(executables_rx).map(|()| Message::ExecutablesChanged);
+        let criterion_reports_changed =
+            Task::stream(criterion_rx).map(|()| Message::CriterionReportsChanged);
+        let task_messages = Task::stream(task_messages_rx).map(Message::TaskMessages);
+        let tasks = Task::batch([
+            cmd,
+            ui_config_request,
+            task_provider_request,
+            variable_request,
+            test_explorer_request,
+            file_saved,
+            executables_changed,
+            criterion_reports_changed,
+            task_messages,
+        ]);
 
         (this, tasks)
     }
@@ -96,19 +460,25 @@ impl Configuration {
             Message::ManifestFilesChanged => {
                 // Features which are potentially are visible could have changed so we need to update the UI
                 self.ui.update();
-                (Task::none(), None)
-            }
-            Message::ConfigChanged(update) => {
-                self.config.update(update);
-                self.ui.update();
-
-                let task = Task::future(persist_state_vs_code(
-                    state_key(&self.root_dir),
-                    self.config.clone(),
-                ))
-                .discard();
-                (task, Some(Event::ConfigUpdate))
+                self.update_watched_files(metadata);
+                self.update_watched_executables(metadata);
+                self.output_links.set_roots(output_link_roots(metadata));
+                self.task_provider.invalidate();
+                self.test_explorer.refresh();
+                self.publish_selection(metadata);
+                self.publish_feature_selection(metadata);
+                self.update_criterion_watcher(metadata);
+                (
+                    Task::batch([
+                        self.embedded_target_context(),
+                        self.wasm_package_context(metadata),
+                        self.load_benchmark_reports(metadata),
+                    ]),
+                    None,
+                )
             }
+            Message::ConfigChanged(update) => self.config_changed(vec![update], metadata),
+            Message::ConfigsChanged(updates) => self.config_changed(updates, metadata),
             Message::Cmd(cmd) => (self.handle_cmd(cmd, metadata), None),
             Message::ConfigUiRequest(request) => {
                 let ConfigUiRequest { mut tx, node_type } = request;
@@ -124,10 +494,405 @@ impl Configuration {
                     None,
                 )
             }
+            Message::TaskProviderRequest(request) => {
+                let TaskProviderRequest { mut tx, kinds } = request;
+
+                let tasks = self.provided_tasks(kinds);
+                (
+                    Task::future(async move { tx.send(tasks).await }).discard(),
+                    None,
+                )
+            }
+            Message::VariableRequest(request) => {
+                let QueryRequest { query, mut tx } = request;
+
+                let value = self.variable(query);
+                let build = match query {
+                    Variable::LaunchTargetPath => self.selected_executable_build(metadata),
+                    _ => None,
+                };
+                let value = async move {
+                    match build {
+                        Some((process, target)) => {
+                            build_executable(process, &target, "launching").await
+                        }
+                        None => value,
+                    }
+                };
+                (
+                    Task::future(async move { tx.send(value.await).await }).discard(),
+                    None,
+                )
+            }
+            Message::WatchedFilesChanged => (self.run_watch_command(), None),
+            Message::WatchRunFinished => {
+                let Some(watch) = self.watch.as_mut() else {
+                    return (Task::none(), None);
+                };
+                let task = if watch.rerun.finish() {
+                    self.run_watch_command()
+                } else {
+                    Task::none()
+                };
+                (task, None)
+            }
+            Message::CleanEnvironmentRun(kind) => (self.clean_environment_run(kind), None),
+            Message::DebugDocTest(package, test) => {
+                (self.debug_doc_test(package, test, metadata), None)
+            }
+            Message::RunDocTest(package, test) => (
+                self.cmd_exec(CargoCommand::RunDocTest { package, test }),
+                None,
+            ),
+            Message::TestExplorerRequest(request) => {
+                (self.handle_test_explorer_request(request, metadata), None)
+            }
+            Message::ChangedFilesListed(files) => {
+                let packages = affected_packages(metadata.packages(), &files);
+                if packages.is_empty() {
+                    info!(
+                        "No workspace member changed since {}",
+                        test_changed_base_ref()
+                    );
+                    return (Task::none(), None);
+                }
+                info!("Testing changed packages {}", packages.join(", "));
+                (
+                    self.test_exec(CargoCommand::TestPackages(packages), metadata),
+                    None,
+                )
+            }
+            Message::PreCommitChangesListed(files) => {
+                let packages = affected_packages(metadata.packages(), &files);
+                if packages.is_empty() {
+                    info!("No workspace member changed since the last commit");
+                    return (Task::none(), None);
+                }
+                info!("Running pre-commit checks of {}", packages.join(", "));
+                let checks = run_pre_commit_checks(
+                    self.pre_commit_view.clone(),
+                    pre_commit_checks(),
+                    packages,
+                    self.config.clone(),
+                );
+                (Task::future(checks).discard(), None)
+            }
+            Message::RevealTest(id) => {
+                self.test_explorer.reveal_test(id);
+                (Task::none(), None)
+            }
+            Message::RunSnapshotTests(update) => (self.run_snapshot_tests(update), None),
+            Message::ReviewSnapshot(pending) => {
+                let Some(accepted) = accepted_snapshot(&pending) else {
+                    return (Task::none(), None);
+                };
+                let root = PathBuf::from(metadata.workspace_root());
+                let [pending, accepted] = [pending.as_str(), accepted]
+                    .map(|path| root.join(path).to_string_lossy().to_string());
+                // Continue with the next pending snapshot until the review is dismissed
+                let review = Task::future(async move {
+                    review_snapshot(pending, accepted)
+                        .await
+                        .as_bool()
+                        .unwrap_or_default()
+                        .then_some(Command::ReviewSnapshots)
+                })
+                .and_then(Task::done)
+                .map(Message::Cmd);
+                (review, None)
+            }
+            Message::DocTestsListed {
+                id,
+                tests,
+                listing,
+                mut tx,
+            } => {
+                let persist = match (listing, self.test_tree.selection(&id)) {
+                    (Some((source_hash, args)), Some(TestSelection::DocTests(package))) => {
+                        self.doc_test_cache.insert(
+                            package.clone(),
+                            source_hash,
+                            &args,
+                            tests.clone(),
+                        );
+                        Task::future(persist_state_vs_code(
+                            doc_tests_key(&self.project),
+                            self.doc_test_cache.clone(),
+                        ))
+                        .discard()
+                    }
+                    _ => Task::none(),
+                };
+                let items = match self.test_tree.selection(&id) {
+                    Some(TestSelection::DocTests(package)) => metadata
+                        .packages()
+                        .iter()
+                        .find(|p| p.name == *package)
+                        .map(|package| self.test_tree.add_doc_tests(package, tests))
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                };
+                let send = Task::future(async move { tx.send(items).await }).discard();
+                (Task::batch([send, persist]), None)
+            }
+            Message::TestsRan { outputs, mut run } => {
+                let TestOutputs {
+                    runs,
+                    coverage,
+                    timed_out,
+                } = outputs;
+                // Retries only run the failed tests, so only the first run has the coverage
+                if run.attempt == 0 {
+                    run.result.coverage = coverage;
+                }
+                if timed_out {
+                    let timeout_secs = test_timeout().map(|t| t.as_secs());
+                    run.result.timeout_secs = timeout_secs;
+                    run.result.output.push_str(&format!(
+                        "\nStopped the test run after the timeout of {} s\n",
+                        timeout_secs.unwrap_or_default()
+                    ));
+                }
+                for (package, output) in runs {
+                    let Some(package) = metadata.packages().iter().find(|p| p.name == package)
+                    else {
+                        continue;
+                    };
+                    for test in parse_results(&output) {
+                        let Some(id) = self.test_tree.result_id(package, &test) else {
+                            continue;
+                        };
+                        if test.outcome == TestOutcome::Failed {
+                            self.failed_tests.insert(id.clone());
+                        } else {
+                            self.failed_tests.remove(&id);
+                        }
+                        let passed = test.outcome == TestOutcome::Passed;
+                        let duration_ms = test.duration.map(|d| d.as_millis() as u64);
+                        if test.outcome != TestOutcome::Ignored {
+                            self.test_history.record(
+                                id.clone(),
+                                TestRecord {
+                                    passed,
+                                    duration_ms,
+                                },
+                            );
+                        }
+                        let regressed = duration_ms.is_some()
+                            && self
+                                .test_history
+                                .stats(&id)
+                                .is_some_and(|stats| stats.regressed);
+                        let mut result = TestItemResult::new(
+                            id,
+                            test.outcome,
+                            test.message,
+                            metadata.workspace_root(),
+                        );
+                        // Passing only on a retry makes a test flaky
+                        result.flaky = passed && run.attempt > 0;
+                        result.duration_ms = duration_ms;
+                        result.regressed = regressed;
+                        match run.result.results.iter_mut().find(|r| r.id == result.id) {
+                            Some(previous) => *previous = result,
+                            None => run.result.results.push(result),
+                        }
+                    }
+                    run.result.output.push_str(&output);
+                }
+
+                let failed: Vec<String> = run
+                    .result
+                    .results
+                    .iter()
+                    .filter(|r| r.outcome == "failed")
+                    .map(|r| r.id.clone())
+                    .collect();
+                let retries = test_retries();
+                // Hanging tests would only time out again
+                if !failed.is_empty() && run.attempt < retries && !timed_out {
+                    run.attempt += 1;
+                    run.result.output.push_str(&format!(
+                        "\nRetrying {} failed tests, attempt {} of {retries}\n",
+                        failed.len(),
+                        run.attempt
+                    ));
+                    let tests = self.run_tests(
+                        &failed,
+                        false,
+                        run.ignored,
+                        &run.harness_args,
+                        &run.run_id,
+                        metadata,
+                    );
+                    let task = Task::future(async move {
+                        let outputs = tests.await;
+                        Message::TestsRan { outputs, run }
+                    });
+                    return (task, None);
+                }
+
+                let TestRun {
+                    mut result, mut tx, ..
+                } = run;
+                let persist = Task::future(persist_state_vs_code(
+                    test_history_key(&self.project),
+                    self.test_history.clone(),
+                ))
+                .discard();
+                self.update_slowest_tests();
+                // The log file has to exist once the result refers to it
+                let write_log = bound_test_output(&mut result, metadata);
+                let send = Task::future(async move { tx.send(result).await }).discard();
+                (Task::batch([write_log.chain(send), persist]), None)
+            }
+            Message::RunWithArgs(args) => {
+                let persist = self.record_arguments(args.clone());
+                (Task::batch([persist, self.run_with_args(&args)]), None)
+            }
+            Message::DebugWithArgs(args) => {
+                let persist = self.record_arguments(args.clone());
+                let debug = match self.config.selected_package.clone() {
+                    Some(package) => {
+                        let target = self.config.get(&package, |s| s.run_target.clone());
+                        let args = args.split_whitespace().map(str::to_string).collect();
+                        self.debug(RunTarget { package, target }, metadata, args, None)
+                    }
+                    None => Task::none(),
+                };
+                (Task::batch([persist, debug]), None)
+            }
+            Message::RunWithSanitizer(sanitizer, platform_target) => {
+                (self.run_with_sanitizer(sanitizer, platform_target), None)
+            }
+            Message::DebugWithSanitizer(sanitizer, platform_target) => {
+                let debug = match self.config.selected_package.clone() {
+                    Some(package) => {
+                        let target = self.config.get(&package, |s| s.run_target.clone());
+                        let sanitizer = Some((sanitizer, platform_target));
+                        self.debug(
+                            RunTarget { package, target },
+                            metadata,
+                            Vec::new(),
+                            sanitizer,
+                        )
+                    }
+                    None => Task::none(),
+                };
+                (debug, None)
+            }
+            Message::FileSaved => (self.run_check_on_save(metadata), None),
+            Message::PackageCreated => (Task::none(), Some(Event::ManifestsEdited)),
+            Message::SelectWorkspace => (Task::none(), Some(Event::SelectWorkspace)),
+            Message::ReloadShellEnv => (Task::none(), Some(Event::ReloadShellEnv)),
+            Message::ProjectChanged => {
+                self.project = project_state_root(&self.root_dir);
+                self.config = get_state_vs_code(state_key(&self.project)).unwrap_or_default();
+                self.argument_history =
+                    get_state_vs_code(argument_history_key(&self.project)).unwrap_or_default();
+                self.test_history =
+                    get_state_vs_code(test_history_key(&self.project)).unwrap_or_default();
+                self.update_slowest_tests();
+                self.doc_test_cache =
+                    get_state_vs_code(doc_tests_key(&self.project)).unwrap_or_default();
+                self.binary_sizes =
+                    get_state_vs_code(binary_sizes_key(&self.project)).unwrap_or_default();
+                self.source_hashes.clear();
+                self.test_tree = TestTree::default();
+                self.failed_tests.clear();
+                if self.watch.take().is_some() {
+                    show_watch_status(false);
+                }
+                self.ui.update();
+                (Task::none(), None)
+            }
+            Message::ExecutablesChanged => {
+                let read = read_executable_sizes(
+                    metadata.target_dir().to_string(),
+                    self.bin_executables(metadata),
+                );
+                (Task::future(read).map(Message::ExecutableSizesRead), None)
+            }
+            Message::ExecutableSizesRead(sizes) => (self.record_binary_sizes(sizes), None),
+            Message::CriterionReportsChanged => (self.load_benchmark_reports(metadata), None),
+            Message::ShowBinarySizeTrend => {
+                show_binary_size_trend(&self.binary_sizes, true);
+                (Task::none(), None)
+            }
+            Message::CheckOnSaveFinished(diagnostics) => {
+                self.check_on_save.diagnostics.publish(&diagnostics);
+                self.build_results.show_results(&diagnostics);
+                let task = if self.check_on_save.rerun.finish() {
+                    self.run_check_on_save(metadata)
+                } else {
+                    Task::none()
+                };
+                (task, None)
+            }
+            Message::BuildResultsChecked(diagnostics) => {
+                self.check_on_save.diagnostics.publish(&diagnostics);
+                self.build_results.show_results(&diagnostics);
+                (Task::none(), None)
+            }
+            Message::TaskMessages(messages) => {
+                // The problem matcher of the task reports them in the Problems panel
+                let diagnostics = host_diagnostics(&messages, metadata.workspace_root());
+                self.build_results.show_results(&diagnostics);
+                let mappings = path_mappings();
+                let built: Vec<_> = RunSubTarget::executables_from_messages(&messages)
+                    .into_iter()
+                    .map(|(target, executable)| (target, mappings.to_host(&executable)))
+                    .collect();
+                if built.is_empty() {
+                    return (Task::none(), None);
+                }
+                self.executables.extend(built);
+                self.update_watched_executables(metadata);
+                self.publish_selection(metadata);
+                // The watcher only knows the executables built before
+                let read = read_executable_sizes(
+                    metadata.target_dir().to_string(),
+                    self.bin_executables(metadata),
+                );
+                (Task::future(read).map(Message::ExecutableSizesRead), None)
+            }
+        }
+    }
+
+    /// Applies all `updates` before refreshing the views and the consumers of the selection once
+    fn config_changed(
+        &mut self,
+        updates: Vec<ConfigUpdate>,
+        metadata: &Metadata,
+    ) -> (Task<Message>, Option<Event>) {
+        for update in updates {
+            self.config.update(update);
         }
+        self.ui.update();
+        self.update_watched_files(metadata);
+        self.update_watched_executables(metadata);
+        // Provided tasks are built from the selection
+        self.task_provider.invalidate();
+        self.publish_selection(metadata);
+        self.publish_feature_selection(metadata);
+
+        let persist = Task::future(persist_state_vs_code(
+            state_key(&self.project),
+            self.config.clone(),
+        ))
+        .discard();
+        (
+            Task::batch([
+                persist,
+                self.sync_rust_analyzer(),
+                self.embedded_target_context(),
+                self.wasm_package_context(metadata),
+            ]),
+            Some(Event::ConfigUpdate),
+        )
     }
 
-    fn handle_cmd(&self, cmd: Command, metadata: &Metadata) -> Task<Message> {
+    fn handle_cmd(&mut self, cmd: Command, metadata: &Metadata) -> Task<Message> {
         match cmd {
             Command::SelectProfile => {
                 let options = metadata.profiles().to_vec();
@@ -136,6 +901,30 @@ impl Configuration {
                 let input = SelectInput { options, current };
                 done(async move { input.select().await.map(ConfigUpdate::SelectedProfile) })
             }
+            Command::SelectNextestProfile => {
+                let config_path =
+                    PathBuf::from_iter([metadata.workspace_root(), ".config", "nextest.toml"])
+                        .to_string_lossy()
+                        .to_string();
+                let current = vec![
+                    self.config
+                        .nextest_profile
+                        .clone()
+                        .unwrap_or(DEFAULT_PROFILE.to_string()),
+                ];
+                done(async move {
+                    // Without a config nextest only has its default profile
+                    let config = read_file_vs_code(config_path).await.unwrap_or_default();
+                    let input = SelectInput {
+                        options: nextest::profiles(&config),
+                        current,
+                    };
+                    let profile = input.select().await?;
+                    Some(ConfigUpdate::SelectedNextestProfile(
+                        (profile != DEFAULT_PROFILE).then_some(profile),
+                    ))
+                })
+            }
             Command::SelectPackage => {
                 let options = iter::once(None)
                     .chain(metadata.packages().iter().map(|p| Some(p.name.clone())))
@@ -175,83 +964,1632 @@ impl Configuration {
                         .and_then(|s| s.benchmark_target.clone()),
                 ];
 
-                let input = Some(SelectInput { options, current });
-                done(async move {
-                    input?
-                        .select()
-                        .await
-                        .map(ConfigUpdate::SelectedBenchmarkTarget)
-                })
+                let input = Some(SelectInput { options, current });
+                done(async move {
+                    input?
+                        .select()
+                        .await
+                        .map(ConfigUpdate::SelectedBenchmarkTarget)
+                })
+            }
+            Command::SelectPlatformTarget => {
+                let current = self.config.platform_target.clone();
+                done(async move { select_platform_target(current.clone()).await })
+            }
+            Command::InstallPlatformTarget => Task::future(install_platform_target()).discard(),
+            Command::SetRustAnalyzerCheckTargets => {
+                Task::future(set_rust_analyzer_check_targets()).discard()
+            }
+            Command::BuildDocs => self.cmd_exec(CargoCommand::Doc),
+            Command::SelectFeatures => {
+                let options = self.config.feature_options(metadata);
+                let current = match self.config.selected_features() {
+                    Features::All => ["All features".to_string()].to_vec(),
+                    Features::Some(features) => features,
+                };
+
+                let input = Some(SelectInput { options, current });
+                let feature_target = self.config.feature_target();
+                done(async move { select_features(input, feature_target).await })
+            }
+            Command::Refresh => self.refresh(metadata),
+            Command::Clean => {
+                let package = self.config.selected_package.clone();
+                self.cmd_exec(CargoCommand::Clean { package })
+            }
+            Command::BuildPackages => {
+                let options = metadata.packages().iter().map(|p| p.name.clone()).collect();
+                let current = self.config.selected_package.iter().cloned().collect();
+
+                let input = SelectInput { options, current };
+                let packages = metadata.packages().to_vec();
+                let config = self.config.clone();
+                Task::future(async move {
+                    if let Some(selected) = input.select_multiple(|_| {}).await {
+                        build_packages(selected, packages, config).await;
+                    }
+                })
+                .discard()
+            }
+            Command::CancelBuild => Task::future(cancel_tasks()).discard(),
+            Command::ToggleWatch => self.toggle_watch(metadata),
+            Command::MakeDefaultBuildTask => Task::future(async {
+                if let Err(e) = make_default_build_task().await {
+                    error!("Failed to set default build task: {}", e.to_error_string());
+                }
+            })
+            .discard(),
+            Command::Build => self.build(metadata),
+            Command::Run => self.cmd_exec(self.task_cmd(TaskKind::Run)),
+            Command::Debug => match self.config.selected_package.clone() {
+                Some(package) => {
+                    let target = self.config.get(&package, |s| s.run_target.clone());
+                    self.debug(RunTarget { package, target }, metadata, Vec::new(), None)
+                }
+                None => Task::none(),
+            },
+            Command::RunWithArgs => self.prompt_arguments("Run").map(Message::RunWithArgs),
+            Command::DebugWithArgs => self.prompt_arguments("Debug").map(Message::DebugWithArgs),
+            Command::RunWithSanitizer => self
+                .select_sanitizer()
+                .map(|(sanitizer, target)| Message::RunWithSanitizer(sanitizer, target)),
+            Command::DebugWithSanitizer => self
+                .select_sanitizer()
+                .map(|(sanitizer, target)| Message::DebugWithSanitizer(sanitizer, target)),
+            Command::BuildAndTest => self.build_and_test(),
+            Command::RunInCleanEnvironment => {
+                let options = TaskKind::all().map(|kind| kind.name().to_string()).to_vec();
+                let input = SelectInput {
+                    options,
+                    current: Vec::new(),
+                };
+                Task::future(async move { input.select().await })
+                    .and_then(|name| Task::done(TaskKind::from_name(&name)))
+                    .and_then(Task::done)
+                    .map(Message::CleanEnvironmentRun)
+            }
+            Command::RunTest { file, name } => {
+                match TestCase::locate(metadata.packages(), &file, &name) {
+                    Some(test) => self.test_exec(CargoCommand::RunTest(test), metadata),
+                    None => {
+                        error!("No cargo target found for test {name} in {file}");
+                        Task::none()
+                    }
+                }
+            }
+            Command::DebugTest { file, name } => {
+                match TestCase::locate(metadata.packages(), &file, &name) {
+                    Some(test) => self.debug_test(test),
+                    None => {
+                        error!("No cargo target found for test {name} in {file}");
+                        Task::none()
+                    }
+                }
+            }
+            Command::DebugDocTest { location: None } => {
+                let Some(package) = self.config.selected_package.clone() else {
+                    error!("Select a package to debug its doctests");
+                    return Task::none();
+                };
+                let cmd = CargoCommand::ListDocTests {
+                    package: package.clone(),
+                };
+                let ctx = cmd.ctx();
+                match cmd.try_into_process(&self.config, ctx) {
+                    Ok(process) => Task::future(select_doc_test(process))
+                        .and_then(Task::done)
+                        .map(move |test| Message::DebugDocTest(package.clone(), test)),
+                    Err(e) => {
+                        error!("{e}");
+                        Task::none()
+                    }
+                }
+            }
+            Command::DebugDocTest {
+                location: Some((file, line)),
+            } => self
+                .locate_doc_test(&file, line, metadata)
+                .map(|(package, test)| Message::DebugDocTest(package, test)),
+            Command::RunDocTest { file, line } => self
+                .locate_doc_test(&file, line, metadata)
+                .map(|(package, test)| Message::RunDocTest(package, test)),
+            Command::DebugLast => Task::future(debug_last()).discard(),
+            Command::RerunFailedTests => {
+                if self.failed_tests.is_empty() {
+                    info!("No tests failed in their last run");
+                } else {
+                    self.test_explorer
+                        .run_tests(self.failed_tests.iter().cloned().collect());
+                }
+                Task::none()
+            }
+            Command::ShowSlowestTests(Some(id)) => Task::done(Message::RevealTest(id)),
+            Command::ShowSlowestTests(None) => {
+                self.slowest_tests.reveal();
+                if !self.test_history.slowest().is_empty() {
+                    self.update_slowest_tests();
+                    return Task::none();
+                }
+                let view = self.slowest_tests.clone();
+                let history = self.test_history.clone();
+                Task::future(async move {
+                    view.show_history(&history, Some(no_durations_message().await));
+                })
+                .discard()
+            }
+            Command::TestChangedPackages => Task::future(changed_files(
+                metadata.workspace_root().to_string(),
+                test_changed_base_ref(),
+            ))
+            .and_then(Task::done)
+            .map(Message::ChangedFilesListed),
+            Command::RunSnapshotTests => {
+                let input = SelectInput {
+                    options: InstaUpdate::all().to_vec(),
+                    current: Vec::new(),
+                };
+                Task::future(async move { input.select().await })
+                    .and_then(Task::done)
+                    .map(Message::RunSnapshotTests)
+            }
+            Command::ReviewSnapshots => {
+                let root = metadata.workspace_root().to_string();
+                Task::future(async move {
+                    let pending = find_pending_snapshots(root, PENDING_SNAPSHOT_EXTENSION).await;
+                    let options: Vec<String> =
+                        serde_wasm_bindgen::from_value(pending).unwrap_or_default();
+                    if options.is_empty() {
+                        info!("No pending snapshots to review");
+                        return None;
+                    }
+                    let input = SelectInput {
+                        options,
+                        current: Vec::new(),
+                    };
+                    input.select().await
+                })
+                .and_then(Task::done)
+                .map(Message::ReviewSnapshot)
+            }
+            Command::OpenCoreDump => self.open_core_dump(metadata),
+            Command::ShowBinarySizeTrend => Task::done(Message::ShowBinarySizeTrend),
+            Command::OpenBenchmarkReport(directory) => {
+                let report_dir = criterion_report_dir(metadata);
+                // Without a benchmark the summary of all benchmarks is opened
+                let (file, title) = match directory {
+                    Some(directory) => (
+                        format!("{report_dir}/{directory}/report/index.html"),
+                        directory,
+                    ),
+                    None => (
+                        format!("{report_dir}/report/index.html"),
+                        "Benchmark Reports".to_string(),
+                    ),
+                };
+                Task::future(open_criterion_report(report_dir, file, title)).discard()
+            }
+            Command::RefreshBuildResults => {
+                let cmd = check_command(self.config.selected_package.clone());
+                let ctx = cmd.ctx();
+                match cmd.try_into_process(&self.config, ctx) {
+                    Ok(process) => Task::future(check_diagnostics(
+                        process,
+                        metadata.workspace_root().to_string(),
+                    ))
+                    .map(Message::BuildResultsChecked),
+                    Err(e) => {
+                        error!("{e}");
+                        Task::none()
+                    }
+                }
+            }
+            Command::FilterBuildResults => {
+                self.build_results.pick_filter();
+                Task::none()
+            }
+            Command::ShowPackageProblems => {
+                self.build_results
+                    .show_package(self.config.selected_package.clone());
+                Task::none()
+            }
+            Command::ShowCompilerCacheStats => {
+                let sccache = rustc_wrapper(host_var("RUSTC_WRAPPER"))
+                    .filter(|wrapper| sccache::is_sccache(wrapper));
+                Task::future(compiler_cache_stats(sccache)).discard()
+            }
+            Command::NewPackage => {
+                let libraries = metadata
+                    .packages()
+                    .iter()
+                    .filter(|p| p.targets.iter().any(|t| t.target_type == TargetType::Lib))
+                    .map(|p| (p.name.clone(), PathBuf::from(p.manifest_dir())))
+                    .collect();
+                Task::future(new_package(
+                    metadata.workspace_root().to_string(),
+                    libraries,
+                ))
+                .and_then(Task::done)
+                .map(|()| Message::PackageCreated)
+            }
+            Command::NewPackageFromTemplate => Task::future(new_package_from_template(
+                metadata.workspace_root().to_string(),
+                cargo_generate_templates(),
+            ))
+            .and_then(Task::done)
+            .map(|()| Message::PackageCreated),
+            Command::NewTarget { package, kind } => {
+                let Some(manifest) = metadata
+                    .packages()
+                    .iter()
+                    .find(|p| p.name == package)
+                    .map(|p| p.manifest.clone())
+                else {
+                    error!("{package} is no member of the workspace");
+                    return Task::none();
+                };
+                let root = metadata.workspace_root().to_string();
+                Task::future(async move {
+                    let path = new_target(root, package, manifest, kind).await?;
+                    open_file(path).await;
+                    Some(())
+                })
+                .and_then(Task::done)
+                .map(|()| Message::PackageCreated)
+            }
+            Command::SelectWorkspace => Task::done(Message::SelectWorkspace),
+            Command::OpenDependencyDocs => {
+                // The dependencies of the selected package, of all packages without a selection
+                let mut options: Vec<RegistryDependency> = metadata
+                    .packages()
+                    .iter()
+                    .filter(|p| {
+                        self.config
+                            .selected_package
+                            .as_ref()
+                            .is_none_or(|s| *s == p.name)
+                    })
+                    .flat_map(|p| p.registry_dependencies.iter().cloned())
+                    .filter(RegistryDependency::is_crates_io)
+                    .collect();
+                options.sort();
+                options.dedup_by(|a, b| a.name == b.name);
+                let lockfile = format!("{}/Cargo.lock", metadata.workspace_root());
+                Task::future(open_dependency_docs(options, lockfile)).discard()
+            }
+            Command::BumpVersion => {
+                let packages = metadata
+                    .packages()
+                    .iter()
+                    .map(|p| (p.name.clone(), p.manifest.clone()))
+                    .collect();
+                Task::future(bump_version(
+                    metadata.workspace_root().to_string(),
+                    packages,
+                    self.config.selected_package.clone(),
+                ))
+                .discard()
+            }
+            Command::RenamePackage => {
+                let packages = metadata
+                    .packages()
+                    .iter()
+                    .map(|p| (p.name.clone(), p.manifest.clone()))
+                    .collect();
+                let selected = self.config.selected_package.clone();
+                Task::future(rename_package(
+                    metadata.workspace_root().to_string(),
+                    packages,
+                    selected.clone(),
+                ))
+                .and_then(Task::done)
+                .then(move |(old, new)| {
+                    // Keep the renamed package selected
+                    let selection = (selected.as_deref() == Some(old.as_str())).then(|| {
+                        Task::done(Message::ConfigChanged(ConfigUpdate::SelectedPackage(Some(
+                            new,
+                        ))))
+                    });
+                    selection
+                        .unwrap_or_else(Task::none)
+                        .chain(Task::done(Message::PackageCreated))
+                })
+            }
+            Command::NormalizeManifest => {
+                let packages = metadata
+                    .packages()
+                    .iter()
+                    .map(|p| (p.name.clone(), p.manifest.clone()))
+                    .collect();
+                Task::future(normalize_manifests(
+                    metadata.workspace_root().to_string(),
+                    packages,
+                    self.config.selected_package.clone(),
+                ))
+                .discard()
+            }
+            Command::LicenseReport => {
+                let manifest = format!("{}/Cargo.toml", metadata.workspace_root());
+                Task::future(license_report(manifest)).discard()
+            }
+            Command::ExplainFeatures => {
+                let manifest = format!("{}/Cargo.toml", metadata.workspace_root());
+                let members = metadata.packages().iter().map(|p| p.name.clone()).collect();
+                Task::future(explain_features(manifest, members)).discard()
+            }
+            Command::SetRustVersion | Command::VerifyRustVersion => {
+                let root = metadata.workspace_root().to_string();
+                let packages = metadata
+                    .packages()
+                    .iter()
+                    .map(|p| (p.name.clone(), p.manifest.clone()))
+                    .collect();
+                if matches!(cmd, Command::SetRustVersion) {
+                    Task::future(set_rust_version(root, packages)).discard()
+                } else {
+                    Task::future(verify_rust_version(root, packages)).discard()
+                }
+            }
+            Command::PromoteToWorkspaceDependency(name) => {
+                let root = format!("{}/Cargo.toml", metadata.workspace_root());
+                let members = metadata
+                    .packages()
+                    .iter()
+                    .map(|p| p.manifest.clone())
+                    .collect();
+                Task::future(promote_to_workspace_dependency(name, root, members)).discard()
+            }
+            Command::UpdateDependency { name, version } => Task::future(update_dependency(
+                metadata.workspace_root().to_string(),
+                name,
+                version,
+            ))
+            .discard(),
+            Command::RunPreCommitChecks => Task::future(changed_files(
+                metadata.workspace_root().to_string(),
+                "HEAD".to_string(),
+            ))
+            .and_then(Task::done)
+            .map(Message::PreCommitChangesListed),
+            Command::ReloadShellEnvironment => Task::done(Message::ReloadShellEnv),
+            Command::ManageToolchainComponents => {
+                Task::future(manage_toolchain_components()).discard()
+            }
+            Command::AddDependency => Task::future(add_dependency(
+                metadata.workspace_root().to_string(),
+                metadata.packages().iter().map(|p| p.name.clone()).collect(),
+                self.config.selected_package.clone(),
+                metadata.registries().clone(),
+            ))
+            .discard(),
+            Command::PublishPackage => Task::future(publish_package(
+                metadata.workspace_root().to_string(),
+                metadata.packages().iter().map(|p| p.name.clone()).collect(),
+                self.config.selected_package.clone(),
+                metadata.registries().clone(),
+            ))
+            .discard(),
+            Command::BuildWasm => match self.selected_package(metadata) {
+                Some(package) => Task::future(build_wasm(package, self.config.clone())).discard(),
+                None => {
+                    error!("Building for WASM needs a selected package");
+                    Task::none()
+                }
+            },
+            Command::ServeWasm => match self.selected_package(metadata) {
+                Some(package) => {
+                    Task::future(serve_wasm_package(package, self.config.clone())).discard()
+                }
+                None => {
+                    error!("Serving needs a selected package");
+                    Task::none()
+                }
+            },
+            Command::GenerateCHeader => match self.selected_package(metadata) {
+                Some(package) => Task::future(generate_c_header(
+                    package,
+                    metadata.target_dir().to_string(),
+                    self.config.clone(),
+                ))
+                .discard(),
+                None => {
+                    error!("Generating a C header needs a selected package");
+                    Task::none()
+                }
+            },
+            Command::ManifestLens {
+                action,
+                manifest,
+                target,
+            } => self.manifest_lens(action, &manifest, target, metadata),
+            Command::ToggleManifestFeature { manifest, feature } => {
+                let Some(package) = metadata.packages().iter().find(|p| p.manifest == manifest)
+                else {
+                    error!("{manifest} is not the manifest of a workspace member");
+                    return Task::none();
+                };
+                let feature_target = FeatureTarget::Package(package.name.clone());
+                let all = self
+                    .config
+                    .get(&package.name, |s| Some(s.selected_features.clone()))
+                    == Some(Features::All);
+                if !all {
+                    return self.toggle_feature(feature_target, feature);
+                }
+                // The manifest shows every feature as enabled, so only `feature` is disabled
+                let features = package
+                    .features
+                    .iter()
+                    .filter(|f| **f != feature)
+                    .cloned()
+                    .collect();
+                Task::done(
+                    ConfigUpdate::SelectedFeatures {
+                        feature_target,
+                        features: Features::Some(features),
+                    }
+                    .into_cargo_msg(),
+                )
+            }
+            Command::SetRegistryToken => {
+                Task::future(set_registry_token(metadata.registries().clone())).discard()
+            }
+            Command::InstallPreCommitHook => Task::future(install_pre_commit_hook(
+                metadata.workspace_root().to_string(),
+                pre_commit_checks(),
+                cargo_command(),
+            ))
+            .discard(),
+            Command::FlashAndRun => self.flash(ProbeRsCommand::Run, metadata),
+            Command::AttachRtt => self.flash(ProbeRsCommand::Attach, metadata),
+            Command::AttachToProcess => {
+                Task::future(attach_to_process(self.bin_executables(metadata))).discard()
+            }
+            Command::Test => self.test_exec(self.task_cmd(TaskKind::Test), metadata),
+            Command::Bench => self.cmd_exec(self.task_cmd(TaskKind::Bench)),
+            Command::DebugBench => match self.config.selected_package.clone() {
+                Some(package) => {
+                    let target = self.config.get(&package, |s| s.benchmark_target.clone());
+                    self.debug_bench(BenchTarget { package, target }, metadata)
+                }
+                None => Task::none(),
+            },
+            Command::ToggleFeature(feature) => {
+                let feature_target = self.config.feature_target();
+                self.toggle_feature(feature_target, feature)
+            }
+        }
+    }
+
+    /// Builds the selection and regenerates the C header of the `cdylib` of the selected
+    /// package after a successful build if `cargoTools.cbindgen.generateOnBuild` is enabled
+    fn build(&self, metadata: &Metadata) -> Task<Message> {
+        let cmd = self.task_cmd(TaskKind::Build);
+        let cdylib = self
+            .selected_package(metadata)
+            .filter(|p| cbindgen_generate_on_build() && cbindgen::cdylib_name(p).is_some());
+        let Some(package) = cdylib else {
+            return self.cmd_exec(cmd);
+        };
+        let target_dir = metadata.target_dir().to_string();
+        let config = self.config.clone();
+        self.cmd_exec_then(
+            cmd,
+            Some(generate_c_header(package, target_dir, config).boxed_local()),
+        )
+    }
+
+    /// Builds the selected package or the whole workspace and only runs its tests if that succeeded
+    fn build_and_test(&self) -> Task<Message> {
+        let package = self.config.selected_package.clone();
+        let build = CargoCommand::Build(package.clone().map(BuildTarget::package_only));
+        let test = CargoCommand::Test { package };
+
+        let [build, test] = [build, test].map(|cmd| {
+            let ctx = cmd.ctx();
+            cmd.try_into_process(&self.config, ctx)
+        });
+        let (build, test) = match (build, test) {
+            (Ok(build), Ok(test)) => (build, test),
+            (Err(e), _) | (_, Err(e)) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        Task::future(async move {
+            if execute_task_and_wait(VsCodeTask::cargo(build)).await {
+                execute_task_and_wait(VsCodeTask::cargo(test)).await;
+            } else {
+                show_tests_skipped();
+            }
+        })
+        .discard()
+    }
+
+    /// Runs the task of `kind` like a CI machine would, without the user's shell environment
+    fn clean_environment_run(&self, kind: TaskKind) -> Task<Message> {
+        let cmd = self.task_cmd(kind);
+        let ctx = cmd.ctx();
+        let process = match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        match into_clean_env(process) {
+            Some(process) => Task::future(execute_task(VsCodeTask::cargo(process))).discard(),
+            None => {
+                error!("Running in a clean environment is not supported on Windows");
+                Task::none()
+            }
+        }
+    }
+
+    /// The command of `kind` for the current selection
+    fn task_cmd(&self, kind: TaskKind) -> CargoCommand {
+        let package = self.config.selected_package.clone();
+        match kind {
+            TaskKind::Build => CargoCommand::Build(package.map(|package| {
+                let target = self.config.get(&package, |s| s.build_target.clone());
+                BuildTarget { package, target }
+            })),
+            TaskKind::Run => CargoCommand::Run(package.map(|package| {
+                let target = self.config.get(&package, |s| s.run_target.clone());
+                RunTarget { package, target }
+            })),
+            TaskKind::Test => CargoCommand::Test { package },
+            TaskKind::Clippy => CargoCommand::Clippy { package },
+            TaskKind::Fmt => CargoCommand::Fmt { package },
+            TaskKind::Doc => CargoCommand::Doc,
+            TaskKind::Bench => CargoCommand::Bench(package.map(|package| {
+                let target = self.config.get(&package, |s| s.benchmark_target.clone());
+                BenchTarget { package, target }
+            })),
+        }
+    }
+
+    fn variable(&self, variable: Variable) -> Option<String> {
+        let selection = self.config.package_selection();
+        match variable {
+            Variable::SelectedPackage => self.config.selected_package.clone(),
+            Variable::SelectedTargetName => selection
+                .and_then(|s| s.build_target.as_ref())
+                .map(|t| t.name().to_string()),
+            // Built by the request for its exact path, see [Self::selected_executable_build]
+            Variable::LaunchTargetPath => None,
+            Variable::BuildProfileFlag => Some(self.config.profile.cargo_args().join(" ")),
+        }
+    }
+
+    fn provided_tasks(&self, kinds: Vec<TaskKind>) -> Vec<ProvidedTask> {
+        kinds
+            .into_iter()
+            .filter_map(|kind| {
+                let cmd = self.task_cmd(kind);
+                let ctx = cmd.ctx();
+                match cmd.try_into_process(&self.config, ctx) {
+                    Ok(process) => Some(ProvidedTask { kind, process }),
+                    Err(e) => {
+                        error!("{e}");
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn cmd_exec(&self, cmd: CargoCommand) -> Task<Message> {
+        self.cmd_exec_then(cmd, None)
+    }
+
+    /// Executes `cmd` and runs `after` once it succeeded. A command with `after` runs as task
+    /// even if it would run in a terminal, as only tasks report whether they succeeded.
+    fn cmd_exec_then(
+        &self,
+        cmd: CargoCommand,
+        after: Option<LocalBoxFuture<'static, ()>>,
+    ) -> Task<Message> {
+        let ctx = cmd.ctx();
+        let mode = cmd.execution_mode();
+        // Checked next to the command so it isn't delayed
+        let component_check = match rustup::required_component(&cmd) {
+            Some(component) => Task::future(warn_missing_component(component)).discard(),
+            None => Task::none(),
+        };
+
+        match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => {
+                let task = VsCodeTask::cargo(process);
+                let exec = match after {
+                    Some(after) => Task::future(async move {
+                        if execute_task_and_wait(task).await {
+                            after.await;
+                        }
+                    }),
+                    None => Task::future(execute(task, mode)),
+                };
+                Task::batch([exec.discard(), component_check])
+            }
+            Err(e) => {
+                error!("{e}");
+                Task::none()
+            }
+        }
+    }
+
+    /// Executes the test command `cmd`, with nextest and the selected nextest profile if that
+    /// is the test runner. A tool config adds the JUnit report and per-test timeout settings.
+    fn test_exec(&self, cmd: CargoCommand, metadata: &Metadata) -> Task<Message> {
+        if !nextest_runner() {
+            return self.cmd_exec(cmd);
+        }
+        let profile = self.config.nextest_profile.clone();
+        let tool_config = ToolConfig {
+            junit_path: nextest_junit_path().map(|junit_path| {
+                PathBuf::from(metadata.workspace_root())
+                    .join(junit_path)
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            test_timeout: nextest_test_timeout(),
+        };
+        let tool_config = (!tool_config.is_empty()).then(|| {
+            let config = tool_config.to_toml(profile.as_deref().unwrap_or(DEFAULT_PROFILE));
+            let path = PathBuf::from_iter([metadata.target_dir(), "cargo-tools", "nextest.toml"]);
+            (path.to_string_lossy().to_string(), config)
+        });
+        let cmd = CargoCommand::Nextest {
+            cmd: Box::new(cmd),
+            profile,
+            tool_config: tool_config.as_ref().map(|(path, _)| path.clone()),
+        };
+        let ctx = cmd.ctx();
+        let mode = cmd.execution_mode();
+
+        let process = match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+        Task::future(async move {
+            if let Some((path, config)) = tool_config
+                && let Err(e) = write_file_vs_code(path, config).await
+            {
+                error!("Failed to write the nextest tool config: {e}");
+            }
+            execute(VsCodeTask::cargo(process), mode).await
+        })
+        .discard()
+    }
+
+    /// Builds the run target for the selected embedded platform target and runs `cmd` of
+    /// probe-rs with its ELF
+    fn flash(&self, cmd: ProbeRsCommand, metadata: &Metadata) -> Task<Message> {
+        if !self.embedded_target_selected() {
+            error!("Flashing needs an embedded platform target, e.g. thumbv7em-none-eabihf");
+            return Task::none();
+        }
+        let Some(package) = self.config.selected_package.clone() else {
+            return Task::none();
+        };
+        if self
+            .config
+            .get(&package, |s| s.run_target.clone())
+            .is_none()
+        {
+            error!("Flashing needs a run target");
+            return Task::none();
+        }
+        let Some((build, target)) = self.selected_executable_build(metadata) else {
+            return Task::none();
+        };
+        Task::future(build_and_flash(build, target, cmd, probe_rs_chip())).discard()
+    }
+
+    /// The build of the selected run target which reports its executable, like the build of a
+    /// debug session
+    fn selected_executable_build(&self, metadata: &Metadata) -> Option<(Process, RunSubTarget)> {
+        let package = self.config.selected_package.clone()?;
+        let target = self.config.get(&package, |s| s.run_target.clone())?;
+        let config = self
+            .config
+            .with_required_features(&package, metadata.required_features(&package, &target));
+        let build = CargoCommand::Debug(Some(RunTarget {
+            package,
+            target: Some(target.clone()),
+        }));
+        let ctx = build.ctx();
+        build
+            .try_into_process(&config, ctx)
+            .inspect_err(|e| error!("{e}"))
+            .ok()
+            .map(|process| (process, target))
+    }
+
+    fn embedded_target_selected(&self) -> bool {
+        self.config
+            .platform_target
+            .as_deref()
+            .is_some_and(probe_rs::is_embedded_target)
+    }
+
+    fn embedded_target_context(&self) -> Task<Message> {
+        Task::future(set_embedded_target_context(self.embedded_target_selected())).discard()
+    }
+
+    fn wasm_package_context(&self, metadata: &Metadata) -> Task<Message> {
+        let wasm = self
+            .selected_package(metadata)
+            .is_some_and(|p| wasm::depends_on_wasm_bindgen(&p));
+        Task::future(set_wasm_package_context(wasm)).discard()
+    }
+
+    /// Runs the `action` of a CodeLens of `manifest` on its package or its run `target`
+    fn manifest_lens(
+        &self,
+        action: ManifestLensAction,
+        manifest: &str,
+        target: Option<RunSubTarget>,
+        metadata: &Metadata,
+    ) -> Task<Message> {
+        let Some(package) = metadata
+            .packages()
+            .iter()
+            .find(|p| p.manifest == manifest)
+            .map(|p| p.name.clone())
+        else {
+            error!("{manifest} is not the manifest of a workspace member");
+            return Task::none();
+        };
+        let run_target = RunTarget { package, target };
+
+        match action {
+            ManifestLensAction::Build => {
+                self.cmd_exec(CargoCommand::Build(Some(BuildTarget::from(run_target))))
+            }
+            ManifestLensAction::Test => self.test_exec(
+                CargoCommand::Test {
+                    package: Some(run_target.package),
+                },
+                metadata,
+            ),
+            ManifestLensAction::Run => self.cmd_exec(CargoCommand::Run(Some(run_target))),
+            ManifestLensAction::Debug => self.debug(run_target, metadata, Vec::new(), None),
+            ManifestLensAction::Select => {
+                // The targets are selected for the package, so it has to be selected first
+                let mut updates = vec![ConfigUpdate::SelectedPackage(Some(
+                    run_target.package.clone(),
+                ))];
+                if let Some(target) = run_target.target.clone() {
+                    updates.push(ConfigUpdate::SelectedRunTarget(Some(target)));
+                    updates.push(ConfigUpdate::SelectedBuildTarget(
+                        BuildTarget::from(run_target).target,
+                    ));
+                }
+                Task::done(Message::ConfigsChanged(updates))
+            }
+        }
+    }
+
+    fn selected_package(&self, metadata: &Metadata) -> Option<Package> {
+        let selected = self.config.selected_package.as_ref()?;
+        metadata
+            .packages()
+            .iter()
+            .find(|p| &p.name == selected)
+            .cloned()
+    }
+
+    /// Shows the selected features in the manifest of the selected package, all of its
+    /// features if all are selected
+    fn publish_feature_selection(&self, metadata: &Metadata) {
+        let package = self.selected_package(metadata);
+        let selected = match (&package, self.config.selected_features()) {
+            (Some(package), Features::All) => package.features.clone(),
+            (_, Features::Some(features)) => features,
+            (None, Features::All) => Vec::new(),
+        };
+        self.manifest_lens
+            .set_feature_selection(package.map(|p| p.manifest), selected);
+    }
+
+    /// Shares the selection with other extensions through the API the extension exports
+    fn publish_selection(&self, metadata: &Metadata) {
+        self.update_problem_count_scope(metadata);
+        let build = self.task_cmd(TaskKind::Build);
+        let ctx = build.ctx();
+        let build = match build.try_into_process(&self.config, ctx) {
+            Ok(build) => build,
+            Err(e) => {
+                error!("{e}");
+                return;
+            }
+        };
+        let executable = self
+            .config
+            .package_selection()
+            .and_then(|s| s.run_target.as_ref())
+            .and_then(|target| self.executables.get(target))
+            .cloned();
+        let selection = Selection::new(&self.config, metadata.workspace_root(), executable, &build);
+        set_selection_env(selection.env());
+        match serde_wasm_bindgen::to_value(&selection) {
+            Ok(selection) => publish_selection(selection),
+            Err(e) => error!("Failed to serialize the selection: {e}"),
+        }
+    }
+
+    /// Counts the problems of the selected package, of the whole workspace without a selection
+    fn update_problem_count_scope(&self, metadata: &Metadata) {
+        let package = self
+            .config
+            .selected_package
+            .as_ref()
+            .and_then(|name| metadata.packages().iter().find(|p| &p.name == name));
+        let package_dir = |package: &Package| {
+            Path::new(&package.manifest)
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default()
+        };
+        let (label, dir) = match package {
+            Some(package) => (package.name.clone(), package_dir(package)),
+            None => (
+                "the workspace".to_string(),
+                PathBuf::from(metadata.workspace_root()),
+            ),
+        };
+        // A root package doesn't own the files of the members in its directory
+        let excluded = match package {
+            Some(_) => metadata
+                .packages()
+                .iter()
+                .map(package_dir)
+                .filter(|nested| nested != &dir && nested.starts_with(&dir))
+                .collect(),
+            None => Vec::new(),
+        };
+        let mappings = path_mappings();
+        let to_host = |dir: &Path| mappings.to_host(&dir.to_string_lossy());
+        self.problem_count.set_scope(
+            label,
+            to_host(&dir),
+            excluded.iter().map(|dir| to_host(dir)).collect(),
+        );
+    }
+
+    /// Writes the selection into the rust-analyzer settings if
+    /// `cargoTools.syncRustAnalyzerSelection` is enabled, see [RustAnalyzerSettings]
+    fn sync_rust_analyzer(&self) -> Task<Message> {
+        if !sync_rust_analyzer_selection() {
+            return Task::none();
+        }
+        // rust-analyzer reports the diagnostics of the check the extension runs
+        let check = check_command(self.config.selected_package.clone());
+        let ctx = check.ctx();
+        match check.try_into_process(&self.config, ctx) {
+            Ok(check) => Task::future(write_rust_analyzer_settings(RustAnalyzerSettings::new(
+                &self.config,
+                &check,
+            )))
+            .discard(),
+            Err(e) => {
+                error!("{e}");
+                Task::none()
+            }
+        }
+    }
+
+    /// Asks for program arguments, previously entered ones are offered first
+    fn prompt_arguments(&self, action: &str) -> Task<String> {
+        let history = self.argument_history.items().to_vec();
+        let placeholder = format!("{action} with arguments, e.g. --verbose input.txt");
+        Task::future(async move {
+            match show_input_with_history(history, placeholder).await {
+                Ok(args) => args.as_string(),
+                Err(e) => {
+                    error!("Failed to get arguments: {}", e.to_error_string());
+                    None
+                }
+            }
+        })
+        .and_then(Task::done)
+    }
+
+    fn record_arguments(&mut self, args: String) -> Task<Message> {
+        if args.trim().is_empty() {
+            return Task::none();
+        }
+        self.argument_history.record(args);
+        self.argument_history.truncate(ARGUMENT_HISTORY_LEN);
+        Task::future(persist_state_vs_code(
+            argument_history_key(&self.project),
+            self.argument_history.clone(),
+        ))
+        .discard()
+    }
+
+    /// Runs the run target with `args` appended to the program arguments, the arguments are
+    /// not stored in the configuration.
+    fn run_with_args(&self, args: &str) -> Task<Message> {
+        let cmd = self.task_cmd(TaskKind::Run);
+        let ctx = cmd.ctx();
+        let mode = cmd.execution_mode();
+
+        match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => {
+                let process = process
+                    .with_program_args(args.split_whitespace().map(str::to_string).collect());
+                Task::future(execute(VsCodeTask::cargo(process), mode)).discard()
+            }
+            Err(e) => {
+                error!("{e}");
+                Task::none()
+            }
+        }
+    }
+
+    /// Inspects a core dump of the selected run target with its last built executable, the
+    /// target is built first if it wasn't built since the extension started
+    fn open_core_dump(&self, metadata: &Metadata) -> Task<Message> {
+        let Some(target) = self
+            .config
+            .package_selection()
+            .and_then(|s| s.run_target.clone())
+        else {
+            error!("Select a run target to open a core dump for");
+            return Task::none();
+        };
+        let built = self.executables.get(&target).cloned();
+        let build = self.selected_executable_build(metadata);
+        let root_dir = self.root_dir.clone();
+
+        Task::future(async move {
+            let program = match (built, build) {
+                (Some(program), _) => program,
+                (None, Some((process, target))) => {
+                    build_executable(process, &target, "a core dump").await?
+                }
+                (None, None) => return None,
+            };
+            debug_core_dump(target.name().to_string(), program, root_dir).await;
+            Some(())
+        })
+        .discard()
+    }
+
+    /// Asks for a sanitizer, sanitized builds are for the platform target or else the host
+    fn select_sanitizer(&self) -> Task<(Sanitizer, String)> {
+        let platform_target = self.config.platform_target.clone();
+        let input = SelectInput {
+            options: Sanitizer::all().to_vec(),
+            current: Vec::new(),
+        };
+        Task::future(async move {
+            let sanitizer = input.select().await?;
+            let platform_target = match platform_target {
+                Some(platform_target) => platform_target,
+                None => host_triple().await?,
+            };
+            Some((sanitizer, platform_target))
+        })
+        .and_then(Task::done)
+    }
+
+    /// Runs the tests of the selection with insta's assertions writing snapshots per `update`
+    fn run_snapshot_tests(&self, update: InstaUpdate) -> Task<Message> {
+        let cmd = self.task_cmd(TaskKind::Test);
+        let (key, value) = update.env();
+        let ctx = cmd.ctx().with_env(key, value);
+        let mode = cmd.execution_mode();
+
+        match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => Task::future(execute(VsCodeTask::cargo(process), mode)).discard(),
+            Err(e) => {
+                error!("{e}");
+                Task::none()
+            }
+        }
+    }
+
+    /// Runs the run target built with `sanitizer` for `platform_target` by nightly cargo
+    fn run_with_sanitizer(&self, sanitizer: Sanitizer, platform_target: String) -> Task<Message> {
+        let cmd = CargoCommand::Sanitized {
+            cmd: Box::new(self.task_cmd(TaskKind::Run)),
+            platform_target,
+        };
+        let mut ctx = cmd.ctx().with_rustflag(sanitizer.rustflag());
+        if let Some((key, value)) = sanitizer.options_env(false) {
+            ctx = ctx.with_env(key, value);
+        }
+        let mode = cmd.execution_mode();
+
+        match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => Task::future(execute(VsCodeTask::cargo(process), mode)).discard(),
+            Err(e) => {
+                error!("{e}");
+                Task::none()
+            }
+        }
+    }
+
+    /// Debugs the run target with `args`, built with the sanitizer for the platform target of
+    /// `sanitizer` if one is given
+    fn debug(
+        &self,
+        target: RunTarget,
+        metadata: &Metadata,
+        args: Vec<String>,
+        sanitizer: Option<(Sanitizer, String)>,
+    ) -> Task<Message> {
+        let Some(run_target) = target.target.clone() else {
+            return Task::none();
+        };
+        let config = self.config.with_required_features(
+            &target.package,
+            metadata.required_features(&target.package, &run_target),
+        );
+
+        let build_debug_cmd = CargoCommand::Debug(Some(target));
+        let ctx = debug_build_context(&build_debug_cmd, &config.profile);
+        let mut env = BTreeMap::new();
+        let (build_debug_cmd, ctx) = match sanitizer {
+            Some((sanitizer, platform_target)) => {
+                env.extend(sanitizer.options_env(true));
+                let cmd = CargoCommand::Sanitized {
+                    cmd: Box::new(build_debug_cmd),
+                    platform_target,
+                };
+                (cmd, ctx.with_rustflag(sanitizer.rustflag()))
+            }
+            None => (build_debug_cmd, ctx),
+        };
+
+        let build_debug_process = match build_debug_cmd.try_into_process(&config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        Task::future(build_and_debug(
+            build_debug_process,
+            run_target,
+            args,
+            env,
+            remote_target(self.config.platform_target.as_deref()),
+        ))
+        .discard()
+    }
+
+    fn debug_bench(&self, bench: BenchTarget, metadata: &Metadata) -> Task<Message> {
+        let Some(package) = metadata.packages().iter().find(|p| p.name == bench.package) else {
+            return Task::none();
+        };
+        let cwd = package.manifest_dir().to_string();
+
+        let build_debug_cmd = CargoCommand::DebugBench(bench.clone());
+        let ctx = build_debug_cmd.ctx();
+
+        let build_debug_process = match build_debug_cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        Task::future(build_and_debug_bench(
+            build_debug_process,
+            bench,
+            cwd,
+            remote_target(self.config.platform_target.as_deref()),
+        ))
+        .discard()
+    }
+
+    /// Finds the doctest of the code block at the zero based `line` of `file` in the
+    /// doctests cargo lists for its package
+    fn locate_doc_test(
+        &self,
+        file: &str,
+        line: usize,
+        metadata: &Metadata,
+    ) -> Task<(String, DocTest)> {
+        let Some(location) = DocTestLocation::locate(metadata.packages(), file, line) else {
+            error!("{file} is no library source, rustdoc only tests the docs of libraries");
+            return Task::none();
+        };
+        let cmd = CargoCommand::ListDocTests {
+            package: location.package.clone(),
+        };
+        let ctx = cmd.ctx();
+        let process = match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        Task::future(async move {
+            let tests = match exec_vs_code(process).await {
+                Ok(output) => DocTest::parse_list(&output),
+                Err(e) => {
+                    error!("Failed to list doctests: {e}");
+                    return None;
+                }
+            };
+            let package = location.package.clone();
+            match location.find(tests) {
+                Some(test) => Some((package, test)),
+                None => {
+                    error!(
+                        "No doctest found at line {} of {}",
+                        location.line, location.file
+                    );
+                    None
+                }
+            }
+        })
+        .and_then(Task::done)
+    }
+
+    /// Debugs a doctest of `package`, rustdoc only keeps doctest executables on nightly
+    fn debug_doc_test(&self, package: String, test: DocTest, metadata: &Metadata) -> Task<Message> {
+        let Some(cwd) = metadata
+            .packages()
+            .iter()
+            .find(|p| p.name == package)
+            .map(|p| p.manifest_dir().to_string())
+        else {
+            return Task::none();
+        };
+        let persist_dir = PathBuf::from_iter([metadata.target_dir(), "doctests"])
+            .to_string_lossy()
+            .to_string();
+
+        let build_debug_cmd = CargoCommand::DebugDocTest {
+            package,
+            test: test.clone(),
+            persist_dir: persist_dir.clone(),
+        };
+        let ctx = build_debug_cmd.ctx();
+
+        let build_debug_process = match build_debug_cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        Task::future(build_and_debug_doc_test(
+            build_debug_process,
+            test,
+            persist_dir,
+            cwd,
+        ))
+        .discard()
+    }
+
+    fn debug_test(&self, test: TestCase) -> Task<Message> {
+        let names = vec![test.name.clone()];
+        self.debug_tests(test, names)
+    }
+
+    /// Debugs the tests `names` of the target of `test`, whose name labels the session
+    fn debug_tests(&self, test: TestCase, names: Vec<String>) -> Task<Message> {
+        let build_debug_cmd = CargoCommand::DebugTest(test.clone());
+        let ctx = debug_build_context(&build_debug_cmd, &self.config.profile);
+
+        let build_debug_process = match build_debug_cmd.try_into_process(&self.config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
+            }
+        };
+
+        Task::future(build_and_debug_tests(
+            build_debug_process,
+            test.target,
+            test.package_dir,
+            test.name,
+            names,
+            remote_target(self.config.platform_target.as_deref()),
+        ))
+        .discard()
+    }
+
+    /// Runs the test explorer items `ids` and returns the output of each cargo invocation by
+    /// package, with the merged line coverage of a `coverage` run
+    fn run_tests(
+        &self,
+        ids: &[String],
+        coverage: bool,
+        ignored: IgnoredTests,
+        harness_args: &[String],
+        run_id: &str,
+        metadata: &Metadata,
+    ) -> impl Future<Output = TestOutputs> + 'static {
+        if coverage {
+            spawn_local(warn_missing_component(LLVM_TOOLS));
+        }
+        let mut processes = Vec::new();
+        for (i, selection) in self.test_tree.run_selections(ids).into_iter().enumerate() {
+            let package = selection.package().to_string();
+            let (cmd, lcov_path) = if coverage {
+                // Doctests are only instrumented on nightly
+                if matches!(
+                    selection,
+                    TestSelection::DocTests(_) | TestSelection::DocTest { .. }
+                ) {
+                    continue;
+                }
+                let lcov_path = PathBuf::from_iter([
+                    metadata.target_dir(),
+                    "cargo-tools-coverage",
+                    &format!("{i}.lcov"),
+                ])
+                .to_string_lossy()
+                .to_string();
+                let cmd = CargoCommand::Coverage {
+                    selection,
+                    ignored,
+                    lcov_path: lcov_path.clone(),
+                };
+                (cmd, Some(lcov_path))
+            } else {
+                (CargoCommand::RunTests { selection, ignored }, None)
+            };
+            let ctx = cmd.ctx();
+            match cmd.try_into_process(&self.config, ctx) {
+                Ok(process) => processes.push((
+                    package,
+                    process.with_program_args(harness_args.to_vec()),
+                    lcov_path,
+                )),
+                Err(e) => error!("{e}"),
             }
-            Command::SelectPlatformTarget => {
-                let current = self.config.platform_target.clone();
-                done(async move { select_platform_target(current.clone()).await })
+        }
+        let run_id = run_id.to_string();
+        // The timeout is shared by all cargo invocations of the run
+        let deadline = test_timeout().map(|timeout| now_ms() + timeout.as_millis() as f64);
+        // One run after another, parallel cargo invocations only wait for the build lock
+        async move {
+            let mut runs = Vec::new();
+            let mut reports = Vec::new();
+            let mut timed_out = false;
+            for (package, process, lcov_path) in processes {
+                let timeout_ms = deadline.map(|deadline| deadline - now_ms());
+                if timeout_ms.is_some_and(|ms| ms <= 0.0) {
+                    timed_out = true;
+                    break;
+                }
+                // A report of an earlier run would hide that this one failed to write one
+                if let Some(lcov_path) = &lcov_path
+                    && let Err(e) = delete_file_vs_code(lcov_path.clone()).await
+                {
+                    error!("Failed to delete the coverage report {lcov_path}: {e}");
+                }
+                let output =
+                    match exec_output_with_timeout_vs_code(process, timeout_ms, &run_id).await {
+                        Ok(output) => {
+                            timed_out |= output.timed_out;
+                            output.output
+                        }
+                        Err(e) => {
+                            error!("Failed to run tests: {e}");
+                            e
+                        }
+                    };
+                runs.push((package, output));
+                if timed_out {
+                    break;
+                }
+                // Without a report cargo-llvm-cov is missing or failed, the output says why
+                if let Some(lcov_path) = lcov_path
+                    && let Ok(report) = read_file_vs_code(lcov_path).await
+                {
+                    reports.push(coverage::parse_lcov(&report));
+                }
             }
-            Command::InstallPlatformTarget => Task::future(install_platform_target()).discard(),
-            Command::SetRustAnalyzerCheckTargets => {
-                Task::future(set_rust_analyzer_check_targets()).discard()
+            TestOutputs {
+                runs,
+                coverage: coverage::merge(reports),
+                timed_out,
             }
-            Command::BuildDocs => self.cmd_exec(CargoCommand::Doc),
-            Command::SelectFeatures => {
-                let options = self.config.feature_options(metadata);
-                let current = match self.config.selected_features() {
-                    Features::All => ["All features".to_string()].to_vec(),
-                    Features::Some(features) => features,
-                };
+        }
+    }
 
-                let input = Some(SelectInput { options, current });
-                let feature_target = self.config.feature_target();
-                done(async move { select_features(input, feature_target).await })
-            }
-            Command::Refresh => self.refresh(metadata),
-            Command::Clean => {
-                let package = self.config.selected_package.clone();
-                self.cmd_exec(CargoCommand::Clean { package })
+    fn handle_test_explorer_request(
+        &mut self,
+        request: TestExplorerRequest,
+        metadata: &Metadata,
+    ) -> Task<Message> {
+        match request {
+            TestExplorerRequest::Discover { files, mut tx } => {
+                self.source_hashes = source_hashes(metadata.packages(), &files);
+                self.test_tree = TestTree::build(metadata.packages(), &files);
+                let items = if metadata.packages().len() > large_workspace_threshold() {
+                    self.test_tree.lazy_items()
+                } else {
+                    self.test_tree.items().to_vec()
+                };
+                Task::future(async move { tx.send(items).await }).discard()
             }
-            Command::Build => {
-                let target = self.config.selected_package.clone().map(|package| {
-                    let target = self.config.get(&package, |s| s.build_target.clone());
-                    BuildTarget { package, target }
-                });
-                self.cmd_exec(CargoCommand::Build(target))
+            TestExplorerRequest::DiscoverChildren { id, mut tx } => {
+                if let Some(children) = self.test_tree.children(&id) {
+                    let children = children.to_vec();
+                    return Task::future(async move { tx.send(children).await }).discard();
+                }
+                let Some(TestSelection::DocTests(package)) = self.test_tree.selection(&id) else {
+                    return Task::none();
+                };
+                let cmd = CargoCommand::ListDocTests {
+                    package: package.clone(),
+                };
+                let ctx = cmd.ctx();
+                let process = match cmd.try_into_process(&self.config, ctx) {
+                    Ok(process) => process,
+                    Err(e) => {
+                        error!("{e}");
+                        return Task::none();
+                    }
+                };
+                // Listing builds the package, unchanged packages reuse their last listing
+                let source_hash = self.source_hashes.get(package).copied();
+                let cached = source_hash
+                    .and_then(|hash| self.doc_test_cache.get(package, hash, process.args()));
+                if let Some(tests) = cached {
+                    return Task::done(Message::DocTestsListed {
+                        id,
+                        tests,
+                        listing: None,
+                        tx,
+                    });
+                }
+                let listing = source_hash.map(|hash| (hash, process.args().to_vec()));
+                Task::future(async move {
+                    let tests = match exec_vs_code(process).await {
+                        Ok(output) => DocTest::parse_list(&output),
+                        Err(e) => {
+                            error!("Failed to list doctests: {e}");
+                            // Failed listings aren't cached
+                            return Message::DocTestsListed {
+                                id,
+                                tests: Vec::new(),
+                                listing: None,
+                                tx,
+                            };
+                        }
+                    };
+                    Message::DocTestsListed {
+                        id,
+                        tests,
+                        listing,
+                        tx,
+                    }
+                })
             }
-            Command::Run => {
-                let target = self.config.selected_package.clone().map(|package| {
-                    let target = self.config.get(&package, |s| s.run_target.clone());
-                    RunTarget { package, target }
-                });
-                self.cmd_exec(CargoCommand::Run(target))
+            TestExplorerRequest::Run {
+                ids,
+                coverage,
+                ignored,
+                harness_args,
+                run_id,
+                tx,
+            } => {
+                let tests =
+                    self.run_tests(&ids, coverage, ignored, &harness_args, &run_id, metadata);
+                let run = TestRun {
+                    ignored,
+                    harness_args,
+                    run_id,
+                    attempt: 0,
+                    result: TestRunResult::default(),
+                    tx,
+                };
+                Task::future(async move {
+                    let outputs = tests.await;
+                    Message::TestsRan { outputs, run }
+                })
             }
-            Command::Debug => match self.config.selected_package.clone() {
-                Some(package) => {
-                    let target = self.config.get(&package, |s| s.run_target.clone());
-                    self.debug(RunTarget { package, target }, metadata.target_dir())
+            TestExplorerRequest::Debug { id } => match self.test_tree.selection(&id).cloned() {
+                Some(TestSelection::Test(test)) => self.debug_test(test),
+                Some(TestSelection::Tests {
+                    package,
+                    package_dir,
+                    target,
+                    module,
+                    names,
+                }) => {
+                    let label = if module.is_empty() {
+                        package.clone()
+                    } else {
+                        module
+                    };
+                    let test = TestCase {
+                        package,
+                        package_dir,
+                        target,
+                        name: label.clone(),
+                    };
+                    self.debug_tests(test, names)
+                }
+                Some(TestSelection::DocTest { package, test }) => {
+                    self.debug_doc_test(package, test, metadata)
+                }
+                Some(
+                    TestSelection::Package(_)
+                    | TestSelection::Targets { .. }
+                    | TestSelection::DocTests(_),
+                ) => {
+                    error!("Select a file, a test or a doctest to debug");
+                    Task::none()
                 }
                 None => Task::none(),
             },
-            Command::Test => {
-                let package = self.config.selected_package.clone();
-                self.cmd_exec(CargoCommand::Test { package })
-            }
-            Command::Bench => {
-                let target = self.config.selected_package.clone().map(|package| {
-                    let target = self.config.get(&package, |s| s.benchmark_target.clone());
-                    BenchTarget { package, target }
-                });
-                self.cmd_exec(CargoCommand::Bench(target))
+        }
+    }
+
+    /// The executables of the bin targets built since the extension started and those whose
+    /// sizes were recorded, of all profiles and platform targets
+    fn bin_executables(&self, metadata: &Metadata) -> Vec<String> {
+        let recorded = self
+            .binary_sizes
+            .artifacts()
+            .map(|artifact| Path::new(metadata.target_dir()).join(artifact))
+            .map(|path| path.to_string_lossy().to_string());
+        self.executables
+            .iter()
+            .filter(|(target, _)| matches!(target, RunSubTarget::Bin(_)))
+            .map(|(_, path)| path.clone())
+            .chain(recorded)
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    fn toggle_watch(&mut self, metadata: &Metadata) -> Task<Message> {
+        if self.watch.take().is_some() {
+            info!("Watch mode stopped");
+            show_watch_status(false);
+            return Task::none();
+        }
+
+        let (tx, rx) = channel(CHANNEL_CAPACITY);
+        self.watch = Some(Watch {
+            file_watcher: TsFileWatcher::new(send_file_changed(tx)),
+            rerun: Rerun::default(),
+        });
+        self.update_watched_files(metadata);
+        info!("Watch mode started");
+        show_watch_status(true);
+
+        // The stream ends once the watcher is dropped when watch mode is toggled off
+        Task::stream(rx).map(|()| Message::WatchedFilesChanged)
+    }
+
+    /// Lists the tests with recorded durations in the **Slowest Tests** view, which keeps
+    /// explaining why there are none until there are
+    fn update_slowest_tests(&self) {
+        if !self.test_history.slowest().is_empty() {
+            self.slowest_tests.show_history(&self.test_history, None);
+        }
+    }
+
+    fn load_benchmark_reports(&self, metadata: &Metadata) -> Task<Message> {
+        Task::future(load_benchmark_reports(
+            self.benchmark_reports.clone(),
+            criterion_report_dir(metadata),
+        ))
+        .discard()
+    }
+
+    /// Watches the criterion reports of the target directory unless it already does
+    fn update_criterion_watcher(&mut self, metadata: &Metadata) {
+        let target_dir = metadata.target_dir();
+        if self.criterion_target_dir.as_deref() == Some(target_dir) {
+            return;
+        }
+        self.criterion_target_dir = Some(target_dir.to_string());
+        self.criterion_watcher.watch_patterns(
+            target_dir.to_string(),
+            vec![format!("{}/**/new/estimates.json", criterion::REPORT_DIR)],
+            Vec::new(),
+        );
+    }
+
+    fn update_watched_executables(&self, metadata: &Metadata) {
+        self.executable_watcher
+            .watch_files(self.bin_executables(metadata));
+    }
+
+    /// Records the sizes of the executables which were built since their last record and warns
+    /// about the ones which grew more than `cargoTools.binarySize.jumpThreshold`
+    fn record_binary_sizes(&mut self, sizes: Vec<(String, FileStat)>) -> Task<Message> {
+        let threshold = f64::from(binary_size_jump_threshold());
+        let jumps: Vec<_> = sizes
+            .into_iter()
+            .filter_map(|(artifact, stat)| {
+                self.binary_sizes
+                    .record(artifact, stat.mtime, stat.size, threshold)
+            })
+            .map(|jump| jump.message())
+            .collect();
+        show_binary_size_trend(&self.binary_sizes, false);
+
+        let persist = Task::future(persist_state_vs_code(
+            binary_sizes_key(&self.project),
+            self.binary_sizes.clone(),
+        ))
+        .discard();
+        if jumps.is_empty() {
+            return persist;
+        }
+        let warn = Task::future(warn_size_jumps(jumps)).then(|show| {
+            if show {
+                Task::done(Message::ShowBinarySizeTrend)
+            } else {
+                Task::none()
             }
-            Command::ToggleFeature(feature) => {
-                let feature_target = self.config.feature_target();
-                self.toggle_feature(feature_target, feature)
+        });
+        Task::batch([persist, warn])
+    }
+
+    fn update_watched_files(&self, metadata: &Metadata) {
+        let Some(watch) = &self.watch else {
+            return;
+        };
+
+        // Changes of the target directory are ignored as builds write to it
+        let ignored = vec![metadata.target_dir().to_string()];
+        let selected = self
+            .config
+            .selected_package
+            .as_ref()
+            .and_then(|selected| metadata.packages().iter().find(|p| &p.name == selected));
+        match selected {
+            Some(package) => watch.file_watcher.watch_patterns(
+                package.manifest_dir().to_string(),
+                WATCHED_PATTERNS.map(str::to_string).to_vec(),
+                ignored,
+            ),
+            None => {
+                let root = metadata.workspace_root();
+                let patterns = metadata
+                    .packages()
+                    .iter()
+                    .filter_map(|p| p.watched_patterns(root))
+                    .flatten()
+                    .collect();
+                watch
+                    .file_watcher
+                    .watch_patterns(root.to_string(), patterns, ignored);
             }
         }
     }
 
-    fn cmd_exec(&self, cmd: CargoCommand) -> Task<Message> {
-        let ctx = cmd.ctx();
+    fn run_watch_command(&mut self) -> Task<Message> {
+        let Some(watch) = self.watch.as_mut() else {
+            return Task::none();
+        };
 
+        let cmd = watch_command(self.config.selected_package.clone());
+        let ctx = cmd.ctx();
         match cmd.try_into_process(&self.config, ctx) {
-            Ok(process) => Task::future(execute_task(VsCodeTask::cargo(process))).discard(),
+            Ok(process) if watch.rerun.start() => {
+                Task::future(execute_task_and_wait(VsCodeTask::cargo(process)))
+                    .map(|_| Message::WatchRunFinished)
+            }
+            Ok(_) => Task::none(),
             Err(e) => {
                 error!("{e}");
                 Task::none()
@@ -259,42 +2597,25 @@ impl Configuration {
         }
     }
 
-    fn debug(&self, target: RunTarget, target_dir: &str) -> Task<Message> {
-        let Some(run_target) = target.target.as_ref() else {
+    fn run_check_on_save(&mut self, metadata: &Metadata) -> Task<Message> {
+        // Read on every save so toggling the setting applies immediately
+        let Some(cmd) = check_on_save_command(self.config.selected_package.clone()) else {
             return Task::none();
         };
-        let build_sub_target = match run_target {
-            RunSubTarget::Bin(t) => BuildSubTarget::Bin(t.clone()),
-            RunSubTarget::Example(t) => BuildSubTarget::Example(t.clone()),
-        };
-        let build_target = BuildTarget {
-            package: target.package.clone(),
-            target: Some(build_sub_target),
-        };
-        let mut config = self.config.clone();
-        config.profile = Profile::Dev; // For now always use standard dev profile
 
-        let build_debug_cmd = CargoCommand::Build(Some(build_target));
-        let ctx = build_debug_cmd.ctx();
-
-        let build_debug_process = match build_debug_cmd.try_into_process(&config, ctx) {
-            Ok(process) => process,
+        let ctx = cmd.ctx();
+        match cmd.try_into_process(&self.config, ctx) {
+            Ok(process) if self.check_on_save.rerun.start() => Task::future(check_diagnostics(
+                process,
+                metadata.workspace_root().to_string(),
+            ))
+            .map(Message::CheckOnSaveFinished),
+            Ok(_) => Task::none(),
             Err(e) => {
                 error!("{e}");
-                return Task::none();
-            }
-        };
-
-        let target_exe_path = exec_path(run_target, &self.config, target_dir);
-
-        Task::future(async move {
-            execute_task(VsCodeTask::cargo(build_debug_process)).await;
-
-            if let Err(e) = debug(&target_exe_path, &target.package).await {
-                error!("Error while debugging: {}", e.to_error_string());
+                Task::none()
             }
-        })
-        .discard()
+        }
     }
 
     fn toggle_feature(&self, feature_type: FeatureTarget, feature: String) -> Task<Message> {
@@ -366,6 +2687,85 @@ fn state_key(root_dir: &str) -> String {
     format!("{root_dir}.cargo_tools.workspace.configuration")
 }
 
+/// Number of one-off program arguments which are remembered
+const ARGUMENT_HISTORY_LEN: usize = 10;
+
+fn argument_history_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.workspace.argument_history")
+}
+
+fn test_history_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.workspace.test_history")
+}
+
+/// Keeps the last `cargoTools.output.maxLines` lines of the output of a test run and writes
+/// all of it to a log file in the target directory, which the result refers to
+fn bound_test_output(result: &mut TestRunResult, metadata: &Metadata) -> Task<Message> {
+    let mut output = BoundedOutput::new(output_max_lines());
+    output.push(&result.output);
+    if output.dropped() == 0 {
+        return Task::none();
+    }
+    let log_file = format!("{}/cargo-tools/test-output.log", metadata.target_dir());
+    let full = std::mem::replace(&mut result.output, output.text(&log_file));
+    result.log_file = Some(log_file.clone());
+    Task::future(async move {
+        if let Err(e) = write_file_vs_code(log_file, full).await {
+            error!("Failed to write the test output log: {e}");
+        }
+    })
+    .discard()
+}
+
+/// The diagnostics of the JSON `messages` with the host paths of their files, cargo reports
+/// them relative to its path of the host `workspace_root`
+fn host_diagnostics(messages: &str, workspace_root: &str) -> Vec<FileDiagnostic> {
+    let mappings = path_mappings();
+    parse_diagnostics(messages, &mappings.to_cargo(workspace_root))
+        .into_iter()
+        .map(|diagnostic| diagnostic.map_paths(|file| mappings.to_host(file)))
+        .collect()
+}
+
+/// The compiler messages of the check `process`
+async fn check_diagnostics(process: Process, workspace_root: String) -> Vec<FileDiagnostic> {
+    // The JSON messages hold the suggestions of rustc, which become quick fixes
+    let process = process.with_cargo_args(vec![JSON_MESSAGE_FORMAT.to_string()]);
+    // The progress of cargo and rustc on stderr would break the JSON messages
+    match exec_stdout_vs_code(process).await {
+        Ok(output) => host_diagnostics(&output, &workspace_root),
+        Err(e) => {
+            error!("Failed to check for compiler messages: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// The directories relative paths of panics are resolved against: rustc is invoked in the
+/// workspace root for members, path dependencies are tried by their package directories
+fn output_link_roots(metadata: &Metadata) -> Vec<String> {
+    let package_dirs = metadata.packages().iter().filter_map(|package| {
+        Path::new(&package.manifest)
+            .parent()
+            .map(|dir| dir.to_string_lossy().to_string())
+    });
+    iter::once(metadata.workspace_root().to_string())
+        .chain(package_dirs)
+        .collect()
+}
+
+fn criterion_report_dir(metadata: &Metadata) -> String {
+    format!("{}/{}", metadata.target_dir(), criterion::REPORT_DIR)
+}
+
+fn binary_sizes_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.workspace.binary_sizes")
+}
+
+fn doc_tests_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.workspace.doc_tests")
+}
+
 fn done(fut: impl Future<Output = Option<ConfigUpdate>> + 'static) -> Task<Message> {
     Task::future(fut)
         .and_then(Task::done)
@@ -404,6 +2804,27 @@ impl IntoMessage for ConfigUpdate {
     }
 }
 
+/// Lists the doctests with `process` and lets the user pick one
+async fn select_doc_test(process: Process) -> Option<DocTest> {
+    let options = match exec_vs_code(process).await {
+        Ok(output) => DocTest::parse_list(&output),
+        Err(e) => {
+            error!("Failed to list doctests: {e}");
+            return None;
+        }
+    };
+    if options.is_empty() {
+        error!("The selected package has no doctests");
+        return None;
+    }
+
+    let input = SelectInput {
+        options,
+        current: Vec::new(),
+    };
+    input.select().await
+}
+
 async fn platform_targets() -> Option<Vec<String>> {
     let process = Process::new(
         "rustup".to_string(),
@@ -459,7 +2880,19 @@ async fn set_rust_analyzer_check_targets() {
     let input = SelectInput { options, current };
 
     if let Some(targets) = input.select_multiple(|_| {}).await {
-        update_rust_analyzer_check_targets(targets).await;
+        // No targets remove the setting
+        let targets = (!targets.is_empty()).then_some(targets);
+        write_rust_analyzer_settings(HashMap::from([("check.targets", targets)])).await;
+    }
+}
+
+/// Writes `settings`, which map the keys of the `rust-analyzer` section to their values, into
+/// the workspace settings. [None] values remove a setting.
+async fn write_rust_analyzer_settings(settings: impl Serialize) {
+    // Maps have to become plain objects
+    match settings.serialize(&serde_wasm_bindgen::Serializer::json_compatible()) {
+        Ok(settings) => update_rust_analyzer_settings(settings).await,
+        Err(e) => error!("Failed to serialize the rust-analyzer settings: {e}"),
     }
 }
 
@@ -480,19 +2913,336 @@ async fn select_features(
     })
 }
 
-fn exec_path(target: &RunSubTarget, config: &Config, target_dir: &str) -> String {
-    let path_components = iter::once(target_dir.to_string())
-        .chain(iter::once("debug".to_string())) // For now always assume debug profile
-        .chain(config.platform_target.as_ref().map(|t| t.to_string()))
-        .chain(match target {
-            RunSubTarget::Bin(bin) => vec![bin.clone()],
-            RunSubTarget::Example(example) => {
-                vec!["examples".to_string(), example.to_string()]
+/// Builds the `selected` packages in dependency order, listing the status of each package in
+/// the Task History view
+async fn build_packages(selected: Vec<String>, packages: Vec<Package>, config: Config) {
+    let mut queue = BuildQueue::new(&selected, &packages, build_parallel_jobs());
+    let mut running = FuturesUnordered::new();
+
+    loop {
+        for package in queue.start_ready() {
+            let cmd = CargoCommand::Build(Some(BuildTarget::package_only(package.clone())));
+            let ctx = cmd.ctx();
+            match cmd.try_into_process(&config, ctx) {
+                Ok(process) => {
+                    info!("Building package '{package}'");
+                    running.push(async move {
+                        let success = execute_task_and_wait(VsCodeTask::cargo(process)).await;
+                        (package, success)
+                    });
+                }
+                Err(e) => {
+                    error!("{e}");
+                    queue.finish(&package, false);
+                }
             }
-        })
-        .chain((host_platform() == "windows").then_some(".exe".to_string()));
+        }
+
+        show_package_builds(queue.statuses());
+        show_build_queue_status(Some(format!(
+            "$(sync~spin) Building packages: {} running, {} queued",
+            queue.count(BuildStatus::Running),
+            queue.count(BuildStatus::Queued)
+        )));
+
+        let Some((package, success)) = running.next().await else {
+            break;
+        };
+        queue.finish(&package, success);
+        if let Some(status) = queue.status(&package) {
+            info!("Build of package '{package}' {status}");
+        }
+    }
+
+    show_build_queue_status(None);
+    show_package_builds(queue.statuses());
+
+    for (package, _) in queue
+        .statuses()
+        .filter(|(_, status)| *status == BuildStatus::Skipped)
+    {
+        info!("Skipped build of package '{package}' because one of its dependencies failed");
+    }
+
+    let failed = queue.count(BuildStatus::Failed);
+    let skipped = queue.count(BuildStatus::Skipped);
+    let summary = format!(
+        "Package builds finished: {} succeeded, {failed} failed, {skipped} skipped",
+        queue.count(BuildStatus::Succeeded)
+    );
+    show_build_queue_summary(summary, failed + skipped == 0);
+}
+
+/// Creates a workspace member with `cargo new` from the name, kind and location the user
+/// enters and appends it to `workspace.members`. The picked `libraries`, packages with their
+/// directory, are added as its dependencies.
+async fn new_package(root: String, libraries: Vec<(String, PathBuf)>) -> Option<()> {
+    let root_manifest_path = format!("{root}/Cargo.toml");
+    let name = show_input_box(
+        "Package name, e.g. my-parser".to_string(),
+        "New Package".to_string(),
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let name = name.trim().to_string();
+    if !new_package::is_valid_name(&name) {
+        error!("'{name}' is no valid package name");
+        return None;
+    }
+    let kind = SelectInput {
+        options: PackageKind::all().to_vec(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+
+    let root_manifest = read_file_vs_code(root_manifest_path.clone()).await.ok()?;
+    let location = show_input_box_with_value(
+        "Directory of the package, relative to the workspace root".to_string(),
+        format!("Location of {name}"),
+        new_package::default_location(&root_manifest, &name),
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let location = location.trim().trim_matches('/').to_string();
+
+    let new = metadata_task_context()
+        .with_cwd(Some(root.clone()))
+        .try_into_process(new_package::new_args(kind, &name, &location));
+    let created = match new {
+        Ok(process) => exec_vs_code(process).await,
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = created {
+        error!("Failed to create {name}: {e}");
+        return None;
+    }
+
+    // Recent cargo versions add the member themselves
+    let root_manifest = read_file_vs_code(root_manifest_path.clone()).await.ok()?;
+    if let Some(edited) = new_package::add_workspace_member(&root_manifest, &location)
+        && let Err(e) = write_file_vs_code(root_manifest_path, edited).await
+    {
+        error!("Failed to add {location} to the workspace members: {e}");
+    }
+
+    let package_dir = PathBuf::from_iter([root.as_str(), location.as_str()]);
+    if !libraries.is_empty() {
+        let picked = SelectInput {
+            options: libraries.iter().map(|(name, _)| name.clone()).collect(),
+            current: Vec::new(),
+        }
+        .select_multiple(|_| {})
+        .await
+        .unwrap_or_default();
+        let dependencies: Vec<MemberDependency> = libraries
+            .iter()
+            .filter(|(name, _)| picked.contains(name))
+            .map(|(name, dir)| MemberDependency::new(name, dir, &package_dir, &root_manifest))
+            .collect();
+
+        let manifest_path = package_dir.join("Cargo.toml").to_string_lossy().to_string();
+        if !dependencies.is_empty()
+            && let Ok(manifest) = read_file_vs_code(manifest_path.clone()).await
+        {
+            let edited = new_package::add_dependencies(&manifest, &dependencies);
+            if let Err(e) = write_file_vs_code(manifest_path, edited).await {
+                error!("Failed to add the dependencies of {name}: {e}");
+            }
+        }
+    }
+
+    let source = package_dir.join(kind.source_file());
+    open_file(source.to_string_lossy().to_string()).await;
+    Some(())
+}
+
+/// Opens the docs.rs documentation of the dependency under the cursor of a manifest, of one of
+/// `options` otherwise, pinned to the version of the `lockfile`
+async fn open_dependency_docs(options: Vec<RegistryDependency>, lockfile: String) {
+    let active: Option<DependencyDocs> =
+        serde_wasm_bindgen::from_value(active_dependency_docs().await).unwrap_or_default();
+    let docs = match active {
+        Some(docs) => docs,
+        None => {
+            if options.is_empty() {
+                info!("No crates.io dependencies to open the docs of");
+                return;
+            }
+            let current = Vec::new();
+            let Some(dependency) = SelectInput { options, current }.select().await else {
+                return;
+            };
+            let lock = read_file_vs_code(lockfile).await.ok();
+            DependencyDocs::new(dependency.name, Some(dependency.req), lock.as_deref())
+        }
+    };
+    open_url(docs.url);
+}
+
+/// Generates a package with cargo-generate from one of the favorite `templates`, or a
+/// repository the user enters if there are none, and offers to append it to
+/// `workspace.members`. The user is asked for the values the template declares.
+async fn new_package_from_template(
+    root: String,
+    templates: HashMap<String, Template>,
+) -> Option<()> {
+    let mut options: Vec<(String, Template)> = templates.into_iter().collect();
+    options.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let (template_name, template) = if options.is_empty() {
+        let git = show_input_box(
+            "Git repository of the template, configure favorites in cargoTools.cargoGenerate.templates"
+                .to_string(),
+            "New Package from Template".to_string(),
+        )
+        .await
+        .ok()?
+        .as_string()?;
+        let git = git.trim().to_string();
+        if git.is_empty() {
+            return None;
+        }
+        let template = Template {
+            git: Some(git.clone()),
+            ..Template::default()
+        };
+        (git, template)
+    } else {
+        SelectInput {
+            options,
+            current: Vec::new(),
+        }
+        .select()
+        .await?
+    };
+
+    let name = show_input_box(
+        "Package name, e.g. my-service".to_string(),
+        format!("New Package from {template_name}"),
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let name = name.trim().to_string();
+    if !new_package::is_valid_name(&name) {
+        error!("'{name}' is no valid package name");
+        return None;
+    }
+
+    let mut values = Vec::new();
+    for (key, default) in &template.values {
+        let value =
+            show_input_box_with_value(key.clone(), format!("{key} of {name}"), default.clone())
+                .await
+                .ok()?
+                .as_string()?;
+        values.push((key.clone(), value));
+    }
+
+    let root_manifest_path = format!("{root}/Cargo.toml");
+    let root_manifest = read_file_vs_code(root_manifest_path.clone()).await.ok()?;
+    let default_location = new_package::default_location(&root_manifest, &name);
+    let parent = default_location
+        .rsplit_once('/')
+        .map_or("", |(dir, _)| dir)
+        .to_string();
+    let parent = show_input_box_with_value(
+        "Directory the package is generated in, relative to the workspace root".to_string(),
+        format!("Location of {name}"),
+        parent,
+    )
+    .await
+    .ok()?
+    .as_string()?;
+    let parent = parent.trim().trim_matches('/').to_string();
+    let location = if parent.is_empty() {
+        name.clone()
+    } else {
+        format!("{parent}/{name}")
+    };
 
-    PathBuf::from_iter(path_components)
+    let destination = PathBuf::from_iter([root.as_str(), parent.as_str()])
         .to_string_lossy()
-        .to_string()
+        .to_string();
+    let Some(args) = template.args(&name, &destination, &values) else {
+        error!("The template {template_name} has neither a git repository nor a path");
+        return None;
+    };
+    let generate = metadata_task_context()
+        .with_cwd(Some(root.clone()))
+        .try_into_process(args);
+    let generated = match generate {
+        Ok(process) => exec_vs_code(process).await,
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = generated {
+        error!("Failed to generate {name} from {template_name}, is cargo-generate installed? {e}");
+        return None;
+    }
+
+    // Recent cargo-generate versions add the member themselves
+    let root_manifest = read_file_vs_code(root_manifest_path.clone()).await.ok()?;
+    if let Some(edited) = new_package::add_workspace_member(&root_manifest, &location)
+        && confirm_workspace_member(location.clone()).await.as_bool() == Some(true)
+        && let Err(e) = write_file_vs_code(root_manifest_path, edited).await
+    {
+        error!("Failed to add {location} to the workspace members: {e}");
+    }
+
+    let manifest = PathBuf::from_iter([root.as_str(), location.as_str(), "Cargo.toml"]);
+    open_file(manifest.to_string_lossy().to_string()).await;
+    Some(())
+}
+
+/// Shows the statistics of `sccache`, the path of the detected sccache `RUSTC_WRAPPER`
+async fn compiler_cache_stats(sccache: Option<String>) {
+    let Some(sccache) = sccache else {
+        show_compiler_cache_stats_failed(
+            "sccache is not the RUSTC_WRAPPER of cargo commands, enable it with cargoTools.sccache.enabled".to_string(),
+        );
+        return;
+    };
+    let process = Process::new(sccache, sccache::stats_args(), HashMap::new());
+    let stats = exec_vs_code(process)
+        .await
+        .and_then(|json| Stats::parse(&json).map_err(|e| e.to_string()));
+    match stats {
+        Ok(stats) => show_compiler_cache_stats(stats.to_markdown()).await,
+        Err(e) => show_compiler_cache_stats_failed(format!("Failed to get the sccache stats: {e}")),
+    }
+}
+
+/// The files of the workspace at `root` changed since its merge base with `base`, committed or
+/// not, and the untracked ones
+async fn changed_files(root: String, base: String) -> Option<Vec<String>> {
+    let git = |args: &[&str]| {
+        Process::new(
+            "git".to_string(),
+            args.iter().map(ToString::to_string).collect(),
+            HashMap::new(),
+        )
+        .with_cwd(Some(root.clone()))
+    };
+    // Both list the paths relative to the workspace root
+    let diff = git(&["diff", "--name-only", "--relative", "--merge-base", &base]);
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"]);
+
+    let mut files = Vec::new();
+    for process in [diff, untracked] {
+        match exec_vs_code(process).await {
+            Ok(output) => files.extend(
+                output
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(|line| format!("{root}/{line}")),
+            ),
+            Err(e) => {
+                error!("Failed to list the files changed since {base}: {e}");
+                return None;
+            }
+        }
+    }
+    Some(files)
 }
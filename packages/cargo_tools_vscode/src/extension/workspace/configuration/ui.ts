@@ -1,12 +1,16 @@
 import * as vscode from 'vscode';
+import * as path from 'path';
 import { CargoConfigurationTreeProviderHandler } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
 import { CargoNode } from './treeprovider';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
 
 export class CargoConfigurationTreeProvider implements vscode.TreeDataProvider<CargoNode> {
     private _onDidChangeTreeData: vscode.EventEmitter<CargoNode | undefined | null | void> = new vscode.EventEmitter<CargoNode | undefined | null | void>();
     readonly onDidChangeTreeData: vscode.Event<CargoNode | undefined | null | void> = this._onDidChangeTreeData.event;
 
     private handler: CargoConfigurationTreeProviderHandler;
+    // Whether a refresh is scheduled, the updates of one selection change refresh the view once
+    private refreshPending = false;
 
     constructor(handler: CargoConfigurationTreeProviderHandler) {
         this.handler = handler;
@@ -20,7 +24,14 @@ export class CargoConfigurationTreeProvider implements vscode.TreeDataProvider<C
     }
 
     update(): void {
-        this._onDidChangeTreeData.fire();
+        if (this.refreshPending) {
+            return;
+        }
+        this.refreshPending = true;
+        setTimeout(() => {
+            this.refreshPending = false;
+            this._onDidChangeTreeData.fire();
+        });
     }
 
     getTreeItem(element: CargoNode): vscode.TreeItem {
@@ -32,19 +43,121 @@ export class CargoConfigurationTreeProvider implements vscode.TreeDataProvider<C
     }
 }
 
+let buildQueueStatus: vscode.StatusBarItem | undefined;
+
+export function show_build_queue_status(status?: string): void {
+    if (status === undefined) {
+        buildQueueStatus?.hide();
+        return;
+    }
+    if (!buildQueueStatus) {
+        buildQueueStatus = vscode.window.createStatusBarItem(vscode.StatusBarAlignment.Left);
+        buildQueueStatus.tooltip = 'Cargo Tools package builds';
+        extension_context?.subscriptions.push(buildQueueStatus);
+    }
+    buildQueueStatus.text = status;
+    buildQueueStatus.show();
+}
+
+export function show_build_queue_summary(summary: string, success: boolean): void {
+    if (success) {
+        vscode.window.showInformationMessage(summary);
+    } else {
+        vscode.window.showWarningMessage(summary);
+    }
+}
+
+export function show_tests_skipped(): void {
+    vscode.window.showWarningMessage('Build failed, tests were not run');
+}
+
+export async function show_compiler_cache_stats(markdown: string): Promise<void> {
+    const document = await vscode.workspace.openTextDocument({ content: markdown, language: 'markdown' });
+    await vscode.commands.executeCommand('markdown.showPreview', document.uri);
+}
+
+export async function open_file(path: string): Promise<void> {
+    await vscode.window.showTextDocument(vscode.Uri.file(path));
+}
+
+export async function confirm_workspace_member(member: string): Promise<boolean> {
+    const choice = await vscode.window.showInformationMessage(
+        `Add ${member} to the workspace members?`, 'Add');
+    return choice === 'Add';
+}
+
+export function show_compiler_cache_stats_failed(reason: string): void {
+    vscode.window.showWarningMessage(reason);
+}
+
+let watchStatus: vscode.StatusBarItem | undefined;
+
+export function show_watch_status(active: boolean): void {
+    if (!watchStatus) {
+        watchStatus = vscode.window.createStatusBarItem(vscode.StatusBarAlignment.Left);
+        watchStatus.text = '$(eye) Watching';
+        watchStatus.tooltip = 'Cargo Tools watch mode is active. Click to stop watching.';
+        watchStatus.command = 'cargo-tools.toggleWatch';
+        extension_context?.subscriptions.push(watchStatus);
+    }
+    if (active) {
+        watchStatus.show();
+    } else {
+        watchStatus.hide();
+    }
+}
+
 export function get_rust_analyzer_check_targets(): string[] {
     let config = vscode.workspace.getConfiguration('rust-analyzer');
     return config.get('check.targets', []) || [];
 }
 
-export async function update_rust_analyzer_check_targets(targets: string[]) {
-    let config = vscode.workspace.getConfiguration('rust-analyzer');
+export async function update_rust_analyzer_settings(settings: Record<string, unknown>): Promise<void> {
+    const config = vscode.workspace.getConfiguration('rust-analyzer');
+    try {
+        for (const [key, value] of Object.entries(settings)) {
+            // Unset values remove the setting, e.g. without a platform target rust-analyzer
+            // analyzes for the host
+            await config.update(key, value ?? undefined, vscode.ConfigurationTarget.Workspace);
+        }
+    } catch (e) {
+        // The settings are unknown if rust-analyzer isn't installed
+        vscode.window.showWarningMessage(`Failed to update the rust-analyzer settings: ${e}`);
+    }
+}
+
+export async function find_pending_snapshots(root: string, extension: string): Promise<string[]> {
+    const files = await vscode.workspace.findFiles(
+        new vscode.RelativePattern(root, `**/*${extension}`), '**/target/**');
+    return files.map((file) => path.relative(root, file.fsPath)).sort();
+}
+
+/**
+ * Shows the pending snapshot as diff to the accepted one and lets the user accept or reject it,
+ * returns whether a choice was made
+ */
+export async function review_snapshot(pending: string, accepted: string): Promise<boolean> {
+    const pendingUri = vscode.Uri.file(pending);
+    const acceptedUri = vscode.Uri.file(accepted);
+    const exists = await vscode.workspace.fs.stat(acceptedUri).then(() => true, () => false);
+    const name = path.basename(accepted);
+    if (exists) {
+        await vscode.commands.executeCommand('vscode.diff', acceptedUri, pendingUri,
+            `${name} (accepted ↔ pending)`);
+    } else {
+        await vscode.window.showTextDocument(pendingUri);
+    }
 
-    if (targets.length === 0) {
-        // Remove setting if no targets selected
-        await config.update('check.targets', undefined, vscode.ConfigurationTarget.Workspace);
+    const choice = await vscode.window.showInformationMessage(
+        exists ? `Accept the pending change of ${name}?` : `Accept the new snapshot ${name}?`,
+        'Accept', 'Reject');
+    if (choice === 'Accept') {
+        await vscode.workspace.fs.rename(pendingUri, acceptedUri, { overwrite: true });
+    } else if (choice === 'Reject') {
+        await vscode.workspace.fs.delete(pendingUri);
     } else {
-        // Set the new targets
-        await config.update('check.targets', targets, vscode.ConfigurationTarget.Workspace);
+        return false;
     }
+    await vscode.commands.executeCommand('workbench.action.closeActiveEditor');
+    return true;
 }
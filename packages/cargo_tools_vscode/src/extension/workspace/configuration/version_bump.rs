@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use cargo_tools::{
+    cargo::version_bump::{
+        Bump, Version, inherits_version, is_publishable, package_version, set_dependency_version,
+        set_version, tag_name,
+    },
+    process::Process,
+};
+use tracing::{error, info};
+
+use crate::{
+    environment::metadata_task_context,
+    extension::workspace::configuration::manifests::{normalize, read_manifests},
+    quick_pick::SelectInput,
+    runtime::{VsCodeTask, exec_vs_code, execute_task_and_wait, write_file_vs_code},
+};
+
+const ALL_PUBLISHABLE: &str = "All publishable members";
+const ONLY_MANIFESTS: &str = "Only update the manifests";
+const COMMIT_AND_TAG: &str = "Commit and tag";
+
+/// Bumps the version of a picked package, or of all publishable `packages` given by name and
+/// manifest, and the requirements of the workspace on them. The bumped manifests can be
+/// committed together with `Cargo.lock` and tagged.
+pub async fn bump_version(
+    root: String,
+    packages: Vec<(String, String)>,
+    selected: Option<String>,
+) -> Option<()> {
+    let mut options = vec![ALL_PUBLISHABLE.to_string()];
+    options.extend(packages.iter().map(|(name, _)| name.clone()));
+    let current = selected.into_iter().collect();
+    let scope = SelectInput { options, current }.select().await?;
+    let bump = SelectInput {
+        options: Bump::all().to_vec(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+
+    // The root manifest first, it is the manifest of the root package too
+    let root_manifest = format!("{root}/Cargo.toml");
+    let manifests: Vec<String> = packages.iter().map(|(_, m)| m.clone()).collect();
+    let (mut files, indices) = read_manifests(&root_manifest, &manifests).await?;
+    let package_files: Vec<(String, usize)> = packages
+        .iter()
+        .map(|(name, _)| name.clone())
+        .zip(indices)
+        .collect();
+    let original: Vec<String> = files.iter().map(|(_, content)| content.clone()).collect();
+    let root_content = original[0].clone();
+
+    let bumped: Vec<&(String, usize)> = package_files
+        .iter()
+        .filter(|(name, i)| match scope.as_str() {
+            ALL_PUBLISHABLE => is_publishable(&original[*i]),
+            scope => scope == name,
+        })
+        .collect();
+    if bumped.is_empty() {
+        info!("The workspace has no publishable members to bump");
+        return None;
+    }
+
+    let mut released: Vec<(String, Version)> = Vec::new();
+    let mut workspace_version = None;
+    for (name, i) in bumped {
+        let Some(version) = package_version(&original[*i], &root_content) else {
+            error!("{name} declares no version to bump");
+            continue;
+        };
+        let next = bump.apply(&version);
+        if inherits_version(&original[*i]) {
+            workspace_version.get_or_insert(next.clone());
+        } else if let Some(edited) = set_version(&files[*i].1, "package", &next) {
+            files[*i].1 = edited;
+        }
+        released.push((name.clone(), next));
+    }
+    if let Some(version) = workspace_version {
+        match set_version(&files[0].1, "workspace.package", &version) {
+            Some(edited) => files[0].1 = edited,
+            None => error!("{root_manifest} declares no [workspace.package] version"),
+        }
+        // All packages which inherit the version are released with it
+        for (name, i) in &package_files {
+            if inherits_version(&original[*i]) && !released.iter().any(|(n, _)| n == name) {
+                released.push((name.clone(), version.clone()));
+            }
+        }
+    }
+
+    for (name, version) in &released {
+        for (_, content) in &mut files {
+            *content = set_dependency_version(content, name, version);
+        }
+    }
+    let mut changed = Vec::new();
+    for ((path, content), original) in files.into_iter().zip(&original) {
+        if content == *original {
+            continue;
+        }
+        match write_file_vs_code(path.clone(), content).await {
+            Ok(()) => changed.push(path),
+            Err(e) => error!("Failed to update {path}: {e}"),
+        }
+    }
+
+    let single_package = packages.len() == 1;
+    let bumped: Vec<String> = released
+        .iter()
+        .map(|(name, version)| format!("{name} {version}"))
+        .collect();
+    info!("Bumped {}", bumped.join(", "));
+    // Packages with `publish = false` aren't released, they get no tag
+    let tags: Vec<String> = released
+        .iter()
+        .filter(|(name, _)| {
+            package_files
+                .iter()
+                .any(|(n, i)| n == name && is_publishable(&original[*i]))
+        })
+        .map(|(name, version)| tag_name(name, version, single_package))
+        .collect();
+
+    let action = SelectInput {
+        options: [ONLY_MANIFESTS, COMMIT_AND_TAG]
+            .map(str::to_string)
+            .to_vec(),
+        current: Vec::new(),
+    }
+    .select()
+    .await?;
+    if action == COMMIT_AND_TAG {
+        commit_and_tag(root, changed, &bumped, tags).await;
+    }
+    Some(())
+}
+
+/// Updates `Cargo.lock` to the `bumped` versions, commits it with the `changed` manifests and
+/// creates an annotated tag for each of `tags`. Gitignored files like the `Cargo.lock` of a
+/// library aren't committed, other changes of the working tree neither. Stops at the first
+/// failing step.
+async fn commit_and_tag(root: String, changed: Vec<String>, bumped: &[String], tags: Vec<String>) {
+    let update = metadata_task_context()
+        .with_cwd(Some(root.clone()))
+        .try_into_process(vec!["update".to_string(), "--workspace".to_string()]);
+    match update {
+        Ok(process) => {
+            if !execute_task_and_wait(VsCodeTask::cargo(process)).await {
+                error!("cargo update failed, nothing was committed");
+                return;
+            }
+        }
+        Err(e) => {
+            error!("{e}");
+            return;
+        }
+    }
+
+    let git = |args: Vec<String>| {
+        Process::new("git".to_string(), args, HashMap::new()).with_cwd(Some(root.clone()))
+    };
+    let mut paths = changed;
+    paths.push(format!("{root}/Cargo.lock"));
+    let mut check_ignore = vec!["check-ignore".to_string(), "--".to_string()];
+    check_ignore.extend(paths.iter().cloned());
+    // Exits with 1 if none of the paths is ignored
+    let ignored = exec_vs_code(git(check_ignore)).await.unwrap_or_default();
+    let ignored: Vec<&str> = ignored.lines().map(str::trim).collect();
+    paths.retain(|path| !ignored.iter().any(|i| normalize(i) == normalize(path)));
+    if paths.is_empty() {
+        error!("All changed files are ignored by git, nothing was committed");
+        return;
+    }
+
+    let message = format!("Release {}", bumped.join(", "));
+    let mut add = vec!["add".to_string(), "--".to_string()];
+    add.extend(paths.iter().cloned());
+    let mut commit = ["commit", "-m", &message, "--"]
+        .map(str::to_string)
+        .to_vec();
+    commit.extend(paths);
+    let tag = |tag: &String| ["tag", "-a", tag, "-m", tag].map(str::to_string).to_vec();
+
+    for args in [add, commit].into_iter().chain(tags.iter().map(tag)) {
+        if let Err(e) = exec_vs_code(git(args.clone())).await {
+            error!("git {} failed: {e}", args.join(" "));
+            return;
+        }
+    }
+    info!("Committed {message} and tagged {}", tags.join(", "));
+}
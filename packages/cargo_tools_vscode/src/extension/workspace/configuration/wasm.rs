@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use cargo_tools::{
+    cargo::{Config, metadata::Package},
+    process::Process,
+    wasm::{TRUNK_FILES, WasmTool},
+};
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    environment::wasm_task_context,
+    runtime::{
+        VsCodeTask, exec_vs_code, execute_task, file_exists_vs_code, open_url, show_warning_message,
+    },
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/wasm.ts"
+)]
+extern "C" {
+    async fn serve_wasm(task: VsCodeTask, name: String);
+}
+
+const INSTALL_INSTRUCTIONS: &str = "Install Instructions";
+
+/// The tool building `package`, Trunk if one of the [TRUNK_FILES] is in its directory
+async fn wasm_tool(package: &Package) -> Option<WasmTool> {
+    let mut has_trunk_file = false;
+    for file in TRUNK_FILES {
+        if file_exists_vs_code(format!("{}/{file}", package.manifest_dir())).await {
+            has_trunk_file = true;
+            break;
+        }
+    }
+    let tool = WasmTool::of(package, has_trunk_file);
+    if tool.is_none() {
+        error!("{} doesn't depend on wasm-bindgen", package.name);
+    }
+    tool
+}
+
+/// Whether `tool` is installed, offers its installation instructions if it isn't
+async fn tool_installed(tool: WasmTool) -> bool {
+    let Some(program) = tool.program() else {
+        return true;
+    };
+    let version = Process::new(
+        program.to_string(),
+        vec!["--version".to_string()],
+        HashMap::new(),
+    );
+    if exec_vs_code(version).await.is_ok() {
+        return true;
+    }
+    let message = format!("{program} is not installed");
+    let action = show_warning_message(&message, vec![INSTALL_INSTRUCTIONS.to_string()]).await;
+    if action.as_string().as_deref() == Some(INSTALL_INSTRUCTIONS) {
+        open_url(tool.install_url().to_string());
+    }
+    false
+}
+
+fn wasm_process(tool: WasmTool, package: &Package, args: Vec<String>) -> Option<Process> {
+    let ctx = wasm_task_context(tool, package.manifest_dir().to_string());
+    ctx.try_into_process(args)
+        .inspect_err(|e| error!("{e}"))
+        .ok()
+}
+
+/// Builds `package` with Trunk, wasm-pack or cargo for wasm32, whichever fits it, with the
+/// selected profile and features of `config`
+pub async fn build_wasm(package: Package, config: Config) {
+    let Some(tool) = wasm_tool(&package).await else {
+        return;
+    };
+    if !tool_installed(tool).await {
+        return;
+    }
+    let args = tool.build_args(&package.name, &config);
+    if let Some(process) = wasm_process(tool, &package, args) {
+        execute_task(VsCodeTask::cargo(process)).await;
+    }
+}
+
+/// Serves `package` with `trunk serve` and notifies about the URL of the dev server
+pub async fn serve_wasm_package(package: Package, config: Config) {
+    let Some(tool) = wasm_tool(&package).await else {
+        return;
+    };
+    let Some(args) = tool.serve_args(&package.name, &config) else {
+        error!(
+            "Serving {} needs Trunk and a Trunk.toml or index.html in its directory",
+            package.name
+        );
+        return;
+    };
+    if !tool_installed(tool).await {
+        return;
+    }
+    if let Some(process) = wasm_process(tool, &package, args) {
+        serve_wasm(VsCodeTask::cargo(process), package.name).await;
+    }
+}
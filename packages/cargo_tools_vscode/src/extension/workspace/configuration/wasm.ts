@@ -0,0 +1,97 @@
+import * as vscode from 'vscode';
+import { spawn } from 'child_process';
+import { VsCodeTask } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { killTree } from '../../../runtime';
+
+// Dev servers like trunk print e.g. `🏠 http://127.0.0.1:8080/` once they listen
+const URL_PATTERN = /https?:\/\/[^\s"'<>]+/;
+const ANSI_PATTERN = /\x1b\[[0-9;]*m/g;
+
+async function notifyDevServer(name: string, url: string): Promise<void> {
+    // In remote sessions the port has to be forwarded to be reachable from the local browser
+    const external = await vscode.env.asExternalUri(vscode.Uri.parse(url));
+    const action = await vscode.window.showInformationMessage(
+        `${name} is served at ${external.toString(true)}`,
+        'Open in Browser',
+    );
+    if (action) {
+        vscode.env.openExternal(external);
+    }
+}
+
+/**
+ * Runs the dev server of `cargo_tools_task` in a terminal of its own and notifies about the
+ * first URL it prints. Closing the terminal stops the server.
+ */
+export async function serve_wasm(cargo_tools_task: VsCodeTask, name: string): Promise<void> {
+    const cmd = cargo_tools_task.cmd();
+    const args = cargo_tools_task.args();
+    const env: { [key: string]: string } = Object.fromEntries(cargo_tools_task.env());
+    const cwd = cargo_tools_task.cwd() ?? vscode.workspace.workspaceFolders?.[0]?.uri.fsPath;
+
+    const execution = new vscode.CustomExecution(async () => {
+        const write = new vscode.EventEmitter<string>();
+        const close = new vscode.EventEmitter<number>();
+        let child: ReturnType<typeof spawn> | undefined;
+        let notified = false;
+
+        const onOutput = (data: string) => {
+            write.fire(data.replace(/\r?\n/g, '\r\n'));
+            const url = notified ? null : data.replace(ANSI_PATTERN, '').match(URL_PATTERN);
+            if (url) {
+                notified = true;
+                notifyDevServer(name, url[0]);
+            }
+        };
+
+        return {
+            onDidWrite: write.event,
+            onDidClose: close.event,
+            open: () => {
+                write.fire(`> ${[cmd, ...args].join(' ')}\r\n`);
+                child = spawn(cmd, args, {
+                    cwd,
+                    env: { ...process.env, ...env },
+                    detached: process.platform !== 'win32',
+                });
+                child.stdout?.setEncoding('utf8');
+                child.stderr?.setEncoding('utf8');
+                child.stdout?.on('data', onOutput);
+                child.stderr?.on('data', onOutput);
+                child.on('error', (e) => {
+                    write.fire(`Failed to run ${cmd}: ${e.message}\r\n`);
+                    close.fire(1);
+                });
+                child.on('close', (code) => close.fire(code ?? 0));
+            },
+            close: () => {
+                if (child) {
+                    killTree(child);
+                }
+            },
+        };
+    });
+
+    const task = new vscode.Task(
+        { type: cargo_tools_task.task_type(), args },
+        vscode.TaskScope.Workspace,
+        `Serve ${name}`,
+        cargo_tools_task.task_type(),
+        execution,
+        ['$rustc'],
+    );
+    task.isBackground = true;
+    task.presentationOptions = {
+        reveal: vscode.TaskRevealKind.Always,
+        panel: vscode.TaskPanelKind.Dedicated,
+        focus: false,
+        clear: true,
+    };
+
+    try {
+        await vscode.tasks.executeTask(task);
+    } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        vscode.window.showErrorMessage(`Failed to serve ${name}: ${message}`);
+    }
+}
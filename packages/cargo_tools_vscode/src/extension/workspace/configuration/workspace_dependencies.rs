@@ -0,0 +1,97 @@
+use std::path::PathBuf;
+
+use cargo_tools::cargo::workspace_dependencies;
+use tracing::{error, info};
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    extension::workspace::configuration::manifests::{normalize, read_manifests},
+    quick_pick::SelectInput,
+    runtime::write_file_vs_code,
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/configuration/workspace_dependencies.ts"
+)]
+extern "C" {
+    pub type WorkspaceDependencyActionProvider;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> WorkspaceDependencyActionProvider;
+}
+
+/// The dependency at `line` of a manifest the typescript code action provider offers to move
+/// to `[workspace.dependencies]`
+#[wasm_bindgen]
+pub fn promotable_dependency_at(manifest: &str, line: u32) -> Option<String> {
+    workspace_dependencies::promotable_at(manifest, line as usize)
+}
+
+/// Moves the dependency `name`, or a picked one several members declare, from the `members`
+/// manifests into `[workspace.dependencies]` of the manifest `root`
+pub async fn promote_to_workspace_dependency(
+    name: Option<String>,
+    root: String,
+    members: Vec<String>,
+) -> Option<()> {
+    let (files, indices) = read_manifests(&root, &members).await?;
+    let root_manifest = files[0].1.clone();
+    let manifests: Vec<String> = indices.iter().map(|i| files[*i].1.clone()).collect();
+
+    let name = match name {
+        Some(name) => name,
+        None => {
+            let options = workspace_dependencies::promotable(&root_manifest, &manifests);
+            if options.is_empty() {
+                info!(
+                    "No dependency is declared by several members without [workspace.dependencies]"
+                );
+                return None;
+            }
+            let current = Vec::new();
+            SelectInput { options, current }.select().await?
+        }
+    };
+
+    let root_member = indices.iter().position(|i| *i == 0);
+    let root_dir = normalize(&root).trim_end_matches("/Cargo.toml").to_string();
+    let dirs: Vec<PathBuf> = members
+        .iter()
+        .map(|member| {
+            let dir = normalize(member)
+                .trim_end_matches("/Cargo.toml")
+                .to_string();
+            match dir.strip_prefix(&format!("{root_dir}/")) {
+                Some(relative) => PathBuf::from(relative),
+                None if dir == root_dir => PathBuf::new(),
+                None => PathBuf::from(dir),
+            }
+        })
+        .collect();
+    let (edited_root, edited_members) =
+        workspace_dependencies::promote(&name, &root_manifest, &manifests, &dirs, root_member)
+            .inspect_err(|e| error!("{e}"))
+            .ok()?;
+
+    let mut edits: Vec<(String, String, &str)> = members
+        .into_iter()
+        .zip(edited_members)
+        .zip(&manifests)
+        .filter_map(|((member, edited), original)| Some((member, edited?, original.as_str())))
+        .collect();
+    edits.push((root.clone(), edited_root, root_manifest.as_str()));
+    // The manifests written before a failed write are restored to keep the workspace consistent
+    for (i, (path, edited, _)) in edits.iter().enumerate() {
+        if let Err(e) = write_file_vs_code(path.clone(), edited.clone()).await {
+            error!("Failed to update {path}, no manifest was changed: {e}");
+            for (path, _, original) in &edits[..i] {
+                if let Err(e) = write_file_vs_code(path.clone(), original.to_string()).await {
+                    error!("Failed to restore {path}: {e}");
+                }
+            }
+            return None;
+        }
+    }
+    info!("Moved {name} to [workspace.dependencies] of {root}");
+    Some(())
+}
@@ -0,0 +1,33 @@
+import * as vscode from 'vscode';
+import { promotable_dependency_at } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+/** Offers to move the dependency under the cursor of a member manifest to `[workspace.dependencies]` */
+export class WorkspaceDependencyActionProvider implements vscode.CodeActionProvider {
+    static readonly providedCodeActionKinds = [vscode.CodeActionKind.RefactorRewrite];
+
+    constructor() {
+        extension_context?.subscriptions.push(
+            vscode.languages.registerCodeActionsProvider({ scheme: 'file', pattern: '**/Cargo.toml' }, this, {
+                providedCodeActionKinds: WorkspaceDependencyActionProvider.providedCodeActionKinds,
+            })
+        );
+    }
+
+    provideCodeActions(document: vscode.TextDocument, range: vscode.Range): vscode.CodeAction[] {
+        const name: string | undefined = promotable_dependency_at(document.getText(), range.start.line);
+        if (!name) {
+            return [];
+        }
+        const action = new vscode.CodeAction(
+            `Move ${name} to [workspace.dependencies]`,
+            vscode.CodeActionKind.RefactorRewrite
+        );
+        action.command = {
+            title: action.title,
+            command: 'cargo-tools.promoteToWorkspaceDependency',
+            arguments: [name],
+        };
+        return [action];
+    }
+}
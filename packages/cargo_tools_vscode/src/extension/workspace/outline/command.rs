@@ -1,6 +1,7 @@
 use cargo_tools::cargo::{
     command::{BenchTarget, BuildTarget, RunTarget},
     config::{FeatureTarget, Update},
+    new_target::TargetKind,
 };
 use futures::channel::mpsc::Sender;
 use wasm_bindgen_futures::js_sys::Array;
@@ -33,6 +34,7 @@ pub enum Command {
     Run(RunTarget),
     Debug(RunTarget),
     Bench(BenchTarget),
+    DebugBench(BenchTarget),
     SelectWorkspaceMemberFilter,
     EditWorkspaceMemberFilter(String),
     SelectTargetTypeFilter,
@@ -43,6 +45,22 @@ pub enum Command {
         feature_type: FeatureTarget,
         feature: String,
     },
+    /// Opens the crates.io page of the dependency
+    OpenOnCratesIo(String),
+    /// Opens the docs.rs documentation of the dependency with its version requirement
+    OpenDocsRs(String, String),
+    /// Opens the `[patch]`, `[replace]` or `paths` override of the dependency of the package
+    GoToOverride {
+        package: String,
+        name: String,
+    },
+    /// Shows the paths through which the package depends on the dependency
+    ExplainDependency {
+        package: String,
+        name: String,
+    },
+    /// Creates an example, integration test or benchmark in the package
+    NewTarget(String, TargetKind),
 }
 
 type CmdFn = fn(Array) -> Option<Command>;
@@ -134,6 +152,11 @@ impl Command {
                     .and_then(OutlineNodeType::try_into_bench_target)
                     .map(Self::Bench)
             }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_DEBUG_BENCH_TARGET, |arg| {
+                try_get_node_type(arg)
+                    .and_then(OutlineNodeType::try_into_bench_target)
+                    .map(Self::DebugBench)
+            }),
             (
                 CARGO_TOOLS_PROJECT_OUTLINE_SET_WORKSPACE_MEMBER_FILTER,
                 |_| Some(Self::SelectWorkspaceMemberFilter),
@@ -169,6 +192,35 @@ impl Command {
                     _ => None,
                 },
             ),
+            (CARGO_TOOLS_PROJECT_OUTLINE_OPEN_ON_CRATES_IO, |arg| {
+                try_get_node_type(arg)
+                    .and_then(OutlineNodeType::try_into_dependency)
+                    .map(|(name, _)| Self::OpenOnCratesIo(name))
+            }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_OPEN_DOCS_RS, |arg| {
+                try_get_node_type(arg)
+                    .and_then(OutlineNodeType::try_into_dependency)
+                    .map(|(name, req)| Self::OpenDocsRs(name, req))
+            }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_GO_TO_OVERRIDE, |arg| {
+                try_get_node_type(arg)
+                    .and_then(OutlineNodeType::try_into_package_dependency)
+                    .map(|(package, name)| Self::GoToOverride { package, name })
+            }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_EXPLAIN_DEPENDENCY, |arg| {
+                try_get_node_type(arg)
+                    .and_then(OutlineNodeType::try_into_package_dependency)
+                    .map(|(package, name)| Self::ExplainDependency { package, name })
+            }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_NEW_EXAMPLE, |arg| {
+                Self::new_target(TargetKind::Example, arg)
+            }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_NEW_INTEGRATION_TEST, |arg| {
+                Self::new_target(TargetKind::Test, arg)
+            }),
+            (CARGO_TOOLS_PROJECT_OUTLINE_NEW_BENCHMARK, |arg| {
+                Self::new_target(TargetKind::Bench, arg)
+            }),
         ]
     }
 
@@ -178,6 +230,12 @@ impl Command {
     ) -> Option<Self> {
         take_first(arg).map(cmd)
     }
+
+    fn new_target(kind: TargetKind, arg: Array) -> Option<Self> {
+        try_get_node_type(arg)
+            .and_then(OutlineNodeType::try_into_package)
+            .map(|package| Self::NewTarget(package, kind))
+    }
 }
 
 pub fn register_outline_commands(tx: Sender<Command>) -> Vec<CommandBinding> {
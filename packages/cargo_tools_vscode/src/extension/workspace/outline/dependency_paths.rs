@@ -0,0 +1,88 @@
+use cargo_tools::cargo::{
+    Dependent,
+    metadata::resolve_args,
+    reverse_dependencies::{direct_dependency_specs, parse_tree, tree_args},
+};
+use serde::Serialize;
+use serde_wasm_bindgen::to_value;
+use tracing::error;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    environment::{metadata_task_context, path_mappings},
+    runtime::exec_vs_code,
+};
+
+#[wasm_bindgen(
+    raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/outline/dependency_paths.ts"
+)]
+extern "C" {
+    /// Shows the paths through which a package depends on a dependency
+    #[derive(Clone)]
+    pub type DependencyPathsView;
+
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> DependencyPathsView;
+
+    #[wasm_bindgen(method)]
+    fn show_paths(this: &DependencyPathsView, paths: JsValue);
+}
+
+#[derive(Serialize)]
+struct DependencyPaths<'a> {
+    package: &'a str,
+    dependency: &'a str,
+    roots: &'a [Dependent],
+}
+
+async fn cargo(args: Vec<String>) -> Option<String> {
+    let process = metadata_task_context()
+        .try_into_process(args)
+        .inspect_err(|e| error!("{e}"))
+        .ok()?;
+    exec_vs_code(process)
+        .await
+        .inspect_err(|e| error!("{e}"))
+        .ok()
+}
+
+/// Shows in `view` which packages pull `dependency` into `package`, whose manifest is
+/// `manifest`, by running `cargo tree --invert` for the versions `package` depends on
+pub async fn explain_dependency(
+    view: DependencyPathsView,
+    manifest: String,
+    package: String,
+    dependency: String,
+) -> Option<()> {
+    let mappings = path_mappings();
+    let manifest = mappings.to_cargo(&manifest);
+    let metadata = cargo(resolve_args(&manifest)).await?;
+    let specs = direct_dependency_specs(&metadata, &package, &dependency)
+        .inspect_err(|e| error!("Failed to parse the dependencies: {e}"))
+        .ok()?;
+    if specs.is_empty() {
+        error!("{package} doesn't depend on {dependency}");
+        return None;
+    }
+    let output = cargo(tree_args(&manifest, Some(&package), &specs, false)).await?;
+    let mut roots = parse_tree(&output);
+    map_paths(&mut roots, &|path| mappings.to_host(path));
+
+    let paths = DependencyPaths {
+        package: &package,
+        dependency: &dependency,
+        roots: &roots,
+    };
+    match to_value(&paths) {
+        Ok(paths) => view.show_paths(paths),
+        Err(e) => error!("Failed to show the paths to {dependency}: {e}"),
+    }
+    Some(())
+}
+
+fn map_paths(nodes: &mut [Dependent], map: &impl Fn(&str) -> String) {
+    for node in nodes {
+        node.path = node.path.as_deref().map(map);
+        map_paths(&mut node.dependents, map);
+    }
+}
@@ -0,0 +1,71 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+interface Dependent {
+    label: string;
+    path?: string;
+    dependents: Dependent[];
+}
+
+interface DependencyPaths {
+    package: string;
+    dependency: string;
+    roots: Dependent[];
+}
+
+/**
+ * Shows the packages of the last "Explain Dependency" which depend on the dependency, each
+ * with the packages depending on it as children down to the workspace member. The view
+ * appears after the first explanation.
+ */
+export class DependencyPathsView implements vscode.TreeDataProvider<Dependent> {
+    private _onDidChangeTreeData = new vscode.EventEmitter<Dependent | undefined | null | void>();
+    readonly onDidChangeTreeData = this._onDidChangeTreeData.event;
+
+    private view: vscode.TreeView<Dependent>;
+    private paths: DependencyPaths = { package: '', dependency: '', roots: [] };
+
+    constructor() {
+        this.view = vscode.window.createTreeView('cargoToolsDependencyPaths', { treeDataProvider: this });
+        extension_context?.subscriptions.push(this.view);
+    }
+
+    getTreeItem(node: Dependent): vscode.TreeItem {
+        const isRoot = this.paths.roots.includes(node);
+        const item = new vscode.TreeItem(
+            node.label,
+            node.dependents.length === 0
+                ? vscode.TreeItemCollapsibleState.None
+                : isRoot
+                    ? vscode.TreeItemCollapsibleState.Expanded
+                    : vscode.TreeItemCollapsibleState.Collapsed
+        );
+        if (node.path) {
+            const manifest = vscode.Uri.file(path.join(node.path, 'Cargo.toml'));
+            item.description = vscode.workspace.asRelativePath(node.path);
+            item.iconPath = new vscode.ThemeIcon('package');
+            item.tooltip = manifest.fsPath;
+            item.command = { command: 'vscode.open', title: 'Open Manifest', arguments: [manifest] };
+        } else {
+            item.iconPath = new vscode.ThemeIcon(isRoot ? 'target' : 'library');
+        }
+        item.contextValue = 'dependent';
+        return item;
+    }
+
+    getChildren(node?: Dependent): Dependent[] {
+        return node ? node.dependents : this.paths.roots;
+    }
+
+    show_paths(paths: DependencyPaths): void {
+        this.paths = paths;
+        this.view.description = `${paths.dependency} in ${paths.package}`;
+        this.view.message = paths.roots.length === 0
+            ? `${paths.package} doesn't depend on ${paths.dependency}`
+            : undefined;
+        vscode.commands.executeCommand('setContext', 'cargoTools:dependencyExplained', true);
+        this._onDidChangeTreeData.fire();
+        vscode.commands.executeCommand('cargoToolsDependencyPaths.focus');
+    }
+}
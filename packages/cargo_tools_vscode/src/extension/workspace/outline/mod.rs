@@ -1,4 +1,5 @@
 pub mod command;
+mod dependency_paths;
 mod ui;
-pub use ui::{Event, Grouping, Message, Outline, TargetTypesFilter};
+pub use ui::{Event, Grouping, Message, Outline, TargetTypesFilter, crate_info};
 pub mod treeprovider;
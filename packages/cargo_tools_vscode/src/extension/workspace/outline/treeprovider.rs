@@ -1,9 +1,12 @@
 use std::{collections::HashMap, iter};
 
-use cargo_tools::cargo::{
-    Config, Features,
-    command::{BenchTarget, BuildSubTarget, BuildTarget, RunSubTarget, RunTarget},
-    metadata::{self, Package, Target, TargetType},
+use cargo_tools::{
+    cargo::{
+        Config, Features,
+        command::{BenchTarget, BuildSubTarget, BuildTarget, RunSubTarget, RunTarget},
+        metadata::{self, Metadata, Package, RegistryDependency, Target, TargetType},
+    },
+    crates_io::{CrateInfoCache, is_outdated},
 };
 use futures::{
     SinkExt, StreamExt,
@@ -13,10 +16,11 @@ use itertools::Itertools;
 use wasm_bindgen::prelude::*;
 
 use crate::{
+    environment::large_workspace_threshold,
     extension::workspace::outline::Grouping,
     icon::{
-        BENCH_TARGET, BIN_TARGET, EXAMPLE_TARGET, FEATURES_CONFIG, Icon, LIB_TARGET, PACKAGE,
-        PROJECT, SELECTED_STATE, UNSELECTED_STATE,
+        BENCH_TARGET, BIN_TARGET, DEPENDENCIES, DEPENDENCY, EXAMPLE_TARGET, FEATURES_CONFIG, Icon,
+        LIB_TARGET, OVERRIDDEN_DEPENDENCY, PACKAGE, PROJECT, SELECTED_STATE, UNSELECTED_STATE,
     },
 };
 use tracing::error;
@@ -52,9 +56,10 @@ extern "C" {
 }
 
 // Make sure to keep this up to date with 'TreeItemCollapsibleState'
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 enum CollapsibleState {
     None = 0,
+    Collapsed = 1,
     Expanded = 2,
 }
 
@@ -74,15 +79,73 @@ impl OutlineNodeType {
     pub fn cloned(&self) -> Self {
         self.clone()
     }
+
+    /// An identifier of the node which stays the same across refreshes, e.g. to restore its
+    /// expansion and selection
+    #[wasm_bindgen]
+    pub fn id(&self) -> String {
+        use OutlineNodeTypeInner::*;
+        match &self.0 {
+            Root => "root".to_string(),
+            RootFeatures => "features".to_string(),
+            RootFeature(name) => format!("features/{name}"),
+            Package { name } => format!("package/{name}"),
+            PackageFeatures { package } => format!("package/{package}/features"),
+            Feature { package, name } => format!("package/{package}/features/{name}"),
+            PackageDependencies { package } => format!("package/{package}/dependencies"),
+            // Without the requirement, which changes with each version bump
+            Dependency { package, name, .. } => format!("package/{package}/dependencies/{name}"),
+            Lib { package, name } => format!("package/{package}/lib/{name}"),
+            Bin { package, name } => format!("package/{package}/bin/{name}"),
+            Example { package, name } => format!("package/{package}/example/{name}"),
+            Bench { package, name } => format!("package/{package}/bench/{name}"),
+            Libraries => "libraries".to_string(),
+            Binaries => "binaries".to_string(),
+            Examples => "examples".to_string(),
+            Benchmarks => "benchmarks".to_string(),
+        }
+    }
+
+    /// The name of a package node
+    #[wasm_bindgen]
+    pub fn package_name(&self) -> Option<String> {
+        match &self.0 {
+            OutlineNodeTypeInner::Package { name } => Some(name.clone()),
+            _ => None,
+        }
+    }
 }
 
 impl OutlineNodeType {
+    /// The ids of the nodes of `packages` which can be expanded
+    pub fn expandable_ids(packages: &[Package]) -> Vec<String> {
+        use OutlineNodeTypeInner::*;
+        let groups = [RootFeatures, Libraries, Binaries, Examples, Benchmarks];
+        let package_nodes = packages.iter().flat_map(|package| {
+            let name = package.name.clone();
+            [
+                Package { name: name.clone() },
+                PackageFeatures {
+                    package: name.clone(),
+                },
+                PackageDependencies { package: name },
+            ]
+        });
+        groups
+            .into_iter()
+            .chain(package_nodes)
+            .map(|node| OutlineNodeType(node).id())
+            .collect()
+    }
+
     pub fn children(
         &self,
         config: &Config,
         packages: &[Package],
         grouping: Grouping,
         show_features: bool,
+        crate_infos: &CrateInfoCache,
+        metadata: &Metadata,
     ) -> Vec<OutlineNodeData> {
         use OutlineNodeTypeInner::*;
         match &self.0 {
@@ -94,6 +157,9 @@ impl OutlineNodeType {
             PackageFeatures { package } => try_package(package, packages)
                 .map(|p| OutlineNodeData::package_features_children(config, p))
                 .unwrap_or_default(),
+            PackageDependencies { package } => try_package(package, packages)
+                .map(|p| OutlineNodeData::package_dependencies_children(p, crate_infos, metadata))
+                .unwrap_or_default(),
             Libraries => OutlineNodeData::targets_children(TargetType::Lib, config, packages),
             Binaries => OutlineNodeData::targets_children(TargetType::Bin, config, packages),
             Examples => OutlineNodeData::targets_children(TargetType::Example, config, packages),
@@ -101,6 +167,7 @@ impl OutlineNodeType {
             // All others never have further child nodes
             RootFeature(_) => Vec::new(),
             Feature { .. } => Vec::new(),
+            Dependency { .. } => Vec::new(),
             Lib { .. } => Vec::new(),
             Bin { .. } => Vec::new(),
             Example { .. } => Vec::new(),
@@ -115,6 +182,22 @@ impl OutlineNodeType {
         }
     }
 
+    /// The name and version requirement of a dependency node
+    pub fn try_into_dependency(self) -> Option<(String, String)> {
+        match self.0 {
+            OutlineNodeTypeInner::Dependency { name, req, .. } => Some((name, req)),
+            _ => None,
+        }
+    }
+
+    /// The package and name of a dependency node
+    pub fn try_into_package_dependency(self) -> Option<(String, String)> {
+        match self.0 {
+            OutlineNodeTypeInner::Dependency { package, name, .. } => Some((package, name)),
+            _ => None,
+        }
+    }
+
     pub fn try_into_build_target(self) -> Option<BuildTarget> {
         use OutlineNodeTypeInner::*;
         let build_target = |package, target| {
@@ -168,13 +251,40 @@ enum OutlineNodeTypeInner {
     Root,
     RootFeatures,
     RootFeature(String),
-    Package { name: String },
-    PackageFeatures { package: String },
-    Feature { package: String, name: String },
-    Lib { package: String, name: String },
-    Bin { package: String, name: String },
-    Example { package: String, name: String },
-    Bench { package: String, name: String },
+    Package {
+        name: String,
+    },
+    PackageFeatures {
+        package: String,
+    },
+    Feature {
+        package: String,
+        name: String,
+    },
+    PackageDependencies {
+        package: String,
+    },
+    Dependency {
+        package: String,
+        name: String,
+        req: String,
+    },
+    Lib {
+        package: String,
+        name: String,
+    },
+    Bin {
+        package: String,
+        name: String,
+    },
+    Example {
+        package: String,
+        name: String,
+    },
+    Bench {
+        package: String,
+        name: String,
+    },
     Libraries,
     Binaries,
     Examples,
@@ -260,6 +370,16 @@ impl OutlineNodeData {
         }
     }
 
+    /// Groups with many nodes start collapsed, VS Code only asks for their children once they
+    /// are expanded
+    fn collapsible_state(num_children: usize) -> CollapsibleState {
+        if num_children > large_workspace_threshold() {
+            CollapsibleState::Collapsed
+        } else {
+            CollapsibleState::Expanded
+        }
+    }
+
     fn target_types_root_children(target_counts: HashMap<TargetType, usize>) -> Vec<Self> {
         use metadata::TargetType::*;
         [Lib, Bin, Example, Bench]
@@ -277,7 +397,7 @@ impl OutlineNodeData {
         Self {
             label: target.label(),
             icon: target.icon(),
-            collapsible_state: CollapsibleState::Expanded,
+            collapsible_state: Self::collapsible_state(num_targets),
             node_type: target.targets_node_type(),
             context_value: None,
             tooltip: None,
@@ -608,9 +728,12 @@ impl OutlineNodeData {
             command_arg: None,
         };
 
+        // The targets and dependencies of the packages of large workspaces are only built for
+        // the expanded packages
+        let collapsible_state = Self::collapsible_state(packages.len());
         let packages = packages
             .iter()
-            .map(|p| Self::package(config.selected_package.as_deref(), p));
+            .map(|p| Self::package(config.selected_package.as_deref(), p, collapsible_state));
 
         if show_features {
             iter::once(root_features).chain(packages).collect()
@@ -619,7 +742,11 @@ impl OutlineNodeData {
         }
     }
 
-    fn package(selected_package: Option<&str>, package: &Package) -> Self {
+    fn package(
+        selected_package: Option<&str>,
+        package: &Package,
+        collapsible_state: CollapsibleState,
+    ) -> Self {
         let mut label = package.name.to_string();
         let mut context = vec!["workspaceMember"];
 
@@ -637,7 +764,7 @@ impl OutlineNodeData {
         Self {
             label,
             icon: PACKAGE,
-            collapsible_state: CollapsibleState::Expanded,
+            collapsible_state,
             node_type: OutlineNodeType(OutlineNodeTypeInner::Package { name }),
             context_value: Some(context.join(",")),
             tooltip: None,
@@ -723,6 +850,88 @@ impl OutlineNodeData {
             .collect()
     }
 
+    fn package_dependencies_children(
+        package: &Package,
+        crate_infos: &CrateInfoCache,
+        metadata: &Metadata,
+    ) -> Vec<Self> {
+        package
+            .registry_dependencies
+            .iter()
+            .map(|dependency| {
+                let RegistryDependency {
+                    name,
+                    req,
+                    kind,
+                    registry,
+                } = dependency;
+                // crates.io information is only fetched for crates.io dependencies
+                let info = crate_infos.get(name).filter(|_| dependency.is_crates_io());
+                let mut description = vec![req.clone()];
+                description.extend(kind.map(str::to_string));
+                if let Some(registry) = registry {
+                    let name = metadata.registries().name_of(registry);
+                    description.push(name.unwrap_or(registry).to_string());
+                }
+                if let Some(info) = info
+                    && is_outdated(req, &info.latest_version)
+                {
+                    description.push(format!("latest {}", info.latest_version));
+                }
+                let tooltip = match (info, registry) {
+                    (Some(info), _) => {
+                        let downloads = format!("{} downloads", info.downloads_display());
+                        Some(match &info.description {
+                            Some(text) => format!("{text}\n\n{downloads}"),
+                            None => downloads,
+                        })
+                    }
+                    (None, Some(registry)) => Some(format!("From the registry {registry}")),
+                    (None, None) => None,
+                };
+                // The crates.io and docs.rs actions only apply to crates.io dependencies
+                let mut context = vec![if dependency.is_crates_io() {
+                    "dependency"
+                } else {
+                    "alternativeRegistryDependency"
+                }];
+                let overridden = metadata.override_of(dependency);
+                let tooltip = match overridden {
+                    Some((source, declaration)) => {
+                        description.push("overridden".to_string());
+                        let overridden = source.description(declaration);
+                        context.push("isOverridden");
+                        Some(match tooltip {
+                            Some(tooltip) => format!("{overridden}\n\n{tooltip}"),
+                            None => overridden,
+                        })
+                    }
+                    None => tooltip,
+                };
+
+                Self {
+                    label: name.clone(),
+                    icon: if overridden.is_some() {
+                        OVERRIDDEN_DEPENDENCY
+                    } else {
+                        DEPENDENCY
+                    },
+                    collapsible_state: CollapsibleState::None,
+                    node_type: OutlineNodeType(OutlineNodeTypeInner::Dependency {
+                        package: package.name.clone(),
+                        name: name.clone(),
+                        req: req.clone(),
+                    }),
+                    context_value: Some(context.join(",")),
+                    tooltip,
+                    description: Some(description.join(" · ")),
+                    command: Some("vscode.open".to_string()),
+                    command_arg: Some(package.manifest.to_string()),
+                }
+            })
+            .collect()
+    }
+
     fn package_children(config: &Config, package: &Package, show_features: bool) -> Vec<Self> {
         let package_name = &package.name;
 
@@ -758,11 +967,22 @@ impl OutlineNodeData {
             command_arg: None,
         };
 
-        if show_features {
-            targets.chain(iter::once(features)).collect()
-        } else {
-            targets.collect()
-        }
+        let dependencies = (!package.registry_dependencies.is_empty()).then(|| Self {
+            label: "Dependencies".to_string(),
+            icon: DEPENDENCIES,
+            collapsible_state: CollapsibleState::Collapsed,
+            node_type: OutlineNodeType(OutlineNodeTypeInner::PackageDependencies {
+                package: package_name.clone(),
+            }),
+            context_value: None,
+            tooltip: None,
+            description: Some(package.registry_dependencies.len().to_string()),
+            command: None,
+            command_arg: None,
+        });
+
+        let features = show_features.then_some(features);
+        targets.chain(features).chain(dependencies).collect()
     }
 }
 
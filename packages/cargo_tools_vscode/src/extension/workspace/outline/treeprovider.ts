@@ -5,7 +5,7 @@ export class CargoOutlineNode extends vscode.TreeItem {
     constructor(
         public readonly label: string,
         public readonly icon: Icon,
-        public readonly collapsibleState: vscode.TreeItemCollapsibleState,
+        public collapsibleState: vscode.TreeItemCollapsibleState,
         public readonly node_type: OutlineNodeType,
         public readonly contextValue?: string,
         public readonly description?: string,
@@ -14,6 +14,7 @@ export class CargoOutlineNode extends vscode.TreeItem {
         public readonly cmd_arg?: string,
     ) {
         super(label, collapsibleState);
+        this.id = node_type.id();
         this.iconPath = new vscode.ThemeIcon(icon.icon, new vscode.ThemeColor(icon.color));
         this.contextValue = contextValue;
         this.command = cmd ? {
@@ -1,16 +1,15 @@
-use std::{
-    iter,
-    path::{Path, PathBuf},
-};
+use std::{cell::RefCell, collections::BTreeMap, path::Path};
 
 use cargo_tools::{
     CargoCommand,
     cargo::{
-        Config, ConfigUpdate, Features, Profile,
-        command::{BuildSubTarget, BuildTarget, RunSubTarget, RunTarget},
+        Config, ConfigUpdate, Features,
+        command::{BenchTarget, RunTarget},
         config::{self, FeatureTarget},
         metadata::{Metadata, Package, Target, TargetType},
+        new_target::TargetKind,
     },
+    crates_io::{self, CrateInfo, CrateInfoCache},
 };
 use futures::{
     SinkExt,
@@ -23,18 +22,25 @@ use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::{js_sys::Array, spawn_local};
 
 use crate::{
-    environment::CommandExt,
+    environment::{CommandExt, crates_io_metadata_enabled, debug_build_context, remote_target},
     extension::{
         CommandBinding,
+        workspace::configuration::dependency_docs::DependencyDocs,
         workspace::outline::{
             command::{Command, register_outline_commands},
-            treeprovider::{CargoOutlineTreeProviderHandler, OutlineNodeData, OutlineUiRequest},
+            dependency_paths::{DependencyPathsView, explain_dependency},
+            treeprovider::{
+                CargoOutlineTreeProviderHandler, OutlineNodeData, OutlineNodeType, OutlineUiRequest,
+            },
         },
     },
     quick_pick::show_quick_pick_type,
     quick_pick::{SelectInput, ToQuickPickItem},
-    runtime::{CHANNEL_CAPACITY, VsCodeTask, get_state_vs_code, persist_state_vs_code},
-    runtime::{JsValueExt, debug, execute_task, host_platform},
+    runtime::{
+        CHANNEL_CAPACITY, VsCodeTask, get_state_vs_code, now_ms, open_file_at, open_url,
+        persist_state_vs_code, read_file_vs_code, show_warning_message,
+    },
+    runtime::{build_and_debug, build_and_debug_bench, execute},
 };
 use tracing::{debug, error};
 
@@ -49,20 +55,37 @@ extern "C" {
 
     #[wasm_bindgen(method)]
     fn update(this: &CargoOutlineTreeProvider);
+
+    /// Updates the nodes of the packages `names`, all nodes if one isn't shown
+    #[wasm_bindgen(method)]
+    fn update_packages(this: &CargoOutlineTreeProvider, names: Vec<String>);
+
+    /// Forgets the expansion of the nodes which aren't among the expandable `ids` anymore
+    #[wasm_bindgen(method)]
+    fn prune_expanded(this: &CargoOutlineTreeProvider, ids: Vec<String>);
+
+    async fn fetch_crate_info(url: String) -> JsValue;
 }
 
 #[derive(Debug)]
 pub enum Message {
     MetadataChanged,
+    /// Only the packages of the names changed, their nodes are updated
+    PackagesChanged(Vec<String>),
     ConfigChanged,
     PreviewSettings(SettingsUpdate),
     SettingsChanged(SettingsUpdate),
     Cmd(Command),
     OutlineUiRequest(OutlineUiRequest),
+    /// Crates which could not be fetched, e.g. while offline, are missing and keep their
+    /// cached information
+    CrateInfosFetched(Vec<(String, CrateInfo)>),
 }
 
 pub enum Event {
     ConfigUpdate(config::Update),
+    /// A target of the kind is to be created in the package
+    NewTarget(String, TargetKind),
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +98,7 @@ pub enum SettingsUpdate {
 pub struct Outline {
     settings: Settings,
     ui: CargoOutlineTreeProvider,
+    dependency_paths: DependencyPathsView,
     filtered_packages: Vec<Package>,
     _cmds: Vec<CommandBinding>,
     root_dir: String,
@@ -87,6 +111,8 @@ impl Outline {
         let _cmds = register_outline_commands(cmd_tx.clone());
 
         let settings = get_state_vs_code(settings_key(&root_dir)).unwrap_or_default();
+        let crate_infos = get_state_vs_code(crate_infos_key(&root_dir)).unwrap_or_default();
+        CRATE_INFOS.set(crate_infos);
 
         let (outline_tx, outline_rx) = channel(CHANNEL_CAPACITY);
         let outline_handler = CargoOutlineTreeProviderHandler::new(outline_tx);
@@ -94,6 +120,7 @@ impl Outline {
         let this = Self {
             settings,
             ui: CargoOutlineTreeProvider::new(outline_handler),
+            dependency_paths: DependencyPathsView::new(),
             filtered_packages: Vec::new(),
             _cmds,
             root_dir,
@@ -117,8 +144,37 @@ impl Outline {
         match msg {
             Message::MetadataChanged => {
                 self.update_selected_packages(metadata.packages());
+                // Failed metadata lists no packages until it is fixed
+                if !metadata.packages().is_empty() {
+                    let ids = OutlineNodeType::expandable_ids(metadata.packages());
+                    self.ui.prune_expanded(ids);
+                }
                 self.ui.update();
-                (Task::none(), None)
+                (self.fetch_crate_infos(metadata), None)
+            }
+            Message::PackagesChanged(names) => {
+                self.update_selected_packages(metadata.packages());
+                self.ui.update_packages(names);
+                (self.fetch_crate_infos(metadata), None)
+            }
+            Message::CrateInfosFetched(infos) => {
+                if infos.is_empty() {
+                    return (Task::none(), None);
+                }
+                let now = now_ms();
+                let crate_infos = CRATE_INFOS.with_borrow_mut(|crate_infos| {
+                    for (name, info) in infos {
+                        crate_infos.insert(name, info, now);
+                    }
+                    crate_infos.clone()
+                });
+                self.ui.update();
+                let persist = Task::future(persist_state_vs_code(
+                    crate_infos_key(&self.root_dir),
+                    crate_infos,
+                ))
+                .discard();
+                (persist, None)
             }
             Message::ConfigChanged => {
                 self.ui.update();
@@ -146,12 +202,16 @@ impl Outline {
                     );
                 };
 
-                let nodes = node_type.children(
-                    config,
-                    &self.filtered_packages,
-                    self.settings.grouping,
-                    self.settings.target_types_filter.features,
-                );
+                let nodes = CRATE_INFOS.with_borrow(|crate_infos| {
+                    node_type.children(
+                        config,
+                        &self.filtered_packages,
+                        self.settings.grouping,
+                        self.settings.target_types_filter.features,
+                        crate_infos,
+                        metadata,
+                    )
+                });
                 let task = Task::future(async move { tx.send(nodes).await }).discard();
                 (task, None)
             }
@@ -200,12 +260,16 @@ impl Outline {
                      manifest,
                      targets,
                      features,
+                     dependencies,
+                     registry_dependencies,
                  }| {
                     Package {
                         name: name.clone(),
                         manifest: manifest.clone(),
                         targets: self.settings.filter_targets(targets).cloned().collect(),
                         features: features.clone(),
+                        dependencies: dependencies.clone(),
+                        registry_dependencies: registry_dependencies.clone(),
                     }
                 },
             )
@@ -227,11 +291,12 @@ impl Outline {
                 (self.cmd_exec(CargoCommand::Clean { package }, config), None)
             }
             Command::Run(target) => (self.cmd_exec(CargoCommand::Run(Some(target)), config), None),
-            Command::Debug(target) => (self.debug(target, metadata, config.clone()), None),
+            Command::Debug(target) => (self.debug(target, metadata, config), None),
             Command::Bench(target) => (
                 self.cmd_exec(CargoCommand::Bench(Some(target)), config),
                 None,
             ),
+            Command::DebugBench(target) => (self.debug_bench(target, metadata, config), None),
             Command::SelectWorkspaceMemberFilter => {
                 (self.select_workspace_member_filter(metadata), None)
             }
@@ -267,14 +332,107 @@ impl Outline {
                 let event = self.toggle_feature(config, feature_type, feature);
                 (Task::none(), Some(event))
             }
+            Command::OpenOnCratesIo(name) => {
+                open_url(crates_io::crate_url(&name));
+                (Task::none(), None)
+            }
+            Command::OpenDocsRs(name, req) => {
+                // The docs of the version the workspace builds with
+                let lock = format!("{}/Cargo.lock", metadata.workspace_root());
+                let task = Task::future(async move {
+                    let lock = read_file_vs_code(lock).await.ok();
+                    open_url(DependencyDocs::new(name, Some(req), lock.as_deref()).url);
+                })
+                .discard();
+                (task, None)
+            }
+            Command::GoToOverride { package, name } => {
+                let overridden = metadata
+                    .packages()
+                    .iter()
+                    .filter(|p| p.name == package)
+                    .flat_map(|p| p.registry_dependencies.iter())
+                    .find(|d| d.name == name)
+                    .and_then(|d| metadata.override_of(d));
+                let task = match overridden {
+                    Some((_, Some(declaration))) => Task::future(open_file_at(
+                        declaration.file.clone(),
+                        declaration.line as u32,
+                    ))
+                    .discard(),
+                    Some((source, None)) => {
+                        let message = format!(
+                            "The override of {name} isn't declared in the workspace. {}",
+                            source.description(None)
+                        );
+                        Task::future(async move {
+                            show_warning_message(&message, Vec::new()).await;
+                        })
+                        .discard()
+                    }
+                    None => Task::none(),
+                };
+                (task, None)
+            }
+            Command::ExplainDependency { package, name } => {
+                let Some(manifest) = metadata
+                    .packages()
+                    .iter()
+                    .find(|p| p.name == package)
+                    .map(|p| p.manifest.clone())
+                else {
+                    return (Task::none(), None);
+                };
+                let task = Task::future(explain_dependency(
+                    self.dependency_paths.clone(),
+                    manifest,
+                    package,
+                    name,
+                ))
+                .discard();
+                (task, None)
+            }
+            Command::NewTarget(package, kind) => {
+                (Task::none(), Some(Event::NewTarget(package, kind)))
+            }
         }
     }
 
+    /// Fetches the crates.io information of the dependencies which are not cached or outdated
+    fn fetch_crate_infos(&self, metadata: &Metadata) -> Task<Message> {
+        if !crates_io_metadata_enabled() {
+            return Task::none();
+        }
+        let names = metadata
+            .packages()
+            .iter()
+            .flat_map(|p| &p.registry_dependencies)
+            .filter(|d| d.is_crates_io())
+            .map(|d| d.name.as_str());
+        let stale = CRATE_INFOS.with_borrow(|crate_infos| crate_infos.stale(names, now_ms()));
+        if stale.is_empty() {
+            return Task::none();
+        }
+
+        Task::future(async move {
+            let mut infos = Vec::new();
+            // One request at a time, `fetch_crate_info` keeps them a second apart as crates.io
+            // asks API clients to
+            for name in stale {
+                if let Some(info) = fetch(&name).await {
+                    infos.push((name, info));
+                }
+            }
+            Message::CrateInfosFetched(infos)
+        })
+    }
+
     fn cmd_exec(&self, cmd: CargoCommand, config: &Config) -> Task<Message> {
         let ctx = cmd.ctx();
+        let mode = cmd.execution_mode();
 
         match cmd.try_into_process(config, ctx) {
-            Ok(process) => Task::future(execute_task(VsCodeTask::cargo(process))).discard(),
+            Ok(process) => Task::future(execute(VsCodeTask::cargo(process), mode)).discard(),
             Err(e) => {
                 error!("{e}");
                 Task::none()
@@ -282,23 +440,17 @@ impl Outline {
         }
     }
 
-    fn debug(&self, target: RunTarget, metadata: &Metadata, mut config: Config) -> Task<Message> {
-        let Some(run_target) = target.target.as_ref() else {
+    fn debug(&self, target: RunTarget, metadata: &Metadata, config: &Config) -> Task<Message> {
+        let Some(run_target) = target.target.clone() else {
             return Task::none();
         };
-        let build_sub_target = match run_target {
-            RunSubTarget::Bin(t) => BuildSubTarget::Bin(t.clone()),
-            RunSubTarget::Example(t) => BuildSubTarget::Example(t.clone()),
-        };
-        let build_target = BuildTarget {
-            package: target.package.clone(),
-            target: Some(build_sub_target),
-        };
-
-        config.profile = Profile::Dev; // For now always use standard dev profile
+        let config = config.with_required_features(
+            &target.package,
+            metadata.required_features(&target.package, &run_target),
+        );
 
-        let build_debug_cmd = CargoCommand::Build(Some(build_target));
-        let ctx = build_debug_cmd.ctx();
+        let build_debug_cmd = CargoCommand::Debug(Some(target));
+        let ctx = debug_build_context(&build_debug_cmd, &config.profile);
 
         let build_debug_process = match build_debug_cmd.try_into_process(&config, ctx) {
             Ok(process) => process,
@@ -308,15 +460,44 @@ impl Outline {
             }
         };
 
-        let target_exe_path = exec_path(run_target, &config, metadata.target_dir());
+        Task::future(build_and_debug(
+            build_debug_process,
+            run_target,
+            Vec::new(),
+            BTreeMap::new(),
+            remote_target(config.platform_target.as_deref()),
+        ))
+        .discard()
+    }
 
-        Task::future(async move {
-            execute_task(VsCodeTask::cargo(build_debug_process)).await;
+    fn debug_bench(
+        &self,
+        bench: BenchTarget,
+        metadata: &Metadata,
+        config: &Config,
+    ) -> Task<Message> {
+        let Some(package) = metadata.packages().iter().find(|p| p.name == bench.package) else {
+            return Task::none();
+        };
+        let cwd = package.manifest_dir().to_string();
 
-            if let Err(e) = debug(&target_exe_path, &target.package).await {
-                error!("Error while debugging: {}", e.to_error_string());
+        let build_debug_cmd = CargoCommand::DebugBench(bench.clone());
+        let ctx = build_debug_cmd.ctx();
+
+        let build_debug_process = match build_debug_cmd.try_into_process(config, ctx) {
+            Ok(process) => process,
+            Err(e) => {
+                error!("{e}");
+                return Task::none();
             }
-        })
+        };
+
+        Task::future(build_and_debug_bench(
+            build_debug_process,
+            bench,
+            cwd,
+            remote_target(config.platform_target.as_deref()),
+        ))
         .discard()
     }
 
@@ -573,21 +754,44 @@ pub fn settings_key(root_dir: &str) -> String {
     format!("{root_dir}.cargo_tools.workspace.outline.settings")
 }
 
-fn exec_path(target: &RunSubTarget, config: &Config, target_dir: &str) -> String {
-    let path_components = iter::once(target_dir.to_string())
-        .chain(iter::once("debug".to_string())) // For now always assume debug profile
-        .chain(config.platform_target.as_ref().map(|t| t.to_string()))
-        .chain(match target {
-            RunSubTarget::Bin(bin) => vec![bin.clone()],
-            RunSubTarget::Example(example) => {
-                vec!["examples".to_string(), example.to_string()]
-            }
-        })
-        .chain((host_platform() == "windows").then_some(".exe".to_string()));
+thread_local! {
+    /// The crates.io information of the dependencies, shared by the outline and the hover of
+    /// dependencies in manifests
+    static CRATE_INFOS: RefCell<CrateInfoCache> = RefCell::default();
+}
+
+/// Fetches the crates.io information of the crate `name`
+async fn fetch(name: &str) -> Option<CrateInfo> {
+    let json = fetch_crate_info(crates_io::api_url(name))
+        .await
+        .as_string()?;
+    CrateInfo::parse(&json)
+        .inspect_err(|e| debug!("Unexpected crates.io response for {name}: {e}"))
+        .ok()
+}
+
+/// The crates.io information of the crate `name`, only fetched if it isn't cached or outdated
+pub async fn crate_info(name: &str) -> Option<CrateInfo> {
+    let now = now_ms();
+    // Information cached before the releases were stored lacks them
+    let cached = CRATE_INFOS.with_borrow(|crate_infos| {
+        crate_infos
+            .fresh(name, now)
+            .filter(|info| !info.versions.is_empty())
+            .cloned()
+    });
+    if cached.is_some() {
+        return cached;
+    }
+    let info = fetch(name).await?;
+    CRATE_INFOS.with_borrow_mut(|crate_infos| {
+        crate_infos.insert(name.to_string(), info.clone(), now_ms())
+    });
+    Some(info)
+}
 
-    PathBuf::from_iter(path_components)
-        .to_string_lossy()
-        .to_string()
+fn crate_infos_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.workspace.outline.crate_infos")
 }
 
 trait IntoMessage {
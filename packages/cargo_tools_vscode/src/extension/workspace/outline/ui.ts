@@ -1,33 +1,149 @@
 import * as vscode from 'vscode';
 import { CargoOutlineTreeProviderHandler } from '../../../../../../vscode_extension/src/wasm/cargo_tools_vscode';
 import { CargoOutlineNode } from './treeprovider';
+import { extension_context } from '../../../../../../vscode_extension/src/extension';
+
+// The workspace state keys of the expanded or collapsed nodes by id and of the selected node
+const EXPANDED_KEY = 'cargo-tools.outline.expanded';
+const SELECTED_KEY = 'cargo-tools.outline.selected';
 
 export class CargoOutlineTreeProvider implements vscode.TreeDataProvider<CargoOutlineNode> {
     private _onDidChangeTreeData: vscode.EventEmitter<CargoOutlineNode | undefined | null | void> = new vscode.EventEmitter<CargoOutlineNode | undefined | null | void>();
     readonly onDidChangeTreeData: vscode.Event<CargoOutlineNode | undefined | null | void> = this._onDidChangeTreeData.event;
 
     private handler: CargoOutlineTreeProviderHandler;
+    // The shown package nodes by name, refreshed on their own when only their packages change
+    private packageNodes = new Map<string, CargoOutlineNode>();
+    // The parents of the shown nodes by id, VS Code needs them to reveal the selected node
+    private parents = new Map<string, CargoOutlineNode | undefined>();
+    // Whether the tree was refreshed and the selected node isn't revealed again yet
+    private restoreSelection = true;
+    private treeView: vscode.TreeView<CargoOutlineNode>;
 
     constructor(handler: CargoOutlineTreeProviderHandler) {
         this.handler = handler;
 
         // register on creation
-        vscode.window.createTreeView('cargoToolsProjectOutline', {
+        this.treeView = vscode.window.createTreeView('cargoToolsProjectOutline', {
             treeDataProvider: this,
             showCollapseAll: true,
             canSelectMany: false
         });
+        this.treeView.onDidExpandElement((e) => this.setExpanded(e.element, true));
+        this.treeView.onDidCollapseElement((e) => this.setExpanded(e.element, false));
+        this.treeView.onDidChangeSelection((e) => {
+            // Refreshes clear the selection, which is restored once the node is shown again
+            if (e.selection.length > 0) {
+                extension_context?.workspaceState.update(SELECTED_KEY, e.selection[0].id);
+            }
+        });
     }
 
     update(): void {
+        this.packageNodes.clear();
+        this.parents.clear();
+        this.restoreSelection = true;
         this._onDidChangeTreeData.fire();
     }
 
+    update_packages(names: string[]): void {
+        const nodes = names.map((name) => this.packageNodes.get(name));
+        if (nodes.some((node) => node === undefined)) {
+            this.update();
+            return;
+        }
+        for (const node of nodes) {
+            this._onDidChangeTreeData.fire(node);
+        }
+    }
+
     getTreeItem(element: CargoOutlineNode): vscode.TreeItem {
+        const expanded = this.expandedStates()[element.id ?? ''];
+        if (expanded !== undefined && element.collapsibleState !== vscode.TreeItemCollapsibleState.None) {
+            element.collapsibleState = expanded
+                ? vscode.TreeItemCollapsibleState.Expanded
+                : vscode.TreeItemCollapsibleState.Collapsed;
+        }
         return element;
     }
 
+    getParent(element: CargoOutlineNode): CargoOutlineNode | undefined {
+        return this.parents.get(element.id ?? '');
+    }
+
     async getChildren(element?: CargoOutlineNode): Promise<CargoOutlineNode[]> {
-        return this.handler.children(element ? element.node_type.cloned() : undefined);
+        const children: CargoOutlineNode[] = await this.handler.children(element ? element.node_type.cloned() : undefined);
+        const selected = extension_context?.workspaceState.get<string>(SELECTED_KEY);
+        const ids = new Set<string>();
+        for (const child of children) {
+            // A crate can be a dependency and a dev dependency of the same package
+            let id = child.id ?? '';
+            for (let i = 1; ids.has(id); i++) {
+                id = `${child.id}#${i}`;
+            }
+            child.id = id;
+            ids.add(id);
+            this.parents.set(id, element);
+
+            const name = child.node_type.package_name();
+            if (name !== undefined) {
+                this.packageNodes.set(name, child);
+            }
+            if (this.restoreSelection && id === selected) {
+                this.restoreSelection = false;
+                // Only once the children are shown
+                setTimeout(() => this.treeView.reveal(child, { select: true, focus: false }).then(undefined, () => { }));
+            }
+        }
+        return children;
+    }
+
+    prune_expanded(ids: string[]): void {
+        const expandable = new Set(ids);
+        const states = this.expandedStates();
+        // Nodes of the same id are told apart by a `#<n>` suffix
+        const pruned = Object.fromEntries(Object.entries(states)
+            .filter(([id]) => expandable.has(id.replace(/#\d+$/, ''))));
+        if (Object.keys(pruned).length !== Object.keys(states).length) {
+            extension_context?.workspaceState.update(EXPANDED_KEY, pruned);
+        }
+    }
+
+    private expandedStates(): { [id: string]: boolean } {
+        return extension_context?.workspaceState.get<{ [id: string]: boolean }>(EXPANDED_KEY) ?? {};
+    }
+
+    private setExpanded(element: CargoOutlineNode, expanded: boolean): void {
+        if (element.id === undefined) {
+            return;
+        }
+        const states = this.expandedStates();
+        states[element.id] = expanded;
+        extension_context?.workspaceState.update(EXPANDED_KEY, states);
+    }
+}
+
+/** crates.io asks API clients for at most one request per second */
+const CRATES_IO_INTERVAL_MS = 1000;
+let cratesIoQueue: Promise<unknown> = Promise.resolve();
+
+/**
+ * The body of the crates.io API response for `url`, undefined if offline or not found. The
+ * requests are queued to send at most one per second.
+ */
+export function fetch_crate_info(url: string): Promise<string | undefined> {
+    const response = cratesIoQueue.then(() => fetchOnce(url));
+    cratesIoQueue = response.then(() => new Promise((resolve) => setTimeout(resolve, CRATES_IO_INTERVAL_MS)));
+    return response;
+}
+
+async function fetchOnce(url: string): Promise<string | undefined> {
+    try {
+        const response = await fetch(url, {
+            headers: { 'User-Agent': 'cargo-tools-vscode (https://github.com/NickelWenzel/cargo-tools)' },
+        });
+        return response.ok ? await response.text() : undefined;
+    } catch {
+        return undefined;
     }
 }
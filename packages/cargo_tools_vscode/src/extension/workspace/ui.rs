@@ -1,30 +1,66 @@
-use cargo_tools::cargo::{
-    Profile,
-    metadata::{
-        Metadata, PackagesAndTargetDir, ParseError, parse_packages_and_target_dir, parse_profiles,
+use std::{cell::Cell, collections::HashMap, path::Path};
+
+use cargo_tools::{
+    cargo::{
+        DependencyOverride, Profile, Registries,
+        discovery::workspace_manifests,
+        metadata::{
+            Metadata, MetadataCache, PackagesAndTargetDir, ParseError, files_hash,
+            parse_packages_and_target_dir, parse_profiles, resolve_args,
+        },
+        overrides::{
+            SourceOverride, override_paths, parse_overrides, path_override, source_overrides,
+        },
     },
+    env_file,
+    path_mapping::{is_wsl_command, wsl_distro},
+    process::Process,
+    shell_env::{self, DIRENV, SHELL},
 };
 use futures::channel::mpsc::channel;
 use iced_viewless::Task;
+use wasm_bindgen::{JsValue, prelude::wasm_bindgen};
 
 use crate::{
-    environment::metadata_task_context,
+    environment::{
+        CROSS_ENABLED_SETTING, METADATA_SETTINGS, cargo_command, cross_enabled, discovery_exclude,
+        discovery_max_depth, env_file, manifest_path, manifest_watcher_debounce,
+        metadata_task_context, path_mappings, set_active_manifest, set_cross_host,
+        set_default_wsl_distro, set_env_file_vars, set_shell_env_vars, set_workspace_folder,
+        set_workspace_root, shell_env_direnv, shell_env_init_command,
+    },
     extension::{
         send_file_changed,
         workspace::{configuration, outline},
     },
+    quick_pick::SelectInput,
     runtime::{
-        CHANNEL_CAPACITY, TsFileWatcher, exec_vs_code, file_exists_vs_code, read_file_vs_code,
-        set_cargo_context,
+        CHANNEL_CAPACITY, TsFileWatcher, TsSettingsWatcher, default_wsl_distro, detect_cross,
+        exec_cancellable_vs_code, exec_vs_code, file_exists_vs_code, get_state_vs_code, host_var,
+        persist_state_vs_code, read_file_vs_code, set_cargo_context,
     },
 };
-use tracing::error;
+use tracing::{error, info};
+
+#[wasm_bindgen(raw_module = "../../../packages/cargo_tools_vscode/src/extension/workspace/ui.ts")]
+extern "C" {
+    async fn find_manifests(root: String, exclude: Vec<String>) -> JsValue;
+}
 
 #[derive(Debug, Clone)]
 pub enum MetadataUpdate {
     PackagesAndTargetDir(PackagesAndTargetDir),
+    /// The packages of the [MetadataCache] shown until `cargo metadata` finished
+    Cached(PackagesAndTargetDir),
     Profiles(Vec<Profile>),
+    Registries(Registries),
+    Overrides(Vec<DependencyOverride>),
+    /// The registry dependencies cargo resolved from elsewhere, also by overrides outside of
+    /// the workspace
+    SourceOverrides(Vec<SourceOverride>),
     NoCargoToml,
+    /// Scanning the workspace was canceled, the packages stay as they are
+    Canceled,
     FailedToParse(String),
     CargoCommandEmpty(String),
 }
@@ -57,7 +93,32 @@ impl MetadataUpdate {
 #[derive(Debug)]
 pub enum Message {
     ManifestChanged,
-    ConfigFileChanged,
+    /// A burst of changes of the manifests or cargo configs of the workspace, refreshes the
+    /// packages and the config at once
+    WorkspaceFilesChanged,
+    /// The [workspace_files_hash] of the files `cargo metadata` reads, the packages are only
+    /// parsed again if it changed
+    WorkspaceFilesHashed(u64),
+    /// One of the [METADATA_SETTINGS] changed, the env files are loaded and the packages parsed
+    /// again
+    MetadataSettingsChanged,
+    /// Parses the packages again even if the files `cargo metadata` reads didn't change
+    RescanWorkspace,
+    EnvFileChanged,
+    EnvFileLoaded(HashMap<String, String>),
+    /// Captures the variables of direnv and the shell init command again
+    ReloadShellEnv,
+    ShellEnvCaptured(HashMap<String, String>),
+    /// The toolchain file or `cargoTools.cross.enabled` changed, `cross` is detected again
+    ToolchainChanged,
+    CrossDetected(Option<String>),
+    /// The default WSL distribution of a `wsl cargo` command, the packages are parsed again
+    /// with its paths
+    WslDistroDetected(Option<String>),
+    /// The root manifests of the Cargo workspaces in the folder, see [workspace_manifests]
+    WorkspacesDiscovered(Vec<String>),
+    SelectWorkspace,
+    ActivateWorkspace(String),
     MetadataChanged(MetadataUpdate),
     Configuration(configuration::Message),
     Outline(outline::Message),
@@ -67,20 +128,51 @@ pub struct Workspace {
     configuration: configuration::Configuration,
     outline: outline::Outline,
     metadata: Metadata,
-    mainfests_file_watcher: TsFileWatcher,
-    config_file_watcher: TsFileWatcher,
+    /// Whether the packages are the cached ones, `cargo metadata` didn't finish yet
+    metadata_from_cache: bool,
+    /// The [workspace_files_hash] the packages were last parsed for
+    workspace_files_hash: Option<u64>,
+    /// Watches the manifests and `.cargo/config.toml` files below the workspace root
+    workspace_files_watcher: TsFileWatcher,
+    env_file_watcher: TsFileWatcher,
+    _metadata_settings_watcher: TsSettingsWatcher,
+    /// Watches the `rust-toolchain.toml` of the workspace, see [Self::detect_cross]
+    toolchain_watcher: TsFileWatcher,
+    _cross_settings_watcher: TsSettingsWatcher,
+    /// The root manifests of the Cargo workspaces in the folder, relative to it
+    workspaces: Vec<String>,
     root_dir: String,
 }
 
 impl Workspace {
     pub fn init(root_dir: String) -> (Self, Task<Message>) {
-        // Init manifest file updates
-        let (manifest_changed_tx, manifest_changed_rx) = channel(CHANNEL_CAPACITY);
-        let manifests_file_watcher = TsFileWatcher::new(send_file_changed(manifest_changed_tx));
+        // Init manifest and config file updates
+        let (files_changed_tx, files_changed_rx) = channel(CHANNEL_CAPACITY);
+        let workspace_files_watcher = TsFileWatcher::new(send_file_changed(files_changed_tx));
+
+        // Init env file updates
+        let (env_file_changed_tx, env_file_changed_rx) = channel(CHANNEL_CAPACITY);
+        let env_file_watcher = TsFileWatcher::new(send_file_changed(env_file_changed_tx));
 
-        // Init config file updates
-        let (config_changed_tx, config_changed_rx) = channel(CHANNEL_CAPACITY);
-        let config_file_watcher = TsFileWatcher::new(send_file_changed(config_changed_tx));
+        // Init updates of the settings `cargo metadata` depends on
+        let (settings_changed_tx, settings_changed_rx) = channel(CHANNEL_CAPACITY);
+        let _metadata_settings_watcher = TsSettingsWatcher::new(
+            METADATA_SETTINGS.map(str::to_string).to_vec(),
+            send_file_changed(settings_changed_tx),
+        );
+
+        // Init updates of the toolchain and `cross` detection
+        let (toolchain_changed_tx, toolchain_changed_rx) = channel(CHANNEL_CAPACITY);
+        let toolchain_watcher = TsFileWatcher::new(send_file_changed(toolchain_changed_tx.clone()));
+        let _cross_settings_watcher = TsSettingsWatcher::new(
+            vec![CROSS_ENABLED_SETTING.to_string()],
+            send_file_changed(toolchain_changed_tx),
+        );
+
+        set_workspace_folder(root_dir.clone());
+
+        // The configuration loads the selection of the workspace in use
+        set_active_manifest(get_state_vs_code(active_manifest_key(&root_dir)));
 
         let (configuration, configuration_task) =
             configuration::Configuration::init(root_dir.clone());
@@ -90,21 +182,38 @@ impl Workspace {
             configuration,
             outline,
             metadata: Metadata::default(),
-            mainfests_file_watcher: manifests_file_watcher,
-            config_file_watcher,
+            metadata_from_cache: false,
+            workspace_files_hash: None,
+            workspace_files_watcher,
+            env_file_watcher,
+            _metadata_settings_watcher,
+            toolchain_watcher,
+            _cross_settings_watcher,
+            workspaces: Vec::new(),
             root_dir,
         };
 
-        this.config_file_watcher
-            .watch_files(vec![this.root_manifest(), this.root_config()]);
+        this.watch_workspace_files();
+        this.watch_toolchain_files();
 
         let task = Task::batch([
             // manifest and config updates will run for the lifetime of the extension
-            Task::stream(manifest_changed_rx).map(|()| Message::ManifestChanged),
-            Task::stream(config_changed_rx).map(|()| Message::ConfigFileChanged),
-            // initially parse metadata
-            this.parse_packages_and_target_dir(),
+            Task::stream(files_changed_rx).map(|()| Message::WorkspaceFilesChanged),
+            Task::stream(env_file_changed_rx).map(|()| Message::EnvFileChanged),
+            Task::stream(settings_changed_rx).map(|()| Message::MetadataSettingsChanged),
+            Task::stream(toolchain_changed_rx).map(|()| Message::ToolchainChanged),
+            // initially show the cached metadata and refresh it
+            this.load_cached_packages()
+                .chain(this.hash_workspace_files()),
             this.parse_profiles(),
+            this.parse_registries(),
+            this.parse_overrides(),
+            this.load_env_files(),
+            this.capture_shell_env(),
+            this.detect_cross(),
+            this.detect_wsl_distro(),
+            Task::future(discover_workspaces(this.root_dir.clone()))
+                .map(Message::WorkspacesDiscovered),
             // initial sub-component tasks
             configuration_task.map(Message::Configuration),
             outline_task.map(Message::Outline),
@@ -117,32 +226,61 @@ impl Workspace {
         match msg {
             Message::MetadataChanged(update) => match update {
                 MetadataUpdate::PackagesAndTargetDir(packages_and_target_dir) => {
-                    self.metadata
-                        .set_packages_and_target_dir(packages_and_target_dir);
-
-                    // Update file watcher
-                    let mut manifests = self.metadata.manifests();
-                    manifests.push(self.root_manifest());
-                    self.mainfests_file_watcher.watch_files(manifests);
-
-                    let config = Task::done(Message::Configuration(
-                        configuration::Message::ManifestFilesChanged,
-                    ));
-                    let outline = Task::done(Message::Outline(outline::Message::MetadataChanged));
-                    let cargo_context = Task::future(set_cargo_context(true)).discard();
-
-                    Task::batch([config, outline, cargo_context])
+                    let from_cache = std::mem::take(&mut self.metadata_from_cache);
+                    // The cached packages are up to date
+                    if from_cache
+                        && self
+                            .metadata
+                            .has_packages_and_target_dir(&packages_and_target_dir)
+                    {
+                        return Task::none();
+                    }
+                    let cache = self.persist_metadata_cache(packages_and_target_dir.clone());
+                    // Edits of members only update the edited packages
+                    if !from_cache
+                        && let Some(changed) =
+                            self.metadata.patch_packages(&packages_and_target_dir)
+                    {
+                        if changed.is_empty() {
+                            return cache;
+                        }
+                        let config = Task::done(Message::Configuration(
+                            configuration::Message::ManifestFilesChanged,
+                        ));
+                        let outline = Task::done(Message::Outline(
+                            outline::Message::PackagesChanged(changed),
+                        ));
+                        return Task::batch([config, outline, cache]);
+                    }
+                    Task::batch([
+                        self.set_packages_and_target_dir(packages_and_target_dir),
+                        cache,
+                    ])
+                }
+                MetadataUpdate::Cached(packages_and_target_dir) => {
+                    self.metadata_from_cache = true;
+                    self.set_packages_and_target_dir(packages_and_target_dir)
                 }
                 MetadataUpdate::Profiles(profiles) => {
                     self.metadata.set_profiles(profiles);
                     Task::none()
                 }
+                MetadataUpdate::Registries(registries) => {
+                    self.metadata.set_registries(registries);
+                    Task::none()
+                }
+                MetadataUpdate::Overrides(overrides) => {
+                    self.metadata.set_overrides(overrides);
+                    Task::done(Message::Outline(outline::Message::ConfigChanged))
+                }
+                MetadataUpdate::SourceOverrides(source_overrides) => {
+                    self.metadata.set_source_overrides(source_overrides);
+                    Task::done(Message::Outline(outline::Message::ConfigChanged))
+                }
                 MetadataUpdate::NoCargoToml => {
-                    // Always check for mainfest in root dir
-                    self.mainfests_file_watcher
-                        .watch_files(vec![self.root_manifest()]);
-
                     self.metadata = Metadata::default();
+                    self.metadata_from_cache = false;
+                    set_workspace_root(None);
 
                     let config = Task::done(Message::Configuration(
                         configuration::Message::ManifestFilesChanged,
@@ -152,20 +290,144 @@ impl Workspace {
 
                     Task::batch([config, outline, cargo_context])
                 }
+                MetadataUpdate::Canceled => {
+                    info!("Scanning the workspace was canceled, the packages are unchanged");
+                    self.workspace_files_hash = None;
+                    Task::none()
+                }
                 // For invalid metadata or cargo command leave everything as is
                 MetadataUpdate::CargoCommandEmpty(e) | MetadataUpdate::FailedToParse(e) => {
                     error!("{e}");
+                    // The same files are parsed again, e.g. after a network error
+                    self.workspace_files_hash = None;
                     Task::none()
                 }
             },
-            Message::ManifestChanged => self.parse_packages_and_target_dir(),
-            Message::ConfigFileChanged => self.parse_profiles(),
+            Message::ManifestChanged => self.hash_workspace_files(),
+            Message::WorkspaceFilesHashed(hash) => {
+                // E.g. saving an unchanged manifest or touching it with a checkout
+                if self.workspace_files_hash == Some(hash) {
+                    return Task::none();
+                }
+                self.workspace_files_hash = Some(hash);
+                self.parse_packages_and_target_dir()
+            }
+            // `cargoTools.envFile` selects other variables for `cargo metadata`, which have to be
+            // loaded before it runs
+            Message::MetadataSettingsChanged => Task::batch([
+                self.load_env_files()
+                    .chain(Task::done(Message::RescanWorkspace)),
+                self.detect_wsl_distro(),
+                // A cargo command like `cargo +nightly` selects another toolchain
+                self.detect_cross(),
+            ]),
+            Message::RescanWorkspace => {
+                self.workspace_files_hash = None;
+                self.hash_workspace_files()
+            }
+            Message::WorkspaceFilesChanged => Task::batch([
+                self.hash_workspace_files(),
+                self.parse_profiles(),
+                self.parse_registries(),
+                self.parse_overrides(),
+            ]),
+            // The watched env files include `.envrc` if direnv is enabled
+            Message::EnvFileChanged => Task::batch([
+                self.load_env_files()
+                    .chain(Task::done(Message::RescanWorkspace)),
+                self.capture_shell_env(),
+            ]),
+            Message::EnvFileLoaded(vars) => {
+                set_env_file_vars(vars);
+                Task::none()
+            }
+            Message::ReloadShellEnv => self.capture_shell_env(),
+            Message::ShellEnvCaptured(vars) => {
+                if !vars.is_empty() {
+                    info!("Captured {} variables of the shell environment", vars.len());
+                }
+                set_shell_env_vars(vars);
+                Task::none()
+            }
+            Message::ToolchainChanged => self.detect_cross(),
+            Message::CrossDetected(host) => {
+                set_cross_host(host);
+                Task::none()
+            }
+            Message::WslDistroDetected(distro) => {
+                let detected = distro.is_some();
+                set_default_wsl_distro(distro);
+                if detected {
+                    Task::done(Message::RescanWorkspace)
+                } else {
+                    Task::none()
+                }
+            }
+            Message::WorkspacesDiscovered(workspaces) => {
+                self.workspaces = workspaces;
+                // A monorepo without a root manifest starts with its first workspace
+                match self.workspaces.first() {
+                    Some(first) if !self.workspaces.contains(&manifest_path()) => {
+                        Task::done(Message::ActivateWorkspace(first.clone()))
+                    }
+                    _ => Task::none(),
+                }
+            }
+            Message::SelectWorkspace => {
+                let root_dir = self.root_dir.clone();
+                Task::future(async move {
+                    let options = discover_workspaces(root_dir).await;
+                    if options.is_empty() {
+                        info!("No Cargo workspaces found in the folder");
+                        return None;
+                    }
+                    let current = vec![manifest_path()];
+                    SelectInput { options, current }
+                        .select()
+                        .await
+                        .map(Message::ActivateWorkspace)
+                })
+                .and_then(Task::done)
+            }
+            Message::ActivateWorkspace(manifest) => {
+                if manifest == manifest_path() {
+                    return Task::none();
+                }
+                set_active_manifest(Some(manifest.clone()));
+                self.metadata = Metadata::default();
+                self.metadata_from_cache = false;
+                self.workspace_files_hash = None;
+                self.watch_workspace_files();
+                self.watch_toolchain_files();
+
+                Task::batch([
+                    Task::future(persist_state_vs_code(
+                        active_manifest_key(&self.root_dir),
+                        manifest,
+                    ))
+                    .discard(),
+                    Task::done(Message::Configuration(
+                        configuration::Message::ProjectChanged,
+                    )),
+                    self.load_cached_packages()
+                        .chain(self.hash_workspace_files()),
+                    self.parse_profiles(),
+                    self.parse_registries(),
+                    self.parse_overrides(),
+                    self.load_env_files(),
+                    self.capture_shell_env(),
+                    self.detect_cross(),
+                ])
+            }
             Message::Configuration(msg) => {
                 let (task, event) = self.configuration.update(msg, &self.metadata);
 
                 let task = task.map(Message::Configuration);
                 match event {
-                    Some(evt) => Task::batch([task, Task::done(evt.into_message())]),
+                    // The selected profile determines which env files apply
+                    Some(evt) => {
+                        Task::batch([task, Task::done(evt.into_message()), self.load_env_files()])
+                    }
                     None => task,
                 }
             }
@@ -182,17 +444,114 @@ impl Workspace {
         }
     }
 
+    fn set_packages_and_target_dir(
+        &mut self,
+        packages_and_target_dir: PackagesAndTargetDir,
+    ) -> Task<Message> {
+        self.metadata
+            .set_packages_and_target_dir(packages_and_target_dir);
+        set_workspace_root(Some(self.metadata.workspace_root().to_string()));
+
+        // The target directory may have changed
+        self.watch_workspace_files();
+
+        let config = Task::done(Message::Configuration(
+            configuration::Message::ManifestFilesChanged,
+        ));
+        let outline = Task::done(Message::Outline(outline::Message::MetadataChanged));
+        let cargo_context = Task::future(set_cargo_context(true)).discard();
+
+        Task::batch([config, outline, cargo_context])
+    }
+
+    /// The packages of the [MetadataCache] of the active workspace if the manifests and the
+    /// cargo config it was parsed from are unchanged
+    fn load_cached_packages(&self) -> Task<Message> {
+        let Some(cache) = get_state_vs_code::<MetadataCache>(self.metadata_cache_key()) else {
+            return Task::none();
+        };
+        Task::future(async move {
+            let contents = read_contents(&cache.files).await;
+            cache
+                .get(&contents)
+                .map(|cached| Message::MetadataChanged(MetadataUpdate::Cached(cached)))
+        })
+        .and_then(Task::done)
+    }
+
+    /// Caches `packages_and_target_dir` with the hash of the files it was parsed from
+    fn persist_metadata_cache(
+        &self,
+        packages_and_target_dir: PackagesAndTargetDir,
+    ) -> Task<Message> {
+        let key = self.metadata_cache_key();
+        let files = self.metadata_files(packages_and_target_dir.manifests());
+        Task::future(async move {
+            let contents = read_contents(&files).await;
+            let cache = MetadataCache {
+                hash: files_hash(&contents),
+                files,
+                packages_and_target_dir,
+            };
+            persist_state_vs_code(key, cache).await
+        })
+        .discard()
+    }
+
+    /// The files `cargo metadata` reads, the root manifest and cargo config and the `manifests`
+    /// of the members
+    fn metadata_files(&self, manifests: Vec<String>) -> Vec<String> {
+        let mut files = vec![self.root_manifest(), self.root_config()];
+        for manifest in manifests {
+            if !files.contains(&manifest) {
+                files.push(manifest);
+            }
+        }
+        files
+    }
+
+    fn hash_workspace_files(&self) -> Task<Message> {
+        let dir = self.manifest_dir();
+        let files = self.metadata_files(self.metadata.manifests());
+        Task::future(workspace_files_hash(dir, files)).map(Message::WorkspaceFilesHashed)
+    }
+
+    fn metadata_cache_key(&self) -> String {
+        format!(
+            "{}.cargo_tools.workspace.metadata_cache.{}",
+            self.root_dir,
+            manifest_path()
+        )
+    }
+
     fn parse_packages_and_target_dir(&self) -> Task<Message> {
         let root_manifest = self.root_manifest();
         Task::future(async move {
-            if file_exists_vs_code(root_manifest.clone()).await {
-                parse_packages_and_target_dir(root_manifest, metadata_task_context(), exec_vs_code)
-                    .await
-            } else {
-                Err(ParseError::NoCargoToml)
+            if !file_exists_vs_code(root_manifest.clone()).await {
+                return MetadataUpdate::NoCargoToml;
             }
+            let mappings = path_mappings();
+            let manifest = mappings.to_cargo(&root_manifest);
+            let canceled = Cell::new(false);
+            let exec = async |process| {
+                let output = exec_cancellable_vs_code(process, "Scanning the Cargo workspace");
+                match output.await {
+                    Ok(Some(output)) => Ok(output),
+                    Ok(None) => {
+                        canceled.set(true);
+                        Err("Canceled".to_string())
+                    }
+                    Err(e) => Err(e),
+                }
+            };
+            let parsed = parse_packages_and_target_dir(manifest, metadata_task_context(), exec)
+                .await
+                .map(|parsed| parsed.map_paths(|path| mappings.to_host(path)));
+            if canceled.get() {
+                return MetadataUpdate::Canceled;
+            }
+            MetadataUpdate::from_parse_manifest_result(parsed)
         })
-        .map(MetadataUpdate::from_parse_manifest_result)
         .map(Message::MetadataChanged)
     }
 
@@ -210,13 +569,252 @@ impl Workspace {
         .map(Message::MetadataChanged)
     }
 
+    /// Parses the `[registries]` of the cargo config of the user and the workspace, the
+    /// workspace's take precedence
+    fn parse_registries(&self) -> Task<Message> {
+        let cargo_home = host_var("CARGO_HOME").or_else(|| {
+            host_var("HOME")
+                .or_else(|| host_var("USERPROFILE"))
+                .map(|home| format!("{home}/.cargo"))
+        });
+        let configs: Vec<String> = cargo_home
+            .map(|home| format!("{home}/config.toml"))
+            .into_iter()
+            .chain([self.root_config()])
+            .collect();
+        Task::future(async move {
+            let mut contents = Vec::new();
+            for config in configs {
+                contents.extend(read_file_vs_code(config).await.ok());
+            }
+            Registries::parse(contents.iter().map(String::as_str))
+        })
+        .map(MetadataUpdate::Registries)
+        .map(Message::MetadataChanged)
+    }
+
+    /// Parses the `[patch]` and `[replace]` overrides of the root manifest and the `[patch]` and
+    /// `paths` overrides of the workspace's cargo config, and which dependencies cargo resolves
+    /// from elsewhere than their registry
+    fn parse_overrides(&self) -> Task<Message> {
+        let manifest = self.root_manifest();
+        let config = self.root_config();
+        let manifest_dir = self.manifest_dir();
+        let declared = Task::future(async move {
+            let mut overrides = Vec::new();
+            if let Ok(content) = read_file_vs_code(manifest.clone()).await {
+                overrides.extend(parse_overrides(&manifest, &content));
+            }
+            if let Ok(content) = read_file_vs_code(config.clone()).await {
+                overrides.extend(parse_overrides(&config, &content));
+                // `paths` are relative to the directory of the `.cargo` directory
+                for (path, line) in override_paths(&content) {
+                    let package = Path::new(&manifest_dir).join(&path).join("Cargo.toml");
+                    let package = package.to_string_lossy().replace('\\', "/");
+                    if let Ok(package) = read_file_vs_code(package).await {
+                        overrides.extend(path_override(&config, line, &path, &package));
+                    }
+                }
+            }
+            overrides
+        })
+        .map(MetadataUpdate::Overrides);
+        Task::batch([declared, self.parse_source_overrides()]).map(Message::MetadataChanged)
+    }
+
+    /// Resolves the dependencies offline to find the ones cargo sources from elsewhere, an
+    /// unresolved workspace keeps the current ones
+    fn parse_source_overrides(&self) -> Task<MetadataUpdate> {
+        let root_manifest = self.root_manifest();
+        Task::future(async move {
+            let mappings = path_mappings();
+            let mut args = resolve_args(&mappings.to_cargo(&root_manifest));
+            args.push("--offline".to_string());
+            let process = metadata_task_context()
+                .try_into_process(args)
+                .inspect_err(|e| error!("{e}"))
+                .ok()?;
+            let output = exec_vs_code(process)
+                .await
+                .inspect_err(|e| error!("Failed to resolve the overridden dependencies: {e}"))
+                .ok()?;
+            let overrides = source_overrides(&output)
+                .inspect_err(|e| error!("Failed to parse the resolved dependencies: {e}"))
+                .ok()?;
+            Some(MetadataUpdate::SourceOverrides(
+                overrides
+                    .into_iter()
+                    .map(|o| o.map_paths(|path| mappings.to_host(path)))
+                    .collect(),
+            ))
+        })
+        .and_then(Task::done)
+    }
+
+    fn load_env_files(&self) -> Task<Message> {
+        let profile = &self.configuration.config().profile;
+        let paths = env_file::env_file_paths(&self.root_dir, &env_file(), profile);
+        let mut watched = paths.clone();
+        if shell_env_direnv() {
+            watched.push(format!("{}/.envrc", self.manifest_dir()));
+        }
+        if !watched.is_empty() {
+            self.env_file_watcher.watch_files(watched);
+        }
+
+        Task::future(async move {
+            let mut vars = HashMap::new();
+            // Missing env files are fine, later files override earlier ones
+            for path in paths {
+                if let Ok(content) = read_file_vs_code(path).await {
+                    vars.extend(env_file::parse(&content));
+                }
+            }
+            vars
+        })
+        .map(Message::EnvFileLoaded)
+    }
+
+    /// Captures the variables of direnv and the shell init command in the directory of the
+    /// root manifest
+    fn capture_shell_env(&self) -> Task<Message> {
+        Task::future(capture_shell_env(self.manifest_dir())).map(Message::ShellEnvCaptured)
+    }
+
+    /// Checks whether `cross` is installed and the host triple of the toolchain if
+    /// `cargoTools.cross.enabled` is set, again when it or the toolchain changes
+    fn detect_cross(&self) -> Task<Message> {
+        if !cross_enabled() {
+            return Task::done(Message::CrossDetected(None));
+        }
+        Task::future(detect_cross()).map(Message::CrossDetected)
+    }
+
+    /// Lists the WSL distributions for the default one if the cargo command runs `wsl` without
+    /// naming one
+    fn detect_wsl_distro(&self) -> Task<Message> {
+        let command = cargo_command();
+        if !is_wsl_command(&command) || wsl_distro(&command).is_some() {
+            return Task::none();
+        }
+        Task::future(default_wsl_distro()).map(Message::WslDistroDetected)
+    }
+
     fn root_manifest(&self) -> String {
-        format!("{}/Cargo.toml", self.root_dir)
+        format!("{}/{}", self.root_dir, manifest_path())
+    }
+
+    /// The directory of the root manifest which is not necessarily the VS Code workspace folder
+    fn manifest_dir(&self) -> String {
+        let root_manifest = self.root_manifest();
+        root_manifest
+            .rsplit_once('/')
+            .map_or(self.root_dir.as_str(), |(dir, _)| dir)
+            .to_string()
     }
 
+    /// The cargo config next to the root manifest
     fn root_config(&self) -> String {
-        format!("{}/.cargo/config.toml", self.root_dir)
+        format!("{}/.cargo/config.toml", self.manifest_dir())
+    }
+
+    /// Watches the toolchain files of the workspace, which select the toolchain of its builds
+    fn watch_toolchain_files(&self) {
+        let dir = self.manifest_dir();
+        self.toolchain_watcher.watch_files(
+            ["rust-toolchain.toml", "rust-toolchain"]
+                .map(|file| format!("{dir}/{file}"))
+                .to_vec(),
+        );
+    }
+
+    /// Watches all manifests and cargo configs below the directory of the root manifest, e.g.
+    /// of members which aren't part of the metadata yet. The files of the target directory are
+    /// ignored, bursts like of a `git checkout` notify once.
+    fn watch_workspace_files(&self) {
+        let target_dir = match self.metadata.target_dir() {
+            "" => format!("{}/target", self.manifest_dir()),
+            target_dir => target_dir.to_string(),
+        };
+        self.workspace_files_watcher
+            .set_debounce(manifest_watcher_debounce());
+        self.workspace_files_watcher.watch_patterns(
+            self.manifest_dir(),
+            ["**/Cargo.toml", "**/.cargo/config.toml", "**/.cargo/config"]
+                .map(str::to_string)
+                .to_vec(),
+            vec![target_dir],
+        );
+    }
+}
+
+/// The root manifests of the Cargo workspaces in `root_dir`, see [workspace_manifests]
+async fn discover_workspaces(root_dir: String) -> Vec<String> {
+    let found = find_manifests(root_dir.clone(), discovery_exclude()).await;
+    let paths: Vec<String> = serde_wasm_bindgen::from_value(found).unwrap_or_default();
+    let mut manifests = Vec::with_capacity(paths.len());
+    for path in paths {
+        if let Ok(content) = read_file_vs_code(format!("{root_dir}/{path}")).await {
+            manifests.push((path, content));
+        }
+    }
+    workspace_manifests(&manifests, discovery_max_depth() as usize)
+}
+
+/// The variables `.envrc` sets if direnv is enabled, overridden by the ones the shell init
+/// command exports. Failures are logged and leave out the variables of the failed source.
+async fn capture_shell_env(dir: String) -> HashMap<String, String> {
+    let process = |cmd: &str, args: Vec<String>| {
+        Process::new(cmd.to_string(), args, HashMap::new()).with_cwd(Some(dir.clone()))
+    };
+
+    let mut vars = HashMap::new();
+    if shell_env_direnv() {
+        let export = exec_vs_code(process(DIRENV, shell_env::direnv_args()))
+            .await
+            .and_then(|json| shell_env::parse_direnv_export(&json).map_err(|e| e.to_string()));
+        match export {
+            Ok(direnv) => vars.extend(direnv),
+            Err(e) => error!(
+                "Failed to load .envrc with direnv, is it installed and allowed with `direnv allow`? {e}"
+            ),
+        }
+    }
+    if let Some(init) = shell_env_init_command() {
+        let before = exec_vs_code(process(SHELL, shell_env::env_args(None))).await;
+        let after = exec_vs_code(process(SHELL, shell_env::env_args(Some(&init)))).await;
+        match before.and_then(|before| Ok((before, after?))) {
+            Ok((before, after)) => vars.extend(shell_env::changed_vars(
+                &shell_env::parse_env(&before),
+                shell_env::parse_env(&after),
+            )),
+            Err(e) => error!("Failed to capture the environment of `{init}` with {SHELL}: {e}"),
+        }
     }
+    vars
+}
+
+/// The contents of `files`, empty for missing ones
+async fn read_contents(files: &[String]) -> Vec<String> {
+    let mut contents = Vec::new();
+    for file in files {
+        contents.push(read_file_vs_code(file.clone()).await.unwrap_or_default());
+    }
+    contents
+}
+
+/// The hash of the contents of `files` and the paths of all manifests below `dir`, which changes
+/// when a manifest is edited, added or removed
+async fn workspace_files_hash(dir: String, files: Vec<String>) -> u64 {
+    let found = find_manifests(dir, discovery_exclude()).await;
+    let manifests: Vec<String> = serde_wasm_bindgen::from_value(found).unwrap_or_default();
+    let mut contents = read_contents(&files).await;
+    contents.extend(manifests);
+    files_hash(&contents)
+}
+
+fn active_manifest_key(root_dir: &str) -> String {
+    format!("{root_dir}.cargo_tools.workspace.active_manifest")
 }
 
 trait IntoMessage {
@@ -229,6 +827,11 @@ impl IntoMessage for outline::Event {
             outline::Event::ConfigUpdate(update) => {
                 Message::Configuration(configuration::Message::ConfigChanged(update))
             }
+            outline::Event::NewTarget(package, kind) => {
+                Message::Configuration(configuration::Message::Cmd(
+                    configuration::command::Command::NewTarget { package, kind },
+                ))
+            }
         }
     }
 }
@@ -237,6 +840,9 @@ impl IntoMessage for configuration::Event {
     fn into_message(self) -> Message {
         match self {
             configuration::Event::ConfigUpdate => Message::Outline(outline::Message::ConfigChanged),
+            configuration::Event::ManifestsEdited => Message::ManifestChanged,
+            configuration::Event::SelectWorkspace => Message::SelectWorkspace,
+            configuration::Event::ReloadShellEnv => Message::ReloadShellEnv,
         }
     }
 }
@@ -0,0 +1,15 @@
+import * as vscode from 'vscode';
+import * as path from 'path';
+
+/**
+ * The manifests below `root` relative to it with forward slashes, skipping the directories of
+ * the `exclude` globs
+ */
+export async function find_manifests(root: string, exclude: string[]): Promise<string[]> {
+    const files = await vscode.workspace.findFiles(
+        new vscode.RelativePattern(root, '**/Cargo.toml'),
+        exclude.length > 0 ? `{${exclude.join(',')}}` : undefined);
+    return files
+        .map((file) => path.relative(root, file.fsPath).split(path.sep).join('/'))
+        .sort();
+}
@@ -34,6 +34,18 @@ pub const WORKSPACE: Icon = Icon {
     icon: "organization",
     color: "charts.purple",
 };
+pub const DEPENDENCIES: Icon = Icon {
+    icon: "references",
+    color: "charts.blue",
+};
+pub const DEPENDENCY: Icon = Icon {
+    icon: "package",
+    color: "charts.foreground",
+};
+pub const OVERRIDDEN_DEPENDENCY: Icon = Icon {
+    icon: "package",
+    color: "charts.yellow",
+};
 
 // Target types with vibrant, distinguishable colors
 pub const BIN_TARGET: Icon = Icon {
@@ -139,6 +151,12 @@ pub const XTASK_ALIAS: Icon = Icon {
     color: "charts.green",
 };
 
+// Just recipe
+pub const JUST_RECIPE: Icon = Icon {
+    icon: "run",
+    color: "charts.orange",
+};
+
 // Utility actions with chart color distinctions
 pub const REFRESH_ACTION: Icon = Icon {
     icon: "refresh",
@@ -1,7 +1,12 @@
 use cargo_tools::{
-    cargo::Profile,
     cargo::command::{BuildSubTarget, RunSubTarget},
+    cargo::{
+        DocTest, InstaUpdate, Profile, Sanitizer, Template, metadata::RegistryDependency,
+        new_package::PackageKind, new_target::BenchHarness, version_bump::Bump,
+    },
     cargo_make::MakefileTask,
+    debugger::RunningProcess,
+    rustup::{Component, Toolchain},
 };
 use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen::to_value;
@@ -32,6 +37,26 @@ extern "C" {
 
     #[wasm_bindgen(catch)]
     pub async fn show_input_box(placeholder: String, prompt: String) -> Result<JsValue, JsValue>;
+
+    /// Like [show_input_box] but prefilled with `value`
+    #[wasm_bindgen(catch)]
+    pub async fn show_input_box_with_value(
+        placeholder: String,
+        prompt: String,
+        value: String,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    pub async fn show_open_file_dialog(
+        title: String,
+        default_dir: String,
+    ) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    pub async fn show_input_with_history(
+        history: Vec<String>,
+        placeholder: String,
+    ) -> Result<JsValue, JsValue>;
 }
 
 /// Represents an item in a VS Code quick pick menu.
@@ -114,6 +139,102 @@ impl ToQuickPickItem for Profile {
     }
 }
 
+impl ToQuickPickItem for Sanitizer {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        let detail = match self {
+            Sanitizer::Address => "Out-of-bounds accesses, use-after-free and leaks",
+            Sanitizer::Thread => "Data races",
+            Sanitizer::Leak => "Memory leaks",
+        };
+        QuickPickItem::new(self.name().to_string())
+            .with_detail(detail.to_string())
+            .with_picked(picked)
+    }
+}
+
+impl ToQuickPickItem for InstaUpdate {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        QuickPickItem::new(self.name().to_string())
+            .with_detail(self.description().to_string())
+            .with_picked(picked)
+    }
+}
+
+impl ToQuickPickItem for PackageKind {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        QuickPickItem::new(self.name().to_string())
+            .with_detail(self.description().to_string())
+            .with_picked(picked)
+    }
+}
+
+impl ToQuickPickItem for Bump {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        QuickPickItem::new(self.name().to_string())
+            .with_detail(self.description().to_string())
+            .with_picked(picked)
+    }
+}
+
+impl ToQuickPickItem for BenchHarness {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        QuickPickItem::new(self.name().to_string())
+            .with_detail(self.description().to_string())
+            .with_picked(picked)
+    }
+}
+
+/// A template of `cargoTools.cargoGenerate.templates` with its name
+impl ToQuickPickItem for (String, Template) {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        let (name, template) = self;
+        let item = QuickPickItem::new(name.clone())
+            .with_description(template.source())
+            .with_picked(picked);
+        match &template.description {
+            Some(description) => item.with_detail(description.clone()),
+            None => item,
+        }
+    }
+}
+
+impl ToQuickPickItem for RegistryDependency {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        let description = match self.kind {
+            Some(kind) => format!("{} · {kind}", self.req),
+            None => self.req.clone(),
+        };
+        QuickPickItem::new(self.name.clone())
+            .with_description(description)
+            .with_picked(picked)
+    }
+}
+
+impl ToQuickPickItem for Toolchain {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        let item = QuickPickItem::new(self.name.clone()).with_picked(picked);
+        if self.default {
+            item.with_description("default".to_string())
+        } else {
+            item
+        }
+    }
+}
+
+impl ToQuickPickItem for Component {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        let status = if self.installed {
+            "installed"
+        } else {
+            "not installed"
+        };
+        QuickPickItem::new(self.name.to_string())
+            .with_description(status.to_string())
+            .with_detail(self.description.to_string())
+            .with_picked(picked)
+    }
+}
+
 impl ToQuickPickItem for String {
     fn to_item(&self, picked: bool) -> QuickPickItem {
         QuickPickItem::new(self.clone()).with_picked(picked)
@@ -180,6 +301,26 @@ impl ToQuickPickItem for MakefileTask {
     }
 }
 
+impl ToQuickPickItem for DocTest {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        QuickPickItem::new(self.item.clone())
+            .with_description(format!("{}:{}", self.file, self.line))
+            .with_picked(picked)
+    }
+}
+
+impl ToQuickPickItem for RunningProcess {
+    fn to_item(&self, picked: bool) -> QuickPickItem {
+        let program = self.command.split_whitespace().next().unwrap_or_default();
+        let name = program.rsplit(['/', '\\']).next().unwrap_or(program);
+
+        QuickPickItem::new(name.to_string())
+            .with_description(format!("PID {}", self.pid))
+            .with_detail(self.command.clone())
+            .with_picked(picked)
+    }
+}
+
 #[derive(Debug)]
 pub struct SelectInput<T> {
     pub options: Vec<T>,
@@ -80,6 +80,61 @@ export async function show_input_box(
     return vscode.window.showInputBox({ placeHolder: placeholder, prompt });
 }
 
+export async function show_input_box_with_value(
+    placeholder: string,
+    prompt: string,
+    value: string,
+): Promise<string | undefined> {
+    return vscode.window.showInputBox({ placeHolder: placeholder, prompt, value });
+}
+
+export async function show_open_file_dialog(title: string, default_dir: string): Promise<string | null> {
+    const uris = await vscode.window.showOpenDialog({
+        title,
+        defaultUri: vscode.Uri.file(default_dir),
+        canSelectFiles: true,
+        canSelectFolders: false,
+        canSelectMany: false,
+    });
+    return uris?.[0]?.fsPath ?? null;
+}
+
+export async function show_input_with_history(
+    history: string[],
+    placeholder: string,
+): Promise<string | null> {
+    const quickPick = vscode.window.createQuickPick();
+    quickPick.placeholder = placeholder;
+
+    // The typed value is offered as the first item so it can be accepted like a previous entry
+    const updateItems = (value: string) => {
+        const typed = value && !history.includes(value) ? [{ label: value, description: 'new' }] : [];
+        quickPick.items = [...typed, ...history.map(label => ({ label, description: 'recent' }))];
+    };
+    updateItems('');
+
+    const onChange = quickPick.onDidChangeValue(updateItems);
+
+    let accepted: string | null = null;
+    quickPick.onDidAccept(() => {
+        accepted = quickPick.selectedItems[0]?.label ?? quickPick.value;
+        quickPick.hide();
+    });
+
+    quickPick.show();
+
+    await new Promise<void>(resolve => {
+        const onHide = quickPick.onDidHide(() => {
+            onChange.dispose();
+            quickPick.dispose();
+            onHide.dispose();
+            resolve();
+        });
+    });
+
+    return accepted;
+}
+
 export async function show_quick_pick_type(current: string, items: QuickPickItem[], on_type: (filter: string) => void): Promise<string | null> {
     const vsCodeItems = to_items(items);
 
@@ -16,6 +16,15 @@ impl RecentItems {
         self.0.insert(0, name);
     }
 
+    pub fn items(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Keeps only the `len` most recently used items.
+    pub fn truncate(&mut self, len: usize) {
+        self.0.truncate(len);
+    }
+
     pub fn remove_obsolete<T>(&mut self, available: &[T], name: impl Fn(&T) -> &str) {
         self.0
             .retain(|recent| available.iter().any(|item| name(item) == recent));
@@ -89,4 +98,16 @@ mod tests {
 
         assert_eq!(recent.0, ["first", "second"]);
     }
+
+    #[test]
+    fn truncates_to_most_recent_items() {
+        let mut recent = RecentItems::default();
+        recent.record("third".into());
+        recent.record("second".into());
+        recent.record("first".into());
+
+        recent.truncate(2);
+
+        assert_eq!(recent.items(), ["first", "second"]);
+    }
 }
@@ -1,18 +1,77 @@
-use cargo_tools::process::Process;
-use serde::{Serialize, de::DeserializeOwned};
-use serde_wasm_bindgen::to_value;
-use std::fmt::Debug;
+use cargo_tools::{
+    cargo::{
+        DocTest, TestTarget,
+        command::{BenchTarget, RunSubTarget},
+        diagnostics::with_rendered_messages,
+        sanitizer,
+    },
+    debugger::{
+        Attach, CoreDump, DebugOverrides, Debugger, Host, Launch, RemoteTarget, RunningProcess,
+        TerminalDebugger,
+    },
+    output::StreamedOutput,
+    path_mapping::parse_default_wsl_distro,
+    probe_rs::{PROBE_RS, ProbeRsCommand},
+    process::{CLEAN_ENV_HOST_VARS, CROSS_COMMAND, Process},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_wasm_bindgen::{Serializer, from_value, to_value};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    path::PathBuf,
+    rc::Rc,
+};
 use tracing::{error, info};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::js_sys::{Array, JsString, Map};
 
+use crate::{
+    environment,
+    quick_pick::{SelectInput, show_input_box, show_open_file_dialog},
+};
+
 #[wasm_bindgen(raw_module = "../../../packages/cargo_tools_vscode/src/runtime.ts")]
 extern "C" {
     #[wasm_bindgen(catch)]
     async fn execute_async(process: VsCodeProcess) -> Result<JsString, JsValue>;
 
+    #[wasm_bindgen(catch)]
+    async fn execute_with_output(process: VsCodeProcess) -> Result<JsString, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn execute_stdout(process: VsCodeProcess) -> Result<JsString, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn execute_cancellable(process: VsCodeProcess, title: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn execute_with_output_timeout(
+        process: VsCodeProcess,
+        timeout_ms: f64,
+        group: &str,
+    ) -> Result<JsValue, JsValue>;
+
+    /// Milliseconds since the epoch
+    #[wasm_bindgen(js_namespace = Date, js_name = now)]
+    pub fn now_ms() -> f64;
+
+    #[wasm_bindgen(catch)]
+    async fn execute_with_progress(
+        process: VsCodeProcess,
+        title: &str,
+    ) -> Result<JsString, JsValue>;
+
     pub async fn execute_task(task: VsCodeTask);
 
+    async fn execute_in_terminal(task: VsCodeTask);
+
+    #[wasm_bindgen(js_name = execute_task_and_wait)]
+    async fn execute_task_and_wait_ts(task: VsCodeTask, silent: bool) -> JsValue;
+
+    pub async fn cancel_tasks();
+
     #[wasm_bindgen(catch)]
     async fn executeCommand(command: &str, rest: Array) -> Result<JsValue, JsValue>;
 
@@ -27,6 +86,53 @@ extern "C" {
     #[wasm_bindgen(method)]
     fn watch_files(this: &FileWatcher, paths: Vec<String>);
 
+    #[wasm_bindgen(method)]
+    fn watch_patterns(
+        this: &FileWatcher,
+        base: String,
+        patterns: Vec<String>,
+        ignored: Vec<String>,
+    );
+
+    #[wasm_bindgen(method)]
+    fn set_debounce(this: &FileWatcher, ms: u32);
+
+    #[wasm_bindgen(method)]
+    fn dispose(this: &FileWatcher);
+
+    type SaveWatcher;
+
+    #[wasm_bindgen(constructor)]
+    fn new() -> SaveWatcher;
+
+    #[wasm_bindgen(method)]
+    fn on_saved(this: &SaveWatcher, callback: &Closure<dyn FnMut()>);
+
+    #[wasm_bindgen(method)]
+    fn dispose(this: &SaveWatcher);
+
+    type TaskMessagesWatcher;
+
+    #[wasm_bindgen(constructor)]
+    fn new() -> TaskMessagesWatcher;
+
+    #[wasm_bindgen(method)]
+    fn on_messages(this: &TaskMessagesWatcher, callback: &Closure<dyn FnMut(String)>);
+
+    #[wasm_bindgen(method)]
+    fn dispose(this: &TaskMessagesWatcher);
+
+    type SettingsWatcher;
+
+    #[wasm_bindgen(constructor)]
+    fn new(keys: Vec<String>) -> SettingsWatcher;
+
+    #[wasm_bindgen(method)]
+    fn on_changed(this: &SettingsWatcher, callback: &Closure<dyn FnMut()>);
+
+    #[wasm_bindgen(method)]
+    fn dispose(this: &SettingsWatcher);
+
     #[wasm_bindgen(catch)]
     async fn read_file(file_path: &str) -> Result<JsString, JsValue>;
 
@@ -34,10 +140,36 @@ extern "C" {
     async fn file_exists(file_path: &str) -> Result<JsValue, JsValue>;
 
     #[wasm_bindgen(catch)]
-    pub async fn debug(target_exe_path: &str, target_name: &str) -> Result<JsValue, JsValue>;
+    async fn delete_file(file_path: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn write_file(file_path: &str, contents: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn file_stat(file_path: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(catch)]
+    async fn debug(config: JsValue, target_name: &str) -> Result<JsValue, JsValue>;
+
+    fn extension_installed(id: &str) -> bool;
+
+    pub async fn show_warning_message(message: &str, actions: Vec<String>) -> JsValue;
+
+    async fn delay(ms: u32);
+
+    /// Opens `url` in the browser
+    pub fn open_url(url: String);
+
+    /// Opens the file `path` with the cursor at the zero based `line`
+    pub async fn open_file_at(path: String, line: u32);
+
+    /// Shows the preview of a `markdown` document
+    pub async fn show_markdown(markdown: String);
 
     pub fn host_platform() -> String;
 
+    fn host_env(keys: Vec<String>) -> JsValue;
+
     #[wasm_bindgen(catch)]
     fn get_state(key: &str) -> Result<String, JsValue>;
 
@@ -60,9 +192,29 @@ impl TsFileWatcher {
         }
     }
 
+    /// Watches the files at the literal `paths`, replacing the watched files. Glob characters
+    /// in their names match literally, see [Self::watch_patterns] for globs.
     pub fn watch_files(&self, paths: Vec<String>) {
         self.file_watcher.watch_files(paths);
     }
+
+    /// Watches the files matching the glob `patterns` below `base` recursively, replacing the
+    /// watched files. Files in the `ignored` directories are skipped.
+    pub fn watch_patterns(&self, base: String, patterns: Vec<String>, ignored: Vec<String>) {
+        self.file_watcher.watch_patterns(base, patterns, ignored);
+    }
+
+    /// Notifies once `ms` after the last change of a burst, 100 ms by default
+    pub fn set_debounce(&self, ms: u32) {
+        self.file_watcher.set_debounce(ms);
+    }
+}
+
+impl Drop for TsFileWatcher {
+    fn drop(&mut self) {
+        // The callback is dropped together with the watcher so it must not fire anymore
+        self.file_watcher.dispose();
+    }
 }
 
 impl Debug for TsFileWatcher {
@@ -71,6 +223,93 @@ impl Debug for TsFileWatcher {
     }
 }
 
+/// Notifies about saved Rust sources and manifests of the workspace
+pub struct TsSaveWatcher {
+    save_watcher: SaveWatcher,
+    _on_saved: Closure<dyn FnMut()>,
+}
+
+impl TsSaveWatcher {
+    pub fn new(callback: Closure<dyn FnMut()>) -> Self {
+        let save_watcher = SaveWatcher::new();
+        save_watcher.on_saved(&callback);
+        Self {
+            save_watcher,
+            _on_saved: callback,
+        }
+    }
+}
+
+impl Drop for TsSaveWatcher {
+    fn drop(&mut self) {
+        self.save_watcher.dispose();
+    }
+}
+
+impl Debug for TsSaveWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TsSaveWatcher").finish()
+    }
+}
+
+/// Passes the JSON messages of finished build, check, clippy, test and bench tasks
+pub struct TsTaskMessagesWatcher {
+    task_messages_watcher: TaskMessagesWatcher,
+    _on_messages: Closure<dyn FnMut(String)>,
+}
+
+impl TsTaskMessagesWatcher {
+    pub fn new(callback: Closure<dyn FnMut(String)>) -> Self {
+        let task_messages_watcher = TaskMessagesWatcher::new();
+        task_messages_watcher.on_messages(&callback);
+        Self {
+            task_messages_watcher,
+            _on_messages: callback,
+        }
+    }
+}
+
+impl Drop for TsTaskMessagesWatcher {
+    fn drop(&mut self) {
+        self.task_messages_watcher.dispose();
+    }
+}
+
+impl Debug for TsTaskMessagesWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TsTaskMessagesWatcher").finish()
+    }
+}
+
+/// Notifies about changes of the settings `keys`, e.g. `cargoTools.extraEnv`
+pub struct TsSettingsWatcher {
+    settings_watcher: SettingsWatcher,
+    _on_changed: Closure<dyn FnMut()>,
+}
+
+impl TsSettingsWatcher {
+    pub fn new(keys: Vec<String>, callback: Closure<dyn FnMut()>) -> Self {
+        let settings_watcher = SettingsWatcher::new(keys);
+        settings_watcher.on_changed(&callback);
+        Self {
+            settings_watcher,
+            _on_changed: callback,
+        }
+    }
+}
+
+impl Drop for TsSettingsWatcher {
+    fn drop(&mut self) {
+        self.settings_watcher.dispose();
+    }
+}
+
+impl Debug for TsSettingsWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TsSettingsWatcher").finish()
+    }
+}
+
 pub async fn set_cargo_context(has_cargo: bool) {
     let res = executeCommand(
         "setContext",
@@ -99,6 +338,20 @@ pub async fn set_xtask_context(has_xtask_config: bool) {
     }
 }
 
+pub async fn set_justfile_context(has_justfile: bool) {
+    let res = executeCommand(
+        "setContext",
+        Array::of2(
+            &JsValue::from_str("cargoTools:workspaceHasJustfile"),
+            &JsValue::from_bool(has_justfile),
+        ),
+    )
+    .await;
+    if let Err(e) = res {
+        error!("{}", e.to_error_string());
+    }
+}
+
 pub async fn set_makefile_context(has_makefile: bool) {
     let res = executeCommand(
         "setContext",
@@ -113,6 +366,63 @@ pub async fn set_makefile_context(has_makefile: bool) {
     }
 }
 
+/// Shows the embedded commands while an embedded platform target is selected
+pub async fn set_embedded_target_context(embedded: bool) {
+    let res = executeCommand(
+        "setContext",
+        Array::of2(
+            &JsValue::from_str("cargoTools:embeddedTarget"),
+            &JsValue::from_bool(embedded),
+        ),
+    )
+    .await;
+    if let Err(e) = res {
+        error!("{}", e.to_error_string());
+    }
+}
+
+/// Shows the WASM commands while the selected package depends on wasm-bindgen
+pub async fn set_wasm_package_context(wasm: bool) {
+    let res = executeCommand(
+        "setContext",
+        Array::of2(
+            &JsValue::from_str("cargoTools:wasmPackage"),
+            &JsValue::from_bool(wasm),
+        ),
+    )
+    .await;
+    if let Err(e) = res {
+        error!("{}", e.to_error_string());
+    }
+}
+
+/// Suffix of executables on the host platform
+pub fn exe_suffix() -> &'static str {
+    if host_platform() == "win32" {
+        ".exe"
+    } else {
+        ""
+    }
+}
+
+/// Runs `process` with only the configured variables and the few host variables cargo needs.
+/// Returns [None] on Windows which has no `env -i`.
+pub fn into_clean_env(process: Process) -> Option<Process> {
+    if host_platform() == "win32" {
+        return None;
+    }
+    let keys = CLEAN_ENV_HOST_VARS.map(ToString::to_string).to_vec();
+    let host_env: HashMap<String, String> = from_value(host_env(keys)).unwrap_or_default();
+    Some(process.into_clean_env(host_env))
+}
+
+/// The variable `key` of the environment VS Code runs in
+pub fn host_var(key: &str) -> Option<String> {
+    let mut host_env: HashMap<String, String> =
+        from_value(host_env(vec![key.to_string()])).unwrap_or_default();
+    host_env.remove(key)
+}
+
 pub trait JsValueExt {
     fn to_error_string(self) -> String;
 }
@@ -132,10 +442,39 @@ pub async fn read_file_vs_code(file_path: String) -> Result<String, String> {
         .map_err(|e| e.to_error_string())
 }
 
+/// Writes `contents` to `file_path`, missing parent directories are created
+pub async fn write_file_vs_code(file_path: String, contents: String) -> Result<(), String> {
+    write_file(&file_path, &contents)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_error_string())
+}
+
+/// Deletes `file_path`, a missing file isn't an error
+pub async fn delete_file_vs_code(file_path: String) -> Result<(), String> {
+    delete_file(&file_path)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_error_string())
+}
+
 pub async fn file_exists_vs_code(file_path: String) -> bool {
     file_exists(&file_path).await.is_ok()
 }
 
+/// The size and modification time of a file
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct FileStat {
+    pub size: u64,
+    /// Milliseconds since the epoch
+    pub mtime: f64,
+}
+
+pub async fn file_stat_vs_code(file_path: String) -> Option<FileStat> {
+    let stat = file_stat(&file_path).await.ok()?;
+    from_value(stat).ok()
+}
+
 pub async fn persist_state_vs_code(key: String, state: impl Serialize) {
     let state = serde_json::to_string(&state);
     let Ok(state) = state else {
@@ -175,6 +514,651 @@ pub async fn exec_vs_code(process: Process) -> Result<String, String> {
         .map_err(|e| e.to_error_string())
 }
 
+/// Executes `process` with a cancellable progress notification titled `title` which shows up
+/// once it runs longer than a second, [None] if it was canceled
+pub async fn exec_cancellable_vs_code(
+    process: Process,
+    title: &str,
+) -> Result<Option<String>, String> {
+    execute_cancellable(VsCodeProcess(process), title)
+        .await
+        .map(|output| output.as_string())
+        .map_err(|e| e.to_error_string())
+}
+
+/// Executes `process` and returns its combined stdout and stderr, also if it fails
+pub async fn exec_output_vs_code(process: Process) -> Result<String, String> {
+    execute_with_output(VsCodeProcess(process))
+        .await
+        .map(|js_str| js_str.as_string().expect("JsString conversion failed"))
+        .map_err(|e| e.to_error_string())
+}
+
+/// Executes `process` and returns its stdout, also if it fails
+pub async fn exec_stdout_vs_code(process: Process) -> Result<String, String> {
+    execute_stdout(VsCodeProcess(process))
+        .await
+        .map(|js_str| js_str.as_string().expect("JsString conversion failed"))
+        .map_err(|e| e.to_error_string())
+}
+
+/// The output of [exec_output_with_timeout_vs_code]
+#[derive(Debug, Deserialize)]
+pub struct TimedOutput {
+    pub output: String,
+    /// The process and its children were killed after the timeout
+    pub timed_out: bool,
+}
+
+/// Like [exec_output_vs_code] but kills the process together with its children once
+/// `timeout_ms` passed, if given. `kill_output_processes(group)` kills it with the other
+/// processes of its `group`, e.g. of a test run.
+pub async fn exec_output_with_timeout_vs_code(
+    process: Process,
+    timeout_ms: Option<f64>,
+    group: &str,
+) -> Result<TimedOutput, String> {
+    let output =
+        execute_with_output_timeout(VsCodeProcess(process), timeout_ms.unwrap_or(0.0), group)
+            .await
+            .map_err(|e| e.to_error_string())?;
+    from_value(output).map_err(|e| e.to_string())
+}
+
+type FindExecutable = Box<dyn Fn(&str) -> Option<String>>;
+
+/// A debug session which builds its executable first, the last one can be repeated
+struct DebugSession {
+    /// Build with `--message-format=json` which produces the executable
+    build: Process,
+    /// Name of the built target for messages
+    target_name: String,
+    /// Finds the executable in the JSON messages of the build
+    executable: FindExecutable,
+    /// The launch of the executable, its program is set after the build
+    launch: Launch,
+    remote: Option<RemoteTarget>,
+    overrides: DebugOverrides,
+}
+
+thread_local! {
+    static LAST_DEBUG_SESSION: RefCell<Option<Rc<DebugSession>>> = const { RefCell::new(None) };
+}
+
+/// Builds the executable of `session` and debugs it
+async fn debug_session(session: Rc<DebugSession>) {
+    LAST_DEBUG_SESSION.with(|last| *last.borrow_mut() = Some(session.clone()));
+
+    let name = &session.target_name;
+    let Some(messages) = build_for_debugging(session.build.clone(), name).await else {
+        return;
+    };
+
+    let Some(program) = (session.executable)(&messages) else {
+        error!("Cargo did not report an executable for {name}");
+        return;
+    };
+    let program = environment::path_mappings().to_host(&program);
+    if !file_exists_vs_code(program.clone()).await {
+        error!("The executable {program} of {name} does not exist");
+        return;
+    }
+
+    let launch = Launch {
+        program,
+        ..session.launch.clone()
+    };
+    start_debugging(launch, session.remote.clone(), &session.overrides).await;
+}
+
+/// Repeats the last debug session with the same target, arguments and environment
+pub async fn debug_last() {
+    let Some(session) = LAST_DEBUG_SESSION.with(|last| last.borrow().clone()) else {
+        error!("There is no debug session to repeat yet");
+        return;
+    };
+    debug_session(session).await;
+}
+
+/// Builds a run target with `process`, which has to emit JSON messages, and debugs the
+/// resulting executable with the program arguments `args` and the variables `env`.
+pub async fn build_and_debug(
+    process: Process,
+    target: RunSubTarget,
+    args: Vec<String>,
+    env: BTreeMap<String, String>,
+    remote: Option<RemoteTarget>,
+) {
+    let name = target.name().to_string();
+    let cwd = process.cwd().map(str::to_string);
+
+    debug_session(Rc::new(DebugSession {
+        build: process,
+        target_name: name.clone(),
+        executable: Box::new(move |messages| target.executable_from_messages(messages)),
+        launch: Launch {
+            name: format!("Debug {name}"),
+            program: String::new(),
+            args,
+            cwd,
+            env,
+            follow_child_processes: environment::follow_child_processes(&name),
+        },
+        remote,
+        overrides: environment::debug_overrides(&name),
+    }))
+    .await;
+}
+
+/// Builds the test executable of `target` with `process`, which has to emit JSON messages,
+/// and debugs only the tests named `names` in it
+pub async fn build_and_debug_tests(
+    process: Process,
+    target: TestTarget,
+    package_dir: String,
+    label: String,
+    names: Vec<String>,
+    remote: Option<RemoteTarget>,
+) {
+    let mut args = names;
+    args.extend(["--exact".to_string(), "--nocapture".to_string()]);
+
+    // cargo runs tests in the package directory, relative paths in tests depend on it
+    debug_session(Rc::new(DebugSession {
+        build: process,
+        target_name: label.clone(),
+        executable: Box::new(move |messages| target.executable_from_messages(messages)),
+        launch: Launch {
+            name: format!("Debug {label}"),
+            program: String::new(),
+            args,
+            cwd: Some(package_dir),
+            env: BTreeMap::new(),
+            follow_child_processes: false,
+        },
+        remote,
+        overrides: DebugOverrides::default(),
+    }))
+    .await;
+}
+
+/// Compiles the documentation test `test` with `process`, which persists the doctest
+/// executables in `persist_dir`, and debugs it in the package directory `cwd`.
+pub async fn build_and_debug_doc_test(
+    process: Process,
+    test: DocTest,
+    persist_dir: String,
+    cwd: String,
+) {
+    let name = test.name();
+    // rustdoc doesn't report the executable, it is found in the persist directory
+    let program = PathBuf::from_iter([
+        persist_dir.as_str(),
+        &test.persisted_dir(),
+        &format!("rust_out{}", exe_suffix()),
+    ])
+    .to_string_lossy()
+    .to_string();
+
+    debug_session(Rc::new(DebugSession {
+        build: process,
+        target_name: name.clone(),
+        executable: Box::new(move |_| Some(program.clone())),
+        launch: Launch {
+            name: format!("Debug {name}"),
+            program: String::new(),
+            args: Vec::new(),
+            cwd: Some(cwd),
+            env: BTreeMap::new(),
+            follow_child_processes: false,
+        },
+        // Doctests are always built for the host
+        remote: None,
+        overrides: DebugOverrides::default(),
+    }))
+    .await;
+}
+
+/// Asks for an optional benchmark filter, builds the benchmark with `process`, which has
+/// to emit JSON messages, and debugs it in the package directory `cwd`.
+pub async fn build_and_debug_bench(
+    process: Process,
+    bench: BenchTarget,
+    cwd: String,
+    remote: Option<RemoteTarget>,
+) {
+    let Some(name) = bench.target.clone() else {
+        error!("Select a benchmark target to debug");
+        return;
+    };
+
+    let placeholder = "Benchmark filter, leave empty to run all benchmarks".to_string();
+    let Ok(filter) = show_input_box(placeholder, format!("Debug {name}")).await else {
+        return;
+    };
+    // The input box was dismissed
+    let Some(filter) = filter.as_string() else {
+        return;
+    };
+
+    let args = std::iter::once("--bench".to_string())
+        .chain((!filter.trim().is_empty()).then(|| filter.trim().to_string()))
+        .collect();
+    debug_session(Rc::new(DebugSession {
+        build: process,
+        target_name: format!("benchmark {name}"),
+        executable: Box::new(move |messages| bench.executable_from_messages(messages)),
+        launch: Launch {
+            name: format!("Debug {name}"),
+            program: String::new(),
+            args,
+            cwd: Some(cwd),
+            env: BTreeMap::new(),
+            follow_child_processes: false,
+        },
+        remote,
+        overrides: DebugOverrides::default(),
+    }))
+    .await;
+}
+
+/// Builds a run target with `process`, which has to emit JSON messages, and runs `cmd` of
+/// probe-rs with the resulting ELF in a terminal which streams the RTT output
+pub async fn build_and_flash(
+    process: Process,
+    target: RunSubTarget,
+    cmd: ProbeRsCommand,
+    chip: Option<String>,
+) {
+    let cwd = process.cwd().map(str::to_string);
+    let version = Process::new(
+        PROBE_RS.to_string(),
+        vec!["--version".to_string()],
+        HashMap::new(),
+    );
+    if let Err(e) = exec_vs_code(version).await {
+        error!("Failed to run probe-rs: {e}");
+        let message = "probe-rs is not installed, see https://probe.rs for how to install it";
+        show_warning_message(message, Vec::new()).await;
+        return;
+    }
+
+    let name = target.name().to_string();
+    let Some(messages) = build_with_progress(process, &name, "flashing").await else {
+        return;
+    };
+    let Some(elf) = target.executable_from_messages(&messages) else {
+        error!("Failed to find the executable of {name} in the build output");
+        return;
+    };
+    let elf = environment::path_mappings().to_host(&elf);
+
+    let probe_rs =
+        Process::new(PROBE_RS.to_string(), cmd.args(elf, chip), HashMap::new()).with_cwd(cwd);
+    execute_task(VsCodeTask::probe_rs(probe_rs)).await
+}
+
+/// Runs the build of a debug session and returns its JSON messages
+async fn build_for_debugging(process: Process, name: &str) -> Option<String> {
+    build_with_progress(process, name, "debugging").await
+}
+
+/// Builds `target` with `process` for `purpose` and returns the path of its executable from the
+/// `compiler-artifact` messages, which is exact for a custom target directory or profile too
+pub async fn build_executable(
+    process: Process,
+    target: &RunSubTarget,
+    purpose: &str,
+) -> Option<String> {
+    let name = target.name();
+    let messages = build_with_progress(process, name, purpose).await?;
+    let executable = target.executable_from_messages(&messages);
+    if executable.is_none() {
+        error!("Failed to find the executable of {name} in the build output");
+    }
+    executable.map(|executable| environment::path_mappings().to_host(&executable))
+}
+
+/// Builds `name` with `process` for `purpose`, e.g. debugging, and returns its JSON messages
+async fn build_with_progress(process: Process, name: &str, purpose: &str) -> Option<String> {
+    match execute_with_progress(
+        VsCodeProcess(process),
+        &format!("Building {name} for {purpose}"),
+    )
+    .await
+    {
+        Ok(messages) => Some(messages.as_string().expect("JsString conversion failed")),
+        Err(e) => {
+            error!("Failed to build {name}: {}", e.to_error_string());
+            None
+        }
+    }
+}
+
+/// Starts a debug session for `launch` with the configured or detected debugger. With a
+/// `remote` target the program runs under its debug server, which the debugger connects to.
+/// The `overrides` of the target are merged into the generated configuration.
+pub async fn start_debugging(
+    launch: Launch,
+    remote: Option<RemoteTarget>,
+    overrides: &DebugOverrides,
+) {
+    let launch = overrides.apply(launch);
+    let platform = host_platform();
+    let debugger = Debugger::select(&environment::debugger(), &platform, extension_installed);
+    let Some(debugger) = debugger else {
+        debug_without_adapter(launch, remote.is_none(), &platform).await;
+        return;
+    };
+
+    let name = launch.name.clone();
+    let host = debug_host().await;
+    if launch.follow_child_processes && !debugger.follows_child_processes(&host.platform) {
+        error!(
+            "{} can't follow child processes on {}, debugging only the started process",
+            debugger.setting_name(),
+            host.platform
+        );
+    }
+    let Some(remote) = remote else {
+        let config = debugger.launch_configuration(launch, &host);
+        start_debug_session(&config, &name, overrides).await;
+        return;
+    };
+
+    let Some(config) = debugger.remote_configuration(launch.clone(), &remote, &host) else {
+        error!(
+            "{} can't connect to a debug server, select another cargoTools.debugger",
+            debugger.setting_name()
+        );
+        return;
+    };
+    if let Some(mut command) = remote.runner_command(&launch.program, &launch.args) {
+        let cmd = command.remove(0);
+        let runner = Process::new(cmd, command, HashMap::new()).with_cwd(launch.cwd);
+        execute_task(VsCodeTask::cargo(runner)).await;
+        // The debugger can only connect once the server listens
+        delay(DEBUG_SERVER_STARTUP_MS).await;
+    }
+    start_debug_session(&config, &name, overrides).await;
+}
+
+/// Time a debug server started by a runner gets before the debugger connects
+const DEBUG_SERVER_STARTUP_MS: u32 = 500;
+
+/// Lets the user pick a running process of one of the `executables` and attaches the
+/// configured or detected debugger to it.
+pub async fn attach_to_process(executables: Vec<String>) {
+    let processes = match running_processes().await {
+        Ok(processes) => processes,
+        Err(e) => {
+            error!("Failed to list running processes: {e}");
+            return;
+        }
+    };
+
+    let options: Vec<RunningProcess> = processes
+        .into_iter()
+        .filter(|process| executables.iter().any(|exe| process.runs(exe)))
+        .collect();
+    if options.is_empty() {
+        error!("No running process of a workspace binary found");
+        return;
+    }
+
+    let input = SelectInput {
+        options,
+        current: Vec::new(),
+    };
+    let Some(process) = input.select().await else {
+        return;
+    };
+    let Some(program) = executables.into_iter().find(|exe| process.runs(exe)) else {
+        return;
+    };
+
+    let Some(debugger) = select_debugger(&host_platform()) else {
+        return;
+    };
+
+    let executable_name = program.rsplit(['/', '\\']).next().unwrap_or(&program);
+    let name = format!("Attach to {executable_name} ({})", process.pid);
+    let attach = Attach {
+        name: name.clone(),
+        pid: process.pid,
+        program,
+    };
+    let config = debugger.attach_configuration(attach, &debug_host().await);
+    start_debug_session(&config, &name, &DebugOverrides::default()).await;
+}
+
+/// Asks for a core file or minidump of the run target `target` in `dump_dir` and inspects it
+/// with the last built executable `program` of the target.
+pub async fn debug_core_dump(target: String, program: String, dump_dir: String) {
+    if !file_exists_vs_code(program.clone()).await {
+        error!("The executable {program} of {target} does not exist, build it first");
+        return;
+    }
+
+    let name = format!("Core dump of {target}");
+    let core = match show_open_file_dialog(name.clone(), dump_dir).await {
+        Ok(core) => core.as_string(),
+        Err(e) => {
+            error!("Failed to select a core dump: {}", e.to_error_string());
+            return;
+        }
+    };
+    // The dialog was dismissed
+    let Some(core) = core else {
+        return;
+    };
+    let Some(debugger) = select_debugger(&host_platform()) else {
+        return;
+    };
+
+    let core_dump = CoreDump {
+        name: name.clone(),
+        program,
+        core,
+    };
+    let config = debugger.core_dump_configuration(core_dump, &debug_host().await);
+    start_debug_session(&config, &name, &environment::debug_overrides(&target)).await;
+}
+
+async fn running_processes() -> Result<Vec<RunningProcess>, String> {
+    let windows = host_platform() == "win32";
+    let (cmd, args) = if windows {
+        ("tasklist", ["/FO", "CSV", "/NH"].as_slice())
+    } else {
+        ("ps", ["-A", "-o", "pid=", "-o", "args="].as_slice())
+    };
+    let args = args.iter().map(ToString::to_string).collect();
+
+    let output = exec_vs_code(Process::new(cmd.to_string(), args, HashMap::new())).await?;
+    Ok(if windows {
+        RunningProcess::parse_tasklist(&output)
+    } else {
+        RunningProcess::parse_ps(&output)
+    })
+}
+
+/// The host with the sysroot of the workspace's toolchain, whose pretty printers are loaded
+async fn debug_host() -> Host {
+    let sysroot = match environment::rustc_task_context()
+        .try_into_process(vec!["--print".to_string(), "sysroot".to_string()])
+    {
+        Ok(process) => exec_vs_code(process).await,
+        Err(e) => Err(e.to_string()),
+    };
+    let sysroot = match sysroot {
+        Ok(sysroot) => Some(sysroot.trim().to_string()),
+        Err(e) => {
+            error!("Failed to get the rust sysroot, debugging without pretty printers: {e}");
+            None
+        }
+    };
+
+    Host {
+        platform: host_platform(),
+        sysroot,
+    }
+}
+
+/// The target triple of the workspace's toolchain on this host
+pub async fn host_triple() -> Option<String> {
+    let version = match environment::rustc_task_context().try_into_process(vec!["-vV".to_string()])
+    {
+        Ok(process) => exec_vs_code(process).await,
+        Err(e) => Err(e.to_string()),
+    };
+    match version {
+        Ok(version) => sanitizer::host_triple(&version),
+        Err(e) => {
+            error!("Failed to get the host target triple: {e}");
+            None
+        }
+    }
+}
+
+/// The host triple builds for other platform targets use `cross` for, `None` with a warning
+/// if `cross` isn't installed
+pub async fn detect_cross() -> Option<String> {
+    let process = Process::new(
+        CROSS_COMMAND.to_string(),
+        vec!["--version".to_string()],
+        HashMap::new(),
+    );
+    if let Err(e) = exec_vs_code(process).await {
+        error!("Failed to run cross: {e}");
+        let message = "cross is not installed, other platform targets are built with cargo. Install it with 'cargo install cross'";
+        show_warning_message(message, Vec::new()).await;
+        return None;
+    }
+    host_triple().await
+}
+
+/// The default WSL distribution of `wsl -l`, [None] with an error if it can't be listed
+pub async fn default_wsl_distro() -> Option<String> {
+    let process = Process::new("wsl".to_string(), vec!["-l".to_string()], HashMap::new());
+    match exec_vs_code(process).await {
+        Ok(output) => parse_default_wsl_distro(&output),
+        Err(e) => {
+            error!("Failed to list the WSL distributions: {e}");
+            None
+        }
+    }
+}
+
+const INSTALL_CODELLDB: &str = "Install CodeLLDB";
+
+/// Offers to install CodeLLDB when no debug adapter is installed. A `local` launch can also
+/// be debugged with the toolchain's `rust-gdb` or `rust-lldb` in the terminal instead.
+async fn debug_without_adapter(launch: Launch, local: bool, platform: &str) {
+    let terminal_debuggers = if local {
+        TerminalDebugger::available(platform)
+    } else {
+        Vec::new()
+    };
+    let actions = std::iter::once(INSTALL_CODELLDB.to_string())
+        .chain(
+            terminal_debuggers
+                .iter()
+                .map(|d| format!("Debug in Terminal with {}", d.name())),
+        )
+        .collect();
+    let message = "No debug adapter found, install CodeLLDB, lldb-dap or the C/C++ extension or set cargoTools.debugger";
+    // The message was dismissed
+    let Some(action) = show_warning_message(message, actions).await.as_string() else {
+        return;
+    };
+
+    if action == INSTALL_CODELLDB {
+        let id = JsValue::from_str(Debugger::CodeLldb.extension_id());
+        let install = executeCommand("workbench.extensions.installExtension", Array::of1(&id));
+        if let Err(e) = install.await {
+            error!("Failed to install CodeLLDB: {}", e.to_error_string());
+        }
+        return;
+    }
+
+    let Some(debugger) = terminal_debuggers
+        .into_iter()
+        .find(|d| action.ends_with(d.name()))
+    else {
+        return;
+    };
+    let args = debugger.args(&launch.program, &launch.args);
+    let env = launch.env.into_iter().collect();
+    let process = Process::new(debugger.name().to_string(), args, env).with_cwd(launch.cwd);
+    execute_in_terminal(VsCodeTask::cargo(process)).await;
+}
+
+fn select_debugger(platform: &str) -> Option<Debugger> {
+    let debugger = Debugger::select(&environment::debugger(), platform, extension_installed);
+    if debugger.is_none() {
+        error!(
+            "No debug adapter found, install CodeLLDB, lldb-dap or the C/C++ extension or set cargoTools.debugger"
+        );
+    }
+    debugger
+}
+
+async fn start_debug_session(config: &impl Serialize, name: &str, overrides: &DebugOverrides) {
+    let mut config = match serde_json::to_value(config) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Failed to serialize debug configuration: {e}");
+            return;
+        }
+    };
+    overrides.merge_into(&mut config);
+    // Maps like `env` or `sourceMap` have to become plain objects for VS Code
+    let config = match config.serialize(&Serializer::json_compatible()) {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Failed to serialize debug configuration: {e}");
+            return;
+        }
+    };
+
+    if let Err(e) = debug(config, name).await {
+        error!("Error while debugging: {}", e.to_error_string());
+    }
+}
+
+/// How cargo commands are executed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// As VS Code task with problem matcher
+    Task,
+    /// In a reusable terminal
+    Terminal,
+}
+
+/// Executes `task` in the way given by `mode`
+pub async fn execute(task: VsCodeTask, mode: ExecutionMode) {
+    match mode {
+        ExecutionMode::Task => execute_task(task).await,
+        ExecutionMode::Terminal => execute_in_terminal(task).await,
+    }
+}
+
+/// Executes `task` and waits for it to finish. Returns whether it succeeded.
+pub async fn execute_task_and_wait(task: VsCodeTask) -> bool {
+    execute_task_and_wait_ts(task, false)
+        .await
+        .as_bool()
+        .unwrap_or(false)
+}
+
+/// Like [execute_task_and_wait] but without revealing the task's terminal
+pub async fn execute_silent_task_and_wait(task: VsCodeTask) -> bool {
+    execute_task_and_wait_ts(task, true)
+        .await
+        .as_bool()
+        .unwrap_or(false)
+}
+
 trait ProcessExt {
     fn js_env(&self) -> Map;
 }
@@ -205,6 +1189,11 @@ impl VsCodeProcess {
     pub fn env(&self) -> Map {
         self.0.js_env()
     }
+
+    #[wasm_bindgen]
+    pub fn cwd(&self) -> Option<String> {
+        self.0.cwd().map(ToString::to_string)
+    }
 }
 
 /// Gives the context in which a [Task] is run
@@ -212,7 +1201,9 @@ enum CargoTask {
     Cargo(Process),
     CargoMake(Process),
     RustUp(Process),
+    ProbeRs(Process),
     XtaskAlias(Process),
+    Just(Process),
 }
 
 /// Task type which is exported in typescript code
@@ -232,16 +1223,26 @@ impl VsCodeTask {
         Self(CargoTask::RustUp(process))
     }
 
+    pub fn probe_rs(process: Process) -> Self {
+        Self(CargoTask::ProbeRs(process))
+    }
+
     pub fn xtask_alias(process: Process) -> Self {
         Self(CargoTask::XtaskAlias(process))
     }
 
+    pub fn just(process: Process) -> Self {
+        Self(CargoTask::Just(process))
+    }
+
     fn process(&self) -> &Process {
         match &self.0 {
             CargoTask::Cargo(process) => process,
             CargoTask::CargoMake(process) => process,
             CargoTask::RustUp(process) => process,
+            CargoTask::ProbeRs(process) => process,
             CargoTask::XtaskAlias(process) => process,
+            CargoTask::Just(process) => process,
         }
     }
 }
@@ -253,8 +1254,9 @@ impl VsCodeTask {
         match self.0 {
             CargoTask::Cargo(_) => "cargo-tools-cargo".to_string(),
             CargoTask::CargoMake(_) => "cargo-tools-cargo-make".to_string(),
-            CargoTask::RustUp(_) => "cargo-tools-cargo".to_string(),
+            CargoTask::RustUp(_) | CargoTask::ProbeRs(_) => "cargo-tools-cargo".to_string(),
             CargoTask::XtaskAlias(_) => "cargo-tools-xtask".to_string(),
+            CargoTask::Just(_) => "cargo-tools-just".to_string(),
         }
     }
 
@@ -272,4 +1274,57 @@ impl VsCodeTask {
     pub fn env(&self) -> Map {
         self.process().js_env()
     }
+
+    #[wasm_bindgen]
+    pub fn cwd(&self) -> Option<String> {
+        self.process().cwd().map(ToString::to_string)
+    }
+
+    /// The arguments of a cargo task which compiles with its compiler messages as JSON, the
+    /// task shows their rendering and the extension reads them
+    #[wasm_bindgen]
+    pub fn json_message_args(&self) -> Option<Vec<String>> {
+        match &self.0 {
+            CargoTask::Cargo(process) => with_rendered_messages(process.args()),
+            _ => None,
+        }
+    }
+}
+
+/// The output of a task with JSON messages for its terminal, which shows the first
+/// `cargoTools.output.maxLines` lines as they arrive and the last ones once the task finished
+#[wasm_bindgen]
+pub struct TaskOutput(StreamedOutput);
+
+impl Default for TaskOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[wasm_bindgen]
+impl TaskOutput {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self(StreamedOutput::new(environment::output_max_lines()))
+    }
+
+    /// Appends `chunk` and returns the part of it to show right away
+    #[wasm_bindgen]
+    pub fn push(&mut self, chunk: &str) -> String {
+        self.0.push(chunk)
+    }
+
+    /// Whether output is held back until the task finished
+    #[wasm_bindgen]
+    pub fn shortened(&self) -> bool {
+        self.0.shortened()
+    }
+
+    /// The held back output, which refers to `log_file` with the full output if lines were
+    /// dropped
+    #[wasm_bindgen]
+    pub fn finish(&self, log_file: &str) -> String {
+        self.0.finish(log_file)
+    }
 }
@@ -1,12 +1,16 @@
 import * as vscode from 'vscode';
-import { spawn } from 'child_process';
-import { VsCodeTask, VsCodeProcess } from '../../../vscode_extension/src/wasm/cargo_tools_vscode';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { ChildProcess, spawn } from 'child_process';
+import { TaskOutput, VsCodeTask, VsCodeProcess } from '../../../vscode_extension/src/wasm/cargo_tools_vscode';
 import { extension_context } from '../../../vscode_extension/src/extension';
 
 export class FileWatcher {
     private watcher?: vscode.Disposable;
     private onChanged?: (() => void);
     private changeTimer?: ReturnType<typeof setTimeout>;
+    private debounceMs = 100;
 
     private scheduleChanged(): void {
         if (this.changeTimer) {
@@ -15,42 +19,76 @@ export class FileWatcher {
         this.changeTimer = setTimeout(() => {
             this.changeTimer = undefined;
             this.onChanged?.();
-        }, 100);
+        }, this.debounceMs);
+    }
+
+    /** Changes within `ms` of each other notify once `ms` after the last one */
+    set_debounce(ms: number): void {
+        this.debounceMs = ms;
     }
 
     on_changed(callback: () => void): void {
         this.onChanged = callback;
     }
 
+    /** Watches the files at the literal absolute `paths` with a watcher per directory, which
+     * is rooted at the directory so that files outside of the workspace folders like those of a
+     * custom target directory are watched too. Globs are watched with `watch_patterns`. */
     watch_files(paths: string[]): void {
-        // Create a single watcher with a pattern that matches all paths.
-        // We only need brace expansion when there's more than one alternative,
-        // so a lone path must be used as-is instead of being wrapped in `{}`.
-        const relativePaths = paths.map((path) => vscode.workspace.asRelativePath(path));
-        const globPattern = relativePaths.length === 1
-            ? relativePaths[0]
-            : `{${relativePaths.join(',')}}`;
-        const pattern = new vscode.RelativePattern(
-            vscode.workspace.workspaceFolders?.[0]?.uri ?? '',
-            globPattern
-        );
+        const names = new Map<string, string[]>();
+        for (const file of paths) {
+            const dir = path.dirname(file);
+            // Glob characters in file names match literally in a character class
+            const name = path.basename(file).replace(/[[\]{}*?,]/g, '[$&]');
+            names.set(dir, [...(names.get(dir) ?? []), name]);
+        }
+        const onEvent = () => this.scheduleChanged();
+        const disposables = [...names].flatMap(([dir, files]) => {
+            // Brace expansion is only needed for more than one alternative
+            const glob = files.length === 1 ? files[0] : `{${files.join(',')}}`;
+            const watcher = vscode.workspace.createFileSystemWatcher(
+                new vscode.RelativePattern(vscode.Uri.file(dir), glob)
+            );
+            return [
+                watcher.onDidChange(onEvent),
+                watcher.onDidCreate(onEvent),
+                watcher.onDidDelete(onEvent),
+                watcher,
+            ];
+        });
 
-        const watcher = vscode.workspace.createFileSystemWatcher(pattern);
-        const changeDisposable = watcher.onDidChange(() => this.scheduleChanged());
-        const createDisposable = watcher.onDidCreate(() => this.scheduleChanged());
-        const deleteDisposable = watcher.onDidDelete(() => this.scheduleChanged());
+        this.watcher?.dispose();
+        this.watcher = { dispose: () => disposables.forEach((d) => d.dispose()) };
+    }
 
-        const compositeDisposable = {
-            dispose: () => {
-                changeDisposable.dispose();
-                createDisposable.dispose();
-                deleteDisposable.dispose();
-                watcher.dispose();
+    /** Watches the files matching the `patterns` below `base` recursively, changes of files in
+     * the `ignored` directories like the target directory don't notify */
+    watch_patterns(base: string, patterns: string[], ignored: string[]): void {
+        const normalize = (file: string) => file.split('\\').join('/');
+        const dirs = ignored.map(normalize);
+        const isIgnored = (uri: vscode.Uri) => {
+            const file = normalize(uri.fsPath);
+            return dirs.some((dir) => file.startsWith(`${dir}/`));
+        };
+        const onEvent = (uri: vscode.Uri) => {
+            if (!isIgnored(uri)) {
+                this.scheduleChanged();
             }
         };
+        const disposables = patterns.flatMap((pattern) => {
+            const watcher = vscode.workspace.createFileSystemWatcher(
+                new vscode.RelativePattern(base, pattern)
+            );
+            return [
+                watcher.onDidChange(onEvent),
+                watcher.onDidCreate(onEvent),
+                watcher.onDidDelete(onEvent),
+                watcher,
+            ];
+        });
 
         this.watcher?.dispose();
-        this.watcher = compositeDisposable;
+        this.watcher = { dispose: () => disposables.forEach((d) => d.dispose()) };
     }
 
     dispose(): void {
@@ -63,32 +101,118 @@ export class FileWatcher {
     }
 }
 
+export class SaveWatcher {
+    private listener: vscode.Disposable;
+    private onSaved?: (() => void);
+    private saveTimer?: ReturnType<typeof setTimeout>;
+
+    constructor() {
+        this.listener = vscode.workspace.onDidSaveTextDocument((document) => {
+            const file = document.uri.fsPath;
+            if (file.endsWith('.rs') || file.endsWith('Cargo.toml')) {
+                this.scheduleSaved();
+            }
+        });
+    }
+
+    private scheduleSaved(): void {
+        // Coalesce rapid saves e.g. from "Save All"
+        if (this.saveTimer) {
+            clearTimeout(this.saveTimer);
+        }
+        this.saveTimer = setTimeout(() => {
+            this.saveTimer = undefined;
+            this.onSaved?.();
+        }, 300);
+    }
+
+    on_saved(callback: () => void): void {
+        this.onSaved = callback;
+    }
+
+    dispose(): void {
+        if (this.saveTimer) {
+            clearTimeout(this.saveTimer);
+            this.saveTimer = undefined;
+        }
+        this.listener.dispose();
+    }
+}
+
+/** Notifies about the JSON messages of finished tasks which compiled, see `onDidEndJsonTask` */
+export class TaskMessagesWatcher {
+    private listener: vscode.Disposable;
+    private onMessages?: ((messages: string) => void);
+
+    constructor() {
+        this.listener = onDidEndJsonTask((end) => this.onMessages?.(end.messages));
+    }
+
+    on_messages(callback: (messages: string) => void): void {
+        this.onMessages = callback;
+    }
+
+    dispose(): void {
+        this.listener.dispose();
+    }
+}
+
+export class SettingsWatcher {
+    private listener: vscode.Disposable;
+    private onChanged?: (() => void);
+
+    constructor(keys: string[]) {
+        this.listener = vscode.workspace.onDidChangeConfiguration((e) => {
+            if (keys.some((key) => e.affectsConfiguration(key))) {
+                this.onChanged?.();
+            }
+        });
+    }
+
+    on_changed(callback: () => void): void {
+        this.onChanged = callback;
+    }
+
+    dispose(): void {
+        this.listener.dispose();
+    }
+}
+
 export async function read_file(file_path: string): Promise<string> {
     const uri = vscode.Uri.file(file_path);
     const fileContent = await vscode.workspace.fs.readFile(uri);
     return new TextDecoder().decode(fileContent);
 }
 
+export async function write_file(file_path: string, contents: string): Promise<void> {
+    const uri = vscode.Uri.file(file_path);
+    await vscode.workspace.fs.createDirectory(vscode.Uri.joinPath(uri, '..'));
+    await vscode.workspace.fs.writeFile(uri, new TextEncoder().encode(contents));
+}
+
+/** Deletes the file `file_path` if it exists */
+export async function delete_file(file_path: string): Promise<void> {
+    try {
+        await vscode.workspace.fs.delete(vscode.Uri.file(file_path));
+    } catch (e) {
+        if (!(e instanceof vscode.FileSystemError && e.code === 'FileNotFound')) {
+            throw e;
+        }
+    }
+}
+
 export async function file_exists(file_path: string): Promise<void> {
     const uri = vscode.Uri.file(file_path);
     await vscode.workspace.fs.stat(uri);
 }
 
-export async function debug(target_exe_path: string, target_name: string): Promise<void> {
-    // Create debug configuration
-    const debugConfig: vscode.DebugConfiguration = {
-        name: `Debug ${target_name}`,
-        type: 'lldb', // Default to LLDB, could be configurable
-        request: 'launch',
-        program: target_exe_path,
-        args: [], // Could be made configurable
-        stopOnEntry: false,
-        showDisplayString: true,
-        sourceLanguages: ['rust']
-    };
+export async function file_stat(file_path: string): Promise<{ size: number, mtime: number }> {
+    const stat = await vscode.workspace.fs.stat(vscode.Uri.file(file_path));
+    return { size: stat.size, mtime: stat.mtime };
+}
 
-    // Start the debug session
-    const started = await vscode.debug.startDebugging(undefined, debugConfig);
+export async function debug(debugConfig: vscode.DebugConfiguration, target_name: string): Promise<void> {
+    const started = await vscode.debug.startDebugging(vscode.workspace.workspaceFolders?.[0], debugConfig);
 
     if (started) {
         vscode.window.showInformationMessage(`Started debugging ${target_name}...`);
@@ -97,10 +221,49 @@ export async function debug(target_exe_path: string, target_name: string): Promi
     }
 }
 
+export function delay(ms: number): Promise<void> {
+    return new Promise(resolve => setTimeout(resolve, ms));
+}
+
+export function extension_installed(id: string): boolean {
+    return vscode.extensions.getExtension(id) !== undefined;
+}
+
+export async function show_warning_message(message: string, actions: string[]): Promise<string | null> {
+    return (await vscode.window.showWarningMessage(message, ...actions)) ?? null;
+}
+
+export function open_url(url: string): void {
+    vscode.env.openExternal(vscode.Uri.parse(url));
+}
+
+export async function open_file_at(path: string, line: number): Promise<void> {
+    const position = new vscode.Position(line, 0);
+    await vscode.window.showTextDocument(vscode.Uri.file(path), {
+        selection: new vscode.Range(position, position),
+    });
+}
+
+export async function show_markdown(markdown: string): Promise<void> {
+    const document = await vscode.workspace.openTextDocument({ content: markdown, language: 'markdown' });
+    await vscode.commands.executeCommand('markdown.showPreview', document.uri);
+}
+
 export function host_platform(): string {
     return process.platform;
 }
 
+export function host_env(keys: string[]): { [key: string]: string } {
+    const env: { [key: string]: string } = {};
+    for (const key of keys) {
+        const value = process.env[key];
+        if (value !== undefined) {
+            env[key] = value;
+        }
+    }
+    return env;
+}
+
 export function get_state(key: string): string | undefined {
     return extension_context?.workspaceState.get(key);
 }
@@ -109,7 +272,11 @@ export async function set_state(key: string, value: string): Promise<void> {
     await extension_context?.workspaceState.update(key, value);
 }
 
-function spawnWithOutput(cargo_tools_process: VsCodeProcess): Promise<{ stdout: string; stderr: string }> {
+function spawnWithOutput(
+    cargo_tools_process: VsCodeProcess,
+    onSpawn?: (child: ChildProcess) => void,
+    allowFailure = false,
+): Promise<{ stdout: string; stderr: string }> {
     const cmd = cargo_tools_process.cmd();
     const args = cargo_tools_process.args();
     const env: { [key: string]: string } = Object.fromEntries(cargo_tools_process.env());
@@ -120,7 +287,9 @@ function spawnWithOutput(cargo_tools_process: VsCodeProcess): Promise<{ stdout:
     }
 
     return new Promise((resolve, reject) => {
-        const child = spawn(cmd, args, { cwd: workspaceFolder.uri.fsPath, env: { ...process.env, ...env } });
+        const cwd = cargo_tools_process.cwd() ?? workspaceFolder.uri.fsPath;
+        const child = spawn(cmd, args, { cwd, env: { ...process.env, ...env } });
+        onSpawn?.(child);
 
         let stdout = "";
         let stderr = "";
@@ -133,7 +302,7 @@ function spawnWithOutput(cargo_tools_process: VsCodeProcess): Promise<{ stdout:
 
         child.on("error", reject);
         child.on("close", code => {
-            code === 0
+            code === 0 || allowFailure
                 ? resolve({ stdout, stderr })
                 : reject(new Error(stderr || `exit ${code}`));
         });
@@ -145,30 +314,372 @@ export async function execute_async(cargo_tools_process: VsCodeProcess): Promise
     return stdout;
 }
 
+/**
+ * Resolves with the stdout of the process even if it fails, e.g. for the JSON messages of a
+ * `cargo check` which finds errors, without the progress cargo prints on stderr
+ */
+export async function execute_stdout(cargo_tools_process: VsCodeProcess): Promise<string> {
+    const { stdout } = await spawnWithOutput(cargo_tools_process, undefined, true);
+    return stdout;
+}
+
+// Quick runs finish before the progress notification shows up
+const CANCELLABLE_PROGRESS_DELAY_MS = 1000;
+
+/**
+ * Executes `cargo_tools_process` like `execute_async`. A cancellable progress notification
+ * titled `title` shows up once it runs longer than a second, canceling kills the process and
+ * resolves with undefined.
+ */
+export async function execute_cancellable(cargo_tools_process: VsCodeProcess, title: string): Promise<string | undefined> {
+    let child: ChildProcess | undefined;
+    let canceled = false;
+    const run = spawnWithOutput(cargo_tools_process, (spawned) => (child = spawned));
+    const timer = setTimeout(() => {
+        vscode.window.withProgress(
+            { location: vscode.ProgressLocation.Notification, title, cancellable: true },
+            (_progress, token) => {
+                token.onCancellationRequested(() => {
+                    canceled = true;
+                    child?.kill('SIGTERM');
+                });
+                return run.catch(() => undefined);
+            }
+        );
+    }, CANCELLABLE_PROGRESS_DELAY_MS);
+    try {
+        const { stdout } = await run;
+        return stdout;
+    } catch (e) {
+        if (canceled) {
+            return undefined;
+        }
+        throw e;
+    } finally {
+        clearTimeout(timer);
+    }
+}
+
+/** The processes started by `execute_with_output_timeout` by their group */
+const outputProcesses = new Map<string, Set<ChildProcess>>();
+/** The groups whose processes were killed, later processes of them aren't started anymore */
+const killedGroups = new Set<string>();
+
+/**
+ * Kills the processes `execute_with_output_timeout` started for `group` together with their
+ * children, e.g. the test executables of `cargo test` of a test run. Their promises resolve
+ * with the output so far.
+ */
+export function kill_output_processes(group: string): void {
+    killedGroups.add(group);
+    outputProcesses.get(group)?.forEach(killTree);
+    outputProcesses.delete(group);
+}
+
+export function killTree(child: ChildProcess): void {
+    if (child.pid === undefined) {
+        return;
+    }
+    if (process.platform === 'win32') {
+        spawn('taskkill', ['/pid', child.pid.toString(), '/T', '/F']);
+    } else {
+        // The negative pid addresses the process group the child leads
+        try {
+            process.kill(-child.pid, 'SIGTERM');
+        } catch {
+            child.kill('SIGTERM');
+        }
+    }
+}
+
+/**
+ * Resolves with the interleaved stdout and stderr even if the process fails, e.g. for
+ * `cargo test` whose results are printed on stdout and test executables on stderr
+ */
+export async function execute_with_output(cargo_tools_process: VsCodeProcess): Promise<string> {
+    return (await execute_with_output_timeout(cargo_tools_process, 0)).output;
+}
+
+/**
+ * Like `execute_with_output`, but kills the process with its children after `timeoutMs` unless
+ * it is 0. The process is killed with the other ones of its `group` by `kill_output_processes`.
+ */
+export function execute_with_output_timeout(cargo_tools_process: VsCodeProcess, timeoutMs: number,
+    group?: string): Promise<{ output: string; timed_out: boolean }> {
+    const workspaceFolder = vscode.workspace.workspaceFolders?.[0];
+    if (!workspaceFolder) {
+        return Promise.reject(new Error('No workspace folder found'));
+    }
+    const env: { [key: string]: string } = Object.fromEntries(cargo_tools_process.env());
+    const cwd = cargo_tools_process.cwd() ?? workspaceFolder.uri.fsPath;
+
+    if (group !== undefined && killedGroups.has(group)) {
+        return Promise.resolve({ output: '', timed_out: false });
+    }
+
+    return new Promise((resolve, reject) => {
+        // A process group of its own lets `kill_output_processes` stop the children as well
+        const child = spawn(cargo_tools_process.cmd(), cargo_tools_process.args(), {
+            cwd,
+            env: { ...process.env, ...env },
+            detached: process.platform !== 'win32',
+        });
+        const processes = group === undefined ? undefined : outputProcesses.get(group) ?? new Set<ChildProcess>();
+        processes?.add(child);
+        if (group !== undefined && processes) {
+            outputProcesses.set(group, processes);
+        }
+        const untrack = () => {
+            processes?.delete(child);
+            if (group !== undefined && processes?.size === 0) {
+                outputProcesses.delete(group);
+            }
+        };
+
+        let output = "";
+        let timedOut = false;
+        child.stdout.setEncoding("utf8");
+        child.stderr.setEncoding("utf8");
+        child.stdout.on("data", d => (output += d));
+        child.stderr.on("data", d => (output += d));
+
+        const timer = timeoutMs > 0
+            ? setTimeout(() => {
+                timedOut = true;
+                killTree(child);
+            }, timeoutMs)
+            : undefined;
+        child.on("error", (e) => {
+            clearTimeout(timer);
+            untrack();
+            reject(e);
+        });
+        child.on("close", () => {
+            clearTimeout(timer);
+            untrack();
+            resolve({ output, timed_out: timedOut });
+        });
+    });
+}
+
+export async function execute_with_progress(cargo_tools_process: VsCodeProcess, title: string): Promise<String> {
+    return await vscode.window.withProgress(
+        { location: vscode.ProgressLocation.Notification, title },
+        async () => {
+            const { stdout } = await spawnWithOutput(cargo_tools_process);
+            return stdout;
+        }
+    );
+}
+
 export async function executeCommand(command: string, rest: any[]): Promise<any> {
     return await vscode.commands.executeCommand(command, ...rest);
 }
 
-export async function execute_task(cargo_tools_task: VsCodeTask): Promise<void> {
-    const cmd = cargo_tools_task.cmd();
-    const args = cargo_tools_task.args();
-    const env: { [key: string]: string } = Object.fromEntries(cargo_tools_task.env());
+/** The command line of a task */
+export interface TaskCommand {
+    cmd: string;
+    args: string[];
+    env: { [key: string]: string };
+    cwd?: string;
+    /** The arguments which report the compiler messages as JSON, see `jsonMessageExecution` */
+    jsonArgs?: string[];
+}
 
-    const definition: vscode.TaskDefinition = {
-        type: cargo_tools_task.task_type(),
-        args: args,
+const taskCommands = new WeakMap<vscode.Task, TaskCommand>();
+
+/** The command line of `task` if it runs a process */
+export function task_command(task: vscode.Task): TaskCommand | undefined {
+    const known = taskCommands.get(task);
+    if (known) {
+        return known;
+    }
+    const execution = task.execution;
+    if (!(execution instanceof vscode.ShellExecution) || execution.command === undefined) {
+        return undefined;
+    }
+    const cmd = execution.command;
+    return {
+        cmd: typeof cmd === 'string' ? cmd : cmd.value,
+        args: (execution.args ?? []).map((arg) => typeof arg === 'string' ? arg : arg.value),
+        env: execution.options?.env ?? {},
+        cwd: execution.options?.cwd,
     };
+}
+
+/** The end of a task which reports its compiler messages as JSON */
+export interface JsonTaskEnd {
+    task: vscode.Task;
+    exitCode?: number;
+    /** The JSON messages of cargo, one per line */
+    messages: string;
+}
+
+// Custom executions have no task process, so their start and exit are reported here
+const jsonTaskStarts = new vscode.EventEmitter<vscode.Task>();
+const jsonTaskEnds = new vscode.EventEmitter<JsonTaskEnd>();
+export const onDidStartJsonTask = jsonTaskStarts.event;
+export const onDidEndJsonTask = jsonTaskEnds.event;
 
-    const execution = new vscode.ShellExecution(cmd, args, { env });
+/** The cargo message of a line of stdout, other output like the one of tests isn't JSON */
+function parseMessage(line: string): { reason: string; message?: { rendered?: string } } | undefined {
+    if (!line.startsWith('{')) {
+        return undefined;
+    }
+    try {
+        const message = JSON.parse(line);
+        return typeof message?.reason === 'string' ? message : undefined;
+    } catch {
+        return undefined;
+    }
+}
 
+/**
+ * Runs `command` with its `jsonArgs` in a pseudoterminal which shows the rendered compiler
+ * messages and the other output like a shell would, and collects the JSON messages for
+ * `onDidEndJsonTask`. The `task` of the execution is created after it.
+ */
+const ANSI_ESCAPES = /\x1b\[[0-9;]*[A-Za-z]/g;
+
+/** The file the full output of the task `name` is written to, the one of its last run */
+function taskLogFile(name: string): string {
+    const dir = extension_context?.logUri.fsPath ?? os.tmpdir();
+    fs.mkdirSync(dir, { recursive: true });
+    return path.join(dir, `${name.replace(/[^\w.-]+/g, '-').slice(0, 100)}.log`);
+}
+
+function jsonMessageExecution(command: TaskCommand, jsonArgs: string[], task: () => vscode.Task): vscode.CustomExecution {
+    return new vscode.CustomExecution(async () => {
+        const write = new vscode.EventEmitter<string>();
+        const close = new vscode.EventEmitter<number>();
+        let child: ChildProcess | undefined;
+        let finished = false;
+        let pending = '';
+        const messages: string[] = [];
+        // Long outputs like builds with hundreds of warnings would slow down the terminal
+        const output = new TaskOutput();
+        const logFile = taskLogFile(task().name);
+        const log = fs.createWriteStream(logFile);
+        let noted = false;
+
+        const toTerminal = (text: string) => write.fire(text.replace(/\r?\n/g, '\r\n'));
+        const show = (text: string) => {
+            log.write(text.replace(ANSI_ESCAPES, ''));
+            const shown = output.push(text);
+            if (shown) {
+                toTerminal(shown);
+            }
+            if (!noted && output.shortened()) {
+                noted = true;
+                toTerminal(`\n... The output is longer than cargoTools.output.maxLines, its last lines follow once the task finished. The full output is written to ${logFile}\n`);
+            }
+        };
+        const onLine = (line: string) => {
+            const message = parseMessage(line.replace(/\r$/, ''));
+            if (!message) {
+                show(`${line}\n`);
+                return;
+            }
+            messages.push(line);
+            if (message.reason === 'compiler-message' && message.message?.rendered) {
+                show(message.message.rendered);
+            }
+        };
+        const onStdout = (data: string) => {
+            const lines = (pending + data).split('\n');
+            pending = lines.pop() ?? '';
+            lines.forEach(onLine);
+        };
+        const finish = (exitCode: number | undefined) => {
+            if (finished) {
+                return;
+            }
+            finished = true;
+            if (pending) {
+                onLine(pending);
+            }
+            toTerminal(output.finish(logFile));
+            output.free();
+            log.end();
+            jsonTaskEnds.fire({ task: task(), exitCode, messages: messages.join('\n') });
+            close.fire(exitCode ?? 1);
+        };
+
+        return {
+            onDidWrite: write.event,
+            onDidClose: close.event,
+            open: () => {
+                show(`> ${[command.cmd, ...command.args].map(quoteArg).join(' ')}\n\n`);
+                jsonTaskStarts.fire(task());
+                // Cargo only colors its progress on stderr for terminals unless asked to
+                child = spawn(command.cmd, jsonArgs, {
+                    cwd: command.cwd ?? vscode.workspace.workspaceFolders?.[0]?.uri.fsPath,
+                    env: { CARGO_TERM_COLOR: 'always', ...process.env, ...command.env },
+                    detached: process.platform !== 'win32',
+                });
+                child.stdout?.setEncoding('utf8');
+                child.stderr?.setEncoding('utf8');
+                child.stdout?.on('data', onStdout);
+                child.stderr?.on('data', show);
+                child.on('error', (e) => {
+                    show(`Failed to run ${command.cmd}: ${e.message}\n`);
+                    finish(undefined);
+                });
+                child.on('close', (code) => finish(code ?? undefined));
+            },
+            close: () => {
+                if (child) {
+                    killTree(child);
+                }
+            },
+            handleInput: (data: string) => {
+                // Ctrl+C stops the task like in a shell
+                if (data === '\x03' && child) {
+                    killTree(child);
+                }
+            },
+        };
+    });
+}
+
+/** A task of `definition` which runs `command` */
+function newTask(command: TaskCommand, definition: vscode.TaskDefinition, name: string): vscode.Task {
+    const execution = command.jsonArgs
+        ? jsonMessageExecution(command, command.jsonArgs, () => task)
+        : new vscode.ShellExecution(command.cmd, command.args, { env: command.env, cwd: command.cwd });
     const task = new vscode.Task(
         definition,
         vscode.TaskScope.Workspace,
-        `${cmd} ${args.join(" ")}`,
+        name,
         definition.type,
         execution,
         ['$rustc']
     );
+    taskCommands.set(task, command);
+    // Records the process of the task once it starts
+    runningTasks.ensureInitialized();
+    return task;
+}
+
+export function createTask(cargo_tools_task: VsCodeTask, taskDefinition?: vscode.TaskDefinition, name?: string): vscode.Task {
+    const cmd = cargo_tools_task.cmd();
+    const args = cargo_tools_task.args();
+    const env: { [key: string]: string } = Object.fromEntries(cargo_tools_task.env());
+
+    const definition: vscode.TaskDefinition = taskDefinition ?? {
+        type: cargo_tools_task.task_type(),
+        args: args,
+    };
+
+    // Tasks run in the cargo workspace root which may be nested in the VS Code folder
+    const command: TaskCommand = {
+        cmd,
+        args,
+        env,
+        cwd: cargo_tools_task.cwd(),
+        jsonArgs: cargo_tools_task.json_message_args(),
+    };
+    const task = newTask(command, definition, name ?? `${cmd} ${args.join(" ")}`);
 
     task.presentationOptions = {
         echo: true,
@@ -179,10 +690,300 @@ export async function execute_task(cargo_tools_task: VsCodeTask): Promise<void>
         clear: false
     };
 
+    return task;
+}
+
+export async function execute_task(cargo_tools_task: VsCodeTask): Promise<void> {
+    const task = createTask(cargo_tools_task);
+
     try {
-        await vscode.tasks.executeTask(task);
+        const taskExecution = await vscode.tasks.executeTask(task);
+        runningTasks.add(taskExecution);
     } catch (error) {
         const message = error instanceof Error ? error.message : String(error);
         vscode.window.showErrorMessage(`Failed to run cargo make task: ${message}`);
     }
 }
+
+const TERMINAL_NAME = 'Cargo Tools';
+
+class CargoTerminal {
+    private terminal?: vscode.Terminal;
+    private env?: string;
+    /** The last command sent to the terminal */
+    private last?: { commandLine: string, env: { [key: string]: string }, cwd?: string };
+
+    isTerminal(terminal: vscode.Terminal): boolean {
+        return terminal === this.terminal;
+    }
+
+    /** Sends the last command again with additional `env` */
+    rerun(env: { [key: string]: string }): boolean {
+        if (!this.last) {
+            return false;
+        }
+        this.send(this.last.commandLine, { ...this.last.env, ...env }, this.last.cwd);
+        return true;
+    }
+
+    send(commandLine: string, env: { [key: string]: string }, cwd?: string): void {
+        this.last = { commandLine, env, cwd };
+        const terminal = this.get(env, cwd);
+        terminal.show(true);
+        terminal.sendText(commandLine);
+    }
+
+    get(env: { [key: string]: string }, cwd?: string): vscode.Terminal {
+        // The environment of a terminal is fixed on creation so it has to be replaced
+        // when the configured environment changes.
+        const envKey = JSON.stringify({ env, cwd });
+        if (this.terminal && this.terminal.exitStatus === undefined && this.env === envKey) {
+            return this.terminal;
+        }
+
+        this.terminal?.dispose();
+        this.terminal = vscode.window.createTerminal({
+            name: TERMINAL_NAME,
+            cwd: cwd ?? vscode.workspace.workspaceFolders?.[0]?.uri,
+            env,
+        });
+        this.env = envKey;
+        return this.terminal;
+    }
+}
+
+const cargoTerminal = new CargoTerminal();
+
+function quoteArg(arg: string): string {
+    return /^[\w@%+=:,./-]+$/.test(arg) ? arg : `"${arg.replace(/(["\\$`])/g, '\\$1')}"`;
+}
+
+export async function execute_in_terminal(cargo_tools_task: VsCodeTask): Promise<void> {
+    const env: { [key: string]: string } = Object.fromEntries(cargo_tools_task.env());
+    const commandLine = [cargo_tools_task.cmd(), ...cargo_tools_task.args()].map(quoteArg).join(' ');
+    cargoTerminal.send(commandLine, env, cargo_tools_task.cwd());
+}
+
+/**
+ * Runs the command which wrote to `terminal` again with `env` added, returns whether the
+ * command is known
+ */
+export async function rerun_in_terminal(terminal: vscode.Terminal, env: { [key: string]: string }): Promise<boolean> {
+    if (cargoTerminal.isTerminal(terminal)) {
+        return cargoTerminal.rerun(env);
+    }
+    const task = await runningTasks.taskOf(terminal);
+    const command = task && task_command(task);
+    if (!task || !command) {
+        return false;
+    }
+    const rerun = newTask({ ...command, env: { ...command.env, ...env } }, task.definition, task.name);
+    rerun.presentationOptions = task.presentationOptions;
+    runningTasks.add(await vscode.tasks.executeTask(rerun));
+    return true;
+}
+
+function waitForExit(taskExecution: vscode.TaskExecution, task: vscode.Task): Promise<number | undefined> {
+    return new Promise((resolve) => {
+        const listeners: vscode.Disposable[] = [];
+        const done = (exitCode: number | undefined) => {
+            listeners.forEach((listener) => listener.dispose());
+            resolve(exitCode);
+        };
+        listeners.push(vscode.tasks.onDidEndTaskProcess((event) => {
+            if (event.execution === taskExecution) {
+                done(event.exitCode);
+            }
+        }));
+        listeners.push(onDidEndJsonTask((end) => {
+            if (end.task === task) {
+                done(end.exitCode);
+            }
+        }));
+        // Tasks which are terminated before their process started never report an exit code
+        listeners.push(vscode.tasks.onDidEndTask((event) => {
+            if (event.execution === taskExecution) {
+                done(undefined);
+            }
+        }));
+    });
+}
+
+export async function execute_task_and_wait(cargo_tools_task: VsCodeTask, silent: boolean): Promise<boolean> {
+    const task = createTask(cargo_tools_task);
+    // Tasks running at the same time each need their own terminal
+    task.presentationOptions.panel = vscode.TaskPanelKind.Dedicated;
+    if (silent) {
+        // Results still show up in the Problems panel
+        task.presentationOptions.reveal = vscode.TaskRevealKind.Silent;
+    }
+
+    try {
+        const taskExecution = await vscode.tasks.executeTask(task);
+        runningTasks.add(taskExecution);
+        return await waitForExit(taskExecution, task) === 0;
+    } catch (error) {
+        const message = error instanceof Error ? error.message : String(error);
+        vscode.window.showErrorMessage(`Failed to run cargo task: ${message}`);
+        return false;
+    }
+}
+
+/** Number of started tasks remembered to run them again */
+const RECENT_TASKS = 20;
+
+/** The name of the task `terminal` runs, older versions of VS Code prefix it with `Task - ` */
+function taskTerminalName(terminal: vscode.Terminal): string {
+    return terminal.name.replace(/^Task - /, '');
+}
+
+class RunningTasks {
+    private executions = new Set<vscode.TaskExecution>();
+    private statusItem?: vscode.StatusBarItem;
+    private recentTasks: vscode.Task[] = [];
+    /** The recently started tasks by the id of their process, which is the one of their terminal */
+    private processTasks = new Map<number, vscode.Task>();
+
+    /**
+     * The recent task which ran in `terminal`, by the process of the terminal or else, for the
+     * pseudoterminals of tasks showing compiler messages, by the name of the terminal
+     */
+    async taskOf(terminal: vscode.Terminal): Promise<vscode.Task | undefined> {
+        const processId = await terminal.processId;
+        const task = processId === undefined ? undefined : this.processTasks.get(processId);
+        return task ?? [...this.recentTasks].reverse().find((recent) =>
+            recent.execution instanceof vscode.CustomExecution && recent.name === taskTerminalName(terminal));
+    }
+
+    add(execution: vscode.TaskExecution): void {
+        this.ensureInitialized();
+        this.executions.add(execution);
+        this.recentTasks = [...this.recentTasks, execution.task].slice(-RECENT_TASKS);
+        this.updateStatus();
+        completionNotifier.track(execution);
+    }
+
+    cancelAll(): void {
+        // Terminating a task execution kills the shell it runs in together with
+        // all of its children (e.g. rustc processes spawned by cargo).
+        for (const execution of this.executions) {
+            execution.terminate();
+        }
+    }
+
+    ensureInitialized(): void {
+        if (this.statusItem) {
+            return;
+        }
+        this.statusItem = vscode.window.createStatusBarItem(vscode.StatusBarAlignment.Left);
+        this.statusItem.text = '$(debug-stop) Cancel';
+        this.statusItem.tooltip = 'Cancel running cargo commands';
+        this.statusItem.command = 'cargo-tools.cancelBuild';
+
+        const endListener = vscode.tasks.onDidEndTask((event) => {
+            if (this.executions.delete(event.execution)) {
+                this.updateStatus();
+            }
+        });
+        const processListener = vscode.tasks.onDidStartTaskProcess((event) => {
+            this.processTasks.set(event.processId, event.execution.task);
+            // Only the recent ones are run again
+            for (const processId of [...this.processTasks.keys()].slice(0, -RECENT_TASKS)) {
+                this.processTasks.delete(processId);
+            }
+        });
+
+        extension_context?.subscriptions.push(this.statusItem, endListener, processListener);
+    }
+
+    private updateStatus(): void {
+        if (this.executions.size > 0) {
+            this.statusItem?.show();
+        } else {
+            this.statusItem?.hide();
+        }
+    }
+}
+
+const runningTasks = new RunningTasks();
+
+/**
+ * Notifies about finished long running tasks while the VS Code window is not focused
+ */
+class CompletionNotifier {
+    private startTimes = new Map<vscode.TaskExecution, number>();
+    private endListener?: vscode.Disposable;
+
+    track(execution: vscode.TaskExecution): void {
+        if (!this.endListener) {
+            this.endListener = vscode.Disposable.from(
+                vscode.tasks.onDidEndTaskProcess((event) => this.onEnd(event.execution, event.exitCode)),
+                onDidEndJsonTask((end) => {
+                    const execution = [...this.startTimes.keys()].find((execution) => execution.task === end.task);
+                    if (execution) {
+                        this.onEnd(execution, end.exitCode);
+                    }
+                }),
+            );
+            extension_context?.subscriptions.push(this.endListener);
+        }
+        this.startTimes.set(execution, Date.now());
+    }
+
+    private onEnd(execution: vscode.TaskExecution, exitCode: number | undefined): void {
+        const start = this.startTimes.get(execution);
+        if (start === undefined) {
+            return;
+        }
+        this.startTimes.delete(execution);
+
+        // A minimum duration of 0 disables the notifications
+        const minDuration = vscode.workspace.getConfiguration('cargoTools').get<number>('notifications.minDuration', 10);
+        const seconds = (Date.now() - start) / 1000;
+        if (minDuration <= 0 || seconds < minDuration || vscode.window.state.focused) {
+            return;
+        }
+
+        this.notify(execution, exitCode === 0, seconds);
+    }
+
+    private async notify(execution: vscode.TaskExecution, success: boolean, seconds: number): Promise<void> {
+        const task = execution.task;
+        const isTest = task_command(task)?.args.includes('test') ?? false;
+        const message = `'${task.name}' ${success ? 'succeeded' : 'failed'} after ${Math.round(seconds)}s`;
+
+        const showOutput = 'Show Output';
+        const run = 'Run';
+        const rerun = isTest ? 'Re-run Tests' : 'Re-run';
+        const actions = success && !isTest ? [showOutput, run, rerun] : [showOutput, rerun];
+
+        const selected = success
+            ? await vscode.window.showInformationMessage(message, ...actions)
+            : await vscode.window.showErrorMessage(message, ...actions);
+
+        switch (selected) {
+            case showOutput: {
+                const shown = await Promise.all(vscode.window.terminals.map((terminal) => runningTasks.taskOf(terminal)));
+                const terminal = vscode.window.terminals.find((_, i) => shown[i] === task);
+                if (terminal) {
+                    terminal.show();
+                } else {
+                    await vscode.commands.executeCommand('workbench.action.terminal.focus');
+                }
+                break;
+            }
+            case run:
+                await vscode.commands.executeCommand('cargo-tools.projectStatus.run');
+                break;
+            case rerun:
+                runningTasks.add(await vscode.tasks.executeTask(task));
+                break;
+        }
+    }
+}
+
+const completionNotifier = new CompletionNotifier();
+
+export async function cancel_tasks(): Promise<void> {
+    runningTasks.cancelAll();
+}
@@ -1,4 +1,6 @@
-use cargo_tools_vscode::commands::{cargo_make, configuration, outline, pinned, tasks, xtask};
+use cargo_tools_vscode::commands::{
+    cargo_make, configuration, history, just, outline, pinned, tasks, variables, xtask,
+};
 
 fn all_configuration_commands() -> [&'static str; configuration::NUMBER_CMDS] {
     use cargo_tools_vscode::commands::configuration::*;
@@ -15,11 +17,72 @@ fn all_configuration_commands() -> [&'static str; configuration::NUMBER_CMDS] {
         CARGO_TOOLS_SELECT_FEATURES,
         CARGO_TOOLS_REFRESH,
         CARGO_TOOLS_CLEAN,
+        CARGO_TOOLS_BUILD_PACKAGES,
+        CARGO_TOOLS_CANCEL_BUILD,
+        CARGO_TOOLS_TOGGLE_WATCH,
+        CARGO_TOOLS_MAKE_DEFAULT_BUILD_TASK,
+        CARGO_TOOLS_BUILD_AND_TEST,
+        CARGO_TOOLS_RUN_IN_CLEAN_ENVIRONMENT,
+        CARGO_TOOLS_RUN_TEST,
+        CARGO_TOOLS_DEBUG_TEST,
+        CARGO_TOOLS_ATTACH_TO_PROCESS,
+        CARGO_TOOLS_DEBUG_DOC_TEST,
+        CARGO_TOOLS_RUN_DOC_TEST,
+        CARGO_TOOLS_RERUN_FAILED_TESTS,
+        CARGO_TOOLS_SHOW_SLOWEST_TESTS,
+        CARGO_TOOLS_TEST_CHANGED_PACKAGES,
+        CARGO_TOOLS_SELECT_NEXTEST_PROFILE,
+        CARGO_TOOLS_RUN_SNAPSHOT_TESTS,
+        CARGO_TOOLS_REVIEW_SNAPSHOTS,
+        CARGO_TOOLS_DEBUG_LAST,
+        CARGO_TOOLS_OPEN_CORE_DUMP,
+        CARGO_TOOLS_SHOW_COMPILER_CACHE_STATS,
+        CARGO_TOOLS_SHOW_BINARY_SIZE_TREND,
+        CARGO_TOOLS_OPEN_BENCHMARK_REPORT,
+        CARGO_TOOLS_REFRESH_BUILD_RESULTS,
+        CARGO_TOOLS_FILTER_BUILD_RESULTS,
+        CARGO_TOOLS_SHOW_PACKAGE_PROBLEMS,
+        CARGO_TOOLS_FLASH_AND_RUN,
+        CARGO_TOOLS_ATTACH_RTT,
+        CARGO_TOOLS_NEW_PACKAGE,
+        CARGO_TOOLS_NEW_PACKAGE_FROM_TEMPLATE,
+        CARGO_TOOLS_SELECT_WORKSPACE,
+        CARGO_TOOLS_OPEN_DEPENDENCY_DOCS,
+        CARGO_TOOLS_UPDATE_DEPENDENCY,
+        CARGO_TOOLS_BUMP_VERSION,
+        CARGO_TOOLS_RENAME_PACKAGE,
+        CARGO_TOOLS_NORMALIZE_MANIFEST,
+        CARGO_TOOLS_LICENSE_REPORT,
+        CARGO_TOOLS_EXPLAIN_FEATURES,
+        CARGO_TOOLS_SET_RUST_VERSION,
+        CARGO_TOOLS_VERIFY_RUST_VERSION,
+        CARGO_TOOLS_PROMOTE_TO_WORKSPACE_DEPENDENCY,
+        CARGO_TOOLS_RUN_PRE_COMMIT_CHECKS,
+        CARGO_TOOLS_INSTALL_PRE_COMMIT_HOOK,
+        CARGO_TOOLS_RELOAD_SHELL_ENVIRONMENT,
+        CARGO_TOOLS_MANAGE_TOOLCHAIN_COMPONENTS,
+        CARGO_TOOLS_ADD_DEPENDENCY,
+        CARGO_TOOLS_PUBLISH_PACKAGE,
+        CARGO_TOOLS_SET_REGISTRY_TOKEN,
+        CARGO_TOOLS_BUILD_WASM,
+        CARGO_TOOLS_SERVE_WASM,
+        CARGO_TOOLS_GENERATE_C_HEADER,
+        CARGO_TOOLS_MANIFEST_LENS_BUILD,
+        CARGO_TOOLS_MANIFEST_LENS_TEST,
+        CARGO_TOOLS_MANIFEST_LENS_RUN,
+        CARGO_TOOLS_MANIFEST_LENS_DEBUG,
+        CARGO_TOOLS_MANIFEST_LENS_SELECT,
+        CARGO_TOOLS_MANIFEST_LENS_TOGGLE_FEATURE,
         CARGO_TOOLS_PROJECT_STATUS_BUILD,
         CARGO_TOOLS_PROJECT_STATUS_RUN,
         CARGO_TOOLS_PROJECT_STATUS_DEBUG,
+        CARGO_TOOLS_PROJECT_STATUS_RUN_WITH_ARGS,
+        CARGO_TOOLS_PROJECT_STATUS_DEBUG_WITH_ARGS,
+        CARGO_TOOLS_PROJECT_STATUS_RUN_WITH_SANITIZER,
+        CARGO_TOOLS_PROJECT_STATUS_DEBUG_WITH_SANITIZER,
         CARGO_TOOLS_PROJECT_STATUS_TEST,
         CARGO_TOOLS_PROJECT_STATUS_BENCH,
+        CARGO_TOOLS_PROJECT_STATUS_DEBUG_BENCH,
         CARGO_TOOLS_PROJECT_STATUS_TOGGLE_FEATURE,
     ]
 }
@@ -45,6 +108,7 @@ const fn all_outline_commands() -> [&'static str; outline::NUMBER_CMDS] {
         CARGO_TOOLS_PROJECT_OUTLINE_RUN_TARGET,
         CARGO_TOOLS_PROJECT_OUTLINE_DEBUG_TARGET,
         CARGO_TOOLS_PROJECT_OUTLINE_BENCH_TARGET,
+        CARGO_TOOLS_PROJECT_OUTLINE_DEBUG_BENCH_TARGET,
         CARGO_TOOLS_PROJECT_OUTLINE_SET_WORKSPACE_MEMBER_FILTER,
         CARGO_TOOLS_PROJECT_OUTLINE_EDIT_WORKSPACE_MEMBER_FILTER,
         CARGO_TOOLS_PROJECT_OUTLINE_SHOW_TARGET_TYPE_FILTER,
@@ -52,6 +116,13 @@ const fn all_outline_commands() -> [&'static str; outline::NUMBER_CMDS] {
         CARGO_TOOLS_PROJECT_OUTLINE_CLEAR_ALL_FILTERS,
         CARGO_TOOLS_PROJECT_OUTLINE_TOGGLE_WORKSPACE_MEMBER_GROUPING,
         CARGO_TOOLS_PROJECT_OUTLINE_TOGGLE_FEATURE,
+        CARGO_TOOLS_PROJECT_OUTLINE_OPEN_ON_CRATES_IO,
+        CARGO_TOOLS_PROJECT_OUTLINE_OPEN_DOCS_RS,
+        CARGO_TOOLS_PROJECT_OUTLINE_GO_TO_OVERRIDE,
+        CARGO_TOOLS_PROJECT_OUTLINE_EXPLAIN_DEPENDENCY,
+        CARGO_TOOLS_PROJECT_OUTLINE_NEW_EXAMPLE,
+        CARGO_TOOLS_PROJECT_OUTLINE_NEW_INTEGRATION_TEST,
+        CARGO_TOOLS_PROJECT_OUTLINE_NEW_BENCHMARK,
     ]
 }
 
@@ -101,6 +172,35 @@ const fn all_xtask_commands() -> [&'static str; xtask::NUMBER_CMDS] {
     ]
 }
 
+const fn all_just_commands() -> [&'static str; just::NUMBER_CMDS] {
+    use cargo_tools_vscode::commands::just::*;
+    [
+        CARGO_TOOLS_JUST_RUN_RECIPE,
+        CARGO_TOOLS_JUST_RUN_RECIPE_WITH_ARGS,
+        CARGO_TOOLS_JUST_SELECT_AND_RUN,
+    ]
+}
+
+const fn all_variables_commands() -> [&'static str; variables::NUMBER_CMDS] {
+    use cargo_tools_vscode::commands::variables::*;
+    [
+        CARGO_TOOLS_SELECTED_PACKAGE,
+        CARGO_TOOLS_SELECTED_TARGET_NAME,
+        CARGO_TOOLS_LAUNCH_TARGET_PATH,
+        CARGO_TOOLS_BUILD_PROFILE_FLAG,
+    ]
+}
+
+const fn all_history_commands() -> [&'static str; history::NUMBER_CMDS] {
+    use cargo_tools_vscode::commands::history::*;
+    [
+        CARGO_TOOLS_TASK_HISTORY_SHOW,
+        CARGO_TOOLS_TASK_HISTORY_RERUN,
+        CARGO_TOOLS_TASK_HISTORY_COPY_COMMAND,
+        CARGO_TOOLS_TASK_HISTORY_CLEAR,
+    ]
+}
+
 fn all_cargo_commands_from_cargo_tools() -> Vec<&'static str> {
     all_configuration_commands()
         .into_iter()
@@ -109,6 +209,9 @@ fn all_cargo_commands_from_cargo_tools() -> Vec<&'static str> {
         .chain(all_tasks_commands())
         .chain(all_pinned_commands())
         .chain(all_xtask_commands())
+        .chain(all_just_commands())
+        .chain(all_variables_commands())
+        .chain(all_history_commands())
         .collect()
 }
 
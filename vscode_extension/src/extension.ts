@@ -1,12 +1,13 @@
 import * as vscode from 'vscode';
 import { run, ExitToken } from './wasm/cargo_tools_vscode';
+import { CargoToolsApi, create_api } from '../../packages/cargo_tools_vscode/src/extension/workspace/configuration/api';
 
 export let extension_context: vscode.ExtensionContext | undefined;
 export let log = vscode.window.createOutputChannel("cargo-tools", { log: true });
 
 let exit: ExitToken | undefined;
 
-export async function activate(context: vscode.ExtensionContext): Promise<any> {
+export async function activate(context: vscode.ExtensionContext): Promise<CargoToolsApi> {
 	// This is important because context is used as a global variable in the typescript code
 	extension_context = context;
 	context.subscriptions.push(log);
@@ -21,7 +22,7 @@ export async function activate(context: vscode.ExtensionContext): Promise<any> {
 		exit = run(workspaceFolder);
 
 		log.info('Cargo Tools extension fully initialized!');
-		return {};
+		return create_api();
 	} catch (error) {
 		await vscode.commands.executeCommand('setContext', 'cargoTools:workspaceHasCargo', false);
 		await vscode.commands.executeCommand('setContext', 'cargoTools:workspaceHasMakefile', false);